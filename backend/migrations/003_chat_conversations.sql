@@ -0,0 +1,22 @@
+-- AI对话会话表
+CREATE TABLE IF NOT EXISTS chat_conversations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER,                 -- 关联连接ID (可选)
+    title TEXT NOT NULL,                   -- 会话标题（取首条提问）
+    created_at INTEGER NOT NULL,           -- 创建时间戳
+    updated_at INTEGER NOT NULL,           -- 最后一条消息时间戳
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE SET NULL
+);
+
+-- AI对话消息表
+CREATE TABLE IF NOT EXISTS chat_messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    conversation_id INTEGER NOT NULL,      -- 所属会话ID
+    role TEXT NOT NULL,                    -- user / assistant
+    content TEXT NOT NULL,                 -- 消息内容
+    created_at INTEGER NOT NULL,           -- 创建时间戳
+    FOREIGN KEY (conversation_id) REFERENCES chat_conversations(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_conversations_updated ON chat_conversations(updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_chat_messages_conversation ON chat_messages(conversation_id, id);
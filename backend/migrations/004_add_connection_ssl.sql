@@ -0,0 +1,6 @@
+-- 为数据库连接表添加SSL/TLS配置字段
+-- ssl_mode: disable, prefer, require, verify-ca, verify-full（为空表示使用驱动默认值）
+ALTER TABLE connections ADD COLUMN ssl_mode TEXT;
+
+-- CA证书文件路径（verify-ca / verify-full 时使用）
+ALTER TABLE connections ADD COLUMN ssl_ca_path TEXT;
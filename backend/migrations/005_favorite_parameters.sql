@@ -0,0 +1,2 @@
+-- 为SQL收藏添加命名参数声明（JSON数组：name, type, default, description）
+ALTER TABLE sql_favorites ADD COLUMN parameters TEXT;
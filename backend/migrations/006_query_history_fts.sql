@@ -0,0 +1,25 @@
+-- 查询历史全文索引（trigram分词，支持SQL片段和中文错误信息的子串搜索）
+CREATE VIRTUAL TABLE IF NOT EXISTS query_history_fts USING fts5(
+    sql_text,
+    error_message,
+    content='query_history',
+    content_rowid='id',
+    tokenize='trigram'
+);
+
+-- 同步触发器
+CREATE TRIGGER IF NOT EXISTS query_history_fts_insert AFTER INSERT ON query_history BEGIN
+    INSERT INTO query_history_fts(rowid, sql_text, error_message) VALUES (new.id, new.sql_text, new.error_message);
+END;
+
+CREATE TRIGGER IF NOT EXISTS query_history_fts_delete AFTER DELETE ON query_history BEGIN
+    INSERT INTO query_history_fts(query_history_fts, rowid, sql_text, error_message) VALUES ('delete', old.id, old.sql_text, old.error_message);
+END;
+
+CREATE TRIGGER IF NOT EXISTS query_history_fts_update AFTER UPDATE OF sql_text, error_message ON query_history BEGIN
+    INSERT INTO query_history_fts(query_history_fts, rowid, sql_text, error_message) VALUES ('delete', old.id, old.sql_text, old.error_message);
+    INSERT INTO query_history_fts(rowid, sql_text, error_message) VALUES (new.id, new.sql_text, new.error_message);
+END;
+
+-- 为已有历史记录建立索引
+INSERT INTO query_history_fts(query_history_fts) VALUES ('rebuild');
@@ -0,0 +1,15 @@
+-- 慢查询日志
+CREATE TABLE IF NOT EXISTS slow_queries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER,
+    fingerprint TEXT NOT NULL,        -- 归一化后的SQL（字面量替换为?），用于聚合同类查询
+    sql_text TEXT NOT NULL,
+    execution_time_ms INTEGER NOT NULL,
+    row_count INTEGER,
+    explain_plan TEXT,                -- 记录时的执行计划
+    recorded_at INTEGER NOT NULL,
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_slow_queries_fingerprint ON slow_queries(fingerprint);
+CREATE INDEX IF NOT EXISTS idx_slow_queries_recorded_at ON slow_queries(recorded_at DESC);
@@ -0,0 +1,14 @@
+-- 表结构语义检索向量（表级文档的 column_name 为空字符串）
+CREATE TABLE IF NOT EXISTS schema_embeddings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL DEFAULT '',
+    content TEXT NOT NULL,            -- 参与向量化的文本
+    content_hash TEXT NOT NULL,       -- 文本摘要，表结构或注释变化时重新向量化
+    model TEXT NOT NULL,
+    vector BLOB NOT NULL,             -- 小端序f32数组
+    updated_at INTEGER NOT NULL,
+    UNIQUE (connection_id, table_name, column_name),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+-- 连接分组（文件夹）
+CREATE TABLE IF NOT EXISTS connection_groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+-- 连接所属分组（为空表示未分组），删除分组时连接移到未分组
+ALTER TABLE connections ADD COLUMN group_id INTEGER REFERENCES connection_groups(id) ON DELETE SET NULL;
+
+-- 连接在分组内的显示顺序
+ALTER TABLE connections ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_connections_group ON connections(group_id, sort_order);
@@ -0,0 +1,12 @@
+-- 连接健康检查记录（后台定期检测激活的连接）
+CREATE TABLE IF NOT EXISTS connection_health (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    checked_at INTEGER NOT NULL,
+    available INTEGER NOT NULL,       -- 1 可用，0 不可用
+    latency_ms INTEGER,               -- 检测耗时（毫秒）
+    error TEXT,                       -- 不可用时的错误信息
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_connection_health_conn ON connection_health(connection_id, checked_at DESC);
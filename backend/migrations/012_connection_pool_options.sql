@@ -0,0 +1,5 @@
+-- 连接池高级选项（为空时使用默认值）
+ALTER TABLE connections ADD COLUMN pool_max_connections INTEGER;
+ALTER TABLE connections ADD COLUMN pool_min_connections INTEGER;
+ALTER TABLE connections ADD COLUMN pool_idle_timeout_secs INTEGER;
+ALTER TABLE connections ADD COLUMN pool_max_lifetime_secs INTEGER;
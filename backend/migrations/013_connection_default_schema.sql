@@ -0,0 +1,2 @@
+-- 连接的默认schema（PostgreSQL search_path），为空时使用数据库默认值
+ALTER TABLE connections ADD COLUMN default_schema TEXT;
@@ -0,0 +1,11 @@
+-- 编辑器会话表（桌面端重启后恢复打开的标签页）
+CREATE TABLE IF NOT EXISTS editor_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,             -- 会话名称（默认 default）
+    tabs TEXT NOT NULL DEFAULT '[]',       -- 标签页列表（JSON：SQL内容、关联连接、光标位置）
+    active_tab TEXT,                       -- 当前激活的标签页ID
+    created_at INTEGER NOT NULL,           -- 创建时间戳
+    updated_at INTEGER NOT NULL            -- 最后保存时间戳
+);
+
+CREATE INDEX IF NOT EXISTS idx_editor_sessions_updated ON editor_sessions(updated_at DESC);
@@ -0,0 +1,11 @@
+-- 表和列注释（SQLite 没有原生注释，注释保存在本地存储；表注释的 column_name 为空字符串）
+CREATE TABLE IF NOT EXISTS object_comments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL DEFAULT '',
+    comment TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (connection_id, table_name, column_name),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
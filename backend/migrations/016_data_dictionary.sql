@@ -0,0 +1,14 @@
+-- 业务数据字典（表级条目的 column_name 为空字符串），生成SQL时写入提示词
+CREATE TABLE IF NOT EXISTS data_dictionary (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL DEFAULT '',
+    description TEXT NOT NULL DEFAULT '',    -- 业务含义
+    synonyms TEXT NOT NULL DEFAULT '[]',     -- 业务同义词（JSON数组）
+    enum_values TEXT NOT NULL DEFAULT '[]',  -- 枚举值含义（JSON数组：value、meaning）
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (connection_id, table_name, column_name),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
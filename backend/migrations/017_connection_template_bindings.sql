@@ -0,0 +1,11 @@
+-- 连接级提示词模板绑定（每个连接的每种模板类型最多绑定一个模板）
+CREATE TABLE IF NOT EXISTS connection_template_bindings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER NOT NULL,
+    template_type TEXT NOT NULL,
+    template_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (connection_id, template_type),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
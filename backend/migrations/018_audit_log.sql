@@ -0,0 +1,20 @@
+-- 写操作审计日志（DML / DDL / DROP / TRUNCATE），连接删除后保留记录
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    connection_id INTEGER,
+    connection_name TEXT NOT NULL,
+    environment TEXT,
+    statement_kind TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    sql_text TEXT NOT NULL,
+    source TEXT NOT NULL,
+    ai_generated INTEGER NOT NULL DEFAULT 0,
+    affected_rows INTEGER,
+    is_success INTEGER NOT NULL,
+    error_message TEXT,
+    executed_by TEXT,
+    executed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_executed_at ON audit_log(executed_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_connection ON audit_log(connection_id, executed_at);
@@ -0,0 +1,2 @@
+-- 连接级查询默认值（最大行数、默认超时、只读、会话初始化语句），JSON格式
+ALTER TABLE connections ADD COLUMN options TEXT NOT NULL DEFAULT '{}';
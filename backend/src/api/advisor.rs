@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use axum::{
+    routing::post,
+    Extension, Json, Router,
+};
+use serde::{Serialize, Deserialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::LocalStorageManager;
+use crate::models::QueryHistoryFilter;
+use crate::services::ai::AiService;
+use crate::services::index_advisor::{self, ExistingIndex, IndexSuggestion};
+use crate::utils::security::referenced_tables;
+
+// 未指定SQL时分析的最近历史记录数
+const DEFAULT_HISTORY_LIMIT: i64 = 200;
+
+// 索引建议请求：指定sql时只分析该SQL，否则分析连接最近的查询历史
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexAdvisorRequest {
+    pub connection_id: Option<i64>,
+    pub sql: Option<String>,
+    pub history_limit: Option<i64>,
+    #[serde(default)]
+    pub explain_with_ai: bool,
+}
+
+// 索引建议响应
+#[derive(Debug, Serialize)]
+pub struct IndexAdvisorResponse {
+    pub analyzed_queries: usize,
+    pub suggestions: Vec<IndexSuggestion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ai_explanation: Option<String>,
+}
+
+// 优化建议路由（挂载在 /api/advisor 下）
+pub fn advisor_routes() -> Router {
+    Router::new()
+        // 索引建议
+        .route("/indexes", post(advise_indexes))
+}
+
+/**
+ * 索引建议处理函数
+ */
+pub async fn advise_indexes(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(payload): Json<IndexAdvisorRequest>,
+) -> Result<Json<IndexAdvisorResponse>, ApiError> {
+    info!("[API] POST /api/advisor/indexes - 索引建议请求: connection_id={:?}, 指定SQL={}", payload.connection_id, payload.sql.is_some());
+
+    let (connection, db_manager) = connect_database(&storage, payload.connection_id).await?;
+    if !matches!(db_manager.db_type, crate::db::DatabaseType::MySQL | crate::db::DatabaseType::PostgreSQL | crate::db::DatabaseType::SQLite) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?} 不支持索引建议", db_manager.db_type)));
+    }
+
+    // 工作负载：指定的SQL或最近成功执行的查询
+    let workload: Vec<String> = match payload.sql.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(sql) => vec![sql.to_string()],
+        None => {
+            let filter = QueryHistoryFilter {
+                connection_id: connection.id,
+                success: Some(true),
+                limit: Some(payload.history_limit.unwrap_or(DEFAULT_HISTORY_LIMIT)),
+                ..Default::default()
+            };
+            storage.search_query_history(&filter).await
+                .map_err(|e| ApiError::db("database_error", format!("获取查询历史失败: {}", e)))?
+                .into_iter()
+                .map(|h| h.sql_text)
+                .collect()
+        }
+    };
+    if workload.is_empty() {
+        return Err(ApiError::bad_request("empty_workload", "没有可分析的SQL，请指定sql或先执行一些查询"));
+    }
+
+    // 读取工作负载涉及的表的已有索引
+    let mut existing: HashMap<String, Vec<ExistingIndex>> = HashMap::new();
+    for sql in &workload {
+        for table in referenced_tables(sql) {
+            if existing.contains_key(&table) {
+                continue;
+            }
+            let indexes = match db_manager.get_indexes(&table).await {
+                Ok(indexes) => indexes.into_iter()
+                    .map(|(name, columns, _)| ExistingIndex { name, columns })
+                    .collect(),
+                Err(e) => {
+                    warn!("[API] 获取表 {} 的索引失败: {}", table, e);
+                    Vec::new()
+                }
+            };
+            existing.insert(table, indexes);
+        }
+    }
+
+    let suggestions = index_advisor::suggest_indexes(&workload, &existing, &db_manager.db_type);
+
+    // 可选：请求AI解释建议
+    let mut ai_explanation = None;
+    if payload.explain_with_ai && !suggestions.is_empty() {
+        match ai_service.as_ref() {
+            Some(ai_service) => {
+                let prompt = index_advisor::explanation_prompt(&db_manager.db_type, &suggestions);
+                match ai_service.chat_completion(vec![("user".to_string(), prompt)], Some(0.3), None).await {
+                    Ok(text) => ai_explanation = Some(text),
+                    Err(e) => warn!("[API] AI解释索引建议失败: {}", e),
+                }
+            }
+            None => warn!("[API] AI服务不可用，跳过索引建议解释"),
+        }
+    }
+
+    info!("[API] POST /api/advisor/indexes - 分析SQL {} 条，生成建议 {} 条", workload.len(), suggestions.len());
+    Ok(Json(IndexAdvisorResponse {
+        analyzed_queries: workload.len(),
+        suggestions,
+        ai_explanation,
+    }))
+}
@@ -0,0 +1,131 @@
+use axum::{Extension, Json};
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+use crate::api::routes::{build_generation_context, execute_query, validate_generated_sql};
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::models::{SqlGenerateRequest, SqlQueryRequest, SqlQueryResult};
+use crate::services::ai::{format_result_preview, AiService};
+use crate::services::sql_validation::SqlValidation;
+use crate::utils::security::{classify_sql_as, StatementPolicy};
+
+// 发送给AI总结的结果行数
+const SUMMARY_PREVIEW_ROWS: usize = 50;
+
+// 自然语言数据分析请求
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeRequest {
+    pub question: String,
+    // 生成SQL时附带低基数列的示例值（见 SqlGenerateRequest）
+    #[serde(default)]
+    pub include_sample_values: bool,
+}
+
+// 自然语言数据分析响应：返回各步骤的中间结果
+// SQL不是只读查询或执行失败时 executed 为false，error 说明原因
+#[derive(Debug, Serialize)]
+pub struct AnalyzeResponse {
+    pub question: String,
+    pub sql: String,
+    pub validation: Option<SqlValidation>,
+    pub executed: bool,
+    pub result: Option<SqlQueryResult>,
+    pub summary: Option<String>,
+    pub follow_up_questions: Vec<String>,
+    pub error: Option<String>,
+}
+
+/**
+ * 自然语言数据分析处理函数
+ * 生成SQL -> 在活动连接上执行（只读） -> AI总结结果并给出追问建议
+ */
+pub async fn analyze_question(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(req): Json<AnalyzeRequest>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    info!("[API] POST /api/ai/analyze - 数据分析请求: 问题长度={}", req.question.len());
+
+    let question = req.question.trim();
+    if question.is_empty() {
+        return Err(ApiError::bad_request("empty_question", "问题不能为空"));
+    }
+    if question.len() > 2000 {
+        return Err(ApiError::bad_request("input_too_long", "问题描述过长，请简化您的描述"));
+    }
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+
+    // 1. 生成SQL
+    let generate_req = SqlGenerateRequest {
+        natural_language: question.to_string(),
+        database_schema: None,
+        database_type: None,
+        explain: false,
+        include_sample_values: req.include_sample_values,
+        template_id: None,
+    };
+    let (connection, db_manager, database_schema, database_type) = build_generation_context(&storage, ai_service, &generate_req).await?;
+    let sql = ai_service.generate_sql(question, Some(&database_schema), Some(&database_type)).await
+        .map_err(|e| ApiError::ai("ai_error", format!("SQL生成失败: {}", e)))?;
+    debug!("[API] 数据分析生成的SQL: {}", sql);
+
+    let validation = validate_generated_sql(&connection, &db_manager, &sql, false).await;
+    let mut response = AnalyzeResponse {
+        question: question.to_string(),
+        sql: sql.clone(),
+        validation,
+        executed: false,
+        result: None,
+        summary: None,
+        follow_up_questions: Vec::new(),
+        error: None,
+    };
+
+    // 2. 只执行只读查询
+    let read_only = StatementPolicy::for_connection(&connection).check(&sql)
+        .and_then(|_| classify_sql_as(DatabaseType::from_name(&connection.db_type), &sql))
+        .and_then(|statements| match statements.iter().all(|s| !s.kind.is_write()) {
+            true => Ok(()),
+            false => Err("生成的SQL包含写操作，数据分析只执行只读查询".to_string()),
+        });
+    if let Err(reason) = read_only {
+        warn!("[API] 数据分析跳过执行: {}", reason);
+        response.error = Some(reason);
+        return Ok(Json(response));
+    }
+
+    let query = SqlQueryRequest {
+        sql: sql.clone(),
+        connection_id: connection.id,
+        ai_generated: true,
+        ..Default::default()
+    };
+    let result = match execute_query(Extension(storage.clone()), Json(query)).await {
+        Ok(Json(result)) => result,
+        Err(e) => {
+            warn!("[API] 数据分析查询执行失败: {}", e.message());
+            response.error = Some(e.message().to_string());
+            return Ok(Json(response));
+        }
+    };
+    response.executed = true;
+
+    // 3. 总结结果
+    let preview = format_result_preview(&result.columns, &result.rows, SUMMARY_PREVIEW_ROWS);
+    match ai_service.summarize_result(question, &sql, &preview, Some(&database_type)).await {
+        Ok(summary) => {
+            response.summary = Some(summary.summary);
+            response.follow_up_questions = summary.follow_up_questions;
+        }
+        Err(e) => {
+            warn!("[API] 数据分析结果总结失败: {}", e);
+            response.error = Some(format!("结果总结失败: {}", e));
+        }
+    }
+    response.result = Some(result);
+
+    info!("[API] POST /api/ai/analyze - 响应成功: 行数={}", response.result.as_ref().map(|r| r.row_count).unwrap_or(0));
+    Ok(Json(response))
+}
@@ -0,0 +1,145 @@
+use axum::{Extension, Json};
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+use crate::api::routes::{cached_table_names, cached_table_structure, connect_database_to, validate_generated_sql};
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::services::ai::AiService;
+use crate::services::sql_validation::SqlValidation;
+use crate::utils::security::referenced_tables;
+
+// 发送给AI的表结构数量上限
+const DIAGNOSE_MAX_TABLES: usize = 10;
+// 引用的表不存在时附带的表名数量上限（用于找出拼写正确的表名）
+const DIAGNOSE_MAX_TABLE_NAMES: usize = 200;
+
+// SQL错误诊断请求
+#[derive(Debug, Deserialize)]
+pub struct DiagnoseRequest {
+    pub sql: String,
+    pub error: String,                  // 数据库返回的错误信息
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub database: Option<String>,       // 执行时切换到的数据库
+}
+
+// SQL错误诊断响应
+#[derive(Debug, Serialize)]
+pub struct DiagnoseResponse {
+    pub explanation: String,
+    pub fixed_sql: Option<String>,
+    pub validation: Option<SqlValidation>,   // 修正后SQL的表名、列名校验
+    pub tables: Vec<String>,                 // 发送给AI的表结构
+}
+
+// SQL中出现的已知表名（SQL无法解析时按单词匹配）
+fn mentioned_tables(sql: &str, known_tables: &[String]) -> Vec<String> {
+    let words: Vec<String> = sql
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    known_tables.iter()
+        .filter(|table| words.contains(&table.to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+/**
+ * SQL错误诊断处理函数
+ * 将SQL、数据库错误信息和相关表结构发送给AI，返回错误原因和修正后的SQL
+ */
+pub async fn diagnose_error(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(req): Json<DiagnoseRequest>,
+) -> Result<Json<DiagnoseResponse>, ApiError> {
+    info!("[API] POST /api/ai/diagnose - 错误诊断请求: SQL长度={}, connection_id={:?}", req.sql.len(), req.connection_id);
+
+    let sql = req.sql.trim();
+    let error_message = req.error.trim();
+    if sql.is_empty() {
+        return Err(ApiError::bad_request("empty_sql", "SQL不能为空"));
+    }
+    if error_message.is_empty() {
+        return Err(ApiError::bad_request("empty_error", "错误信息不能为空"));
+    }
+    if sql.len() > 20000 || error_message.len() > 5000 {
+        return Err(ApiError::bad_request("input_too_long", "SQL或错误信息过长"));
+    }
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+
+    // 连接失败（报错本身可能就是连接问题）时不附带表结构，仍然诊断
+    let database = req.database.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    let context = match connect_database_to(&storage, req.connection_id, database).await {
+        Ok(context) => Some(context),
+        Err(e) => {
+            warn!("[API] 错误诊断时连接数据库失败，不附带表结构: {}", e.message());
+            None
+        }
+    };
+
+    let mut schema = String::new();
+    let mut tables = Vec::new();
+    let mut database_type = None;
+    if let Some((connection, db_manager)) = &context {
+        database_type = Some(connection.db_type.clone());
+        if !matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis) {
+            let known_tables = cached_table_names(connection.id, db_manager).await.unwrap_or_else(|e| {
+                warn!("[API] 错误诊断时获取表列表失败: {}", e);
+                Vec::new()
+            });
+            let referenced = referenced_tables(sql);
+            let referenced = if referenced.is_empty() { mentioned_tables(sql, &known_tables) } else { referenced };
+
+            let mut missing = false;
+            for table in referenced.iter().take(DIAGNOSE_MAX_TABLES) {
+                let Some(actual) = known_tables.iter().find(|t| t.eq_ignore_ascii_case(table)) else {
+                    missing = true;
+                    continue;
+                };
+                match cached_table_structure(connection.id, db_manager, actual).await {
+                    Ok(structure) => {
+                        schema.push_str(&format!("表名: {}\n", actual));
+                        for col in &structure.columns {
+                            schema.push_str(&format!(
+                                "  - {} ({}){}\n",
+                                col.name,
+                                col.data_type.as_deref().unwrap_or("UNKNOWN"),
+                                if col.is_primary_key.unwrap_or(false) { " [主键]" } else { "" },
+                            ));
+                        }
+                        tables.push(actual.clone());
+                    }
+                    Err(e) => warn!("[API] 错误诊断时获取表 {} 结构失败: {}", actual, e),
+                }
+            }
+            // 引用了不存在的表（或无法识别引用的表）时附带已有表名
+            if (missing || referenced.is_empty()) && !known_tables.is_empty() {
+                let names: Vec<&str> = known_tables.iter().take(DIAGNOSE_MAX_TABLE_NAMES).map(String::as_str).collect();
+                schema.push_str(&format!("数据库中的表: {}\n", names.join(", ")));
+            }
+        }
+    }
+    if schema.is_empty() {
+        schema.push_str("（无法获取表结构）\n");
+    }
+
+    let diagnosis = ai_service.diagnose_error(sql, error_message, &schema, database_type.as_deref()).await
+        .map_err(|e| ApiError::ai("ai_error", format!("错误诊断失败: {}", e)))?;
+
+    let validation = match (&context, &diagnosis.fixed_sql) {
+        (Some((connection, db_manager)), Some(fixed_sql)) => validate_generated_sql(connection, db_manager, fixed_sql, false).await,
+        _ => None,
+    };
+
+    info!("[API] POST /api/ai/diagnose - 响应成功: 表结构数量={}, 是否给出修正SQL={}", tables.len(), diagnosis.fixed_sql.is_some());
+    Ok(Json(DiagnoseResponse {
+        explanation: diagnosis.explanation,
+        fixed_sql: diagnosis.fixed_sql,
+        validation,
+        tables,
+    }))
+}
@@ -0,0 +1,96 @@
+use axum::{Extension, Json};
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiError;
+use crate::db::ddl::validate_generated_ddl;
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::services::ai::AiService;
+
+// 生成的DDL无法通过语法校验时让AI修正的次数
+const DDL_REPAIR_ATTEMPTS: usize = 1;
+
+// 建表DDL生成请求
+#[derive(Debug, Deserialize)]
+pub struct SchemaGenerateRequest {
+    pub description: String,
+    pub dialect: Option<String>,        // mysql / postgresql / sqlite，为空时使用连接的数据库类型
+    pub connection_id: Option<i64>,
+}
+
+// 建表DDL生成响应
+#[derive(Debug, Serialize)]
+pub struct SchemaGenerateResponse {
+    pub dialect: String,
+    pub ddl: String,                    // AI返回的完整DDL（保留格式）
+    pub statements: Vec<String>,        // 解析后的各条语句
+    pub tables: Vec<String>,
+    pub attempts: usize,                // 生成次数（含修正）
+}
+
+// 目标方言：请求指定的方言，否则使用指定连接的数据库类型
+async fn resolve_dialect(storage: &LocalStorageManager, req: &SchemaGenerateRequest) -> Result<DatabaseType, ApiError> {
+    let name = match (req.dialect.as_deref().map(str::trim).filter(|d| !d.is_empty()), req.connection_id) {
+        (Some(dialect), _) => dialect.to_string(),
+        (None, Some(id)) => storage.get_connection_by_id(id).await
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("connection_not_found", format!("连接ID {}不存在", id)))?
+            .db_type,
+        (None, None) => return Err(ApiError::bad_request("missing_dialect", "请指定 dialect（mysql / postgresql / sqlite）或 connection_id")),
+    };
+    match DatabaseType::from_name(&name) {
+        Some(db_type @ (DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite)) => Ok(db_type),
+        _ => Err(ApiError::bad_request("unsupported_dialect", format!("不支持为 {} 生成建表DDL，仅支持 mysql / postgresql / sqlite", name))),
+    }
+}
+
+/**
+ * AI生成建表DDL处理函数
+ * 根据实体描述生成 CREATE TABLE（含索引和注释），按目标方言解析校验后返回；校验失败时让AI修正一次
+ */
+pub async fn generate_schema(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(req): Json<SchemaGenerateRequest>,
+) -> Result<Json<SchemaGenerateResponse>, ApiError> {
+    info!("[API] POST /api/ai/schema/generate - 请求: 描述长度={}, dialect={:?}", req.description.len(), req.dialect);
+
+    let description = req.description.trim();
+    if description.is_empty() {
+        return Err(ApiError::bad_request("empty_description", "描述不能为空"));
+    }
+    if description.len() > 4000 {
+        return Err(ApiError::bad_request("input_too_long", "描述过长，请简化您的描述"));
+    }
+    let db_type = resolve_dialect(&storage, &req).await?;
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+
+    let mut ddl = ai_service.generate_table_ddl(description, db_type.as_str(), None).await
+        .map_err(|e| ApiError::ai("ai_error", format!("生成建表DDL失败: {}", e)))?;
+    let mut attempts = 1;
+    let generated = loop {
+        match validate_generated_ddl(db_type, &ddl) {
+            Ok(generated) => break generated,
+            Err(e) if attempts <= DDL_REPAIR_ATTEMPTS => {
+                warn!("[API] 生成的DDL校验失败，请求AI修正: {}", e);
+                ddl = ai_service.generate_table_ddl(description, db_type.as_str(), Some((&ddl, &e))).await
+                    .map_err(|e| ApiError::ai("ai_error", format!("生成建表DDL失败: {}", e)))?;
+                attempts += 1;
+            }
+            Err(e) => {
+                warn!("[API] 生成的DDL校验失败: {}", e);
+                return Err(ApiError::ai("invalid_generated_ddl", format!("生成的DDL未通过语法校验: {}", e)).with_details(ddl));
+            }
+        }
+    };
+
+    info!("[API] POST /api/ai/schema/generate - 响应成功: 表={:?}, 语句数={}, 生成次数={}", generated.tables, generated.statements.len(), attempts);
+    Ok(Json(SchemaGenerateResponse {
+        dialect: db_type.as_str().to_string(),
+        ddl,
+        statements: generated.statements,
+        tables: generated.tables,
+        attempts,
+    }))
+}
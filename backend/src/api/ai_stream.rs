@@ -0,0 +1,174 @@
+use std::convert::Infallible;
+use std::future::Future;
+use axum::{
+    response::sse::{Event, KeepAlive, Sse},
+    Extension, Json,
+};
+use futures_util::Stream;
+use log::*;
+use tokio::sync::mpsc;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{build_generation_context, validate_generated_sql};
+use crate::api::template_bindings::ai_service_for_template;
+use crate::db::LocalStorageManager;
+use crate::models::{ErrorResponse as ModelErrorResponse, SqlExplainRequest, SqlGenerateRequest, SqlOptimizeRequest, TemplateType};
+use crate::services::ai::{AiService, StreamSender};
+use crate::services::templates::TemplateManager;
+use crate::utils::security::{SqlInjectionProtection, StatementPolicy};
+
+fn require_ai_service(ai_service: &Option<AiService>) -> Result<&AiService, ApiError> {
+    ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))
+}
+
+fn ai_failure(action: &str, e: impl std::fmt::Display) -> ModelErrorResponse {
+    ModelErrorResponse {
+        error: "ai_error".to_string(),
+        message: format!("{}失败: {}", action, e),
+        details: None,
+    }
+}
+
+// 将AI任务包装为SSE响应：
+//   event: delta  增量文本 {"content": "..."}
+//   event: done   最终结果（与对应非流式接口的响应体一致）
+//   event: error  错误信息 {"error": "...", "message": "..."}
+fn sse_response<F, Fut>(run: F) -> Sse<impl Stream<Item = Result<Event, Infallible>>>
+where
+    F: FnOnce(StreamSender) -> Fut,
+    Fut: Future<Output = Result<serde_json::Value, ModelErrorResponse>> + Send + 'static,
+{
+    let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+    let (delta_tx, mut delta_rx) = mpsc::unbounded_channel::<String>();
+    let task = run(delta_tx);
+
+    let forward_tx = event_tx.clone();
+    let forwarder = tokio::spawn(async move {
+        while let Some(text) = delta_rx.recv().await {
+            let event = Event::default().event("delta").data(serde_json::json!({ "content": text }).to_string());
+            if forward_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let result = task.await;
+        // 任务结束后增量通道关闭，等待剩余增量发送完再发送结束事件
+        let _ = forwarder.await;
+        let event = match result {
+            Ok(data) => Event::default().event("done").data(data.to_string()),
+            Err(e) => {
+                warn!("[API] 流式AI请求失败: {}", e.message);
+                Event::default().event("error").data(serde_json::json!({ "error": e.error, "message": e.message }).to_string())
+            }
+        };
+        let _ = event_tx.send(event);
+    });
+
+    let stream = futures_util::stream::unfold(event_rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/**
+ * 流式SQL生成处理函数
+ */
+pub async fn generate_sql_stream(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Json(req): Json<SqlGenerateRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    info!("[API] POST /api/ai/sql/generate/stream - 流式SQL生成请求: 自然语言长度={}", req.natural_language.len());
+
+    if req.natural_language.len() > 2000 {
+        return Err(ApiError::bad_request("input_too_long", "自然语言描述过长，请简化您的描述"));
+    }
+    let ai_service = require_ai_service(&ai_service)?.clone();
+
+    let (connection, db_manager, database_schema, database_type) = build_generation_context(&storage, &ai_service, &req).await?;
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, &ai_service, connection.id, TemplateType::Generation, req.template_id.as_deref(),
+    ).await?;
+
+    Ok(sse_response(move |sender| async move {
+        let sql = ai_service.generate_sql_stream(&req.natural_language, Some(&database_schema), Some(&database_type), sender).await
+            .map_err(|e| ai_failure("SQL生成", e))?;
+
+        // 按语句类别和连接配置检查生成的SQL
+        if let Err(reason) = StatementPolicy::for_connection(&connection).check(&sql) {
+            return Err(ModelErrorResponse {
+                error: "generated_sql_invalid".to_string(),
+                message: "生成的SQL存在安全风险，请重新尝试".to_string(),
+                details: Some(reason),
+            });
+        }
+
+        let validation = validate_generated_sql(&connection, &db_manager, &sql, req.explain).await;
+        Ok(serde_json::json!({
+            "sql": sql,
+            "explanation": format!("根据 {} 数据库的表结构生成", database_type),
+            "validation": validation,
+        }))
+    }))
+}
+
+/**
+ * 流式SQL优化处理函数
+ */
+pub async fn optimize_sql_stream(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Json(req): Json<SqlOptimizeRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    info!("[API] POST /api/ai/sql/optimize/stream - 流式SQL优化请求: SQL长度={}, database_type={:?}", req.sql.len(), req.database_type);
+
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, require_ai_service(&ai_service)?, req.connection_id, TemplateType::Optimize, req.template_id.as_deref(),
+    ).await?;
+
+    Ok(sse_response(move |sender| async move {
+        let (optimized_sql, tips) = ai_service.optimize_sql_stream(&req.sql, req.database_type.as_deref(), sender).await
+            .map_err(|e| ai_failure("SQL优化", e))?;
+        Ok(serde_json::json!({
+            "optimized_sql": optimized_sql,
+            "optimization_tips": tips,
+            "execution_time": 0,
+        }))
+    }))
+}
+
+/**
+ * 流式SQL解释处理函数
+ */
+pub async fn explain_sql_stream(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Json(req): Json<SqlExplainRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    info!("[API] POST /api/ai/sql/explain/stream - 流式SQL解释请求: SQL长度={}", req.sql.len());
+
+    if req.sql.len() > 10000 {
+        return Err(ApiError::bad_request("sql_too_long", "SQL语句过长，请提供更简洁的SQL"));
+    }
+    if let Err(reason) = SqlInjectionProtection::detect_injection(&req.sql) {
+        return Err(ApiError::bad_request("sql_injection_risk", "检测到SQL注入风险").with_details(reason));
+    }
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, require_ai_service(&ai_service)?, req.connection_id, TemplateType::Explain, req.template_id.as_deref(),
+    ).await?;
+
+    Ok(sse_response(move |sender| async move {
+        let explanation = ai_service.explain_sql_stream(&req.sql, None, sender).await
+            .map_err(|e| ai_failure("SQL解释", e))?;
+        Ok(serde_json::json!({
+            "explanation": explanation,
+            "execution_plan": null,
+        }))
+    }))
+}
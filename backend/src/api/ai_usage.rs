@@ -0,0 +1,37 @@
+use axum::{
+    extract::Query,
+    Extension, Json,
+};
+use serde::Deserialize;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::services::ai_usage::{self, DEFAULT_USAGE_DAYS, MAX_USAGE_RECORDS};
+
+// AI用量统计参数
+#[derive(Debug, Deserialize)]
+pub struct AiUsageParams {
+    pub days: Option<i64>,  // 统计最近天数，默认30天
+}
+
+/**
+ * AI用量统计处理函数
+ */
+pub async fn get_ai_usage(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<AiUsageParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let days = params.days.unwrap_or(DEFAULT_USAGE_DAYS).clamp(1, 366);
+    info!("[API] GET /api/ai/usage - AI用量统计请求: days={}", days);
+
+    let since = LocalStorageManager::current_timestamp() - days * 86_400;
+    let records = storage.list_ai_usage(since, MAX_USAGE_RECORDS).await
+        .map_err(|e| ApiError::db("database_error", format!("获取AI用量记录失败: {}", e)))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "days": days,
+        "data": ai_usage::compute_report(&records),
+    })))
+}
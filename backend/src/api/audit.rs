@@ -0,0 +1,49 @@
+use axum::{
+    extract::Query,
+    routing::get,
+    Extension, Json, Router,
+};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::models::AuditLogFilter;
+
+// 单次查询返回的最大条数
+const MAX_AUDIT_LIMIT: i64 = 1000;
+
+// 审计日志路由（挂载在 /api/audit 下）
+pub fn audit_routes() -> Router {
+    Router::new()
+        // 按连接 / 环境 / 语句类型 / 时间范围等条件检索
+        .route("/", get(search_audit_log))
+}
+
+/**
+ * 检索审计日志处理函数
+ * 按执行时间倒序返回，limit 最大 1000
+ */
+pub async fn search_audit_log(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(mut filter): Query<AuditLogFilter>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/audit - 检索审计日志请求: connection_id={:?}, environment={:?}, kind={:?}",
+        filter.connection_id, filter.environment, filter.statement_kind);
+
+    if let (Some(start), Some(end)) = (filter.start_time, filter.end_time) {
+        if start > end {
+            return Err(ApiError::bad_request("invalid_time_range", "开始时间不能晚于结束时间"));
+        }
+    }
+    filter.limit = filter.limit.map(|limit| limit.clamp(1, MAX_AUDIT_LIMIT));
+    filter.offset = filter.offset.map(|offset| offset.max(0));
+
+    let entries = storage.search_audit_log(&filter).await
+        .map_err(|e| ApiError::db("database_error", format!("检索审计日志失败: {}", e)))?;
+    debug!("[API] 审计日志检索完成: {} 条", entries.len());
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": entries,
+        "count": entries.len(),
+    })))
+}
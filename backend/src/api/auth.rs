@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use log::*;
+
+use crate::api::error::ApiError;
+
+// 访问令牌环境变量（由桌面应用启动后端时生成并传入），未设置时不启用认证
+pub const AUTH_TOKEN_ENV: &str = "SMART_SQL_AUTH_TOKEN";
+// WebSocket 等无法设置请求头的场景通过查询参数传递令牌
+const TOKEN_QUERY_PARAM: &str = "token";
+// 不需要令牌的路径（存活检查）
+const PUBLIC_PATHS: &[&str] = &["/api/health"];
+
+// 认证配置
+#[derive(Clone, Default)]
+pub struct AuthConfig {
+    token: Option<Arc<str>>,
+}
+
+impl AuthConfig {
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            token: token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).map(Arc::from),
+        }
+    }
+
+    // 从环境变量读取访问令牌
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(AUTH_TOKEN_ENV).ok())
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.token.is_some()
+    }
+}
+
+// 固定时间比较，避免通过响应时间猜测令牌
+fn token_matches(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected.iter().zip(provided).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+// 从 Authorization: Bearer <token> 或 ?token= 中取出令牌
+fn request_token(request: &Request) -> Option<String> {
+    let bearer = request.headers().get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    bearer.or_else(|| {
+        let Query(mut params) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+        params.remove(TOKEN_QUERY_PARAM)
+    })
+}
+
+/**
+ * 访问令牌认证中间件
+ * 启用后除存活检查和 CORS 预检外的请求都需要携带令牌，否则返回 401 unauthorized
+ */
+pub async fn require_token(State(config): State<AuthConfig>, request: Request, next: Next) -> Response {
+    let Some(expected) = config.token.as_deref() else {
+        return next.run(request).await;
+    };
+    if request.method() == Method::OPTIONS || PUBLIC_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    match request_token(&request) {
+        Some(token) if token_matches(expected, &token) => next.run(request).await,
+        provided => {
+            warn!("[API] {} {} - 访问令牌{}", request.method(), request.uri().path(),
+                if provided.is_some() { "无效" } else { "缺失" });
+            ApiError::unauthorized("unauthorized", "缺少或无效的访问令牌").into_response()
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use axum::{
+    body::Body,
+    http::header,
+    response::Response,
+    Extension, Json,
+};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::dump::{self, TableDump, INSERT_BATCH_ROWS};
+use crate::db::{DatabaseManager, DatabasePool, DatabaseType, LocalStorageManager};
+use crate::services::jobs::{self, JobKind};
+
+// 响应分块大小
+const RESPONSE_CHUNK_SIZE: usize = 64 * 1024;
+
+// 备份内容
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupContent {
+    #[default]
+    Full,        // 表结构和数据
+    SchemaOnly,  // 仅表结构
+    DataOnly,    // 仅数据
+}
+
+// 备份请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupRequest {
+    pub connection_id: Option<i64>,
+    // 要备份的表，为空时备份全部表
+    #[serde(default)]
+    pub tables: Option<Vec<String>>,
+    #[serde(default)]
+    pub content: BackupContent,
+    // 建表前添加 DROP TABLE IF EXISTS
+    #[serde(default)]
+    pub include_drop: bool,
+    pub file_name: Option<String>,
+}
+
+// 缓冲备份内容，达到分块大小后发送给响应流
+struct DumpWriter {
+    buf: String,
+    tx: mpsc::Sender<std::io::Result<Vec<u8>>>,
+}
+
+impl DumpWriter {
+    // 写入一条语句，客户端断开时返回Err
+    async fn statement(&mut self, sql: &str) -> Result<(), String> {
+        self.buf.push_str(sql);
+        self.buf.push_str(";\n");
+        self.flush_if_full().await
+    }
+
+    async fn line(&mut self, text: &str) -> Result<(), String> {
+        self.buf.push_str(text);
+        self.buf.push('\n');
+        self.flush_if_full().await
+    }
+
+    async fn flush_if_full(&mut self) -> Result<(), String> {
+        if self.buf.len() >= RESPONSE_CHUNK_SIZE {
+            self.flush().await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), String> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::take(&mut self.buf).into_bytes();
+        self.tx.send(Ok(chunk)).await.map_err(|_| "客户端已断开连接".to_string())
+    }
+}
+
+// 读取一行中各列的SQL字面量文本（由数据库的 quote 函数生成）
+fn row_literals<R>(row: &R) -> Vec<String>
+where
+    R: sqlx::Row,
+    usize: sqlx::ColumnIndex<R>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> Vec<u8>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    (0..row.len())
+        .map(|i| {
+            if let Ok(v) = row.try_get::<Option<String>, _>(i) {
+                v.unwrap_or_else(|| "NULL".to_string())
+            } else if let Ok(v) = row.try_get::<Option<Vec<u8>>, _>(i) {
+                v.map(|v| String::from_utf8_lossy(&v).into_owned()).unwrap_or_else(|| "NULL".to_string())
+            } else {
+                "NULL".to_string()
+            }
+        })
+        .collect()
+}
+
+// 逐行读取表数据并按批写出 INSERT 语句，返回行数
+macro_rules! dump_rows {
+    ($pool:expr, $db_type:expr, $table:expr, $writer:expr) => {{
+        let sql = dump::literal_select_sql($db_type, &$table.table, &$table.columns)?;
+        let mut rows = sqlx::query(&sql).fetch($pool);
+        let mut batch: Vec<Vec<String>> = Vec::with_capacity(INSERT_BATCH_ROWS);
+        let mut count: u64 = 0;
+        while let Some(row) = rows.try_next().await.map_err(|e| format!("读取表 {} 数据失败: {}", $table.table, e))? {
+            batch.push(row_literals(&row));
+            count += 1;
+            if batch.len() >= INSERT_BATCH_ROWS {
+                $writer.statement(&dump::insert_sql($db_type, &$table.table, &$table.columns, &batch)?).await?;
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            $writer.statement(&dump::insert_sql($db_type, &$table.table, &$table.columns, &batch)?).await?;
+        }
+        count
+    }};
+}
+
+async fn write_table_data(db_manager: &DatabaseManager, table: &TableDump, writer: &mut DumpWriter) -> Result<u64, String> {
+    if table.columns.is_empty() {
+        return Ok(0);
+    }
+    let db_type = db_manager.db_type;
+    let count = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => dump_rows!(pool, db_type, table, writer),
+        DatabasePool::PostgreSQL(pool) => dump_rows!(pool, db_type, table, writer),
+        DatabasePool::SQLite(pool) => dump_rows!(pool, db_type, table, writer),
+        DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => return Err(format!("{:?}不支持备份", db_type)),
+    };
+    Ok(count)
+}
+
+// 依次写出文件头、表结构、数据、索引/约束和文件尾
+async fn write_dump(
+    db_manager: &DatabaseManager,
+    tables: &[TableDump],
+    content: BackupContent,
+    include_drop: bool,
+    writer: &mut DumpWriter,
+) -> Result<u64, String> {
+    let db_type = db_manager.db_type;
+    let with_schema = content != BackupContent::DataOnly;
+    let with_data = content != BackupContent::SchemaOnly;
+
+    writer.line("-- Smart SQL 数据库备份").await?;
+    writer.line(&format!("-- 数据库类型: {:?}", db_type)).await?;
+    writer.line(&format!("-- 生成时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"))).await?;
+    writer.line(&format!("-- 表数量: {}\n", tables.len())).await?;
+    for sql in dump::dump_header(db_type) {
+        writer.statement(&sql).await?;
+    }
+
+    if with_schema {
+        for table in tables {
+            writer.line(&format!("\n-- 表结构: {}", table.table)).await?;
+            if include_drop {
+                writer.statement(&dump::drop_table_sql(db_type, &table.table)?).await?;
+            }
+            for sql in &table.schema {
+                writer.statement(sql).await?;
+            }
+        }
+    }
+
+    let mut total_rows = 0;
+    if with_data {
+        for table in tables {
+            writer.line(&format!("\n-- 表数据: {}", table.table)).await?;
+            total_rows += write_table_data(db_manager, table, writer).await?;
+        }
+    }
+
+    for table in tables {
+        let mut statements: Vec<&String> = Vec::new();
+        if with_schema {
+            statements.extend(&table.post_schema);
+        }
+        if with_data {
+            statements.extend(&table.sequence_updates);
+        }
+        if !statements.is_empty() {
+            writer.line(&format!("\n-- 索引、约束和序列: {}", table.table)).await?;
+            for sql in statements {
+                writer.statement(sql).await?;
+            }
+        }
+    }
+
+    writer.line("").await?;
+    for sql in dump::dump_footer(db_type) {
+        writer.statement(&sql).await?;
+    }
+    writer.flush().await?;
+    Ok(total_rows)
+}
+
+/**
+ * 数据库备份处理函数
+ * 生成逻辑备份（建表语句 + INSERT），以SQL文件下载的方式流式返回
+ * 表结构在响应开始前读取，读取数据时出错会在文件末尾写入错误注释并中断下载
+ */
+pub async fn backup_database(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<BackupRequest>,
+) -> Result<Response, ApiError> {
+    info!("[API] POST /api/database/backup - 请求: connection_id={:?}, tables={:?}, content={:?}",
+        payload.connection_id, payload.tables, payload.content);
+
+    let (connection, db_manager) = connect_database(&storage, payload.connection_id).await?;
+    if !matches!(db_manager.db_type, DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite) {
+        return Err(ApiError::not_implemented("unsupported_database", format!("{:?}不支持备份", db_manager.db_type)));
+    }
+
+    let all_tables = db_manager.get_schema().await
+        .map_err(|e| ApiError::db("database_error", format!("获取表列表失败: {}", e)))?;
+    let table_names = match payload.tables.filter(|t| !t.is_empty()) {
+        Some(selected) => {
+            if let Some(missing) = selected.iter().find(|t| !all_tables.contains(t)) {
+                return Err(ApiError::not_found("table_not_found", format!("表 {} 不存在", missing)));
+            }
+            selected
+        }
+        None => all_tables,
+    };
+    if table_names.is_empty() {
+        return Err(ApiError::bad_request("empty_backup", "数据库中没有可备份的表"));
+    }
+
+    let mut tables = Vec::with_capacity(table_names.len());
+    for name in &table_names {
+        let table = db_manager.get_table_dump(name).await
+            .map_err(|e| ApiError::db("database_error", format!("读取表 {} 的结构失败: {}", name, e)))?;
+        tables.push(table);
+    }
+
+    let (chunk_tx, chunk_rx) = mpsc::channel::<std::io::Result<Vec<u8>>>(8);
+    let content = payload.content;
+    let include_drop = payload.include_drop;
+    let table_count = tables.len();
+    // 登记为后台任务，可通过 /api/jobs/:id/cancel 中断下载
+    let handle = jobs::registry().register(None, JobKind::Backup, connection.id, format!("备份 {} 张表", table_count))
+        .map_err(|e| ApiError::internal("job_error", e))?;
+    let job_id = handle.id.clone();
+    jobs::spawn(handle, move |mut handle| async move {
+        let mut writer = DumpWriter { buf: String::new(), tx: chunk_tx };
+        let outcome = tokio::select! {
+            res = write_dump(&db_manager, &tables, content, include_drop, &mut writer) => res,
+            _ = handle.cancelled() => Err("备份已取消".to_string()),
+        };
+        match outcome {
+            Ok(rows) => {
+                info!("[API] POST /api/database/backup - 备份完成: 表数量={}, 行数={}", table_count, rows);
+                Ok(Some(serde_json::json!({ "tables": table_count, "rows": rows })))
+            }
+            Err(e) => {
+                error!("[API] POST /api/database/backup - 备份失败: {}", e);
+                // 写入错误注释后以错误结束响应流，避免客户端把不完整的备份当作成功
+                let _ = writer.line(&format!("\n-- ERROR: 备份未完成: {}", e)).await;
+                let _ = writer.flush().await;
+                let _ = writer.tx.send(Err(std::io::Error::other(e.clone()))).await;
+                Err(e)
+            }
+        }
+    });
+
+    let stream = futures_util::stream::unfold(chunk_rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    let file_name = payload.file_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!(
+            "{}-{}.sql",
+            connection.database_name.clone().unwrap_or_else(|| connection.name.clone()),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+        .replace(['"', '/', '\\'], "")
+        // 响应头只允许可见ASCII字符
+        .chars()
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .collect::<String>();
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/sql; charset=utf-8")
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name))
+        .header("X-Table-Count", table_count.to_string())
+        .header("X-Job-Id", job_id)
+        .body(Body::from_stream(stream))
+        .map_err(|e| ApiError::internal("backup_error", format!("构建响应失败: {}", e)))
+}
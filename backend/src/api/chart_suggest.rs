@@ -0,0 +1,77 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::services::ai::{format_result_preview, AiService};
+use crate::services::chart_suggest::{format_profiles, profile_columns, suggest_charts, validate_spec, ChartSpec, ColumnProfile};
+
+// 参与统计的最大行数（超出部分忽略）
+const MAX_ROWS: usize = 10_000;
+// 发送给AI的预览行数
+const AI_PREVIEW_ROWS: usize = 20;
+
+// 图表推荐请求：之前返回的查询结果，可选让AI参与推荐
+#[derive(Debug, Deserialize)]
+pub struct ChartSuggestRequest {
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub rows: Vec<Vec<JsonValue>>,
+    #[serde(default)]
+    pub use_ai: bool,
+    // 生成该结果的自然语言问题，帮助AI理解分析意图
+    #[serde(default)]
+    pub question: Option<String>,
+}
+
+// 图表推荐响应：chart 为首选配置，suggestions 为按适合程度排序的全部候选
+#[derive(Debug, Serialize)]
+pub struct ChartSuggestResponse {
+    pub chart: ChartSpec,
+    pub suggestions: Vec<ChartSpec>,
+    pub columns: Vec<ColumnProfile>,
+    pub source: &'static str,  // rules / ai
+}
+
+/**
+ * 图表推荐处理函数
+ * 按列类型和基数推荐图表；use_ai 时由AI选择，AI不可用或配置无效时回退到规则推荐
+ */
+pub async fn suggest_chart(
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(req): Json<ChartSuggestRequest>,
+) -> Result<Json<ChartSuggestResponse>, ApiError> {
+    info!("[API] POST /api/tools/chart-suggest - 图表推荐请求: 列数={}, 行数={}, use_ai={}", req.columns.len(), req.rows.len(), req.use_ai);
+
+    if req.columns.is_empty() {
+        return Err(ApiError::bad_request("empty_columns", "结果列不能为空"));
+    }
+    let rows = &req.rows[..req.rows.len().min(MAX_ROWS)];
+    let profiles = profile_columns(&req.columns, rows);
+    let mut suggestions = suggest_charts(&profiles);
+    let mut source = "rules";
+
+    if req.use_ai {
+        let ai_service = ai_service.as_ref()
+            .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+        let preview = format_result_preview(&req.columns, rows, AI_PREVIEW_ROWS);
+        match ai_service.suggest_chart(req.question.as_deref(), &format_profiles(&profiles), &preview).await {
+            Ok(Some(chart)) => match validate_spec(&chart, &req.columns) {
+                Ok(()) => {
+                    // AI的推荐排在首位，去掉与之相同映射的规则候选
+                    suggestions.retain(|s| (s.chart_type, &s.x, &s.y) != (chart.chart_type, &chart.x, &chart.y));
+                    suggestions.insert(0, chart);
+                    source = "ai";
+                }
+                Err(e) => warn!("[API] AI图表推荐无效，使用规则推荐: {}", e),
+            },
+            Ok(None) => warn!("[API] AI图表推荐无法解析，使用规则推荐"),
+            Err(e) => warn!("[API] AI图表推荐失败，使用规则推荐: {}", e),
+        }
+    }
+
+    let chart = suggestions[0].clone();
+    info!("[API] POST /api/tools/chart-suggest - 响应成功: chart_type={:?}, source={}", chart.chart_type, source);
+    Ok(Json(ChartSuggestResponse { chart, suggestions, columns: profiles, source }))
+}
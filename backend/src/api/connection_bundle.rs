@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::services::connection_bundle::{self, ConflictStrategy, ConnectionBundle, ExportedConnection};
+
+// 连接配置导出请求
+#[derive(Debug, Deserialize)]
+pub struct ExportConnectionsRequest {
+    pub passphrase: String,
+    // 是否包含密码（不包含时连接字符串中的密码也会被去掉）
+    #[serde(default)]
+    pub include_passwords: bool,
+    // 要导出的连接，为空时导出全部
+    #[serde(default)]
+    pub connection_ids: Option<Vec<i64>>,
+}
+
+// 连接配置导入请求
+#[derive(Debug, Deserialize)]
+pub struct ImportConnectionsRequest {
+    pub bundle: ConnectionBundle,
+    pub passphrase: String,
+    #[serde(default)]
+    pub on_conflict: ConflictStrategy,
+}
+
+// 单个连接的导入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Created,
+    Overwritten,
+    Renamed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedConnection {
+    pub name: String,
+    pub action: ImportAction,
+    pub id: Option<i64>,
+    pub imported_as: Option<String>,  // 重命名导入时的新名称
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ImportConnectionsResult {
+    pub created: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+    pub connections: Vec<ImportedConnection>,
+}
+
+/**
+ * 导出连接配置处理函数
+ * 返回用口令加密的JSON配置包，可在其他机器上导入
+ */
+pub async fn export_connections(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<ExportConnectionsRequest>,
+) -> Result<Json<ConnectionBundle>, ApiError> {
+    info!("[API] POST /api/connections/export - 请求: include_passwords={}, connection_ids={:?}",
+        payload.include_passwords, payload.connection_ids);
+
+    let mut connections = storage.list_connections().await
+        .map_err(|e| ApiError::db("database_error", format!("获取连接列表失败: {}", e)))?;
+    if let Some(ids) = payload.connection_ids.filter(|ids| !ids.is_empty()) {
+        connections.retain(|c| c.id.map(|id| ids.contains(&id)).unwrap_or(false));
+    }
+    let groups: HashMap<i64, String> = storage.list_connection_groups().await
+        .map_err(|e| ApiError::db("database_error", format!("获取分组列表失败: {}", e)))?
+        .into_iter()
+        .map(|g| (g.id, g.name))
+        .collect();
+
+    let exported: Vec<ExportedConnection> = connections.iter()
+        .map(|c| {
+            let group = c.group_id.and_then(|id| groups.get(&id).cloned());
+            ExportedConnection::from_connection(c, group, payload.include_passwords)
+        })
+        .collect();
+    // 密钥派生耗时较长，在阻塞线程中执行
+    let exported_at = LocalStorageManager::current_timestamp();
+    let bundle = tokio::task::spawn_blocking(move || {
+        connection_bundle::seal(&exported, &payload.passphrase, payload.include_passwords, exported_at)
+    })
+    .await
+    .map_err(|e| ApiError::internal("export_failed", format!("加密连接配置失败: {}", e)))?
+    .map_err(|e| ApiError::bad_request("invalid_passphrase", e))?;
+
+    info!("[API] POST /api/connections/export - 导出完成: 连接数={}", bundle.count);
+    Ok(Json(bundle))
+}
+
+/**
+ * 导入连接配置处理函数
+ * 按连接名处理冲突：skip 跳过、overwrite 覆盖、rename 以新名称导入；分组按名称匹配，不存在时创建
+ */
+pub async fn import_connections(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<ImportConnectionsRequest>,
+) -> Result<Json<ImportConnectionsResult>, ApiError> {
+    info!("[API] POST /api/connections/import - 请求: 连接数={}, on_conflict={:?}", payload.bundle.count, payload.on_conflict);
+
+    // 密钥派生耗时较长，在阻塞线程中执行
+    let ImportConnectionsRequest { bundle, passphrase, on_conflict } = payload;
+    let includes_passwords = bundle.includes_passwords;
+    let imported = tokio::task::spawn_blocking(move || connection_bundle::open(&bundle, &passphrase))
+        .await
+        .map_err(|e| ApiError::internal("import_failed", format!("解密连接配置失败: {}", e)))?
+        .map_err(|e| ApiError::bad_request("invalid_bundle", e))?;
+    for connection in &imported {
+        crate::db::ssl::normalize_ssl_mode(connection.ssl_mode.as_deref())
+            .map_err(|e| ApiError::bad_request("invalid_ssl_config", format!("连接 {} 的SSL配置无效: {}", connection.name, e)))?;
+        crate::db::validate_pool_options(&connection.pool_options)
+            .map_err(|e| ApiError::bad_request("invalid_pool_options", format!("连接 {} 的连接池配置无效: {}", connection.name, e)))?;
+        crate::db::validate_connection_options(&connection.options, &connection.db_type)
+            .map_err(|e| ApiError::bad_request("invalid_connection_options", format!("连接 {} 的查询默认值无效: {}", connection.name, e)))?;
+    }
+
+    let storage_error = |e: sqlx::Error| ApiError::db("database_error", format!("导入连接失败: {}", e));
+    let mut existing: HashMap<String, i64> = storage.list_connections().await
+        .map_err(storage_error)?
+        .into_iter()
+        .filter_map(|c| c.id.map(|id| (c.name, id)))
+        .collect();
+    let mut groups: HashMap<String, i64> = storage.list_connection_groups().await
+        .map_err(storage_error)?
+        .into_iter()
+        .map(|g| (g.name, g.id))
+        .collect();
+
+    let mut result = ImportConnectionsResult::default();
+    for connection in imported {
+        let name = connection.name.clone();
+        let existing_id = existing.get(&name).copied();
+        if existing_id.is_some() && on_conflict == ConflictStrategy::Skip {
+            result.skipped += 1;
+            result.connections.push(ImportedConnection { name, action: ImportAction::Skipped, id: existing_id, imported_as: None });
+            continue;
+        }
+
+        let group_id = match connection.group.as_deref().map(str::trim).filter(|g| !g.is_empty()) {
+            Some(group) => match groups.get(group) {
+                Some(id) => Some(*id),
+                None => {
+                    let created = storage.create_connection_group(group, None).await.map_err(storage_error)?;
+                    groups.insert(created.name.clone(), created.id);
+                    Some(created.id)
+                }
+            },
+            None => None,
+        };
+
+        match (existing_id, on_conflict) {
+            (Some(id), ConflictStrategy::Overwrite) => {
+                // 配置包不含密码时保留已有密码
+                let keep_password = !includes_passwords;
+                let current = storage.get_connection(id).await.map_err(storage_error)?;
+                let mut request = connection.into_request(name.clone(), group_id);
+                if keep_password {
+                    request.password = current.password;
+                    // 连接字符串只去掉了密码时沿用原连接字符串
+                    if let (Some(imported), Some(old)) = (&request.connection_string, &current.connection_string) {
+                        if *imported == connection_bundle::strip_url_password(old) {
+                            request.connection_string = Some(old.clone());
+                        }
+                    }
+                }
+                storage.update_connection(id, request).await.map_err(storage_error)?;
+                crate::db::pool_cache::invalidate(id);
+                storage.move_connection(id, group_id, None).await.map_err(storage_error)?;
+                result.overwritten += 1;
+                result.connections.push(ImportedConnection { name, action: ImportAction::Overwritten, id: Some(id), imported_as: None });
+            }
+            (Some(_), _) => {
+                let new_name = connection_bundle::unique_name(&name, |candidate| existing.contains_key(candidate));
+                let created = storage.create_connection(connection.into_request(new_name.clone(), group_id)).await.map_err(storage_error)?;
+                existing.insert(new_name.clone(), created.id.unwrap_or_default());
+                result.renamed += 1;
+                result.connections.push(ImportedConnection { name, action: ImportAction::Renamed, id: created.id, imported_as: Some(new_name) });
+            }
+            (None, _) => {
+                let created = storage.create_connection(connection.into_request(name.clone(), group_id)).await.map_err(storage_error)?;
+                existing.insert(name.clone(), created.id.unwrap_or_default());
+                result.created += 1;
+                result.connections.push(ImportedConnection { name, action: ImportAction::Created, id: created.id, imported_as: None });
+            }
+        }
+    }
+
+    info!("[API] POST /api/connections/import - 导入完成: 新建={}, 覆盖={}, 重命名={}, 跳过={}",
+        result.created, result.overwritten, result.renamed, result.skipped);
+    Ok(Json(result))
+}
@@ -0,0 +1,141 @@
+use std::time::Instant;
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::{pool_cache, LocalStorageManager};
+use crate::models::{ConnectionHealthRecord, DatabaseConnection};
+use crate::services::connection_health::{
+    self, ConnectionHealthSummary, DEFAULT_HEALTH_HISTORY, HEALTH_CHECK_TIMEOUT, HEALTH_RETENTION_DAYS, MAX_HEALTH_HISTORY,
+};
+use crate::services::events::{self, ServerEvent};
+
+// 检测单个连接：建立连接并执行 SELECT 1 / ping
+async fn check_connection(storage: &LocalStorageManager, connection: &DatabaseConnection) -> Option<ConnectionHealthRecord> {
+    let connection_id = connection.id?;
+    let start = Instant::now();
+    let check = async {
+        let (_, db_manager) = connect_database(storage, Some(connection_id)).await
+            .map_err(|e| e.message().to_string())?;
+        db_manager.test_connection().await.map_err(|e| e.to_string())
+    };
+    let result = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("检测超时（{}秒）", HEALTH_CHECK_TIMEOUT.as_secs())),
+    };
+
+    Some(ConnectionHealthRecord {
+        id: None,
+        connection_id,
+        checked_at: LocalStorageManager::current_timestamp(),
+        available: result.is_ok(),
+        latency_ms: Some(start.elapsed().as_millis() as i64),
+        error: result.err(),
+    })
+}
+
+// 并发检测所有激活的连接，保存结果并推送 connection_health 事件
+async fn check_active_connections(storage: &LocalStorageManager) {
+    let connections = match storage.get_active_connections().await {
+        Ok(connections) => connections,
+        Err(e) => {
+            warn!("[Health] 获取激活连接失败: {}", e);
+            return;
+        }
+    };
+
+    let checks = connections.iter().map(|connection| check_connection(storage, connection));
+    for record in futures_util::future::join_all(checks).await.into_iter().flatten() {
+        if !record.available {
+            warn!("[Health] 连接不可用: connection_id={}, 错误={:?}", record.connection_id, record.error);
+        }
+        if let Err(e) = storage.add_connection_health(&record).await {
+            warn!("[Health] 保存健康检查记录失败: {}", e);
+        }
+        events::publish(ServerEvent::ConnectionHealth {
+            connection_id: record.connection_id,
+            available: record.available,
+            latency_ms: record.latency_ms,
+            error: record.error,
+            timestamp: events::now(),
+        });
+    }
+
+    let before = LocalStorageManager::current_timestamp() - HEALTH_RETENTION_DAYS * 24 * 3600;
+    if let Err(e) = storage.prune_connection_health(before).await {
+        warn!("[Health] 清理过期健康检查记录失败: {}", e);
+    }
+}
+
+// 启动后台健康检测任务（CONNECTION_HEALTH_INTERVAL_SECS=0 时不启动），返回任务句柄用于停止
+pub fn spawn_health_monitor(storage: LocalStorageManager) -> Option<JoinHandle<()>> {
+    let Some(interval) = connection_health::health_interval() else {
+        info!("[Health] 连接健康检测已关闭");
+        return None;
+    };
+    info!("[Health] 连接健康检测已启动: 间隔={}秒", interval.as_secs());
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            check_active_connections(&storage).await;
+        }
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HealthHistoryParams {
+    pub limit: Option<i64>,
+}
+
+/**
+ * 获取连接健康状态处理函数
+ * 返回最近状态、可用率、平均延迟和检测历史（按时间倒序）
+ */
+pub async fn get_connection_health(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+    Query(params): Query<HealthHistoryParams>,
+) -> Result<Json<ConnectionHealthSummary>, ApiError> {
+    info!("[API] GET /api/connections/{}/health - 请求: limit={:?}", id, params.limit);
+
+    storage.get_connection_by_id(id).await
+        .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("not_found", format!("连接ID {}不存在", id)))?;
+    let limit = params.limit.unwrap_or(DEFAULT_HEALTH_HISTORY).clamp(1, MAX_HEALTH_HISTORY);
+    let records = storage.list_connection_health(id, limit).await
+        .map_err(|e| ApiError::db("database_error", format!("获取健康检查记录失败: {}", e)))?;
+
+    Ok(Json(connection_health::summarize(id, records)))
+}
+
+/**
+ * 获取连接池统计处理函数
+ * 返回连接的各个缓存连接池（含切换数据库后的连接池）的连接数、空闲连接、等待次数和建立连接失败记录
+ */
+pub async fn get_connection_pool_stats(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/connections/{}/pool-stats - 请求", id);
+
+    storage.get_connection_by_id(id).await
+        .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("not_found", format!("连接ID {}不存在", id)))?;
+    let pools = pool_cache::pool_stats(id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "connection_id": id,
+        "data": pools,
+        "count": pools.len(),
+    })))
+}
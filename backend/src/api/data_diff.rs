@@ -0,0 +1,196 @@
+use std::time::Instant;
+use axum::Json;
+use axum::Extension;
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database, get_table_structure_internal, parse_sql};
+use crate::db::ddl::quote_identifier;
+use crate::db::{DatabaseManager, DatabasePool, DatabaseType, LocalStorageManager};
+use crate::services::data_diff::{self, DataDiffResult, DiffTable};
+
+// 每侧默认读取的行数
+const DEFAULT_ROW_LIMIT: usize = 10_000;
+// 每侧最多读取的行数
+const MAX_ROW_LIMIT: usize = 100_000;
+// 每类差异默认返回的行明细数
+const DEFAULT_REPORTED_ROWS: usize = 100;
+
+// 对比的一侧：表名或查询二选一
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffSide {
+    pub connection_id: Option<i64>,
+    pub table: Option<String>,
+    pub sql: Option<String>,
+}
+
+// 数据对比请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataDiffRequest {
+    pub source: DiffSide,
+    pub target: DiffSide,
+    // 用于匹配行的列，为空时使用源表的主键
+    #[serde(default)]
+    pub key_columns: Option<Vec<String>>,
+    pub row_limit: Option<usize>,
+    pub max_reported_rows: Option<usize>,
+}
+
+// 将一行数据转换为文本值（NULL 为 None，无法解码的值按字节转换）
+fn row_texts<R>(row: &R) -> Vec<Option<String>>
+where
+    R: sqlx::Row,
+    usize: sqlx::ColumnIndex<R>,
+    for<'r> String: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> f64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> bool: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    for<'r> Vec<u8>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+{
+    (0..row.len())
+        .map(|i| {
+            if let Ok(v) = row.try_get::<Option<String>, _>(i) {
+                v
+            } else if let Ok(v) = row.try_get::<Option<i64>, _>(i) {
+                v.map(|v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<f64>, _>(i) {
+                v.map(|v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<bool>, _>(i) {
+                v.map(|v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<Vec<u8>>, _>(i) {
+                v.map(|v| String::from_utf8_lossy(&v).into_owned())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+macro_rules! fetch_diff_rows {
+    ($pool:expr, $sql:expr) => {{
+        use sqlx::Column;
+        let mut rows = sqlx::query($sql).fetch($pool);
+        let mut table = DiffTable::default();
+        while let Some(row) = rows.try_next().await
+            .map_err(|e| ApiError::db("query_error", format!("读取对比数据失败: {}", e)))?
+        {
+            if table.columns.is_empty() {
+                table.columns = sqlx::Row::columns(&row).iter().map(|c| c.name().to_string()).collect();
+            }
+            table.rows.push(row_texts(&row));
+        }
+        table
+    }};
+}
+
+// 将列转换为文本后读取，使不同数据库、不同类型的值可以按文本比较
+fn text_expression(db_type: DatabaseType, column: &str) -> Result<String, String> {
+    let quoted = quote_identifier(db_type, column)?;
+    Ok(match db_type {
+        DatabaseType::MySQL => format!("CAST({0} AS CHAR) AS {0}", quoted),
+        DatabaseType::PostgreSQL => format!("{0}::text AS {0}", quoted),
+        _ => format!("CAST({0} AS TEXT) AS {0}", quoted),
+    })
+}
+
+// 生成读取一侧数据的SQL：按主键排序，多读取一行用于判断是否超出上限
+async fn side_sql(db_manager: &DatabaseManager, side: &DiffSide, key_columns: &[String], limit: usize) -> Result<String, ApiError> {
+    let db_type = db_manager.db_type;
+    let quote = |name: &str| quote_identifier(db_type, name).map_err(|e| ApiError::bad_request("invalid_identifier", e));
+    let order_by = key_columns.iter().map(|c| quote(c)).collect::<Result<Vec<_>, _>>()?.join(", ");
+
+    match (&side.table, &side.sql) {
+        (Some(table), None) => {
+            let structure = get_table_structure_internal(db_manager, table).await
+                .map_err(|e| ApiError::db("database_error", format!("获取表 {} 的结构失败: {}", table, e)))?;
+            if structure.columns.is_empty() {
+                return Err(ApiError::not_found("table_not_found", format!("表 {} 不存在", table)));
+            }
+            let columns = structure.columns.iter()
+                .map(|c| text_expression(db_type, &c.name))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| ApiError::bad_request("invalid_identifier", e))?;
+            Ok(format!("SELECT {} FROM {} ORDER BY {} LIMIT {}", columns.join(", "), quote(table)?, order_by, limit + 1))
+        }
+        (None, Some(sql)) => {
+            let sql = sql.trim().trim_end_matches(';');
+            if !matches!(parse_sql(db_type, sql), Ok(sqlparser::ast::Statement::Query(_))) {
+                return Err(ApiError::bad_request("invalid_query", "数据对比只支持 SELECT 查询"));
+            }
+            Ok(format!("SELECT * FROM ({}) diff_rows ORDER BY {} LIMIT {}", sql, order_by, limit + 1))
+        }
+        _ => Err(ApiError::bad_request("invalid_diff_side", "每一侧必须且只能指定 table 或 sql 之一")),
+    }
+}
+
+async fn load_side(storage: &LocalStorageManager, side: &DiffSide, key_columns: &[String], limit: usize) -> Result<(DiffTable, bool), ApiError> {
+    let (_, db_manager) = connect_database(storage, side.connection_id).await?;
+    let sql = side_sql(&db_manager, side, key_columns, limit).await?;
+    let mut table = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => fetch_diff_rows!(pool, &sql),
+        DatabasePool::PostgreSQL(pool) => fetch_diff_rows!(pool, &sql),
+        DatabasePool::SQLite(pool) => fetch_diff_rows!(pool, &sql),
+        DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => {
+            return Err(ApiError::not_implemented("unsupported_database", format!("{:?}不支持数据对比", db_manager.db_type)));
+        }
+    };
+    let truncated = table.rows.len() > limit;
+    table.rows.truncate(limit);
+    Ok((table, truncated))
+}
+
+// 未指定匹配列时使用源表的主键
+async fn default_key_columns(storage: &LocalStorageManager, source: &DiffSide) -> Result<Vec<String>, ApiError> {
+    let Some(table) = &source.table else {
+        return Err(ApiError::bad_request("missing_key_columns", "对比查询结果时必须指定 key_columns"));
+    };
+    let (_, db_manager) = connect_database(storage, source.connection_id).await?;
+    let structure = get_table_structure_internal(&db_manager, table).await
+        .map_err(|e| ApiError::db("database_error", format!("获取表 {} 的结构失败: {}", table, e)))?;
+    let keys: Vec<String> = structure.columns.into_iter()
+        .filter(|c| c.is_primary_key == Some(true))
+        .map(|c| c.name)
+        .collect();
+    if keys.is_empty() {
+        return Err(ApiError::bad_request("missing_key_columns", format!("表 {} 没有主键，请指定 key_columns", table)));
+    }
+    Ok(keys)
+}
+
+/**
+ * 数据对比处理函数
+ * 读取两个连接中表（或查询结果）的数据，按主键匹配行并比较校验和，返回新增、修改、删除的行
+ * 两侧均按主键排序并最多读取 row_limit 行，超出时 truncated 为 true，上限附近的差异可能不准确
+ */
+pub async fn data_diff(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<DataDiffRequest>,
+) -> Result<Json<DataDiffResult>, ApiError> {
+    info!("[API] POST /api/tools/data-diff - 请求: source={:?}/{:?}, target={:?}/{:?}",
+        payload.source.connection_id, payload.source.table, payload.target.connection_id, payload.target.table);
+    let start = Instant::now();
+
+    let row_limit = payload.row_limit.unwrap_or(DEFAULT_ROW_LIMIT);
+    if row_limit == 0 || row_limit > MAX_ROW_LIMIT {
+        return Err(ApiError::bad_request("invalid_row_limit", format!("row_limit 必须在 1 到 {} 之间", MAX_ROW_LIMIT)));
+    }
+    let key_columns = match payload.key_columns.filter(|k| !k.is_empty()) {
+        Some(keys) => keys,
+        None => default_key_columns(&storage, &payload.source).await?,
+    };
+
+    let (source, source_truncated) = load_side(&storage, &payload.source, &key_columns, row_limit).await?;
+    let (target, target_truncated) = load_side(&storage, &payload.target, &key_columns, row_limit).await?;
+
+    let max_reported = payload.max_reported_rows.unwrap_or(DEFAULT_REPORTED_ROWS);
+    let mut result = data_diff::diff_tables(&source, &target, &key_columns, max_reported)
+        .map_err(|e| ApiError::bad_request("diff_failed", e))?;
+    result.truncated = source_truncated || target_truncated;
+    result.execution_time_ms = start.elapsed().as_millis();
+
+    info!("[API] POST /api/tools/data-diff - 对比完成: 新增={}, 修改={}, 删除={}, 相同={}, 耗时={}ms",
+        result.inserted, result.updated, result.deleted, result.unchanged, result.execution_time_ms);
+    Ok(Json(result))
+}
@@ -0,0 +1,683 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use serde::{Serialize, Deserialize};
+use log::*;
+
+use crate::api::routes::{connect_database, get_schema_cache, get_table_structure_internal, parse_sql, production_guard};
+use crate::db::comments::table_comment_sql;
+use crate::db::ddl::{self, ColumnDefinition, IndexDefinition, TableDefinition, TriggerDefinition, ViewDefinition};
+use crate::db::objects::{DatabaseObject, ObjectKind, ViewSource};
+use crate::db::dump::format_ddl;
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::api::error::ApiError;
+use crate::models::DatabaseConnection;
+use crate::services::audit::{self, AuditRecord};
+
+// 连接参数（通过查询字符串指定，未指定时使用第一个活动连接）
+#[derive(Deserialize)]
+pub struct ConnectionParams {
+    pub connection_id: Option<i64>,
+    // 生产环境连接上执行时需显式确认
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+// 删除表 / 视图参数
+#[derive(Deserialize)]
+pub struct DropTableParams {
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub if_exists: bool,
+    #[serde(default)]
+    pub cascade: bool,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+// 重命名表请求
+#[derive(Serialize, Deserialize)]
+pub struct RenameTableRequest {
+    pub new_name: String,
+}
+
+// 创建索引请求
+#[derive(Serialize, Deserialize)]
+pub struct CreateIndexRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub unique: bool,
+    // AI优化建议中的 CREATE INDEX 语句（指定时忽略上面的字段）
+    #[serde(default)]
+    pub sql: Option<String>,
+}
+
+// DDL执行响应
+#[derive(Serialize, Deserialize)]
+pub struct DdlResponse {
+    pub success: bool,
+    pub statements: Vec<String>,
+    pub message: String,
+}
+
+// 设置注释请求（comment 为空或null时清除注释）
+#[derive(Serialize, Deserialize)]
+pub struct CommentRequest {
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+// 表DDL响应
+#[derive(Serialize, Deserialize)]
+pub struct TableDdlResponse {
+    pub table: String,
+    pub ddl: String,              // 完整的DDL文本
+    pub statements: Vec<String>,  // 建表语句以及索引、触发器、外键
+}
+
+// 修改视图请求（视图名由路径指定）
+#[derive(Serialize, Deserialize)]
+pub struct AlterViewRequest {
+    pub query: String,
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+// 视图管理路由（挂载在 /api/database/view 下）
+pub fn view_routes() -> Router {
+    Router::new()
+        // 创建视图
+        .route("/", post(create_view))
+        // 视图定义 / 修改视图 / 删除视图
+        .route("/:view", get(get_view_definition).put(alter_view).delete(drop_view))
+}
+
+// 表管理路由（挂载在 /api/database/table 下）
+pub fn table_routes() -> Router {
+    Router::new()
+        // 创建表
+        .route("/", post(create_table))
+        // 删除表
+        .route("/:table", delete(drop_table))
+        // 获取建表语句（SHOW CREATE TABLE）
+        .route("/:table/ddl", get(get_table_ddl))
+        // 重命名表
+        .route("/:table/rename", put(rename_table))
+        // 添加列
+        .route("/:table/columns", post(add_column))
+        // 修改列
+        .route("/:table/columns/:column", put(modify_column))
+        // 删除列
+        .route("/:table/columns/:column", delete(drop_column))
+        // 设置表注释
+        .route("/:table/comment", put(set_table_comment))
+        // 设置列注释
+        .route("/:table/columns/:column/comment", put(set_column_comment))
+        // 创建索引
+        .route("/:table/indexes", post(create_index))
+        // 删除索引
+        .route("/:table/indexes/:name", delete(drop_index))
+        // 表的触发器列表 / 创建触发器
+        .route("/:table/triggers", get(list_table_triggers).post(create_trigger))
+        // 触发器定义 / 删除触发器
+        .route("/:table/triggers/:name", get(get_trigger_ddl).delete(drop_trigger))
+}
+
+// 删除触发器参数
+#[derive(Deserialize)]
+pub struct DropTriggerParams {
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub if_exists: bool,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+// 触发器定义响应
+#[derive(Serialize, Deserialize)]
+pub struct TriggerDdlResponse {
+    pub table: String,
+    pub trigger: String,
+    pub ddl: String,
+}
+
+fn ensure_trigger_support(db_type: DatabaseType) -> Result<(), ApiError> {
+    if matches!(db_type, DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite) {
+        return Ok(());
+    }
+    Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持触发器管理", db_type)))
+}
+
+// 记录DDL的审计日志
+fn record_audit(storage: &LocalStorageManager, connection: &DatabaseConnection, statements: &[String], error: Option<String>) {
+    audit::record_in_background(storage, AuditRecord {
+        connection,
+        source: audit::SOURCE_DDL,
+        ai_generated: false,
+        affected_rows: None,
+        error,
+    }, statements);
+}
+
+// 生成并执行DDL语句
+async fn run_ddl<F>(
+    storage: &LocalStorageManager,
+    connection_id: Option<i64>,
+    confirm_production: bool,
+    action: &str,
+    build: F,
+) -> Result<Json<DdlResponse>, ApiError>
+where
+    F: FnOnce(DatabaseType) -> Result<Vec<String>, String>,
+{
+    let (connection, db_manager) = connect_database(storage, connection_id).await?;
+
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持表结构管理", db_manager.db_type)));
+    }
+
+    let statements = build(db_manager.db_type).map_err(|e| ApiError::bad_request("invalid_ddl", e))?;
+
+    production_guard(&connection, &statements, confirm_production)?;
+
+    info!("[API] 执行DDL({}): {:?}", action, statements);
+
+    let result = db_manager.execute_statements(&statements).await;
+    record_audit(storage, &connection, &statements, result.as_ref().err().map(|e| e.to_string()));
+    result.map_err(|e| {
+        ApiError::bad_request("ddl_error", format!("{}失败: {}", action, e)).with_details(statements.join(";\n"))
+    })?;
+
+    info!("[API] DDL执行成功: {}", action);
+
+    if let Some(id) = connection.id {
+        get_schema_cache().invalidate(id);
+    }
+
+    Ok(Json(DdlResponse {
+        success: true,
+        statements,
+        message: format!("{}成功", action),
+    }))
+}
+
+/**
+ * 创建表处理函数
+ */
+pub async fn create_table(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<TableDefinition>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] POST /api/database/table - 创建表请求: 表={}, 列数={}", payload.table_name, payload.columns.len());
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "创建表", |db_type| {
+        ddl::create_table_sql(db_type, &payload)
+    }).await
+}
+
+/**
+ * 删除表处理函数
+ */
+pub async fn drop_table(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<DropTableParams>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] DELETE /api/database/table/{} - 删除表请求", table);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "删除表", |db_type| {
+        ddl::drop_table_sql(db_type, &table, params.if_exists, params.cascade)
+    }).await
+}
+
+/**
+ * 获取表DDL处理函数
+ * MySQL 使用 SHOW CREATE TABLE，PostgreSQL 由系统目录重建，SQLite 读取 sqlite_master
+ */
+pub async fn get_table_ddl(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<TableDdlResponse>, ApiError> {
+    info!("[API] GET /api/database/table/{}/ddl - 获取表DDL请求", table);
+
+    let (_, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持导出表DDL", db_manager.db_type)));
+    }
+
+    let dump = db_manager.get_table_dump(&table).await
+        .map_err(|e| ApiError::bad_request("ddl_error", format!("获取表 {} 的DDL失败: {}", table, e)))?;
+    let statements = dump.ddl_statements();
+    if statements.is_empty() {
+        return Err(ApiError::not_found("table_not_found", format!("表 {} 不存在", table)));
+    }
+
+    info!("[API] GET /api/database/table/{}/ddl - 响应成功: 语句数量={}", table, statements.len());
+    Ok(Json(TableDdlResponse {
+        table,
+        ddl: format_ddl(&statements),
+        statements,
+    }))
+}
+
+/**
+ * 重命名表处理函数
+ */
+pub async fn rename_table(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<RenameTableRequest>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] PUT /api/database/table/{}/rename - 重命名表请求: 新表名={}", table, payload.new_name);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "重命名表", |db_type| {
+        ddl::rename_table_sql(db_type, &table, &payload.new_name)
+    }).await
+}
+
+/**
+ * 添加列处理函数
+ */
+pub async fn add_column(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<ColumnDefinition>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] POST /api/database/table/{}/columns - 添加列请求: 列={}", table, payload.name);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "添加列", |db_type| {
+        ddl::add_column_sql(db_type, &table, &payload)
+    }).await
+}
+
+/**
+ * 修改列处理函数
+ */
+pub async fn modify_column(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, column)): Path<(String, String)>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<ColumnDefinition>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] PUT /api/database/table/{}/columns/{} - 修改列请求", table, column);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "修改列", |db_type| {
+        ddl::modify_column_sql(db_type, &table, &column, &payload)
+    }).await
+}
+
+/**
+ * 删除列处理函数
+ */
+pub async fn drop_column(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, column)): Path<(String, String)>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] DELETE /api/database/table/{}/columns/{} - 删除列请求", table, column);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "删除列", |db_type| {
+        ddl::drop_column_sql(db_type, &table, &column)
+    }).await
+}
+
+// 校验 CREATE INDEX 语句只作用于指定表
+fn validate_create_index_sql(db_type: DatabaseType, sql: &str, table: &str) -> Result<String, String> {
+    lazy_static::lazy_static! {
+        static ref ON_TABLE_RE: regex::Regex = regex::Regex::new(
+            r#"(?is)^\s*CREATE\s+(?:UNIQUE\s+)?INDEX\s+.*?\bON\s+(?:[`"]?\w+[`"]?\.)?[`"]?(\w+)[`"]?"#
+        ).unwrap();
+    }
+
+    let statement = parse_sql(db_type, sql)?;
+    if !matches!(statement, sqlparser::ast::Statement::CreateIndex { .. }) {
+        return Err("只允许执行 CREATE INDEX 语句".to_string());
+    }
+
+    match ON_TABLE_RE.captures(sql) {
+        Some(caps) if caps[1].eq_ignore_ascii_case(table) => {
+            Ok(sql.trim().trim_end_matches(';').to_string())
+        }
+        Some(caps) => Err(format!("索引语句作用于表 {}，与当前表 {} 不一致", &caps[1], table)),
+        None => Err("无法识别索引语句中的表名".to_string()),
+    }
+}
+
+// 设置表或列注释：MySQL / PostgreSQL 执行DDL，SQLite 没有原生注释，保存在本地存储
+async fn apply_comment(
+    storage: &LocalStorageManager,
+    params: &ConnectionParams,
+    table: &str,
+    column: Option<&str>,
+    comment: Option<String>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    let comment = comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    let (connection, db_manager) = connect_database(storage, params.connection_id).await?;
+    let target = match column {
+        Some(column) => format!("列 {}.{}", table, column),
+        None => format!("表 {}", table),
+    };
+
+    let statements = match db_manager.db_type {
+        DatabaseType::MySQL | DatabaseType::PostgreSQL => {
+            let statements = match column {
+                Some(column) => db_manager.column_comment_statements(table, column, comment.as_deref()).await
+                    .map_err(|e| ApiError::bad_request("invalid_ddl", format!("生成注释语句失败: {}", e)))?,
+                None => table_comment_sql(db_manager.db_type, table, comment.as_deref())
+                    .map_err(|e| ApiError::bad_request("invalid_ddl", e))?,
+            };
+            production_guard(&connection, &statements, params.confirm_production)?;
+            info!("[API] 设置{}的注释: {:?}", target, statements);
+            let result = db_manager.execute_statements(&statements).await;
+            record_audit(storage, &connection, &statements, result.as_ref().err().map(|e| e.to_string()));
+            result.map_err(|e| ApiError::bad_request("ddl_error", format!("设置{}的注释失败: {}", target, e)))?;
+            statements
+        }
+        DatabaseType::SQLite => {
+            let conn_id = connection.id
+                .ok_or_else(|| ApiError::bad_request("connection_not_found", "连接未保存，无法保存注释"))?;
+            // 注释保存在本地，先确认表和列存在
+            let structure = get_table_structure_internal(&db_manager, table).await
+                .map_err(|e| ApiError::bad_request("database_error", format!("获取表 {} 的结构失败: {}", table, e)))?;
+            let exists = match column {
+                Some(column) => structure.columns.iter().any(|c| c.name == column),
+                None => !structure.columns.is_empty(),
+            };
+            if !exists {
+                return Err(ApiError::not_found("object_not_found", format!("{}不存在", target)));
+            }
+            storage.set_object_comment(conn_id, table, column.unwrap_or_default(), comment.as_deref()).await
+                .map_err(|e| ApiError::internal("storage_error", format!("保存注释失败: {}", e)))?;
+            info!("[API] {}的注释已保存到本地存储", target);
+            Vec::new()
+        }
+        _ => return Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持设置注释", db_manager.db_type))),
+    };
+
+    if let Some(id) = connection.id {
+        get_schema_cache().invalidate(id);
+    }
+
+    Ok(Json(DdlResponse {
+        success: true,
+        statements,
+        message: format!("设置{}的注释成功", target),
+    }))
+}
+
+/**
+ * 设置表注释处理函数
+ */
+pub async fn set_table_comment(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<CommentRequest>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] PUT /api/database/table/{}/comment - 设置表注释请求", table);
+    apply_comment(&storage, &params, &table, None, payload.comment).await
+}
+
+/**
+ * 设置列注释处理函数
+ */
+pub async fn set_column_comment(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, column)): Path<(String, String)>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<CommentRequest>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] PUT /api/database/table/{}/columns/{}/comment - 设置列注释请求", table, column);
+    apply_comment(&storage, &params, &table, Some(&column), payload.comment).await
+}
+
+/**
+ * 创建索引处理函数
+ */
+pub async fn create_index(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<CreateIndexRequest>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] POST /api/database/table/{}/indexes - 创建索引请求: 列={:?}, SQL={:?}", table, payload.columns, payload.sql);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    let preview = payload.sql.clone().unwrap_or_else(|| format!("CREATE INDEX ON {}", table));
+    production_guard(&connection, std::slice::from_ref(&preview), params.confirm_production)?;
+
+    let result = match &payload.sql {
+        Some(sql) => {
+            let sql = validate_create_index_sql(db_manager.db_type, sql, &table)
+                .map_err(|e| ApiError::bad_request("invalid_ddl", e).with_details(sql.clone()))?;
+            db_manager.execute_statements(std::slice::from_ref(&sql)).await.map(|_| sql)
+        }
+        None => {
+            let index = IndexDefinition {
+                name: payload.name.clone(),
+                columns: payload.columns.clone(),
+                unique: payload.unique,
+            };
+            db_manager.create_index(&table, &index).await
+        }
+    };
+
+    match &result {
+        Ok(sql) => record_audit(&storage, &connection, std::slice::from_ref(sql), None),
+        Err(e) => record_audit(&storage, &connection, std::slice::from_ref(&preview), Some(e.to_string())),
+    }
+    let sql = result.map_err(|e| ApiError::bad_request("ddl_error", format!("创建索引失败: {}", e)))?;
+
+    info!("[API] POST /api/database/table/{}/indexes - 响应成功: {}", table, sql);
+
+    if let Some(id) = connection.id {
+        get_schema_cache().invalidate(id);
+    }
+
+    Ok(Json(DdlResponse {
+        success: true,
+        statements: vec![sql],
+        message: "创建索引成功".to_string(),
+    }))
+}
+
+/**
+ * 删除索引处理函数
+ */
+pub async fn drop_index(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, name)): Path<(String, String)>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] DELETE /api/database/table/{}/indexes/{} - 删除索引请求", table, name);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    let preview = format!("DROP INDEX {}", name);
+    production_guard(&connection, std::slice::from_ref(&preview), params.confirm_production)?;
+
+    let result = db_manager.drop_index(&table, &name).await;
+    match &result {
+        Ok(sql) => record_audit(&storage, &connection, std::slice::from_ref(sql), None),
+        Err(e) => record_audit(&storage, &connection, std::slice::from_ref(&preview), Some(e.to_string())),
+    }
+    let sql = result.map_err(|e| ApiError::bad_request("ddl_error", format!("删除索引失败: {}", e)))?;
+
+    info!("[API] DELETE /api/database/table/{}/indexes/{} - 响应成功", table, name);
+
+    if let Some(id) = connection.id {
+        get_schema_cache().invalidate(id);
+    }
+
+    Ok(Json(DdlResponse {
+        success: true,
+        statements: vec![sql],
+        message: "删除索引成功".to_string(),
+    }))
+}
+
+/**
+ * 获取视图定义处理函数
+ * 返回视图的 SELECT 语句和完整的建视图语句
+ */
+pub async fn get_view_definition(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(view): Path<String>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<ViewSource>, ApiError> {
+    info!("[API] GET /api/database/view/{} - 获取视图定义请求", view);
+
+    let (_, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    if !matches!(db_manager.db_type, DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持视图管理", db_manager.db_type)));
+    }
+
+    let source = db_manager.get_view_source(&view).await
+        .map_err(|e| ApiError::bad_request("ddl_error", format!("获取视图 {} 的定义失败: {}", view, e)))?;
+    source.map(Json).ok_or_else(|| ApiError::not_found("view_not_found", format!("视图 {} 不存在", view)))
+}
+
+/**
+ * 创建视图处理函数
+ */
+pub async fn create_view(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<ViewDefinition>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] POST /api/database/view - 创建视图请求: 视图={}", payload.view_name);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "创建视图", |db_type| {
+        ddl::create_view_sql(db_type, &payload, false)
+    }).await
+}
+
+/**
+ * 修改视图处理函数
+ * MySQL / PostgreSQL 使用 CREATE OR REPLACE VIEW，SQLite 先删除再重建
+ */
+pub async fn alter_view(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(view): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<AlterViewRequest>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] PUT /api/database/view/{} - 修改视图请求", view);
+
+    let definition = ViewDefinition {
+        view_name: view,
+        query: payload.query,
+        columns: payload.columns,
+    };
+    run_ddl(&storage, params.connection_id, params.confirm_production, "修改视图", |db_type| {
+        ddl::create_view_sql(db_type, &definition, true)
+    }).await
+}
+
+/**
+ * 删除视图处理函数
+ */
+pub async fn drop_view(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(view): Path<String>,
+    Query(params): Query<DropTableParams>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] DELETE /api/database/view/{} - 删除视图请求", view);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "删除视图", |db_type| {
+        ddl::drop_view_sql(db_type, &view, params.if_exists, params.cascade)
+    }).await
+}
+
+/**
+ * 获取表的触发器列表处理函数
+ * 包含触发时机、事件和触发器语句
+ */
+pub async fn list_table_triggers(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<Vec<DatabaseObject>>, ApiError> {
+    info!("[API] GET /api/database/table/{}/triggers - 获取触发器列表请求", table);
+
+    let (_, db_manager) = connect_database(&storage, params.connection_id).await?;
+    ensure_trigger_support(db_manager.db_type)?;
+
+    let triggers = db_manager.list_objects(Some(ObjectKind::Trigger), None).await
+        .map_err(|e| ApiError::bad_request("ddl_error", format!("获取表 {} 的触发器失败: {}", table, e)))?;
+    let triggers: Vec<DatabaseObject> = triggers.into_iter()
+        .filter(|trigger| trigger.table_name.as_deref() == Some(table.as_str()))
+        .collect();
+
+    info!("[API] GET /api/database/table/{}/triggers - 响应成功: 触发器数量={}", table, triggers.len());
+    Ok(Json(triggers))
+}
+
+/**
+ * 获取触发器定义处理函数
+ * MySQL 使用 SHOW CREATE TRIGGER，PostgreSQL 同时返回触发器函数，SQLite 读取 sqlite_master
+ */
+pub async fn get_trigger_ddl(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, name)): Path<(String, String)>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<TriggerDdlResponse>, ApiError> {
+    info!("[API] GET /api/database/table/{}/triggers/{} - 获取触发器定义请求", table, name);
+
+    let (_, db_manager) = connect_database(&storage, params.connection_id).await?;
+    ensure_trigger_support(db_manager.db_type)?;
+
+    let ddl = db_manager.get_trigger_ddl(&name).await
+        .map_err(|e| ApiError::bad_request("ddl_error", format!("获取触发器 {} 的定义失败: {}", name, e)))?;
+    let ddl = ddl.ok_or_else(|| ApiError::not_found("trigger_not_found", format!("触发器 {} 不存在", name)))?;
+
+    Ok(Json(TriggerDdlResponse {
+        table,
+        trigger: name,
+        ddl,
+    }))
+}
+
+/**
+ * 创建触发器处理函数
+ * PostgreSQL 未指定 function_name 时由 body 创建名为 触发器名_fn 的触发器函数
+ */
+pub async fn create_trigger(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<TriggerDefinition>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] POST /api/database/table/{}/triggers - 创建触发器请求: 触发器={}", table, payload.trigger_name);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "创建触发器", |db_type| {
+        ddl::create_trigger_sql(db_type, &table, &payload)
+    }).await
+}
+
+/**
+ * 删除触发器处理函数
+ */
+pub async fn drop_trigger(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((table, name)): Path<(String, String)>,
+    Query(params): Query<DropTriggerParams>,
+) -> Result<Json<DdlResponse>, ApiError> {
+    info!("[API] DELETE /api/database/table/{}/triggers/{} - 删除触发器请求", table, name);
+
+    run_ddl(&storage, params.connection_id, params.confirm_production, "删除触发器", |db_type| {
+        ddl::drop_trigger_sql(db_type, &table, &name, params.if_exists)
+    }).await
+}
@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Extension, Json, Router,
+};
+use log::*;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::models::{DictionaryEntry, DictionaryEntryRequest};
+use crate::services::data_dictionary::normalize_request;
+
+// 数据字典列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct DictionaryQuery {
+    pub connection_id: i64,
+    #[serde(default)]
+    pub table: Option<String>,
+}
+
+// 数据字典路由（挂载在 /api/dictionary 下）
+pub fn dictionary_routes() -> Router {
+    Router::new()
+        // 条目列表 / 创建或覆盖条目（按 连接+表+列 唯一）
+        .route("/", get(list_dictionary_entries).post(upsert_dictionary_entry))
+        // 获取 / 更新 / 删除单个条目
+        .route("/:id", get(get_dictionary_entry).put(update_dictionary_entry).delete(delete_dictionary_entry))
+}
+
+fn dictionary_storage_error(e: sqlx::Error, action: &str) -> ApiError {
+    match &e {
+        sqlx::Error::RowNotFound => ApiError::not_found("entry_not_found", "数据字典条目不存在"),
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            ApiError::conflict("entry_conflict", "该表/列的数据字典条目已存在")
+        }
+        _ => ApiError::db("database_error", format!("{}失败: {}", action, e)),
+    }
+}
+
+// 校验请求并确认连接存在
+async fn validate_request(storage: &LocalStorageManager, req: DictionaryEntryRequest) -> Result<DictionaryEntryRequest, ApiError> {
+    let req = normalize_request(req).map_err(|e| ApiError::bad_request("invalid_entry", e))?;
+    match storage.get_connection(req.connection_id).await {
+        Ok(_) => Ok(req),
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::not_found("connection_not_found", format!("连接不存在: {}", req.connection_id))),
+        Err(e) => Err(ApiError::db("database_error", format!("获取连接失败: {}", e))),
+    }
+}
+
+/**
+ * 获取数据字典条目列表处理函数
+ * 可按表名过滤
+ */
+pub async fn list_dictionary_entries(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(query): Query<DictionaryQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/dictionary - 获取数据字典请求: connection_id={}, table={:?}", query.connection_id, query.table);
+
+    let entries = storage.list_dictionary_entries(query.connection_id, query.table.as_deref()).await
+        .map_err(|e| dictionary_storage_error(e, "获取数据字典"))?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": entries,
+        "count": entries.len(),
+    })))
+}
+
+/**
+ * 创建数据字典条目处理函数
+ * 同一连接下相同的表/列已有条目时整体覆盖
+ */
+pub async fn upsert_dictionary_entry(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<DictionaryEntryRequest>,
+) -> Result<Json<DictionaryEntry>, ApiError> {
+    info!("[API] POST /api/dictionary - 保存数据字典条目请求: connection_id={}, table={}, column={:?}",
+        payload.connection_id, payload.table_name, payload.column_name);
+
+    let req = validate_request(&storage, payload).await?;
+    let entry = storage.upsert_dictionary_entry(&req).await
+        .map_err(|e| dictionary_storage_error(e, "保存数据字典条目"))?;
+    debug!("[API] 数据字典条目已保存: id={:?}", entry.id);
+    Ok(Json(entry))
+}
+
+/**
+ * 获取单个数据字典条目处理函数
+ */
+pub async fn get_dictionary_entry(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+) -> Result<Json<DictionaryEntry>, ApiError> {
+    info!("[API] GET /api/dictionary/:id - 获取数据字典条目请求: id={}", id);
+
+    storage.get_dictionary_entry(id).await
+        .map(Json)
+        .map_err(|e| dictionary_storage_error(e, "获取数据字典条目"))
+}
+
+/**
+ * 更新数据字典条目处理函数
+ * 改为已有条目的表/列时返回 409
+ */
+pub async fn update_dictionary_entry(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+    Json(payload): Json<DictionaryEntryRequest>,
+) -> Result<Json<DictionaryEntry>, ApiError> {
+    info!("[API] PUT /api/dictionary/:id - 更新数据字典条目请求: id={}", id);
+
+    let req = validate_request(&storage, payload).await?;
+    storage.update_dictionary_entry(id, &req).await
+        .map(Json)
+        .map_err(|e| dictionary_storage_error(e, "更新数据字典条目"))
+}
+
+/**
+ * 删除数据字典条目处理函数
+ */
+pub async fn delete_dictionary_entry(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/dictionary/:id - 删除数据字典条目请求: id={}", id);
+
+    let deleted = storage.delete_dictionary_entry(id).await
+        .map_err(|e| dictionary_storage_error(e, "删除数据字典条目"))?;
+    if !deleted {
+        return Err(ApiError::not_found("entry_not_found", "数据字典条目不存在"));
+    }
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "数据字典条目已删除",
+    })))
+}
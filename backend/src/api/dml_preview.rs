@@ -0,0 +1,93 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::execute_query;
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::models::SqlQueryRequest;
+use crate::services::dml_preview::preview_query;
+
+// 默认返回的受影响行数
+const DEFAULT_PREVIEW_ROWS: u64 = 100;
+// 最多返回的受影响行数
+const MAX_PREVIEW_ROWS: u64 = 1000;
+
+// 预览请求
+#[derive(Debug, Deserialize)]
+pub struct DmlPreviewRequest {
+    pub sql: String,
+    #[serde(default)]
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,   // 为空时使用连接设置的默认超时
+}
+
+// 预览响应：受影响的总行数和前 limit 行数据
+#[derive(Debug, Serialize)]
+pub struct DmlPreviewResponse {
+    pub operation: String,
+    pub table: String,
+    pub select_sql: String,
+    pub affected_rows: u64,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+    pub truncated: bool,             // 受影响的行多于返回的行
+    pub execution_time_ms: u128,
+}
+
+/**
+ * UPDATE / DELETE 预览处理函数
+ * 改写为相同 WHERE 条件的 SELECT 并执行，返回将受影响的行数和行数据，不修改任何数据
+ */
+pub async fn preview_dml(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<DmlPreviewRequest>,
+) -> Result<Json<DmlPreviewResponse>, ApiError> {
+    info!("[API] POST /api/database/query/preview - 预览请求: connection_id={:?}, SQL长度={}", req.connection_id, req.sql.len());
+
+    let db_type = match req.connection_id {
+        Some(id) => storage.get_connection(id).await
+            .map(|c| DatabaseType::from_name(&c.db_type))
+            .map_err(|e| ApiError::not_found("connection_not_found", format!("获取连接失败: {}", e)))?,
+        None => None,
+    };
+    if matches!(db_type, Some(DatabaseType::MongoDB | DatabaseType::Redis)) {
+        return Err(ApiError::bad_request("unsupported_database", "该数据库不支持 UPDATE / DELETE 预览"));
+    }
+    let preview = preview_query(db_type, &req.sql)
+        .map_err(|e| ApiError::bad_request("preview_not_supported", e).with_details(req.sql.clone()))?;
+    debug!("[API] 预览查询: {}", preview.select_sql);
+
+    let limit = req.limit.unwrap_or(DEFAULT_PREVIEW_ROWS).clamp(1, MAX_PREVIEW_ROWS);
+    let query = SqlQueryRequest {
+        sql: preview.select_sql.clone(),
+        connection_id: req.connection_id,
+        timeout_secs: req.timeout_secs,
+        page: Some(1),
+        page_size: Some(limit),
+        count_total: true,
+        database: req.database,
+        ..Default::default()
+    };
+    let Json(result) = execute_query(Extension(storage), Json(query)).await
+        .map_err(|e| e.with_details(preview.select_sql.clone()))?;
+
+    let affected_rows = result.total_rows.unwrap_or(result.row_count as u64);
+    info!("[API] POST /api/database/query/preview - 响应成功: {} {}, 受影响行数={}", preview.operation, preview.table, affected_rows);
+    Ok(Json(DmlPreviewResponse {
+        operation: preview.operation,
+        table: preview.table,
+        select_sql: preview.select_sql,
+        affected_rows,
+        columns: result.columns,
+        truncated: result.has_more,
+        rows: result.rows,
+        execution_time_ms: result.execution_time_ms,
+    }))
+}
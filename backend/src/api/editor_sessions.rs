@@ -0,0 +1,115 @@
+use axum::{
+    extract::Path,
+    routing::get,
+    Extension, Json, Router,
+};
+use log::*;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::models::{EditorSession, EditorTab};
+
+// 未指定名称时使用的会话
+pub const DEFAULT_SESSION_NAME: &str = "default";
+// 单个会话允许保存的标签页数量上限
+const MAX_TABS: usize = 100;
+
+// 保存编辑器会话请求
+#[derive(Debug, Deserialize)]
+pub struct SaveEditorSessionRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tabs: Vec<EditorTab>,
+    #[serde(default)]
+    pub active_tab: Option<String>,
+}
+
+// 编辑器会话路由（挂载在 /api/editor 下）
+pub fn editor_session_routes() -> Router {
+    Router::new()
+        // 会话列表 / 保存会话
+        .route("/sessions", get(list_editor_sessions).put(save_editor_session))
+        // 恢复 / 删除会话
+        .route("/sessions/:name", get(restore_editor_session).delete(delete_editor_session))
+}
+
+/**
+ * 获取编辑器会话列表处理函数
+ */
+pub async fn list_editor_sessions(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/editor/sessions - 获取编辑器会话列表请求");
+
+    let sessions = storage.list_editor_sessions().await
+        .map_err(|e| ApiError::db("list_sessions_failed", format!("获取编辑器会话列表失败: {}", e)))?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": sessions,
+        "count": sessions.len(),
+    })))
+}
+
+/**
+ * 保存编辑器会话处理函数
+ * 同名会话整体覆盖，未指定名称时保存为 default
+ */
+pub async fn save_editor_session(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<SaveEditorSessionRequest>,
+) -> Result<Json<EditorSession>, ApiError> {
+    let name = payload.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).unwrap_or(DEFAULT_SESSION_NAME);
+    info!("[API] PUT /api/editor/sessions - 保存编辑器会话请求: name={}, tabs={}", name, payload.tabs.len());
+
+    if payload.tabs.len() > MAX_TABS {
+        return Err(ApiError::bad_request("too_many_tabs", format!("标签页数量不能超过 {}", MAX_TABS)));
+    }
+    if let Some(index) = payload.tabs.iter().position(|t| t.id.trim().is_empty()) {
+        return Err(ApiError::bad_request("invalid_tab", format!("第 {} 个标签页缺少ID", index + 1)));
+    }
+    // 激活的标签页不在列表中时忽略
+    let active_tab = payload.active_tab.as_deref().filter(|id| payload.tabs.iter().any(|t| t.id == *id));
+
+    let session = storage.save_editor_session(name, &payload.tabs, active_tab).await
+        .map_err(|e| ApiError::db("save_session_failed", format!("保存编辑器会话失败: {}", e)))?;
+    debug!("[API] 编辑器会话已保存: name={}", name);
+    Ok(Json(session))
+}
+
+/**
+ * 恢复编辑器会话处理函数
+ */
+pub async fn restore_editor_session(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(name): Path<String>,
+) -> Result<Json<EditorSession>, ApiError> {
+    info!("[API] GET /api/editor/sessions/:name - 恢复编辑器会话请求: name={}", name);
+
+    match storage.get_editor_session(&name).await {
+        Ok(session) => Ok(Json(session)),
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::not_found("session_not_found", format!("编辑器会话不存在: {}", name))),
+        Err(e) => Err(ApiError::db("get_session_failed", format!("获取编辑器会话失败: {}", e))),
+    }
+}
+
+/**
+ * 删除编辑器会话处理函数
+ */
+pub async fn delete_editor_session(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/editor/sessions/:name - 删除编辑器会话请求: name={}", name);
+
+    let deleted = storage.delete_editor_session(&name).await
+        .map_err(|e| ApiError::db("delete_session_failed", format!("删除编辑器会话失败: {}", e)))?;
+    if !deleted {
+        return Err(ApiError::not_found("session_not_found", format!("编辑器会话不存在: {}", name)));
+    }
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "编辑器会话已删除",
+    })))
+}
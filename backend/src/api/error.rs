@@ -0,0 +1,271 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::models::ErrorResponse as ModelErrorResponse;
+
+// 错误信息：code 为机器可读的错误码（前端据此做错误处理和国际化），
+// message 为默认的中文说明，hint 为可选的处理建议
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct ErrorInfo {
+    #[serde(rename = "error")]
+    pub code: String,
+    pub message: String,
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+// 统一的API错误类型，变体决定HTTP状态码
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest(ErrorInfo),            // 400 请求参数错误
+    Unauthorized(ErrorInfo),          // 401 缺少或无效的访问令牌
+    Forbidden(ErrorInfo),             // 403 策略禁止的操作
+    NotFound(ErrorInfo),              // 404 资源不存在
+    Conflict(ErrorInfo),              // 409 资源冲突
+    ConfirmationRequired(ErrorInfo),  // 428 需要用户确认后重新提交
+    DbError(ErrorInfo),               // 500 数据库或本地存储错误
+    AiError(ErrorInfo),               // 500 AI服务调用失败
+    Internal(ErrorInfo),              // 500 其他内部错误
+    NotImplemented(ErrorInfo),        // 501 当前数据库类型不支持
+    BadGateway(ErrorInfo),            // 502 外部服务（Webhook、向量服务）调用失败
+    ServiceUnavailable(ErrorInfo),    // 503 依赖的服务不可用（如AI未配置）
+    Timeout(ErrorInfo),               // 504 执行超时
+}
+
+fn info(code: &str, message: impl Into<String>) -> ErrorInfo {
+    ErrorInfo {
+        code: code.to_string(),
+        message: message.into(),
+        details: None,
+        hint: None,
+    }
+}
+
+// 常见错误码的默认处理建议
+fn default_hint(code: &str) -> Option<&'static str> {
+    match code {
+        "no_connection" | "no_active_connection" => Some("请先在连接管理中创建并启用数据库连接"),
+        "connection_not_found" => Some("连接可能已被删除，请刷新连接列表后重试"),
+        "connection_failed" => Some("请检查主机、端口、用户名、密码以及数据库服务是否可访问"),
+        "ai_service_unavailable" => Some("请在AI设置中配置服务商和API密钥"),
+        "unauthorized" => Some("请重新启动应用以获取新的访问令牌"),
+        "production_confirmation_required" => Some("确认操作无误后携带 confirmed=true 重新提交"),
+        "query_timeout" => Some("请缩小查询范围、添加过滤条件或调大超时时间"),
+        _ => None,
+    }
+}
+
+impl ApiError {
+    pub fn bad_request(code: &str, message: impl Into<String>) -> Self {
+        ApiError::BadRequest(info(code, message))
+    }
+
+    pub fn unauthorized(code: &str, message: impl Into<String>) -> Self {
+        ApiError::Unauthorized(info(code, message))
+    }
+
+    pub fn forbidden(code: &str, message: impl Into<String>) -> Self {
+        ApiError::Forbidden(info(code, message))
+    }
+
+    pub fn not_found(code: &str, message: impl Into<String>) -> Self {
+        ApiError::NotFound(info(code, message))
+    }
+
+    pub fn conflict(code: &str, message: impl Into<String>) -> Self {
+        ApiError::Conflict(info(code, message))
+    }
+
+    pub fn confirmation_required(code: &str, message: impl Into<String>) -> Self {
+        ApiError::ConfirmationRequired(info(code, message))
+    }
+
+    pub fn db(code: &str, message: impl Into<String>) -> Self {
+        ApiError::DbError(info(code, message))
+    }
+
+    pub fn ai(code: &str, message: impl Into<String>) -> Self {
+        ApiError::AiError(info(code, message))
+    }
+
+    pub fn internal(code: &str, message: impl Into<String>) -> Self {
+        ApiError::Internal(info(code, message))
+    }
+
+    pub fn not_implemented(code: &str, message: impl Into<String>) -> Self {
+        ApiError::NotImplemented(info(code, message))
+    }
+
+    pub fn bad_gateway(code: &str, message: impl Into<String>) -> Self {
+        ApiError::BadGateway(info(code, message))
+    }
+
+    pub fn service_unavailable(code: &str, message: impl Into<String>) -> Self {
+        ApiError::ServiceUnavailable(info(code, message))
+    }
+
+    pub fn timeout(code: &str, message: impl Into<String>) -> Self {
+        ApiError::Timeout(info(code, message))
+    }
+
+    // 按HTTP状态码选择变体（用于兼容仍返回 (StatusCode, Json<ErrorResponse>) 的模块）
+    pub fn from_status(status: StatusCode, info: ErrorInfo) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => ApiError::BadRequest(info),
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized(info),
+            StatusCode::FORBIDDEN => ApiError::Forbidden(info),
+            StatusCode::NOT_FOUND => ApiError::NotFound(info),
+            StatusCode::CONFLICT => ApiError::Conflict(info),
+            StatusCode::PRECONDITION_REQUIRED => ApiError::ConfirmationRequired(info),
+            StatusCode::NOT_IMPLEMENTED => ApiError::NotImplemented(info),
+            StatusCode::BAD_GATEWAY => ApiError::BadGateway(info),
+            StatusCode::SERVICE_UNAVAILABLE => ApiError::ServiceUnavailable(info),
+            StatusCode::GATEWAY_TIMEOUT | StatusCode::REQUEST_TIMEOUT => ApiError::Timeout(info),
+            _ if info.code == "database_error" => ApiError::DbError(info),
+            _ if info.code.starts_with("ai_") => ApiError::AiError(info),
+            _ => ApiError::Internal(info),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::ConfirmationRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+            ApiError::DbError(_) | ApiError::AiError(_) | ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            ApiError::BadGateway(_) => StatusCode::BAD_GATEWAY,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    pub fn info(&self) -> &ErrorInfo {
+        match self {
+            ApiError::BadRequest(info)
+            | ApiError::Unauthorized(info)
+            | ApiError::Forbidden(info)
+            | ApiError::NotFound(info)
+            | ApiError::Conflict(info)
+            | ApiError::ConfirmationRequired(info)
+            | ApiError::DbError(info)
+            | ApiError::AiError(info)
+            | ApiError::Internal(info)
+            | ApiError::NotImplemented(info)
+            | ApiError::BadGateway(info)
+            | ApiError::ServiceUnavailable(info)
+            | ApiError::Timeout(info) => info,
+        }
+    }
+
+    fn info_mut(&mut self) -> &mut ErrorInfo {
+        match self {
+            ApiError::BadRequest(info)
+            | ApiError::Unauthorized(info)
+            | ApiError::Forbidden(info)
+            | ApiError::NotFound(info)
+            | ApiError::Conflict(info)
+            | ApiError::ConfirmationRequired(info)
+            | ApiError::DbError(info)
+            | ApiError::AiError(info)
+            | ApiError::Internal(info)
+            | ApiError::NotImplemented(info)
+            | ApiError::BadGateway(info)
+            | ApiError::ServiceUnavailable(info)
+            | ApiError::Timeout(info) => info,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.info().code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.info().message
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.info_mut().details = Some(details.into());
+        self
+    }
+
+    pub fn with_optional_details(mut self, details: Option<String>) -> Self {
+        self.info_mut().details = details;
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.info_mut().hint = Some(hint.into());
+        self
+    }
+
+    fn into_info(self) -> ErrorInfo {
+        match self {
+            ApiError::BadRequest(info)
+            | ApiError::Unauthorized(info)
+            | ApiError::Forbidden(info)
+            | ApiError::NotFound(info)
+            | ApiError::Conflict(info)
+            | ApiError::ConfirmationRequired(info)
+            | ApiError::DbError(info)
+            | ApiError::AiError(info)
+            | ApiError::Internal(info)
+            | ApiError::NotImplemented(info)
+            | ApiError::BadGateway(info)
+            | ApiError::ServiceUnavailable(info)
+            | ApiError::Timeout(info) => info,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let mut info = self.into_info();
+        if info.hint.is_none() {
+            info.hint = default_hint(&info.code).map(str::to_string);
+        }
+        (status, Json(info)).into_response()
+    }
+}
+
+// 与仍使用 (StatusCode, Json<ErrorResponse>) 的模块互相转换，可直接使用 ? 传播
+impl From<(StatusCode, Json<ModelErrorResponse>)> for ApiError {
+    fn from((status, Json(body)): (StatusCode, Json<ModelErrorResponse>)) -> Self {
+        ApiError::from_status(status, ErrorInfo {
+            code: body.error,
+            message: body.message,
+            details: body.details,
+            hint: None,
+        })
+    }
+}
+
+impl From<ApiError> for (StatusCode, Json<ModelErrorResponse>) {
+    fn from(error: ApiError) -> Self {
+        let status = error.status();
+        let info = error.into_info();
+        (
+            status,
+            Json(ModelErrorResponse {
+                error: info.code,
+                message: info.message,
+                details: info.details,
+            })
+        )
+    }
+}
@@ -0,0 +1,177 @@
+use axum::{
+    body::Body,
+    http::header,
+    response::Response,
+    Extension, Json,
+};
+use futures_util::TryStreamExt;
+use serde::{Serialize, Deserialize};
+use serde_json::Value as JsonValue;
+use log::*;
+use std::io::Read;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::{DatabasePool, DatabaseType, LocalStorageManager, RowValues};
+use crate::utils::security::{classify_sql_as, StatementKind};
+use crate::utils::spool::{spool_dir, SpoolFile};
+
+// 缓冲写入通道容量（行）
+const SPOOL_CHANNEL_CAPACITY: usize = 1024;
+// 响应分块大小
+const RESPONSE_CHUNK_SIZE: usize = 64 * 1024;
+
+// 导出请求
+#[derive(Serialize, Deserialize)]
+pub struct ExportRequest {
+    pub sql: String,
+    pub connection_id: Option<i64>,
+    pub file_name: Option<String>,
+}
+
+// 将一行数据转换为CSV记录，与查询结果表格使用相同的类型转换（NULL输出为空字符串，JSON等结构化值输出为JSON文本）
+fn row_to_record<R: RowValues>(row: &R) -> Vec<String> {
+    row.json_values()
+        .into_iter()
+        .map(|value| match value {
+            JsonValue::Null => String::new(),
+            JsonValue::String(s) => s,
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+fn column_names<R: sqlx::Row>(row: &R) -> Vec<String> {
+    use sqlx::Column;
+    row.columns().iter().map(|c| c.name().to_string()).collect()
+}
+
+// 逐行读取查询结果并发送到缓冲写入任务
+macro_rules! stream_rows {
+    ($pool:expr, $sql:expr, $tx:expr) => {{
+        let mut rows = sqlx::query($sql).fetch($pool);
+        let mut header_sent = false;
+        let mut count: u64 = 0;
+        loop {
+            let row = match rows.try_next().await {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(e) => return Err(ApiError::bad_request("query_error", format!("查询执行失败: {}", e))),
+            };
+            if !header_sent {
+                if $tx.send(column_names(&row)).await.is_err() {
+                    break;
+                }
+                header_sent = true;
+            }
+            if $tx.send(row_to_record(&row)).await.is_err() {
+                break;
+            }
+            count += 1;
+        }
+        count
+    }};
+}
+
+/**
+ * 导出查询结果为CSV
+ * 结果先以gzip压缩写入磁盘缓冲文件，再以流的方式返回，避免大结果集占用内存
+ */
+pub async fn export_query_csv(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<ExportRequest>,
+) -> Result<Response, ApiError> {
+    info!("[API] POST /api/database/export - 请求: SQL长度={}", payload.sql.len());
+
+    let (connection, db_manager) = connect_database(&storage, payload.connection_id).await?;
+
+    // 导出不经过查询执行的生产环境确认、只读连接检查和审计，只允许单条只读查询
+    let statements = classify_sql_as(DatabaseType::from_name(&connection.db_type), &payload.sql)
+        .map_err(|e| ApiError::bad_request("invalid_sql", e))?;
+    if statements.len() != 1 || statements[0].kind != StatementKind::Query {
+        warn!("[API] POST /api/database/export - 拒绝非只读语句");
+        return Err(ApiError::forbidden("read_only", "导出只能执行单条只读查询"));
+    }
+
+    let spool = SpoolFile::new_in(&spool_dir())
+        .map_err(|e| ApiError::internal("spool_error", format!("创建缓冲文件失败: {}", e)))?;
+    let writer = spool.csv_writer()
+        .map_err(|e| ApiError::internal("spool_error", format!("创建缓冲文件失败: {}", e)))?;
+
+    // 在阻塞线程中写入缓冲文件
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<String>>(SPOOL_CHANNEL_CAPACITY);
+    let writer_task = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        let mut writer = writer;
+        while let Some(record) = rx.blocking_recv() {
+            writer.write_record(&record)?;
+        }
+        SpoolFile::finish_writer(writer)
+    });
+
+    let row_count = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => stream_rows!(pool, &payload.sql, tx),
+        DatabasePool::PostgreSQL(pool) => stream_rows!(pool, &payload.sql, tx),
+        DatabasePool::SQLite(pool) => stream_rows!(pool, &payload.sql, tx),
+        DatabasePool::MongoDB(_, _) => {
+            return Err(ApiError::bad_request("unsupported_database", "MongoDB 暂不支持CSV导出"));
+        }
+        DatabasePool::Redis(_) => {
+            return Err(ApiError::bad_request("unsupported_database", "Redis 暂不支持CSV导出"));
+        }
+        DatabasePool::DuckDB(_) => {
+            return Err(ApiError::bad_request("unsupported_database", "DuckDB 暂不支持CSV导出，请使用 COPY ... TO 语句"));
+        }
+    };
+    drop(tx);
+
+    writer_task.await
+        .map_err(|e| ApiError::internal("spool_error", format!("写入缓冲文件失败: {}", e)))?
+        .map_err(|e| ApiError::internal("spool_error", format!("写入缓冲文件失败: {}", e)))?;
+
+    info!("[API] POST /api/database/export - 缓冲完成: 行数={}, 文件={:?}", row_count, spool.path());
+
+    // 在阻塞线程中解压缓冲文件并分块发送，读取结束或客户端断开后缓冲文件随SpoolFile一起删除
+    let (chunk_tx, chunk_rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(8);
+    tokio::task::spawn_blocking(move || {
+        let mut reader = match spool.reader() {
+            Ok(reader) => reader,
+            Err(e) => {
+                let _ = chunk_tx.blocking_send(Err(e));
+                return;
+            }
+        };
+        loop {
+            let mut buf = vec![0u8; RESPONSE_CHUNK_SIZE];
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    buf.truncate(n);
+                    if chunk_tx.blocking_send(Ok(buf)).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    let _ = chunk_tx.blocking_send(Err(e));
+                    break;
+                }
+            }
+        }
+        drop(spool);
+    });
+
+    let stream = futures_util::stream::unfold(chunk_rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    let file_name = payload.file_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| "export.csv".to_string())
+        .replace(['"', '\r', '\n'], "");
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "text/csv; charset=utf-8")
+        .header(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name))
+        .header("X-Row-Count", row_count.to_string())
+        .body(Body::from_stream(stream))
+        .map_err(|e| ApiError::internal("export_error", format!("构建响应失败: {}", e)))
+}
@@ -0,0 +1,173 @@
+use std::time::Instant;
+
+use axum::{routing::post, Extension, Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Column, Executor, Row};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::execute_query;
+use crate::db::{bind_json_values, DatabaseType, LocalStorageManager, RowValues};
+use crate::models::SqlQueryRequest;
+use crate::services::federation::{
+    create_table_sql, insert_sql, table_columns, validate_alias, MAX_RESULT_ROWS, MAX_SOURCES, MAX_SOURCE_ROWS,
+};
+use crate::utils::security::{classify_sql_as, StatementKind};
+
+// 联邦查询数据源：在指定连接上执行只读查询，结果以 alias 为表名载入内存库
+#[derive(Debug, Deserialize)]
+pub struct FederationSource {
+    pub connection_id: i64,
+    pub sql: String,
+    pub alias: String,
+}
+
+// 联邦查询请求：query 为在内存SQLite库上执行的关联查询（使用SQLite语法）
+#[derive(Debug, Deserialize)]
+pub struct FederationRequest {
+    pub sources: Vec<FederationSource>,
+    pub query: String,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+// 数据源载入情况
+#[derive(Debug, Serialize)]
+pub struct FederationSourceSummary {
+    pub alias: String,
+    pub connection_id: i64,
+    pub row_count: usize,
+    pub columns: Vec<String>,  // 内存表中的列名（重名或非法字符时与原结果不同）
+}
+
+#[derive(Debug, Serialize)]
+pub struct FederationResponse {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+    pub row_count: usize,
+    pub truncated: bool,
+    pub sources: Vec<FederationSourceSummary>,
+    pub execution_time_ms: u128,
+}
+
+// 联邦查询路由（挂载在 /api/federation 下）
+pub fn federation_routes() -> Router {
+    Router::new()
+        .route("/execute", post(execute_federated_query))
+}
+
+// 只允许单条只读查询（同时排除 ATTACH 等访问本地文件的语句）
+fn ensure_query(db_type: Option<DatabaseType>, sql: &str, label: &str) -> Result<(), ApiError> {
+    let statements = classify_sql_as(db_type, sql)
+        .map_err(|e| ApiError::bad_request("invalid_sql", format!("{}: {}", label, e)))?;
+    if statements.len() != 1 || statements[0].kind != StatementKind::Query {
+        return Err(ApiError::forbidden("read_only", format!("{}只能是单条只读查询", label)));
+    }
+    Ok(())
+}
+
+/**
+ * 联邦查询处理函数
+ * 依次在各连接上执行数据源查询，载入临时内存SQLite库后执行关联查询
+ */
+pub async fn execute_federated_query(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<FederationRequest>,
+) -> Result<Json<FederationResponse>, ApiError> {
+    info!("[API] POST /api/federation/execute - 联邦查询请求: 数据源数={}", req.sources.len());
+    let start = Instant::now();
+
+    if req.sources.is_empty() || req.sources.len() > MAX_SOURCES {
+        return Err(ApiError::bad_request("invalid_sources", format!("数据源数量必须在 1 到 {} 之间", MAX_SOURCES)));
+    }
+    let mut aliases = std::collections::HashSet::new();
+    for source in &req.sources {
+        validate_alias(&source.alias).map_err(|e| ApiError::bad_request("invalid_alias", e))?;
+        if !aliases.insert(source.alias.to_lowercase()) {
+            return Err(ApiError::bad_request("duplicate_alias", format!("数据源别名重复: {}", source.alias)));
+        }
+    }
+    ensure_query(Some(DatabaseType::SQLite), &req.query, "关联查询")?;
+
+    // 每个请求使用独立的内存库，单连接且不回收，保证所有语句访问同一个库
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .map_err(|e| ApiError::internal("federation_engine_failed", format!("创建内存数据库失败: {}", e)))?;
+
+    let mut summaries = Vec::with_capacity(req.sources.len());
+    for source in &req.sources {
+        let connection = storage.get_connection(source.connection_id).await
+            .map_err(|e| ApiError::not_found("connection_not_found", format!("数据源 {} 的连接不存在: {}", source.alias, e)))?;
+        ensure_query(DatabaseType::from_name(&connection.db_type), &source.sql, &format!("数据源 {}", source.alias))?;
+
+        let query = SqlQueryRequest {
+            sql: source.sql.clone(),
+            connection_id: Some(source.connection_id),
+            timeout_secs: Some(req.timeout_secs),
+            ignore_limit: true,
+            ..Default::default()
+        };
+        let Json(result) = execute_query(Extension(storage.clone()), Json(query)).await
+            .map_err(|e| e.with_details(format!("数据源: {}", source.alias)))?;
+        if result.rows.len() > MAX_SOURCE_ROWS {
+            return Err(ApiError::bad_request("source_too_large", format!("数据源 {} 返回 {} 行，超过上限 {}", source.alias, result.rows.len(), MAX_SOURCE_ROWS)));
+        }
+
+        let columns = table_columns(&result.columns);
+        if columns.is_empty() {
+            return Err(ApiError::bad_request("empty_source", format!("数据源 {} 没有返回列", source.alias)));
+        }
+        let create_sql = create_table_sql(&source.alias, &columns, &result.rows)
+            .map_err(|e| ApiError::bad_request("invalid_source", e))?;
+        let insert = insert_sql(&source.alias, columns.len())
+            .map_err(|e| ApiError::bad_request("invalid_source", e))?;
+
+        let load_failed = |e: sqlx::Error| ApiError::internal("federation_load_failed", format!("载入数据源 {} 失败: {}", source.alias, e));
+        let mut tx = pool.begin().await.map_err(load_failed)?;
+        sqlx::query(&create_sql).execute(&mut *tx).await.map_err(load_failed)?;
+        for row in &result.rows {
+            bind_json_values!(sqlx::query(&insert), row).execute(&mut *tx).await.map_err(load_failed)?;
+        }
+        tx.commit().await.map_err(load_failed)?;
+        debug!("[API] 联邦查询数据源已载入: alias={}, rows={}", source.alias, result.rows.len());
+
+        summaries.push(FederationSourceSummary {
+            alias: source.alias.clone(),
+            connection_id: source.connection_id,
+            row_count: result.rows.len(),
+            columns,
+        });
+    }
+
+    let query_failed = |e: sqlx::Error| ApiError::bad_request("federation_query_failed", format!("关联查询执行失败: {}", e));
+    let rows = sqlx::query(&req.query).fetch_all(&pool).await.map_err(query_failed)?;
+    let columns: Vec<String> = match rows.first() {
+        Some(row) => row.columns().iter().map(|c| c.name().to_string()).collect(),
+        // 没有结果行时从语句描述中获取列名
+        None => (&pool).describe(&req.query).await.map_err(query_failed)?
+            .columns().iter().map(|c| c.name().to_string()).collect(),
+    };
+    let row_count = rows.len();
+    let values: Vec<Vec<JsonValue>> = rows.iter().take(MAX_RESULT_ROWS).map(|row| row.json_values()).collect();
+    pool.close().await;
+
+    info!("[API] POST /api/federation/execute - 响应成功: 行数={}, 耗时={}ms", row_count, start.elapsed().as_millis());
+    Ok(Json(FederationResponse {
+        columns,
+        rows: values,
+        row_count,
+        truncated: row_count > MAX_RESULT_ROWS,
+        sources: summaries,
+        execution_time_ms: start.elapsed().as_millis(),
+    }))
+}
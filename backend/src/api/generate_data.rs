@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database, get_table_structure_internal, production_guard};
+use crate::db::{DatabaseManager, DatabaseType, LocalStorageManager};
+use crate::services::ai::AiService;
+use crate::services::audit::{self, AuditRecord};
+use crate::services::data_generator::{
+    infer_pattern, is_integer_type, max_value_sql, parse_max_length, reference_values_sql, ColumnPlan, DataGenerator,
+    PatternSource, ValuePattern, DEFAULT_BATCH_SIZE, MAX_BATCH_SIZE, MAX_ROWS, PREVIEW_ROWS,
+};
+use crate::services::dml_generator::{generate_dml, DmlKind};
+
+// 测试数据生成请求
+#[derive(Debug, Deserialize)]
+pub struct GenerateDataRequest {
+    pub connection_id: Option<i64>,
+    pub table: String,
+    pub rows: usize,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    // 指定后生成结果可重复
+    #[serde(default)]
+    pub seed: Option<u64>,
+    // 按列名指定取值模式，优先于AI建议和规则推断
+    #[serde(default)]
+    pub patterns: HashMap<String, ValuePattern>,
+    // 不生成的列（使用数据库默认值）
+    #[serde(default)]
+    pub skip_columns: Vec<String>,
+    // 由AI根据列名和注释建议取值模式
+    #[serde(default)]
+    pub use_ai: bool,
+    // 只返回生成计划和预览行，不写入数据库
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+// 测试数据生成响应
+#[derive(Debug, Serialize)]
+pub struct GenerateDataResponse {
+    pub table: String,
+    pub columns: Vec<ColumnPlan>,
+    pub skipped_columns: Vec<String>,  // 未生成的列（自增主键、数据库默认序列、skip_columns）
+    pub preview: Vec<Vec<JsonValue>>,
+    pub inserted: usize,
+    pub batches: usize,
+    pub dry_run: bool,
+    pub execution_time_ms: u128,
+}
+
+// 读取外键引用表中已有的值
+async fn load_reference_values(db_manager: &DatabaseManager, table: &str, column: &str) -> Result<Vec<JsonValue>, ApiError> {
+    let sql = reference_values_sql(db_manager.db_type, table, column)
+        .map_err(|e| ApiError::bad_request("invalid_identifier", e))?;
+    let rows = db_manager.query_json_rows(&sql).await
+        .map_err(|e| ApiError::db("database_error", format!("读取引用表 {}.{} 的数据失败: {}", table, column, e)))?;
+    Ok(rows.into_iter().filter_map(|row| row.into_iter().next()).collect())
+}
+
+// 读取唯一整数列的当前最大值，生成的序列从其后开始
+async fn next_sequence_start(db_manager: &DatabaseManager, table: &str, column: &str) -> Result<i64, ApiError> {
+    let sql = max_value_sql(db_manager.db_type, table, column)
+        .map_err(|e| ApiError::bad_request("invalid_identifier", e))?;
+    let rows = db_manager.query_json_rows(&sql).await
+        .map_err(|e| ApiError::db("database_error", format!("读取列 {} 的最大值失败: {}", column, e)))?;
+    let max = rows.first().and_then(|row| row.first()).and_then(|value| match value {
+        JsonValue::Number(n) => n.as_i64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    });
+    Ok(max.map(|m| m.saturating_add(1)).unwrap_or(1))
+}
+
+/**
+ * 测试数据生成处理函数
+ * 按表结构为每列选择取值模式（外键从引用表取值，唯一列去重），分批在事务中插入生成的行
+ */
+pub async fn generate_table_data(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Json(req): Json<GenerateDataRequest>,
+) -> Result<Json<GenerateDataResponse>, ApiError> {
+    info!("[API] POST /api/tools/generate-data - 请求: connection_id={:?}, table={}, 行数={}, use_ai={}, dry_run={}",
+        req.connection_id, req.table, req.rows, req.use_ai, req.dry_run);
+    let start = Instant::now();
+
+    if req.rows == 0 || req.rows > MAX_ROWS {
+        return Err(ApiError::bad_request("invalid_rows", format!("生成行数必须在 1 到 {} 之间", MAX_ROWS)));
+    }
+    let batch_size = req.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    if batch_size == 0 || batch_size > MAX_BATCH_SIZE {
+        return Err(ApiError::bad_request("invalid_batch_size", format!("每批行数必须在 1 到 {} 之间", MAX_BATCH_SIZE)));
+    }
+    for (column, pattern) in &req.patterns {
+        pattern.validate().map_err(|e| ApiError::bad_request("invalid_pattern", format!("列 {} 的取值模式无效: {}", column, e)))?;
+    }
+
+    let (connection, db_manager) = connect_database(&storage, req.connection_id).await?;
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB) {
+        return Err(ApiError::not_implemented("unsupported_database", format!("{:?}不支持生成测试数据", db_manager.db_type)));
+    }
+    let structure = get_table_structure_internal(&db_manager, &req.table).await
+        .map_err(|e| ApiError::db("database_error", format!("获取表 {} 的结构失败: {}", req.table, e)))?;
+    if structure.columns.is_empty() {
+        return Err(ApiError::not_found("table_not_found", format!("表 {} 不存在或没有列", req.table)));
+    }
+
+    // 单列主键和单列唯一索引的列需要去重
+    let primary_keys: Vec<&str> = structure.columns.iter()
+        .filter(|c| c.is_primary_key == Some(true))
+        .map(|c| c.name.as_str())
+        .collect();
+    let mut unique_columns: HashSet<String> = structure.indexes.iter().flatten()
+        .filter(|index| index.unique == Some(true) && index.columns.len() == 1)
+        .map(|index| index.columns[0].clone())
+        .collect();
+    if let [key] = primary_keys[..] {
+        unique_columns.insert(key.to_string());
+    }
+    let foreign_keys: HashMap<&str, (&str, &str)> = structure.foreign_keys.iter().flatten()
+        .map(|fk| (fk.column_name.as_str(), (fk.referenced_table.as_str(), fk.referenced_column.as_str())))
+        .collect();
+
+    // 由数据库生成值的列：单列整数主键（自增/rowid）和默认值为序列的列
+    let skip: HashSet<&str> = req.skip_columns.iter().map(String::as_str).collect();
+    let mut skipped_columns = Vec::new();
+    let mut candidates = Vec::new();
+    for column in &structure.columns {
+        let data_type = column.data_type.as_deref().unwrap_or_default();
+        let default_value = column.default_value.as_deref().unwrap_or_default().to_lowercase();
+        let generated_by_database = (primary_keys.len() == 1 && column.is_primary_key == Some(true) && is_integer_type(data_type))
+            || default_value.contains("nextval(");
+        if skip.contains(column.name.as_str()) || (generated_by_database && !req.patterns.contains_key(&column.name)) {
+            skipped_columns.push(column.name.clone());
+        } else {
+            candidates.push(column);
+        }
+    }
+    if candidates.is_empty() {
+        return Err(ApiError::bad_request("no_columns", format!("表 {} 没有需要生成数据的列", req.table)));
+    }
+
+    // AI只为没有指定模式且不是外键的列提供建议，失败时使用规则推断
+    let mut ai_patterns = HashMap::new();
+    if req.use_ai {
+        let ai_service = ai_service.as_ref()
+            .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+        let descriptions: String = candidates.iter()
+            .filter(|c| !req.patterns.contains_key(&c.name) && !foreign_keys.contains_key(c.name.as_str()))
+            .map(|c| {
+                let comment = c.comment.as_deref().filter(|s| !s.is_empty()).map(|s| format!(" -- {}", s)).unwrap_or_default();
+                format!("- {} {}{}\n", c.name, c.data_type.as_deref().unwrap_or("unknown"), comment)
+            })
+            .collect();
+        if !descriptions.is_empty() {
+            match ai_service.suggest_value_patterns(&req.table, &descriptions).await {
+                Ok(patterns) => ai_patterns = patterns,
+                Err(e) => warn!("[API] AI测试数据模式建议失败，使用规则推断: {}", e),
+            }
+        }
+    }
+
+    let mut plans = Vec::with_capacity(candidates.len());
+    for column in candidates {
+        let data_type = column.data_type.as_deref().unwrap_or_default();
+        let (mut pattern, source) = if let Some(pattern) = req.patterns.get(&column.name) {
+            (pattern.clone(), PatternSource::Override)
+        } else if let Some((table, referenced)) = foreign_keys.get(column.name.as_str()) {
+            (ValuePattern::ForeignKey { table: table.to_string(), column: referenced.to_string(), values: Vec::new() }, PatternSource::ForeignKey)
+        } else if let Some(pattern) = ai_patterns.remove(&column.name) {
+            (pattern, PatternSource::Ai)
+        } else {
+            (infer_pattern(&column.name, data_type), PatternSource::Rule)
+        };
+        let unique = unique_columns.contains(&column.name);
+
+        if let ValuePattern::ForeignKey { table, column: referenced, values } = &mut pattern {
+            *values = load_reference_values(&db_manager, table, referenced).await?;
+            if values.is_empty() && column.is_nullable != Some(true) {
+                return Err(ApiError::bad_request("empty_reference", format!("列 {} 引用的表 {} 中没有数据，请先为其生成数据", column.name, table)));
+            }
+        }
+        // 唯一整数列使用从当前最大值开始的序列
+        if unique && source != PatternSource::Override && matches!(pattern, ValuePattern::Integer { .. }) {
+            pattern = ValuePattern::Sequence { start: next_sequence_start(&db_manager, &req.table, &column.name).await? };
+        }
+        plans.push(ColumnPlan {
+            name: column.name.clone(),
+            pattern,
+            unique,
+            max_length: parse_max_length(data_type),
+            source,
+        });
+    }
+
+    let column_names: Vec<String> = plans.iter().map(|p| p.name.clone()).collect();
+    let mut generator = DataGenerator::new(req.seed);
+
+    if req.dry_run {
+        let preview = generator.generate_rows(&plans, 0, req.rows.min(PREVIEW_ROWS));
+        info!("[API] POST /api/tools/generate-data - 预览完成: 列数={}", plans.len());
+        return Ok(Json(GenerateDataResponse {
+            table: req.table,
+            columns: plans,
+            skipped_columns,
+            preview,
+            inserted: 0,
+            batches: 0,
+            dry_run: true,
+            execution_time_ms: start.elapsed().as_millis(),
+        }));
+    }
+
+    let mut preview = Vec::new();
+    let mut inserted = 0;
+    let mut batches = 0;
+    while inserted < req.rows {
+        let count = batch_size.min(req.rows - inserted);
+        let rows = generator.generate_rows(&plans, inserted, count);
+        let statements = generate_dml(db_manager.db_type, &req.table, &column_names, &rows, DmlKind::Insert, &[])
+            .map_err(|e| ApiError::bad_request("generate_failed", e))?;
+        if batches == 0 {
+            production_guard(&connection, &statements[..1], req.confirm_production)?;
+        }
+        let result = db_manager.execute_in_transaction(&statements).await;
+        // 每批只记录一条审计日志（首条语句 + 批次行数），避免逐行写入
+        audit::record_in_background(&storage, AuditRecord {
+            connection: &connection,
+            source: audit::SOURCE_GENERATE_DATA,
+            ai_generated: false,
+            affected_rows: Some(count as i64),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }, &statements[..1]);
+        result
+            .map_err(|e| ApiError::db("insert_failed", format!("第 {} 批插入失败: {}", batches + 1, e))
+                .with_details(format!("已插入 {} 行", inserted)))?;
+        if preview.len() < PREVIEW_ROWS {
+            preview.extend(rows.into_iter().take(PREVIEW_ROWS - preview.len()));
+        }
+        inserted += count;
+        batches += 1;
+        debug!("[API] 测试数据批次插入完成: table={}, batch={}, 已插入={}", req.table, batches, inserted);
+    }
+
+    info!("[API] POST /api/tools/generate-data - 响应成功: table={}, 插入行数={}, 批次={}, 耗时={}ms",
+        req.table, inserted, batches, start.elapsed().as_millis());
+    Ok(Json(GenerateDataResponse {
+        table: req.table,
+        columns: plans,
+        skipped_columns,
+        preview,
+        inserted,
+        batches,
+        dry_run: false,
+        execution_time_ms: start.elapsed().as_millis(),
+    }))
+}
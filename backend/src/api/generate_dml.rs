@@ -0,0 +1,86 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database, get_table_structure_internal};
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::services::dml_generator::{generate_dml, DmlKind};
+
+// 单次最多生成语句的行数
+const MAX_ROWS: usize = 10_000;
+
+// 生成DML请求：选中的结果行 + 目标表，按目标连接的方言生成语句
+#[derive(Debug, Deserialize)]
+pub struct GenerateDmlRequest {
+    pub connection_id: Option<i64>,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+    #[serde(default)]
+    pub statement_type: DmlKind,
+    // UPDATE / DELETE 定位行的列，为空时使用表的主键
+    #[serde(default)]
+    pub key_columns: Option<Vec<String>>,
+}
+
+// 生成DML响应
+#[derive(Debug, Serialize)]
+pub struct GenerateDmlResponse {
+    pub statements: Vec<String>,
+    pub sql: String,  // 以分号和换行连接的完整文本，便于复制
+    pub count: usize,
+}
+
+/**
+ * 生成DML处理函数
+ * 将结果行转换为 INSERT（或按主键的 UPDATE / DELETE）语句文本，只生成不执行
+ */
+pub async fn generate_dml_statements(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<GenerateDmlRequest>,
+) -> Result<Json<GenerateDmlResponse>, ApiError> {
+    info!("[API] POST /api/tools/generate-dml - 请求: connection_id={:?}, table={}, type={:?}, 行数={}",
+        payload.connection_id, payload.table, payload.statement_type, payload.rows.len());
+
+    if payload.rows.is_empty() {
+        return Err(ApiError::bad_request("empty_rows", "请选择要生成语句的行"));
+    }
+    if payload.rows.len() > MAX_ROWS {
+        return Err(ApiError::bad_request("too_many_rows", format!("单次最多生成 {} 行", MAX_ROWS)));
+    }
+
+    let (_, db_manager) = connect_database(&storage, payload.connection_id).await?;
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis) {
+        return Err(ApiError::not_implemented("unsupported_database", format!("{:?}不支持生成SQL语句", db_manager.db_type)));
+    }
+
+    let key_columns = match payload.key_columns.filter(|k| !k.is_empty()) {
+        Some(keys) => keys,
+        None if payload.statement_type == DmlKind::Insert => Vec::new(),
+        None => {
+            let structure = get_table_structure_internal(&db_manager, &payload.table).await
+                .map_err(|e| ApiError::db("database_error", format!("获取表 {} 的结构失败: {}", payload.table, e)))?;
+            let keys: Vec<String> = structure.columns.into_iter()
+                .filter(|c| c.is_primary_key == Some(true))
+                .map(|c| c.name)
+                .collect();
+            if keys.is_empty() {
+                return Err(ApiError::bad_request("missing_key_columns", format!("表 {} 没有主键，请指定 key_columns", payload.table)));
+            }
+            keys
+        }
+    };
+
+    let statements = generate_dml(db_manager.db_type, &payload.table, &payload.columns, &payload.rows, payload.statement_type, &key_columns)
+        .map_err(|e| ApiError::bad_request("generate_failed", e))?;
+    let sql = statements.iter().map(|s| format!("{};", s)).collect::<Vec<_>>().join("\n");
+
+    info!("[API] POST /api/tools/generate-dml - 响应成功: 语句数量={}", statements.len());
+    Ok(Json(GenerateDmlResponse {
+        count: statements.len(),
+        statements,
+        sql,
+    }))
+}
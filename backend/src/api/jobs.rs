@@ -0,0 +1,206 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use log::*;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{execute_query, get_query_cancellers};
+use crate::api::script::{execute_script, ScriptRequest};
+use crate::db::LocalStorageManager;
+use crate::models::SqlQueryRequest;
+use crate::services::events::{self, ServerEvent};
+use crate::services::jobs::{self, registry, JobError, JobFilter, JobInfo, JobKind, JobStatus};
+
+// 后台任务路由（挂载在 /api/jobs 下）
+pub fn jobs_routes() -> Router {
+    Router::new()
+        // 任务列表（可按状态、类型、连接过滤）
+        .route("/", get(list_jobs))
+        // 提交后台查询 / 脚本任务
+        .route("/query", post(submit_query_job))
+        .route("/script", post(submit_script_job))
+        // 任务详情 / 删除已结束的任务
+        .route("/:id", get(get_job).delete(delete_job))
+        // 取消任务
+        .route("/:id/cancel", post(cancel_job))
+        // 任务结果
+        .route("/:id/result", get(get_job_result))
+}
+
+fn job_error(id: &str, e: JobError) -> ApiError {
+    match e {
+        JobError::NotFound => ApiError::not_found("job_not_found", format!("任务 {} 不存在或已被清理", id)),
+        JobError::InvalidState(JobStatus::Running) => ApiError::conflict("job_running", format!("任务 {} 正在运行，请先取消", id)),
+        JobError::InvalidState(_) => ApiError::conflict("job_finished", format!("任务 {} 已结束", id)),
+    }
+}
+
+// 通知正在执行的查询或脚本停止（与 /api/database/query/:id/cancel 相同的取消通道）
+fn cancel_running_query(id: &str) {
+    if let Some(sender) = get_query_cancellers().lock().unwrap().remove(id) {
+        let _ = sender.send(());
+    }
+}
+
+/**
+ * 提交后台查询任务处理函数
+ * 立即返回任务信息，查询ID与任务ID相同，结果通过 /api/jobs/:id/result 获取
+ */
+pub async fn submit_query_job(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(mut payload): Json<SqlQueryRequest>,
+) -> Result<Json<JobInfo>, ApiError> {
+    info!("[API] POST /api/jobs/query - 提交查询任务: connection_id={:?}, SQL长度={}", payload.connection_id, payload.sql.len());
+
+    let handle = registry().register(payload.query_id.take(), JobKind::Query, payload.connection_id, events::sql_preview(&payload.sql))
+        .map_err(|e| ApiError::conflict("job_id_conflict", e))?;
+    payload.query_id = Some(handle.id.clone());
+    let info = registry().get(&handle.id).ok_or_else(|| ApiError::internal("job_error", "任务登记失败"))?;
+
+    jobs::spawn(handle, move |mut handle| async move {
+        let run = execute_query(Extension(storage), Json(payload));
+        tokio::pin!(run);
+        let outcome = tokio::select! {
+            res = &mut run => res,
+            _ = handle.cancelled() => {
+                cancel_running_query(&handle.id);
+                (&mut run).await
+            }
+        };
+        outcome
+            .map(|Json(result)| serde_json::to_value(result).ok())
+            .map_err(|e| e.message().to_string())
+    });
+    Ok(Json(info))
+}
+
+/**
+ * 提交后台脚本任务处理函数
+ * 脚本ID与任务ID相同，按已执行的语句数更新任务进度
+ */
+pub async fn submit_script_job(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(mut payload): Json<ScriptRequest>,
+) -> Result<Json<JobInfo>, ApiError> {
+    info!("[API] POST /api/jobs/script - 提交脚本任务: connection_id={:?}, 脚本长度={}", payload.connection_id, payload.script.len());
+
+    let handle = registry().register(payload.script_id.take(), JobKind::Script, payload.connection_id, events::sql_preview(&payload.script))
+        .map_err(|e| ApiError::conflict("job_id_conflict", e))?;
+    payload.script_id = Some(handle.id.clone());
+    let info = registry().get(&handle.id).ok_or_else(|| ApiError::internal("job_error", "任务登记失败"))?;
+
+    // 订阅脚本进度事件（在脚本开始执行前订阅，避免漏掉事件）
+    let mut progress_events = events::subscribe();
+    let job_id = handle.id.clone();
+    let progress = tokio::spawn(async move {
+        loop {
+            match progress_events.recv().await {
+                Ok(ServerEvent::ScriptProgress { script_id, index, total, .. }) if script_id == job_id && total > 0 => {
+                    registry().set_progress(&job_id, (index + 1) as f64 * 100.0 / total as f64);
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    jobs::spawn(handle, move |mut handle| async move {
+        let run = execute_script(Extension(storage), Json(payload));
+        tokio::pin!(run);
+        let outcome = tokio::select! {
+            res = &mut run => res,
+            _ = handle.cancelled() => {
+                cancel_running_query(&handle.id);
+                (&mut run).await
+            }
+        };
+        progress.abort();
+        outcome
+            .map(|Json(result)| serde_json::to_value(result).ok())
+            .map_err(|e| e.message().to_string())
+    });
+    Ok(Json(info))
+}
+
+/**
+ * 获取任务列表处理函数
+ * 按创建时间倒序返回，已结束超过保留时间的任务会被自动清理
+ */
+pub async fn list_jobs(
+    Query(filter): Query<JobFilter>,
+) -> Json<serde_json::Value> {
+    debug!("[API] GET /api/jobs - 获取任务列表: {:?}", filter);
+
+    let jobs = registry().list(&filter);
+    Json(serde_json::json!({
+        "success": true,
+        "data": jobs,
+        "count": jobs.len(),
+    }))
+}
+
+/**
+ * 获取任务详情处理函数
+ */
+pub async fn get_job(
+    Path(id): Path<String>,
+) -> Result<Json<JobInfo>, ApiError> {
+    registry().get(&id)
+        .map(Json)
+        .ok_or_else(|| job_error(&id, JobError::NotFound))
+}
+
+/**
+ * 获取任务结果处理函数
+ * 任务未结束或没有结果时返回 409
+ */
+pub async fn get_job_result(
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/jobs/{}/result - 获取任务结果", id);
+
+    let (job, result) = registry().result(&id).ok_or_else(|| job_error(&id, JobError::NotFound))?;
+    match (job.status, result) {
+        (JobStatus::Running, _) => Err(ApiError::conflict("job_running", format!("任务 {} 尚未结束", id))),
+        (_, Some(result)) => Ok(Json(serde_json::json!({
+            "success": true,
+            "job": job,
+            "result": result,
+        }))),
+        (status, None) => Err(ApiError::conflict("job_no_result", format!("任务 {} 没有结果（状态: {:?}）", id, status))
+            .with_details(job.error.unwrap_or_default())),
+    }
+}
+
+/**
+ * 取消任务处理函数
+ * 查询和脚本任务会同时终止数据库中正在执行的语句
+ */
+pub async fn cancel_job(
+    Path(id): Path<String>,
+) -> Result<Json<JobInfo>, ApiError> {
+    info!("[API] POST /api/jobs/{}/cancel - 取消任务", id);
+
+    registry().cancel(&id)
+        .map(Json)
+        .map_err(|e| job_error(&id, e))
+}
+
+/**
+ * 删除任务处理函数
+ * 只能删除已结束的任务
+ */
+pub async fn delete_job(
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/jobs/{} - 删除任务", id);
+
+    registry().remove(&id).map_err(|e| job_error(&id, e))?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "任务已删除",
+    })))
+}
@@ -0,0 +1,72 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::services::sql_lint::{lint_sql, LintIssue, LintSeverity};
+
+// SQL检查请求（未指定 db_type 时使用连接的数据库类型）
+#[derive(Debug, Deserialize)]
+pub struct LintSqlRequest {
+    pub sql: String,
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub db_type: Option<String>,
+}
+
+// SQL检查响应
+#[derive(Debug, Serialize)]
+pub struct LintSqlResponse {
+    pub valid: bool,  // 没有语法错误
+    pub dialect: String,
+    pub issues: Vec<LintIssue>,
+}
+
+// 确定检查使用的方言：请求指定的类型 > 指定连接 > 第一个活动连接
+async fn resolve_db_type(storage: &LocalStorageManager, payload: &LintSqlRequest) -> Result<DatabaseType, ApiError> {
+    let name = match (&payload.db_type, payload.connection_id) {
+        (Some(db_type), _) => db_type.clone(),
+        (None, Some(id)) => storage.get_connection_by_id(id).await
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("connection_not_found", format!("连接ID {}不存在", id)))?
+            .db_type,
+        (None, None) => storage.get_active_connections().await
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ApiError::bad_request("no_connection", "请先激活一个数据库连接或指定 db_type"))?
+            .db_type,
+    };
+    DatabaseType::from_name(&name)
+        .ok_or_else(|| ApiError::bad_request("unsupported_database", format!("不支持的数据库类型: {}", name)))
+}
+
+/**
+ * SQL检查处理函数
+ * 本地按连接方言解析SQL，返回带位置的语法错误以及 SELECT *、UPDATE/DELETE 缺少 WHERE、隐式交叉连接等警告
+ */
+pub async fn lint_sql_handler(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<LintSqlRequest>,
+) -> Result<Json<LintSqlResponse>, ApiError> {
+    info!("[API] POST /api/tools/lint-sql - 请求: connection_id={:?}, db_type={:?}, SQL长度={}",
+        payload.connection_id, payload.db_type, payload.sql.len());
+
+    if payload.sql.trim().is_empty() {
+        return Err(ApiError::bad_request("empty_sql", "SQL不能为空"));
+    }
+    let db_type = resolve_db_type(&storage, &payload).await?;
+    if matches!(db_type, DatabaseType::MongoDB | DatabaseType::Redis) {
+        return Err(ApiError::not_implemented("unsupported_database", format!("{:?}不支持SQL检查", db_type)));
+    }
+
+    let issues = lint_sql(db_type, &payload.sql);
+    let valid = !issues.iter().any(|i| i.severity == LintSeverity::Error);
+    info!("[API] POST /api/tools/lint-sql - 响应成功: valid={}, 问题数量={}", valid, issues.len());
+    Ok(Json(LintSqlResponse {
+        valid,
+        dialect: format!("{:?}", db_type),
+        issues,
+    }))
+}
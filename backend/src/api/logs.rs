@@ -0,0 +1,40 @@
+use axum::{
+    extract::Query,
+    Json,
+};
+use serde::Deserialize;
+use log::*;
+
+use crate::utils::logging::{self, RecentLogFilter, RECENT_LOG_CAPACITY};
+
+// 最近日志查询参数
+#[derive(Debug, Deserialize)]
+pub struct RecentLogParams {
+    pub level: Option<String>,       // 最低级别：trace / debug / info / warn / error
+    pub request_id: Option<String>,  // 只返回指定请求的日志
+    pub keyword: Option<String>,     // 按日志内容过滤
+    pub limit: Option<usize>,        // 默认200条
+}
+
+/**
+ * 获取最近日志处理函数（诊断面板）
+ */
+pub async fn get_recent_logs(
+    Query(params): Query<RecentLogParams>,
+) -> Json<serde_json::Value> {
+    debug!("[API] GET /api/logs/recent - 获取最近日志请求: level={:?}, request_id={:?}", params.level, params.request_id);
+
+    let filter = RecentLogFilter {
+        min_level: params.level,
+        request_id: params.request_id,
+        keyword: params.keyword,
+        limit: params.limit.unwrap_or(200).clamp(1, RECENT_LOG_CAPACITY),
+    };
+    let entries = logging::recent(&filter);
+
+    Json(serde_json::json!({
+        "success": true,
+        "data": entries,
+        "count": entries.len(),
+    }))
+}
@@ -0,0 +1,128 @@
+use axum::Json;
+use serde::Serialize;
+use log::*;
+
+// 列类型到JSON值类型的映射
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeMapping {
+    pub engine: &'static str,
+    pub source_types: Vec<&'static str>,   // 数据库列类型（大写）
+    pub json_kind: &'static str,           // integer / number / string / boolean / object / null
+    pub encoding: Option<&'static str>,    // 特殊编码方式，如 extended_json
+    pub lossy: bool,                       // 转换是否可能丢失精度或信息
+    pub notes: &'static str,
+}
+
+// 类型映射表响应
+#[derive(Debug, Serialize)]
+pub struct TypeMappingsResponse {
+    pub mappings: Vec<TypeMapping>,
+    pub null_handling: &'static str,
+}
+
+fn mapping(
+    engine: &'static str,
+    source_types: &[&'static str],
+    json_kind: &'static str,
+    encoding: Option<&'static str>,
+    lossy: bool,
+    notes: &'static str,
+) -> TypeMapping {
+    TypeMapping {
+        engine,
+        source_types: source_types.to_vec(),
+        json_kind,
+        encoding,
+        lossy,
+        notes,
+    }
+}
+
+// 后端在 execute_query 中将各数据库列类型转换为JSON时使用的映射表
+// 修改行数据转换逻辑时需要同步更新此表
+pub fn type_mappings() -> Vec<TypeMapping> {
+    vec![
+        // MySQL：按列类型名解码（db::row_values）
+        mapping("mysql", &["CHAR", "VARCHAR", "TEXT", "TINYTEXT", "MEDIUMTEXT", "LONGTEXT", "ENUM", "SET"],
+            "string", None, false, "按字符串解码"),
+        mapping("mysql", &["BOOLEAN"], "boolean", None, false, "TINYINT(1) 按布尔值解码"),
+        mapping("mysql", &["TINYINT", "SMALLINT", "MEDIUMINT", "INT", "BIGINT"],
+            "integer", None, true, "按i64解码，超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("mysql", &["* UNSIGNED"],
+            "integer", None, true, "按u64解码，超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("mysql", &["FLOAT", "DOUBLE"],
+            "number", None, false, "按f32/f64解码"),
+        mapping("mysql", &["DECIMAL"], "string", Some("decimal_string"), false, "以字符串返回，保留精度"),
+        mapping("mysql", &["DATE", "TIME"], "string", Some("iso8601"), false, "YYYY-MM-DD / HH:MM:SS[.ffffff]"),
+        mapping("mysql", &["DATETIME"], "string", Some("iso8601"), false, "YYYY-MM-DDTHH:MM:SS[.ffffff]，不带时区"),
+        mapping("mysql", &["TIMESTAMP"], "string", Some("iso8601"), false, "按UTC格式化，以Z结尾"),
+        mapping("mysql", &["JSON"], "object", None, false, "解析为JSON值返回"),
+        mapping("mysql", &["BLOB", "BINARY", "VARBINARY", "BIT"],
+            "string", Some("base64"), true, "base64编码；超过64KB时返回 <binary N bytes>"),
+        mapping("mysql", &["*"],
+            "string", None, true, "其他类型依次尝试按字符串、二进制（base64）解码，失败时返回null"),
+        // PostgreSQL：按列类型名解码（db::row_values）
+        mapping("postgresql", &["BOOL"], "boolean", None, false, "按布尔值解码"),
+        mapping("postgresql", &["INT2", "INT4", "INT8", "OID"],
+            "integer", None, true, "INT8超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("postgresql", &["FLOAT4", "FLOAT8"],
+            "number", None, false, "按f32/f64解码"),
+        mapping("postgresql", &["NUMERIC"], "string", Some("decimal_string"), false, "以字符串返回，保留精度"),
+        mapping("postgresql", &["DATE", "TIME", "TIMESTAMP"], "string", Some("iso8601"), false, "TIMESTAMP不带时区"),
+        mapping("postgresql", &["TIMESTAMPTZ"], "string", Some("iso8601"), false, "按UTC格式化，以Z结尾"),
+        mapping("postgresql", &["UUID"], "string", None, false, "标准连字符格式"),
+        mapping("postgresql", &["JSON", "JSONB"], "object", None, false, "解析为JSON值返回"),
+        mapping("postgresql", &["BYTEA"], "string", Some("base64"), true, "base64编码；超过64KB时返回 <binary N bytes>"),
+        mapping("postgresql", &["INT2[]", "INT4[]", "INT8[]", "TEXT[]", "VARCHAR[]"], "object", None, false, "以JSON数组返回"),
+        mapping("postgresql", &["VARCHAR", "TEXT", "CHAR", "BPCHAR", "NAME"],
+            "string", None, false, "按字符串解码"),
+        mapping("postgresql", &["*"],
+            "string", None, true, "其他类型依次尝试按字符串、二进制（base64）解码，失败时返回null"),
+        // SQLite：按值的实际存储类型解码
+        mapping("sqlite", &["BOOLEAN"], "boolean", None, false, "声明为BOOLEAN的列按布尔值解码"),
+        mapping("sqlite", &["INTEGER"],
+            "integer", None, true, "按i64解码，超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("sqlite", &["REAL"],
+            "number", None, false, "按f64解码"),
+        mapping("sqlite", &["TEXT"],
+            "string", None, false, "按字符串解码"),
+        mapping("sqlite", &["BLOB"],
+            "string", Some("base64"), true, "base64编码；超过64KB时返回 <binary N bytes>"),
+        // MongoDB：BSON值按其serde序列化格式转换（特殊类型为 {"$xxx": ...} 形式的对象）
+        mapping("mongodb", &["String"], "string", None, false, "原样返回"),
+        mapping("mongodb", &["Int32", "Int64"], "integer", None, true, "Int64超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("mongodb", &["Double"], "number", None, false, "原样返回"),
+        mapping("mongodb", &["Boolean"], "boolean", None, false, "原样返回"),
+        mapping("mongodb", &["Decimal128"], "object", Some("extended_json"), false, "{\"$numberDecimalBytes\": [...]}，高精度小数以原始字节编码"),
+        mapping("mongodb", &["ObjectId"], "object", Some("extended_json"), false, "{\"$oid\": \"...\"}"),
+        mapping("mongodb", &["DateTime"], "object", Some("extended_json"), false, "{\"$date\": {\"$numberLong\": \"...\"}}，毫秒时间戳"),
+        mapping("mongodb", &["Binary"], "object", Some("extended_json"), false, "{\"$binary\": {...}}"),
+        mapping("mongodb", &["Document", "Array"], "object", None, false, "嵌套文档/数组递归转换"),
+        // Redis：命令回复按类型转换为表格
+        mapping("redis", &["BulkString", "SimpleString"], "string", None, true, "按UTF-8解码，非法字节被替换"),
+        mapping("redis", &["Integer"], "integer", None, false, "原样返回"),
+        mapping("redis", &["Nil"], "null", None, false, "键不存在时返回null"),
+        mapping("redis", &["Array"], "object", None, false, "展开为 index/value 行，HGETALL 为 field/value 行，SCAN 为 key/next_cursor 行"),
+        // DuckDB：按值类型转换
+        mapping("duckdb", &["TINYINT", "SMALLINT", "INTEGER", "BIGINT", "UTINYINT", "USMALLINT", "UINTEGER", "UBIGINT"],
+            "integer", None, true, "BIGINT超过 2^53 的值在JavaScript中会丢失精度"),
+        mapping("duckdb", &["HUGEINT", "DECIMAL"], "string", None, false, "以字符串返回，避免丢失精度"),
+        mapping("duckdb", &["FLOAT", "DOUBLE"], "number", None, false, "NaN/Infinity返回null"),
+        mapping("duckdb", &["DATE", "TIMESTAMP", "TIME"], "string", None, false, "格式化为文本，TIMESTAMP按UTC"),
+        mapping("duckdb", &["BLOB"], "string", None, false, "0x开头的十六进制文本"),
+        mapping("duckdb", &["LIST"], "object", None, false, "元素递归转换"),
+        mapping("duckdb", &["*"], "string", None, true, "其他类型（STRUCT、MAP等）以调试文本返回"),
+    ]
+}
+
+/**
+ * 获取列类型映射表处理函数
+ */
+pub async fn get_type_mappings() -> Json<TypeMappingsResponse> {
+    info!("[API] GET /api/meta/type-mappings - 获取类型映射表请求");
+
+    Json(TypeMappingsResponse {
+        mappings: type_mappings(),
+        null_handling: "数据库NULL值始终返回JSON null",
+    })
+}
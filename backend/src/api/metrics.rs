@@ -0,0 +1,36 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::services::metrics;
+
+// Prometheus 文本格式的内容类型
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/**
+ * 指标处理函数（挂载在 /metrics）
+ * 以 Prometheus 文本格式返回请求数、查询耗时、AI调用耗时和Token用量、连接池使用情况
+ */
+pub async fn metrics_handler() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], metrics::render())
+}
+
+/**
+ * 请求指标中间件
+ * 按路由模板（如 /api/connections/:id）统计请求数和耗时，未匹配路由的请求归为 unmatched，避免标签数量无限增长
+ */
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let route = request.extensions().get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = request.method().to_string();
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics::record_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
@@ -1,2 +1,49 @@
 pub mod routes;
-pub mod bulk_operations;
\ No newline at end of file
+pub mod bulk_operations;
+pub mod temporal;
+pub mod export;
+pub mod meta;
+pub mod ddl;
+pub mod table_data;
+pub mod schema_graph;
+pub mod notifications;
+pub mod performance;
+pub mod advisor;
+pub mod overview;
+pub mod schema_search;
+pub mod ai_stream;
+pub mod ai_usage;
+pub mod logs;
+pub mod error;
+pub mod openapi;
+pub mod ws;
+pub mod script;
+pub mod backup;
+pub mod data_diff;
+pub mod connection_bundle;
+pub mod connection_health;
+pub mod schemas;
+pub mod multi_result;
+pub mod generate_dml;
+pub mod lint;
+pub mod settings;
+pub mod editor_sessions;
+pub mod ai_analyze;
+pub mod ai_diagnose;
+pub mod ai_schema;
+pub mod chart_suggest;
+pub mod result_aggregate;
+pub mod federation;
+pub mod generate_data;
+pub mod dictionary;
+pub mod template_bindings;
+pub mod auth;
+pub mod audit;
+pub mod dml_preview;
+pub mod jobs;
+pub mod metrics;
+pub mod query_sessions;
+pub mod objects;
+pub mod privileges;
+pub mod processes;
+pub mod monitor;
@@ -0,0 +1,71 @@
+use axum::{extract::Query, Extension, Json};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::monitor::MonitorSnapshot;
+use crate::db::{DatabaseError, LocalStorageManager};
+use crate::services::monitor::{self, DEFAULT_MONITOR_HISTORY, MAX_MONITOR_HISTORY};
+
+// 监控指标参数
+#[derive(Debug, Deserialize)]
+pub struct MonitorParams {
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub history: bool,              // 是否同时返回近期采样（用于绘制曲线）
+    pub limit: Option<usize>,       // 返回的采样数，默认 60
+}
+
+// 监控指标响应
+#[derive(Debug, Serialize)]
+pub struct MonitorResponse {
+    pub connection_id: Option<i64>,
+    pub database_type: String,
+    pub snapshot: MonitorSnapshot,
+    pub history: Vec<MonitorSnapshot>,   // 按时间正序，包含本次采样
+}
+
+/**
+ * 获取数据库监控指标处理函数
+ * 每次请求采集一次快照并计入连接的监控历史（保存在内存中），速率按与上一次采样的差值计算
+ */
+pub async fn get_database_monitor(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<MonitorParams>,
+) -> Result<Json<MonitorResponse>, ApiError> {
+    info!("[API] GET /api/database/monitor - 请求: connection_id={:?}, history={}", params.connection_id, params.history);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    let snapshot = db_manager.collect_metrics().await
+        .map_err(|e| match e {
+            DatabaseError::UnsupportedDatabaseType(message) => ApiError::bad_request("unsupported_database", message),
+            e => ApiError::internal("monitor_error", format!("采集监控指标失败: {}", e)),
+        })?;
+
+    // 未保存的连接没有ID，只计算启动以来的平均速率，不保留历史
+    let (snapshot, history) = match connection.id {
+        Some(id) => {
+            let snapshot = monitor::record(id, snapshot);
+            let history = if params.history {
+                let limit = params.limit.unwrap_or(DEFAULT_MONITOR_HISTORY).clamp(1, MAX_MONITOR_HISTORY);
+                monitor::history(id, limit)
+            } else {
+                Vec::new()
+            };
+            (snapshot, history)
+        }
+        None => {
+            let mut snapshot = snapshot;
+            monitor::compute_rates(&mut snapshot, None);
+            (snapshot, Vec::new())
+        }
+    };
+
+    Ok(Json(MonitorResponse {
+        connection_id: connection.id,
+        database_type: connection.db_type,
+        snapshot,
+        history,
+    }))
+}
@@ -0,0 +1,134 @@
+use std::time::{Duration, Instant};
+use axum::{Extension, Json};
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use sqlx::{Column, Row};
+use uuid::Uuid;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database_to, production_guard, register_canceller};
+use crate::db::{DatabasePool, LocalStorageManager, RowValues};
+use crate::models::SqlMultiResult;
+use crate::services::audit::{self, AuditRecord};
+use crate::services::hooks::{self, QueryContext};
+use crate::services::result_sets::{ResultSetCollector, DEFAULT_MAX_ROWS_PER_SET};
+
+// 单个结果集最多返回的行数上限
+const MAX_ROWS_PER_SET: usize = 10_000;
+
+// 多结果集查询请求
+#[derive(Debug, Deserialize)]
+pub struct MultiQueryRequest {
+    pub sql: String,
+    pub connection_id: Option<i64>,
+    // 在同一服务器的其他数据库上执行
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    // 单个结果集最多返回的行数（默认1000）
+    #[serde(default)]
+    pub max_rows_per_set: Option<usize>,
+    #[serde(default)]
+    pub query_id: Option<String>,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn column_names<R: Row>(row: &R) -> Vec<String> {
+    row.columns().iter().map(|c| c.name().to_string()).collect()
+}
+
+// 以文本协议执行SQL，逐个收集结果集（存储过程的每个SELECT、多条语句各自对应一个结果集）
+macro_rules! collect_result_sets {
+    ($pool:expr, $sql:expr, $collector:expr) => {{
+        let mut stream = sqlx::raw_sql($sql).fetch_many($pool);
+        while let Some(item) = stream.try_next().await
+            .map_err(|e| ApiError::bad_request("query_error", format!("查询执行失败: {}", e)))?
+        {
+            match item {
+                sqlx::Either::Left(done) => $collector.finish_set(done.rows_affected()),
+                sqlx::Either::Right(row) => $collector.push_row(|| column_names(&row), || row.json_values()),
+            }
+        }
+    }};
+}
+
+async fn run_multi_query(pool: &DatabasePool, sql: &str, max_rows: usize) -> Result<ResultSetCollector, ApiError> {
+    let mut collector = ResultSetCollector::new(max_rows);
+    match pool {
+        DatabasePool::MySQL(pool) => collect_result_sets!(pool, sql, collector),
+        DatabasePool::PostgreSQL(pool) => collect_result_sets!(pool, sql, collector),
+        DatabasePool::SQLite(pool) => collect_result_sets!(pool, sql, collector),
+        DatabasePool::MongoDB(..) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => {
+            return Err(ApiError::bad_request("unsupported_database", "多结果集查询仅支持 MySQL / PostgreSQL / SQLite"));
+        }
+    }
+    Ok(collector)
+}
+
+/**
+ * 多结果集查询处理函数
+ * 适用于 CALL 存储过程、SHOW ENGINE INNODB STATUS 等返回多个结果集的语句，按顺序返回所有结果集
+ */
+pub async fn execute_multi_query(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<MultiQueryRequest>,
+) -> Result<Json<SqlMultiResult>, ApiError> {
+    info!("[API] POST /api/database/query/multi - 请求: connection_id={:?}, database={:?}, SQL长度={}",
+        payload.connection_id, payload.database, payload.sql.len());
+
+    if payload.sql.trim().is_empty() {
+        return Err(ApiError::bad_request("empty_sql", "SQL不能为空"));
+    }
+    let (connection, db_manager) = connect_database_to(&storage, payload.connection_id, payload.database.as_deref()).await?;
+    production_guard(&connection, &crate::services::policy::split_statements(&payload.sql), payload.confirm_production)?;
+
+    let query_id = payload.query_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let hook_ctx = QueryContext {
+        query_id: &query_id,
+        sql: &payload.sql,
+        connection: &connection,
+    };
+    hooks::registry().run_before(&hook_ctx)
+        .map_err(|rejection| ApiError::forbidden("query_rejected", format!("查询被拒绝: {}", rejection)))?;
+
+    // 注册取消通道，可通过 /api/database/query/:query_id/cancel 取消
+    let Some((_canceller, cancel_rx)) = register_canceller(&query_id) else {
+        return Err(ApiError::conflict("query_id_conflict", format!("查询ID {} 正在执行中", query_id)));
+    };
+
+    let max_rows = payload.max_rows_per_set.unwrap_or(DEFAULT_MAX_ROWS_PER_SET).clamp(1, MAX_ROWS_PER_SET);
+    let start = Instant::now();
+    let outcome = tokio::select! {
+        result = tokio::time::timeout(Duration::from_secs(payload.timeout_secs), run_multi_query(&db_manager.pool, &payload.sql, max_rows)) => {
+            match result {
+                Ok(result) => result,
+                Err(_) => Err(ApiError::timeout("query_timeout", format!("查询超时（{}秒）", payload.timeout_secs))),
+            }
+        }
+        _ = cancel_rx => Err(ApiError::bad_request("query_cancelled", "查询已取消")),
+    };
+
+    audit::record_in_background(&storage, AuditRecord {
+        connection: &connection,
+        source: audit::SOURCE_MULTI_QUERY,
+        ai_generated: false,
+        affected_rows: None,
+        error: outcome.as_ref().err().map(|e| e.message().to_string()),
+    }, &crate::services::policy::split_statements(&payload.sql));
+
+    let result_sets = outcome?.finish();
+    let execution_time_ms = start.elapsed().as_millis();
+    info!("[API] POST /api/database/query/multi - 响应成功: 结果集数量={}, 耗时={}ms", result_sets.len(), execution_time_ms);
+    Ok(Json(SqlMultiResult {
+        result_sets,
+        execution_time_ms,
+        query_id: Some(query_id),
+    }))
+}
@@ -0,0 +1,107 @@
+use axum::{
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Serialize, Deserialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::services::notifications::{self, Notification, WebhookConfig, EVENT_TEST, WEBHOOK_SETTING_KEY};
+
+// 测试通知请求（未指定配置时使用已保存的全局配置）
+#[derive(Serialize, Deserialize)]
+pub struct TestWebhookRequest {
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>,
+}
+
+// 通知配置路由（挂载在 /api/notifications 下）
+pub fn notification_routes() -> Router {
+    Router::new()
+        // 获取 / 保存Webhook配置
+        .route("/webhook", get(get_webhook_config).put(save_webhook_config).delete(delete_webhook_config))
+        // 发送测试通知
+        .route("/webhook/test", post(test_webhook))
+}
+
+/**
+ * 获取Webhook配置处理函数
+ */
+pub async fn get_webhook_config(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/notifications/webhook - 获取Webhook配置请求");
+
+    let config = notifications::load_config(&storage).await;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": config,
+    })))
+}
+
+/**
+ * 保存Webhook配置处理函数
+ */
+pub async fn save_webhook_config(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<WebhookConfig>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] PUT /api/notifications/webhook - 保存Webhook配置请求: format={:?}, events={:?}", payload.format, payload.events);
+
+    payload.validate().map_err(|e| ApiError::bad_request("invalid_webhook", e))?;
+
+    let value = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::internal("serialize_error", e.to_string()))?;
+    storage.set_app_setting(WEBHOOK_SETTING_KEY, &value).await
+        .map_err(|e| ApiError::db("database_error", format!("保存Webhook配置失败: {}", e)))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": payload,
+        "message": "Webhook配置已保存",
+    })))
+}
+
+/**
+ * 删除Webhook配置处理函数
+ */
+pub async fn delete_webhook_config(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/notifications/webhook - 删除Webhook配置请求");
+
+    storage.delete_app_setting(WEBHOOK_SETTING_KEY).await
+        .map_err(|e| ApiError::db("database_error", format!("删除Webhook配置失败: {}", e)))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Webhook配置已删除",
+    })))
+}
+
+/**
+ * 发送测试通知处理函数
+ */
+pub async fn test_webhook(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<TestWebhookRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] POST /api/notifications/webhook/test - 测试Webhook请求");
+
+    let config = match payload.webhook {
+        Some(config) => config,
+        None => notifications::load_config(&storage).await
+            .ok_or_else(|| ApiError::bad_request("webhook_not_configured", "尚未配置Webhook"))?,
+    };
+    config.validate().map_err(|e| ApiError::bad_request("invalid_webhook", e))?;
+
+    let notification = Notification::new(EVENT_TEST, "智能SQLer测试通知", "Webhook配置成功，可以正常接收通知");
+    notifications::send(&config, &notification).await
+        .map_err(|e| ApiError::bad_gateway("webhook_failed", e))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "测试通知已发送",
+    })))
+}
@@ -0,0 +1,51 @@
+use axum::{extract::Query, Extension, Json};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database_to;
+use crate::db::objects::{DatabaseObject, ObjectKind};
+use crate::db::LocalStorageManager;
+
+// 数据库对象列表参数
+#[derive(Debug, Deserialize)]
+pub struct ObjectListParams {
+    pub connection_id: Option<i64>,
+    pub database: Option<String>,   // 切换到同一服务器上的其他数据库
+    pub schema: Option<String>,     // 为空时使用当前数据库 / 搜索路径中的schema
+    pub kind: Option<ObjectKind>,   // procedure / function / trigger / view，为空时返回全部
+}
+
+// 数据库对象列表响应
+#[derive(Debug, Serialize)]
+pub struct ObjectListResponse {
+    pub connection_id: Option<i64>,
+    pub objects: Vec<DatabaseObject>,
+    pub count: usize,
+}
+
+/**
+ * 获取存储过程、函数、触发器和视图列表处理函数
+ * 包含参数签名、返回类型和源码；SQLite 只有视图和触发器
+ */
+pub async fn list_database_objects(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ObjectListParams>,
+) -> Result<Json<ObjectListResponse>, ApiError> {
+    info!("[API] GET /api/database/objects - 请求: connection_id={:?}, schema={:?}, kind={:?}", params.connection_id, params.schema, params.kind);
+
+    let (connection, db_manager) = connect_database_to(&storage, params.connection_id, params.database.as_deref()).await?;
+    let schema = params.schema.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let objects = db_manager.list_objects(params.kind, schema).await
+        .map_err(|e| match e {
+            crate::db::DatabaseError::UnsupportedDatabaseType(message) => ApiError::bad_request("unsupported_database", message),
+            e => ApiError::internal("query_failed", format!("获取数据库对象失败: {}", e)),
+        })?;
+
+    info!("[API] GET /api/database/objects - 响应成功: 对象数量={}", objects.len());
+    Ok(Json(ObjectListResponse {
+        connection_id: connection.id,
+        count: objects.len(),
+        objects,
+    }))
+}
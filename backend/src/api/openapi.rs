@@ -0,0 +1,79 @@
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::error::ErrorInfo;
+use crate::api::routes::{self, AiConfigRequest, ApiTableSchema, DatabaseInfoResponse, DetailedHealthResponse, HealthResponse, LocalStorageStatus, TableRequest};
+use crate::models::{
+    BatchSqlRequest, BatchSqlResult, ChatAnalysisRequest, ChatAnalysisResponse, ChatMessage,
+    CompletionSuggestion, ConnectionOptions, ConnectionPoolOptions, ConnectionRequest, ConnectionTestRequest, ConnectionTestResponse,
+    DatabaseConnection, DatabaseInfo, ErrorResponse, ExecutionPlanNode, ExecutionPlanRequest,
+    ExecutionPlanResponse, ForeignKeyInfo, PlanNodeActual, QueryHistory, QueryPerformance,
+    SqlCompleteRequest, SqlCompleteResponse, SqlCompletionRequest, SqlCompletionResponse,
+    SqlExplainRequest, SqlExplainResponse, SqlGenerateRequest, SqlGenerateResponse,
+    SqlMultiResult, SqlOptimizeRequest, SqlOptimizeResponse, SqlQueryRequest, SqlQueryResult, SqlResultSet,
+    SqlToNaturalLanguageRequest, SqlToNaturalLanguageResponse, StatementResult, TableColumn,
+    TableIndex,
+};
+use crate::services::llm::ProviderKind;
+use crate::services::sql_validation::{SqlValidation, ValidationIssue, ValidationIssueKind};
+
+// OpenAPI文档路径和Swagger UI路径
+pub const OPENAPI_JSON_PATH: &str = "/api/openapi.json";
+pub const SWAGGER_UI_PATH: &str = "/api/docs";
+
+// REST API 的 OpenAPI 文档（由处理函数上的 #[utoipa::path] 和模型的 ToSchema 生成）
+// 新增接口时在 paths 中登记，请求/响应模型在 components 中登记
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "智能SQLer API", description = "智能SQLer后端REST接口。错误响应统一为 ErrorInfo（error 字段为错误码）"),
+    paths(
+        routes::health_check,
+        routes::detailed_health_check,
+        routes::get_database_info,
+        routes::get_table_structure,
+        routes::execute_query,
+        routes::get_execution_plan,
+        routes::cancel_query,
+        routes::generate_sql,
+        routes::optimize_sql,
+        routes::explain_sql,
+        routes::get_ai_config,
+        routes::save_ai_config,
+        routes::test_ai_config,
+        routes::list_connections,
+        routes::create_connection,
+        routes::get_connection,
+        routes::test_connection,
+    ),
+    components(schemas(
+        ErrorInfo, ErrorResponse,
+        HealthResponse, DetailedHealthResponse, LocalStorageStatus, DatabaseInfoResponse, DatabaseInfo,
+        TableRequest, ApiTableSchema, TableColumn, TableIndex, ForeignKeyInfo,
+        SqlQueryRequest, SqlQueryResult, QueryPerformance,
+        SqlMultiResult, SqlResultSet,
+        BatchSqlRequest, BatchSqlResult, StatementResult,
+        ExecutionPlanRequest, ExecutionPlanResponse, ExecutionPlanNode, PlanNodeActual,
+        SqlGenerateRequest, SqlGenerateResponse, SqlValidation, ValidationIssue, ValidationIssueKind,
+        SqlOptimizeRequest, SqlOptimizeResponse,
+        SqlExplainRequest, SqlExplainResponse,
+        SqlToNaturalLanguageRequest, SqlToNaturalLanguageResponse,
+        SqlCompletionRequest, SqlCompletionResponse,
+        SqlCompleteRequest, SqlCompleteResponse, CompletionSuggestion,
+        ChatAnalysisRequest, ChatAnalysisResponse, ChatMessage,
+        AiConfigRequest, ProviderKind,
+        DatabaseConnection, ConnectionRequest, ConnectionPoolOptions, ConnectionOptions, ConnectionTestRequest, ConnectionTestResponse,
+        QueryHistory,
+    )),
+    tags(
+        (name = "system", description = "服务状态"),
+        (name = "database", description = "数据库元数据与查询执行"),
+        (name = "ai", description = "AI辅助SQL与AI配置"),
+        (name = "connections", description = "连接管理"),
+    )
+)]
+pub struct ApiDoc;
+
+// Swagger UI 路由（同时提供 /api/openapi.json），在顶层路由上合并
+pub fn swagger_routes() -> SwaggerUi {
+    SwaggerUi::new(SWAGGER_UI_PATH).url(OPENAPI_JSON_PATH, ApiDoc::openapi())
+}
@@ -0,0 +1,53 @@
+use axum::{extract::Query, http::StatusCode, Extension, Json};
+use serde::Serialize;
+use log::*;
+
+use crate::api::ddl::ConnectionParams;
+use crate::api::routes::connect_database;
+use crate::db::{DatabaseOverview, LocalStorageManager};
+use crate::models::ErrorResponse as ModelErrorResponse;
+
+// 数据库概览响应
+#[derive(Debug, Serialize)]
+pub struct DatabaseOverviewResponse {
+    pub connection_id: Option<i64>,
+    pub connection_name: String,
+    pub database_type: String,
+    #[serde(flatten)]
+    pub overview: DatabaseOverview,
+}
+
+/**
+ * 获取数据库概览处理函数（版本、大小、字符集、连接数、运行时长）
+ */
+pub async fn get_database_overview(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+    info!("[API] GET /api/database/overview - 获取数据库概览请求: connection_id={:?}", params.connection_id);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    let overview = db_manager.get_overview().await.map_err(|e| {
+        error!("获取数据库概览失败: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ModelErrorResponse {
+                error: "overview_error".to_string(),
+                message: format!("获取数据库概览失败: {}", e),
+                details: None,
+            })
+        )
+    })?;
+
+    info!("[API] GET /api/database/overview - 连接 {} 版本: {:?}", connection.name, overview.server_version);
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": DatabaseOverviewResponse {
+            connection_id: connection.id,
+            connection_name: connection.name,
+            database_type: connection.db_type,
+            overview,
+        },
+    })))
+}
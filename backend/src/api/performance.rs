@@ -0,0 +1,186 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Serialize, Deserialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::services::ai::AiService;
+use crate::services::slow_queries::{self, SlowQuerySettings, SLOW_QUERY_SETTING_KEY};
+
+// 慢查询排行参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowQueryRankParams {
+    pub connection_id: Option<i64>,
+    pub order_by: Option<String>,  // total_time（默认）/ count / avg_time / max_time
+    pub limit: Option<i64>,
+}
+
+// 慢查询明细参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowQueryListParams {
+    pub connection_id: Option<i64>,
+    pub fingerprint: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// 性能分析路由（挂载在 /api/performance 下）
+pub fn performance_routes() -> Router {
+    Router::new()
+        // 慢查询排行 / 清空
+        .route("/slow-queries", get(rank_slow_queries).delete(clear_slow_queries))
+        // 慢查询明细
+        .route("/slow-queries/records", get(list_slow_queries))
+        // 慢查询日志配置
+        .route("/slow-queries/settings", get(get_slow_query_settings).put(save_slow_query_settings))
+        // 请求AI优化慢查询
+        .route("/slow-queries/:id/optimize", post(optimize_slow_query))
+}
+
+fn database_error(message: &str, e: sqlx::Error) -> ApiError {
+    ApiError::db("database_error", format!("{}: {}", message, e))
+}
+
+/**
+ * 慢查询排行处理函数
+ */
+pub async fn rank_slow_queries(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<SlowQueryRankParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/performance/slow-queries - 慢查询排行请求: connection_id={:?}, order_by={:?}", params.connection_id, params.order_by);
+
+    let order_by = params.order_by.as_deref().unwrap_or("total_time");
+    let ranking = storage.rank_slow_queries(params.connection_id, order_by, params.limit.unwrap_or(50)).await
+        .map_err(|e| database_error("获取慢查询排行失败", e))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": ranking,
+        "count": ranking.len(),
+    })))
+}
+
+/**
+ * 慢查询明细处理函数
+ */
+pub async fn list_slow_queries(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<SlowQueryListParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/performance/slow-queries/records - 慢查询明细请求: connection_id={:?}", params.connection_id);
+
+    let records = storage.list_slow_queries(params.fingerprint.as_deref(), params.connection_id, params.limit.unwrap_or(100)).await
+        .map_err(|e| database_error("获取慢查询记录失败", e))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": records,
+        "count": records.len(),
+    })))
+}
+
+/**
+ * 清空慢查询日志处理函数
+ */
+pub async fn clear_slow_queries(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<SlowQueryListParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/performance/slow-queries - 清空慢查询请求: connection_id={:?}", params.connection_id);
+
+    let deleted = storage.clear_slow_queries(params.connection_id).await
+        .map_err(|e| database_error("清空慢查询日志失败", e))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": format!("已删除 {} 条慢查询记录", deleted),
+    })))
+}
+
+/**
+ * 获取慢查询日志配置处理函数
+ */
+pub async fn get_slow_query_settings(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/performance/slow-queries/settings - 获取慢查询配置请求");
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": slow_queries::load_settings(&storage).await,
+    })))
+}
+
+/**
+ * 保存慢查询日志配置处理函数
+ */
+pub async fn save_slow_query_settings(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<SlowQuerySettings>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] PUT /api/performance/slow-queries/settings - 保存慢查询配置请求: enabled={}, threshold_ms={}", payload.enabled, payload.threshold_ms);
+
+    payload.validate().map_err(|e| ApiError::bad_request("invalid_settings", e))?;
+
+    let value = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::internal("serialize_error", e.to_string()))?;
+    storage.set_app_setting(SLOW_QUERY_SETTING_KEY, &value).await
+        .map_err(|e| database_error("保存慢查询配置失败", e))?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": payload,
+        "message": "慢查询配置已保存",
+    })))
+}
+
+/**
+ * AI优化慢查询处理函数
+ */
+pub async fn optimize_slow_query(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] POST /api/performance/slow-queries/{}/optimize - AI优化慢查询请求", id);
+
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用"))?;
+
+    let record = storage.get_slow_query(id).await
+        .map_err(|e| database_error("获取慢查询记录失败", e))?
+        .ok_or_else(|| ApiError::not_found("not_found", format!("慢查询记录 {} 不存在", id)))?;
+
+    let database_type = match record.connection_id {
+        Some(connection_id) => storage.get_connection_by_id(connection_id).await
+            .map_err(|e| database_error("获取连接失败", e))?
+            .map(|c| c.db_type),
+        None => None,
+    };
+
+    // 附带执行计划和耗时，帮助AI判断瓶颈
+    let mut sql = record.sql_text.clone();
+    if let Some(plan) = record.explain_plan.as_deref().filter(|p| !p.is_empty()) {
+        let plan_comment = plan.lines().map(|l| format!("-- {}", l)).collect::<Vec<_>>().join("\n");
+        sql = format!("-- 执行耗时: {}ms\n-- 执行计划:\n{}\n{}", record.execution_time_ms, plan_comment, sql);
+    }
+
+    let (optimized_sql, tips) = ai_service.optimize_sql(&sql, database_type.as_deref()).await
+        .map_err(|e| {
+            error!("慢查询AI优化失败: {:?}", e);
+            ApiError::ai("ai_error", format!("SQL优化失败: {}", e))
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "slow_query": record,
+            "optimized_sql": optimized_sql,
+            "optimization_tips": tips,
+        },
+    })))
+}
@@ -0,0 +1,50 @@
+use axum::{extract::Query, Extension, Json};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database_to;
+use crate::db::privileges::{DatabaseUser, PrivilegeGrant};
+use crate::db::LocalStorageManager;
+
+// 用户权限查询参数
+#[derive(Debug, Deserialize)]
+pub struct PrivilegeParams {
+    pub connection_id: Option<i64>,
+    pub database: Option<String>,   // 切换到同一服务器上的其他数据库
+}
+
+// 用户权限响应
+#[derive(Debug, Serialize)]
+pub struct PrivilegeResponse {
+    pub connection_id: Option<i64>,
+    pub database: Option<String>,
+    pub users: Vec<DatabaseUser>,
+    pub grants: Vec<PrivilegeGrant>,
+}
+
+/**
+ * 获取数据库用户 / 角色及其权限处理函数（只读）
+ * MySQL 读取 mysql.user、SHOW GRANTS 和 information_schema 权限表；PostgreSQL 读取 pg_roles、库级 ACL 和 role_table_grants
+ */
+pub async fn list_privileges(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<PrivilegeParams>,
+) -> Result<Json<PrivilegeResponse>, ApiError> {
+    info!("[API] GET /api/database/privileges - 请求: connection_id={:?}, database={:?}", params.connection_id, params.database);
+
+    let (connection, db_manager) = connect_database_to(&storage, params.connection_id, params.database.as_deref()).await?;
+    let overview = db_manager.list_privileges().await
+        .map_err(|e| match e {
+            crate::db::DatabaseError::UnsupportedDatabaseType(message) => ApiError::bad_request("unsupported_database", message),
+            e => ApiError::internal("query_failed", format!("获取用户权限失败: {}", e)),
+        })?;
+
+    info!("[API] GET /api/database/privileges - 响应成功: 用户数量={}, 权限数量={}", overview.users.len(), overview.grants.len());
+    Ok(Json(PrivilegeResponse {
+        connection_id: connection.id,
+        database: overview.database,
+        users: overview.users,
+        grants: overview.grants,
+    }))
+}
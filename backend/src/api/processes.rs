@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::processes::{kill_statement, KillMode, ServerProcess};
+use crate::db::{DatabaseError, LocalStorageManager};
+use crate::services::audit::{self, AuditRecord};
+use crate::services::policy::is_production;
+
+// 会话列表参数
+#[derive(Debug, Deserialize)]
+pub struct ProcessListParams {
+    pub connection_id: Option<i64>,
+}
+
+// 终止会话参数
+#[derive(Debug, Deserialize)]
+pub struct KillProcessParams {
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub mode: KillMode,             // query：只取消正在执行的语句（默认）；connection：断开会话
+    // 生产环境连接上终止会话需显式确认
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+// 会话列表响应
+#[derive(Debug, Serialize)]
+pub struct ProcessListResponse {
+    pub connection_id: Option<i64>,
+    pub processes: Vec<ServerProcess>,
+    pub count: usize,
+}
+
+// 服务端会话路由（挂载在 /api/database 下）
+pub fn process_routes() -> Router {
+    Router::new()
+        // 服务端会话列表
+        .route("/processes", get(list_processes))
+        // 终止会话或其正在执行的语句
+        .route("/processes/:id", delete(kill_process))
+}
+
+fn map_process_error(e: DatabaseError, action: &str) -> ApiError {
+    match e {
+        DatabaseError::UnsupportedDatabaseType(message) => ApiError::bad_request("unsupported_database", message),
+        e => ApiError::internal("query_failed", format!("{}失败: {}", action, e)),
+    }
+}
+
+/**
+ * 获取服务端会话列表处理函数
+ * MySQL 读取 PROCESSLIST，PostgreSQL 读取 pg_stat_activity，按语句持续时间倒序
+ */
+pub async fn list_processes(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ProcessListParams>,
+) -> Result<Json<ProcessListResponse>, ApiError> {
+    info!("[API] GET /api/database/processes - 请求: connection_id={:?}", params.connection_id);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    let processes = db_manager.list_processes().await
+        .map_err(|e| map_process_error(e, "获取服务端会话"))?;
+
+    info!("[API] GET /api/database/processes - 响应成功: 会话数量={}", processes.len());
+    Ok(Json(ProcessListResponse {
+        connection_id: connection.id,
+        count: processes.len(),
+        processes,
+    }))
+}
+
+/**
+ * 终止服务端会话处理函数
+ * 用于客户端取消无法中断的、已在服务端执行的查询；mode=connection 时断开整个会话
+ */
+pub async fn kill_process(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+    Query(params): Query<KillProcessParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/database/processes/{} - 终止会话请求: connection_id={:?}, mode={:?}", id, params.connection_id, params.mode);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    let Some(statement) = kill_statement(db_manager.db_type, id, params.mode) else {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}不支持终止服务端会话", db_manager.db_type)));
+    };
+    if is_production(&connection) && !params.confirm_production {
+        return Err(ApiError::confirmation_required(
+            "production_confirmation_required",
+            format!("连接 {} 为生产环境，终止会话前需要确认", connection.name),
+        ));
+    }
+
+    let outcome = db_manager.kill_process(id, params.mode).await;
+    let error = match &outcome {
+        Ok(true) => None,
+        Ok(false) => Some(format!("会话 {} 不存在或已结束", id)),
+        Err(e) => Some(e.to_string()),
+    };
+    // 终止会话记录审计日志（包括失败的操作）
+    audit::record_admin_in_background(&storage, AuditRecord {
+        connection: &connection,
+        source: audit::SOURCE_PROCESSES,
+        ai_generated: false,
+        affected_rows: None,
+        error: error.clone(),
+    }, &statement);
+
+    let killed = outcome.map_err(|e| map_process_error(e, "终止会话"))?;
+    if !killed {
+        return Err(ApiError::not_found("process_not_found", error.unwrap_or_default()));
+    }
+
+    info!("[API] 已终止服务端会话: 连接={}, 语句={}", connection.name, statement);
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "statement": statement,
+        "message": match params.mode {
+            KillMode::Query => format!("已取消会话 {} 正在执行的语句", id),
+            KillMode::Connection => format!("已断开会话 {}", id),
+        },
+    })))
+}
@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, Query},
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use log::*;
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::api::error::ApiError;
+use crate::api::routes::load_connection_to;
+use crate::db::query_sessions::{self, QuerySessionInfo, SessionError, SESSION_IDLE_TIMEOUT};
+use crate::db::{DatabaseType, LocalStorageManager};
+
+// 打开会话请求
+#[derive(Debug, Deserialize)]
+pub struct OpenSessionRequest {
+    pub connection_id: Option<i64>,   // 为空时使用第一个活动连接
+    #[serde(default)]
+    pub database: Option<String>,     // 切换到同一服务器上的其他数据库
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListParams {
+    pub connection_id: Option<i64>,
+}
+
+// 查询会话路由（挂载在 /api/database 下）
+pub fn query_session_routes() -> Router {
+    Router::new()
+        // 会话列表 / 打开会话
+        .route("/sessions", get(list_sessions).post(open_session))
+        // 关闭会话
+        .route("/sessions/:id", delete(close_session))
+}
+
+// 启动后台任务，定期关闭闲置超时的会话（查询时也会顺带清理）
+pub fn spawn_session_reaper() -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SESSION_IDLE_TIMEOUT / 15);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            query_sessions::reap_idle();
+        }
+    })
+}
+
+/**
+ * 打开查询会话处理函数
+ * 为连接建立一个专用连接，查询时携带 session_id 即在该连接上执行，闲置超时后自动关闭
+ */
+pub async fn open_session(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<OpenSessionRequest>,
+) -> Result<Json<QuerySessionInfo>, ApiError> {
+    info!("[API] POST /api/database/sessions - 打开会话: connection_id={:?}, database={:?}", payload.connection_id, payload.database);
+
+    let database = payload.database.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    let (connection, conn_str) = load_connection_to(&storage, payload.connection_id, database).await?;
+    let connection_id = connection.id.ok_or_else(|| ApiError::bad_request("connection_not_found", "连接尚未保存"))?;
+    if !query_sessions::is_supported(DatabaseType::from_name(&connection.db_type)) {
+        return Err(ApiError::bad_request("unsupported_database", "会话模式仅支持 MySQL / PostgreSQL / SQLite 连接"));
+    }
+
+    let session = query_sessions::open(connection_id, database, &conn_str, &connection.options.init_sql).await
+        .map_err(|e| match e {
+            SessionError::TooManySessions => ApiError::conflict("too_many_sessions", e.to_string()),
+            SessionError::Connect(_) => ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)),
+        })?;
+    Ok(Json(session))
+}
+
+/**
+ * 获取查询会话列表处理函数
+ */
+pub async fn list_sessions(
+    Query(params): Query<SessionListParams>,
+) -> Json<serde_json::Value> {
+    let sessions = query_sessions::list(params.connection_id);
+    Json(serde_json::json!({
+        "success": true,
+        "data": sessions,
+        "count": sessions.len(),
+    }))
+}
+
+/**
+ * 关闭查询会话处理函数
+ * 释放会话的专用连接，会话变量和临时表随之清除
+ */
+pub async fn close_session(
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/database/sessions/{} - 关闭会话", id);
+
+    if !query_sessions::close(&id) {
+        return Err(ApiError::not_found("session_not_found", format!("会话 {} 不存在或已因闲置超时关闭", id)));
+    }
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "会话已关闭",
+    })))
+}
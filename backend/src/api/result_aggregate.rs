@@ -0,0 +1,85 @@
+use axum::{Extension, Json};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::execute_query;
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::models::SqlQueryRequest;
+use crate::services::result_aggregate::{aggregate, AggregateResult, AggregateSpec};
+use crate::utils::security::classify_sql_as;
+
+// 聚合请求体大小上限（直接提交十万行级别的结果）
+pub const MAX_AGGREGATE_BODY_BYTES: usize = 64 * 1024 * 1024;
+// 参与聚合的最大行数
+const MAX_ROWS: usize = 1_000_000;
+
+// 结果聚合请求：提交之前返回的结果（columns + rows），或指定 connection_id + sql 重新执行查询
+#[derive(Debug, Deserialize)]
+pub struct AggregateRequest {
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub rows: Option<Vec<Vec<JsonValue>>>,
+    #[serde(default)]
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub sql: Option<String>,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    #[serde(flatten)]
+    pub spec: AggregateSpec,
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+/**
+ * 结果聚合处理函数
+ * 在服务端对结果集分组、聚合和透视，不需要编写新的SQL
+ */
+pub async fn aggregate_result(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<AggregateRequest>,
+) -> Result<Json<AggregateResult>, ApiError> {
+    info!("[API] POST /api/tools/result/aggregate - 结果聚合请求: group_by={:?}, 聚合数={}, pivot={:?}",
+        req.spec.group_by, req.spec.aggregations.len(), req.spec.pivot);
+
+    let (columns, rows) = match (req.columns, req.rows, req.sql) {
+        (Some(columns), Some(rows), _) => (columns, rows),
+        (_, _, Some(sql)) => {
+            // 重新执行查询时只允许只读语句
+            let db_type = match req.connection_id {
+                Some(id) => storage.get_connection(id).await
+                    .map(|c| DatabaseType::from_name(&c.db_type))
+                    .map_err(|e| ApiError::not_found("connection_not_found", format!("获取连接失败: {}", e)))?,
+                None => None,
+            };
+            let statements = classify_sql_as(db_type, &sql)
+                .map_err(|e| ApiError::bad_request("invalid_sql", e))?;
+            if statements.iter().any(|s| s.kind.is_write()) {
+                return Err(ApiError::forbidden("write_not_allowed", "结果聚合只能重新执行只读查询"));
+            }
+            let query = SqlQueryRequest {
+                sql,
+                connection_id: req.connection_id,
+                timeout_secs: Some(req.timeout_secs),
+                ignore_limit: true,
+                ..Default::default()
+            };
+            let Json(result) = execute_query(Extension(storage.clone()), Json(query)).await?;
+            (result.columns, result.rows)
+        }
+        _ => return Err(ApiError::bad_request("missing_result", "需要提供 columns 和 rows，或 sql")),
+    };
+    if rows.len() > MAX_ROWS {
+        return Err(ApiError::bad_request("too_many_rows", format!("参与聚合的行数不能超过 {}", MAX_ROWS)));
+    }
+
+    let result = aggregate(&columns, &rows, &req.spec)
+        .map_err(|e| ApiError::bad_request("invalid_aggregation", e))?;
+    info!("[API] POST /api/tools/result/aggregate - 响应成功: 源行数={}, 分组数={}", result.source_rows, result.group_count);
+    Ok(Json(result))
+}
@@ -1,48 +1,138 @@
-use axum::{routing::{get, post, put, delete}, Router, Extension, Json, http::StatusCode, extract::Query};
+use axum::{routing::{get, post, put, delete}, Router, Extension, Json, http::StatusCode, extract::{DefaultBodyLimit, Query}};
 use serde::{Serialize, Deserialize};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use log::*;
 use uuid::Uuid;
 use sqlx::Row;
 use futures_util::TryStreamExt;
 
-use crate::db::{DatabaseManager, LocalStorageManager};
+use crate::db::{bind_json_values, fetch_rows_affected, query_sessions, DatabaseManager, LocalStorageManager, RowValues};
 use crate::models::{
     SqlGenerateRequest, SqlGenerateResponse,
     SqlOptimizeRequest, SqlOptimizeResponse,
     SqlExplainRequest, SqlExplainResponse,
     SqlToNaturalLanguageRequest, SqlToNaturalLanguageResponse,
     SqlCompletionRequest, SqlCompletionResponse,
+    SqlCompleteRequest, SqlCompleteResponse, CompletionSuggestion,
     ChatAnalysisRequest, ChatAnalysisResponse, ChatMessage,
-    TemplateListResponse, SqlQueryRequest, SqlQueryResult,
-    ErrorResponse as ModelErrorResponse,
+    TemplateListResponse, SqlQueryRequest, SqlQueryResult, QueryPerformance,
     TableColumn, TableIndex, TemplateType, TemplateResponse, TemplateRequest,
     BatchSqlRequest, BatchSqlResult,
-    ExecutionPlanRequest, ExecutionPlanResponse, ExecutionPlanNode,
-    DatabaseConnection as DbConnection
+    ExecutionPlanRequest, ExecutionPlanResponse, ExecutionPlanNode, FavoriteParameter,
+    DatabaseConnection as DbConnection, DEFAULT_QUERY_TIMEOUT_SECS
 };
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 
-use crate::services::ai::AiService;
+use crate::services::ai::{AiFeature, AiService};
+use crate::services::llm::{LlmConfig, ProviderKind};
 use crate::services::templates::{TemplateManager, PromptTemplate};
+use crate::services::hooks::{self, QueryContext, QueryOutcome};
+use crate::services::audit::{self, AuditRecord};
+use crate::services::notifications::{self, Notification, EVENT_QUERY_FAILED};
+use crate::services::slow_queries;
+use crate::services::metrics;
+use crate::services::keyset::{build_keyset_sql, next_cursor, Keyset};
+use crate::services::result_cache::{self, ResultCache, ResultCacheKey};
+use crate::services::app_settings::{self, AppSettings};
+use crate::services::sql_validation::{self, SqlValidation};
+use crate::services::sample_values;
+use crate::services::schema_cache::{self, SchemaCache, DEFAULT_SCHEMA_CACHE_TTL};
+use crate::services::table_relevance;
+use crate::services::data_dictionary;
+use crate::services::embeddings;
+use crate::services::events::{self, ServerEvent};
+use crate::services::completion::{self, SchemaTable};
+use crate::utils::bson_parser::{self, MongoOperation};
+use crate::utils::explain_parser;
 use crate::api::bulk_operations::{bulk_insert_data, bulk_update_data, bulk_delete_data};
+use crate::api::temporal::{rewrite_as_of_query, list_temporal_tables};
+use crate::api::export::export_query_csv;
+use crate::api::meta::get_type_mappings;
+use crate::api::ddl::{table_routes, view_routes};
+use crate::api::table_data::{row_routes, update_result_cell};
+use crate::api::schema_graph::get_schema_graph;
+use crate::api::notifications::notification_routes;
+use crate::api::performance::performance_routes;
+use crate::api::advisor::advisor_routes;
+use crate::api::overview::get_database_overview;
+use crate::api::schema_search::search_schema;
+use crate::api::ai_stream::{generate_sql_stream, optimize_sql_stream, explain_sql_stream};
+use crate::api::ai_usage::get_ai_usage;
+use crate::api::logs::get_recent_logs;
+use crate::api::ws::ws_handler;
+use crate::api::script::{execute_script, upload_script, MAX_UPLOAD_BYTES};
+use crate::api::backup::backup_database;
+use crate::api::data_diff::data_diff;
+use crate::api::generate_dml::generate_dml_statements;
+use crate::api::generate_data::generate_table_data;
+use crate::api::lint::lint_sql_handler;
+use crate::api::chart_suggest::suggest_chart;
+use crate::api::federation::federation_routes;
+use crate::api::result_aggregate::{aggregate_result, MAX_AGGREGATE_BODY_BYTES};
+use crate::api::settings::{get_settings, update_settings};
+use crate::api::editor_sessions::editor_session_routes;
+use crate::api::dictionary::dictionary_routes;
+use crate::api::audit::audit_routes;
+use crate::api::dml_preview::preview_dml;
+use crate::api::jobs::jobs_routes;
+use crate::api::query_sessions::query_session_routes;
+use crate::api::processes::process_routes;
+use crate::api::monitor::get_database_monitor;
+use crate::api::template_bindings::{ai_service_for_template, template_binding_routes};
+use crate::api::ai_analyze::analyze_question;
+use crate::api::ai_diagnose::diagnose_error;
+use crate::api::ai_schema::generate_schema;
+use crate::api::connection_bundle::{export_connections, import_connections};
+use crate::api::connection_health::{get_connection_health, get_connection_pool_stats};
+use crate::api::schemas::{list_schemas, list_connection_databases};
+use crate::api::objects::list_database_objects;
+use crate::api::privileges::list_privileges;
+use crate::api::multi_result::execute_multi_query;
+use crate::api::error::ApiError;
 
 // 类型别名，用于简化复杂类型
-type QueryCancellerMap = Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>>;
+pub(crate) type QueryCancellerMap = Arc<Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>>;
 
 // 健康检查响应
-#[derive(Serialize)]
-struct HealthResponse {
+#[derive(Serialize, utoipa::ToSchema)]
+pub(crate) struct HealthResponse {
     status: String,
     message: String,
 }
 
+// 详细健康检查响应（供桌面应用和排查启动问题使用）
+#[derive(Serialize, utoipa::ToSchema)]
+pub(crate) struct DetailedHealthResponse {
+    status: String,                  // ok / degraded（本地存储不可用时）
+    version: String,
+    uptime_secs: u64,
+    local_storage: LocalStorageStatus,
+    cached_pools: usize,             // 已缓存的数据库连接池数量
+    ai_configured: bool,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub(crate) struct LocalStorageStatus {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// 服务启动时间（用于计算运行时长）
+static SERVER_STARTED_AT: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+
+// 记录并获取服务启动时间，启动时调用一次
+pub fn server_started_at() -> Instant {
+    *SERVER_STARTED_AT.get_or_init(Instant::now)
+}
+
 // 数据库信息响应
-#[derive(Serialize)]
-struct DatabaseInfoResponse {
+#[derive(Serialize, utoipa::ToSchema)]
+pub(crate) struct DatabaseInfoResponse {
     database_type: String,
     tables: Vec<String>,
+    views: Vec<String>,
 }
 
 // 敏感词汇检测
@@ -50,16 +140,19 @@ struct DatabaseInfoResponse {
 
 
 // 表请求参数
-#[derive(Serialize, Deserialize)]
-struct TableRequest {
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
+pub(crate) struct TableRequest {
     table_name: String,
     schema_name: Option<String>,
     connection_id: Option<i64>, // 支持指定连接ID
+    // SQLite默认行数统计有上限，为true时精确统计
+    #[serde(default)]
+    exact_count: bool,
 }
 
 // API 表结构响应（与前端对应）
-#[derive(Debug, Serialize)]
-struct ApiTableSchema {
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub(crate) struct ApiTableSchema {
     pub name: String,
     pub columns: Vec<TableColumn>,
     pub indexes: Option<Vec<TableIndex>>,
@@ -71,7 +164,9 @@ struct ApiTableSchema {
     pub updated_at: Option<String>,
     #[serde(rename = "rowCount")]
     pub row_count: Option<u64>,
-    pub size: Option<u64>,
+    pub size: Option<u64>,  // 数据和索引占用空间（字节）
+    #[serde(rename = "rowCountEstimated", skip_serializing_if = "Option::is_none")]
+    pub row_count_estimated: Option<bool>,
 }
 
 // 创建API路由
@@ -79,27 +174,73 @@ pub fn create_routes() -> Router {
     Router::new()
         // 健康检查
         .route("/health", get(health_check))
+        // 详细健康检查（版本、本地存储、连接池、AI配置、运行时长）
+        .route("/health/detailed", get(detailed_health_check))
         // 数据库API路由组
         .nest("/database", 
             Router::new()
                 // 数据库信息
                 .route("/info", get(get_database_info))
+                // 数据库概览（版本、大小、字符集、连接数、运行时长）
+                .route("/overview", get(get_database_overview))
+                // 监控指标（连接数、QPS、缓存命中率等）及近期采样
+                .route("/monitor", get(get_database_monitor))
+                // 可用schema列表
+                .route("/schemas", get(list_schemas))
+                // 存储过程、函数、触发器和视图（含参数签名和源码）
+                .route("/objects", get(list_database_objects))
+                // 用户 / 角色及其权限（只读）
+                .route("/privileges", get(list_privileges))
                 // 获取表结构
                 .route("/table/structure", post(get_table_structure))
+                // 表结构管理（建表、改表、删表、索引）及表格数据编辑
+                .nest("/table", table_routes().merge(row_routes()))
+                // 视图管理（创建、修改、删除、查看定义）
+                .nest("/view", view_routes())
+                // ER图数据（表、列、主键、外键关系）
+                .route("/schema/graph", get(get_schema_graph))
+                // 清除表结构缓存
+                .route("/schema/refresh", post(refresh_schema_cache))
+                // 表结构语义检索（基于嵌入向量）
+                .route("/schema/search", get(search_schema))
                 // 执行SQL查询
                 .route("/query", post(execute_query))
                 // 批量执行SQL查询
                 .route("/query/batch", post(execute_batch_query))
+                // 多结果集查询（存储过程、SHOW 语句）
+                .route("/query/multi", post(execute_multi_query))
+                // 清除查询结果缓存
+                .route("/query/cache/clear", post(clear_result_cache))
+                // 预览 UPDATE / DELETE 将影响的行（不执行修改）
+                .route("/query/preview", post(preview_dml))
+                // 查询结果单元格编辑（按主键写回）
+                .route("/query/cell", put(update_result_cell))
+                // 执行SQL脚本（多语句，逐条返回执行结果）
+                .route("/script", post(execute_script))
+                // 上传并执行SQL文件（multipart，放宽请求体大小限制）
+                .route("/script/upload", post(upload_script).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)))
                 // 获取执行计划
                 .route("/query/explain", post(get_execution_plan))
                 // 取消查询
                 .route("/query/:query_id/cancel", post(cancel_query))
+                // 时间旅行查询（改写为 AS OF 查询）
+                .route("/query/as-of", post(rewrite_as_of_query))
+                // 系统版本表元数据
+                .route("/temporal/tables", get(list_temporal_tables))
+                // 导出查询结果（CSV）
+                .route("/export", post(export_query_csv))
+                // 数据库备份（建表语句 + INSERT，SQL文件下载）
+                .route("/backup", post(backup_database))
                 // 批量插入数据
                 .route("/data/bulk-insert", post(bulk_insert_data))
                 // 批量更新数据
                 .route("/data/bulk-update", post(bulk_update_data))
                 // 批量删除数据
                 .route("/data/bulk-delete", post(bulk_delete_data))
+                // 查询会话（专用连接，会话状态在查询间保留）
+                .merge(query_session_routes())
+                // 服务端会话列表及终止（PROCESSLIST / pg_stat_activity）
+                .merge(process_routes())
         )
         // AI功能API路由组
         .nest("/ai", 
@@ -110,17 +251,39 @@ pub fn create_routes() -> Router {
                 .route("/sql/optimize", post(optimize_sql))
                 // 解释SQL
                 .route("/sql/explain", post(explain_sql))
+                // 流式生成/优化/解释SQL（SSE，逐段返回AI输出）
+                .route("/sql/generate/stream", post(generate_sql_stream))
+                .route("/sql/optimize/stream", post(optimize_sql_stream))
+                .route("/sql/explain/stream", post(explain_sql_stream))
                 // SQL转自然语言
                 .route("/sql/to-natural-language", post(sql_to_natural_language))
+                // SQL描述（生成自然语言说明，可保存到收藏）
+                .route("/sql/describe", post(sql_to_natural_language))
                 // SQL智能补全
                 .route("/sql/completion", post(sql_completion))
+                // 基于表结构的SQL自动补全（本地补全 + AI补全）
+                .route("/sql/complete", post(sql_complete))
                 // 对话式AI分析
                 .route("/chat", post(chat_analysis))
+                // 自然语言数据分析（生成SQL、执行并总结结果）
+                .route("/analyze", post(analyze_question))
+                // SQL错误诊断（错误原因和修正后的SQL）
+                .route("/diagnose", post(diagnose_error))
+                // 根据实体描述生成建表DDL（含索引和注释，经语法校验）
+                .route("/schema/generate", post(generate_schema))
+                // 对话会话管理
+                .route("/chat/conversations", get(list_chat_conversations))
+                .route("/chat/conversations/:id", get(get_chat_conversation))
+                .route("/chat/conversations/:id", delete(delete_chat_conversation))
                 // AI生成建表SQL
                 .route("/table/create", post(create_table))
+                // AI调用用量与费用统计
+                .route("/usage", get(get_ai_usage))
                 // AI配置管理
                 .route("/config", get(get_ai_config))
                 .route("/config", post(save_ai_config))
+                // 保存前测试AI配置是否可用
+                .route("/config/test", post(test_ai_config))
         )
         // 模板管理API路由组
         .nest("/templates", 
@@ -137,6 +300,8 @@ pub fn create_routes() -> Router {
                 .route("/:template_id", delete(delete_template))
                 // 设置默认模板
                 .route("/set-default", post(set_default_template))
+                // 预览模板渲染结果
+                .route("/:template_id/preview", post(preview_template))
         )
         // 连接配置管理API路由组
         .nest("/connections",
@@ -155,17 +320,84 @@ pub fn create_routes() -> Router {
                 .route("/:id/toggle", post(toggle_connection_active))
                 // 测试连接
                 .route("/test", post(test_connection))
+                // 调整连接顺序
+                .route("/reorder", post(reorder_connections))
+                // 导出/导入连接配置（口令加密的JSON配置包）
+                .route("/export", post(export_connections))
+                .route("/import", post(import_connections))
+                // 移动连接到分组
+                .route("/:id/move", post(move_connection))
+                // 连接健康状态（后台定期检测）
+                .route("/:id/health", get(get_connection_health))
+                // 缓存连接池统计（连接数、空闲连接、等待次数、连接错误）
+                .route("/:id/pool-stats", get(get_connection_pool_stats))
+                // 连接所在服务器的数据库列表
+                .route("/:id/databases", get(list_connection_databases))
+                // 连接分组（文件夹）
+                .route("/groups", get(list_connection_groups))
+                .route("/groups", post(create_connection_group))
+                .route("/groups/reorder", post(reorder_connection_groups))
+                .route("/groups/:id", put(update_connection_group))
+                .route("/groups/:id", delete(delete_connection_group))
+                // 连接级提示词模板绑定
+                .merge(template_binding_routes())
         )
         // 查询历史API路由组
         .nest("/history",
             Router::new()
                 // 查询历史列表
                 .route("/", get(list_query_history))
+                // 查询历史统计
+                .route("/stats", get(query_history_stats))
                 // 切换收藏状态
                 .route("/:id/favorite", post(toggle_query_favorite))
                 // 清空历史
                 .route("/clear", delete(clear_query_history))
         )
+        // 元数据API路由组
+        .nest("/meta",
+            Router::new()
+                // 列类型映射表
+                .route("/type-mappings", get(get_type_mappings))
+        )
+        // 通知（Webhook）配置API路由组
+        .nest("/notifications", notification_routes())
+        // 性能分析（慢查询日志）API路由组
+        .nest("/performance", performance_routes())
+        // 运行日志（诊断面板）
+        .nest("/logs", Router::new().route("/recent", get(get_recent_logs)))
+        // 服务端事件推送（查询进度、表结构刷新等）
+        .route("/ws", get(ws_handler))
+        // 工具API路由组
+        .nest("/tools",
+            Router::new()
+                // 跨连接数据对比
+                .route("/data-diff", post(data_diff))
+                // 由结果行生成 INSERT / UPDATE / DELETE 语句
+                .route("/generate-dml", post(generate_dml_statements))
+                // 按表结构生成测试数据并分批插入
+                .route("/generate-data", post(generate_table_data))
+                // 本地SQL语法检查和常见问题提示
+                .route("/lint-sql", post(lint_sql_handler))
+                // 按查询结果推荐图表配置
+                .route("/chart-suggest", post(suggest_chart))
+                // 对结果集分组聚合/透视（放宽请求体大小限制）
+                .route("/result/aggregate", post(aggregate_result).layer(DefaultBodyLimit::max(MAX_AGGREGATE_BODY_BYTES)))
+        )
+        // 应用设置（查询行数限制等）
+        .route("/settings", get(get_settings).put(update_settings))
+        // 编辑器会话（标签页持久化）API路由组
+        .nest("/editor", editor_session_routes())
+        // 跨连接联邦查询API路由组
+        .nest("/federation", federation_routes())
+        // 数据字典（业务术语、同义词、枚举含义）API路由组
+        .nest("/dictionary", dictionary_routes())
+        // 写操作审计日志API路由组
+        .nest("/audit", audit_routes())
+        // 后台任务（查询、脚本、备份）API路由组
+        .nest("/jobs", jobs_routes())
+        // 优化建议（索引建议）API路由组
+        .nest("/advisor", advisor_routes())
         // SQL收藏夹API路由组
         .nest("/favorites",
             Router::new()
@@ -183,10 +415,18 @@ pub fn create_routes() -> Router {
                 .route("/categories", get(list_favorite_categories))
                 // 增加收藏使用次数
                 .route("/:id/use", post(increment_favorite_usage))
+                // 绑定参数并执行收藏的SQL
+                .route("/:id/execute", post(execute_sql_favorite))
         )
 }
 
 // 健康检查处理函数
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "system",
+    responses((status = 200, description = "服务运行正常", body = HealthResponse))
+)]
 async fn health_check() -> Json<HealthResponse> {
     info!("[API] GET /health - 健康检查请求");
     let response = HealthResponse {
@@ -197,70 +437,75 @@ async fn health_check() -> Json<HealthResponse> {
     Json(response)
 }
 
+// 详细健康检查处理函数
+#[utoipa::path(
+    get,
+    path = "/api/health/detailed",
+    tag = "system",
+    responses((status = 200, description = "版本、本地存储状态、缓存连接池数量、AI配置状态和运行时长", body = DetailedHealthResponse))
+)]
+async fn detailed_health_check(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Json<DetailedHealthResponse> {
+    info!("[API] GET /health/detailed - 详细健康检查请求");
+    let local_storage = match storage.ping().await {
+        Ok(()) => LocalStorageStatus { ok: true, error: None },
+        Err(e) => {
+            warn!("[API] GET /health/detailed - 本地存储不可用: {}", e);
+            LocalStorageStatus { ok: false, error: Some(e.to_string()) }
+        }
+    };
+    let response = DetailedHealthResponse {
+        status: if local_storage.ok { "ok" } else { "degraded" }.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_secs: server_started_at().elapsed().as_secs(),
+        cached_pools: crate::db::pool_cache::cached_pool_count(),
+        ai_configured: AiService::is_configured(&storage).await,
+        local_storage,
+    };
+    debug!("[API] GET /health/detailed - 响应: {}", response.status);
+    Json(response)
+}
+
 // 获取数据库信息处理函数
+#[utoipa::path(
+    get,
+    path = "/api/database/info",
+    tag = "database",
+    params(("connection_id" = Option<i64>, Query, description = "连接ID，未指定时使用当前激活的连接")),
+    responses(
+        (status = 200, description = "数据库类型和表名列表", body = DatabaseInfoResponse),
+        (status = 404, description = "连接不存在", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn get_database_info(
     Extension(storage): Extension<LocalStorageManager>,
     Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<DatabaseInfoResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<DatabaseInfoResponse>, ApiError> {
     info!("[API] GET /api/database/info - 获取数据库信息请求");
     
     // 尝试从查询参数获取连接ID
     let connection = if let Some(conn_id_str) = params.get("connection_id") {
         // 使用指定的连接ID
         let conn_id = conn_id_str.parse::<i64>().map_err(|_| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "invalid_connection_id".to_string(),
-                    message: "无效的连接ID".to_string(),
-                    details: None,
-                })
-            )
+            ApiError::bad_request("invalid_connection_id", "无效的连接ID")
         })?;
         
         storage.get_connection_by_id(conn_id).await.map_err(|e| {
             log::error!("获取连接失败: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "connection_not_found".to_string(),
-                    message: format!("连接不存在: {}", e),
-                    details: None,
-                })
-            )
+            ApiError::internal("connection_not_found", format!("连接不存在: {}", e))
         })?.ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ModelErrorResponse {
-                    error: "connection_not_found".to_string(),
-                    message: "连接不存在".to_string(),
-                    details: None,
-                })
-            )
+            ApiError::not_found("connection_not_found", "连接不存在")
         })?
     } else {
         // 获取第一个活动连接
         let connections = storage.get_active_connections().await.map_err(|e| {
             log::error!("获取活动连接失败: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接信息失败: {}", e),
-                    details: None,
-                })
-            )
+            ApiError::db("database_error", format!("获取连接信息失败: {}", e))
         })?;
         
         if connections.is_empty() {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "no_active_connection".to_string(),
-                    message: "没有活动的数据库连接".to_string(),
-                    details: None,
-                }),
-            ));
+            return Err(ApiError::bad_request("no_active_connection", "没有活动的数据库连接"));
         }
         
         connections.into_iter().next().unwrap()
@@ -272,7 +517,7 @@ async fn get_database_info(
     let conn_str = build_connection_string(&connection)?;
     
     // 创建数据库管理器
-    match DatabaseManager::from_connection_string(&conn_str).await {
+    match crate::db::pool_cache::get_or_connect(connection.id, &conn_str, &connection.pool_options, &connection.options.init_sql).await {
         Ok(db_manager) => {
             // 获取数据库类型
             let database_type = format!("{:?}", db_manager.db_type);
@@ -283,26 +528,26 @@ async fn get_database_info(
                 vec![]
             });
             
+            // 获取视图列表（与表分开列出）
+            let views = db_manager.get_views().await.unwrap_or_else(|e| {
+                log::warn!("获取视图列表失败: {}", e);
+                vec![]
+            });
+            
             let response = DatabaseInfoResponse {
                 database_type: database_type.clone(),
                 tables: tables.clone(),
+                views,
             };
             info!("[API] GET /api/database/info - 响应: 数据库类型={}, 表数量={}", database_type, tables.len());
             if let Ok(resp_json) = serde_json::to_string(&response) {
-                log::info!("[API] GET /api/database/info - 响应体: {}", resp_json);
+                log::debug!("[API] GET /api/database/info - 响应体: {}", resp_json);
             }
             Ok(Json(response))
         }
         Err(e) => {
             log::error!("数据库连接失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "connection_failed".to_string(),
-                    message: format!("数据库连接失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)))
         }
     }
 }
@@ -353,54 +598,6 @@ fn split_params(s: &str) -> Vec<&str> {
     params
 }
 
-// 辅助函数：解析MongoDB投影参数，支持MongoDB Shell语法，如 { name: 1, _id: 0 }
-fn parse_mongodb_projection(projection_str: &str) -> Result<mongodb::bson::Document, String> {
-    // 从第一性原理出发，直接解析投影字符串
-    // 支持的格式：{ name: 1, _id: 0 }
-    let mut doc = mongodb::bson::Document::new();
-    
-    // 移除首尾的空格和大括号
-    let trimmed = projection_str.trim().trim_matches(|c| c == '{' || c == '}').trim();
-    if trimmed.is_empty() {
-        return Ok(doc);
-    }
-    
-    // 按逗号分割键值对
-    let pairs: Vec<&str> = trimmed.split(',').map(|s| s.trim()).collect();
-    
-    for pair in pairs {
-        // 按冒号分割键和值
-        if let Some((key, value)) = pair.split_once(':') {
-            let key_trimmed = key.trim();
-            let value_trimmed = value.trim();
-            
-            // 解析值
-            let bson_value = match value_trimmed {
-                "1" => mongodb::bson::Bson::Int32(1),
-                "0" => mongodb::bson::Bson::Int32(0),
-                "true" => mongodb::bson::Bson::Boolean(true),
-                "false" => mongodb::bson::Bson::Boolean(false),
-                _ => {
-                    // 尝试解析为其他类型
-                    if let Ok(num) = value_trimmed.parse::<i32>() {
-                        mongodb::bson::Bson::Int32(num)
-                    } else if let Ok(num) = value_trimmed.parse::<f64>() {
-                        mongodb::bson::Bson::Double(num)
-                    } else {
-                        // 视为字符串
-                        mongodb::bson::Bson::String(value_trimmed.to_string())
-                    }
-                }
-            };
-            
-            // 添加到文档
-            doc.insert(key_trimmed, bson_value);
-        }
-    }
-    
-    Ok(doc)
-}
-
 /// 将包含MongoDB扩展JSON格式（如$oid）的JSON值转换为BSON值
 /// 递归处理对象和数组，将{"$oid": "..."}转换为ObjectId
 fn json_value_to_bson(value: &serde_json::Value) -> Result<mongodb::bson::Bson, String> {
@@ -475,18 +672,38 @@ fn json_str_to_bson_document(json_str: &str) -> Result<mongodb::bson::Document,
 }
 
 
+// 辅助函数：将SQL字符串解析为单个AST语句
+// 生产环境执行保护：写操作需确认，策略禁止的语句直接拒绝
+pub(crate) fn production_guard(
+    connection: &DbConnection,
+    statements: &[String],
+    confirmed: bool,
+) -> Result<(), ApiError> {
+    use crate::services::policy::{check_read_only, ProductionPolicy};
+
+    // 只读连接（连接选项 read_only）不允许写操作，确认后也不能执行
+    check_read_only(connection, statements).map_err(|message| {
+        warn!("[Policy] 只读连接执行写操作被拦截: 连接={}", connection.name);
+        ApiError::forbidden("read_only_connection", message)
+    })?;
 
-// 常量定义
-const MAX_LIMIT: u64 = 1500;
-const DEFAULT_LIMIT: u64 = 200;
+    ProductionPolicy::from_env().check(connection, statements, confirmed).map_err(|warning| {
+        warn!("[Policy] 生产环境执行被拦截: 连接={}, blocked={}, 语句={:?}", warning.connection_name, warning.blocked, warning.statements);
+        let error = if warning.blocked {
+            ApiError::forbidden("production_operation_blocked", warning.message.clone())
+        } else {
+            ApiError::confirmation_required("production_confirmation_required", warning.message.clone())
+        };
+        error.with_optional_details(serde_json::to_string(&warning).ok())
+    })
+}
 
-// 辅助函数：将SQL字符串解析为单个AST语句
-fn parse_sql(sql: &str) -> Result<sqlparser::ast::Statement, String> {
+// 按连接的数据库方言解析单条SQL（MySQL 反引号、PostgreSQL :: 类型转换等）
+pub(crate) fn parse_sql(db_type: crate::db::DatabaseType, sql: &str) -> Result<sqlparser::ast::Statement, String> {
     use sqlparser::parser::Parser;
-    use sqlparser::dialect::GenericDialect;
     
-    let dialect = GenericDialect {};
-    let mut ast = Parser::parse_sql(&dialect, sql)
+    let dialect = crate::utils::security::dialect_for(db_type);
+    let mut ast = Parser::parse_sql(dialect.as_ref(), sql)
         .map_err(|e| format!("SQL 语法错误: {}", e))?;
     
     if ast.len() != 1 {
@@ -497,7 +714,7 @@ fn parse_sql(sql: &str) -> Result<sqlparser::ast::Statement, String> {
 }
 
 // 辅助函数：在AST级别应用Limit兜底和限制逻辑
-fn apply_limit_clamping(statement: sqlparser::ast::Statement) -> sqlparser::ast::Statement {
+fn apply_limit_clamping(statement: sqlparser::ast::Statement, limits: &AppSettings) -> sqlparser::ast::Statement {
     use sqlparser::ast::{Statement, Expr, Value};
     use std::cmp;
     
@@ -513,7 +730,7 @@ fn apply_limit_clamping(statement: sqlparser::ast::Statement) -> sqlparser::ast:
                     // 尝试解析当前的 LIMIT 表达式，如果解析失败则保持原样（安全第一）
                     if let Expr::Value(Value::Number(s, _)) = expr {
                         if let Ok(current_limit) = s.parse::<u64>() {
-                            let clamped_limit = cmp::min(current_limit, MAX_LIMIT);
+                            let clamped_limit = cmp::min(current_limit, limits.max_query_limit);
                             // 更新 AST 中的 LIMIT 值
                             *s = clamped_limit.to_string();
                         }
@@ -522,7 +739,7 @@ fn apply_limit_clamping(statement: sqlparser::ast::Statement) -> sqlparser::ast:
                 // 情况 2: LIMIT 不存在，插入默认值 (Defaulting)
                 None => {
                     let default_limit_value = Expr::Value(
-                        Value::Number(DEFAULT_LIMIT.to_string(), false)
+                        Value::Number(limits.default_query_limit.to_string(), false)
                     );
                     query.limit = Some(default_limit_value);
                 }
@@ -535,74 +752,269 @@ fn apply_limit_clamping(statement: sqlparser::ast::Statement) -> sqlparser::ast:
     }
 }
 
-// 辅助函数：将修改后的AST重构回SQL字符串
-fn reconstruct_sql(statement: &sqlparser::ast::Statement) -> String {
-    statement.to_string()
-}
-
 // 辅助函数：为SQL语句添加LIMIT限制（AST-based方案）
-// 如果没有LIMIT，添加默认LIMIT 200
-// 如果有LIMIT，将其限制在1500以内
-fn add_limit_to_sql(sql: &str) -> String {
+// 如果没有LIMIT，添加默认LIMIT（default_query_limit）
+// 如果有LIMIT，将其限制在 max_query_limit 以内；非查询语句保持原样
+fn add_limit_to_sql(db_type: crate::db::DatabaseType, sql: &str, limits: &AppSettings) -> String {
     // 尝试使用AST-based方案
-    match parse_sql(sql) {
-        Ok(ast) => {
-            let modified_ast = apply_limit_clamping(ast);
-            reconstruct_sql(&modified_ast)
-        },
+    match parse_sql(db_type, sql) {
+        Ok(ast @ sqlparser::ast::Statement::Query(_)) => apply_limit_clamping(ast, limits).to_string(),
+        Ok(_) => sql.to_string(),
         Err(_) => {
-            // AST解析失败，回退到简单的字符串替换方案
-            let sql_lower = sql.to_lowercase();
+            // AST解析失败，只对SELECT语句回退到简单的字符串替换方案
+            let trimmed = strip_trailing_semicolon(sql);
+            let sql_lower = trimmed.to_lowercase();
+            if !sql_lower.trim_start().starts_with("select") {
+                return sql.to_string();
+            }
             
             // 检查是否已经包含LIMIT子句
-            if sql_lower.contains(" limit ") {
-                // 提取当前的LIMIT值
-                if let Some(limit_index) = sql_lower.find(" limit ") {
-                    let after_limit = &sql[limit_index + 7..];
-                    
-                    // 查找LIMIT后面的数字
-                    let mut limit_value = String::new();
-                    for c in after_limit.chars() {
-                        if c.is_ascii_digit() {
-                            limit_value.push(c);
-                        } else if c.is_whitespace() {
-                            continue;
-                        } else {
-                            break;
-                        }
-                    }
-                    
-                    // 解析LIMIT值
-                    let mut limit = limit_value.parse::<u32>().unwrap_or(200);
-                    // 限制在1500以内
-                    limit = limit.min(1500);
-                    
-                    // 替换原有的LIMIT子句
-                    let before_limit = &sql[..limit_index + 7];
-                    let after_limit_digit = if let Some(non_digit) = after_limit.find(|c: char| !c.is_ascii_digit() && !c.is_whitespace()) {
-                        &after_limit[non_digit..]
-                    } else {
-                        ""
-                    };
-                    
-                    format!("{}{}{}", before_limit, limit, after_limit_digit)
-                } else {
-                    // 无法找到LIMIT位置，添加默认LIMIT
-                    format!("{} LIMIT 200", sql)
-                }
+            if let Some(limit_index) = sql_lower.rfind(" limit ") {
+                let after_limit = &trimmed[limit_index + 7..];
+                
+                // 查找LIMIT后面的数字
+                let limit_value: String = after_limit.trim_start().chars().take_while(|c| c.is_ascii_digit()).collect();
+                
+                // 解析LIMIT值并限制在上限以内
+                let limit = limit_value.parse::<u64>().unwrap_or(limits.default_query_limit).min(limits.max_query_limit);
+                
+                // 替换原有的LIMIT子句
+                let after_limit_digit = match after_limit.find(|c: char| !c.is_ascii_digit() && !c.is_whitespace()) {
+                    Some(non_digit) => &after_limit[non_digit..],
+                    None => "",
+                };
+                
+                format!("{}{} {}", &trimmed[..limit_index + 7], limit, after_limit_digit).trim_end().to_string()
             } else {
-                // 没有LIMIT，添加默认LIMIT 200
-                format!("{} LIMIT 200", sql)
+                // 没有LIMIT，添加默认LIMIT
+                format!("{} LIMIT {}", trimmed, limits.default_query_limit)
             }
         }
     }
 }
 
+// 分页参数
+#[derive(Debug, Clone)]
+struct Pagination {
+    page: u64,       // 页码（从1开始）
+    page_size: u64,  // 每页大小（不超过查询行数上限）
+    offset: u64,
+    keyset: Option<Keyset>,  // 游标分页时按排序列定位，不使用 offset
+}
+
+impl Pagination {
+    // 仅当请求指定了page时启用分页
+    fn from_request(payload: &SqlQueryRequest, settings: &AppSettings) -> Result<Option<Self>, ApiError> {
+        let Some(page) = payload.page.map(|p| p.max(1)) else {
+            return Ok(None);
+        };
+        let page_size = payload.page_size.unwrap_or(settings.default_page_size).clamp(1, settings.max_query_limit);
+        let keyset = match &payload.keyset_columns {
+            Some(columns) => Some(
+                Keyset::new(columns, payload.keyset_descending, payload.cursor.as_deref())
+                    .map_err(|e| ApiError::bad_request("pagination_error", e))?,
+            ),
+            None => None,
+        };
+        Ok(Some(Self {
+            page,
+            page_size,
+            offset: if keyset.is_some() { 0 } else { (page - 1) * page_size },
+            keyset,
+        }))
+    }
+}
+
+// 辅助函数：去掉SQL末尾的分号，便于作为子查询包装
+fn strip_trailing_semicolon(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';').trim_end()
+}
+
+// 辅助函数：可直接添加 LIMIT/OFFSET 的查询（没有自带 LIMIT/OFFSET/FETCH），否则返回None
+fn unlimited_query(statement: Option<sqlparser::ast::Statement>) -> Option<sqlparser::ast::Query> {
+    match statement {
+        Some(sqlparser::ast::Statement::Query(query))
+            if query.limit.is_none() && query.offset.is_none() && query.fetch.is_none() && query.limit_by.is_empty() => Some(*query),
+        _ => None,
+    }
+}
+
+fn number_expr(n: u64) -> sqlparser::ast::Expr {
+    sqlparser::ast::Expr::Value(sqlparser::ast::Value::Number(n.to_string(), false))
+}
+
+// 辅助函数：为SELECT语句添加分页
+// 直接在解析后的查询上设置 LIMIT/OFFSET（保留原有排序，不受子查询列名重复的影响），
+// 查询自带 LIMIT 或无法解析时包装为子查询；多取一行用于判断是否还有下一页
+// 游标分页时游标值的占位符排在查询自身的 param_offset 个参数之后
+fn build_paged_sql(db_type: crate::db::DatabaseType, sql: &str, pagination: &Pagination, param_offset: usize) -> Result<String, ApiError> {
+    let statement = parse_sql(db_type, sql).ok();
+    if statement.as_ref().is_some_and(|s| !matches!(s, sqlparser::ast::Statement::Query(_))) {
+        return Err(ApiError::bad_request("pagination_error", "分页只支持SELECT查询").with_details(sql.to_string()));
+    }
+    
+    if let Some(keyset) = &pagination.keyset {
+        return build_keyset_sql(db_type, sql, keyset, param_offset, pagination.page_size + 1)
+            .map_err(|e| ApiError::bad_request("pagination_error", e).with_details(sql.to_string()));
+    }
+    
+    if let Some(mut query) = unlimited_query(statement) {
+        query.limit = Some(number_expr(pagination.page_size + 1));
+        query.offset = Some(sqlparser::ast::Offset {
+            value: number_expr(pagination.offset),
+            rows: sqlparser::ast::OffsetRows::None,
+        });
+        return Ok(query.to_string());
+    }
+    
+    Ok(format!(
+        "SELECT * FROM ({}) AS _paged LIMIT {} OFFSET {}",
+        strip_trailing_semicolon(sql),
+        pagination.page_size + 1,
+        pagination.offset
+    ))
+}
+
+// 辅助函数：生成统计总行数的SQL
+// 去掉对计数无用的 ORDER BY；选择列都是普通列或常量时替换为常量，避免子查询中重复的列名。
+// 带 DISTINCT 或表达式列（可能是聚合函数）时保留原选择列，查询自带 LIMIT 或无法解析时直接包装为子查询
+fn build_count_sql(db_type: crate::db::DatabaseType, sql: &str) -> String {
+    use sqlparser::ast::{Expr, SelectItem, SetExpr};
+    
+    let plain_column = |item: &SelectItem| match item {
+        SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(_, _) => true,
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+            matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_) | Expr::Value(_))
+        }
+    };
+    
+    let Some(mut query) = unlimited_query(parse_sql(db_type, sql).ok()) else {
+        return format!("SELECT COUNT(*) FROM ({}) AS _counted", strip_trailing_semicolon(sql));
+    };
+    query.order_by.clear();
+    if let SetExpr::Select(select) = query.body.as_mut() {
+        if select.distinct.is_none() && select.into.is_none() && select.projection.iter().all(plain_column) {
+            select.projection = vec![SelectItem::UnnamedExpr(number_expr(1))];
+        }
+    }
+    format!("SELECT COUNT(*) FROM ({}) AS _counted", query)
+}
+
+// 辅助函数：统计查询结果总行数（MongoDB在查询时单独统计，此处返回None）
+async fn count_total_rows(
+    db_manager: &DatabaseManager,
+    sql: &str,
+    params: &[serde_json::Value],
+) -> Result<Option<u64>, ApiError> {
+    let count_sql = build_count_sql(db_manager.db_type, sql);
+    
+    let total = match &db_manager.pool {
+        crate::db::DatabasePool::MySQL(pool) => {
+            bind_json_values!(sqlx::query_scalar::<_, i64>(&count_sql), params).fetch_one(pool).await
+        }
+        crate::db::DatabasePool::PostgreSQL(pool) => {
+            bind_json_values!(sqlx::query_scalar::<_, i64>(&count_sql), params).fetch_one(pool).await
+        }
+        crate::db::DatabasePool::SQLite(pool) => {
+            bind_json_values!(sqlx::query_scalar::<_, i64>(&count_sql), params).fetch_one(pool).await
+        }
+        crate::db::DatabasePool::MongoDB(_, _) | crate::db::DatabasePool::Redis(_) => return Ok(None),
+        crate::db::DatabasePool::DuckDB(db) => {
+            let result = db.query(&count_sql).await
+                .map_err(|e| ApiError::bad_request("query_error", format!("统计总行数失败: {}", e)).with_details(count_sql.clone()))?;
+            return Ok(result.rows.first().and_then(|row| row.first()).and_then(|v| v.as_u64()));
+        }
+    }
+    .map_err(|e| ApiError::bad_request("query_error", format!("统计总行数失败: {}", e)).with_details(count_sql.clone()))?;
+    
+    Ok(Some(total.max(0) as u64))
+}
+
+// 辅助函数：构建Redis连接字符串，database_name 为库编号（默认0）
+fn redis_connection_string(
+    host: &str,
+    port: Option<i32>,
+    username: Option<&str>,
+    password: Option<&str>,
+    database_name: Option<&str>,
+) -> String {
+    let auth = match (username.filter(|u| !u.is_empty()), password.filter(|p| !p.is_empty())) {
+        (Some(user), Some(pass)) => format!("{}:{}@", user, pass),
+        (None, Some(pass)) => format!(":{}@", pass),
+        _ => String::new(),
+    };
+    let db = database_name.map(str::trim).filter(|d| !d.is_empty()).unwrap_or("0");
+    format!("redis://{}{}:{}/{}", auth, host, port.unwrap_or(6379), db)
+}
+
+// 辅助函数：构建DuckDB连接字符串，file_path 为数据库文件和/或数据文件（CSV/Parquet/JSON），多个以分号分隔
+fn duckdb_connection_string(file_path: Option<&str>) -> String {
+    format!("{}{}", crate::db::duckdb_engine::URL_PREFIX, file_path.map(str::trim).unwrap_or(""))
+}
+
+// 辅助函数：按连接的SSL配置追加连接字符串参数
+fn with_ssl_params(url: String, connection: &DbConnection) -> Result<String, ApiError> {
+    crate::db::ssl::apply_ssl_params(&url, &connection.db_type, connection.ssl_mode.as_deref(), connection.ssl_ca_path.as_deref())
+        .map_err(invalid_ssl_config)
+}
+
+fn invalid_ssl_config(message: String) -> ApiError {
+    ApiError::bad_request("invalid_ssl_config", message)
+}
+
+// 设置了默认schema时追加到连接字符串（仅PostgreSQL）
+fn with_default_schema(url: String, connection: &DbConnection) -> Result<String, ApiError> {
+    crate::db::apply_default_schema(&url, &connection.db_type, connection.default_schema.as_deref())
+        .map_err(invalid_default_schema)
+}
+
+fn invalid_default_schema(message: String) -> ApiError {
+    ApiError::bad_request("invalid_default_schema", message)
+}
+
+// 切换到同一服务器上的其他数据库（只修改本次使用的连接配置，不保存）
+fn switch_database(connection: &mut DbConnection, database: &str) -> Result<(), ApiError> {
+    let database = database.trim();
+    if database.is_empty() || database.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '/' | '\\' | '?' | '#' | '@' | '"' | '\'')) {
+        return Err(ApiError::bad_request("invalid_database", format!("数据库名称无效: {}", database)));
+    }
+    match connection.db_type.as_str() {
+        "sqlite" => return Err(ApiError::bad_request("unsupported_database", "SQLite连接不支持切换数据库")),
+        "duckdb" => return Err(ApiError::bad_request("unsupported_database", "DuckDB连接不支持切换数据库")),
+        "redis" if database.parse::<u8>().is_err() => {
+            return Err(ApiError::bad_request("invalid_database", "Redis数据库必须是编号"));
+        }
+        _ => {}
+    }
+    if let Some(ref cs) = connection.connection_string {
+        let switched = crate::db::replace_url_database(cs, database)
+            .ok_or_else(|| ApiError::bad_request("unsupported_database", "无法从连接字符串切换数据库"))?;
+        connection.connection_string = Some(switched);
+    }
+    connection.database_name = Some(database.to_string());
+    Ok(())
+}
+
+fn invalid_pool_options(message: String) -> ApiError {
+    ApiError::bad_request("invalid_pool_options", message)
+}
+
+fn invalid_connection_options(message: String) -> ApiError {
+    ApiError::bad_request("invalid_connection_options", message)
+}
+
 // 辅助函数：构建连接字符串
-fn build_connection_string(connection: &DbConnection) -> Result<String, (StatusCode, Json<ModelErrorResponse>)> {
+fn build_connection_string(connection: &DbConnection) -> Result<String, ApiError> {
     if let Some(ref cs) = connection.connection_string {
         log::info!("[build_connection_string] 使用自定义连接字符串: {}", cs);
-        return Ok(cs.clone());
+        return with_default_schema(cs.clone(), connection);
+    }
+    
+    // DuckDB为嵌入式引擎，file_path 为空时使用内存数据库
+    if connection.db_type == "duckdb" {
+        let conn_str = duckdb_connection_string(connection.file_path.as_deref());
+        log::info!("[build_connection_string] DuckDB连接 - 连接字符串: {}", conn_str);
+        return Ok(conn_str);
     }
     
     if let Some(ref file_path) = connection.file_path {
@@ -613,6 +1025,21 @@ fn build_connection_string(connection: &DbConnection) -> Result<String, (StatusC
         }
     }
     
+    // Redis的库编号可以省略
+    if connection.db_type == "redis" {
+        if let Some(ref host) = connection.host {
+            let conn_str = redis_connection_string(
+                host,
+                connection.port,
+                connection.username.as_deref(),
+                connection.password.as_deref(),
+                connection.database_name.as_deref(),
+            );
+            log::info!("[build_connection_string] Redis连接 - host: {}, port: {:?}", host, connection.port);
+            return Ok(conn_str);
+        }
+    }
+    
     if let (Some(ref host), Some(port), Some(ref db_name)) = 
         (&connection.host, connection.port, &connection.database_name) 
     {
@@ -620,15 +1047,21 @@ fn build_connection_string(connection: &DbConnection) -> Result<String, (StatusC
             "mysql" => {
                 let user = connection.username.as_deref().unwrap_or("root");
                 let pass = connection.password.as_deref().unwrap_or("");
-                let conn_str = format!("mysql://{}:{}@{}:{}/{}", user, pass, host, port, db_name);
-                log::info!("[build_connection_string] MySQL连接字符串: mysql://{}:***@{}:{}/{}", user, host, port, db_name);
+                let conn_str = with_ssl_params(
+                    format!("mysql://{}:{}@{}:{}/{}", user, pass, host, port, db_name),
+                    connection,
+                )?;
+                log::info!("[build_connection_string] MySQL连接字符串: mysql://{}:***@{}:{}/{}, ssl_mode={:?}", user, host, port, db_name, connection.ssl_mode);
                 return Ok(conn_str);
             }
             "postgresql" => {
                 let user = connection.username.as_deref().unwrap_or("postgres");
                 let pass = connection.password.as_deref().unwrap_or("");
-                let conn_str = format!("postgresql://{}:{}@{}:{}/{}", user, pass, host, port, db_name);
-                log::info!("[build_connection_string] PostgreSQL连接字符串: postgresql://{}:***@{}:{}/{}", user, host, port, db_name);
+                let conn_str = with_default_schema(with_ssl_params(
+                    format!("postgresql://{}:{}@{}:{}/{}", user, pass, host, port, db_name),
+                    connection,
+                )?, connection)?;
+                log::info!("[build_connection_string] PostgreSQL连接字符串: postgresql://{}:***@{}:{}/{}, ssl_mode={:?}, default_schema={:?}", user, host, port, db_name, connection.ssl_mode, connection.default_schema);
                 return Ok(conn_str);
             }
             "mongodb" => {
@@ -650,81 +1083,108 @@ fn build_connection_string(connection: &DbConnection) -> Result<String, (StatusC
     }
     
     log::error!("[build_connection_string] 连接配置不完整 - connection: {:?}", connection);
-    Err((
-        StatusCode::BAD_REQUEST,
-        Json(ModelErrorResponse {
-            error: "invalid_connection".to_string(),
-            message: "连接配置不完整".to_string(),
-            details: None,
-        })
-    ))
+    Err(ApiError::bad_request("invalid_connection", "连接配置不完整"))
+}
+
+// 辅助函数：获取连接配置（未指定连接ID时使用第一个活动连接）并创建数据库管理器
+pub(crate) async fn connect_database(
+    storage: &LocalStorageManager,
+    connection_id: Option<i64>,
+) -> Result<(DbConnection, DatabaseManager), ApiError> {
+    connect_database_to(storage, connection_id, None).await
+}
+
+// 辅助函数：同 connect_database，指定 database 时切换到同一服务器上的该数据库
+pub(crate) async fn connect_database_to(
+    storage: &LocalStorageManager,
+    connection_id: Option<i64>,
+    database: Option<&str>,
+) -> Result<(DbConnection, DatabaseManager), ApiError> {
+    let (connection, conn_str) = load_connection_to(storage, connection_id, database).await?;
+    let database = database.map(str::trim).filter(|d| !d.is_empty());
+    let db_manager = crate::db::pool_cache::get_or_connect_database(connection.id, database, &conn_str, &connection.pool_options, &connection.options.init_sql).await
+        .map_err(|e| ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)))?;
+    
+    Ok((connection, db_manager))
+}
+
+// 辅助函数：获取连接配置（切换到 database）并构建连接字符串，不建立连接
+pub(crate) async fn load_connection_to(
+    storage: &LocalStorageManager,
+    connection_id: Option<i64>,
+    database: Option<&str>,
+) -> Result<(DbConnection, String), ApiError> {
+    let mut connection = if let Some(conn_id) = connection_id {
+        storage.get_connection_by_id(conn_id).await
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("connection_not_found", format!("连接ID {}不存在", conn_id)))?
+    } else {
+        let active_conns = storage.get_active_connections().await
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?;
+        
+        active_conns.into_iter().next().ok_or_else(|| ApiError::bad_request("no_connection", "请先激活一个数据库连接"))?
+    };
+    
+    let database = database.map(str::trim).filter(|d| !d.is_empty());
+    if let Some(database) = database {
+        switch_database(&mut connection, database)?;
+    }
+    
+    let conn_str = build_connection_string(&connection)?;
+    Ok((connection, conn_str))
 }
 
 // 获取表结构处理函数
+#[utoipa::path(
+    post,
+    path = "/api/database/table/structure",
+    tag = "database",
+    request_body = TableRequest,
+    responses(
+        (status = 200, description = "表结构（列、索引、外键）", body = ApiTableSchema),
+        (status = 404, description = "表或连接不存在", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn get_table_structure(
     Extension(storage): Extension<LocalStorageManager>,
     Json(payload): Json<TableRequest>
-) -> Result<Json<ApiTableSchema>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<ApiTableSchema>, ApiError> {
     info!("[API] POST /api/database/table/structure - 请求: table_name={}", payload.table_name);
     if let Ok(req_json) = serde_json::to_string(&payload) {
-        log::info!("[API] POST /api/database/table/structure - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/database/table/structure - 请求体: {}", req_json);
     }
     
     // 获取连接：优先使用指定的连接ID，否则使用第一个活动连接
     let connection = if let Some(conn_id) = payload.connection_id {
         storage.get_connection_by_id(conn_id).await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?
-            .ok_or_else(|| (
-                StatusCode::NOT_FOUND,
-                Json(ModelErrorResponse {
-                    error: "connection_not_found".to_string(),
-                    message: "连接不存在".to_string(),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("connection_not_found", "连接不存在"))?
     } else {
         // 获取第一个活动连接
         let connections = storage.get_active_connections().await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?;
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?;
         
-        connections.into_iter().next().ok_or_else(|| (
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "no_connection".to_string(),
-                message: "请先激活一个数据库连接".to_string(),
-                details: None,
-            })
-        ))?
+        connections.into_iter().next().ok_or_else(|| ApiError::bad_request("no_connection", "请先激活一个数据库连接"))?
     };
     
+    // 优先使用缓存的表结构；AI接口缓存的结构不含统计信息，此时重新读取
+    if let Some(conn_id) = connection.id.filter(|_| !payload.exact_count) {
+        if let Some(mut cached) = get_schema_cache().structure(conn_id, &payload.table_name) {
+            if cached.row_count_estimated.is_some() {
+                apply_local_comments(&storage, &connection, &mut cached).await;
+                info!("[API] POST /api/database/table/structure - 命中缓存: 表={}", payload.table_name);
+                return Ok(Json(cached));
+            }
+        }
+    }
+    
     // 构建连接字符串
     #[allow(clippy::needless_borrow)]
     let conn_str = build_connection_string(&connection)?;
     
     // 创建数据库管理器
-    let db_manager = DatabaseManager::from_connection_string(&conn_str).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "connection_failed".to_string(),
-                message: format!("数据库连接失败: {}", e),
-                details: None,
-            })
-        ))?;
+    let db_manager = crate::db::pool_cache::get_or_connect(connection.id, &conn_str, &connection.pool_options, &connection.options.init_sql).await
+        .map_err(|e| ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)))?;
     
     // 获取表结构
     let table_name = &payload.table_name;
@@ -741,14 +1201,7 @@ async fn get_table_structure(
             .bind(table_name)
             .fetch_all(pool)
             .await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "query_failed".to_string(),
-                    message: format!("查询表结构失败: {}", e),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::internal("query_failed", format!("查询表结构失败: {}", e)))?
             .into_iter()
             .map(|(name, data_type, is_nullable, key, default_value, comment)| {
                 TableColumn {
@@ -767,25 +1220,19 @@ async fn get_table_structure(
             .collect::<Vec<_>>()
         }
         crate::db::DatabasePool::PostgreSQL(pool) => {
-            sqlx::query_as::<_, (String, String, String)>(
-                "SELECT column_name, data_type, is_nullable 
+            sqlx::query_as::<_, (String, String, String, Option<String>)>(
+                "SELECT column_name::text, data_type::text, is_nullable::text,
+                        col_description(format('%I.%I', table_schema, table_name)::regclass, ordinal_position::int)
                  FROM information_schema.columns 
-                 WHERE table_name = $1 
+                 WHERE table_name = $1 AND table_schema = ANY(current_schemas(false))
                  ORDER BY ordinal_position"
             )
             .bind(table_name)
             .fetch_all(pool)
             .await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "query_failed".to_string(),
-                    message: format!("查询表结构失败: {}", e),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::internal("query_failed", format!("查询表结构失败: {}", e)))?
             .into_iter()
-            .map(|(name, data_type, is_nullable)| {
+            .map(|(name, data_type, is_nullable, comment)| {
                 TableColumn {
                     name,
                     data_type: Some(data_type.clone()),
@@ -795,8 +1242,8 @@ async fn get_table_structure(
                     is_primary_key: Some(false),
                     default_: None,
                     default_value: None,
-                    comment: None,
-                    description: None,
+                    comment: comment.clone(),
+                    description: comment,
                 }
             })
             .collect::<Vec<_>>()
@@ -807,14 +1254,7 @@ async fn get_table_structure(
             )
             .fetch_all(pool)
             .await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "query_failed".to_string(),
-                    message: format!("查询表结构失败: {}", e),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::internal("query_failed", format!("查询表结构失败: {}", e)))?
             .into_iter()
             .map(|(_cid, name, type_, notnull, dflt_value, pk)| {
                 TableColumn {
@@ -833,9 +1273,29 @@ async fn get_table_structure(
             .collect::<Vec<_>>()
         }
         crate::db::DatabasePool::MongoDB(_, _) => {
-            // MongoDB没有固定的表结构，返回空列表
-            // 实际应用中可以从集合中采样文档来推断结构
-            Vec::new()
+            // MongoDB没有固定的表结构，通过采样文档推断
+            mongo_sampled_columns(&db_manager, table_name).await
+        }
+        crate::db::DatabasePool::Redis(_) => redis_key_columns(),
+        crate::db::DatabasePool::DuckDB(db) => {
+            db.table_columns(table_name).await
+                .map_err(|e| ApiError::internal("query_failed", format!("查询表结构失败: {}", e)))?
+                .into_iter()
+                .map(|(name, data_type, nullable, default_value)| {
+                    TableColumn {
+                        name,
+                        data_type: Some(data_type.clone()),
+                        type_: Some(data_type),
+                        nullable: Some(nullable),
+                        is_nullable: Some(nullable),
+                        is_primary_key: Some(false),
+                        default_: default_value.clone(),
+                        default_value,
+                        comment: None,
+                        description: None,
+                    }
+                })
+                .collect::<Vec<_>>()
         }
     };
     
@@ -862,104 +1322,98 @@ async fn get_table_structure(
     };
     
     // 获取外键信息
+    // 查询成功时始终返回列表（空列表表示没有外键），失败时为null
     let foreign_keys = match db_manager.get_foreign_keys(table_name).await {
-        Ok(fk_list) => {
-            if fk_list.is_empty() {
-                None
-            } else {
-                Some(fk_list)
-            }
-        },
+        Ok(fk_list) => Some(fk_list),
         Err(e) => {
             log::warn!("获取外键信息失败: {}", e);
             None
         }
     };
     
+    // 获取行数和占用空间
+    let stats = match db_manager.get_table_stats(table_name, payload.exact_count).await {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            log::warn!("获取表统计信息失败: {}", e);
+            None
+        }
+    };
+    
+    // 表注释（MySQL / PostgreSQL）
+    let description = match db_manager.get_table_comment(table_name).await {
+        Ok(comment) => comment,
+        Err(e) => {
+            log::warn!("获取表注释失败: {}", e);
+            None
+        }
+    };
+    
     let fk_count = foreign_keys.as_ref().map(|fk| fk.len()).unwrap_or(0);
-    let response = ApiTableSchema {
+    let mut response = ApiTableSchema {
         name: table_name.clone(),
         columns: columns.clone(),
         indexes: indexes.clone(),
         foreign_keys,
-        description: None,
+        description,
         created_at: None,
         updated_at: None,
-        row_count: None,
-        size: None,
+        row_count: stats.as_ref().and_then(|s| s.row_count),
+        size: stats.as_ref().and_then(|s| s.size_bytes),
+        row_count_estimated: stats.as_ref().map(|s| s.row_count_estimated),
     };
     info!("[API] POST /api/database/table/structure - 响应: 表={}, 字段数={}, 索引数={}, 外键数={}", 
         table_name, columns.len(), 
         indexes.as_ref().map(|i| i.len()).unwrap_or(0),
         fk_count);
     if let Ok(resp_json) = serde_json::to_string(&response) {
-        log::info!("[API] POST /api/database/table/structure - 响应体: {}", resp_json);
+        log::debug!("[API] POST /api/database/table/structure - 响应体: {}", resp_json);
+    }
+    apply_local_comments(&storage, &connection, &mut response).await;
+    if let Some(conn_id) = connection.id {
+        get_schema_cache().put_structure(conn_id, table_name, response.clone());
     }
     Ok(Json(response))
 }
 
-// SQL生成处理函数
-async fn generate_sql(
-    Extension(storage): Extension<LocalStorageManager>,
-    Extension(ai_service): Extension<Option<AiService>>,
-    Json(req): Json<SqlGenerateRequest>,
-) -> Result<Json<SqlGenerateResponse>, (StatusCode, Json<ModelErrorResponse>)> {
-    log::info!("收到SQL生成请求 - 自然语言长度: {} 字符", req.natural_language.len());
-    if let Ok(req_json) = serde_json::to_string(&req) {
-        log::info!("[API] POST /api/ai/sql/generate - 请求体: {}", req_json);
-    }
-    
-    // 安全检查：验证输入长度
-    if req.natural_language.len() > 2000 {
-        log::warn!("自然语言描述过长: {} 字符", req.natural_language.len());
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "input_too_long".to_string(),
-                message: "自然语言描述过长，请简化您的描述".to_string(),
-                details: None,
-            })
-        ));
+// SQLite 没有原生注释，将本地存储中的表/列注释填入表结构
+pub(crate) async fn apply_local_comments(storage: &LocalStorageManager, connection: &DbConnection, schema: &mut ApiTableSchema) {
+    let Some(conn_id) = connection.id.filter(|_| connection.db_type == "sqlite") else {
+        return;
+    };
+    let comments = match storage.list_object_comments(conn_id, &schema.name).await {
+        Ok(comments) => comments,
+        Err(e) => {
+            log::warn!("读取表 {} 的本地注释失败: {}", schema.name, e);
+            return;
+        }
+    };
+    for item in comments {
+        if item.column_name.is_empty() {
+            schema.description = Some(item.comment);
+        } else if let Some(column) = schema.columns.iter_mut().find(|c| c.name == item.column_name) {
+            column.comment = Some(item.comment.clone());
+            column.description = Some(item.comment);
+        }
     }
-    
-    // 检查AI服务是否可用
-    let ai_service = ai_service.as_ref()
-        .ok_or_else(|| {
-            log::error!("AI服务不可用");
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ModelErrorResponse {
-                    error: "ai_service_unavailable".to_string(),
-                    message: "AI服务不可用，请检查API密钥配置".to_string(),
-                    details: None,
-                })
-            )
-        })?;
-    
+}
+
+// 读取活动连接的表结构，构建SQL生成提示词中的schema：(连接, schema文本, 数据库类型)
+pub(crate) async fn build_generation_context(
+    storage: &LocalStorageManager,
+    ai_service: &AiService,
+    req: &SqlGenerateRequest,
+) -> Result<(DbConnection, DatabaseManager, String, String), ApiError> {
     // 获取当前活动连接（使用第一个）
     let connections = storage.get_active_connections().await
         .map_err(|e| {
             log::error!("获取活动连接失败: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            )
+            ApiError::db("database_error", format!("获取连接失败: {}", e))
         })?;
     
     let connection = connections.first().ok_or_else(|| {
             log::warn!("没有活动的数据库连接");
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "no_connection".to_string(),
-                    message: "请先激活一个数据库连接".to_string(),
-                    details: None,
-                })
-            )
+            ApiError::bad_request("no_connection", "请先激活一个数据库连接")
         })?;
     
     log::info!("使用连接: {} (类型: {})", connection.name, connection.db_type);
@@ -969,33 +1423,19 @@ async fn generate_sql(
     let conn_str = build_connection_string(&connection)?;
     
     // 创建数据库管理器并获取所有表的schema
-    let db_manager = DatabaseManager::from_connection_string(&conn_str).await
+    let db_manager = crate::db::pool_cache::get_or_connect(connection.id, &conn_str, &connection.pool_options, &connection.options.init_sql).await
         .map_err(|e| {
             log::error!("数据库连接失败: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "connection_failed".to_string(),
-                    message: format!("数据库连接失败: {}", e),
-                    details: None,
-                })
-            )
+            ApiError::internal("connection_failed", format!("数据库连接失败: {}", e))
         })?;
     
     log::info!("开始获取数据库Schema");
     
     // 获取所有表名
-    let tables = db_manager.get_schema().await
+    let tables = cached_table_names(connection.id, &db_manager).await
         .map_err(|e| {
             log::error!("获取表列表失败: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "schema_error".to_string(),
-                    message: format!("获取数据库表列表失败: {}", e),
-                    details: None,
-                })
-            )
+            ApiError::internal("schema_error", format!("获取数据库表列表失败: {}", e))
         })?;
     
     log::info!("找到 {} 个表", tables.len());
@@ -1005,24 +1445,85 @@ async fn generate_sql(
     // 优先使用请求中的database_type，否则使用连接的数据库类型
     let effective_db_type = req.database_type.as_deref().unwrap_or(&connection.db_type);
     schema_builder.push_str(&format!("数据库类型: {}\n", effective_db_type));
-    schema_builder.push_str(&format!("数据库名称: {}\n\n", connection.database_name.as_deref().unwrap_or("default")));
+    schema_builder.push_str(&format!("数据库名称: {}\n", connection.database_name.as_deref().unwrap_or("default")));
+    if let Some(schema) = connection.default_schema.as_deref().filter(|s| !s.trim().is_empty()) {
+        schema_builder.push_str(&format!("默认Schema: {}（以下表均位于该schema，SQL中可直接使用表名）\n", schema.trim()));
+    }
+    schema_builder.push('\n');
     schema_builder.push_str("表结构:\n");
     
-    // 获取每个表的详细结构（限制前20个表，避免schema过大）
-    for (idx, table_name) in tables.iter().take(20).enumerate() {
-        log::debug!("获取表 {} 的结构", table_name);
+    // 连接的数据字典（业务说明、同义词、枚举含义），读取失败时不影响生成
+    let dictionary = match connection.id {
+        Some(id) => storage.list_dictionary_entries(id, None).await.unwrap_or_else(|e| {
+            log::warn!("读取数据字典失败，忽略业务术语: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+    
+    // 表较多时只写入与问题相关的表，避免截断掉问题涉及的表；没有匹配时退回前 GENERATE_MAX_TABLES 个表
+    let prompt_tables: Vec<String> = if tables.len() <= GENERATE_MAX_TABLES {
+        tables.clone()
+    } else {
+        let catalog = db_manager.get_column_catalog().await.unwrap_or_else(|e| {
+            log::warn!("获取列目录失败，仅按表名匹配: {}", e);
+            Vec::new()
+        });
+        let profiles = table_relevance::build_profiles(&tables, catalog);
+        let keyword = table_relevance::select_relevant_tables(&req.natural_language, &profiles, table_relevance::DEFAULT_TOP_K);
         
-        match get_table_structure_internal(&db_manager, table_name).await {
-            Ok(schema) => {
-                schema_builder.push_str(&format!("\n{}. 表名: {}\n", idx + 1, table_name));
-                schema_builder.push_str("   字段:\n");
-                
-                for col in &schema.columns {
-                    schema_builder.push_str(&format!(
-                        "     - {} ({}){}{}",
-                        col.name,
-                        col.data_type.as_deref().unwrap_or("UNKNOWN"),
-                        if col.is_primary_key.unwrap_or(false) { " [主键]" } else { "" },
+        // 连接已建立表结构向量时合并语义检索结果
+        let semantic = match connection.id {
+            Some(id) => match embeddings::search_schema(ai_service, storage, id, &req.natural_language, embeddings::DEFAULT_SEARCH_LIMIT).await {
+                Ok(hits) => embeddings::hit_tables(&hits),
+                Err(e) => {
+                    log::warn!("表结构语义检索失败，仅使用关键词匹配: {}", e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        // 问题中出现数据字典同义词的表优先
+        let glossary = data_dictionary::matched_tables(&req.natural_language, &dictionary, &tables);
+        let ranked = table_relevance::merge_ranked(&keyword, &semantic, table_relevance::DEFAULT_TOP_K);
+        let mut relevant = glossary;
+        relevant.truncate(table_relevance::DEFAULT_TOP_K);
+        for table in ranked {
+            if relevant.len() < table_relevance::DEFAULT_TOP_K && !relevant.contains(&table) {
+                relevant.push(table);
+            }
+        }
+        log::info!("按问题选出相关表 {} 个: {:?}", relevant.len(), relevant);
+        if relevant.is_empty() {
+            tables.iter().take(GENERATE_MAX_TABLES).cloned().collect()
+        } else {
+            relevant
+        }
+    };
+    
+    // 已写入提示词的示例值字符数
+    let mut sample_chars = 0;
+    
+    // 获取每个表的详细结构
+    for (idx, table_name) in prompt_tables.iter().enumerate() {
+        log::debug!("获取表 {} 的结构", table_name);
+        
+        match cached_table_structure(connection.id, &db_manager, table_name).await {
+            Ok(mut schema) => {
+                apply_local_comments(storage, connection, &mut schema).await;
+                schema_builder.push_str(&format!("\n{}. 表名: {}", idx + 1, table_name));
+                if let Some(description) = schema.description.as_deref().filter(|d| !d.is_empty()) {
+                    schema_builder.push_str(&format!(" // {}", description));
+                }
+                schema_builder.push('\n');
+                schema_builder.push_str("   字段:\n");
+                
+                for col in &schema.columns {
+                    schema_builder.push_str(&format!(
+                        "     - {} ({}){}{}",
+                        col.name,
+                        col.data_type.as_deref().unwrap_or("UNKNOWN"),
+                        if col.is_primary_key.unwrap_or(false) { " [主键]" } else { "" },
                         if !col.is_nullable.unwrap_or(true) { " [NOT NULL]" } else { "" }
                     ));
                     if let Some(comment) = &col.comment {
@@ -1032,6 +1533,22 @@ async fn generate_sql(
                     }
                     schema_builder.push('\n');
                 }
+                schema_builder.push_str(&data_dictionary::format_table_dictionary(table_name, &dictionary));
+                
+                if req.include_sample_values && sample_chars < sample_values::MAX_SAMPLE_CHARS {
+                    let samples = sample_table_values(&db_manager, table_name, &schema.columns).await;
+                    if !samples.is_empty() {
+                        schema_builder.push_str("   示例值（低基数列）:\n");
+                        for (column, values) in samples {
+                            let line = sample_values::format_sample_line(&column, &values);
+                            sample_chars += line.len();
+                            if sample_chars > sample_values::MAX_SAMPLE_CHARS {
+                                break;
+                            }
+                            schema_builder.push_str(&line);
+                        }
+                    }
+                }
                 
                 if let Some(indexes) = &schema.indexes {
                     if !indexes.is_empty() {
@@ -1054,12 +1571,154 @@ async fn generate_sql(
         }
     }
     
-    if tables.len() > 20 {
-        schema_builder.push_str(&format!("\n... 还有 {} 个表未显示\n", tables.len() - 20));
+    if tables.len() > prompt_tables.len() {
+        schema_builder.push_str(&format!("\n... 还有 {} 个表未显示\n", tables.len() - prompt_tables.len()));
+    }
+    
+    Ok((connection.clone(), db_manager, schema_builder, effective_db_type.to_string()))
+}
+
+// 读取表的采样行，返回低基数文本列的取值（读取失败时返回空列表）
+async fn sample_table_values(db_manager: &DatabaseManager, table_name: &str, columns: &[TableColumn]) -> Vec<(String, Vec<String>)> {
+    let sampled: Vec<String> = columns.iter()
+        .filter(|c| !c.is_primary_key.unwrap_or(false))
+        .filter(|c| c.data_type.as_deref().is_some_and(sample_values::is_sampled_type))
+        .map(|c| c.name.clone())
+        .take(sample_values::MAX_SAMPLE_COLUMNS)
+        .collect();
+    if sampled.is_empty() {
+        return Vec::new();
+    }
+    let sql = match sample_values::sample_sql(db_manager.db_type, table_name, &sampled) {
+        Ok(sql) => sql,
+        Err(e) => {
+            log::warn!("生成表 {} 的采样SQL失败: {}", table_name, e);
+            return Vec::new();
+        }
+    };
+    let rows = match &db_manager.pool {
+        crate::db::DatabasePool::MySQL(pool) => sqlx::query(&sql).fetch_all(pool).await.map(|rows| rows.iter().map(|r| r.json_values()).collect::<Vec<_>>()),
+        crate::db::DatabasePool::PostgreSQL(pool) => sqlx::query(&sql).fetch_all(pool).await.map(|rows| rows.iter().map(|r| r.json_values()).collect::<Vec<_>>()),
+        crate::db::DatabasePool::SQLite(pool) => sqlx::query(&sql).fetch_all(pool).await.map(|rows| rows.iter().map(|r| r.json_values()).collect::<Vec<_>>()),
+        _ => return Vec::new(),
+    };
+    match rows {
+        Ok(rows) => sample_values::low_cardinality_values(&sampled, &rows),
+        Err(e) => {
+            log::warn!("读取表 {} 的采样数据失败: {}", table_name, e);
+            Vec::new()
+        }
+    }
+}
+
+// 校验AI生成的SQL：按连接方言解析，对照表结构缓存检查引用的表和列，explain 为true时在连接上执行 EXPLAIN
+// MongoDB / Redis 不校验，获取表列表失败时跳过校验
+pub(crate) async fn validate_generated_sql(
+    connection: &DbConnection,
+    db_manager: &DatabaseManager,
+    sql: &str,
+    explain: bool,
+) -> Option<SqlValidation> {
+    let db_type = db_manager.db_type;
+    if matches!(db_type, crate::db::DatabaseType::MongoDB | crate::db::DatabaseType::Redis) {
+        return None;
+    }
+    let references = match sql_validation::collect_references(db_type, sql) {
+        Ok(references) => references,
+        Err(e) => return Some(SqlValidation::from_issues(vec![sql_validation::syntax_issue(e)])),
+    };
+    let known_tables = match cached_table_names(connection.id, db_manager).await {
+        Ok(tables) => tables,
+        Err(e) => {
+            log::warn!("校验生成的SQL时获取表列表失败: {}", e);
+            return None;
+        }
+    };
+    
+    let mut table_columns = HashMap::new();
+    for table in &references.tables {
+        let Some(actual) = known_tables.iter().find(|t| t.eq_ignore_ascii_case(table)) else { continue };
+        match cached_table_structure(connection.id, db_manager, actual).await {
+            Ok(schema) => {
+                table_columns.insert(table.to_lowercase(), schema.columns.into_iter().map(|c| c.name).collect::<Vec<_>>());
+            }
+            Err(e) => log::warn!("校验生成的SQL时获取表 {} 结构失败: {}", actual, e),
+        }
+    }
+    let mut validation = SqlValidation::from_issues(references.check(&known_tables, &table_columns));
+    
+    if explain && validation.valid {
+        match explain_statement(db_manager, sql).await {
+            Ok(()) => validation.explained = true,
+            Err(e) => validation.push(sql_validation::explain_issue(e)),
+        }
+    }
+    if !validation.valid {
+        log::warn!("生成的SQL校验未通过: {:?}", validation.issues);
+    }
+    Some(validation)
+}
+
+// 在连接上执行 EXPLAIN 检查单条语句能否通过数据库的语义检查（不执行语句本身）
+async fn explain_statement(db_manager: &DatabaseManager, sql: &str) -> Result<(), String> {
+    use sqlparser::ast::Statement;
+    
+    let statement = parse_sql(db_manager.db_type, sql)?;
+    if !matches!(statement, Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. }) {
+        return Err("只支持对 SELECT / INSERT / UPDATE / DELETE 执行 EXPLAIN".to_string());
+    }
+    let result = match &db_manager.pool {
+        crate::db::DatabasePool::MySQL(pool) => sqlx::query(&format!("EXPLAIN {}", sql)).fetch_all(pool).await.map(|_| ()),
+        crate::db::DatabasePool::PostgreSQL(pool) => sqlx::query(&format!("EXPLAIN {}", sql)).fetch_all(pool).await.map(|_| ()),
+        crate::db::DatabasePool::SQLite(pool) => sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql)).fetch_all(pool).await.map(|_| ()),
+        _ => return Err(format!("{:?} 不支持 EXPLAIN", db_manager.db_type)),
+    };
+    result.map_err(|e| format!("EXPLAIN 执行失败: {}", e))
+}
+
+// 表数量不超过该值时将全部表结构写入SQL生成提示词
+const GENERATE_MAX_TABLES: usize = 20;
+
+// SQL生成处理函数
+#[utoipa::path(
+    post,
+    path = "/api/ai/sql/generate",
+    tag = "ai",
+    request_body = SqlGenerateRequest,
+    responses(
+        (status = 200, description = "生成的SQL", body = SqlGenerateResponse),
+        (status = 503, description = "AI服务未配置", body = crate::api::error::ErrorInfo),
+    )
+)]
+async fn generate_sql(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Json(req): Json<SqlGenerateRequest>,
+) -> Result<Json<SqlGenerateResponse>, ApiError> {
+    log::info!("收到SQL生成请求 - 自然语言长度: {} 字符", req.natural_language.len());
+    if let Ok(req_json) = serde_json::to_string(&req) {
+        log::debug!("[API] POST /api/ai/sql/generate - 请求体: {}", req_json);
+    }
+    
+    // 安全检查：验证输入长度
+    if req.natural_language.len() > 2000 {
+        log::warn!("自然语言描述过长: {} 字符", req.natural_language.len());
+        return Err(ApiError::bad_request("input_too_long", "自然语言描述过长，请简化您的描述"));
     }
     
-    let database_schema = schema_builder;
-    let database_type = effective_db_type;
+    // 检查AI服务是否可用
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| {
+            log::error!("AI服务不可用");
+            ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置")
+        })?;
+    
+    let (connection, db_manager, database_schema, database_type) = build_generation_context(&storage, ai_service, &req).await?;
+    let database_type = database_type.as_str();
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, ai_service, connection.id, TemplateType::Generation, req.template_id.as_deref(),
+    ).await?;
     
     log::info!("Schema构建完成，长度: {} 字符", database_schema.len());
     log::info!("调用AI服务生成SQL");
@@ -1074,44 +1733,91 @@ async fn generate_sql(
             log::info!("AI生成SQL成功，长度: {} 字符", sql.len());
             log::debug!("生成的SQL: {}", sql);
             
-            // 对生成的SQL进行安全检查
-            if let Err(reason) = crate::utils::security::SqlInjectionProtection::detect_injection(&sql) {
+            // 按语句类别和连接配置检查生成的SQL
+            if let Err(reason) = crate::utils::security::StatementPolicy::for_connection(&connection).check(&sql) {
                 log::error!("生成的SQL包含注入风险: {}", reason);
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ModelErrorResponse {
-                        error: "generated_sql_invalid".to_string(),
-                        message: "生成的SQL存在安全风险，请重新尝试".to_string(),
-                        details: Some(reason),
-                    })
-                ));
+                return Err(ApiError::internal("generated_sql_invalid", "生成的SQL存在安全风险，请重新尝试").with_details(reason));
             }
             
+            // 检查生成的SQL引用的表和列是否存在，供前端在执行前标记
+            let validation = validate_generated_sql(&connection, &db_manager, &sql, req.explain).await;
+            
             let response = SqlGenerateResponse {
                 sql: sql.clone(),
                 explanation: Some(format!("根据 {} 数据库的表结构生成", database_type)),
+                validation,
             };
             if let Ok(resp_json) = serde_json::to_string(&response) {
-                log::info!("[API] POST /api/ai/sql/generate - 响应体: {}", resp_json);
+                log::debug!("[API] POST /api/ai/sql/generate - 响应体: {}", resp_json);
             }
             Ok(Json(response))
         },
         Err(e) => {
             log::error!("AI生成SQL失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "ai_error".to_string(),
-                    message: format!("SQL生成失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::ai("ai_error", format!("SQL生成失败: {}", e)))
         }
     }
 }
 
+// MongoDB结构推断的采样文档数
+const MONGO_SCHEMA_SAMPLE_SIZE: i64 = 100;
+
+// 采样MongoDB集合文档，将推断出的字段转换为列信息（采样失败时返回空列表）
+async fn mongo_sampled_columns(db_manager: &DatabaseManager, collection_name: &str) -> Vec<TableColumn> {
+    let fields = match db_manager.infer_mongo_fields(collection_name, MONGO_SCHEMA_SAMPLE_SIZE).await {
+        Ok(fields) => fields,
+        Err(e) => {
+            log::warn!("[MongoDB] 采样推断集合 {} 结构失败: {}", collection_name, e);
+            return Vec::new();
+        }
+    };
+    
+    fields.into_iter()
+        .map(|field| {
+            let data_type = field.bson_types.join(" | ");
+            // 并非所有文档都包含该字段，或出现过null值时视为可空
+            let nullable = field.occurrences < field.sample_size || field.bson_types.iter().any(|t| t == "Null");
+            let frequency = format!(
+                "出现频率 {:.0}% ({}/{})",
+                field.frequency() * 100.0, field.occurrences, field.sample_size
+            );
+            TableColumn {
+                is_primary_key: Some(field.path == "_id"),
+                name: field.path,
+                data_type: Some(data_type.clone()),
+                type_: Some(data_type),
+                nullable: Some(nullable),
+                is_nullable: Some(nullable),
+                default_: None,
+                default_value: None,
+                comment: Some(frequency.clone()),
+                description: Some(frequency),
+            }
+        })
+        .collect()
+}
+
+// Redis键模式的通用属性列（key / type / ttl）
+fn redis_key_columns() -> Vec<TableColumn> {
+    [("key", "string", true), ("type", "string", false), ("ttl", "integer", false)]
+        .into_iter()
+        .map(|(name, data_type, is_key)| TableColumn {
+            name: name.to_string(),
+            data_type: Some(data_type.to_string()),
+            type_: Some(data_type.to_string()),
+            nullable: Some(false),
+            is_nullable: Some(false),
+            is_primary_key: Some(is_key),
+            default_: None,
+            default_value: None,
+            comment: None,
+            description: None,
+        })
+        .collect()
+}
+
 // 内部辅助函数：获取表结构
-async fn get_table_structure_internal(
+pub(crate) async fn get_table_structure_internal(
     db_manager: &DatabaseManager,
     table_name: &str,
 ) -> Result<ApiTableSchema, String> {
@@ -1187,14 +1893,9 @@ async fn get_table_structure_internal(
             }).collect();
             
             // 获取外键信息
+            // 查询成功时始终返回列表（空列表表示没有外键），失败时为null
             let foreign_keys = match db_manager.get_foreign_keys(table_name).await {
-                Ok(fk_list) => {
-                    if fk_list.is_empty() {
-                        None
-                    } else {
-                        Some(fk_list)
-                    }
-                },
+                Ok(fk_list) => Some(fk_list),
                 Err(e) => {
                     log::warn!("获取外键信息失败: {}", e);
                     None
@@ -1206,19 +1907,21 @@ async fn get_table_structure_internal(
                 columns,
                 indexes: Some(indexes),
                 foreign_keys,
-                description: None,
+                description: db_manager.get_table_comment(table_name).await.unwrap_or_default(),
                 created_at: None,
                 updated_at: None,
                 row_count: None,
                 size: None,
+                row_count_estimated: None,
             })
         },
         crate::db::DatabasePool::PostgreSQL(pool) => {
             // 获取PostgreSQL表结构
             let rows = sqlx::query(
-                "SELECT column_name, data_type, is_nullable, column_default, description
+                "SELECT column_name::text, data_type::text, is_nullable::text, column_default::text,
+                        col_description(format('%I.%I', table_schema, table_name)::regclass, ordinal_position::int) AS description
                  FROM information_schema.columns
-                 WHERE table_name = $1
+                 WHERE table_name = $1 AND table_schema = ANY(current_schemas(false))
                  ORDER BY ordinal_position"
             )
             .bind(table_name)
@@ -1249,14 +1952,9 @@ async fn get_table_structure_internal(
             }
             
             // 获取外键信息
+            // 查询成功时始终返回列表（空列表表示没有外键），失败时为null
             let foreign_keys = match db_manager.get_foreign_keys(table_name).await {
-                Ok(fk_list) => {
-                    if fk_list.is_empty() {
-                        None
-                    } else {
-                        Some(fk_list)
-                    }
-                },
+                Ok(fk_list) => Some(fk_list),
                 Err(e) => {
                     log::warn!("获取外键信息失败: {}", e);
                     None
@@ -1268,11 +1966,12 @@ async fn get_table_structure_internal(
                 columns,
                 indexes: None, // 简化处理，暂不获取PostgreSQL索引
                 foreign_keys,
-                description: None,
+                description: db_manager.get_table_comment(table_name).await.unwrap_or_default(),
                 created_at: None,
                 updated_at: None,
                 row_count: None,
                 size: None,
+                row_count_estimated: None,
             })
         },
         crate::db::DatabasePool::SQLite(pool) => {
@@ -1307,14 +2006,9 @@ async fn get_table_structure_internal(
             }
             
             // 获取外键信息
+            // 查询成功时始终返回列表（空列表表示没有外键），失败时为null
             let foreign_keys = match db_manager.get_foreign_keys(table_name).await {
-                Ok(fk_list) => {
-                    if fk_list.is_empty() {
-                        None
-                    } else {
-                        Some(fk_list)
-                    }
-                },
+                Ok(fk_list) => Some(fk_list),
                 Err(e) => {
                     log::warn!("获取外键信息失败: {}", e);
                     None
@@ -1331,14 +2025,14 @@ async fn get_table_structure_internal(
                 updated_at: None,
                 row_count: None,
                 size: None,
+                row_count_estimated: None,
             })
         },
         crate::db::DatabasePool::MongoDB(_, _) => {
-            // MongoDB没有固定的表结构，返回空的列列表
-            // 实际应用中可以从集合中采样文档来推断结构
+            // MongoDB没有固定的表结构，通过采样文档推断
             Ok(ApiTableSchema {
                 name: table_name.to_string(),
-                columns: Vec::new(),
+                columns: mongo_sampled_columns(db_manager, table_name).await,
                 indexes: None,
                 foreign_keys: None, // MongoDB不支持外键
                 description: None,
@@ -1346,55 +2040,103 @@ async fn get_table_structure_internal(
                 updated_at: None,
                 row_count: None,
                 size: None,
+                row_count_estimated: None,
+            })
+        },
+        crate::db::DatabasePool::Redis(_) => {
+            // Redis键模式没有结构，返回键的通用属性列
+            Ok(ApiTableSchema {
+                name: table_name.to_string(),
+                columns: redis_key_columns(),
+                indexes: None,
+                foreign_keys: None,
+                description: Some("Redis键模式".to_string()),
+                created_at: None,
+                updated_at: None,
+                row_count: None,
+                size: None,
+                row_count_estimated: None,
+            })
+        },
+        crate::db::DatabasePool::DuckDB(db) => {
+            let columns: Vec<TableColumn> = db.table_columns(table_name).await
+                .map_err(|e| format!("查询表结构失败: {}", e))?
+                .into_iter()
+                .map(|(name, data_type, nullable, default_value)| TableColumn {
+                    name,
+                    data_type: Some(data_type.clone()),
+                    type_: Some(data_type),
+                    nullable: Some(nullable),
+                    is_nullable: Some(nullable),
+                    is_primary_key: Some(false),
+                    default_: default_value.clone(),
+                    default_value,
+                    comment: None,
+                    description: None,
+                })
+                .collect();
+            if columns.is_empty() {
+                return Err(format!("表 {} 不存在", table_name));
+            }
+            // 挂载的数据文件以视图形式存在，描述中给出文件路径
+            let description = db.attached_files().iter()
+                .find(|f| f.table == table_name)
+                .map(|f| format!("数据文件: {}", f.path));
+            Ok(ApiTableSchema {
+                name: table_name.to_string(),
+                columns,
+                indexes: None,
+                foreign_keys: Some(Vec::new()),
+                description,
+                created_at: None,
+                updated_at: None,
+                row_count: None,
+                size: None,
+                row_count_estimated: None,
             })
         },
     }
 }
 
 // SQL解释处理函数
+#[utoipa::path(
+    post,
+    path = "/api/ai/sql/explain",
+    tag = "ai",
+    request_body = SqlExplainRequest,
+    responses(
+        (status = 200, description = "SQL解释", body = SqlExplainResponse),
+        (status = 503, description = "AI服务未配置", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn explain_sql(
+    Extension(storage): Extension<LocalStorageManager>,
     Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
     Json(req): Json<SqlExplainRequest>,
-) -> Result<Json<SqlExplainResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<SqlExplainResponse>, ApiError> {
     info!("[API] POST /api/ai/sql/explain - 请求: SQL长度={}", req.sql.len());
     debug!("[API] POST /api/ai/sql/explain - SQL内容: {}", req.sql);
     if let Ok(req_json) = serde_json::to_string(&req) {
-        log::info!("[API] POST /api/ai/sql/explain - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/ai/sql/explain - 请求体: {}", req_json);
     }
     // 安全检查：验证SQL长度
     if req.sql.len() > 10000 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "sql_too_long".to_string(),
-                message: "SQL语句过长，请提供更简洁的SQL".to_string(),
-                details: None,
-            })
-        ));
+        return Err(ApiError::bad_request("sql_too_long", "SQL语句过长，请提供更简洁的SQL"));
     }
     
     // 安全检查：检测潜在的注入风险
     if let Err(reason) = crate::utils::security::SqlInjectionProtection::detect_injection(&req.sql) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "sql_injection_risk".to_string(),
-                message: "检测到SQL注入风险".to_string(),
-                details: Some(reason),
-            })
-        ));
+        return Err(ApiError::bad_request("sql_injection_risk", "检测到SQL注入风险").with_details(reason));
     }
     
     // 检查AI服务是否可用
     let ai_service = ai_service.as_ref()
-        .ok_or_else(|| (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ModelErrorResponse {
-                error: "ai_service_unavailable".to_string(),
-                message: "AI服务不可用，请检查API密钥配置".to_string(),
-                details: None,
-            })
-        ))?;
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用，请检查API密钥配置"))?;
+    
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, ai_service, req.connection_id, TemplateType::Explain, req.template_id.as_deref(),
+    ).await?;
     
     // 记录请求（脱敏）
     info!("开始解释SQL，长度: {} 字符", req.sql.len());
@@ -1409,20 +2151,13 @@ async fn explain_sql(
                 execution_plan: None,
             };
             if let Ok(resp_json) = serde_json::to_string(&response) {
-                log::info!("[API] POST /api/ai/sql/explain - 响应体: {}", resp_json);
+                log::debug!("[API] POST /api/ai/sql/explain - 响应体: {}", resp_json);
             }
             Ok(Json(response))
         },
         Err(e) => {
             error!("SQL解释失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "ai_error".to_string(),
-                    message: format!("SQL解释失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::ai("ai_error", format!("SQL解释失败: {}", e)))
         }
     }
 }
@@ -1432,21 +2167,14 @@ async fn sql_to_natural_language(
     Extension(ai_service): Extension<Option<AiService>>,
     Extension(storage): Extension<LocalStorageManager>,
     Json(req): Json<SqlToNaturalLanguageRequest>,
-) -> Result<Json<SqlToNaturalLanguageResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<SqlToNaturalLanguageResponse>, ApiError> {
     info!("[API] POST /api/ai/sql/to-natural-language - 请求: SQL长度={}", req.sql.len());
     debug!("[API] POST /api/ai/sql/to-natural-language - SQL内容: {}", req.sql);
     
     let ai_service = match ai_service {
         Some(service) => service,
         None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "ai_service_unavailable".to_string(),
-                    message: "AI服务未配置，请先配置API密钥".to_string(),
-                    details: None,
-                })
-            ))
+            return Err(ApiError::bad_request("ai_service_unavailable", "AI服务未配置，请先配置API密钥"))
         }
     };
     
@@ -1454,10 +2182,25 @@ async fn sql_to_natural_language(
         Ok(natural_language) => {
             info!("[API] POST /api/ai/sql/to-natural-language - 响应成功: 描述长度={}", natural_language.len());
             debug!("[API] POST /api/ai/sql/to-natural-language - 自然语言描述: {}", natural_language);
+            
+            // 将描述保存为收藏说明
+            let mut saved_favorite_id = None;
+            if let Some(favorite_id) = req.favorite_id {
+                let result = match storage.get_sql_favorite(favorite_id).await {
+                    Ok(_) => storage.update_sql_favorite(favorite_id, &None, &None, &Some(natural_language.clone()), &None, &None).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(_) => saved_favorite_id = Some(favorite_id),
+                    Err(e) => warn!("[API] 保存SQL描述到收藏失败: id={}, 错误={}", favorite_id, e),
+                }
+            }
+            
             Ok(Json(SqlToNaturalLanguageResponse {
                 success: true,
                 natural_language: Some(natural_language),
                 error: None,
+                saved_favorite_id,
             }))
         },
         Err(e) => {
@@ -1466,6 +2209,7 @@ async fn sql_to_natural_language(
                 success: false,
                 natural_language: None,
                 error: Some(format!("SQL转自然语言失败: {}", e)),
+                saved_favorite_id: None,
             }))
         }
     }
@@ -1476,21 +2220,14 @@ async fn sql_completion(
     Extension(ai_service): Extension<Option<AiService>>,
     Extension(_storage): Extension<LocalStorageManager>,
     Json(req): Json<SqlCompletionRequest>,
-) -> Result<Json<SqlCompletionResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<SqlCompletionResponse>, ApiError> {
     info!("[API] POST /api/ai/sql/completion - 请求: 部分SQL长度={}", req.partial_sql.len());
     debug!("[API] POST /api/ai/sql/completion - 部分SQL内容: {}", req.partial_sql);
     
     let ai_service = match ai_service {
         Some(service) => service,
         None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "ai_service_unavailable".to_string(),
-                    message: "AI服务未配置，请先配置API密钥".to_string(),
-                    details: None,
-                })
-            ))
+            return Err(ApiError::bad_request("ai_service_unavailable", "AI服务未配置，请先配置API密钥"))
         }
     };
     
@@ -1519,51 +2256,178 @@ async fn sql_completion(
     }
 }
 
+// 自动补全时最多读取的表数量
+const COMPLETE_MAX_TABLES: usize = 50;
+
+// 读取前 max_tables 张表的列信息（单表失败时只保留表名）
+async fn load_schema_tables(connection_id: Option<i64>, db_manager: &DatabaseManager, table_names: Vec<String>, max_tables: usize) -> Vec<SchemaTable> {
+    let mut tables = Vec::new();
+    for table_name in table_names.into_iter().take(max_tables) {
+        let columns = match cached_table_structure(connection_id, db_manager, &table_name).await {
+            Ok(schema) => schema.columns.into_iter()
+                .map(|c| (c.name, c.data_type))
+                .collect(),
+            Err(e) => {
+                warn!("[API] 获取表 {} 结构失败，仅保留表名: {}", table_name, e);
+                Vec::new()
+            }
+        };
+        tables.push(SchemaTable { name: table_name, columns });
+    }
+    tables
+}
+
+/**
+ * 基于表结构的SQL自动补全处理函数
+ */
+async fn sql_complete(
+    Extension(ai_service): Extension<Option<AiService>>,
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<SqlCompleteRequest>,
+) -> Result<Json<SqlCompleteResponse>, ApiError> {
+    let cursor = req.cursor_position.unwrap_or_else(|| req.sql.chars().count());
+    info!("[API] POST /api/ai/sql/complete - 请求: SQL长度={}, 光标位置={}, 使用AI={}", req.sql.len(), cursor, req.use_ai);
+
+    let (connection, db_manager) = connect_database(&storage, req.connection_id).await?;
+
+    let table_names = cached_table_names(connection.id, &db_manager).await.map_err(|e| ApiError::internal("schema_error", format!("获取表列表失败: {}", e)))?;
+
+    let tables = load_schema_tables(connection.id, &db_manager, table_names, COMPLETE_MAX_TABLES).await;
+
+    let mut suggestions = completion::local_completions(&req.sql, cursor, &tables, req.max_suggestions);
+
+    // AI补全整条语句，失败时仅返回本地补全结果
+    if req.use_ai {
+        if let Some(ai_service) = ai_service.as_ref() {
+            let before_cursor: String = req.sql.chars().take(cursor).collect();
+            let schema = completion::schema_prompt(&tables);
+            match ai_service.suggest_sql_completion(&before_cursor, Some(&schema), Some(&connection.db_type)).await {
+                Ok(items) => {
+                    suggestions.extend(items.into_iter().enumerate().map(|(i, text)| CompletionSuggestion {
+                        label: text.clone(),
+                        kind: "statement".to_string(),
+                        detail: Some("AI补全".to_string()),
+                        insert_text: text,
+                        score: 60.0 - i as f64,
+                        source: "ai".to_string(),
+                    }));
+                }
+                Err(e) => warn!("[API] POST /api/ai/sql/complete - AI补全失败，仅返回本地补全: {:?}", e),
+            }
+        }
+    }
+
+    suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    suggestions.truncate(req.max_suggestions);
+
+    info!("[API] POST /api/ai/sql/complete - 响应成功: 建议数量={}", suggestions.len());
+
+    Ok(Json(SqlCompleteResponse {
+        success: true,
+        suggestions,
+        error: None,
+    }))
+}
+
+// 对话时写入系统提示的最大表数量
+const CHAT_MAX_SCHEMA_TABLES: usize = 30;
+
 // 对话式AI分析处理函数
 async fn chat_analysis(
     Extension(ai_service): Extension<Option<AiService>>,
-    Extension(_storage): Extension<LocalStorageManager>,
+    Extension(storage): Extension<LocalStorageManager>,
     Json(req): Json<ChatAnalysisRequest>,
-) -> Result<Json<ChatAnalysisResponse>, (StatusCode, Json<ModelErrorResponse>)> {
-    info!("[API] POST /api/ai/chat - 请求: 查询长度={}, 历史消息数={}", 
+) -> Result<Json<ChatAnalysisResponse>, ApiError> {
+    info!("[API] POST /api/ai/chat - 请求: 查询长度={}, 会话ID={:?}, 历史消息数={}", 
         req.query.len(), 
+        req.conversation_id,
         req.conversation_history.as_ref().map(|h| h.len()).unwrap_or(0));
     debug!("[API] POST /api/ai/chat - 当前查询: {}", req.query);
     
     let ai_service = match ai_service {
         Some(service) => service,
         None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "ai_service_unavailable".to_string(),
-                    message: "AI服务未配置，请先配置API密钥".to_string(),
-                    details: None,
-                })
-            ))
+            return Err(ApiError::bad_request("ai_service_unavailable", "AI服务未配置，请先配置API密钥"))
         }
     };
     
-    // 转换对话历史格式
-    let conversation_history: Vec<(String, String)> = req.conversation_history
-        .unwrap_or_default()
-        .into_iter()
-        .map(|msg| (msg.role, msg.content))
-        .collect();
+    // 已有会话从本地存储加载历史消息，新会话使用请求中的历史（兼容旧客户端）
+    let conversation = match req.conversation_id {
+        Some(id) => Some(storage.get_chat_conversation(id).await.map_err(|e| ApiError::not_found("conversation_not_found", format!("对话会话不存在: {}", e)))?),
+        None => None,
+    };
+    
+    let conversation_history: Vec<(String, String)> = match &conversation {
+        Some(conv) => storage.list_chat_messages(conv.id.unwrap_or_default()).await
+            .map_err(|e| ApiError::db("database_error", format!("加载对话历史失败: {}", e)))?
+            .into_iter()
+            .map(|msg| (msg.role, msg.content))
+            .collect(),
+        None => req.conversation_history
+            .unwrap_or_default()
+            .into_iter()
+            .map(|msg| (msg.role, msg.content))
+            .collect(),
+    };
+    
+    let connection_id = req.connection_id.or(conversation.as_ref().and_then(|c| c.connection_id));
+    
+    // 未提供表结构时读取连接的表结构写入系统提示，失败时不带表结构继续对话
+    let (database_schema, database_type) = match req.database_schema {
+        Some(schema) => (Some(schema), req.database_type),
+        None => match connect_database(&storage, connection_id).await {
+            Ok((connection, db_manager)) => {
+                let schema = match cached_table_names(connection.id, &db_manager).await {
+                    Ok(table_names) => {
+                        let tables = load_schema_tables(connection.id, &db_manager, table_names, CHAT_MAX_SCHEMA_TABLES).await;
+                        Some(completion::schema_prompt(&tables))
+                    }
+                    Err(e) => {
+                        warn!("[API] POST /api/ai/chat - 获取表结构失败: {}", e);
+                        None
+                    }
+                };
+                (schema, req.database_type.or(Some(connection.db_type)))
+            }
+            Err(e) => {
+                warn!("[API] POST /api/ai/chat - 无可用连接，不附带表结构: {}", e.message());
+                (None, req.database_type)
+            }
+        },
+    };
     
     match ai_service.chat_analysis(
         conversation_history,
         &req.query,
-        req.database_schema.as_deref(),
-        req.database_type.as_deref(),
+        database_schema.as_deref(),
+        database_type.as_deref(),
     ).await {
         Ok(response) => {
             info!("[API] POST /api/ai/chat - 响应成功: 回复长度={}", response.len());
             debug!("[API] POST /api/ai/chat - AI回复: {}", response);
+            
+            // 持久化本轮对话
+            let conversation_id = match conversation.and_then(|c| c.id) {
+                Some(id) => id,
+                None => {
+                    let title: String = req.query.chars().take(50).collect();
+                    storage.create_chat_conversation(connection_id, &title).await
+                        .map_err(|e| ApiError::db("database_error", format!("创建对话会话失败: {}", e)))?
+                        .id
+                        .unwrap_or_default()
+                }
+            };
+            for (role, content) in [("user", req.query.as_str()), ("assistant", response.as_str())] {
+                if let Err(e) = storage.add_chat_message(conversation_id, role, content).await {
+                    error!("[API] 保存对话消息失败: 会话ID={}, 错误={}", conversation_id, e);
+                }
+            }
+            
             Ok(Json(ChatAnalysisResponse {
                 success: true,
                 response: Some(response),
                 error: None,
+                conversation_id: Some(conversation_id),
             }))
         },
         Err(e) => {
@@ -1572,32 +2436,115 @@ async fn chat_analysis(
                 success: false,
                 response: None,
                 error: Some(format!("对话式AI分析失败: {}", e)),
+                conversation_id: req.conversation_id,
             }))
         }
     }
 }
 
+// 获取对话会话列表
+async fn list_chat_conversations(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    log::info!("[API] GET /api/ai/chat/conversations - 获取对话会话列表请求");
+    
+    let connection_id = params.get("connection_id").and_then(|s| s.parse::<i64>().ok());
+    
+    match storage.list_chat_conversations(connection_id).await {
+        Ok(conversations) => {
+            log::debug!("[API] 获取对话会话 {} 条", conversations.len());
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "data": conversations,
+                "count": conversations.len()
+            })))
+        },
+        Err(e) => {
+            log::error!("[API] 获取对话会话失败: {}", e);
+            Err(ApiError::db("list_conversations_failed", format!("获取对话会话列表失败: {}", e)))
+        }
+    }
+}
+
+// 获取单个对话会话及其消息
+async fn get_chat_conversation(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    log::info!("[API] GET /api/ai/chat/conversations/:id - 获取对话会话请求: id={}", id);
+    
+    let conversation = storage.get_chat_conversation(id).await.map_err(|e| {
+        log::error!("[API] 获取对话会话失败: {}", e);
+        ApiError::not_found("conversation_not_found", format!("对话会话不存在: {}", e))
+    })?;
+    
+    let messages = storage.list_chat_messages(id).await.map_err(|e| {
+        log::error!("[API] 获取对话消息失败: {}", e);
+        ApiError::db("list_messages_failed", format!("获取对话消息失败: {}", e))
+    })?;
+    
+    log::info!("[API] 获取对话会话成功: id={}, 消息数={}", id, messages.len());
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "conversation": conversation,
+            "messages": messages
+        }
+    })))
+}
+
+// 删除对话会话
+async fn delete_chat_conversation(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    log::info!("[API] DELETE /api/ai/chat/conversations/:id - 删除对话会话请求: id={}", id);
+    
+    match storage.delete_chat_conversation(id).await {
+        Ok(_) => {
+            log::info!("[API] 对话会话删除成功: id={}", id);
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "message": "对话会话已删除"
+            })))
+        },
+        Err(e) => {
+            log::error!("[API] 删除对话会话失败: {}", e);
+            Err(ApiError::db("delete_conversation_failed", format!("删除对话会话失败: {}", e)))
+        }
+    }
+}
+
 // SQL优化处理函数
+#[utoipa::path(
+    post,
+    path = "/api/ai/sql/optimize",
+    tag = "ai",
+    request_body = SqlOptimizeRequest,
+    responses(
+        (status = 200, description = "优化后的SQL和建议", body = SqlOptimizeResponse),
+        (status = 503, description = "AI服务未配置", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn optimize_sql(
+    Extension(storage): Extension<LocalStorageManager>,
     Extension(ai_service): Extension<Option<AiService>>,
+    Extension(template_manager): Extension<TemplateManager>,
     Json(req): Json<SqlOptimizeRequest>,
-) -> Result<Json<SqlOptimizeResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<SqlOptimizeResponse>, ApiError> {
     info!("[API] POST /api/ai/sql/optimize - 请求: SQL长度={}, database_type={:?}", 
         req.sql.len(), req.database_type);
     debug!("[API] POST /api/ai/sql/optimize - SQL内容: {}", req.sql);
     if let Ok(req_json) = serde_json::to_string(&req) {
-        log::info!("[API] POST /api/ai/sql/optimize - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/ai/sql/optimize - 请求体: {}", req_json);
     }
     // 检查AI服务是否可用
     let ai_service = ai_service.as_ref()
-        .ok_or_else(|| (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ModelErrorResponse {
-                error: "ai_service_unavailable".to_string(),
-                message: "AI服务不可用".to_string(),
-                details: None,
-            })
-        ))?;
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用"))?;
+    let ai_service = ai_service_for_template(
+        &storage, &template_manager, ai_service, req.connection_id, TemplateType::Optimize, req.template_id.as_deref(),
+    ).await?;
 
     info!("开始优化SQL");
     
@@ -1612,20 +2559,13 @@ async fn optimize_sql(
                 execution_time: 0,
             };
             if let Ok(resp_json) = serde_json::to_string(&response) {
-                log::info!("[API] POST /api/ai/sql/optimize - 响应体: {}", resp_json);
+                log::debug!("[API] POST /api/ai/sql/optimize - 响应体: {}", resp_json);
             }
             Ok(Json(response))
         },
         Err(e) => {
             error!("SQL优化失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "ai_error".to_string(),
-                    message: format!("SQL优化失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::ai("ai_error", format!("SQL优化失败: {}", e)))
         }
     }
 }
@@ -1647,23 +2587,16 @@ struct CreateTableResponse {
 async fn create_table(
     Extension(ai_service): Extension<Option<AiService>>,
     Json(req): Json<CreateTableRequest>,
-) -> Result<Json<CreateTableResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<CreateTableResponse>, ApiError> {
     info!("[API] POST /api/ai/table/create - 请求: 描述长度={}", req.natural_language.len());
     debug!("[API] POST /api/ai/table/create - 请求内容: {}", req.natural_language);
     if let Ok(req_json) = serde_json::to_string(&req) {
-        log::info!("[API] POST /api/ai/table/create - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/ai/table/create - 请求体: {}", req_json);
     }
 
     // 检查AI服务是否可用
     let ai_service = ai_service.as_ref()
-        .ok_or_else(|| (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ModelErrorResponse {
-                error: "ai_service_unavailable".to_string(),
-                message: "AI服务不可用".to_string(),
-                details: None,
-            })
-        ))?;
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用"))?;
 
     info!("开始生成建表SQL");
 
@@ -1689,21 +2622,14 @@ async fn create_table(
             };
 
             if let Ok(resp_json) = serde_json::to_string(&response) {
-                log::info!("[API] POST /api/ai/table/create - 响应体: {}", resp_json);
+                log::debug!("[API] POST /api/ai/table/create - 响应体: {}", resp_json);
             }
 
             Ok(Json(response))
         },
         Err(e) => {
             error!("生成建表SQL失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "ai_error".to_string(),
-                    message: format!("生成建表SQL失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::ai("ai_error", format!("生成建表SQL失败: {}", e)))
         }
     }
 }
@@ -1777,106 +2703,324 @@ fn extract_columns_from_sql(sql: &str) -> Vec<serde_json::Value> {
 
 // 执行SQL查询处理函数
 // TODO: 实现从活动连接动态创建DatabaseManager
-async fn execute_query(
+#[utoipa::path(
+    post,
+    path = "/api/database/query",
+    tag = "database",
+    request_body(content = SqlQueryRequest, description = "指定 page 时启用分页，page_size 默认取应用设置（100），count_total 为true时返回 total_rows"),
+    responses(
+        (status = 200, description = "查询结果", body = SqlQueryResult),
+        (status = 400, description = "SQL无效或被策略拒绝", body = crate::api::error::ErrorInfo),
+        (status = 428, description = "生产环境写操作需要确认", body = crate::api::error::ErrorInfo),
+        (status = 504, description = "查询超时", body = crate::api::error::ErrorInfo),
+    )
+)]
+pub(crate) async fn execute_query(
     Extension(storage): Extension<LocalStorageManager>,
     Json(payload): Json<SqlQueryRequest>
-) -> Result<Json<SqlQueryResult>, (StatusCode, Json<ModelErrorResponse>)> {
-    use std::time::Instant;
-    use sqlx::{Row, Column, TypeInfo};
-    
+) -> Result<Json<SqlQueryResult>, ApiError> {
     info!("[API] POST /api/database/query - 请求: SQL长度={}", payload.sql.len());
     debug!("[API] POST /api/database/query - SQL内容: {}", payload.sql);
     if let Ok(req_json) = serde_json::to_string(&payload) {
-        log::info!("[API] POST /api/database/query - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/database/query - 请求体: {}", req_json);
     }
     
+    // 会话模式：使用会话的专用连接（会话变量、临时表、USE 切换的数据库在后续查询中保留）
+    let session = match payload.session_id.as_deref() {
+        Some(id) => Some(query_sessions::get(id).ok_or_else(|| {
+            ApiError::not_found("session_not_found", format!("会话 {} 不存在或已因闲置超时关闭", id))
+        })?),
+        None => None,
+    };
+    let connection_id = match (&session, payload.connection_id) {
+        (Some(session), Some(id)) if session.connection_id != id => {
+            return Err(ApiError::bad_request("session_connection_mismatch", format!("会话不属于连接ID {}", id)));
+        }
+        (Some(session), _) => Some(session.connection_id),
+        (None, id) => id,
+    };
+    
     // 获取要查询的连接
-    let connection = if let Some(conn_id) = payload.connection_id {
+    let mut connection = if let Some(conn_id) = connection_id {
         // 使用指定的连接ID
         storage.get_connection_by_id(conn_id).await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?
-            .ok_or_else(|| (
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "connection_not_found".to_string(),
-                    message: format!("连接ID {}不存在", conn_id),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("connection_not_found", format!("连接ID {}不存在", conn_id)))?
     } else {
         // 如果未指定，使用第一个活动连接
         let active_conns = storage.get_active_connections().await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?;
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?;
         
-        active_conns.into_iter().next().ok_or_else(|| (
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "no_connection".to_string(),
-                message: "请先激活一个数据库连接".to_string(),
-                details: None,
-            })
-        ))?
+        active_conns.into_iter().next().ok_or_else(|| ApiError::bad_request("no_connection", "请先激活一个数据库连接"))?
     };
     
-    // 构建连接字符串
-    let conn_str = build_connection_string(&connection)?;
+    // 指定了数据库时切换到该数据库（会话模式下为打开会话时指定的数据库）
+    let database = match &session {
+        Some(session) => session.database.as_deref(),
+        None => payload.database.as_deref().map(str::trim).filter(|d| !d.is_empty()),
+    };
+    if let Some(database) = database {
+        switch_database(&mut connection, database)?;
+    }
     
-    // 创建数据库管理器
-    let db_manager = DatabaseManager::from_connection_string(&conn_str).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "connection_failed".to_string(),
-                message: format!("数据库连接失败: {}", e),
-                details: None,
-            })
-        ))?;
+    // 生产环境写操作需要确认
+    production_guard(&connection, &crate::services::policy::split_statements(&payload.sql), payload.confirm_production)?;
+    
+    // 查询行数限制（默认行数和上限来自应用设置，连接设置了默认最大行数时不超过该值）
+    let mut settings = app_settings::load_settings(&storage).await;
+    if let Some(max_rows) = connection.options.max_rows {
+        settings.max_query_limit = settings.max_query_limit.min(max_rows);
+        settings.default_query_limit = settings.default_query_limit.min(max_rows);
+    }
+    let query_timeout = connection_query_timeout(&connection, &payload);
+    
+    // 查询结果缓存：命中时直接返回，不连接数据库（会话中的查询结果依赖会话状态，不使用缓存）
+    let cache_key = if session.is_some() { None } else { result_cache_key(&connection, database, &payload, &settings) };
+    let cache_ttl = Duration::from_secs(settings.result_cache_ttl_secs);
+    if let (Some(key), false) = (&cache_key, payload.bypass_cache) {
+        if let Some(mut result) = get_result_cache().get(key, cache_ttl) {
+            info!("[API] POST /api/database/query - 命中查询结果缓存: 行数={}", result.row_count);
+            result.cached = true;
+            result.query_id = Some(payload.query_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()));
+            return Ok(Json(result));
+        }
+    }
+    
+    // 创建数据库管理器（会话模式下使用会话的专用连接）
+    let db_manager = match &session {
+        Some(session) => session.manager.clone(),
+        None => {
+            let conn_str = build_connection_string(&connection)?;
+            crate::db::pool_cache::get_or_connect_database(connection.id, database, &conn_str, &connection.pool_options, &connection.options.init_sql).await
+                .map_err(|e| ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)))?
+        }
+    };
+    
+    // 生成查询ID并注册取消通道，客户端可通过 /api/database/query/:query_id/cancel 取消查询
+    let query_id = payload.query_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    // 取消通道在 _canceller 离开作用域时移除（包括客户端断开、请求被丢弃的情况）
+    let Some((_canceller, cancel_rx)) = register_canceller(&query_id) else {
+        return Err(ApiError::conflict("query_id_conflict", format!("查询ID {} 正在执行中", query_id)));
+    };
+    
+    // 执行前钩子（策略检查等），被拒绝时不执行查询
+    let hook_ctx = QueryContext {
+        query_id: &query_id,
+        sql: &payload.sql,
+        connection: &connection,
+    };
+    if let Err(rejection) = hooks::registry().run_before(&hook_ctx) {
+        return Err(ApiError::forbidden("query_rejected", format!("查询被拒绝: {}", rejection)));
+    }
+    
+    log::info!("[API] 准备执行查询 - query_id: {}, 数据库类型: {:?}, SQL: {}", query_id, db_manager.db_type, payload.sql);
+    
+    // 执行查询的后端会话ID（MySQL: CONNECTION_ID()，PostgreSQL: pg_backend_pid()），取消时用于终止服务端查询
+    // 仅在客户端指定 query_id 时记录（需额外一次往返），未记录时取消或超时只丢弃连接
+    let backend_id: BackendSessionId = Arc::new(Mutex::new(None));
+    
+    // 执行期间通过 /api/ws 定时推送进度
+    let progress = AbortOnDrop(events::spawn_query_progress(query_id.clone()));
+    
+    let started = Instant::now();
+    let outcome = tokio::select! {
+        res = run_query(&db_manager, &payload, &settings, backend_id.clone()) => res,
+        Ok(()) = cancel_rx => {
+            warn!("[API] 查询已取消: query_id={}", query_id);
+            let session_id = *backend_id.lock().unwrap();
+            stop_backend_query(&db_manager, session.is_some(), &connection, database, session_id).await;
+            Err(ApiError::bad_request("query_cancelled", "查询已被取消").with_details(query_id.clone()))
+        }
+        _ = tokio::time::sleep(query_timeout) => {
+            warn!("[API] 查询超时: query_id={}", query_id);
+            let session_id = *backend_id.lock().unwrap();
+            stop_backend_query(&db_manager, session.is_some(), &connection, database, session_id).await;
+            Err(ApiError::timeout("query_timeout", format!("查询超时（{}秒）", query_timeout.as_secs())).with_details(query_id.clone()))
+        }
+    };
+    
+    // 查询结束（成功、失败或取消）后停止推送进度
+    drop(progress);
+    metrics::record_query(db_manager.db_type.as_str(), outcome.is_ok(), started.elapsed());
+    
+    // 执行后钩子
+    let hook_outcome = match &outcome {
+        Ok(result) => QueryOutcome {
+            success: true,
+            row_count: result.row_count,
+            execution_time_ms: result.execution_time_ms,
+            error: None,
+        },
+        Err(err) => QueryOutcome {
+            success: false,
+            row_count: 0,
+            execution_time_ms: 0,
+            error: Some(err.message().to_string()),
+        },
+    };
+    hooks::registry().run_after(&hook_ctx, &hook_outcome);
+    
+    // 写操作记录审计日志（包括执行失败的语句）
+    audit::record_in_background(&storage, AuditRecord {
+        connection: &connection,
+        source: audit::SOURCE_QUERY,
+        ai_generated: payload.ai_generated,
+        affected_rows: outcome.as_ref().ok().and_then(|result| result.rows_affected).map(|rows| rows as i64),
+        error: hook_outcome.error.clone(),
+    }, &crate::services::policy::split_statements(&payload.sql));
+    
+    // 记录查询历史
+    let (history_time, history_rows, history_error) = match &outcome {
+        Ok(result) => (Some(result.execution_time_ms as i64), Some(result.row_count as i64), None),
+        Err(err) => (None, None, Some(err.message())),
+    };
+    if let Err(e) = storage.add_query_history(connection.id, &payload.sql, history_time, history_rows, outcome.is_ok(), history_error).await {
+        warn!("[API] 记录查询历史失败: {}", e);
+    }
+    
+    // 结构变更语句执行成功后清除表结构缓存，写操作后清除该连接的查询结果缓存
+    if let (Ok(_), Some(id)) = (&outcome, connection.id) {
+        if schema_cache::changes_schema(&payload.sql) {
+            get_schema_cache().invalidate(id);
+        }
+        if !result_cache::is_cacheable(crate::db::DatabaseType::from_name(&connection.db_type), &payload.sql) {
+            get_result_cache().invalidate(id);
+        }
+    }
+    
+    // 缓存只读查询的结果
+    if let (Some(key), Ok(result)) = (cache_key, &outcome) {
+        get_result_cache().put(key, result.clone(), cache_ttl, settings.result_cache_max_entries);
+    }
+    
+    // 记录慢查询日志
+    if let Ok(result) = &outcome {
+        slow_queries::record_in_background(
+            storage.clone(),
+            db_manager.clone(),
+            connection.id,
+            payload.sql.clone(),
+            payload.parameters.clone().unwrap_or_default(),
+            result.execution_time_ms,
+            result.row_count,
+        );
+    }
+    
+    // 慢查询、查询失败时发送Webhook通知
+    match &outcome {
+        Ok(result) => notifications::notify_slow_query(storage.clone(), &connection.name, &payload.sql, result.execution_time_ms, settings.slow_query_threshold_ms),
+        Err(err) if err.code() != "query_cancelled" => notifications::notify_in_background(
+            storage.clone(),
+            Notification::new(EVENT_QUERY_FAILED, "查询执行失败", format!("连接 {} 上的查询失败: {}", connection.name, err.message()))
+                .with_details(serde_json::json!({ "connection": connection.name, "sql": payload.sql, "error": err.message() })),
+        ),
+        Err(_) => {}
+    }
+    
+    let mut result = outcome?;
+    result.query_id = Some(query_id);
+    
+    info!("[API] POST /api/database/query - 响应成功: 行数={}, 执行时间={}ms", 
+        result.row_count, result.execution_time_ms);
+    if let Ok(resp_json) = serde_json::to_string(&result) {
+        log::debug!("[API] POST /api/database/query - 响应体: {}", resp_json);
+    }
+    Ok(Json(result))
+}
+
+// 执行查询的后端会话ID
+type BackendSessionId = Arc<Mutex<Option<i64>>>;
+
+// 按数据库类型执行查询并转换结果
+async fn run_query(
+    db_manager: &DatabaseManager,
+    payload: &SqlQueryRequest,
+    settings: &AppSettings,
+    backend_id: BackendSessionId,
+) -> Result<SqlQueryResult, ApiError> {
+    use sqlx::Column;
     
-    // 执行查询
     let start = Instant::now();
     
-    log::info!("[API] 准备执行查询 - 数据库类型: {:?}, SQL: {}", db_manager.db_type, payload.sql);
+    // 分页参数（未指定page时不分页）
+    let pagination = Pagination::from_request(payload, settings)?;
+    if pagination.as_ref().is_some_and(|p| p.keyset.is_some())
+        && !matches!(db_manager.db_type, crate::db::DatabaseType::MySQL | crate::db::DatabaseType::PostgreSQL | crate::db::DatabaseType::SQLite)
+    {
+        return Err(ApiError::bad_request("pagination_error", format!("{:?}不支持游标分页", db_manager.db_type)));
+    }
+    // MongoDB在查询时统计的总文档数
+    let mut mongo_total: Option<u64> = None;
+    // 数据库返回结果所用时间（之后为结果转换时间）
+    let mut query_elapsed: Option<std::time::Duration> = None;
+    // 扫描行数（请求统计时MySQL取自会话状态，PostgreSQL取自EXPLAIN估算）
+    let mut rows_examined: Option<usize> = None;
+    let mut examined_estimated = false;
+    
+    // 绑定参数：MySQL/SQLite 使用 ? 占位符，PostgreSQL 使用 $n 占位符
+    let query_params: &[serde_json::Value] = payload.parameters.as_deref().unwrap_or(&[]);
+    // 游标分页时游标值追加在查询参数之后
+    let bound_params: Vec<serde_json::Value> = match pagination.as_ref().and_then(|p| p.keyset.as_ref()) {
+        Some(keyset) => query_params.iter().chain(keyset.params()).cloned().collect(),
+        None => query_params.to_vec(),
+    };
+    let params: &[serde_json::Value] = &bound_params;
+    if !params.is_empty() && matches!(db_manager.db_type, crate::db::DatabaseType::MongoDB | crate::db::DatabaseType::Redis | crate::db::DatabaseType::DuckDB) {
+        return Err(ApiError::bad_request("unsupported_parameters", format!("{:?}不支持参数化查询", db_manager.db_type)));
+    }
+    if !params.is_empty() {
+        log::info!("[API] 参数化查询，参数数量: {}", params.len());
+    }
     
-    let result = match &db_manager.pool {
+    let mut result = match &db_manager.pool {
         crate::db::DatabasePool::MySQL(pool) => {
             // 记录实际执行的SQL语句
             log::info!("[API] 执行MySQL查询: {}", payload.sql);
             
+            // 使用独立连接执行查询，客户端指定了 query_id 时记录其会话ID以便取消
+            let mut conn = pool.acquire().await.map_err(|e| ApiError::internal("connection_failed", format!("获取数据库连接失败: {}", e)))?;
+            if payload.query_id.is_some() {
+                if let Ok(id) = sqlx::query_scalar::<_, u64>("SELECT CONNECTION_ID()")
+                    .fetch_one(&mut *conn)
+                    .await {
+                    *backend_id.lock().unwrap() = Some(id as i64);
+                }
+            }
+            
+            // 为SQL语句添加LIMIT限制
+            let exec_sql = match &pagination {
+                Some(p) => build_paged_sql(db_manager.db_type, &payload.sql, p, query_params.len())?,
+                None if payload.ignore_limit => payload.sql.clone(),
+                None => add_limit_to_sql(db_manager.db_type, &payload.sql, settings),
+            };
+            
+            // 请求统计时在执行前记录会话Handler读取计数，两次读取的差值为状态查询自身的开销
+            let handler_reads = match payload.collect_stats {
+                true => Some((mysql_handler_reads(&mut conn).await, mysql_handler_reads(&mut conn).await)),
+                false => None,
+            };
+            
             // 尝试使用fetch_all方法，添加详细的错误日志
-            let rows = match sqlx::query(&payload.sql)
-                .fetch_all(pool)
+            let (rows, rows_affected) = match fetch_rows_affected!(bind_json_values!(sqlx::query(&exec_sql), params), &mut *conn)
                 .await {
-                    Ok(rows) => {
-                        log::info!("[API] MySQL查询成功，返回 {} 行数据", rows.len());
-                        rows
+                    Ok((rows, rows_affected)) => {
+                        log::info!("[API] MySQL查询成功，返回 {} 行数据，影响 {} 行", rows.len(), rows_affected);
+                        (rows, rows_affected)
                     },
                     Err(e) => {
                         log::error!("[API] MySQL查询失败: {}", e);
                         log::error!("[API] 失败的SQL: {}", payload.sql);
-                        return Err((
-                            StatusCode::BAD_REQUEST,
-                            Json(ModelErrorResponse {
-                                error: "query_error".to_string(),
-                                message: format!("查询执行失败: {}", e),
-                                details: Some(payload.sql.clone()),
-                            })
-                        ));
+                        return Err(ApiError::bad_request("query_error", format!("查询执行失败: {}", e)).with_details(payload.sql.clone()));
                     }
                 };
+            query_elapsed = Some(start.elapsed());
+            
+            if let Some((Some(before), Some(baseline))) = handler_reads {
+                if let Some(after) = mysql_handler_reads(&mut conn).await {
+                    let overhead = baseline.saturating_sub(before);
+                    rows_examined = Some(after.saturating_sub(baseline).saturating_sub(overhead) as usize);
+                }
+            }
             
             // 提取列名
             let columns: Vec<String> = if let Some(first_row) = rows.first() {
@@ -1887,49 +3031,8 @@ async fn execute_query(
                 vec![]
             };
             
-            // 转换行数据为JSON
-            let mut json_rows = Vec::new();
-            for (row_idx, row) in rows.iter().enumerate() {
-                let mut json_row = Vec::new();
-                for (i, column) in row.columns().iter().enumerate() {
-                    let col_name = column.name();
-                    let col_type = column.type_info().name();
-                    log::debug!("[API] 处理行 {} 的列 {} (类型: {})
-", row_idx, col_name, col_type);
-                    
-                    // 使用更通用的方式获取数据
-                    let value = match row.try_get::<String, _>(i) {
-                        Ok(v) => {
-                            log::debug!("[API] 列 {} 获取为字符串: {}", col_name, v);
-                            serde_json::json!(v)
-                        },
-                        Err(e1) => {
-                            log::debug!("[API] 列 {} 获取字符串失败: {}, 尝试获取为i64", col_name, e1);
-                            match row.try_get::<i64, _>(i) {
-                                Ok(v) => {
-                                    log::debug!("[API] 列 {} 获取为i64: {}", col_name, v);
-                                    serde_json::json!(v)
-                                },
-                                Err(e2) => {
-                                    log::debug!("[API] 列 {} 获取i64失败: {}, 尝试获取为f64", col_name, e2);
-                                    match row.try_get::<f64, _>(i) {
-                                        Ok(v) => {
-                                            log::debug!("[API] 列 {} 获取为f64: {}", col_name, v);
-                                            serde_json::json!(v)
-                                        },
-                                        Err(e3) => {
-                                            log::debug!("[API] 列 {} 获取f64失败: {}, 返回null", col_name, e3);
-                                            serde_json::json!(null)
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    };
-                    json_row.push(value);
-                }
-                json_rows.push(json_row);
-            }
+            // 按列类型转换行数据为JSON
+            let json_rows: Vec<Vec<serde_json::Value>> = rows.iter().map(|row| row.json_values()).collect();
             
             let execution_time = start.elapsed();
             log::info!("[API] MySQL查询完成，耗时 {}ms", execution_time.as_millis());
@@ -1944,23 +3047,45 @@ async fn execute_query(
                 page_size: None,
                 has_more: false,
                 performance: None,
+                query_id: None,
+                next_cursor: None,
+                cached: false,
+                rows_affected: Some(rows_affected),
             }
         }
         crate::db::DatabasePool::PostgreSQL(pool) => {
             // 为SQL语句添加LIMIT限制
-            let limited_sql = add_limit_to_sql(&payload.sql);
+            let limited_sql = match &pagination {
+                Some(p) => build_paged_sql(db_manager.db_type, &payload.sql, p, query_params.len())?,
+                None if payload.ignore_limit => payload.sql.clone(),
+                None => add_limit_to_sql(db_manager.db_type, &payload.sql, settings),
+            };
             
-            let rows = sqlx::query(&limited_sql)
-                .fetch_all(pool)
+            // 使用独立连接执行查询，客户端指定了 query_id 时记录其后端进程ID以便取消
+            let mut conn = pool.acquire().await.map_err(|e| ApiError::internal("connection_failed", format!("获取数据库连接失败: {}", e)))?;
+            if payload.query_id.is_some() {
+                if let Ok(pid) = sqlx::query_scalar::<_, i32>("SELECT pg_backend_pid()")
+                    .fetch_one(&mut *conn)
+                    .await {
+                    *backend_id.lock().unwrap() = Some(pid as i64);
+                }
+            }
+            
+            let (rows, rows_affected) = fetch_rows_affected!(bind_json_values!(sqlx::query(&limited_sql), params), &mut *conn)
                 .await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "query_error".to_string(),
-                        message: format!("查询执行失败: {}", e),
-                        details: None,
-                    })
-                ))?;
+                .map_err(|e| ApiError::bad_request("query_error", format!("查询执行失败: {}", e)))?;
+            query_elapsed = Some(start.elapsed());
+            
+            // 请求统计时通过EXPLAIN估算扫描行数（仅SELECT）
+            if payload.collect_stats && matches!(parse_sql(db_manager.db_type, &limited_sql), Ok(sqlparser::ast::Statement::Query(_))) {
+                let explain_sql = format!("EXPLAIN {}", limited_sql);
+                if let Ok(plan_lines) = bind_json_values!(sqlx::query_scalar::<_, String>(&explain_sql), params)
+                    .fetch_all(&mut *conn)
+                    .await {
+                    rows_examined = Some(estimate_scanned_rows(&plan_lines));
+                    examined_estimated = true;
+                }
+            }
             
             // 提取列名
             let columns: Vec<String> = if let Some(first_row) = rows.first() {
@@ -1969,37 +3094,8 @@ async fn execute_query(
                 vec![]
             };
             
-            // 转换行数据为JSON
-            let mut json_rows = Vec::new();
-            for row in &rows {
-                let mut json_row = Vec::new();
-                for (i, column) in row.columns().iter().enumerate() {
-                    let value = match column.type_info().name() {
-                        "INT2" | "INT4" | "INT8" => {
-                            row.try_get::<i64, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        "FLOAT4" | "FLOAT8" | "NUMERIC" => {
-                            row.try_get::<f64, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        "VARCHAR" | "TEXT" | "CHAR" => {
-                            row.try_get::<String, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        _ => {
-                            row.try_get::<String, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                    };
-                    json_row.push(value);
-                }
-                json_rows.push(json_row);
-            }
+            // 按列类型转换行数据为JSON
+            let json_rows: Vec<Vec<serde_json::Value>> = rows.iter().map(|row| row.json_values()).collect();
             
             let execution_time = start.elapsed();
             
@@ -2013,23 +3109,24 @@ async fn execute_query(
                 page_size: None,
                 has_more: false,
                 performance: None,
+                query_id: None,
+                next_cursor: None,
+                cached: false,
+                rows_affected: Some(rows_affected),
             }
         }
         crate::db::DatabasePool::SQLite(pool) => {
             // 为SQL语句添加LIMIT限制
-            let limited_sql = add_limit_to_sql(&payload.sql);
+            let limited_sql = match &pagination {
+                Some(p) => build_paged_sql(db_manager.db_type, &payload.sql, p, query_params.len())?,
+                None if payload.ignore_limit => payload.sql.clone(),
+                None => add_limit_to_sql(db_manager.db_type, &payload.sql, settings),
+            };
             
-            let rows = sqlx::query(&limited_sql)
-                .fetch_all(pool)
+            let (rows, rows_affected) = fetch_rows_affected!(bind_json_values!(sqlx::query(&limited_sql), params), pool)
                 .await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "query_error".to_string(),
-                        message: format!("查询执行失败: {}", e),
-                        details: None,
-                    })
-                ))?;
+                .map_err(|e| ApiError::bad_request("query_error", format!("查询执行失败: {}", e)))?;
+            query_elapsed = Some(start.elapsed());
             
             // 提取列名
             let columns: Vec<String> = if let Some(first_row) = rows.first() {
@@ -2038,37 +3135,8 @@ async fn execute_query(
                 vec![]
             };
             
-            // 转换行数据为JSON
-            let mut json_rows = Vec::new();
-            for row in &rows {
-                let mut json_row = Vec::new();
-                for (i, column) in row.columns().iter().enumerate() {
-                    let value = match column.type_info().name() {
-                        "INTEGER" => {
-                            row.try_get::<i64, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        "REAL" => {
-                            row.try_get::<f64, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        "TEXT" => {
-                            row.try_get::<String, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                        _ => {
-                            row.try_get::<String, _>(i)
-                                .map(|v| serde_json::json!(v))
-                                .unwrap_or(serde_json::json!(null))
-                        }
-                    };
-                    json_row.push(value);
-                }
-                json_rows.push(json_row);
-            }
+            // 按列类型转换行数据为JSON
+            let json_rows: Vec<Vec<serde_json::Value>> = rows.iter().map(|row| row.json_values()).collect();
             
             let execution_time = start.elapsed();
             
@@ -2082,6 +3150,10 @@ async fn execute_query(
                 page_size: None,
                 has_more: false,
                 performance: None,
+                query_id: None,
+                next_cursor: None,
+                cached: false,
+                rows_affected: Some(rows_affected),
             }
         }
         crate::db::DatabasePool::MongoDB(client, db_name) => {
@@ -2109,14 +3181,7 @@ async fn execute_query(
                             collection_match.split("'").nth(1).unwrap_or_default().to_string()
                         }
                     } else {
-                        return Err((
-                            StatusCode::BAD_REQUEST,
-                            Json(ModelErrorResponse {
-                                error: "invalid_collection_name".to_string(),
-                                message: "无法从MongoDB语句中提取集合名".to_string(),
-                                details: Some(sql.to_string()),
-                            })
-                        ));
+                        return Err(ApiError::bad_request("invalid_collection_name", "无法从MongoDB语句中提取集合名").with_details(sql.to_string()));
                     }
                 } else if sql.starts_with("db.") {
                     // 格式：db.collection_name.updateOne(...)
@@ -2127,99 +3192,43 @@ async fn execute_query(
                         if parts.len() >= 2 {
                             let name = parts[1].trim().to_string();
                             if name.is_empty() {
-                                return Err((
-                                    StatusCode::BAD_REQUEST,
-                                    Json(ModelErrorResponse {
-                                        error: "empty_collection_name".to_string(),
-                                        message: "集合名不能为空".to_string(),
-                                        details: Some(sql.to_string()),
-                                    })
-                                ));
+                                return Err(ApiError::bad_request("empty_collection_name", "集合名不能为空").with_details(sql.to_string()));
                             }
                             log::info!("[MongoDB Update] 从 '{}' 提取集合名: '{}'", collection_part, name);
                             name
                         } else {
                             log::error!("[MongoDB Update] 无法解析集合名，parts: {:?}, sql: {}", parts, sql);
-                            return Err((
-                                StatusCode::BAD_REQUEST,
-                                Json(ModelErrorResponse {
-                                    error: "invalid_collection_name".to_string(),
-                                    message: format!("无法从MongoDB语句中提取集合名，格式应为 db.collection_name.updateOne(...): {}", sql),
-                                    details: Some(sql.to_string()),
-                                })
-                            ));
+                            return Err(ApiError::bad_request("invalid_collection_name", format!("无法从MongoDB语句中提取集合名，格式应为 db.collection_name.updateOne(...): {}", sql)).with_details(sql.to_string()));
                         }
                     } else {
                         log::error!("[MongoDB Update] 无法找到集合名部分，sql: {}", sql);
-                        return Err((
-                            StatusCode::BAD_REQUEST,
-                            Json(ModelErrorResponse {
-                                error: "invalid_collection_name".to_string(),
-                                message: "无法从MongoDB语句中提取集合名".to_string(),
-                                details: Some(sql.to_string()),
-                            })
-                        ));
+                        return Err(ApiError::bad_request("invalid_collection_name", "无法从MongoDB语句中提取集合名").with_details(sql.to_string()));
                     }
                 } else {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "invalid_mongodb_syntax".to_string(),
-                            message: format!("无效的MongoDB语句格式: {}", sql),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("invalid_mongodb_syntax", format!("无效的MongoDB语句格式: {}", sql)).with_details(sql.to_string()));
                 };
                 
                 // 验证集合名不为空
                 if collection_name.is_empty() {
                     log::error!("[MongoDB Update] 集合名为空，sql: {}", sql);
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "empty_collection_name".to_string(),
-                            message: "集合名不能为空".to_string(),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("empty_collection_name", "集合名不能为空").with_details(sql.to_string()));
                 }
                 
                 // 验证集合名不是数据库名（防止混淆）
                 if collection_name == *db_name {
                     log::error!("[MongoDB Update] 集合名与数据库名相同，这可能是提取错误。集合名: {}, 数据库名: {}, sql: {}", collection_name, db_name, sql);
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "invalid_collection_name".to_string(),
-                            message: format!("集合名不能与数据库名相同。集合名: {}, 数据库名: {}", collection_name, db_name),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("invalid_collection_name", format!("集合名不能与数据库名相同。集合名: {}, 数据库名: {}", collection_name, db_name)).with_details(sql.to_string()));
                 }
                 
                 log::info!("[MongoDB Update] 集合名: '{}', 数据库名: '{}', SQL: {}", collection_name, db_name, sql);
                 
                 // 验证集合名和数据库名都不为空
                 if collection_name.trim().is_empty() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "empty_collection_name".to_string(),
-                            message: "集合名不能为空".to_string(),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("empty_collection_name", "集合名不能为空").with_details(sql.to_string()));
                 }
                 
                 if db_name.trim().is_empty() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "empty_database_name".to_string(),
-                            message: "数据库名不能为空".to_string(),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("empty_database_name", "数据库名不能为空").with_details(sql.to_string()));
                 }
                 
                 log::debug!("[MongoDB Update] 创建集合对象 - 数据库: '{}', 集合: '{}'", db_name, collection_name);
@@ -2228,26 +3237,12 @@ async fn execute_query(
                 let clean_collection_name = collection_name.trim();
                 if clean_collection_name.is_empty() {
                     log::error!("[MongoDB Update] 集合名为空");
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "empty_collection_name".to_string(),
-                            message: "集合名不能为空".to_string(),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("empty_collection_name", "集合名不能为空").with_details(sql.to_string()));
                 }
                 
                 if clean_collection_name.contains('.') {
                     log::error!("[MongoDB Update] 集合名包含点号，这可能导致命名空间错误: '{}'", clean_collection_name);
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "invalid_collection_name".to_string(),
-                            message: format!("集合名不能包含点号: '{}'", clean_collection_name),
-                            details: Some(sql.to_string()),
-                        })
-                    ));
+                    return Err(ApiError::bad_request("invalid_collection_name", format!("集合名不能包含点号: '{}'", clean_collection_name)).with_details(sql.to_string()));
                 }
                 
                 // 验证命名空间格式
@@ -2272,6 +3267,7 @@ async fn execute_query(
                             let trimmed = filter_str.trim();
                             if !trimmed.is_empty() && trimmed != "{}" {
                                 json_str_to_bson_document(trimmed)
+                                    .or_else(|_| bson_parser::parse_document(trimmed))
                                     .map_err(|e| {
                                         log::error!("[MongoDB Update] Filter解析失败: {}, filter_str: {}", e, trimmed);
                                         e
@@ -2289,44 +3285,24 @@ async fn execute_query(
                             let trimmed = update_str.trim();
                             if !trimmed.is_empty() && trimmed != "{}" {
                                 json_str_to_bson_document(trimmed)
+                                    .or_else(|_| bson_parser::parse_document(trimmed))
                                     .map_err(|e| {
                                         log::error!("[MongoDB Update] Update解析失败: {}, update_str: {}", e, trimmed);
                                         e
                                     })
                                     .ok()
                             } else {
-                                return Err((
-                                    StatusCode::BAD_REQUEST,
-                                    Json(ModelErrorResponse {
-                                        error: "invalid_update_syntax".to_string(),
-                                        message: "MongoDB更新操作不能为空".to_string(),
-                                        details: Some(sql.to_string()),
-                                    })
-                                ));
+                                return Err(ApiError::bad_request("invalid_update_syntax", "MongoDB更新操作不能为空").with_details(sql.to_string()));
                             }
                         } else {
-                            return Err((
-                                StatusCode::BAD_REQUEST,
-                                Json(ModelErrorResponse {
-                                    error: "invalid_update_syntax".to_string(),
-                                    message: "MongoDB更新操作缺少update参数".to_string(),
-                                    details: Some(sql.to_string()),
-                                })
-                            ));
+                            return Err(ApiError::bad_request("invalid_update_syntax", "MongoDB更新操作缺少update参数").with_details(sql.to_string()));
                         };
                         
                         let filter = filter_doc.unwrap_or_else(|| mongodb::bson::Document::new());
                         let update = match update_doc {
                             Some(doc) => doc,
                             None => {
-                                return Err((
-                                    StatusCode::BAD_REQUEST,
-                                    Json(ModelErrorResponse {
-                                        error: "invalid_update_syntax".to_string(),
-                                        message: "MongoDB更新操作缺少update参数".to_string(),
-                                        details: Some(sql.to_string()),
-                                    })
-                                ));
+                                return Err(ApiError::bad_request("invalid_update_syntax", "MongoDB更新操作缺少update参数").with_details(sql.to_string()));
                             }
                         };
                         
@@ -2335,14 +3311,7 @@ async fn execute_query(
                         
                         // 验证集合名和数据库名的有效性（已在之前验证过，这里使用 clean_collection_name）
                         if clean_collection_name.contains('.') || clean_collection_name.contains(' ') {
-                            return Err((
-                                StatusCode::BAD_REQUEST,
-                                Json(ModelErrorResponse {
-                                    error: "invalid_collection_name".to_string(),
-                                    message: format!("集合名包含无效字符: '{}'", collection_name),
-                                    details: Some(sql.to_string()),
-                                })
-                            ));
+                            return Err(ApiError::bad_request("invalid_collection_name", format!("集合名包含无效字符: '{}'", collection_name)).with_details(sql.to_string()));
                         }
                         
                         // 执行更新操作前再次验证集合名
@@ -2381,6 +3350,10 @@ async fn execute_query(
                                     page_size: None,
                                     has_more: false,
                                     performance: None,
+                                    query_id: None,
+                                    next_cursor: None,
+                                    cached: false,
+                                    rows_affected: None,
                                 }
                             },
                             Err(e) => {
@@ -2392,201 +3365,82 @@ async fn execute_query(
                                 log::error!("[MongoDB Update] Update文档序列化: {:?}", 
                                            mongodb::bson::to_bson(&update).ok());
                                 
-                                return Err((
-                                    StatusCode::BAD_REQUEST,
-                                    Json(ModelErrorResponse {
-                                        error: "update_error".to_string(),
-                                        message: format!("MongoDB更新操作失败: {} (数据库: '{}', 集合: '{}', 命名空间: '{}.{}')", e, db_name, clean_collection_name, db_name, clean_collection_name),
-                                        details: Some(format!("数据库: '{}', 集合: '{}', 命名空间: '{}.{}', filter: {:?}, update: {:?}", db_name, clean_collection_name, db_name, clean_collection_name, filter, update)),
-                                    })
-                                ));
+                                return Err(ApiError::bad_request("update_error", format!("MongoDB更新操作失败: {} (数据库: '{}', 集合: '{}', 命名空间: '{}.{}')", e, db_name, clean_collection_name, db_name, clean_collection_name)).with_details(format!("数据库: '{}', 集合: '{}', 命名空间: '{}.{}', filter: {:?}, update: {:?}", db_name, clean_collection_name, db_name, clean_collection_name, filter, update)));
                             }
                         }
                     } else {
-                        return Err((
-                            StatusCode::BAD_REQUEST,
-                            Json(ModelErrorResponse {
-                                error: "invalid_update_syntax".to_string(),
-                                message: "MongoDB更新语句格式错误：无法解析参数".to_string(),
-                                details: None,
-                            })
-                        ));
+                        return Err(ApiError::bad_request("invalid_update_syntax", "MongoDB更新语句格式错误：无法解析参数"));
                     }
                 } else {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "invalid_update_syntax".to_string(),
-                            message: "MongoDB更新语句格式错误：找不到updateOne或updateMany方法".to_string(),
-                            details: None,
-                        })
-                    ));
+                    return Err(ApiError::bad_request("invalid_update_syntax", "MongoDB更新语句格式错误：找不到updateOne或updateMany方法"));
                 }
             } else {
-                // 原有的查询逻辑（find/aggregate）
-                // 解析集合名
-                let collection_name = if sql.starts_with("db.getCollection(") {
-                    // 格式：db.getCollection("collection_name").find() 或 db.getCollection("collection_name").aggregate()
-                    let method_split = if sql.contains(".find(") {
-                        ".find("
-                    } else if sql.contains(".aggregate(") {
-                        ".aggregate("
-                    } else {
-                        "."
-                    };
-                    
-                    if let Some(collection_match) = sql.split(method_split).next() {
-                        if let Some(name) = collection_match.split('"').nth(1) {
-                            name.to_string()
-                        } else {
-                            // 尝试单引号
-                            collection_match.split("'").nth(1).unwrap_or_default().to_string()
-                        }
-                    } else {
-                        sql.to_string()
-                    }
-                } else if sql.starts_with("db.") {
-                    // 格式：db.collection_name.find() 或 db.collection_name.aggregate()
-                    let method_split = if sql.contains(".find(") {
-                        ".find("
-                    } else if sql.contains(".aggregate(") {
-                        ".aggregate("
-                    } else {
-                        "."
-                    };
-                    
-                    if let Some(collection_part) = sql.split(method_split).next() {
-                        collection_part.split('.').nth(1).unwrap_or_default().to_string()
-                    } else {
-                        sql.to_string()
-                    }
-                } else {
-                    // 直接的集合名
-                    sql.to_string()
-                };
-                
-                let collection = database.collection::<mongodb::bson::Document>(&collection_name);
+                // 查询逻辑（find/count/distinct/aggregate），使用Shell语法解析器解析整条链式调用
+                let mongo_query = bson_parser::parse_shell_query(sql).map_err(|e| ApiError::bad_request("invalid_mongodb_syntax", format!("MongoDB语句解析失败: {}", e)).with_details(sql.to_string()))?;
+                log::info!("[MongoDB Query] 解析结果 - 集合: '{}', 操作: {:?}", mongo_query.collection, mongo_query.operation);
                 
-                // 解析find()方法的参数：find(query, projection)
-                let mut query = None;
-                let mut projection = None;
+                let collection = database.collection::<mongodb::bson::Document>(&mongo_query.collection);
+                let query_error = |action: &str, e: mongodb::error::Error| ApiError::bad_request("query_error", format!("MongoDB{}失败: {}", action, e));
                 
-                // 查找find()方法的参数部分
-                if let Some(find_params) = sql.split_once(".find(") {
-                    let params_part = find_params.1;
-                    // 找到find()方法的结束括号
-                    if let Some(end_idx) = find_close_bracket(params_part) {
-                        let params_str = &params_part[..end_idx];
-                        
-                        // 解析参数
-                        let params: Vec<&str> = split_params(params_str);
+                let documents: Vec<mongodb::bson::Document> = match mongo_query.operation {
+                    MongoOperation::Find { filter, projection, sort, skip, limit } => {
+                        let mut options = mongodb::options::FindOptions::default();
+                        options.projection = projection;
+                        options.sort = sort;
+                        options.skip = skip;
+                        options.limit = Some(limit);
                         
-                        // 第一个参数是查询条件
-                        if let Some(query_str) = params.first() {
-                            let trimmed = query_str.trim();
-                            if !trimmed.is_empty() && trimmed != "{}" {
-                                // 使用新的辅助函数解析JSON字符串，正确处理$oid等扩展JSON格式
-                                match json_str_to_bson_document(trimmed) {
-                                    Ok(bson_doc) => {
-                                        query = Some(bson_doc);
-                                    }
-                                    Err(e) => {
-                                        log::warn!("[MongoDB Find] 查询条件解析失败: {}, query_str: {}", e, trimmed);
-                                    }
-                                }
+                        // 分页：在语句自身的skip之后跳过前面的文档，多取一条用于判断是否还有下一页
+                        if let Some(p) = &pagination {
+                            options.skip = Some(skip.unwrap_or(0) + p.offset);
+                            options.limit = Some((p.page_size + 1) as i64);
+                            
+                            if payload.count_total {
+                                let total = collection.count_documents(filter.clone(), None).await
+                                    .map_err(|e| query_error("统计文档数", e))?;
+                                mongo_total = Some(total.saturating_sub(skip.unwrap_or(0)));
                             }
                         }
                         
-                        // 第二个参数是投影
-                        if let Some(projection_str) = params.get(1) {
-                            let trimmed = projection_str.trim();
-                            if !trimmed.is_empty() && trimmed != "{}" {
-                                // 尝试解析投影参数
-                                let parsed_projection = parse_mongodb_projection(trimmed);
-                                if let Ok(doc) = parsed_projection {
-                                    projection = Some(doc);
-                                } else {
-                                    log::warn!("[MongoDB Find] 投影参数解析失败: {}, 尝试使用JSON解析", parsed_projection.unwrap_err());
-                                    // 回退到使用JSON解析，但使用新的辅助函数以支持$oid
-                                    match json_str_to_bson_document(trimmed) {
-                                        Ok(bson_doc) => {
-                                            projection = Some(bson_doc);
-                                        }
-                                        Err(e) => {
-                                            log::warn!("[MongoDB Find] 投影参数JSON解析也失败: {}", e);
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        let cursor = collection.find(filter, Some(options)).await
+                            .map_err(|e| query_error("查询执行", e))?;
+                        cursor.try_collect().await
+                            .map_err(|e| query_error("查询结果获取", e))?
                     }
-                }
-                
-                // 执行查询
-                let mut options = mongodb::options::FindOptions::default();
-                
-                // 设置投影参数
-                options.projection = projection;
-                
-                // 添加LIMIT限制
-                // 检查查询中是否已经包含limit
-                let has_limit = sql_lower.contains(" limit") || sql_lower.contains(".limit(");
-                
-                if has_limit {
-                    // 如果有limit，提取limit值并限制在1500以内
-                    if let Some(limit_index) = sql_lower.find(".limit(") {
-                        let after_limit = &sql[limit_index + 7..];
-                        
-                        // 查找limit后面的数字
-                        let mut limit_value = String::new();
-                        for c in after_limit.chars() {
-                            if c.is_ascii_digit() {
-                                limit_value.push(c);
-                            } else if c.is_whitespace() {
-                                continue;
-                            } else {
-                                break;
-                            }
+                    MongoOperation::Count { filter } => {
+                        let count = collection.count_documents(filter, None).await
+                            .map_err(|e| query_error("统计文档数", e))?;
+                        vec![mongodb::bson::doc! { "count": count as i64 }]
+                    }
+                    MongoOperation::Distinct { field, filter } => {
+                        let values = collection.distinct(&field, filter, None).await
+                            .map_err(|e| query_error("distinct查询", e))?;
+                        values.into_iter()
+                            .take(bson_parser::MAX_LIMIT as usize)
+                            .map(|value| {
+                                let mut doc = mongodb::bson::Document::new();
+                                doc.insert(field.clone(), value);
+                                doc
+                            })
+                            .collect()
+                    }
+                    MongoOperation::Aggregate { mut pipeline } => {
+                        // 分页：追加 $skip / $limit 阶段
+                        if let Some(p) = &pagination {
+                            pipeline.push(mongodb::bson::doc! { "$skip": p.offset as i64 });
+                            pipeline.push(mongodb::bson::doc! { "$limit": (p.page_size + 1) as i64 });
                         }
-                        
-                        // 解析limit值
-                        let limit = limit_value.parse::<i64>().unwrap_or(200);
-                        // 限制在1500以内
-                        options.limit = Some(limit.min(1500));
-                    } else {
-                        // 默认限制
-                        options.limit = Some(200);
+                        let cursor = collection.aggregate(pipeline, None).await
+                            .map_err(|e| query_error("聚合查询执行", e))?;
+                        cursor.try_collect().await
+                            .map_err(|e| query_error("聚合结果获取", e))?
                     }
-                } else {
-                    // 没有limit，添加默认limit 200
-                    options.limit = Some(200);
-                }
-                
-                let cursor = collection.find(query, Some(options)).await
-                    .map_err(|e| (
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "query_error".to_string(),
-                            message: format!("MongoDB查询执行失败: {}", e),
-                            details: None,
-                        })
-                    ))?;
-                
-                // 获取所有文档
-                let documents: Vec<mongodb::bson::Document> = cursor.try_collect().await
-                    .map_err(|e| (
-                        StatusCode::BAD_REQUEST,
-                        Json(ModelErrorResponse {
-                            error: "query_error".to_string(),
-                            message: format!("MongoDB查询结果获取失败: {}", e),
-                            details: None,
-                        })
-                    ))?;
+                };
+                query_elapsed = Some(start.elapsed());
                 
                 // 提取所有唯一列名 - 直接从文档中提取，因为MongoDB驱动已经根据投影参数过滤了字段
                 let mut all_columns = std::collections::HashSet::new();
                 for doc in &documents {
-                    // 使用iter()方法获取键值对，这样可以更明确地获取键的类型
                     for (key, _) in doc.iter() {
                         all_columns.insert(key.to_string());
                     }
@@ -2625,17 +3479,187 @@ async fn execute_query(
                     page_size: None,
                     has_more: false,
                     performance: None,
+                    query_id: None,
+                    next_cursor: None,
+                    cached: false,
+                    rows_affected: None,
                 }
             }
         }
+        crate::db::DatabasePool::Redis(conn) => {
+            // Redis命令（GET/SET/HGETALL/SCAN/TTL等），结果转换为表格
+            let args = crate::db::redis_client::parse_command(&payload.sql).map_err(|e| ApiError::bad_request("invalid_redis_command", e).with_details(payload.sql.clone()))?;
+            log::info!("[Redis Query] 执行命令: {}", args[0]);
+            
+            let (columns, rows) = crate::db::redis_client::execute(conn, &args).await.map_err(|e| ApiError::bad_request("query_error", format!("Redis命令执行失败: {}", e)))?;
+            query_elapsed = Some(start.elapsed());
+            
+            SqlQueryResult {
+                row_count: rows.len(),
+                columns,
+                rows,
+                execution_time_ms: start.elapsed().as_millis(),
+                total_rows: None,
+                page: None,
+                page_size: None,
+                has_more: false,
+                performance: None,
+                query_id: None,
+                next_cursor: None,
+                cached: false,
+                rows_affected: None,
+            }
+        }
+        crate::db::DatabasePool::DuckDB(db) => {
+            // 为SQL语句添加LIMIT限制
+            let limited_sql = match &pagination {
+                Some(p) => build_paged_sql(db_manager.db_type, &payload.sql, p, query_params.len())?,
+                None if payload.ignore_limit => payload.sql.clone(),
+                None => add_limit_to_sql(db_manager.db_type, &payload.sql, settings),
+            };
+            log::info!("[DuckDB Query] 执行查询: {}", limited_sql);
+            
+            let result = db.query(&limited_sql).await
+                .map_err(|e| ApiError::bad_request("query_error", format!("查询执行失败: {}", e)))?;
+            query_elapsed = Some(start.elapsed());
+            
+            SqlQueryResult {
+                row_count: result.rows.len(),
+                columns: result.columns,
+                rows: result.rows,
+                execution_time_ms: start.elapsed().as_millis(),
+                total_rows: None,
+                page: None,
+                page_size: None,
+                has_more: false,
+                performance: None,
+                query_id: None,
+                next_cursor: None,
+                cached: false,
+                rows_affected: None,
+            }
+        }
     };
     
-    info!("[API] POST /api/database/query - 响应成功: 行数={}, 执行时间={}ms", 
-        result.row_count, result.execution_time_ms);
-    if let Ok(resp_json) = serde_json::to_string(&result) {
-        log::info!("[API] POST /api/database/query - 响应体: {}", resp_json);
+    // 填充分页信息
+    if let Some(p) = pagination {
+        result.has_more = result.rows.len() as u64 > p.page_size;
+        result.rows.truncate(p.page_size as usize);
+        result.row_count = result.rows.len();
+        result.page = Some(p.page);
+        result.page_size = Some(p.page_size);
+        
+        if payload.count_total {
+            result.total_rows = match mongo_total {
+                Some(total) => Some(total),
+                None => count_total_rows(db_manager, &payload.sql, query_params).await?,
+            };
+        }
+        
+        // 游标分页：由本页最后一行生成下一页的游标
+        if let (Some(keyset), true) = (&p.keyset, result.has_more) {
+            if let Some(last_row) = result.rows.last() {
+                result.next_cursor = Some(next_cursor(keyset, &result.columns, last_row)
+                    .map_err(|e| ApiError::bad_request("pagination_error", e))?);
+            }
+        }
+    }
+    
+    // 填充性能信息
+    let total_elapsed = start.elapsed();
+    let query_time = query_elapsed.unwrap_or(total_elapsed);
+    let fetch_time = total_elapsed.saturating_sub(query_time);
+    let mut performance = QueryPerformance::new(
+        query_time.as_millis(),
+        fetch_time.as_millis(),
+        rows_examined.unwrap_or(result.row_count),
+        result.row_count,
+        settings.slow_query_threshold_ms,
+    );
+    if examined_estimated {
+        performance.warnings.push("扫描行数为EXPLAIN估算值".to_string());
+    }
+    if result.has_more && result.page.is_none() {
+        performance.warnings.push("结果已被LIMIT截断".to_string());
+    }
+    result.performance = Some(performance);
+    
+    Ok(result)
+}
+
+// 辅助函数：读取MySQL会话的Handler_read*计数之和（近似扫描行数）
+async fn mysql_handler_reads(conn: &mut sqlx::pool::PoolConnection<sqlx::MySql>) -> Option<u64> {
+    let rows = sqlx::query_as::<_, (String, String)>("SHOW SESSION STATUS LIKE 'Handler_read%'")
+        .fetch_all(&mut **conn)
+        .await
+        .ok()?;
+    
+    Some(rows.iter().filter_map(|(_, value)| value.parse::<u64>().ok()).sum())
+}
+
+// 辅助函数：从PostgreSQL文本格式的EXPLAIN结果中累加扫描节点的估算行数
+fn estimate_scanned_rows(plan_lines: &[String]) -> usize {
+    lazy_static::lazy_static! {
+        static ref ROWS_RE: regex::Regex = regex::Regex::new(r"rows=(\d+)").unwrap();
+    }
+    
+    plan_lines.iter()
+        .filter(|line| line.contains("Scan"))
+        .filter_map(|line| ROWS_RE.captures(line))
+        .filter_map(|caps| caps[1].parse::<usize>().ok())
+        .sum()
+}
+
+// 终止正在执行的服务端查询
+// SQLite/MongoDB 查询在丢弃future后即中断，无需额外处理
+async fn kill_backend_query(db_manager: &DatabaseManager, backend_id: Option<i64>) {
+    let Some(id) = backend_id else {
+        return;
+    };
+    
+    let result = match &db_manager.pool {
+        crate::db::DatabasePool::MySQL(pool) => {
+            sqlx::query(&format!("KILL QUERY {}", id))
+                .execute(pool)
+                .await
+                .map(|_| ())
+        }
+        crate::db::DatabasePool::PostgreSQL(pool) => {
+            sqlx::query("SELECT pg_cancel_backend($1)")
+                .bind(id as i32)
+                .execute(pool)
+                .await
+                .map(|_| ())
+        }
+        _ => Ok(()),
+    };
+    
+    match result {
+        Ok(()) => info!("[API] 已终止服务端查询: 会话ID={}", id),
+        Err(e) => warn!("[API] 终止服务端查询失败: 会话ID={}, 错误: {}", id, e),
+    }
+}
+
+// 终止正在执行的查询：会话模式下会话唯一的专用连接正在执行查询，通过连接的共享连接池发送终止语句
+async fn stop_backend_query(
+    db_manager: &DatabaseManager,
+    in_session: bool,
+    connection: &DbConnection,
+    database: Option<&str>,
+    backend_id: Option<i64>,
+) {
+    if !in_session || backend_id.is_none() {
+        return kill_backend_query(db_manager, backend_id).await;
+    }
+    let shared = match build_connection_string(connection) {
+        Ok(conn_str) => crate::db::pool_cache::get_or_connect_database(connection.id, database, &conn_str, &connection.pool_options, &connection.options.init_sql).await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.message().to_string()),
+    };
+    match shared {
+        Ok(shared) => kill_backend_query(&shared, backend_id).await,
+        Err(e) => warn!("[API] 终止会话中的查询失败: {}", e),
     }
-    Ok(Json(result))
 }
 
 // 查询取消管理器（存储正在执行的查询）
@@ -2643,109 +3667,267 @@ async fn execute_query(
 static QUERY_CANCELLERS: std::sync::OnceLock<QueryCancellerMap> = 
     std::sync::OnceLock::new();
 
-fn get_query_cancellers() -> QueryCancellerMap {
+pub(crate) fn get_query_cancellers() -> QueryCancellerMap {
     QUERY_CANCELLERS.get_or_init(|| Arc::new(Mutex::new(HashMap::new()))).clone()
 }
 
+// 已注册的取消通道，离开作用域时从取消管理器中移除
+pub(crate) struct CancellerGuard {
+    query_id: String,
+}
+
+impl Drop for CancellerGuard {
+    fn drop(&mut self) {
+        if let Ok(mut cancellers) = get_query_cancellers().lock() {
+            cancellers.remove(&self.query_id);
+        }
+    }
+}
+
+// 离开作用域时终止后台任务（客户端断开、请求被丢弃时不会遗留推送进度的任务）
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+// 注册查询的取消通道，返回的守卫需持有到查询结束；查询ID正在执行中时返回None
+pub(crate) fn register_canceller(query_id: &str) -> Option<(CancellerGuard, tokio::sync::oneshot::Receiver<()>)> {
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    let cancellers = get_query_cancellers();
+    let mut cancellers = cancellers.lock().unwrap();
+    if cancellers.contains_key(query_id) {
+        return None;
+    }
+    cancellers.insert(query_id.to_string(), cancel_tx);
+    Some((CancellerGuard { query_id: query_id.to_string() }, cancel_rx))
+}
+
+// 表结构缓存（AI接口与表结构接口共用）
+static SCHEMA_CACHE: std::sync::OnceLock<SchemaCache<ApiTableSchema>> = std::sync::OnceLock::new();
+
+pub(crate) fn get_schema_cache() -> &'static SchemaCache<ApiTableSchema> {
+    SCHEMA_CACHE.get_or_init(|| SchemaCache::new(DEFAULT_SCHEMA_CACHE_TTL))
+}
+
+// 获取表列表，优先读取缓存（没有连接ID时不缓存）
+pub(crate) async fn cached_table_names(
+    connection_id: Option<i64>,
+    db_manager: &DatabaseManager,
+) -> Result<Vec<String>, crate::db::DatabaseError> {
+    if let Some(tables) = connection_id.and_then(|id| get_schema_cache().tables(id)) {
+        return Ok(tables);
+    }
+    let tables = db_manager.get_schema().await?;
+    if let Some(id) = connection_id {
+        get_schema_cache().put_tables(id, tables.clone());
+    }
+    Ok(tables)
+}
+
+// 获取表结构，优先读取缓存（没有连接ID时不缓存）
+pub(crate) async fn cached_table_structure(
+    connection_id: Option<i64>,
+    db_manager: &DatabaseManager,
+    table_name: &str,
+) -> Result<ApiTableSchema, String> {
+    if let Some(schema) = connection_id.and_then(|id| get_schema_cache().structure(id, table_name)) {
+        return Ok(schema);
+    }
+    let schema = get_table_structure_internal(db_manager, table_name).await?;
+    if let Some(id) = connection_id {
+        get_schema_cache().put_structure(id, table_name, schema.clone());
+    }
+    Ok(schema)
+}
+
+static RESULT_CACHE: std::sync::OnceLock<ResultCache<SqlQueryResult>> = std::sync::OnceLock::new();
+
+// 获取全局查询结果缓存
+pub(crate) fn get_result_cache() -> &'static ResultCache<SqlQueryResult> {
+    RESULT_CACHE.get_or_init(ResultCache::new)
+}
+
+// 查询超时：优先使用请求指定的超时，其次是连接设置的默认超时，都未设置时为 DEFAULT_QUERY_TIMEOUT_SECS
+fn connection_query_timeout(connection: &DbConnection, payload: &SqlQueryRequest) -> Duration {
+    let secs = payload.timeout_secs
+        .or(connection.options.timeout_secs)
+        .unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+// 生成查询结果缓存键，未启用缓存、连接未保存或不是只读查询时返回None
+fn result_cache_key(connection: &DbConnection, database: Option<&str>, payload: &SqlQueryRequest, settings: &AppSettings) -> Option<ResultCacheKey> {
+    let connection_id = connection.id?;
+    if settings.result_cache_ttl_secs == 0
+        || !result_cache::is_cacheable(crate::db::DatabaseType::from_name(&connection.db_type), &payload.sql)
+    {
+        return None;
+    }
+    // 分页、行数限制等选项不同时结果不同
+    let options = format!(
+        "page={:?};size={:?};count={};ignore_limit={};limit={}/{};keyset={:?};desc={};cursor={:?}",
+        payload.page, payload.page_size, payload.count_total, payload.ignore_limit,
+        settings.default_query_limit, settings.max_query_limit,
+        payload.keyset_columns, payload.keyset_descending, payload.cursor,
+    );
+    Some(ResultCacheKey::new(connection_id, database, &payload.sql, payload.parameters.as_deref().unwrap_or(&[]), options))
+}
+
+// 清除查询结果缓存请求（不指定连接时清除全部）
+#[derive(Debug, Default, Deserialize)]
+struct ResultCacheClearRequest {
+    connection_id: Option<i64>,
+}
+
+/**
+ * 清除查询结果缓存处理函数
+ */
+async fn clear_result_cache(
+    payload: Option<Json<ResultCacheClearRequest>>,
+) -> Json<serde_json::Value> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    info!("[API] POST /api/database/query/cache/clear - 清除查询结果缓存请求: connection_id={:?}", payload.connection_id);
+
+    let cleared = match payload.connection_id {
+        Some(id) => get_result_cache().invalidate(id),
+        None => get_result_cache().invalidate_all(),
+    };
+
+    Json(serde_json::json!({
+        "success": true,
+        "cleared": cleared,
+        "message": format!("已清除 {} 条查询结果缓存", cleared),
+    }))
+}
+
+// 清除表结构缓存请求（不指定连接时清除全部）
+#[derive(Debug, Default, Deserialize)]
+struct SchemaRefreshRequest {
+    connection_id: Option<i64>,
+}
+
+/**
+ * 清除表结构缓存处理函数
+ */
+async fn refresh_schema_cache(
+    payload: Option<Json<SchemaRefreshRequest>>,
+) -> Json<serde_json::Value> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    info!("[API] POST /api/database/schema/refresh - 清除表结构缓存请求: connection_id={:?}", payload.connection_id);
+
+    let cleared = match payload.connection_id {
+        Some(id) => get_schema_cache().invalidate(id),
+        None => get_schema_cache().invalidate_all(),
+    };
+    events::publish(ServerEvent::SchemaRefreshed {
+        connection_id: payload.connection_id,
+        cleared_tables: cleared,
+        timestamp: events::now(),
+    });
+
+    Json(serde_json::json!({
+        "success": true,
+        "message": format!("已清除 {} 张表的结构缓存", cleared),
+    }))
+}
+
 // 批量执行SQL查询处理函数
 // TODO: 实现从活动连接动态创建DatabaseManager
 async fn execute_batch_query(
     Json(_payload): Json<BatchSqlRequest>
-) -> Result<Json<BatchSqlResult>, (StatusCode, Json<ModelErrorResponse>)> {
-    Err((
-        StatusCode::NOT_IMPLEMENTED,
-        Json(ModelErrorResponse {
-            error: "not_implemented".to_string(),
-            message: "此功能正在开发中，请先配置数据库连接".to_string(),
-            details: None,
-        })
-    ))
+) -> Result<Json<BatchSqlResult>, ApiError> {
+    Err(ApiError::not_implemented("not_implemented", "此功能正在开发中，请先配置数据库连接"))
 }
 
 // 获取执行计划处理函数
+#[utoipa::path(
+    post,
+    path = "/api/database/query/explain",
+    tag = "database",
+    request_body = ExecutionPlanRequest,
+    responses(
+        (status = 200, description = "执行计划", body = ExecutionPlanResponse),
+        (status = 400, description = "SQL无效", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn get_execution_plan(
     Extension(storage): Extension<LocalStorageManager>,
     Extension(ai_service): Extension<Option<crate::services::ai::AiService>>,
     Json(payload): Json<ExecutionPlanRequest>
-) -> Result<Json<ExecutionPlanResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<ExecutionPlanResponse>, ApiError> {
     info!("[API] POST /api/database/query/explain - 请求: SQL长度={}", payload.sql.len());
     debug!("[API] POST /api/database/query/explain - SQL内容: {}", payload.sql);
     if let Ok(req_json) = serde_json::to_string(&payload) {
-        log::info!("[API] POST /api/database/query/explain - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/database/query/explain - 请求体: {}", req_json);
+    }
+    
+    // EXPLAIN ANALYZE 会实际执行语句，先确认是只读的单条SELECT
+    if payload.analyze {
+        crate::utils::security::ensure_select_only(&payload.sql)
+            .map_err(|e| ApiError::bad_request("analyze_not_allowed", format!("EXPLAIN ANALYZE 仅支持SELECT语句: {}", e)))?;
     }
     
     // 获取要查询的连接
     let connection = if let Some(conn_id) = payload.connection_id {
         // 使用指定的连接ID
         storage.get_connection_by_id(conn_id).await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?
-            .ok_or_else(|| (
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "connection_not_found".to_string(),
-                    message: format!("连接ID {}不存在", conn_id),
-                    details: None,
-                })
-            ))?
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("connection_not_found", format!("连接ID {}不存在", conn_id)))?
     } else {
         // 使用第一个活动连接
         let active_conns = storage.get_active_connections().await
-            .map_err(|e| (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "database_error".to_string(),
-                    message: format!("获取连接失败: {}", e),
-                    details: None,
-                })
-            ))?;
+            .map_err(|e| ApiError::db("database_error", format!("获取连接失败: {}", e)))?;
         
-        active_conns.into_iter().next().ok_or_else(|| (
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "no_connection".to_string(),
-                message: "请先激活一个数据库连接".to_string(),
-                details: None,
-            })
-        ))?
+        active_conns.into_iter().next().ok_or_else(|| ApiError::bad_request("no_connection", "请先激活一个数据库连接"))?
     };
     
     // 构建连接字符串
     let conn_str = build_connection_string(&connection)?;
     
     // 创建数据库管理器
-    let db_manager = DatabaseManager::from_connection_string(&conn_str).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "connection_failed".to_string(),
-                message: format!("数据库连接失败: {}", e),
-                details: None,
-            })
-        ))?;
+    let db_manager = crate::db::pool_cache::get_or_connect(connection.id, &conn_str, &connection.pool_options, &connection.options.init_sql).await
+        .map_err(|e| ApiError::internal("connection_failed", format!("数据库连接失败: {}", e)))?;
+    
+    // EXPLAIN ANALYZE 会实际执行语句，仅支持 MySQL / PostgreSQL
+    if payload.analyze && !matches!(db_manager.pool, crate::db::DatabasePool::MySQL(_) | crate::db::DatabasePool::PostgreSQL(_)) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?} 不支持 EXPLAIN ANALYZE", db_manager.db_type)));
+    }
     
     // 执行EXPLAIN查询获取执行计划
     let mut result = match &db_manager.pool {
+        crate::db::DatabasePool::MySQL(pool) if payload.analyze => {
+            // MySQL 8.0.18+ EXPLAIN ANALYZE（树形文本输出）
+            let explain_sql = format!("EXPLAIN ANALYZE {}", payload.sql);
+            let output: String = sqlx::query_scalar(&explain_sql)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| ApiError::bad_request("explain_error", format!("执行计划查询失败（EXPLAIN ANALYZE 需要 MySQL 8.0.18 及以上版本）: {}", e)).with_details(explain_sql.clone()))?;
+            
+            let parsed = explain_parser::parse_mysql_tree_plan(&output);
+            let mut query_plan = String::new();
+            query_plan.push_str("MySQL执行计划（EXPLAIN ANALYZE）\n");
+            query_plan.push_str("============================================================\n");
+            query_plan.push_str(&parsed.text);
+            
+            ExecutionPlanResponse {
+                plan: parsed.nodes,
+                query_plan: Some(query_plan),
+                planning_time: None,
+                execution_time: None,
+                ai_optimization_advice: None,
+                ai_optimized_sql: None,
+            }
+        },
         crate::db::DatabasePool::MySQL(pool) => {
             // MySQL执行计划
             let explain_sql = format!("EXPLAIN {}", payload.sql);
             let rows = sqlx::query(&explain_sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "explain_error".to_string(),
-                        message: format!("执行计划查询失败: {}", e),
-                        details: Some(explain_sql),
-                    })
-                ))?;
+                .map_err(|e| ApiError::bad_request("explain_error", format!("执行计划查询失败: {}", e)).with_details(explain_sql))?;
             
             // 转换为ExecutionPlanNode
             let mut plan_nodes = Vec::new();
@@ -2809,6 +3991,7 @@ async fn get_execution_plan(
                     width: None, // MySQL不直接返回width
                     filter: Some(extra),
                     join_type: Some(join_type),
+                    actual: None,
                 });
             }
             
@@ -2821,81 +4004,42 @@ async fn get_execution_plan(
                 ai_optimized_sql: None,
             }
         },
-        crate::db::DatabasePool::PostgreSQL(pool) => {
-            // PostgreSQL执行计划
-            let explain_sql = format!("EXPLAIN (ANALYZE false, VERBOSE false, FORMAT TEXT) {}", payload.sql);
-            let rows = sqlx::query(&explain_sql)
-                .fetch_all(pool)
-                .await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "explain_error".to_string(),
-                        message: format!("执行计划查询失败: {}", e),
-                        details: Some(explain_sql),
-                    })
-                ))?;
-            
-            // 转换为ExecutionPlanNode
-            let mut plan_nodes = Vec::new();
-            let mut query_plan = String::new();
-            
-            // 添加执行计划标题
-            query_plan.push_str("PostgreSQL执行计划\n");
-            query_plan.push_str("============================================================\n");
-            
-            // 解析执行计划行
-            for (i, row) in rows.iter().enumerate() {
-                let id = i as i32;
-                let parent = if i > 0 { Some(i as i32 - 1) } else { None };
-                
-                // 提取执行计划文本
-                let plan_text: String = row.try_get(0).unwrap_or("未知".to_string());
-                
-                // 构建detail字符串
-                let detail = format!("执行步骤 {}: {}", id + 1, plan_text);
-                
-                // 添加到query_plan
-                query_plan.push_str(&format!("{}\n", plan_text));
-                
-                plan_nodes.push(ExecutionPlanNode {
-                    id,
-                    parent,
-                    detail,
-                    operation: None,
-                    table: None,
-                    index: None,
-                    cost: None,
-                    rows: None,
-                    width: None,
-                    filter: None,
-                    join_type: None,
-                });
-            }
-            
-            ExecutionPlanResponse {
-                plan: plan_nodes,
-                query_plan: Some(query_plan),
-                planning_time: None,
-                execution_time: None,
-                ai_optimization_advice: None,
-                ai_optimized_sql: None,
-            }
-        },
+        crate::db::DatabasePool::PostgreSQL(pool) => {
+            // PostgreSQL执行计划（JSON格式，解析为计划树）
+            let explain_sql = if payload.analyze {
+                format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", payload.sql)
+            } else {
+                format!("EXPLAIN (FORMAT JSON) {}", payload.sql)
+            };
+            let plan_json: serde_json::Value = sqlx::query_scalar(&explain_sql)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| ApiError::bad_request("explain_error", format!("执行计划查询失败: {}", e)).with_details(explain_sql.clone()))?;
+            
+            let parsed = explain_parser::parse_pg_json_plan(&plan_json)
+                .map_err(|e| ApiError::internal("explain_parse_error", e).with_details(explain_sql.clone()))?;
+            
+            let mut query_plan = String::new();
+            query_plan.push_str("PostgreSQL执行计划\n");
+            query_plan.push_str("============================================================\n");
+            query_plan.push_str(&parsed.text);
+            
+            ExecutionPlanResponse {
+                plan: parsed.nodes,
+                query_plan: Some(query_plan),
+                planning_time: parsed.planning_time,
+                execution_time: parsed.execution_time,
+                ai_optimization_advice: None,
+                ai_optimized_sql: None,
+            }
+        },
         crate::db::DatabasePool::SQLite(pool) => {
             // SQLite执行计划
             let explain_sql = format!("EXPLAIN QUERY PLAN {}", payload.sql);
             let rows = sqlx::query(&explain_sql)
                 .fetch_all(pool)
                 .await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "explain_error".to_string(),
-                        message: format!("执行计划查询失败: {}", e),
-                        details: Some(explain_sql),
-                    })
-                ))?;
+                .map_err(|e| ApiError::bad_request("explain_error", format!("执行计划查询失败: {}", e)).with_details(explain_sql))?;
             
             // 转换为ExecutionPlanNode
             let mut plan_nodes = Vec::new();
@@ -2941,6 +4085,7 @@ async fn get_execution_plan(
                     width: None,
                     filter: None,
                     join_type: None,
+                    actual: None,
                 });
             }
             
@@ -3050,25 +4195,11 @@ async fn get_execution_plan(
             
             // 执行explain命令
             let explain_result = database.run_command(explain_command, None).await
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "explain_error".to_string(),
-                        message: format!("MongoDB执行计划查询失败: {}", e),
-                        details: Some(payload.sql.clone()),
-                    })
-                ))?;
+                .map_err(|e| ApiError::bad_request("explain_error", format!("MongoDB执行计划查询失败: {}", e)).with_details(payload.sql.clone()))?;
             
             // 转换为JSON字符串，以便显示
             let explain_json = serde_json::to_string_pretty(&explain_result)
-                .map_err(|e| (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ModelErrorResponse {
-                        error: "json_serialize_error".to_string(),
-                        message: format!("执行计划序列化失败: {}", e),
-                        details: None,
-                    })
-                ))?;
+                .map_err(|e| ApiError::internal("json_serialize_error", format!("执行计划序列化失败: {}", e)))?;
             
             // 构建执行计划节点
             let plan_nodes = vec![ExecutionPlanNode {
@@ -3083,6 +4214,7 @@ async fn get_execution_plan(
                 width: None,
                 filter: None,
                 join_type: None,
+                actual: None,
             }];
             
             ExecutionPlanResponse {
@@ -3094,6 +4226,40 @@ async fn get_execution_plan(
                 ai_optimized_sql: None,
             }
         },
+        crate::db::DatabasePool::Redis(_) => {
+            return Err(ApiError::bad_request("unsupported_database", "Redis不支持执行计划"));
+        },
+        crate::db::DatabasePool::DuckDB(db) => {
+            // EXPLAIN 返回 (explain_key, explain_value)，explain_value 为文本形式的物理计划
+            let explained = db.query(&format!("EXPLAIN {}", payload.sql)).await
+                .map_err(|e| ApiError::bad_request("explain_error", format!("DuckDB执行计划查询失败: {}", e)).with_details(payload.sql.clone()))?;
+            let plan_text = explained.rows.iter()
+                .filter_map(|row| row.get(1).and_then(|v| v.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            
+            ExecutionPlanResponse {
+                plan: vec![ExecutionPlanNode {
+                    id: 0,
+                    parent: None,
+                    detail: plan_text.clone(),
+                    operation: Some("EXPLAIN".to_string()),
+                    table: None,
+                    index: None,
+                    cost: None,
+                    rows: None,
+                    width: None,
+                    filter: None,
+                    join_type: None,
+                    actual: None,
+                }],
+                query_plan: Some(plan_text),
+                planning_time: None,
+                execution_time: None,
+                ai_optimization_advice: None,
+                ai_optimized_sql: None,
+            }
+        },
     };
     
     // 调用AI服务生成优化建议
@@ -3121,9 +4287,19 @@ async fn get_execution_plan(
 }
 
 // 取消查询处理函数
+#[utoipa::path(
+    post,
+    path = "/api/database/query/{query_id}/cancel",
+    tag = "database",
+    params(("query_id" = String, Path, description = "执行查询时返回的查询ID")),
+    responses(
+        (status = 200, description = "已发送取消信号", body = Object),
+        (status = 404, description = "查询不存在或已结束", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn cancel_query(
     axum::extract::Path(query_id): axum::extract::Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     let cancellers = get_query_cancellers();
     let mut cancellers = cancellers.lock().unwrap();
     
@@ -3134,62 +4310,40 @@ async fn cancel_query(
             "message": "查询已取消"
         })))
     } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ModelErrorResponse {
-                error: "query_not_found".to_string(),
-                message: "查询不存在或已完成".to_string(),
-                details: None,
-            })
-        ))
+        Err(ApiError::not_found("query_not_found", "查询不存在或已完成"))
+    }
+}
+
+// 转换为模板响应
+fn template_response(template_manager: &TemplateManager, template: &PromptTemplate) -> TemplateResponse {
+    TemplateResponse {
+        template_id: template.template_id.clone(),
+        name: template.name.clone(),
+        description: template.description.clone(),
+        content: template.content.clone(),
+        template_type: template.template_type.clone(),
+        variables: template.variables.clone(),
+        default_variables: template.default_variables.clone(),
+        is_default: template_manager.is_default(template),
     }
 }
 
 // 获取模板列表处理函数
 async fn get_templates(
     Extension(template_manager): Extension<TemplateManager>,
-    axum::extract::Query(template_type): axum::extract::Query<Option<TemplateType>>
-) -> Result<Json<TemplateListResponse>, (StatusCode, Json<ModelErrorResponse>)> {
-    let templates = template_manager.get_available_templates();
-    
+    axum::extract::Query(query): axum::extract::Query<crate::models::TemplateListQuery>
+) -> Result<Json<TemplateListResponse>, ApiError> {
     // 过滤模板类型
-    let filtered_templates: Vec<&PromptTemplate> = if let Some(tt) = template_type {
-        templates.iter()
-            .filter(|t| t.template_id.contains(tt.as_str()))
-            .copied()
-            .collect()
-    } else {
-        templates.into_iter().collect()
+    let templates = match &query.template_type {
+        Some(template_type) => template_manager.get_templates_by_type(template_type),
+        None => template_manager.get_available_templates(),
     };
     
     // 转换为响应格式
-    let template_responses: Vec<TemplateResponse> = filtered_templates.iter().map(|t| {
-        // 确定是否为默认模板
-        let is_default = template_manager.default_templates.values()
-            .any(|default_id| default_id == &t.template_id);
-        
-        // 根据模板ID确定类型
-        let template_type = if t.template_id.contains("sql_generation") {
-            TemplateType::Generation
-        } else if t.template_id.contains("sql_explain") {
-            TemplateType::Explain
-        } else if t.template_id.contains("sql_optimize") {
-            TemplateType::Optimize
-        } else {
-            TemplateType::Generation
-        };
-        
-        TemplateResponse {
-            template_id: t.template_id.clone(),
-            name: t.name.clone(),
-            description: t.description.clone(),
-            content: t.content.clone(),
-            template_type,
-            variables: t.variables.clone(),
-            default_variables: t.default_variables.clone(),
-            is_default,
-        }
-    }).collect();
+    let mut template_responses: Vec<TemplateResponse> = templates.into_iter()
+        .map(|t| template_response(&template_manager, t))
+        .collect();
+    template_responses.sort_by(|a, b| a.template_id.cmp(&b.template_id));
     
     let total = template_responses.len();
     Ok(Json(TemplateListResponse {
@@ -3202,44 +4356,10 @@ async fn get_templates(
 async fn get_template(
     axum::extract::Path(template_id): axum::extract::Path<String>,
     Extension(template_manager): Extension<TemplateManager>,
-) -> Result<Json<TemplateResponse>, (StatusCode, Json<ModelErrorResponse>)> {
-    if let Some(template) = template_manager.get_template(&template_id) {
-        // 确定是否为默认模板
-        let is_default = template_manager.default_templates.values()
-            .any(|default_id| default_id == &template_id);
-        
-        // 根据模板ID确定类型
-        let template_type = if template_id.contains("sql_generation") {
-            TemplateType::Generation
-        } else if template_id.contains("sql_explain") {
-            TemplateType::Explain
-        } else if template_id.contains("sql_optimize") {
-            TemplateType::Optimize
-        } else {
-            TemplateType::Generation
-        };
-        
-        let response = TemplateResponse {
-            template_id: template.template_id.clone(),
-            name: template.name.clone(),
-            description: template.description.clone(),
-            content: template.content.clone(),
-            template_type,
-            variables: template.variables.clone(),
-            default_variables: template.default_variables.clone(),
-            is_default,
-        };
-        
-        Ok(Json(response))
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ModelErrorResponse {
-                error: "template_not_found".to_string(),
-                message: "模板不存在".to_string(),
-                details: None,
-            })
-        ))
+) -> Result<Json<TemplateResponse>, ApiError> {
+    match template_manager.get_template(&template_id) {
+        Some(template) => Ok(Json(template_response(&template_manager, template))),
+        None => Err(ApiError::not_found("template_not_found", "模板不存在")),
     }
 }
 
@@ -3247,7 +4367,7 @@ async fn get_template(
 async fn create_template(
     Extension(mut template_manager): Extension<TemplateManager>,
     Json(req): Json<TemplateRequest>
-) -> Result<Json<TemplateResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<TemplateResponse>, ApiError> {
     // 生成唯一模板ID
     let template_id = format!("{}_{}", req.template_type.as_str(), Uuid::new_v4());
     
@@ -3257,37 +4377,20 @@ async fn create_template(
         name: req.name.clone(),
         description: req.description.clone(),
         content: req.content.clone(),
+        template_type: req.template_type.clone(),
         variables: req.variables.clone(),
         default_variables: req.default_variables.clone(),
     };
     
     // 添加到模板管理器
-    match template_manager.add_template(prompt_template) {
+    match template_manager.add_template(prompt_template.clone()) {
         Ok(_) => {
-            let response = TemplateResponse {
-                template_id: template_id.clone(),
-                name: req.name.clone(),
-                description: req.description.clone(),
-                content: req.content.clone(),
-                template_type: req.template_type.clone(),
-                variables: req.variables.clone(),
-                default_variables: req.default_variables.clone(),
-                is_default: false,
-            };
-            
             info!("模板创建成功: {}", template_id);
-            Ok(Json(response))
+            Ok(Json(template_response(&template_manager, &prompt_template)))
         },
         Err(e) => {
             error!("创建模板失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "template_creation_failed".to_string(),
-                    message: format!("创建模板失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("template_creation_failed", format!("创建模板失败: {}", e)))
         }
     }
 }
@@ -3297,19 +4400,12 @@ async fn update_template(
     axum::extract::Path(template_id): axum::extract::Path<String>,
     Extension(mut template_manager): Extension<TemplateManager>,
     Json(req): Json<crate::models::UpdateTemplateRequest>
-) -> Result<Json<TemplateResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<TemplateResponse>, ApiError> {
     // 先获取并克隆模板
     let template = match template_manager.get_template(&template_id) {
         Some(t) => t.clone(),
         None => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ModelErrorResponse {
-                    error: "template_not_found".to_string(),
-                    message: "模板不存在".to_string(),
-                    details: None,
-                })
-            ));
+            return Err(ApiError::not_found("template_not_found", "模板不存在"));
         }
     };
     
@@ -3332,49 +4428,23 @@ async fn update_template(
     if let Some(default_variables) = &req.default_variables {
         updated_template.default_variables = default_variables.clone();
     }
+    if let Some(template_type) = &req.template_type {
+        // 默认模板改变类型后原类型将没有默认模板
+        if *template_type != updated_template.template_type && template_manager.is_default(&updated_template) {
+            return Err(ApiError::bad_request("cannot_change_default_template_type", "不能修改默认模板的类型"));
+        }
+        updated_template.template_type = template_type.clone();
+    }
     
     // 保存更新后的模板
     match template_manager.update_template(updated_template.clone()) {
         Ok(_) => {
-            // 确定是否为默认模板
-            let is_default = template_manager.default_templates.values()
-                .any(|default_id| default_id == &template_id);
-            
-            // 根据模板ID确定类型
-            let template_type = if template_id.contains("sql_generation") {
-                TemplateType::Generation
-            } else if template_id.contains("sql_explain") {
-                TemplateType::Explain
-            } else if template_id.contains("sql_optimize") {
-                TemplateType::Optimize
-            } else {
-                TemplateType::Generation
-            };
-            
-            let response = TemplateResponse {
-                template_id: updated_template.template_id.clone(),
-                name: updated_template.name.clone(),
-                description: updated_template.description.clone(),
-                content: updated_template.content.clone(),
-                template_type,
-                variables: updated_template.variables.clone(),
-                default_variables: updated_template.default_variables.clone(),
-                is_default,
-            };
-            
             info!("模板更新成功: {}", template_id);
-            Ok(Json(response))
+            Ok(Json(template_response(&template_manager, &updated_template)))
         },
         Err(e) => {
             error!("更新模板失败: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "template_update_failed".to_string(),
-                    message: format!("更新模板失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("template_update_failed", format!("更新模板失败: {}", e)))
         }
     }
 }
@@ -3384,20 +4454,13 @@ async fn update_template(
 async fn delete_template(
     axum::extract::Path(template_id): axum::extract::Path<String>,
     Extension(mut template_manager): Extension<TemplateManager>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     // 检查是否为默认模板
     let is_default = template_manager.default_templates.values()
         .any(|default_id| default_id == &template_id);
     
     if is_default {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "cannot_delete_default_template".to_string(),
-                message: "不能删除默认模板".to_string(),
-                details: None,
-            })
-        ));
+        return Err(ApiError::bad_request("cannot_delete_default_template", "不能删除默认模板"));
     }
     
     // 删除模板
@@ -3409,23 +4472,9 @@ async fn delete_template(
         Err(e) => {
             error!("删除模板失败: {:?}", e);
             if e.to_string().contains("not found") {
-                Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ModelErrorResponse {
-                        error: "template_not_found".to_string(),
-                        message: "模板不存在".to_string(),
-                        details: None,
-                    })
-                ))
+                Err(ApiError::not_found("template_not_found", "模板不存在"))
             } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ModelErrorResponse {
-                        error: "template_deletion_failed".to_string(),
-                        message: format!("删除模板失败: {}", e),
-                        details: None,
-                    })
-                ))
+                Err(ApiError::db("template_deletion_failed", format!("删除模板失败: {}", e)))
             }
         }
     }
@@ -3435,123 +4484,132 @@ async fn delete_template(
 async fn set_default_template(
     Extension(mut template_manager): Extension<TemplateManager>,
     Json(req): Json<crate::models::SetDefaultTemplateRequest>
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
-    // 检查模板是否存在并克隆名称
-    let template_name = match template_manager.get_template(&req.template_id) {
-        Some(template) => template.name.clone(),
+) -> Result<Json<serde_json::Value>, ApiError> {
+    // 检查模板是否存在
+    let template = match template_manager.get_template(&req.template_id) {
+        Some(template) => template.clone(),
         None => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ModelErrorResponse {
-                    error: "template_not_found".to_string(),
-                    message: "模板不存在".to_string(),
-                    details: None,
-                })
-            ));
+            return Err(ApiError::not_found("template_not_found", "模板不存在"));
         }
     };
-    
-    // 确定模板类型
-    let template_type_str = if req.template_id.contains("sql_generation") {
-        "sql_generation"
-    } else if req.template_id.contains("sql_explain") {
-        "sql_explain"
-    } else if req.template_id.contains("sql_optimize") {
-        "sql_optimize"
-    } else {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "invalid_template_type".to_string(),
-                message: "无效的模板类型".to_string(),
-                details: None,
-            })
-        ));
-    };
+    let template_type_str = template.template_type.as_str();
     
     // 设置默认模板
-    template_manager.set_default_template(template_type_str, &req.template_id);
+    template_manager.set_default_template(&template.template_type, &req.template_id);
     
     info!("默认模板设置成功: {} 类型: {}", req.template_id, template_type_str);
     Ok(Json(serde_json::json!({ 
         "status": "success",
-        "message": format!("已将 {} 设置为 {} 类型的默认模板", template_name, template_type_str)
+        "message": format!("已将 {} 设置为 {} 类型的默认模板", template.name, template_type_str)
     })))
 }
 
+// 预览模板处理函数：用提供的变量渲染模板，返回最终提示词及缺少/未使用的变量
+async fn preview_template(
+    axum::extract::Path(template_id): axum::extract::Path<String>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Json(req): Json<crate::models::TemplatePreviewRequest>
+) -> Result<Json<crate::models::TemplatePreviewResponse>, ApiError> {
+    info!("[API] POST /api/templates/:template_id/preview - 预览模板请求: template_id={}, 变量数={}", template_id, req.variables.len());
+    
+    let preview = template_manager.preview_template(&template_id, &req.variables)
+        .map_err(|_| ApiError::not_found("template_not_found", "模板不存在"))?;
+    if !preview.missing_variables.is_empty() {
+        debug!("[API] 模板 {} 缺少变量: {:?}", template_id, preview.missing_variables);
+    }
+    
+    Ok(Json(crate::models::TemplatePreviewResponse {
+        template_id,
+        prompt: preview.rendered,
+        variables: preview.placeholders,
+        valid: preview.missing_variables.is_empty(),
+        missing_variables: preview.missing_variables,
+        unused_variables: preview.unused_variables,
+    }))
+}
+
 // ========== 连接配置管理API ==========
 
 use crate::models::{DatabaseConnection, ConnectionRequest, ConnectionTestRequest, ConnectionTestResponse, 
-    ActivateConnectionResponse};
+    ActivateConnectionResponse, ConnectionGroup, ConnectionGroupRequest, MoveConnectionRequest, ReorderRequest};
 
 /// 获取所有连接配置
+#[utoipa::path(
+    get,
+    path = "/api/connections",
+    tag = "connections",
+    responses((status = 200, description = "连接列表（不含密码）", body = Vec<DatabaseConnection>))
+)]
 async fn list_connections(
     Extension(storage): Extension<LocalStorageManager>,
-) -> Result<Json<Vec<DatabaseConnection>>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<Vec<DatabaseConnection>>, ApiError> {
     info!("[API] GET /api/connections - 获取连接列表请求");
     match storage.list_connections().await {
         Ok(connections) => {
             info!("[API] GET /api/connections - 响应成功: 连接数量={}", connections.len());
             if let Ok(resp_json) = serde_json::to_string(&connections) {
-                log::info!("[API] GET /api/connections - 响应体: {}", resp_json);
+                log::debug!("[API] GET /api/connections - 响应体: {}", resp_json);
             }
             Ok(Json(connections))
         },
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("获取连接列表失败: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::db("database_error", format!("获取连接列表失败: {}", e)))
     }
 }
 
 /// 创建新连接配置
+#[utoipa::path(
+    post,
+    path = "/api/connections",
+    tag = "connections",
+    request_body = ConnectionRequest,
+    responses(
+        (status = 200, description = "创建的连接", body = DatabaseConnection),
+        (status = 400, description = "参数错误", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn create_connection(
     Extension(storage): Extension<LocalStorageManager>,
     Json(req): Json<ConnectionRequest>,
-) -> Result<Json<DatabaseConnection>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<DatabaseConnection>, ApiError> {
     info!("[API] POST /api/connections - 请求: name={}, db_type={}, host={:?}", 
         req.name, req.db_type, req.host);
     if let Ok(req_json) = serde_json::to_string(&req) {
-        log::info!("[API] POST /api/connections - 请求体: {}", req_json);
+        log::debug!("[API] POST /api/connections - 请求体: {}", req_json);
     }
+    crate::db::ssl::normalize_ssl_mode(req.ssl_mode.as_deref()).map_err(invalid_ssl_config)?;
+    crate::db::validate_pool_options(&req.pool_options).map_err(invalid_pool_options)?;
+    crate::db::normalize_schema_name(req.default_schema.as_deref()).map_err(invalid_default_schema)?;
+    crate::db::validate_connection_options(&req.options, &req.db_type).map_err(invalid_connection_options)?;
     match storage.create_connection(req).await {
         Ok(connection) => {
             info!("[API] POST /api/connections - 响应成功: id={:?}, name={}", connection.id, connection.name);
             if let Ok(resp_json) = serde_json::to_string(&connection) {
-                log::info!("[API] POST /api/connections - 响应体: {}", resp_json);
+                log::debug!("[API] POST /api/connections - 响应体: {}", resp_json);
             }
             Ok(Json(connection))
         },
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("创建连接失败: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::db("database_error", format!("创建连接失败: {}", e)))
     }
 }
 
 /// 获取单个连接配置
+#[utoipa::path(
+    get,
+    path = "/api/connections/{id}",
+    tag = "connections",
+    params(("id" = i64, Path, description = "连接ID")),
+    responses(
+        (status = 200, description = "连接详情", body = DatabaseConnection),
+        (status = 404, description = "连接不存在", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn get_connection(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<Json<DatabaseConnection>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<DatabaseConnection>, ApiError> {
     match storage.get_connection(id).await {
         Ok(connection) => Ok(Json(connection)),
-        Err(e) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ModelErrorResponse {
-                error: "not_found".to_string(),
-                message: format!("连接不存在: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::not_found("not_found", format!("连接不存在: {}", e)))
     }
 }
 
@@ -3560,17 +4618,19 @@ async fn update_connection(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
     Json(req): Json<ConnectionRequest>,
-) -> Result<Json<DatabaseConnection>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<DatabaseConnection>, ApiError> {
+    crate::db::ssl::normalize_ssl_mode(req.ssl_mode.as_deref()).map_err(invalid_ssl_config)?;
+    crate::db::validate_pool_options(&req.pool_options).map_err(invalid_pool_options)?;
+    crate::db::normalize_schema_name(req.default_schema.as_deref()).map_err(invalid_default_schema)?;
+    crate::db::validate_connection_options(&req.options, &req.db_type).map_err(invalid_connection_options)?;
     match storage.update_connection(id, req).await {
-        Ok(connection) => Ok(Json(connection)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("更新连接失败: {}", e),
-                details: None,
-            })
-        ))
+        Ok(connection) => {
+            // 连接配置变化后释放缓存的连接池和表结构，下次使用时按新配置重建
+            crate::db::pool_cache::invalidate(id);
+            get_schema_cache().invalidate(id);
+            Ok(Json(connection))
+        },
+        Err(e) => Err(ApiError::db("database_error", format!("更新连接失败: {}", e)))
     }
 }
 
@@ -3578,17 +4638,14 @@ async fn update_connection(
 async fn delete_connection(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<StatusCode, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<StatusCode, ApiError> {
     match storage.delete_connection(id).await {
-        Ok(_) => Ok(StatusCode::NO_CONTENT),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("删除连接失败: {}", e),
-                details: None,
-            })
-        ))
+        Ok(_) => {
+            crate::db::pool_cache::invalidate(id);
+            crate::services::monitor::clear(id);
+            Ok(StatusCode::NO_CONTENT)
+        },
+        Err(e) => Err(ApiError::db("database_error", format!("删除连接失败: {}", e)))
     }
 }
 
@@ -3596,32 +4653,18 @@ async fn delete_connection(
 async fn toggle_connection_active(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<Json<ActivateConnectionResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<ActivateConnectionResponse>, ApiError> {
     info!("[API] POST /api/connections/{}/toggle - 切换连接激活状态", id);
     
     // 获取当前连接状态
     let connection = storage.get_connection(id).await
-        .map_err(|e| (
-            StatusCode::NOT_FOUND,
-            Json(ModelErrorResponse {
-                error: "not_found".to_string(),
-                message: format!("连接不存在: {}", e),
-                details: None,
-            })
-        ))?;
+        .map_err(|e| ApiError::not_found("not_found", format!("连接不存在: {}", e)))?;
     
     let new_active_state = !connection.is_active;
     
     // 切换激活状态
     storage.toggle_connection_active(id, new_active_state).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("切换连接状态失败: {}", e),
-                details: None,
-            })
-        ))?;
+        .map_err(|e| ApiError::db("database_error", format!("切换连接状态失败: {}", e)))?;
     
     Ok(Json(ActivateConnectionResponse {
         success: true,
@@ -3630,10 +4673,119 @@ async fn toggle_connection_active(
     }))
 }
 
+// 分组名校验，重名时返回冲突错误
+fn validate_group_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("invalid_group_name", "分组名称不能为空"));
+    }
+    Ok(name.to_string())
+}
+
+fn group_storage_error(e: sqlx::Error, action: &str) -> ApiError {
+    match &e {
+        sqlx::Error::RowNotFound => ApiError::not_found("group_not_found", "分组不存在"),
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::conflict("group_name_conflict", "分组名称已存在"),
+        _ => ApiError::db("database_error", format!("{}失败: {}", action, e)),
+    }
+}
+
+/// 获取所有连接分组
+async fn list_connection_groups(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<Vec<ConnectionGroup>>, ApiError> {
+    storage.list_connection_groups().await
+        .map(Json)
+        .map_err(|e| group_storage_error(e, "获取分组列表"))
+}
+
+/// 创建连接分组
+async fn create_connection_group(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<ConnectionGroupRequest>,
+) -> Result<Json<ConnectionGroup>, ApiError> {
+    info!("[API] POST /api/connections/groups - 请求: name={}", req.name);
+    let name = validate_group_name(&req.name)?;
+    storage.create_connection_group(&name, req.sort_order).await
+        .map(Json)
+        .map_err(|e| group_storage_error(e, "创建分组"))
+}
+
+/// 重命名连接分组（可同时调整顺序）
+async fn update_connection_group(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(req): Json<ConnectionGroupRequest>,
+) -> Result<Json<ConnectionGroup>, ApiError> {
+    info!("[API] PUT /api/connections/groups/{} - 请求: name={}", id, req.name);
+    let name = validate_group_name(&req.name)?;
+    storage.update_connection_group(id, &name, req.sort_order).await
+        .map(Json)
+        .map_err(|e| group_storage_error(e, "更新分组"))
+}
+
+/// 删除连接分组（分组内的连接移到未分组，不会被删除）
+async fn delete_connection_group(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    info!("[API] DELETE /api/connections/groups/{}", id);
+    match storage.delete_connection_group(id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::not_found("group_not_found", "分组不存在")),
+        Err(e) => Err(group_storage_error(e, "删除分组")),
+    }
+}
+
+/// 调整分组顺序
+async fn reorder_connection_groups(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<ReorderRequest>,
+) -> Result<StatusCode, ApiError> {
+    storage.reorder_connection_groups(&req.ids).await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| group_storage_error(e, "调整分组顺序"))
+}
+
+/// 调整连接顺序（通常为同一分组内的连接）
+async fn reorder_connections(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(req): Json<ReorderRequest>,
+) -> Result<StatusCode, ApiError> {
+    storage.reorder_connections(&req.ids).await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| ApiError::db("database_error", format!("调整连接顺序失败: {}", e)))
+}
+
+/// 移动连接到分组
+async fn move_connection(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(req): Json<MoveConnectionRequest>,
+) -> Result<Json<DatabaseConnection>, ApiError> {
+    info!("[API] POST /api/connections/{}/move - 请求: group_id={:?}, sort_order={:?}", id, req.group_id, req.sort_order);
+    if let Some(group_id) = req.group_id {
+        storage.get_connection_group(group_id).await
+            .map_err(|e| group_storage_error(e, "获取分组"))?;
+    }
+    match storage.move_connection(id, req.group_id, req.sort_order).await {
+        Ok(connection) => Ok(Json(connection)),
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::not_found("not_found", "连接不存在")),
+        Err(e) => Err(ApiError::db("database_error", format!("移动连接失败: {}", e))),
+    }
+}
+
 /// 测试数据库连接
+#[utoipa::path(
+    post,
+    path = "/api/connections/test",
+    tag = "connections",
+    request_body = ConnectionTestRequest,
+    responses((status = 200, description = "连接测试结果", body = ConnectionTestResponse))
+)]
 async fn test_connection(
     Json(req): Json<ConnectionTestRequest>,
-) -> Result<Json<ConnectionTestResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<ConnectionTestResponse>, ApiError> {
     let start = Instant::now();
     
     // 记录请求信息（隐藏密码）
@@ -3649,6 +4801,10 @@ async fn test_connection(
     // 构建连接字符串
     let conn_str = if let Some(ref cs) = req.connection_string {
         cs.clone()
+    } else if req.db_type == "duckdb" {
+        duckdb_connection_string(req.file_path.as_deref())
+    } else if let (true, Some(host)) = (req.db_type == "redis", req.host.as_deref()) {
+        redis_connection_string(host, req.port, req.username.as_deref(), req.password.as_deref(), req.database_name.as_deref())
     } else if let Some(ref file_path) = req.file_path {
         // 只有当 file_path 不为空时才使用
         if file_path.trim().is_empty() {
@@ -3671,25 +4827,11 @@ async fn test_connection(
                         format!(r#"mongodb://{}:{}@{}:{}/{}?authSource=admin"#, user, pass, host, port, db_name)
                     }
                     _ => {
-                        return Err((
-                            StatusCode::BAD_REQUEST,
-                            Json(ModelErrorResponse {
-                                error: "invalid_db_type".to_string(),
-                                message: "不支持的数据库类型".to_string(),
-                                details: None,
-                            })
-                        ));
+                        return Err(ApiError::bad_request("invalid_db_type", "不支持的数据库类型"));
                     }
                 }
             } else {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "invalid_request".to_string(),
-                        message: "缺少必要的连接参数".to_string(),
-                        details: None,
-                    })
-                ));
+                return Err(ApiError::bad_request("invalid_request", "缺少必要的连接参数"));
             }
         } else {
             format!("sqlite://{}?mode=rwc", file_path)
@@ -3712,25 +4854,19 @@ async fn test_connection(
                 format!(r#"mongodb://{}:{}@{}:{}/{}?authSource=admin"#, user, pass, host, port, db_name)
             }
             _ => {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "invalid_db_type".to_string(),
-                        message: "不支持的数据库类型".to_string(),
-                        details: None,
-                    })
-                ));
+                return Err(ApiError::bad_request("invalid_db_type", "不支持的数据库类型"));
             }
         }
     } else {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ModelErrorResponse {
-                error: "invalid_request".to_string(),
-                message: "缺少必要的连接参数".to_string(),
-                details: None,
-            })
-        ));
+        return Err(ApiError::bad_request("invalid_request", "缺少必要的连接参数"));
+    };
+    
+    // 自定义连接字符串自行携带SSL参数，其余按请求中的SSL配置追加
+    let conn_str = if req.connection_string.is_some() {
+        conn_str
+    } else {
+        crate::db::ssl::apply_ssl_params(&conn_str, &req.db_type, req.ssl_mode.as_deref(), req.ssl_ca_path.as_deref())
+            .map_err(invalid_ssl_config)?
     };
     
     // 根据数据库类型尝试连接
@@ -3742,16 +4878,12 @@ async fn test_connection(
             log::info!("准备连接到MySQL: {}", conn_str.replace(req.password.as_deref().unwrap_or(""), "***"));
             
             // 解析连接选项并配置
-            let options = MySqlConnectOptions::from_str(&conn_str)
-                .map_err(|e| (
-                    StatusCode::BAD_REQUEST,
-                    Json(ModelErrorResponse {
-                        error: "invalid_connection_string".to_string(),
-                        message: format!("无效的连接字符串: {}", e),
-                        details: None,
-                    })
-                ))?
-                .ssl_mode(MySqlSslMode::Disabled);  // 禁用 SSL
+            let mut options = MySqlConnectOptions::from_str(&conn_str)
+                .map_err(|e| ApiError::bad_request("invalid_connection_string", format!("无效的连接字符串: {}", e)))?;
+            // 未配置SSL时保持原有行为（禁用 SSL），已配置时使用连接字符串中的 ssl-mode
+            if req.connection_string.is_none() && req.ssl_mode.is_none() && req.ssl_ca_path.is_none() {
+                options = options.ssl_mode(MySqlSslMode::Disabled);
+            }
             
             log::info!("开始建立MySQL连接...");
             
@@ -3779,7 +4911,7 @@ async fn test_connection(
                         server_version, response_time);
                     
                     if let Ok(resp_json) = serde_json::to_string(&response) {
-                        log::info!("[API] POST /api/connections/test - 响应体: {}", resp_json);
+                        log::debug!("[API] POST /api/connections/test - 响应体: {}", resp_json);
                     }
                     
                     // 关闭连接
@@ -3798,7 +4930,7 @@ async fn test_connection(
                         response_time_ms: response_time,
                     };
                     if let Ok(resp_json) = serde_json::to_string(&response) {
-                        log::info!("[API] POST /api/connections/test - 响应体: {}", resp_json);
+                        log::debug!("[API] POST /api/connections/test - 响应体: {}", resp_json);
                     }
                     Ok(Json(response))
                 }
@@ -3879,7 +5011,7 @@ async fn test_connection(
                                 server_version, response_time);
                             
                             if let Ok(resp_json) = serde_json::to_string(&response) {
-                                log::info!("[API] POST /api/connections/test - 响应体: {}", resp_json);
+                                log::debug!("[API] POST /api/connections/test - 响应体: {}", resp_json);
                             }
                             
                             Ok(Json(response))
@@ -3895,7 +5027,7 @@ async fn test_connection(
                                 response_time_ms: response_time,
                             };
                             if let Ok(resp_json) = serde_json::to_string(&response) {
-                                log::info!("[API] POST /api/connections/test - 响应体: {}", resp_json);
+                                log::debug!("[API] POST /api/connections/test - 响应体: {}", resp_json);
                             }
                             Ok(Json(response))
                         }
@@ -3912,12 +5044,45 @@ async fn test_connection(
                         response_time_ms: response_time,
                     };
                     if let Ok(resp_json) = serde_json::to_string(&response) {
-                        log::info!("[API] POST /api/connections/test - 响应体: {}", resp_json);
+                        log::debug!("[API] POST /api/connections/test - 响应体: {}", resp_json);
                     }
                     Ok(Json(response))
                 }
             }
         }
+        "redis" => {
+            log::info!("准备连接到Redis: {}", conn_str.replace(req.password.as_deref().unwrap_or(""), "***"));
+            
+            let result: Result<Option<String>, redis::RedisError> = async {
+                let conn = crate::db::redis_client::connect(&conn_str).await?;
+                crate::db::redis_client::ping(&conn).await?;
+                crate::db::redis_client::server_version(&conn).await
+            }.await;
+            
+            let response_time = start.elapsed().as_millis();
+            let response = match result {
+                Ok(server_version) => {
+                    info!("[API] POST /api/connections/test - 响应成功: 连接成功, 版本={:?}, 耗时={}ms", 
+                        server_version, response_time);
+                    ConnectionTestResponse {
+                        success: true,
+                        message: "连接成功".to_string(),
+                        server_version,
+                        response_time_ms: response_time,
+                    }
+                }
+                Err(e) => {
+                    error!("[API] POST /api/connections/test - Redis连接失败: {}", e);
+                    ConnectionTestResponse {
+                        success: false,
+                        message: format!("连接失败: {}", e),
+                        server_version: None,
+                        response_time_ms: response_time,
+                    }
+                }
+            };
+            Ok(Json(response))
+        }
         "sqlite" => {
             match sqlx::SqlitePool::connect(&conn_str).await {
                 Ok(pool) => {
@@ -3954,60 +5119,100 @@ async fn test_connection(
                 }
             }
         }
+        "duckdb" => {
+            let result: Result<(Option<String>, usize), crate::db::DatabaseError> = async {
+                let db = crate::db::duckdb_engine::DuckDbPool::open(&conn_str).await?;
+                Ok((db.version().await?, db.attached_files().len()))
+            }.await;
+            
+            let response_time = start.elapsed().as_millis();
+            let response = match result {
+                Ok((server_version, file_count)) => {
+                    info!("[API] POST /api/connections/test - 响应成功: DuckDB打开成功, 挂载文件数={}, 耗时={}ms", file_count, response_time);
+                    ConnectionTestResponse {
+                        success: true,
+                        message: format!("连接成功，已挂载 {} 个数据文件", file_count),
+                        server_version: server_version.map(|v| format!("DuckDB {}", v)),
+                        response_time_ms: response_time,
+                    }
+                }
+                Err(e) => {
+                    error!("[API] POST /api/connections/test - DuckDB打开失败: {}", e);
+                    ConnectionTestResponse {
+                        success: false,
+                        message: format!("连接失败: {}", e),
+                        server_version: None,
+                        response_time_ms: response_time,
+                    }
+                }
+            };
+            Ok(Json(response))
+        }
         _ => {
-            Err((
-                StatusCode::BAD_REQUEST,
-                Json(ModelErrorResponse {
-                    error: "unsupported_db_type".to_string(),
-                    message: format!("不支持的数据库类型: {}", req.db_type),
-                    details: None,
-                })
-            ))
+            Err(ApiError::bad_request("unsupported_db_type", format!("不支持的数据库类型: {}", req.db_type)))
         }
     }
 }
 
 // ========== 查询历史管理API ==========
 
-use crate::models::QueryHistory;
+use crate::models::{QueryHistory, QueryHistoryFilter};
+use crate::services::history_stats;
 
 /// 获取查询历史列表
 async fn list_query_history(
     Extension(storage): Extension<LocalStorageManager>,
-    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<Vec<QueryHistory>>, (StatusCode, Json<ModelErrorResponse>)> {
-    let connection_id = params.get("connection_id").and_then(|s| s.parse::<i64>().ok());
-    let limit = params.get("limit").and_then(|s| s.parse::<i64>().ok()).unwrap_or(100);
-    let offset = params.get("offset").and_then(|s| s.parse::<i64>().ok()).unwrap_or(0);
+    axum::extract::Query(filter): axum::extract::Query<QueryHistoryFilter>,
+) -> Result<Json<Vec<QueryHistory>>, ApiError> {
+    info!("[API] GET /api/history - 查询历史请求: q={:?}, success={:?}, min_duration_ms={:?}", filter.q, filter.success, filter.min_duration_ms);
     
-    match storage.list_query_history(connection_id, limit, offset).await {
+    match storage.search_query_history(&filter).await {
         Ok(history) => Ok(Json(history)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("获取历史记录失败: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::db("database_error", format!("获取历史记录失败: {}", e)))
     }
 }
 
+// 查询历史统计参数
+#[derive(Debug, Deserialize)]
+struct HistoryStatsParams {
+    connection_id: Option<i64>,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+    top: Option<usize>,  // 常用表 / 慢查询返回数量
+}
+
+/// 获取查询历史统计
+async fn query_history_stats(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Query(params): axum::extract::Query<HistoryStatsParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/history/stats - 查询历史统计请求: connection_id={:?}, start_time={:?}, end_time={:?}", params.connection_id, params.start_time, params.end_time);
+    
+    let filter = QueryHistoryFilter {
+        connection_id: params.connection_id,
+        start_time: params.start_time,
+        end_time: params.end_time,
+        limit: Some(history_stats::MAX_STATS_RECORDS),
+        ..Default::default()
+    };
+    let records = storage.search_query_history(&filter).await
+        .map_err(|e| ApiError::db("database_error", format!("获取历史记录失败: {}", e)))?;
+    
+    let stats = history_stats::compute_stats(&records, params.top.unwrap_or(history_stats::DEFAULT_TOP_N));
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": stats,
+    })))
+}
+
 /// 切换收藏状态
 async fn toggle_query_favorite(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<StatusCode, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<StatusCode, ApiError> {
     match storage.toggle_query_favorite(id).await {
         Ok(_) => Ok(StatusCode::OK),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("切换收藏失败: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::db("database_error", format!("切换收藏失败: {}", e)))
     }
 }
 
@@ -4015,7 +5220,7 @@ async fn toggle_query_favorite(
 async fn clear_query_history(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     let keep_favorites = params.get("keep_favorites")
         .and_then(|s| s.parse::<bool>().ok())
         .unwrap_or(true);
@@ -4025,64 +5230,101 @@ async fn clear_query_history(
             "deleted_count": count,
             "message": format!("已清空 {} 条历史记录", count)
         }))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("清空历史失败: {}", e),
-                details: None,
-            })
-        ))
+        Err(e) => Err(ApiError::db("database_error", format!("清空历史失败: {}", e)))
     }
 }
 
 // ========== AI配置管理API ==========
 
 /// AI配置请求结构
-#[derive(Deserialize)]
-struct AiConfigRequest {
+#[derive(Deserialize, utoipa::ToSchema)]
+pub(crate) struct AiConfigRequest {
     base_url: String,
     api_key: String,
     model: String,
+    // AI服务商：openai / azure / anthropic / deepseek / ollama，未指定时为 openai
+    #[serde(default)]
+    provider: Option<ProviderKind>,
+    // Azure OpenAI 的API版本
+    #[serde(default)]
+    api_version: Option<String>,
+    // 按功能覆盖模型（键为功能类型，如 sql_generation），值为空时清除覆盖
+    #[serde(default)]
+    feature_models: Option<HashMap<String, String>>,
+}
+
+/// 前端回传的API密钥为空或为掩码值时，沿用已保存的密钥
+async fn resolve_submitted_api_key(storage: &LocalStorageManager, submitted: &str) -> String {
+    let submitted = submitted.trim();
+    let stored = storage.get_app_setting("ai_api_key").await.ok().flatten().unwrap_or_default();
+    if !stored.is_empty() && (submitted.is_empty() || submitted == crate::utils::security::mask_secret(&stored)) {
+        stored
+    } else {
+        submitted.to_string()
+    }
+}
+
+/// 校验服务商相关的配置项，返回服务商类型
+fn validate_ai_provider_config(payload: &AiConfigRequest) -> Result<ProviderKind, ApiError> {
+    let provider = payload.provider.unwrap_or(ProviderKind::OpenAi);
+    
+    // Azure 没有统一的默认地址，必须填写资源地址
+    if provider == ProviderKind::Azure && payload.base_url.trim().is_empty() {
+        return Err(ApiError::bad_request("invalid_ai_config", "Azure OpenAI 需要填写资源地址（base_url）"));
+    }
+    if provider.requires_api_key() && payload.api_key.trim().is_empty() {
+        return Err(ApiError::bad_request("invalid_ai_config", format!("AI服务商 {} 需要配置API密钥", provider.as_str())));
+    }
+    Ok(provider)
 }
 
 /// 保存AI配置
+#[utoipa::path(
+    post,
+    path = "/api/ai/config",
+    tag = "ai",
+    request_body(content = AiConfigRequest, description = "api_key 为空或为掩码值时沿用已保存的密钥"),
+    responses(
+        (status = 200, description = "保存成功", body = Object),
+        (status = 400, description = "配置无效", body = crate::api::error::ErrorInfo),
+    )
+)]
 async fn save_ai_config(
     Extension(storage): Extension<LocalStorageManager>,
-    Json(payload): Json<AiConfigRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
-    log::info!("[API] POST /api/ai/config - 保存AI配置请求");
+    Json(mut payload): Json<AiConfigRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    log::info!("[API] POST /api/ai/config - 保存AI配置请求: provider={:?}", payload.provider);
+    
+    payload.api_key = resolve_submitted_api_key(&storage, &payload.api_key).await;
+    let provider = validate_ai_provider_config(&payload)?;
+    let mut feature_models = Vec::new();
+    for (feature, model) in payload.feature_models.iter().flatten() {
+        match AiFeature::parse(feature) {
+            Some(feature) => feature_models.push((feature, model.trim())),
+            None => return Err(ApiError::bad_request("invalid_ai_config", format!("未知的AI功能类型: {}", feature)).with_details(format!("可选值: {}", AiFeature::ALL.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(", ")))),
+        }
+    }
     
     // 保存配置到本地存储
+    storage.set_app_setting("ai_provider", provider.as_str()).await
+        .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
+    
+    storage.set_app_setting("ai_api_version", payload.api_version.as_deref().unwrap_or("").trim()).await
+        .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
+    
     storage.set_app_setting("ai_api_base_url", &payload.base_url).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("保存AI配置失败: {}", e),
-                details: None,
-            })
-        ))?;
+        .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
     
     storage.set_app_setting("ai_api_key", &payload.api_key).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("保存AI配置失败: {}", e),
-                details: None,
-            })
-        ))?;
+        .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
     
     storage.set_app_setting("ai_model", &payload.model).await
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ModelErrorResponse {
-                error: "database_error".to_string(),
-                message: format!("保存AI配置失败: {}", e),
-                details: None,
-            })
-        ))?;
+        .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
+    
+    for (feature, model) in feature_models {
+        storage.set_app_setting(&feature.model_setting_key(), model).await
+            .map_err(|e| ApiError::db("database_error", format!("保存AI配置失败: {}", e)))?;
+    }
     
     log::info!("[API] POST /api/ai/config - AI配置保存成功");
     
@@ -4092,35 +5334,119 @@ async fn save_ai_config(
     })))
 }
 
+/// 测试AI配置：使用请求中的配置发送一次最小的补全请求（不保存配置）
+#[utoipa::path(
+    post,
+    path = "/api/ai/config/test",
+    tag = "ai",
+    request_body = AiConfigRequest,
+    responses(
+        (status = 200, description = "测试结果（success、latency_ms 等）", body = Object),
+        (status = 400, description = "配置无效", body = crate::api::error::ErrorInfo),
+    )
+)]
+async fn test_ai_config(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(mut payload): Json<AiConfigRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    log::info!("[API] POST /api/ai/config/test - 测试AI配置请求: provider={:?}, model={}", payload.provider, payload.model);
+    
+    payload.api_key = resolve_submitted_api_key(&storage, &payload.api_key).await;
+    let provider = validate_ai_provider_config(&payload)?;
+    let config = LlmConfig {
+        provider,
+        api_key: payload.api_key.trim().to_string(),
+        base_url: Some(payload.base_url.trim()).filter(|u| !u.is_empty()).unwrap_or(provider.default_base_url()).to_string(),
+        model: Some(payload.model.trim()).filter(|m| !m.is_empty()).unwrap_or(provider.default_model()).to_string(),
+        api_version: payload.api_version.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string),
+    };
+    
+    let start_time = std::time::Instant::now();
+    let result = AiService::test_config(&config).await;
+    let latency_ms = start_time.elapsed().as_millis() as u64;
+    
+    match result {
+        Ok(reply) => {
+            log::info!("[API] POST /api/ai/config/test - AI配置测试成功: 耗时={}ms", latency_ms);
+            Ok(Json(serde_json::json!({
+                "success": true,
+                "provider": provider,
+                "model": config.model,
+                "latency_ms": latency_ms,
+                "reply": reply,
+                "message": "AI服务连接成功"
+            })))
+        }
+        Err(e) => {
+            log::warn!("[API] POST /api/ai/config/test - AI配置测试失败: 耗时={}ms, 错误={}", latency_ms, e);
+            Ok(Json(serde_json::json!({
+                "success": false,
+                "provider": provider,
+                "model": config.model,
+                "latency_ms": latency_ms,
+                "message": format!("AI服务连接失败: {}", e)
+            })))
+        }
+    }
+}
+
 /// 获取AI配置
+#[utoipa::path(
+    get,
+    path = "/api/ai/config",
+    tag = "ai",
+    responses((status = 200, description = "当前AI配置（API密钥已掩码）", body = Object))
+)]
 async fn get_ai_config(
     Extension(storage): Extension<LocalStorageManager>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] GET /api/ai/config - 获取AI配置请求");
     
-    // 从本地存储获取配置
+    // 从本地存储获取配置，未配置的项使用服务商的默认值
+    let provider = match storage.get_app_setting("ai_provider").await {
+        Ok(Some(p)) => ProviderKind::parse(&p).unwrap_or(ProviderKind::OpenAi),
+        _ => ProviderKind::OpenAi,
+    };
+    
     let base_url = match storage.get_app_setting("ai_api_base_url").await {
-        Ok(Some(url)) => url,
-        Ok(None) => "https://api.openai.com/v1".to_string(),
-        Err(_) => "https://api.openai.com/v1".to_string(),
+        Ok(Some(url)) if !url.trim().is_empty() => url,
+        _ => provider.default_base_url().to_string(),
     };
     
+    // 不返回原始密钥，只返回掩码值（保存时回传掩码值表示不修改密钥）
     let api_key = match storage.get_app_setting("ai_api_key").await {
         Ok(Some(key)) => key,
         Ok(None) => "".to_string(),
         Err(_) => "".to_string(),
     };
+    let has_key = !api_key.trim().is_empty();
     
     let model = match storage.get_app_setting("ai_model").await {
-        Ok(Some(m)) => m,
-        Ok(None) => "gpt-4o-mini".to_string(),
-        Err(_) => "gpt-4o-mini".to_string(),
+        Ok(Some(m)) if !m.trim().is_empty() => m,
+        _ => provider.default_model().to_string(),
     };
     
+    let api_version = storage.get_app_setting("ai_api_version").await.ok().flatten()
+        .filter(|v| !v.is_empty());
+    
+    // 已配置的功能专用模型，未配置的功能使用全局模型
+    let mut feature_models = serde_json::Map::new();
+    for feature in AiFeature::ALL {
+        if let Ok(Some(m)) = storage.get_app_setting(&feature.model_setting_key()).await {
+            if !m.trim().is_empty() {
+                feature_models.insert(feature.as_str().to_string(), serde_json::Value::String(m));
+            }
+        }
+    }
+    
     Ok(Json(serde_json::json!({
+        "provider": provider,
         "base_url": base_url,
-        "api_key": api_key,
-        "model": model
+        "api_key": crate::utils::security::mask_secret(&api_key),
+        "has_key": has_key,
+        "model": model,
+        "api_version": api_version,
+        "feature_models": feature_models
     })))
 }
 
@@ -4135,6 +5461,8 @@ struct CreateSqlFavoriteRequest {
     description: Option<String>,
     category: Option<String>,
     connection_id: Option<i64>,
+    #[serde(default)]
+    parameters: Option<Vec<FavoriteParameter>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -4143,13 +5471,40 @@ struct UpdateSqlFavoriteRequest {
     sql_text: Option<String>,
     description: Option<String>,
     category: Option<String>,
+    #[serde(default)]
+    parameters: Option<Vec<FavoriteParameter>>,
+}
+
+// 执行收藏SQL请求
+#[derive(Serialize, Deserialize)]
+struct ExecuteFavoriteRequest {
+    // 未指定时依次使用收藏关联的连接、第一个活动连接
+    #[serde(default)]
+    connection_id: Option<i64>,
+    // 参数名 -> 参数值
+    #[serde(default)]
+    values: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    page: Option<u64>,
+    #[serde(default)]
+    page_size: Option<u64>,
+    #[serde(default)]
+    confirm_production: bool,
+}
+
+// 校验收藏的参数声明
+fn validate_favorite_parameters(parameters: &Option<Vec<FavoriteParameter>>) -> Result<(), ApiError> {
+    match parameters {
+        Some(parameters) => crate::utils::sql_params::validate_parameters(parameters).map_err(|e| ApiError::bad_request("invalid_parameters", e)),
+        None => Ok(()),
+    }
 }
 
 // 获取所有收藏
 async fn list_sql_favorites(
     Extension(storage): Extension<LocalStorageManager>,
     Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] GET /api/favorites - 获取SQL收藏列表请求");
     
     let category = params.get("category").map(|s| s.as_str());
@@ -4165,14 +5520,7 @@ async fn list_sql_favorites(
         },
         Err(e) => {
             log::error!("[API] 获取SQL收藏失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "list_favorites_failed".to_string(),
-                    message: format!("获取收藏列表失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("list_favorites_failed", format!("获取收藏列表失败: {}", e)))
         }
     }
 }
@@ -4181,10 +5529,11 @@ async fn list_sql_favorites(
 async fn create_sql_favorite(
     Extension(storage): Extension<LocalStorageManager>,
     Json(req): Json<CreateSqlFavoriteRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] POST /api/favorites - 创建SQL收藏请求");
+    validate_favorite_parameters(&req.parameters)?;
     
-    match storage.create_sql_favorite(&req.name, &req.sql_text, req.description.as_deref(), req.category.as_deref(), req.connection_id).await {
+    match storage.create_sql_favorite(&req.name, &req.sql_text, req.description.as_deref(), req.category.as_deref(), req.connection_id, req.parameters.as_deref()).await {
         Ok(favorite) => {
             log::info!("[API] SQL收藏创建成功: id={:?}", favorite.id);
             Ok(Json(serde_json::json!({
@@ -4195,14 +5544,7 @@ async fn create_sql_favorite(
         },
         Err(e) => {
             log::error!("[API] 创建SQL收藏失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "create_favorite_failed".to_string(),
-                    message: format!("创建收藏失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("create_favorite_failed", format!("创建收藏失败: {}", e)))
         }
     }
 }
@@ -4211,7 +5553,7 @@ async fn create_sql_favorite(
 async fn get_sql_favorite(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] GET /api/favorites/:id - 获取SQL收藏请求: id={}", id);
     
     match storage.get_sql_favorite(id).await {
@@ -4224,14 +5566,7 @@ async fn get_sql_favorite(
         },
         Err(e) => {
             log::error!("[API] 获取SQL收藏失败: {}", e);
-            Err((
-                StatusCode::NOT_FOUND,
-                Json(ModelErrorResponse {
-                    error: "favorite_not_found".to_string(),
-                    message: format!("收藏不存在: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::not_found("favorite_not_found", format!("收藏不存在: {}", e)))
         }
     }
 }
@@ -4241,10 +5576,11 @@ async fn update_sql_favorite(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
     Json(req): Json<UpdateSqlFavoriteRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] PUT /api/favorites/:id - 更新SQL收藏请求: id={}", id);
+    validate_favorite_parameters(&req.parameters)?;
     
-    match storage.update_sql_favorite(id, &req.name, &req.sql_text, &req.description, &req.category).await {
+    match storage.update_sql_favorite(id, &req.name, &req.sql_text, &req.description, &req.category, &req.parameters).await {
         Ok(_) => {
             match storage.get_sql_favorite(id).await {
                 Ok(favorite) => {
@@ -4265,14 +5601,7 @@ async fn update_sql_favorite(
         },
         Err(e) => {
             log::error!("[API] 更新SQL收藏失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "update_favorite_failed".to_string(),
-                    message: format!("更新收藏失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("update_favorite_failed", format!("更新收藏失败: {}", e)))
         }
     }
 }
@@ -4281,7 +5610,7 @@ async fn update_sql_favorite(
 async fn delete_sql_favorite(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] DELETE /api/favorites/:id - 删除SQL收藏请求: id={}", id);
     
     match storage.delete_sql_favorite(id).await {
@@ -4294,14 +5623,7 @@ async fn delete_sql_favorite(
         },
         Err(e) => {
             log::error!("[API] 删除SQL收藏失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "delete_favorite_failed".to_string(),
-                    message: format!("删除收藏失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("delete_favorite_failed", format!("删除收藏失败: {}", e)))
         }
     }
 }
@@ -4309,7 +5631,7 @@ async fn delete_sql_favorite(
 // 获取收藏分组列表
 async fn list_favorite_categories(
     Extension(storage): Extension<LocalStorageManager>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] GET /api/favorites/categories - 获取收藏分组列表请求");
     
     match storage.list_favorite_categories().await {
@@ -4323,23 +5645,58 @@ async fn list_favorite_categories(
         },
         Err(e) => {
             log::error!("[API] 获取收藏分组失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "list_categories_failed".to_string(),
-                    message: format!("获取分组列表失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("list_categories_failed", format!("获取分组列表失败: {}", e)))
         }
     }
 }
 
+// 执行收藏的SQL（绑定命名参数）
+async fn execute_sql_favorite(
+    Extension(storage): Extension<LocalStorageManager>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+    Json(req): Json<ExecuteFavoriteRequest>,
+) -> Result<Json<SqlQueryResult>, ApiError> {
+    log::info!("[API] POST /api/favorites/:id/execute - 执行SQL收藏请求: id={}, 参数={:?}", id, req.values.keys().collect::<Vec<_>>());
+    
+    let favorite = storage.get_sql_favorite(id).await.map_err(|e| ApiError::not_found("favorite_not_found", format!("收藏不存在: {}", e)))?;
+    
+    // 确定连接，占位符风格取决于数据库类型
+    let connection = match req.connection_id.or(favorite.connection_id) {
+        Some(conn_id) => storage.get_connection_by_id(conn_id).await.ok().flatten(),
+        None => storage.get_active_connections().await.ok().and_then(|c| c.into_iter().next()),
+    }
+    .ok_or_else(|| ApiError::bad_request("no_connection", "未找到可用的数据库连接"))?;
+    
+    let declared = favorite.parameters.as_ref().map(|p| p.0.as_slice()).unwrap_or(&[]);
+    let placeholder = |n: usize| if connection.db_type == "postgresql" { format!("${}", n) } else { "?".to_string() };
+    let (sql, parameters) = crate::utils::sql_params::bind_named_params(&favorite.sql_text, declared, &req.values, placeholder)
+        .map_err(|e| ApiError::bad_request("invalid_parameters", e))?;
+    
+    let query = SqlQueryRequest {
+        sql,
+        connection_id: connection.id,
+        parameters: if parameters.is_empty() { None } else { Some(parameters) },
+        page: req.page,
+        page_size: req.page_size,
+        count_total: req.page.is_some(),
+        confirm_production: req.confirm_production,
+        ..Default::default()
+    };
+    let result = execute_query(Extension(storage.clone()), Json(query)).await?;
+    
+    if let Err(e) = storage.increment_favorite_usage(id).await {
+        log::warn!("[API] 更新收藏使用次数失败: {}", e);
+    }
+    
+    log::info!("[API] POST /api/favorites/:id/execute - 响应成功: 行数={}", result.row_count);
+    Ok(result)
+}
+
 // 增加收藏使用次数
 async fn increment_favorite_usage(
     Extension(storage): Extension<LocalStorageManager>,
     axum::extract::Path(id): axum::extract::Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ModelErrorResponse>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     log::info!("[API] POST /api/favorites/:id/use - 增加收藏使用次数请求: id={}", id);
     
     match storage.increment_favorite_usage(id).await {
@@ -4363,14 +5720,7 @@ async fn increment_favorite_usage(
         },
         Err(e) => {
             log::error!("[API] 增加收藏使用次数失败: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ModelErrorResponse {
-                    error: "increment_usage_failed".to_string(),
-                    message: format!("更新使用次数失败: {}", e),
-                    details: None,
-                })
-            ))
+            Err(ApiError::db("increment_usage_failed", format!("更新使用次数失败: {}", e)))
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,141 @@
+use axum::{extract::Query, http::StatusCode, Extension, Json};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use log::*;
+
+use crate::api::ddl::ConnectionParams;
+use crate::api::routes::{connect_database, get_table_structure_internal};
+use crate::db::LocalStorageManager;
+use crate::models::{ErrorResponse as ModelErrorResponse, ForeignKeyInfo};
+
+// ER图中的列
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphColumn {
+    pub name: String,
+    pub data_type: Option<String>,
+    pub nullable: Option<bool>,
+    pub is_primary_key: bool,
+    pub is_foreign_key: bool,
+}
+
+// ER图节点（表）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub columns: Vec<GraphColumn>,
+    pub primary_keys: Vec<String>,
+}
+
+// ER图边（外键关系，复合外键合并为一条边）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub source_columns: Vec<String>,
+    pub target_columns: Vec<String>,
+}
+
+// ER图响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchemaGraphResponse {
+    pub database_type: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    // 读取结构失败的表（仍以无列节点出现在图中）
+    pub warnings: Vec<String>,
+}
+
+// 将一张表的外键按约束名合并为边
+fn build_edges(table: &str, foreign_keys: &[ForeignKeyInfo]) -> Vec<GraphEdge> {
+    let mut grouped: BTreeMap<(&str, &str), GraphEdge> = BTreeMap::new();
+    for fk in foreign_keys {
+        let edge = grouped
+            .entry((fk.constraint_name.as_str(), fk.referenced_table.as_str()))
+            .or_insert_with(|| GraphEdge {
+                id: format!("{}.{}", table, fk.constraint_name),
+                source: table.to_string(),
+                target: fk.referenced_table.clone(),
+                source_columns: Vec::new(),
+                target_columns: Vec::new(),
+            });
+        edge.source_columns.push(fk.column_name.clone());
+        edge.target_columns.push(fk.referenced_column.clone());
+    }
+    grouped.into_values().collect()
+}
+
+/**
+ * 获取ER图数据处理函数
+ */
+pub async fn get_schema_graph(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<SchemaGraphResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+    info!("[API] GET /api/database/schema/graph - 获取ER图数据请求: connection_id={:?}", params.connection_id);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    let table_names = db_manager.get_schema().await.map_err(|e| (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ModelErrorResponse {
+            error: "schema_error".to_string(),
+            message: format!("获取表列表失败: {}", e),
+            details: None,
+        })
+    ))?;
+
+    let mut nodes = Vec::with_capacity(table_names.len());
+    let mut edges = Vec::new();
+    let mut warnings = Vec::new();
+
+    for table_name in table_names {
+        let schema = match get_table_structure_internal(&db_manager, &table_name).await {
+            Ok(schema) => schema,
+            Err(e) => {
+                warn!("[API] 获取表 {} 结构失败: {}", table_name, e);
+                warnings.push(format!("{}: {}", table_name, e));
+                nodes.push(GraphNode {
+                    id: table_name.clone(),
+                    name: table_name,
+                    columns: Vec::new(),
+                    primary_keys: Vec::new(),
+                });
+                continue;
+            }
+        };
+
+        let foreign_keys = schema.foreign_keys.unwrap_or_default();
+        let columns: Vec<GraphColumn> = schema.columns.into_iter()
+            .map(|c| GraphColumn {
+                is_foreign_key: foreign_keys.iter().any(|fk| fk.column_name == c.name),
+                is_primary_key: c.is_primary_key.unwrap_or(false),
+                nullable: c.nullable,
+                data_type: c.data_type,
+                name: c.name,
+            })
+            .collect();
+        let primary_keys = columns.iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.clone())
+            .collect();
+
+        edges.extend(build_edges(&table_name, &foreign_keys));
+        nodes.push(GraphNode {
+            id: table_name.clone(),
+            name: table_name,
+            columns,
+            primary_keys,
+        });
+    }
+
+    info!("[API] GET /api/database/schema/graph - 响应成功: 节点数={}, 边数={}", nodes.len(), edges.len());
+
+    Ok(Json(SchemaGraphResponse {
+        database_type: connection.db_type,
+        nodes,
+        edges,
+        warnings,
+    }))
+}
@@ -0,0 +1,59 @@
+use axum::{extract::Query, Extension, Json};
+use serde::Deserialize;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{cached_table_names, connect_database};
+use crate::db::LocalStorageManager;
+use crate::services::ai::AiService;
+use crate::services::embeddings::{self, DEFAULT_SEARCH_LIMIT};
+
+// 表结构语义检索参数
+#[derive(Debug, Deserialize)]
+pub struct SchemaSearchParams {
+    pub q: String,
+    pub connection_id: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/**
+ * 表结构语义检索处理函数（检索前增量同步表和列的嵌入向量）
+ */
+pub async fn search_schema(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(ai_service): Extension<Option<AiService>>,
+    Query(params): Query<SchemaSearchParams>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/database/schema/search - 表结构语义检索请求: q={}, connection_id={:?}", params.q, params.connection_id);
+
+    let query = params.q.trim();
+    if query.is_empty() {
+        return Err(ApiError::bad_request("invalid_query", "检索内容不能为空"));
+    }
+    let ai_service = ai_service.as_ref()
+        .ok_or_else(|| ApiError::service_unavailable("ai_service_unavailable", "AI服务不可用"))?;
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    let connection_id = connection.id
+        .ok_or_else(|| ApiError::bad_request("invalid_connection", "连接缺少ID"))?;
+
+    let table_names = cached_table_names(connection.id, &db_manager).await
+        .map_err(|e| ApiError::internal("schema_error", format!("获取表列表失败: {}", e)))?;
+    let catalog = db_manager.get_column_catalog().await
+        .map_err(|e| ApiError::internal("schema_error", format!("获取列信息失败: {}", e)))?;
+
+    let documents = embeddings::schema_documents(&table_names, catalog);
+    let summary = embeddings::sync_schema_embeddings(ai_service, &storage, connection_id, documents).await
+        .map_err(|e| ApiError::bad_gateway("embedding_error", format!("同步表结构向量失败: {}", e)))?;
+
+    let hits = embeddings::search_schema(ai_service, &storage, connection_id, query, params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT)).await
+        .map_err(|e| ApiError::bad_gateway("embedding_error", format!("语义检索失败: {}", e)))?;
+
+    info!("[API] GET /api/database/schema/search - 命中 {} 条", hits.len());
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "data": hits,
+        "count": hits.len(),
+        "sync": summary,
+    })))
+}
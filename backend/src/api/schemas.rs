@@ -0,0 +1,80 @@
+use axum::{extract::{Path, Query}, Extension, Json};
+use serde::Serialize;
+use log::*;
+
+use crate::api::ddl::ConnectionParams;
+use crate::api::error::ApiError;
+use crate::api::routes::connect_database;
+use crate::db::LocalStorageManager;
+
+// schema列表响应
+#[derive(Debug, Serialize)]
+pub struct SchemaListResponse {
+    pub connection_id: Option<i64>,
+    pub schemas: Vec<String>,
+    pub current_schema: Option<String>,  // 连接当前生效的schema
+    pub default_schema: Option<String>,  // 连接配置的默认schema
+}
+
+/**
+ * 获取可用schema列表处理函数
+ * PostgreSQL 返回用户schema，MySQL/MongoDB 返回数据库列表，SQLite 返回已附加的数据库
+ */
+pub async fn list_schemas(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+) -> Result<Json<SchemaListResponse>, ApiError> {
+    info!("[API] GET /api/database/schemas - 请求: connection_id={:?}", params.connection_id);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    if matches!(db_manager.db_type, crate::db::DatabaseType::Redis) {
+        return Err(ApiError::bad_request("unsupported_database", "Redis不支持schema"));
+    }
+    let schemas = db_manager.list_schemas().await
+        .map_err(|e| ApiError::internal("query_failed", format!("获取schema列表失败: {}", e)))?;
+    let current_schema = db_manager.current_schema().await
+        .map_err(|e| ApiError::internal("query_failed", format!("获取当前schema失败: {}", e)))?;
+
+    info!("[API] GET /api/database/schemas - 响应成功: schema数量={}, 当前={:?}", schemas.len(), current_schema);
+    Ok(Json(SchemaListResponse {
+        connection_id: connection.id,
+        schemas,
+        current_schema,
+        default_schema: connection.default_schema,
+    }))
+}
+
+// 数据库列表响应
+#[derive(Debug, Serialize)]
+pub struct DatabaseListResponse {
+    pub connection_id: i64,
+    pub databases: Vec<String>,
+    pub current_database: Option<String>,  // 连接配置的数据库
+}
+
+/**
+ * 获取连接所在服务器的数据库列表处理函数
+ * 查询时可通过 database 参数切换到列表中的数据库，无需修改连接配置
+ */
+pub async fn list_connection_databases(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(id): Path<i64>,
+) -> Result<Json<DatabaseListResponse>, ApiError> {
+    info!("[API] GET /api/connections/{}/databases - 请求", id);
+
+    let (_, db_manager) = connect_database(&storage, Some(id)).await?;
+    if matches!(db_manager.db_type, crate::db::DatabaseType::Redis) {
+        return Err(ApiError::bad_request("unsupported_database", "Redis不支持列出数据库"));
+    }
+    let databases = db_manager.list_databases().await
+        .map_err(|e| ApiError::internal("query_failed", format!("获取数据库列表失败: {}", e)))?;
+    let current_database = db_manager.current_database().await
+        .map_err(|e| ApiError::internal("query_failed", format!("获取当前数据库失败: {}", e)))?;
+
+    info!("[API] GET /api/connections/{}/databases - 响应成功: 数据库数量={}", id, databases.len());
+    Ok(Json(DatabaseListResponse {
+        connection_id: id,
+        databases,
+        current_database,
+    }))
+}
@@ -0,0 +1,293 @@
+use std::time::Instant;
+use axum::{
+    extract::{Multipart, Query},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database, get_result_cache, get_schema_cache, production_guard, register_canceller};
+use crate::db::{DatabaseManager, DatabasePool, LocalStorageManager};
+use crate::models::DatabaseConnection as DbConnection;
+use crate::services::audit::{self, AuditRecord};
+use crate::services::schema_cache;
+use crate::services::script_runner::{ScriptResult, ScriptRunner, MAX_SCRIPT_STATEMENTS};
+use crate::utils::sql_script::{split_script, ScriptSplitter, ScriptStatement};
+
+// 上传SQL文件大小上限
+pub const MAX_UPLOAD_BYTES: usize = 100 * 1024 * 1024;
+// 上传SQL文件最多包含的语句数（种子数据文件通常由大量INSERT组成）
+const MAX_UPLOAD_STATEMENTS: usize = 200_000;
+
+// SQL脚本执行请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScriptRequest {
+    pub script: String,
+    pub connection_id: Option<i64>,
+    // 出错后停止执行后续语句（默认true）
+    #[serde(default = "default_stop_on_error")]
+    pub stop_on_error: bool,
+    // 在一个事务中执行，出错时回滚（MySQL的DDL会隐式提交，无法回滚）
+    #[serde(default)]
+    pub use_transaction: bool,
+    // 脚本ID（可选，由客户端指定），用于订阅 /api/ws 进度事件和通过 /api/database/query/:id/cancel 取消
+    #[serde(default)]
+    pub script_id: Option<String>,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+fn default_stop_on_error() -> bool {
+    true
+}
+
+// 在同一个连接上依次执行脚本语句（保证 USE、SET、临时表等会话状态在后续语句中有效）
+// 返回是否已回滚（事务模式下出错或被取消时回滚）
+macro_rules! run_script_on {
+    ($pool:expr, $runner:expr, $cancel_rx:expr, $use_transaction:expr) => {{
+        let mut conn = $pool.acquire().await
+            .map_err(|e| ApiError::internal("connection_failed", format!("获取数据库连接失败: {}", e)))?;
+        if $use_transaction {
+            sqlx::Executor::execute(&mut *conn, "BEGIN").await
+                .map_err(|e| ApiError::db("database_error", format!("开启事务失败: {}", e)))?;
+        }
+        while let Some(sql) = $runner.next_statement() {
+            if $cancel_rx.try_recv().is_ok() {
+                warn!("[API] SQL脚本已取消");
+                $runner.cancel();
+                break;
+            }
+            let start = Instant::now();
+            // 无参数语句使用文本协议执行，支持存储过程定义等无法预处理的语句
+            let result = sqlx::Executor::execute(&mut *conn, sql.as_str()).await
+                .map(|r| r.rows_affected())
+                .map_err(|e| e.to_string());
+            $runner.record(result, start.elapsed());
+        }
+        if $use_transaction {
+            let rollback = $runner.has_failed() || $runner.is_cancelled();
+            let end = if rollback { "ROLLBACK" } else { "COMMIT" };
+            sqlx::Executor::execute(&mut *conn, end).await
+                .map_err(|e| ApiError::db("database_error", format!("结束事务失败: {}", e)))?;
+            rollback
+        } else {
+            false
+        }
+    }};
+}
+
+// 脚本执行选项
+struct ScriptOptions {
+    stop_on_error: bool,
+    use_transaction: bool,
+    script_id: Option<String>,
+    confirm_production: bool,
+}
+
+/**
+ * 执行SQL脚本处理函数
+ * 按语句依次执行，每条语句执行后通过 /api/ws 推送 script_progress 事件
+ */
+pub async fn execute_script(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<ScriptRequest>,
+) -> Result<Json<ScriptResult>, ApiError> {
+    info!("[API] POST /api/database/script - 请求: 脚本长度={}, stop_on_error={}, use_transaction={}",
+        payload.script.len(), payload.stop_on_error, payload.use_transaction);
+
+    let (connection, db_manager) = connect_database(&storage, payload.connection_id).await?;
+    let statements = split_script(&payload.script, db_manager.db_type);
+    let options = ScriptOptions {
+        stop_on_error: payload.stop_on_error,
+        use_transaction: payload.use_transaction,
+        script_id: payload.script_id,
+        confirm_production: payload.confirm_production,
+    };
+
+    let result = run_script(&storage, &connection, &db_manager, statements, options, MAX_SCRIPT_STATEMENTS).await?;
+    info!("[API] POST /api/database/script - 执行完成: script_id={}, 成功={}, 失败={}, 跳过={}, 耗时={}ms",
+        result.script_id, result.succeeded, result.failed, result.skipped, result.execution_time_ms);
+    Ok(Json(result))
+}
+
+// 上传SQL文件的执行参数（通过查询字符串指定）
+#[derive(Debug, Deserialize)]
+pub struct ScriptUploadParams {
+    pub connection_id: Option<i64>,
+    #[serde(default = "default_stop_on_error")]
+    pub stop_on_error: bool,
+    #[serde(default)]
+    pub use_transaction: bool,
+    #[serde(default)]
+    pub script_id: Option<String>,
+    #[serde(default)]
+    pub confirm_production: bool,
+}
+
+/**
+ * 上传并执行SQL文件处理函数（multipart，文件字段名为 file）
+ * 文件按块读取并增量拆分为语句，读取完成后依次执行
+ */
+pub async fn upload_script(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ScriptUploadParams>,
+    mut multipart: Multipart,
+) -> Result<Json<ScriptResult>, ApiError> {
+    info!("[API] POST /api/database/script/upload - 请求: connection_id={:?}, stop_on_error={}, use_transaction={}",
+        params.connection_id, params.stop_on_error, params.use_transaction);
+
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+
+    let mut field = loop {
+        let field = multipart.next_field().await
+            .map_err(|e| ApiError::bad_request("invalid_upload", format!("读取上传内容失败: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("missing_file", "请求中没有名为 file 的SQL文件"))?;
+        if field.name() == Some("file") {
+            break field;
+        }
+    };
+    let file_name = field.file_name().unwrap_or("script.sql").to_string();
+
+    let mut splitter = ScriptSplitter::new(db_manager.db_type);
+    let mut statements = Vec::new();
+    let mut decoder = Utf8ChunkDecoder::default();
+    let mut total_bytes = 0usize;
+    while let Some(chunk) = field.chunk().await
+        .map_err(|e| ApiError::bad_request("invalid_upload", format!("读取上传文件失败: {}", e)))?
+    {
+        total_bytes += chunk.len();
+        if total_bytes > MAX_UPLOAD_BYTES {
+            return Err(ApiError::bad_request("file_too_large", format!("SQL文件不能超过 {}MB", MAX_UPLOAD_BYTES / 1024 / 1024)));
+        }
+        let text = decoder.decode(&chunk)
+            .map_err(|_| ApiError::bad_request("invalid_encoding", "SQL文件必须为UTF-8编码"))?;
+        statements.extend(splitter.feed(&text));
+        if statements.len() > MAX_UPLOAD_STATEMENTS {
+            return Err(ApiError::bad_request("script_too_large", format!("SQL文件最多包含 {} 条语句", MAX_UPLOAD_STATEMENTS)));
+        }
+    }
+    if !decoder.is_empty() {
+        return Err(ApiError::bad_request("invalid_encoding", "SQL文件必须为UTF-8编码"));
+    }
+    statements.extend(splitter.finish());
+    info!("[API] 已读取SQL文件: {}, 大小={}字节, 语句数={}", file_name, total_bytes, statements.len());
+
+    let options = ScriptOptions {
+        stop_on_error: params.stop_on_error,
+        use_transaction: params.use_transaction,
+        script_id: params.script_id,
+        confirm_production: params.confirm_production,
+    };
+    let result = run_script(&storage, &connection, &db_manager, statements, options, MAX_UPLOAD_STATEMENTS).await?;
+    info!("[API] POST /api/database/script/upload - 执行完成: script_id={}, 成功={}, 失败={}, 跳过={}, 耗时={}ms",
+        result.script_id, result.succeeded, result.failed, result.skipped, result.execution_time_ms);
+    Ok(Json(result))
+}
+
+// 按块解码UTF-8，多字节字符被截断在块尾时留到下一块
+#[derive(Default)]
+struct Utf8ChunkDecoder {
+    remainder: Vec<u8>,
+    started: bool,
+}
+
+impl Utf8ChunkDecoder {
+    fn decode(&mut self, chunk: &[u8]) -> Result<String, std::str::Utf8Error> {
+        self.remainder.extend_from_slice(chunk);
+        let valid_len = match std::str::from_utf8(&self.remainder) {
+            Ok(_) => self.remainder.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(e),
+        };
+        let rest = self.remainder.split_off(valid_len);
+        let bytes = std::mem::replace(&mut self.remainder, rest);
+        let mut text = String::from_utf8(bytes).expect("已校验为UTF-8");
+        // 去掉文件开头的BOM
+        if !self.started && !text.is_empty() {
+            self.started = true;
+            if let Some(stripped) = text.strip_prefix('\u{feff}') {
+                text = stripped.to_string();
+            }
+        }
+        Ok(text)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.remainder.is_empty()
+    }
+}
+
+// 校验语句数量和生产环境策略后执行脚本
+async fn run_script(
+    storage: &LocalStorageManager,
+    connection: &DbConnection,
+    db_manager: &DatabaseManager,
+    statements: Vec<ScriptStatement>,
+    options: ScriptOptions,
+    max_statements: usize,
+) -> Result<ScriptResult, ApiError> {
+    if statements.is_empty() {
+        return Err(ApiError::bad_request("empty_script", "脚本中没有可执行的语句"));
+    }
+    if statements.len() > max_statements {
+        return Err(ApiError::bad_request("script_too_large", format!("脚本最多包含 {} 条语句，当前 {} 条", max_statements, statements.len())));
+    }
+
+    let sqls: Vec<String> = statements.iter().map(|s| s.sql.clone()).collect();
+    production_guard(connection, &sqls, options.confirm_production)?;
+
+    // 注册取消通道，与普通查询共用取消接口
+    let script_id = options.script_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let Some((_canceller, mut cancel_rx)) = register_canceller(&script_id) else {
+        return Err(ApiError::conflict("query_id_conflict", format!("脚本ID {} 正在执行中", script_id)));
+    };
+
+    info!("[API] 开始执行SQL脚本: script_id={}, 语句数={}", script_id, statements.len());
+    // 事务模式下出错必须停止，否则回滚前的后续语句没有意义
+    let stop_on_error = options.stop_on_error || options.use_transaction;
+    let use_transaction = options.use_transaction;
+    let mut runner = ScriptRunner::new(script_id.clone(), statements, stop_on_error);
+
+    let outcome: Result<bool, ApiError> = async {
+        match &db_manager.pool {
+            DatabasePool::MySQL(pool) => Ok(run_script_on!(pool, runner, cancel_rx, use_transaction)),
+            DatabasePool::PostgreSQL(pool) => Ok(run_script_on!(pool, runner, cancel_rx, use_transaction)),
+            DatabasePool::SQLite(pool) => Ok(run_script_on!(pool, runner, cancel_rx, use_transaction)),
+            DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => Err(ApiError::not_implemented(
+                "unsupported_database",
+                format!("{:?}不支持执行SQL脚本", db_manager.db_type),
+            )),
+        }
+    }.await;
+    let rolled_back = outcome?;
+
+    // 已执行的写操作逐条记录审计日志，事务回滚的语句标记为失败
+    for (sql, result) in runner.attempted() {
+        let error = match &result.error {
+            Some(error) => Some(error.clone()),
+            None if rolled_back => Some("事务已回滚".to_string()),
+            None => None,
+        };
+        audit::record_in_background(storage, AuditRecord {
+            connection,
+            source: audit::SOURCE_SCRIPT,
+            ai_generated: false,
+            affected_rows: result.rows_affected.map(|rows| rows as i64),
+            error,
+        }, &[sql.to_string()]);
+    }
+
+    // 执行过结构变更语句时清除表结构缓存，脚本修改过数据时清除查询结果缓存
+    if let Some(id) = connection.id {
+        if !rolled_back && runner.executed_sql().any(schema_cache::changes_schema) {
+            get_schema_cache().invalidate(id);
+        }
+        if !rolled_back && runner.attempted().next().is_some() {
+            get_result_cache().invalidate(id);
+        }
+    }
+
+    Ok(runner.finish(rolled_back))
+}
@@ -0,0 +1,36 @@
+use axum::{Extension, Json};
+use log::*;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::services::app_settings::{self, AppSettings};
+
+/**
+ * 获取应用设置处理函数
+ */
+pub async fn get_settings(
+    Extension(storage): Extension<LocalStorageManager>,
+) -> Result<Json<AppSettings>, ApiError> {
+    info!("[API] GET /api/settings - 获取应用设置请求");
+
+    Ok(Json(app_settings::load_settings(&storage).await))
+}
+
+/**
+ * 保存应用设置处理函数
+ * 未提供的字段使用默认值，保存后立即生效（日志级别、历史清理）
+ */
+pub async fn update_settings(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<AppSettings>,
+) -> Result<Json<AppSettings>, ApiError> {
+    info!("[API] PUT /api/settings - 保存应用设置请求: {:?}", payload);
+
+    payload.validate().map_err(|e| ApiError::bad_request("invalid_settings", e))?;
+    app_settings::save_settings(&storage, &payload).await
+        .map_err(|e| ApiError::db("database_error", e))?;
+    app_settings::apply(&storage, &payload).await;
+
+    info!("[API] PUT /api/settings - 应用设置已保存");
+    Ok(Json(payload))
+}
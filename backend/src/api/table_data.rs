@@ -0,0 +1,367 @@
+use axum::{
+    extract::{Path, Query},
+    routing::post,
+    Extension, Json, Router,
+};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value as JsonValue};
+use sqlx::{Column, Row};
+use log::*;
+
+use crate::api::ddl::ConnectionParams;
+use crate::api::error::ApiError;
+use crate::api::routes::{connect_database, get_result_cache, get_table_structure_internal, production_guard};
+use crate::db::ddl::quote_identifier;
+use crate::db::{bind_json_values, DatabasePool, DatabaseType, LocalStorageManager, RowValues};
+use crate::models::DatabaseConnection;
+use crate::services::audit::{self, AuditRecord};
+use crate::services::result_edit::resolve_editable_column;
+
+// 插入行请求
+#[derive(Serialize, Deserialize)]
+pub struct InsertRowRequest {
+    pub values: Map<String, JsonValue>,
+}
+
+// 更新行请求（按主键定位）
+#[derive(Serialize, Deserialize)]
+pub struct UpdateRowRequest {
+    pub primary_key: Map<String, JsonValue>,
+    pub values: Map<String, JsonValue>,
+}
+
+// 删除行请求（按主键定位）
+#[derive(Serialize, Deserialize)]
+pub struct DeleteRowRequest {
+    pub primary_key: Map<String, JsonValue>,
+}
+
+// 行编辑响应
+#[derive(Serialize, Deserialize)]
+pub struct RowMutationResponse {
+    pub success: bool,
+    pub affected_rows: u64,
+    pub sql: String,
+}
+
+// 查询结果单元格编辑请求（按主键定位行）
+#[derive(Serialize, Deserialize)]
+pub struct CellUpdateRequest {
+    pub sql: String,              // 产生该结果的原始SELECT
+    #[serde(default)]
+    pub table: Option<String>,    // 目标表（可选，用于校验）
+    pub primary_key: Map<String, JsonValue>,
+    pub column: String,           // 结果列名
+    pub value: JsonValue,
+}
+
+// 单元格编辑响应（附带修改后的整行）
+#[derive(Serialize, Deserialize)]
+pub struct CellUpdateResponse {
+    pub success: bool,
+    pub affected_rows: u64,
+    pub sql: String,
+    pub columns: Vec<String>,
+    pub row: Option<Vec<JsonValue>>,
+}
+
+// 行数据编辑路由（挂载在 /api/database/table 下）
+pub fn row_routes() -> Router {
+    Router::new()
+        // 插入行 / 更新行 / 删除行
+        .route("/:table/rows", post(insert_row).put(update_row).delete(delete_row))
+}
+
+// 在事务中执行参数化语句，影响行数超过 max_rows 时回滚
+macro_rules! execute_in_transaction {
+    ($pool:expr, $sql:expr, $values:expr, $max_rows:expr) => {{
+        let mut tx = $pool.begin().await
+            .map_err(|e| ApiError::bad_request("database_error", format!("开启事务失败: {}", e)))?;
+        let affected = bind_json_values!(sqlx::query($sql), $values)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::bad_request("query_error", format!("执行失败: {}", e)))?
+            .rows_affected();
+        if let Some(max_rows) = $max_rows {
+            if affected > max_rows {
+                let _ = tx.rollback().await;
+                return Err(ApiError::bad_request(
+                    "too_many_rows",
+                    format!("主键条件匹配了 {} 行，已回滚，请确认主键列是否正确", affected),
+                ));
+            }
+        }
+        tx.commit().await
+            .map_err(|e| ApiError::bad_request("database_error", format!("提交事务失败: {}", e)))?;
+        affected
+    }};
+}
+
+// 按主键读取单行，返回 (列名, 行数据)
+macro_rules! fetch_row_by_key {
+    ($pool:expr, $sql:expr, $values:expr) => {{
+        bind_json_values!(sqlx::query($sql), $values)
+            .fetch_optional($pool)
+            .await
+            .map_err(|e| ApiError::bad_request("query_error", format!("读取修改后的行失败: {}", e)))?
+            .map(|row| (row.columns().iter().map(|c| c.name().to_string()).collect::<Vec<_>>(), row.json_values()))
+    }};
+}
+
+// 生成第n个参数占位符
+fn placeholder(db_type: DatabaseType, n: usize) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!("${}", n),
+        _ => "?".to_string(),
+    }
+}
+
+// 生成 列 = 占位符 列表，返回 (子句列表, 参数值)
+fn assignments(
+    db_type: DatabaseType,
+    values: &Map<String, JsonValue>,
+    start: usize,
+) -> Result<(Vec<String>, Vec<&JsonValue>), String> {
+    let mut clauses = Vec::new();
+    let mut params = Vec::new();
+    for (i, (column, value)) in values.iter().enumerate() {
+        clauses.push(format!("{} = {}", quote_identifier(db_type, column)?, placeholder(db_type, start + i)));
+        params.push(value);
+    }
+    Ok((clauses, params))
+}
+
+// 行编辑完成后记录审计日志，修改成功时清除该连接的查询结果缓存
+fn record_mutation(storage: &LocalStorageManager, connection: &DatabaseConnection, sql: &str, outcome: &Result<u64, ApiError>) {
+    if let (Ok(_), Some(id)) = (outcome, connection.id) {
+        get_result_cache().invalidate(id);
+    }
+    audit::record_in_background(storage, AuditRecord {
+        connection,
+        source: audit::SOURCE_TABLE_DATA,
+        ai_generated: false,
+        affected_rows: outcome.as_ref().ok().map(|rows| *rows as i64),
+        error: outcome.as_ref().err().map(|e| e.message().to_string()),
+    }, &[sql.to_string()]);
+}
+
+// 执行参数化语句
+async fn execute_mutation(
+    storage: &LocalStorageManager,
+    params: &ConnectionParams,
+    build: impl FnOnce(DatabaseType) -> Result<(String, Vec<JsonValue>), String>,
+    max_rows: Option<u64>,
+) -> Result<Json<RowMutationResponse>, ApiError> {
+    let (connection, db_manager) = connect_database(storage, params.connection_id).await?;
+
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}暂不支持表格数据编辑", db_manager.db_type)));
+    }
+
+    let (sql, values) = build(db_manager.db_type).map_err(|e| ApiError::bad_request("invalid_request", e))?;
+    production_guard(&connection, std::slice::from_ref(&sql), params.confirm_production)?;
+    info!("[API] 执行行编辑: SQL={}, 参数数量={}", sql, values.len());
+
+    let outcome: Result<u64, ApiError> = async {
+        Ok(match &db_manager.pool {
+            DatabasePool::MySQL(pool) => execute_in_transaction!(pool, &sql, &values, max_rows),
+            DatabasePool::PostgreSQL(pool) => execute_in_transaction!(pool, &sql, &values, max_rows),
+            DatabasePool::SQLite(pool) => execute_in_transaction!(pool, &sql, &values, max_rows),
+            DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => unreachable!(),
+        })
+    }.await;
+    record_mutation(storage, &connection, &sql, &outcome);
+    let affected_rows = outcome?;
+
+    info!("[API] 行编辑成功: 影响行数={}", affected_rows);
+
+    Ok(Json(RowMutationResponse {
+        success: true,
+        affected_rows,
+        sql,
+    }))
+}
+
+/**
+ * 插入行处理函数
+ */
+pub async fn insert_row(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<InsertRowRequest>,
+) -> Result<Json<RowMutationResponse>, ApiError> {
+    info!("[API] POST /api/database/table/{}/rows - 插入行请求: 列数={}", table, payload.values.len());
+
+    execute_mutation(&storage, &params, |db_type| {
+        if payload.values.is_empty() {
+            return Err("插入的数据不能为空".to_string());
+        }
+        let columns = payload.values.keys()
+            .map(|c| quote_identifier(db_type, c))
+            .collect::<Result<Vec<_>, _>>()?;
+        let placeholders: Vec<String> = (1..=columns.len()).map(|n| placeholder(db_type, n)).collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_identifier(db_type, &table)?,
+            columns.join(", "),
+            placeholders.join(", ")
+        );
+        Ok((sql, payload.values.values().cloned().collect()))
+    }, None).await
+}
+
+/**
+ * 更新行处理函数（按主键）
+ */
+pub async fn update_row(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<UpdateRowRequest>,
+) -> Result<Json<RowMutationResponse>, ApiError> {
+    info!("[API] PUT /api/database/table/{}/rows - 更新行请求: 主键={:?}", table, payload.primary_key);
+
+    execute_mutation(&storage, &params, |db_type| {
+        if payload.primary_key.is_empty() {
+            return Err("必须指定主键".to_string());
+        }
+        if payload.values.is_empty() {
+            return Err("更新的数据不能为空".to_string());
+        }
+        let (set_clauses, mut values) = assignments(db_type, &payload.values, 1)?;
+        let (where_clauses, key_values) = assignments(db_type, &payload.primary_key, set_clauses.len() + 1)?;
+        values.extend(key_values);
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            quote_identifier(db_type, &table)?,
+            set_clauses.join(", "),
+            where_clauses.join(" AND ")
+        );
+        Ok((sql, values.into_iter().cloned().collect()))
+    }, Some(1)).await
+}
+
+/**
+ * 删除行处理函数（按主键）
+ */
+pub async fn delete_row(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(table): Path<String>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<DeleteRowRequest>,
+) -> Result<Json<RowMutationResponse>, ApiError> {
+    info!("[API] DELETE /api/database/table/{}/rows - 删除行请求: 主键={:?}", table, payload.primary_key);
+
+    execute_mutation(&storage, &params, |db_type| {
+        if payload.primary_key.is_empty() {
+            return Err("必须指定主键".to_string());
+        }
+        let (where_clauses, values) = assignments(db_type, &payload.primary_key, 1)?;
+        let sql = format!(
+            "DELETE FROM {} WHERE {}",
+            quote_identifier(db_type, &table)?,
+            where_clauses.join(" AND ")
+        );
+        Ok((sql, values.into_iter().cloned().collect()))
+    }, Some(1)).await
+}
+
+/**
+ * 查询结果单元格编辑处理函数
+ * 根据原始SELECT推断结果列所属的表和列，按主键生成参数化UPDATE并返回修改后的行
+ */
+pub async fn update_result_cell(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<ConnectionParams>,
+    Json(payload): Json<CellUpdateRequest>,
+) -> Result<Json<CellUpdateResponse>, ApiError> {
+    info!("[API] PUT /api/database/query/cell - 请求: 列={}, 主键={:?}", payload.column, payload.primary_key);
+
+    let target = resolve_editable_column(&payload.sql, &payload.column, payload.table.as_deref())
+        .map_err(|e| ApiError::bad_request("not_editable", e))?;
+    let (connection, db_manager) = connect_database(&storage, params.connection_id).await?;
+    if matches!(db_manager.db_type, DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB) {
+        return Err(ApiError::bad_request("unsupported_database", format!("{:?}暂不支持结果编辑", db_manager.db_type)));
+    }
+
+    // 表结构和 UPDATE 都按当前schema中的表名处理，限定了其他schema的查询不可编辑
+    if let Some(schema) = &target.schema {
+        let current = db_manager.current_schema().await
+            .map_err(|e| ApiError::db("database_error", format!("获取当前schema失败: {}", e)))?;
+        if !current.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(schema)) {
+            return Err(ApiError::bad_request("not_editable", format!(
+                "表 {}.{} 不在当前schema {} 中，无法编辑",
+                schema, target.table, current.as_deref().unwrap_or("-")
+            )));
+        }
+    }
+
+    // 主键必须与表的主键列完全一致，避免误改多行
+    let schema = get_table_structure_internal(&db_manager, &target.table).await
+        .map_err(|e| ApiError::bad_request("table_not_found", e))?;
+    let key_columns: Vec<&str> = schema.columns.iter()
+        .filter(|c| c.is_primary_key.unwrap_or(false))
+        .map(|c| c.name.as_str())
+        .collect();
+    if key_columns.is_empty() {
+        return Err(ApiError::bad_request("no_primary_key", format!("表 {} 没有主键，无法定位要修改的行", target.table)));
+    }
+    if key_columns.len() != payload.primary_key.len() || !key_columns.iter().all(|c| payload.primary_key.contains_key(*c)) {
+        return Err(ApiError::bad_request("invalid_primary_key", format!("主键必须包含且仅包含列: {}", key_columns.join(", "))));
+    }
+    let column = schema.columns.iter()
+        .find(|c| c.name.eq_ignore_ascii_case(&target.column))
+        .map(|c| c.name.clone())
+        .ok_or_else(|| ApiError::bad_request("column_not_found", format!("表 {} 中不存在列 {}", target.table, target.column)))?;
+
+    let db_type = db_manager.db_type;
+    let table = quote_identifier(db_type, &target.table).map_err(|e| ApiError::bad_request("invalid_request", e))?;
+    let changes = Map::from_iter([(column.clone(), payload.value.clone())]);
+    let (set_clauses, mut values) = assignments(db_type, &changes, 1).map_err(|e| ApiError::bad_request("invalid_request", e))?;
+    let (where_clauses, key_values) = assignments(db_type, &payload.primary_key, 2).map_err(|e| ApiError::bad_request("invalid_request", e))?;
+    values.extend(key_values);
+    let values: Vec<JsonValue> = values.into_iter().cloned().collect();
+    let sql = format!("UPDATE {} SET {} WHERE {}", table, set_clauses.join(", "), where_clauses.join(" AND "));
+    production_guard(&connection, std::slice::from_ref(&sql), params.confirm_production)?;
+    info!("[API] 执行单元格编辑: SQL={}, 参数数量={}", sql, values.len());
+
+    let outcome: Result<u64, ApiError> = async {
+        Ok(match &db_manager.pool {
+            DatabasePool::MySQL(pool) => execute_in_transaction!(pool, &sql, &values, Some(1)),
+            DatabasePool::PostgreSQL(pool) => execute_in_transaction!(pool, &sql, &values, Some(1)),
+            DatabasePool::SQLite(pool) => execute_in_transaction!(pool, &sql, &values, Some(1)),
+            DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => unreachable!(),
+        })
+    }.await;
+    record_mutation(&storage, &connection, &sql, &outcome);
+    let affected_rows = outcome?;
+
+    // 重新读取修改后的行（编辑的是主键列时按新值定位）
+    let mut lookup = payload.primary_key.clone();
+    if let Some(key) = lookup.get_mut(&column) {
+        *key = payload.value.clone();
+    }
+    let (where_clauses, key_values) = assignments(db_type, &lookup, 1).map_err(|e| ApiError::bad_request("invalid_request", e))?;
+    let key_values: Vec<JsonValue> = key_values.into_iter().cloned().collect();
+    let select_sql = format!("SELECT * FROM {} WHERE {}", table, where_clauses.join(" AND "));
+    let fetched = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => fetch_row_by_key!(pool, &select_sql, &key_values),
+        DatabasePool::PostgreSQL(pool) => fetch_row_by_key!(pool, &select_sql, &key_values),
+        DatabasePool::SQLite(pool) => fetch_row_by_key!(pool, &select_sql, &key_values),
+        DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => unreachable!(),
+    };
+    let (columns, row) = match fetched {
+        Some((columns, row)) => (columns, Some(row)),
+        None => (Vec::new(), None),
+    };
+
+    info!("[API] PUT /api/database/query/cell - 响应成功: 影响行数={}", affected_rows);
+    Ok(Json(CellUpdateResponse {
+        success: true,
+        affected_rows,
+        sql,
+        columns,
+        row,
+    }))
+}
@@ -0,0 +1,168 @@
+use axum::{
+    extract::Path,
+    routing::get,
+    Extension, Json, Router,
+};
+use log::*;
+use serde::Deserialize;
+
+use crate::api::error::ApiError;
+use crate::db::LocalStorageManager;
+use crate::models::{ConnectionTemplateBinding, TemplateType};
+use crate::services::ai::AiService;
+use crate::services::templates::{PromptTemplate, TemplateManager};
+
+// 绑定模板请求
+#[derive(Debug, Deserialize)]
+pub struct TemplateBindingRequest {
+    pub template_id: String,
+}
+
+// 连接模板绑定路由（挂载在 /api/connections 下）
+pub fn template_binding_routes() -> Router {
+    Router::new()
+        // 连接的模板绑定列表
+        .route("/:id/templates", get(list_template_bindings))
+        // 绑定 / 解除绑定某种类型的模板
+        .route("/:id/templates/:template_type", get(get_template_binding).put(set_template_binding).delete(delete_template_binding))
+}
+
+// 查找指定类型的模板
+fn find_template(template_manager: &TemplateManager, template_id: &str, template_type: &TemplateType) -> Result<PromptTemplate, ApiError> {
+    let template = template_manager.get_template(template_id)
+        .ok_or_else(|| ApiError::not_found("template_not_found", format!("模板不存在: {}", template_id)))?;
+    if &template.template_type != template_type {
+        return Err(ApiError::bad_request(
+            "template_type_mismatch",
+            format!("模板 {} 的类型是 {}，不能用于 {}", template_id, template.template_type.as_str(), template_type.as_str()),
+        ));
+    }
+    Ok(template.clone())
+}
+
+/**
+ * 选择AI调用使用的提示词模板
+ * 按 请求指定 → 连接绑定 → 全局默认 的顺序，返回使用该模板的AI服务；
+ * 连接绑定的模板已不存在或类型不符时记录警告并使用全局默认模板
+ */
+pub(crate) async fn ai_service_for_template(
+    storage: &LocalStorageManager,
+    template_manager: &TemplateManager,
+    ai_service: &AiService,
+    connection_id: Option<i64>,
+    template_type: TemplateType,
+    requested: Option<&str>,
+) -> Result<AiService, ApiError> {
+    if let Some(template_id) = requested.map(str::trim).filter(|id| !id.is_empty()) {
+        let template = find_template(template_manager, template_id, &template_type)?;
+        debug!("[API] 使用请求指定的模板: {}", template_id);
+        return Ok(ai_service.with_prompt_template(template));
+    }
+
+    let Some(connection_id) = connection_id else {
+        return Ok(ai_service.clone());
+    };
+    let bound = match storage.get_template_binding(connection_id, template_type.as_str()).await {
+        Ok(bound) => bound,
+        Err(e) => {
+            warn!("[API] 读取连接 {} 的模板绑定失败，使用默认模板: {}", connection_id, e);
+            None
+        }
+    };
+    match bound.map(|template_id| find_template(template_manager, &template_id, &template_type)) {
+        Some(Ok(template)) => {
+            debug!("[API] 使用连接 {} 绑定的模板: {}", connection_id, template.template_id);
+            Ok(ai_service.with_prompt_template(template))
+        }
+        Some(Err(e)) => {
+            warn!("[API] 连接 {} 绑定的 {} 模板不可用，使用默认模板: {}", connection_id, template_type.as_str(), e.info().message);
+            Ok(ai_service.clone())
+        }
+        None => Ok(ai_service.clone()),
+    }
+}
+
+// 确认连接存在
+async fn ensure_connection(storage: &LocalStorageManager, connection_id: i64) -> Result<(), ApiError> {
+    match storage.get_connection(connection_id).await {
+        Ok(_) => Ok(()),
+        Err(sqlx::Error::RowNotFound) => Err(ApiError::not_found("connection_not_found", format!("连接不存在: {}", connection_id))),
+        Err(e) => Err(ApiError::db("database_error", format!("获取连接失败: {}", e))),
+    }
+}
+
+/**
+ * 获取连接模板绑定列表处理函数
+ */
+pub async fn list_template_bindings(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path(connection_id): Path<i64>,
+) -> Result<Json<Vec<ConnectionTemplateBinding>>, ApiError> {
+    info!("[API] GET /api/connections/:id/templates - 获取模板绑定请求: connection_id={}", connection_id);
+
+    ensure_connection(&storage, connection_id).await?;
+    storage.list_template_bindings(connection_id).await
+        .map(Json)
+        .map_err(|e| ApiError::db("database_error", format!("获取模板绑定失败: {}", e)))
+}
+
+/**
+ * 获取连接某种类型绑定的模板处理函数
+ */
+pub async fn get_template_binding(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((connection_id, template_type)): Path<(i64, TemplateType)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] GET /api/connections/:id/templates/:template_type - 获取模板绑定请求: connection_id={}, type={}",
+        connection_id, template_type.as_str());
+
+    let template_id = storage.get_template_binding(connection_id, template_type.as_str()).await
+        .map_err(|e| ApiError::db("database_error", format!("获取模板绑定失败: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("binding_not_found", format!("连接未绑定 {} 模板", template_type.as_str())))?;
+    Ok(Json(serde_json::json!({
+        "connection_id": connection_id,
+        "template_type": template_type,
+        "template_id": template_id,
+    })))
+}
+
+/**
+ * 绑定连接模板处理函数
+ * 模板类型需与路径中的类型一致，已有绑定时覆盖
+ */
+pub async fn set_template_binding(
+    Extension(storage): Extension<LocalStorageManager>,
+    Extension(template_manager): Extension<TemplateManager>,
+    Path((connection_id, template_type)): Path<(i64, TemplateType)>,
+    Json(req): Json<TemplateBindingRequest>,
+) -> Result<Json<ConnectionTemplateBinding>, ApiError> {
+    info!("[API] PUT /api/connections/:id/templates/:template_type - 绑定模板请求: connection_id={}, type={}, template_id={}",
+        connection_id, template_type.as_str(), req.template_id);
+
+    ensure_connection(&storage, connection_id).await?;
+    let template = find_template(&template_manager, req.template_id.trim(), &template_type)?;
+    let binding = storage.set_template_binding(connection_id, template_type.as_str(), &template.template_id).await
+        .map_err(|e| ApiError::db("database_error", format!("绑定模板失败: {}", e)))?;
+    Ok(Json(binding))
+}
+
+/**
+ * 解除连接模板绑定处理函数
+ */
+pub async fn delete_template_binding(
+    Extension(storage): Extension<LocalStorageManager>,
+    Path((connection_id, template_type)): Path<(i64, TemplateType)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    info!("[API] DELETE /api/connections/:id/templates/:template_type - 解除模板绑定请求: connection_id={}, type={}",
+        connection_id, template_type.as_str());
+
+    let deleted = storage.delete_template_binding(connection_id, template_type.as_str()).await
+        .map_err(|e| ApiError::db("database_error", format!("解除模板绑定失败: {}", e)))?;
+    if !deleted {
+        return Err(ApiError::not_found("binding_not_found", format!("连接未绑定 {} 模板", template_type.as_str())));
+    }
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "模板绑定已解除",
+    })))
+}
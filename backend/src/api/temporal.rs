@@ -0,0 +1,318 @@
+use axum::{http::StatusCode, Extension, Json, extract::Query};
+use serde::{Serialize, Deserialize};
+use log::*;
+use sqlparser::ast::{DataType, Expr, SelectItem, SetExpr, Statement, TableFactor, TableVersion, TableWithJoins, TimezoneInfo};
+
+use crate::api::routes::{connect_database, parse_sql};
+use crate::db::{DatabaseManager, DatabasePool, DatabaseType, LocalStorageManager};
+use crate::models::ErrorResponse as ModelErrorResponse;
+
+// 时间旅行查询请求
+#[derive(Serialize, Deserialize)]
+pub struct AsOfQueryRequest {
+    pub sql: String,
+    pub timestamp: String,           // 格式: YYYY-MM-DD HH:MM:SS
+    pub table: Option<String>,       // 只改写指定表，不指定时改写所有系统版本表
+    pub connection_id: Option<i64>,
+}
+
+// 时间旅行查询响应
+#[derive(Serialize, Deserialize)]
+pub struct AsOfQueryResponse {
+    pub sql: String,
+    pub engine: String,
+    pub timestamp: String,
+}
+
+// 系统版本表查询参数
+#[derive(Deserialize)]
+pub struct TemporalTablesQuery {
+    pub connection_id: Option<i64>,
+}
+
+// 系统版本表元数据响应
+#[derive(Serialize, Deserialize)]
+pub struct TemporalTablesResponse {
+    pub supported: bool,
+    pub engine: String,
+    pub tables: Vec<String>,
+}
+
+fn unsupported_error(message: String) -> (StatusCode, Json<ModelErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ModelErrorResponse {
+            error: "temporal_not_supported".to_string(),
+            message,
+            details: None,
+        })
+    )
+}
+
+// 检测数据库引擎是否支持 FOR SYSTEM_TIME AS OF，返回引擎描述
+async fn detect_temporal_engine(db_manager: &DatabaseManager) -> Result<(bool, String), (StatusCode, Json<ModelErrorResponse>)> {
+    match &db_manager.pool {
+        DatabasePool::MySQL(pool) => {
+            let version = sqlx::query_scalar::<_, String>("SELECT VERSION()")
+                .fetch_one(pool)
+                .await
+                .map_err(|e| (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ModelErrorResponse {
+                        error: "query_error".to_string(),
+                        message: format!("获取数据库版本失败: {}", e),
+                        details: None,
+                    })
+                ))?;
+            // 只有MariaDB支持系统版本表，MySQL不支持
+            let is_mariadb = version.to_lowercase().contains("mariadb");
+            let engine = if is_mariadb { format!("MariaDB {}", version) } else { format!("MySQL {}", version) };
+            Ok((is_mariadb, engine))
+        }
+        DatabasePool::PostgreSQL(_) => Ok((false, "PostgreSQL".to_string())),
+        DatabasePool::SQLite(_) => Ok((false, "SQLite".to_string())),
+        DatabasePool::MongoDB(_, _) => Ok((false, "MongoDB".to_string())),
+        DatabasePool::Redis(_) => Ok((false, "Redis".to_string())),
+        DatabasePool::DuckDB(_) => Ok((false, "DuckDB".to_string())),
+    }
+}
+
+// 在查询的AST上为系统版本表添加 FOR SYSTEM_TIME AS OF 子句
+// 只改写 FROM/JOIN 中引用的表（包括子查询和CTE内部），同名的CTE引用不改写
+struct AsOfRewriter<'a> {
+    tables: &'a [String],       // 需要改写的表名（小写）
+    timestamp: Expr,
+    rewritten: Vec<String>,
+}
+
+impl AsOfRewriter<'_> {
+    fn query(&mut self, query: &mut sqlparser::ast::Query, ctes: &[String]) -> Result<(), String> {
+        let mut scope = ctes.to_vec();
+        if let Some(with) = &mut query.with {
+            let recursive = with.recursive;
+            for cte in &mut with.cte_tables {
+                // 递归CTE可以引用自身，普通CTE中的同名引用指向实际的表
+                let name = cte.alias.name.value.to_lowercase();
+                if recursive {
+                    scope.push(name.clone());
+                }
+                self.query(&mut cte.query, &scope)?;
+                if !recursive {
+                    scope.push(name);
+                }
+            }
+        }
+        self.set_expr(&mut query.body, &scope)
+    }
+
+    fn set_expr(&mut self, body: &mut SetExpr, ctes: &[String]) -> Result<(), String> {
+        match body {
+            SetExpr::Select(select) => {
+                for table_with_joins in &mut select.from {
+                    self.table_with_joins(table_with_joins, ctes)?;
+                }
+                for item in &mut select.projection {
+                    if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item {
+                        self.expr(expr, ctes)?;
+                    }
+                }
+                if let Some(selection) = &mut select.selection {
+                    self.expr(selection, ctes)?;
+                }
+                if let Some(having) = &mut select.having {
+                    self.expr(having, ctes)?;
+                }
+                Ok(())
+            }
+            SetExpr::Query(query) => self.query(query, ctes),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left, ctes)?;
+                self.set_expr(right, ctes)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn table_with_joins(&mut self, table_with_joins: &mut TableWithJoins, ctes: &[String]) -> Result<(), String> {
+        self.table_factor(&mut table_with_joins.relation, ctes)?;
+        for join in &mut table_with_joins.joins {
+            self.table_factor(&mut join.relation, ctes)?;
+        }
+        Ok(())
+    }
+
+    fn table_factor(&mut self, factor: &mut TableFactor, ctes: &[String]) -> Result<(), String> {
+        match factor {
+            TableFactor::Table { name, args: None, version, .. } => {
+                let Some(table) = name.0.last().map(|ident| ident.value.to_lowercase()) else {
+                    return Ok(());
+                };
+                if (name.0.len() == 1 && ctes.contains(&table)) || !self.tables.contains(&table) {
+                    return Ok(());
+                }
+                if version.is_some() {
+                    return Err(format!("表 {} 已包含 FOR SYSTEM_TIME 子句", name));
+                }
+                *version = Some(TableVersion::ForSystemTimeAsOf(self.timestamp.clone()));
+                self.rewritten.push(name.to_string());
+                Ok(())
+            }
+            TableFactor::Derived { subquery, .. } => self.query(subquery, ctes),
+            TableFactor::NestedJoin { table_with_joins, .. } => self.table_with_joins(table_with_joins, ctes),
+            _ => Ok(()),
+        }
+    }
+
+    // 条件和选择列中的子查询
+    fn expr(&mut self, expr: &mut Expr, ctes: &[String]) -> Result<(), String> {
+        match expr {
+            Expr::Subquery(query) | Expr::Exists { subquery: query, .. } => self.query(query, ctes),
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.expr(expr, ctes)?;
+                self.query(subquery, ctes)
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left, ctes)?;
+                self.expr(right, ctes)
+            }
+            Expr::UnaryOp { expr, .. } | Expr::Nested(expr) => self.expr(expr, ctes),
+            _ => Ok(()),
+        }
+    }
+}
+
+// 将SELECT语句中引用的系统版本表改写为 FOR SYSTEM_TIME AS OF 查询
+// versioned_tables 为当前数据库中的系统版本表，未指定 table 时只改写这些表（普通表不支持该子句）
+// 系统版本表仅 MariaDB 支持，按 MySQL 方言解析
+pub(crate) fn apply_as_of(sql: &str, table: Option<&str>, timestamp: &str, versioned_tables: &[String]) -> Result<String, String> {
+    let mut statement = parse_sql(DatabaseType::MySQL, sql)?;
+    let Statement::Query(query) = &mut statement else {
+        return Err("时间旅行查询只支持SELECT语句".to_string());
+    };
+
+    // 校验时间戳格式，避免拼接任意内容
+    let ts = timestamp.trim();
+    if chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f").is_err() {
+        return Err(format!("时间戳格式错误: {}，应为 YYYY-MM-DD HH:MM:SS", timestamp));
+    }
+
+    let versioned: Vec<String> = versioned_tables.iter().map(|t| t.to_lowercase()).collect();
+    let tables = match table {
+        Some(name) => {
+            let name = name.trim_matches('`');
+            if !versioned.contains(&name.to_lowercase()) {
+                return Err(format!("表 {} 不是系统版本表", name));
+            }
+            vec![name.to_lowercase()]
+        }
+        None if versioned.is_empty() => return Err("当前数据库中没有系统版本表".to_string()),
+        None => versioned,
+    };
+
+    let mut rewriter = AsOfRewriter {
+        tables: &tables,
+        timestamp: Expr::TypedString {
+            data_type: DataType::Timestamp(None, TimezoneInfo::None),
+            value: ts.to_string(),
+        },
+        rewritten: Vec::new(),
+    };
+    rewriter.query(query, &[])?;
+    if rewriter.rewritten.is_empty() {
+        return Err(match table {
+            Some(name) => format!("SQL中未找到表: {}", name),
+            None => "SQL中未引用系统版本表".to_string(),
+        });
+    }
+    debug!("[API] 时间旅行查询改写的表: {:?}", rewriter.rewritten);
+
+    Ok(statement.to_string())
+}
+
+// 当前数据库中的系统版本表（MariaDB）
+async fn system_versioned_tables(pool: &sqlx::MySqlPool) -> Result<Vec<String>, (StatusCode, Json<ModelErrorResponse>)> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT TABLE_NAME FROM information_schema.TABLES \
+         WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'SYSTEM VERSIONED' \
+         ORDER BY TABLE_NAME"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ModelErrorResponse {
+            error: "query_error".to_string(),
+            message: format!("获取系统版本表失败: {}", e),
+            details: None,
+        })
+    ))
+}
+
+/**
+ * 时间旅行查询改写处理函数
+ */
+pub async fn rewrite_as_of_query(
+    Extension(storage): Extension<LocalStorageManager>,
+    Json(payload): Json<AsOfQueryRequest>,
+) -> Result<Json<AsOfQueryResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+    info!("[API] POST /api/database/query/as-of - 请求: 时间={}, 表={:?}", payload.timestamp, payload.table);
+
+    let (_, db_manager) = connect_database(&storage, payload.connection_id).await?;
+    let (supported, engine) = detect_temporal_engine(&db_manager).await?;
+
+    if !supported {
+        let message = match db_manager.pool {
+            DatabasePool::PostgreSQL(_) => "PostgreSQL 不支持 AS OF 查询（temporal_tables 等扩展使用独立的历史表），请直接查询历史表".to_string(),
+            _ => format!("{} 不支持系统版本表，时间旅行查询仅支持 MariaDB", engine),
+        };
+        return Err(unsupported_error(message));
+    }
+
+    let versioned_tables = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => system_versioned_tables(pool).await?,
+        _ => Vec::new(),
+    };
+    let sql = apply_as_of(&payload.sql, payload.table.as_deref(), &payload.timestamp, &versioned_tables)
+        .map_err(|e| (
+            StatusCode::BAD_REQUEST,
+            Json(ModelErrorResponse {
+                error: "rewrite_failed".to_string(),
+                message: e,
+                details: Some(payload.sql.clone()),
+            })
+        ))?;
+
+    info!("[API] POST /api/database/query/as-of - 响应成功: SQL={}", sql);
+
+    Ok(Json(AsOfQueryResponse {
+        sql,
+        engine,
+        timestamp: payload.timestamp,
+    }))
+}
+
+/**
+ * 获取系统版本表列表处理函数
+ */
+pub async fn list_temporal_tables(
+    Extension(storage): Extension<LocalStorageManager>,
+    Query(params): Query<TemporalTablesQuery>,
+) -> Result<Json<TemporalTablesResponse>, (StatusCode, Json<ModelErrorResponse>)> {
+    info!("[API] GET /api/database/temporal/tables - 请求: connection_id={:?}", params.connection_id);
+
+    let (_, db_manager) = connect_database(&storage, params.connection_id).await?;
+    let (supported, engine) = detect_temporal_engine(&db_manager).await?;
+
+    let tables = match &db_manager.pool {
+        DatabasePool::MySQL(pool) if supported => system_versioned_tables(pool).await?,
+        _ => vec![],
+    };
+
+    info!("[API] GET /api/database/temporal/tables - 响应成功: 引擎={}, 系统版本表数量={}", engine, tables.len());
+
+    Ok(Json(TemporalTablesResponse {
+        supported,
+        engine,
+        tables,
+    }))
+}
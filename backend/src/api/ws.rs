@@ -0,0 +1,87 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    response::Response,
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use log::*;
+
+use crate::services::events::{self, EventFilter};
+
+// WebSocket订阅参数
+#[derive(Debug, Default, Deserialize)]
+pub struct WsParams {
+    pub events: Option<String>,    // 逗号分隔的事件类型，如 query_started,query_finished；为空时接收全部
+    pub query_id: Option<String>,  // 只接收指定查询（或脚本）的事件
+}
+
+impl WsParams {
+    fn into_filter(self) -> EventFilter {
+        EventFilter {
+            event_types: self.events
+                .map(|types| types.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+                .unwrap_or_default(),
+            query_id: self.query_id.filter(|id| !id.is_empty()),
+        }
+    }
+}
+
+/**
+ * 服务端事件推送处理函数（GET /api/ws，升级为WebSocket）
+ * 推送JSON文本消息，type 字段为 query_started / query_progress / query_finished / schema_refreshed /
+ * script_progress / script_finished / connection_health
+ */
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<WsParams>,
+) -> Response {
+    info!("[API] GET /api/ws - WebSocket连接请求: events={:?}, query_id={:?}", params.events, params.query_id);
+    let filter = params.into_filter();
+    ws.on_upgrade(move |socket| handle_socket(socket, filter))
+}
+
+async fn handle_socket(mut socket: WebSocket, filter: EventFilter) {
+    let mut receiver = events::subscribe();
+
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if !filter.accepts(&event) {
+                        continue;
+                    }
+                    let text = match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("[WS] 事件序列化失败: {}", e);
+                            continue;
+                        }
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                // 客户端处理过慢时跳过被丢弃的事件
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("[WS] 客户端接收过慢，跳过 {} 条事件", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Ping(payload))) => {
+                    if socket.send(Message::Pong(payload)).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // 客户端发送的其他消息忽略
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    debug!("[WS] WebSocket连接已关闭");
+}
@@ -0,0 +1,157 @@
+use regex::Regex;
+
+use super::ddl::{quote_identifier, quote_literal};
+use super::{DatabaseError, DatabaseManager, DatabasePool, DatabaseType};
+
+// 从 SHOW CREATE TABLE 的结果中取出列定义，并去掉原有的 COMMENT 子句
+pub fn mysql_column_definition(create_sql: &str, column: &str) -> Option<String> {
+    lazy_static::lazy_static! {
+        static ref COMMENT_RE: Regex = Regex::new(r"(?i)\s+COMMENT\s+'(?:[^'\\]|\\.|'')*'").unwrap();
+    }
+    let prefix = format!("`{}` ", column.replace('`', "``"));
+    let line = create_sql.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&prefix))?
+        .trim_end_matches(',');
+    // COMMENT 位于列定义末尾，只去掉最后一个匹配，避免误删默认值中的文本
+    let definition = match COMMENT_RE.find_iter(line).last() {
+        Some(m) => format!("{}{}", &line[..m.start()], &line[m.end()..]),
+        None => line.to_string(),
+    };
+    Some(definition)
+}
+
+// 设置表注释的语句，comment 为 None 时清除注释
+pub fn table_comment_sql(db_type: DatabaseType, table: &str, comment: Option<&str>) -> Result<Vec<String>, String> {
+    let table_name = quote_identifier(db_type, table)?;
+    match db_type {
+        DatabaseType::MySQL => Ok(vec![format!("ALTER TABLE {} COMMENT = {}", table_name, quote_literal(db_type, comment.unwrap_or_default()))]),
+        DatabaseType::PostgreSQL => Ok(vec![format!(
+            "COMMENT ON TABLE {} IS {}",
+            table_name,
+            comment.map(|c| quote_literal(db_type, c)).unwrap_or_else(|| "NULL".to_string())
+        )]),
+        _ => Err(format!("{:?}不支持原生表注释", db_type)),
+    }
+}
+
+// 设置列注释的语句；MySQL 需要完整的列定义（由 SHOW CREATE TABLE 得到）
+pub fn column_comment_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    comment: Option<&str>,
+    mysql_definition: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let table_name = quote_identifier(db_type, table)?;
+    let column_name = quote_identifier(db_type, column)?;
+    match db_type {
+        DatabaseType::MySQL => {
+            let definition = mysql_definition.ok_or_else(|| format!("表 {} 中不存在列 {}", table, column))?;
+            Ok(vec![format!(
+                "ALTER TABLE {} MODIFY COLUMN {} COMMENT {}",
+                table_name, definition, quote_literal(db_type, comment.unwrap_or_default())
+            )])
+        }
+        DatabaseType::PostgreSQL => Ok(vec![format!(
+            "COMMENT ON COLUMN {}.{} IS {}",
+            table_name,
+            column_name,
+            comment.map(|c| quote_literal(db_type, c)).unwrap_or_else(|| "NULL".to_string())
+        )]),
+        _ => Err(format!("{:?}不支持原生列注释", db_type)),
+    }
+}
+
+impl DatabaseManager {
+    // 读取表注释（MySQL / PostgreSQL），其他数据库返回 None
+    pub async fn get_table_comment(&self, table_name: &str) -> Result<Option<String>, DatabaseError> {
+        let comment = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_scalar::<_, Option<String>>(
+                    "SELECT TABLE_COMMENT FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?"
+                )
+                .bind(table_name)
+                .fetch_optional(pool)
+                .await?
+                .flatten()
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_scalar::<_, Option<String>>(
+                    r#"SELECT obj_description(c.oid, 'pg_class')
+                     FROM pg_class c
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE c.relname = $1 AND n.nspname = ANY(current_schemas(false))
+                     LIMIT 1"#
+                )
+                .bind(table_name)
+                .fetch_optional(pool)
+                .await?
+                .flatten()
+            }
+            _ => None,
+        };
+        Ok(comment.filter(|c| !c.is_empty()))
+    }
+
+    // 生成设置列注释的语句，MySQL 需要先读取原列定义
+    pub async fn column_comment_statements(&self, table_name: &str, column: &str, comment: Option<&str>) -> Result<Vec<String>, DatabaseError> {
+        let definition = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let quoted = quote_identifier(DatabaseType::MySQL, table_name).map_err(DatabaseError::InvalidDefinition)?;
+                let (_, create_sql) = sqlx::query_as::<_, (String, String)>(&format!("SHOW CREATE TABLE {}", quoted))
+                    .fetch_one(pool)
+                    .await?;
+                mysql_column_definition(&create_sql, column)
+            }
+            _ => None,
+        };
+        column_comment_sql(self.db_type, table_name, column, comment, definition.as_deref())
+            .map_err(DatabaseError::InvalidDefinition)
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREATE_SQL: &str = "CREATE TABLE `users` (\n  `id` int NOT NULL AUTO_INCREMENT,\n  \
+        `name` varchar(50) NOT NULL DEFAULT '' COMMENT '用户''名',\n  \
+        `note` varchar(20) DEFAULT 'COMMENT ''x''',\n  PRIMARY KEY (`id`)\n) ENGINE=InnoDB";
+
+    #[test]
+    fn test_mysql_column_definition() {
+        assert_eq!(mysql_column_definition(CREATE_SQL, "id").unwrap(), "`id` int NOT NULL AUTO_INCREMENT");
+        assert_eq!(mysql_column_definition(CREATE_SQL, "name").unwrap(), "`name` varchar(50) NOT NULL DEFAULT ''");
+        assert_eq!(mysql_column_definition(CREATE_SQL, "note").unwrap(), "`note` varchar(20) DEFAULT 'COMMENT ''x'''");
+        assert!(mysql_column_definition(CREATE_SQL, "email").is_none());
+    }
+
+    #[test]
+    fn test_table_comment_sql() {
+        assert_eq!(
+            table_comment_sql(DatabaseType::MySQL, "users", Some("用户\\表")).unwrap(),
+            vec!["ALTER TABLE `users` COMMENT = '用户\\\\表'"]
+        );
+        assert_eq!(
+            table_comment_sql(DatabaseType::PostgreSQL, "users", None).unwrap(),
+            vec!["COMMENT ON TABLE \"users\" IS NULL"]
+        );
+        assert!(table_comment_sql(DatabaseType::SQLite, "users", Some("x")).is_err());
+    }
+
+    #[test]
+    fn test_column_comment_sql() {
+        let definition = mysql_column_definition(CREATE_SQL, "name");
+        assert_eq!(
+            column_comment_sql(DatabaseType::MySQL, "users", "name", Some("姓名"), definition.as_deref()).unwrap(),
+            vec!["ALTER TABLE `users` MODIFY COLUMN `name` varchar(50) NOT NULL DEFAULT '' COMMENT '姓名'"]
+        );
+        assert!(column_comment_sql(DatabaseType::MySQL, "users", "email", Some("x"), None).is_err());
+        assert_eq!(
+            column_comment_sql(DatabaseType::PostgreSQL, "users", "name", Some("it's"), None).unwrap(),
+            vec!["COMMENT ON COLUMN \"users\".\"name\" IS 'it''s'"]
+        );
+    }
+}
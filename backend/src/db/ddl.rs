@@ -0,0 +1,721 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value as JsonValue;
+use regex::Regex;
+
+use super::DatabaseType;
+
+// 列定义（用于建表、添加列、修改列）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub data_type: String,                  // 如 VARCHAR(255)、INT、DECIMAL(10,2)
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    #[serde(default)]
+    pub default_value: Option<JsonValue>,   // JSON字面量，或 CURRENT_TIMESTAMP 等函数
+    #[serde(default)]
+    pub primary_key: bool,
+    #[serde(default)]
+    pub auto_increment: bool,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+// 建表定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDefinition {
+    pub table_name: String,
+    pub columns: Vec<ColumnDefinition>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+// 允许直接作为默认值的SQL函数
+const DEFAULT_FUNCTIONS: &[&str] = &["CURRENT_TIMESTAMP", "CURRENT_DATE", "CURRENT_TIME", "NOW()"];
+
+// 引用标识符（表名、列名），拒绝包含引号或控制字符的名称
+pub fn quote_identifier(db_type: DatabaseType, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("标识符不能为空".to_string());
+    }
+    if name.chars().any(|c| c == '`' || c == '"' || c.is_control()) {
+        return Err(format!("标识符包含非法字符: {}", name));
+    }
+    match db_type {
+        DatabaseType::MySQL => Ok(format!("`{}`", name)),
+        DatabaseType::PostgreSQL | DatabaseType::SQLite | DatabaseType::DuckDB => Ok(format!("\"{}\"", name)),
+        DatabaseType::MongoDB | DatabaseType::Redis => Err(format!("{:?}不支持DDL操作", db_type)),
+    }
+}
+
+// 校验列类型，只允许 类型名[(精度[,标度])][修饰词][[]] 形式
+pub fn validate_data_type(data_type: &str) -> Result<String, String> {
+    lazy_static::lazy_static! {
+        static ref TYPE_RE: Regex = Regex::new(
+            r"^[A-Za-z][A-Za-z0-9_ ]*(\(\s*\d+\s*(,\s*\d+\s*)?\))?(\s+[A-Za-z ]+)?(\[\])?$"
+        ).unwrap();
+    }
+    let data_type = data_type.trim();
+    if TYPE_RE.is_match(data_type) {
+        Ok(data_type.to_string())
+    } else {
+        Err(format!("不支持的列类型: {}", data_type))
+    }
+}
+
+// 字符串字面量（MySQL 默认将反斜杠视为转义字符，需要额外转义）
+pub fn quote_literal(db_type: DatabaseType, value: &str) -> String {
+    let value = match db_type {
+        DatabaseType::MySQL => value.replace('\\', "\\\\"),
+        _ => value.to_string(),
+    };
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// 将默认值转换为SQL表达式
+fn default_expression(db_type: DatabaseType, value: &JsonValue) -> Result<String, String> {
+    match value {
+        JsonValue::Null => Ok("NULL".to_string()),
+        JsonValue::Bool(b) => Ok(if *b { "TRUE".to_string() } else { "FALSE".to_string() }),
+        JsonValue::Number(n) => Ok(n.to_string()),
+        JsonValue::String(s) => {
+            let upper = s.trim().to_uppercase();
+            if DEFAULT_FUNCTIONS.contains(&upper.as_str()) {
+                Ok(upper)
+            } else {
+                Ok(quote_literal(db_type, s))
+            }
+        }
+        _ => Err("默认值只支持字符串、数字、布尔值或null".to_string()),
+    }
+}
+
+// 生成列定义子句
+// inline_primary_key: 是否在列上直接声明主键（单列主键时使用）
+fn column_clause(db_type: DatabaseType, column: &ColumnDefinition, inline_primary_key: bool) -> Result<String, String> {
+    let mut parts = vec![quote_identifier(db_type, &column.name)?];
+
+    let data_type = validate_data_type(&column.data_type)?;
+    match (db_type, column.auto_increment) {
+        // SQLite自增列必须是 INTEGER PRIMARY KEY
+        (DatabaseType::SQLite, true) => parts.push("INTEGER".to_string()),
+        _ => parts.push(data_type),
+    }
+
+    if column.auto_increment && db_type == DatabaseType::PostgreSQL {
+        parts.push("GENERATED BY DEFAULT AS IDENTITY".to_string());
+    }
+
+    if !column.nullable || column.primary_key {
+        parts.push("NOT NULL".to_string());
+    }
+
+    if let Some(default) = &column.default_value {
+        parts.push(format!("DEFAULT {}", default_expression(db_type, default)?));
+    }
+
+    if inline_primary_key {
+        parts.push("PRIMARY KEY".to_string());
+        if column.auto_increment && db_type == DatabaseType::SQLite {
+            parts.push("AUTOINCREMENT".to_string());
+        }
+    }
+
+    if column.auto_increment && db_type == DatabaseType::MySQL {
+        parts.push("AUTO_INCREMENT".to_string());
+    }
+
+    if let (DatabaseType::MySQL, Some(comment)) = (db_type, &column.comment) {
+        parts.push(format!("COMMENT {}", quote_literal(db_type, comment)));
+    }
+
+    Ok(parts.join(" "))
+}
+
+// PostgreSQL的列注释需要单独的语句
+fn pg_column_comment(table: &str, column: &ColumnDefinition) -> Result<Option<String>, String> {
+    match &column.comment {
+        Some(comment) => Ok(Some(format!(
+            "COMMENT ON COLUMN {}.{} IS {}",
+            quote_identifier(DatabaseType::PostgreSQL, table)?,
+            quote_identifier(DatabaseType::PostgreSQL, &column.name)?,
+            quote_literal(DatabaseType::PostgreSQL, comment)
+        ))),
+        None => Ok(None),
+    }
+}
+
+// 生成建表语句
+pub fn create_table_sql(db_type: DatabaseType, table: &TableDefinition) -> Result<Vec<String>, String> {
+    if table.columns.is_empty() {
+        return Err("建表至少需要一个列".to_string());
+    }
+
+    let table_name = quote_identifier(db_type, &table.table_name)?;
+    let primary_keys: Vec<&ColumnDefinition> = table.columns.iter().filter(|c| c.primary_key).collect();
+    let inline_pk = primary_keys.len() == 1;
+
+    if db_type == DatabaseType::SQLite && table.columns.iter().any(|c| c.auto_increment && !(c.primary_key && inline_pk)) {
+        return Err("SQLite的自增列必须是唯一的主键列".to_string());
+    }
+
+    let mut clauses = Vec::new();
+    for column in &table.columns {
+        clauses.push(column_clause(db_type, column, inline_pk && column.primary_key)?);
+    }
+    if primary_keys.len() > 1 {
+        let keys = primary_keys.iter()
+            .map(|c| quote_identifier(db_type, &c.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        clauses.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+    }
+
+    let mut sql = format!(
+        "CREATE TABLE {}{} (\n    {}\n)",
+        if table.if_not_exists { "IF NOT EXISTS " } else { "" },
+        table_name,
+        clauses.join(",\n    ")
+    );
+    if let (DatabaseType::MySQL, Some(comment)) = (db_type, &table.comment) {
+        sql.push_str(&format!(" COMMENT={}", quote_literal(db_type, comment)));
+    }
+
+    let mut statements = vec![sql];
+    if db_type == DatabaseType::PostgreSQL {
+        if let Some(comment) = &table.comment {
+            statements.push(format!("COMMENT ON TABLE {} IS {}", table_name, quote_literal(db_type, comment)));
+        }
+        for column in &table.columns {
+            if let Some(stmt) = pg_column_comment(&table.table_name, column)? {
+                statements.push(stmt);
+            }
+        }
+    }
+
+    Ok(statements)
+}
+
+// 生成添加列语句
+pub fn add_column_sql(db_type: DatabaseType, table: &str, column: &ColumnDefinition) -> Result<Vec<String>, String> {
+    if column.primary_key {
+        return Err("不支持通过添加列设置主键".to_string());
+    }
+
+    let mut statements = vec![format!(
+        "ALTER TABLE {} ADD COLUMN {}",
+        quote_identifier(db_type, table)?,
+        column_clause(db_type, column, false)?
+    )];
+    if db_type == DatabaseType::PostgreSQL {
+        if let Some(stmt) = pg_column_comment(table, column)? {
+            statements.push(stmt);
+        }
+    }
+    Ok(statements)
+}
+
+// 生成修改列语句（column为原列名，definition.name为新列名）
+pub fn modify_column_sql(db_type: DatabaseType, table: &str, column: &str, definition: &ColumnDefinition) -> Result<Vec<String>, String> {
+    let table_name = quote_identifier(db_type, table)?;
+    let old_name = quote_identifier(db_type, column)?;
+    let new_name = quote_identifier(db_type, &definition.name)?;
+    let renamed = column != definition.name;
+
+    match db_type {
+        DatabaseType::MySQL => Ok(vec![format!(
+            "ALTER TABLE {} CHANGE COLUMN {} {}",
+            table_name,
+            old_name,
+            column_clause(db_type, definition, false)?
+        )]),
+        DatabaseType::PostgreSQL => {
+            let mut statements = Vec::new();
+            if renamed {
+                statements.push(format!("ALTER TABLE {} RENAME COLUMN {} TO {}", table_name, old_name, new_name));
+            }
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {}",
+                table_name, new_name, validate_data_type(&definition.data_type)?
+            ));
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL",
+                table_name, new_name, if definition.nullable { "DROP" } else { "SET" }
+            ));
+            statements.push(match &definition.default_value {
+                Some(default) => format!("ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {}", table_name, new_name, default_expression(DatabaseType::PostgreSQL, default)?),
+                None => format!("ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT", table_name, new_name),
+            });
+            if let Some(stmt) = pg_column_comment(table, definition)? {
+                statements.push(stmt);
+            }
+            Ok(statements)
+        }
+        DatabaseType::SQLite => {
+            // SQLite只支持重命名列，修改类型/约束需要重建表
+            if !renamed {
+                return Err("SQLite不支持修改列类型或约束，请重建表".to_string());
+            }
+            Ok(vec![format!("ALTER TABLE {} RENAME COLUMN {} TO {}", table_name, old_name, new_name)])
+        }
+        DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB => Err(format!("{:?}不支持DDL操作", db_type)),
+    }
+}
+
+// 生成删除列语句
+pub fn drop_column_sql(db_type: DatabaseType, table: &str, column: &str) -> Result<Vec<String>, String> {
+    Ok(vec![format!(
+        "ALTER TABLE {} DROP COLUMN {}",
+        quote_identifier(db_type, table)?,
+        quote_identifier(db_type, column)?
+    )])
+}
+
+// 生成重命名表语句
+pub fn rename_table_sql(db_type: DatabaseType, table: &str, new_name: &str) -> Result<Vec<String>, String> {
+    let old_name = quote_identifier(db_type, table)?;
+    let new_name = quote_identifier(db_type, new_name)?;
+    match db_type {
+        DatabaseType::MySQL => Ok(vec![format!("RENAME TABLE {} TO {}", old_name, new_name)]),
+        _ => Ok(vec![format!("ALTER TABLE {} RENAME TO {}", old_name, new_name)]),
+    }
+}
+
+// 生成删除表语句
+pub fn drop_table_sql(db_type: DatabaseType, table: &str, if_exists: bool, cascade: bool) -> Result<Vec<String>, String> {
+    let mut sql = format!(
+        "DROP TABLE {}{}",
+        if if_exists { "IF EXISTS " } else { "" },
+        quote_identifier(db_type, table)?
+    );
+    if cascade && db_type == DatabaseType::PostgreSQL {
+        sql.push_str(" CASCADE");
+    }
+    Ok(vec![sql])
+}
+
+// 索引定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    pub name: Option<String>,        // 不指定时自动生成 idx_表名_列名
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub unique: bool,
+}
+
+// 生成创建索引语句
+pub fn create_index_sql(db_type: DatabaseType, table: &str, index: &IndexDefinition) -> Result<String, String> {
+    if index.columns.is_empty() {
+        return Err("索引至少需要一个列".to_string());
+    }
+
+    let index_name = match &index.name {
+        Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => format!("idx_{}_{}", table, index.columns.join("_")),
+    };
+    let columns = index.columns.iter()
+        .map(|c| quote_identifier(db_type, c))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        quote_identifier(db_type, &index_name)?,
+        quote_identifier(db_type, table)?,
+        columns.join(", ")
+    ))
+}
+
+// 生成删除索引语句
+pub fn drop_index_sql(db_type: DatabaseType, table: &str, index_name: &str) -> Result<String, String> {
+    match db_type {
+        // MySQL的索引属于表，需要指定表名
+        DatabaseType::MySQL => Ok(format!(
+            "DROP INDEX {} ON {}",
+            quote_identifier(db_type, index_name)?,
+            quote_identifier(db_type, table)?
+        )),
+        _ => Ok(format!("DROP INDEX {}", quote_identifier(db_type, index_name)?)),
+    }
+}
+
+// 视图定义（创建、修改视图）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    pub view_name: String,
+    pub query: String,                      // 视图的 SELECT 语句
+    #[serde(default)]
+    pub columns: Vec<String>,               // 视图的列名（为空时使用查询结果的列名）
+}
+
+// 校验视图查询只包含一条 SELECT 语句，返回去掉末尾分号的查询
+fn validate_view_query(db_type: DatabaseType, query: &str) -> Result<String, String> {
+    let query = query.trim().trim_end_matches(';').trim();
+    if query.is_empty() {
+        return Err("视图查询不能为空".to_string());
+    }
+    let statements = crate::utils::security::parse_statements_as(Some(db_type), query)
+        .map_err(|e| format!("视图查询解析失败: {}", e))?;
+    match statements.as_slice() {
+        [sqlparser::ast::Statement::Query(_)] => Ok(query.to_string()),
+        [_] => Err("视图查询必须是 SELECT 语句".to_string()),
+        _ => Err("视图查询只能包含一条语句".to_string()),
+    }
+}
+
+// 生成创建视图语句，replace 为 true 时替换已有视图（SQLite 不支持 OR REPLACE，先删除再创建）
+pub fn create_view_sql(db_type: DatabaseType, view: &ViewDefinition, replace: bool) -> Result<Vec<String>, String> {
+    let view_name = quote_identifier(db_type, &view.view_name)?;
+    let query = validate_view_query(db_type, &view.query)?;
+    let columns = if view.columns.is_empty() {
+        String::new()
+    } else {
+        let columns = view.columns.iter()
+            .map(|c| quote_identifier(db_type, c))
+            .collect::<Result<Vec<_>, _>>()?;
+        format!(" ({})", columns.join(", "))
+    };
+    let create = format!("VIEW {}{} AS\n{}", view_name, columns, query);
+    match (db_type, replace) {
+        (DatabaseType::SQLite, true) => Ok(vec![format!("DROP VIEW IF EXISTS {}", view_name), format!("CREATE {}", create)]),
+        (_, true) => Ok(vec![format!("CREATE OR REPLACE {}", create)]),
+        (_, false) => Ok(vec![format!("CREATE {}", create)]),
+    }
+}
+
+// 生成删除视图语句
+pub fn drop_view_sql(db_type: DatabaseType, view: &str, if_exists: bool, cascade: bool) -> Result<Vec<String>, String> {
+    let mut sql = format!(
+        "DROP VIEW {}{}",
+        if if_exists { "IF EXISTS " } else { "" },
+        quote_identifier(db_type, view)?
+    );
+    if cascade && db_type == DatabaseType::PostgreSQL {
+        sql.push_str(" CASCADE");
+    }
+    Ok(vec![sql])
+}
+
+// 触发器定义（表名由路径指定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerDefinition {
+    pub trigger_name: String,
+    pub timing: String,                     // BEFORE / AFTER / INSTEAD OF
+    pub events: Vec<String>,                // INSERT / UPDATE / DELETE（MySQL、SQLite 只能指定一个）
+    #[serde(default)]
+    pub when: Option<String>,               // 触发条件（PostgreSQL / SQLite）
+    #[serde(default)]
+    pub body: String,                       // 触发时执行的语句；PostgreSQL 为 PL/pgSQL 函数体
+    #[serde(default)]
+    pub function_name: Option<String>,      // PostgreSQL：使用已有的触发器函数（指定时忽略 body）
+}
+
+// PostgreSQL 触发器函数体的 dollar quote 标记
+const PG_TRIGGER_QUOTE: &str = "$trigger$";
+
+// 去掉末尾分号和空白
+fn trim_statement(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';').trim_end()
+}
+
+fn starts_with_keyword(sql: &str, keyword: &str) -> bool {
+    sql.split_whitespace().next().is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+}
+
+// 生成创建触发器语句（PostgreSQL 未指定 function_name 时先由 body 创建触发器函数）
+pub fn create_trigger_sql(db_type: DatabaseType, table: &str, trigger: &TriggerDefinition) -> Result<Vec<String>, String> {
+    let trigger_name = quote_identifier(db_type, &trigger.trigger_name)?;
+    let table_name = quote_identifier(db_type, table)?;
+
+    let timing = trigger.timing.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase();
+    if !matches!(timing.as_str(), "BEFORE" | "AFTER" | "INSTEAD OF") {
+        return Err(format!("不支持的触发时机: {}", trigger.timing));
+    }
+    if timing == "INSTEAD OF" && db_type == DatabaseType::MySQL {
+        return Err("MySQL不支持 INSTEAD OF 触发器".to_string());
+    }
+
+    let mut events: Vec<String> = Vec::new();
+    for event in &trigger.events {
+        let event = event.trim().to_uppercase();
+        if !matches!(event.as_str(), "INSERT" | "UPDATE" | "DELETE") {
+            return Err(format!("不支持的触发事件: {}", event));
+        }
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    if events.is_empty() {
+        return Err("触发器至少需要一个触发事件".to_string());
+    }
+    if events.len() > 1 && db_type != DatabaseType::PostgreSQL {
+        return Err(format!("{:?}的触发器只能指定一个触发事件", db_type));
+    }
+
+    let condition = match trigger.when.as_deref().map(str::trim).filter(|w| !w.is_empty()) {
+        Some(_) if db_type == DatabaseType::MySQL => return Err("MySQL触发器不支持 WHEN 条件".to_string()),
+        Some(w) if w.contains(';') => return Err("触发条件不能包含分号".to_string()),
+        Some(w) => format!(" WHEN ({})", w),
+        None => String::new(),
+    };
+
+    let body = trim_statement(&trigger.body);
+    let existing_function = trigger.function_name.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    if body.is_empty() && !(db_type == DatabaseType::PostgreSQL && existing_function.is_some()) {
+        return Err("触发器语句不能为空".to_string());
+    }
+
+    let head = format!("CREATE TRIGGER {} {} {} ON {} FOR EACH ROW", trigger_name, timing, events.join(" OR "), table_name);
+    match db_type {
+        DatabaseType::MySQL => Ok(vec![format!("{} {}", head, body)]),
+        DatabaseType::SQLite => {
+            let body = if starts_with_keyword(body, "BEGIN") {
+                body.to_string()
+            } else {
+                format!("BEGIN\n  {};\nEND", body)
+            };
+            Ok(vec![format!("{}{}\n{}", head, condition, body)])
+        }
+        DatabaseType::PostgreSQL => {
+            let mut statements = Vec::new();
+            let function_name = match existing_function {
+                Some(function) => quote_identifier(db_type, function)?,
+                None => {
+                    if body.contains(PG_TRIGGER_QUOTE) {
+                        return Err(format!("触发器函数体不能包含 {}", PG_TRIGGER_QUOTE));
+                    }
+                    // 只有语句时补上 BEGIN ... END，返回 NEW（DELETE 时为 OLD）使 BEFORE 触发器继续执行原操作
+                    let body = if starts_with_keyword(body, "BEGIN") || starts_with_keyword(body, "DECLARE") {
+                        format!("{};", body)
+                    } else {
+                        format!("BEGIN\n  {};\n  RETURN COALESCE(NEW, OLD);\nEND;", body)
+                    };
+                    let function_name = quote_identifier(db_type, &format!("{}_fn", trigger.trigger_name.trim()))?;
+                    statements.push(format!(
+                        "CREATE OR REPLACE FUNCTION {}() RETURNS trigger LANGUAGE plpgsql AS {}\n{}\n{}",
+                        function_name, PG_TRIGGER_QUOTE, body, PG_TRIGGER_QUOTE
+                    ));
+                    function_name
+                }
+            };
+            statements.push(format!("{}{} EXECUTE FUNCTION {}()", head, condition, function_name));
+            Ok(statements)
+        }
+        _ => Err(format!("{:?}不支持触发器", db_type)),
+    }
+}
+
+// 生成删除触发器语句（PostgreSQL 的触发器属于表，需要指定表名）
+pub fn drop_trigger_sql(db_type: DatabaseType, table: &str, trigger: &str, if_exists: bool) -> Result<Vec<String>, String> {
+    let if_exists = if if_exists { "IF EXISTS " } else { "" };
+    let trigger_name = quote_identifier(db_type, trigger)?;
+    match db_type {
+        DatabaseType::PostgreSQL => Ok(vec![format!("DROP TRIGGER {}{} ON {}", if_exists, trigger_name, quote_identifier(db_type, table)?)]),
+        DatabaseType::MySQL | DatabaseType::SQLite => Ok(vec![format!("DROP TRIGGER {}{}", if_exists, trigger_name)]),
+        _ => Err(format!("{:?}不支持触发器", db_type)),
+    }
+}
+
+// 校验通过的生成DDL
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedDdl {
+    pub tables: Vec<String>,        // 创建的表
+    pub statements: Vec<String>,
+}
+
+// 校验AI生成的建表DDL：按方言解析，只允许 CREATE TABLE / CREATE INDEX / COMMENT，且至少创建一张表
+pub fn validate_generated_ddl(db_type: DatabaseType, ddl: &str) -> Result<GeneratedDdl, String> {
+    use sqlparser::ast::Statement;
+
+    let statements = crate::utils::security::parse_statements_as(Some(db_type), ddl)
+        .map_err(|e| format!("DDL解析失败: {}", e))?;
+    let mut tables = Vec::new();
+    for statement in &statements {
+        match statement {
+            Statement::CreateTable { name, .. } => {
+                if let Some(ident) = name.0.last() {
+                    tables.push(ident.value.clone());
+                }
+            }
+            Statement::CreateIndex { .. } | Statement::Comment { .. } => {}
+            other => {
+                let keyword = crate::utils::security::leading_keyword(&other.to_string());
+                return Err(format!("DDL只能包含 CREATE TABLE、CREATE INDEX 和 COMMENT 语句，不允许 {}", keyword));
+            }
+        }
+    }
+    if tables.is_empty() {
+        return Err("DDL中没有 CREATE TABLE 语句".to_string());
+    }
+    Ok(GeneratedDdl {
+        tables,
+        statements: statements.iter().map(|s| s.to_string()).collect(),
+    })
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> ColumnDefinition {
+        ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default_value: None,
+            primary_key: false,
+            auto_increment: false,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn test_create_table_per_dialect() {
+        let mut id = column("id", "BIGINT");
+        id.primary_key = true;
+        id.auto_increment = true;
+        let mut name = column("name", "VARCHAR(100)");
+        name.nullable = false;
+        name.default_value = Some(JsonValue::String("it's".to_string()));
+
+        let table = TableDefinition {
+            table_name: "users".to_string(),
+            columns: vec![id, name],
+            if_not_exists: false,
+            comment: None,
+        };
+
+        let mysql = create_table_sql(DatabaseType::MySQL, &table).unwrap();
+        assert!(mysql[0].contains("`id` BIGINT NOT NULL PRIMARY KEY AUTO_INCREMENT"));
+        assert!(mysql[0].contains("`name` VARCHAR(100) NOT NULL DEFAULT 'it''s'"));
+
+        let pg = create_table_sql(DatabaseType::PostgreSQL, &table).unwrap();
+        assert!(pg[0].contains("\"id\" BIGINT GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY"));
+
+        let sqlite = create_table_sql(DatabaseType::SQLite, &table).unwrap();
+        assert!(sqlite[0].contains("\"id\" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT"));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_input() {
+        assert!(quote_identifier(DatabaseType::MySQL, "users`; DROP TABLE x").is_err());
+        assert!(validate_data_type("INT; DROP TABLE x").is_err());
+        assert!(validate_data_type("DECIMAL(10, 2)").is_ok());
+        assert!(modify_column_sql(DatabaseType::SQLite, "users", "name", &column("name", "TEXT")).is_err());
+        // MySQL 中结尾的反斜杠不能转义闭合引号
+        assert_eq!(quote_literal(DatabaseType::MySQL, "a\\'"), "'a\\\\'''");
+        assert_eq!(quote_literal(DatabaseType::PostgreSQL, "a\\'"), "'a\\'''");
+    }
+
+    #[test]
+    fn test_index_sql() {
+        let index = IndexDefinition {
+            name: None,
+            columns: vec!["email".to_string()],
+            unique: true,
+        };
+        assert_eq!(
+            create_index_sql(DatabaseType::MySQL, "users", &index).unwrap(),
+            "CREATE UNIQUE INDEX `idx_users_email` ON `users` (`email`)"
+        );
+        assert_eq!(drop_index_sql(DatabaseType::MySQL, "users", "idx").unwrap(), "DROP INDEX `idx` ON `users`");
+        assert_eq!(drop_index_sql(DatabaseType::PostgreSQL, "users", "idx").unwrap(), "DROP INDEX \"idx\"");
+    }
+
+    #[test]
+    fn test_view_sql() {
+        let view = ViewDefinition {
+            view_name: "active_users".to_string(),
+            query: "SELECT id, name FROM users WHERE active = 1;".to_string(),
+            columns: vec![],
+        };
+        assert_eq!(
+            create_view_sql(DatabaseType::MySQL, &view, true).unwrap(),
+            vec!["CREATE OR REPLACE VIEW `active_users` AS\nSELECT id, name FROM users WHERE active = 1"]
+        );
+        let sqlite = create_view_sql(DatabaseType::SQLite, &view, true).unwrap();
+        assert_eq!(sqlite[0], "DROP VIEW IF EXISTS \"active_users\"");
+        assert!(sqlite[1].starts_with("CREATE VIEW \"active_users\" AS"));
+
+        let with_columns = ViewDefinition { columns: vec!["user_id".to_string(), "user_name".to_string()], ..view.clone() };
+        assert!(create_view_sql(DatabaseType::PostgreSQL, &with_columns, false).unwrap()[0]
+            .starts_with("CREATE VIEW \"active_users\" (\"user_id\", \"user_name\") AS"));
+
+        let not_select = ViewDefinition { query: "DELETE FROM users".to_string(), ..view.clone() };
+        assert!(create_view_sql(DatabaseType::MySQL, &not_select, false).is_err());
+        let multiple = ViewDefinition { query: "SELECT 1; DROP TABLE users".to_string(), ..view };
+        assert!(create_view_sql(DatabaseType::MySQL, &multiple, false).is_err());
+
+        assert_eq!(
+            drop_view_sql(DatabaseType::PostgreSQL, "active_users", true, true).unwrap(),
+            vec!["DROP VIEW IF EXISTS \"active_users\" CASCADE"]
+        );
+    }
+
+    #[test]
+    fn test_validate_generated_ddl() {
+        let ddl = "CREATE TABLE coupons (\n  id BIGINT PRIMARY KEY,\n  code VARCHAR(32) NOT NULL COMMENT '券码'\n);\n\
+                   CREATE UNIQUE INDEX idx_coupons_code ON coupons (code);";
+        let generated = validate_generated_ddl(DatabaseType::MySQL, ddl).unwrap();
+        assert_eq!(generated.tables, vec!["coupons"]);
+        assert_eq!(generated.statements.len(), 2);
+
+        let pg = "CREATE TABLE coupons (id BIGSERIAL PRIMARY KEY, code TEXT NOT NULL);\n\
+                  COMMENT ON TABLE coupons IS '优惠券';";
+        assert_eq!(validate_generated_ddl(DatabaseType::PostgreSQL, pg).unwrap().statements.len(), 2);
+
+        assert!(validate_generated_ddl(DatabaseType::MySQL, "CREATE TABLE t (id INT); DROP TABLE users").is_err());
+        assert!(validate_generated_ddl(DatabaseType::SQLite, "CREATE INDEX idx ON t (id)").is_err());
+        assert!(validate_generated_ddl(DatabaseType::SQLite, "CREATE TABLE t (id INT").is_err());
+    }
+
+    fn trigger(events: &[&str], body: &str) -> TriggerDefinition {
+        TriggerDefinition {
+            trigger_name: "users_audit".to_string(),
+            timing: "after".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            when: None,
+            body: body.to_string(),
+            function_name: None,
+        }
+    }
+
+    #[test]
+    fn test_trigger_sql() {
+        let insert = trigger(&["insert"], "INSERT INTO audit (user_id) VALUES (NEW.id);");
+        assert_eq!(
+            create_trigger_sql(DatabaseType::MySQL, "users", &insert).unwrap(),
+            vec!["CREATE TRIGGER `users_audit` AFTER INSERT ON `users` FOR EACH ROW INSERT INTO audit (user_id) VALUES (NEW.id)"]
+        );
+        assert_eq!(
+            create_trigger_sql(DatabaseType::SQLite, "users", &insert).unwrap(),
+            vec!["CREATE TRIGGER \"users_audit\" AFTER INSERT ON \"users\" FOR EACH ROW\nBEGIN\n  INSERT INTO audit (user_id) VALUES (NEW.id);\nEND"]
+        );
+
+        let pg = create_trigger_sql(DatabaseType::PostgreSQL, "users", &trigger(&["INSERT", "update"], "INSERT INTO audit VALUES (NEW.id)")).unwrap();
+        assert_eq!(pg.len(), 2);
+        assert!(pg[0].starts_with("CREATE OR REPLACE FUNCTION \"users_audit_fn\"() RETURNS trigger LANGUAGE plpgsql AS $trigger$"));
+        assert!(pg[0].contains("RETURN COALESCE(NEW, OLD);"));
+        assert_eq!(pg[1], "CREATE TRIGGER \"users_audit\" AFTER INSERT OR UPDATE ON \"users\" FOR EACH ROW EXECUTE FUNCTION \"users_audit_fn\"()");
+
+        let existing = TriggerDefinition { function_name: Some("audit_fn".to_string()), when: Some("OLD.id > 0".to_string()), ..trigger(&["DELETE"], "") };
+        assert_eq!(
+            create_trigger_sql(DatabaseType::PostgreSQL, "users", &existing).unwrap(),
+            vec!["CREATE TRIGGER \"users_audit\" AFTER DELETE ON \"users\" FOR EACH ROW WHEN (OLD.id > 0) EXECUTE FUNCTION \"audit_fn\"()"]
+        );
+
+        assert!(create_trigger_sql(DatabaseType::MySQL, "users", &trigger(&["INSERT", "UPDATE"], "SET @x = 1")).is_err());
+        assert!(create_trigger_sql(DatabaseType::MySQL, "users", &trigger(&["TRUNCATE"], "SET @x = 1")).is_err());
+        assert!(create_trigger_sql(DatabaseType::SQLite, "users", &trigger(&["INSERT"], "")).is_err());
+
+        assert_eq!(drop_trigger_sql(DatabaseType::PostgreSQL, "users", "t", true).unwrap(), vec!["DROP TRIGGER IF EXISTS \"t\" ON \"users\""]);
+        assert_eq!(drop_trigger_sql(DatabaseType::MySQL, "users", "t", false).unwrap(), vec!["DROP TRIGGER `t`"]);
+    }
+}
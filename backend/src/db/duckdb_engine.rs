@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use super::ddl::quote_literal;
+use super::{DatabaseError, DatabaseType};
+
+// 连接字符串前缀：duckdb://<路径>[;<路径>...]，路径为空时使用内存数据库
+pub const URL_PREFIX: &str = "duckdb://";
+
+// 可作为表打开的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFormat {
+    Csv,
+    Parquet,
+    Json,
+}
+
+impl FileFormat {
+    // 按扩展名识别（支持通配符路径，如 logs/*.parquet）
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit('.').next()?.to_lowercase();
+        match extension.as_str() {
+            "csv" | "tsv" | "txt" => Some(FileFormat::Csv),
+            "parquet" => Some(FileFormat::Parquet),
+            "json" | "jsonl" | "ndjson" => Some(FileFormat::Json),
+            _ => None,
+        }
+    }
+
+    fn reader(self) -> &'static str {
+        match self {
+            FileFormat::Csv => "read_csv_auto",
+            FileFormat::Parquet => "read_parquet",
+            FileFormat::Json => "read_json_auto",
+        }
+    }
+}
+
+// 以视图形式挂载的数据文件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttachedFile {
+    pub table: String,
+    pub path: String,
+    pub format: FileFormat,
+}
+
+// 解析后的连接配置：数据库文件（可选）+ 挂载的数据文件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DuckDbSource {
+    pub database: Option<String>,
+    pub files: Vec<AttachedFile>,
+}
+
+// 由文件名生成视图名：只保留字母、数字和下划线，重名时追加序号；通配符路径使用所在目录名
+fn view_name(path: &str, used: &mut HashSet<String>) -> String {
+    let path = Path::new(path);
+    let stem = match path.file_stem().and_then(|s| s.to_str()) {
+        Some(stem) if stem.contains(['*', '?']) => path.parent().and_then(|p| p.file_name()).and_then(|s| s.to_str()).unwrap_or("data"),
+        Some(stem) => stem,
+        None => "data",
+    };
+    let mut base: String = stem.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    base = base.trim_matches('_').to_string();
+    if base.is_empty() || base.starts_with(|c: char| c.is_ascii_digit()) {
+        base = format!("t_{}", base);
+    }
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    name
+}
+
+// 解析连接字符串（也接受不带前缀的文件路径列表）
+pub fn parse_source(url: &str) -> Result<DuckDbSource, String> {
+    let paths = url.strip_prefix(URL_PREFIX).unwrap_or(url);
+    let mut source = DuckDbSource::default();
+    let mut used = HashSet::new();
+    for path in paths.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        match FileFormat::from_path(path) {
+            Some(format) => source.files.push(AttachedFile { table: view_name(path, &mut used), path: path.to_string(), format }),
+            None if source.database.is_none() => source.database = Some(path.to_string()),
+            None => return Err(format!("只能打开一个DuckDB数据库文件，无法识别的文件: {}", path)),
+        }
+    }
+    Ok(source)
+}
+
+// 创建数据文件视图的语句
+pub fn attach_sql(file: &AttachedFile) -> String {
+    format!("CREATE OR REPLACE VIEW \"{}\" AS SELECT * FROM {}({})", file.table, file.format.reader(), quote_literal(DatabaseType::DuckDB, &file.path))
+}
+
+// 查询结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DuckDbResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+}
+
+// 嵌入式DuckDB连接：duckdb::Connection 为同步接口，所有操作在阻塞线程池中串行执行
+#[derive(Clone)]
+pub struct DuckDbPool {
+    #[cfg(feature = "duckdb")]
+    conn: Arc<Mutex<duckdb::Connection>>,
+    #[cfg(not(feature = "duckdb"))]
+    conn: Arc<Mutex<()>>,
+    files: Arc<Vec<AttachedFile>>,
+}
+
+#[cfg(not(feature = "duckdb"))]
+fn disabled() -> DatabaseError {
+    DatabaseError::UnsupportedDatabaseType("未启用DuckDB支持（编译时需开启 duckdb 特性）".to_string())
+}
+
+impl DuckDbPool {
+    // 打开数据库文件（或内存数据库）并挂载数据文件
+    pub async fn open(url: &str) -> Result<Self, DatabaseError> {
+        let source = parse_source(url).map_err(DatabaseError::InvalidDefinition)?;
+        Self::open_source(source).await
+    }
+
+    #[cfg(feature = "duckdb")]
+    async fn open_source(source: DuckDbSource) -> Result<Self, DatabaseError> {
+        let conn = tokio::task::spawn_blocking(move || -> Result<_, duckdb::Error> {
+            let conn = match &source.database {
+                Some(path) => duckdb::Connection::open(path)?,
+                None => duckdb::Connection::open_in_memory()?,
+            };
+            for file in &source.files {
+                conn.execute_batch(&attach_sql(file))?;
+                log::info!("[DuckDB] 已挂载数据文件: {} -> {}", file.path, file.table);
+            }
+            Ok((conn, source.files))
+        }).await;
+        match conn {
+            Ok(Ok((conn, files))) => Ok(Self { conn: Arc::new(Mutex::new(conn)), files: Arc::new(files) }),
+            Ok(Err(e)) => Err(DatabaseError::DuckDbFailed(e.to_string())),
+            Err(e) => Err(DatabaseError::DuckDbFailed(e.to_string())),
+        }
+    }
+
+    #[cfg(not(feature = "duckdb"))]
+    async fn open_source(_source: DuckDbSource) -> Result<Self, DatabaseError> {
+        Err(disabled())
+    }
+
+    // 以视图形式挂载的数据文件
+    pub fn attached_files(&self) -> &[AttachedFile] {
+        &self.files
+    }
+
+    // 执行查询并返回全部结果行
+    #[cfg(feature = "duckdb")]
+    pub async fn query(&self, sql: &str) -> Result<DuckDbResult, DatabaseError> {
+        let conn = self.conn.clone();
+        let sql = sql.to_string();
+        tokio::task::spawn_blocking(move || -> Result<DuckDbResult, duckdb::Error> {
+            let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query([])?;
+            let columns = rows.as_ref().map(|s| s.column_names()).unwrap_or_default();
+            let mut result = DuckDbResult { columns, rows: Vec::new() };
+            while let Some(row) = rows.next()? {
+                let values = (0..result.columns.len())
+                    .map(|i| row.get::<_, duckdb::types::Value>(i).map(json_value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                result.rows.push(values);
+            }
+            Ok(result)
+        })
+        .await
+        .map_err(|e| DatabaseError::DuckDbFailed(e.to_string()))?
+        .map_err(|e| DatabaseError::DuckDbFailed(e.to_string()))
+    }
+
+    #[cfg(not(feature = "duckdb"))]
+    pub async fn query(&self, _sql: &str) -> Result<DuckDbResult, DatabaseError> {
+        let _ = &self.conn;
+        Err(disabled())
+    }
+
+    // 执行多条语句（DDL等），不返回结果
+    #[cfg(feature = "duckdb")]
+    pub async fn execute_batch(&self, sql: &str) -> Result<(), DatabaseError> {
+        let conn = self.conn.clone();
+        let sql = sql.to_string();
+        tokio::task::spawn_blocking(move || conn.lock().unwrap_or_else(|e| e.into_inner()).execute_batch(&sql))
+            .await
+            .map_err(|e| DatabaseError::DuckDbFailed(e.to_string()))?
+            .map_err(|e| DatabaseError::DuckDbFailed(e.to_string()))
+    }
+
+    #[cfg(not(feature = "duckdb"))]
+    pub async fn execute_batch(&self, _sql: &str) -> Result<(), DatabaseError> {
+        Err(disabled())
+    }
+
+    // 表和视图（含挂载的数据文件）
+    pub async fn list_tables(&self) -> Result<Vec<String>, DatabaseError> {
+        let result = self.query(
+            "SELECT table_name FROM information_schema.tables WHERE table_schema = current_schema() ORDER BY table_name"
+        ).await?;
+        Ok(result.rows.into_iter().filter_map(|row| row.into_iter().next()?.as_str().map(str::to_string)).collect())
+    }
+
+    // 表的列：(列名, 类型, 可为空, 默认值)
+    pub async fn table_columns(&self, table: &str) -> Result<Vec<(String, String, bool, Option<String>)>, DatabaseError> {
+        let result = self.query(&format!(
+            "SELECT column_name, data_type, is_nullable, column_default FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND table_name = {} ORDER BY ordinal_position",
+            quote_literal(DatabaseType::DuckDB, table)
+        )).await?;
+        Ok(result.rows.into_iter()
+            .map(|row| {
+                let text = |i: usize| row.get(i).and_then(|v| v.as_str()).map(str::to_string);
+                (text(0).unwrap_or_default(), text(1).unwrap_or_default(), text(2).as_deref() == Some("YES"), text(3))
+            })
+            .collect())
+    }
+
+    pub async fn version(&self) -> Result<Option<String>, DatabaseError> {
+        let result = self.query("SELECT version()").await?;
+        Ok(result.rows.first().and_then(|row| row.first()).and_then(|v| v.as_str()).map(str::to_string))
+    }
+}
+
+// DuckDB值转换为JSON：时间类型转为文本，超出JSON精度的整数和小数转为字符串
+#[cfg(feature = "duckdb")]
+fn json_value(value: duckdb::types::Value) -> JsonValue {
+    use duckdb::types::Value;
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Boolean(b) => JsonValue::Bool(b),
+        Value::TinyInt(v) => v.into(),
+        Value::SmallInt(v) => v.into(),
+        Value::Int(v) => v.into(),
+        Value::BigInt(v) => v.into(),
+        Value::UTinyInt(v) => v.into(),
+        Value::USmallInt(v) => v.into(),
+        Value::UInt(v) => v.into(),
+        Value::UBigInt(v) => v.into(),
+        Value::HugeInt(v) => JsonValue::String(v.to_string()),
+        Value::Float(v) => serde_json::Number::from_f64(v as f64).map(JsonValue::Number).unwrap_or(JsonValue::Null),
+        Value::Double(v) => serde_json::Number::from_f64(v).map(JsonValue::Number).unwrap_or(JsonValue::Null),
+        Value::Decimal(v) => JsonValue::String(v.to_string()),
+        Value::Text(s) | Value::Enum(s) => JsonValue::String(s),
+        Value::Blob(bytes) => JsonValue::String(format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())),
+        Value::Date32(days) => chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(days as i64)))
+            .map(|d| JsonValue::String(d.to_string()))
+            .unwrap_or(JsonValue::Null),
+        Value::Timestamp(unit, v) => chrono::DateTime::from_timestamp_micros(unit.to_micros(v))
+            .map(|t| JsonValue::String(t.naive_utc().to_string()))
+            .unwrap_or(JsonValue::Null),
+        Value::Time64(unit, v) => chrono::NaiveTime::from_num_seconds_from_midnight_opt(0, 0)
+            .map(|midnight| midnight + chrono::Duration::microseconds(unit.to_micros(v)))
+            .map(|t| JsonValue::String(t.to_string()))
+            .unwrap_or(JsonValue::Null),
+        Value::List(values) => JsonValue::Array(values.into_iter().map(json_value).collect()),
+        other => JsonValue::String(format!("{:?}", other)),
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source() {
+        let source = parse_source("duckdb:///data/sales.duckdb; /data/2024 orders.csv;/data/logs/*.parquet;/tmp/orders.json").unwrap();
+        assert_eq!(source.database.as_deref(), Some("/data/sales.duckdb"));
+        let tables: Vec<&str> = source.files.iter().map(|f| f.table.as_str()).collect();
+        assert_eq!(tables, vec!["t_2024_orders", "logs", "orders"]);
+        assert_eq!(source.files[1].format, FileFormat::Parquet);
+
+        assert_eq!(parse_source("duckdb://").unwrap(), DuckDbSource::default());
+        assert!(parse_source("duckdb://a.duckdb;b.duckdb").is_err());
+    }
+
+    #[test]
+    fn test_attach_sql() {
+        let file = AttachedFile { table: "orders".to_string(), path: "/data/o'brien.csv".to_string(), format: FileFormat::Csv };
+        assert_eq!(attach_sql(&file), "CREATE OR REPLACE VIEW \"orders\" AS SELECT * FROM read_csv_auto('/data/o''brien.csv')");
+    }
+}
@@ -0,0 +1,340 @@
+use regex::Regex;
+
+use super::ddl::{quote_identifier, quote_literal};
+use super::{DatabaseError, DatabaseManager, DatabasePool, DatabaseType};
+
+// 备份中单条 INSERT 语句包含的行数
+pub const INSERT_BATCH_ROWS: usize = 100;
+
+// 备份时导出的列
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpColumn {
+    pub name: String,
+    pub binary: bool,  // 二进制列（MySQL 以十六进制导出）
+}
+
+// 单张表的备份定义
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableDump {
+    pub table: String,
+    pub columns: Vec<DumpColumn>,
+    pub schema: Vec<String>,            // 建表语句（含所需的序列）
+    pub post_schema: Vec<String>,       // 数据导入后执行：索引、触发器、外键
+    pub sequence_updates: Vec<String>,  // 数据导入后同步自增序列（PostgreSQL）
+}
+
+impl TableDump {
+    // 表的完整DDL：建表语句（含所需的序列）以及索引、触发器、外键
+    pub fn ddl_statements(&self) -> Vec<String> {
+        self.schema.iter().chain(&self.post_schema).cloned().collect()
+    }
+}
+
+// 以分号和空行连接DDL语句，便于复制执行
+pub fn format_ddl(statements: &[String]) -> String {
+    statements.iter()
+        .map(|s| format!("{};", s.trim_end().trim_end_matches(';')))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// 备份文件头部的会话设置
+pub fn dump_header(db_type: DatabaseType) -> Vec<String> {
+    match db_type {
+        DatabaseType::MySQL => vec!["SET NAMES utf8mb4".to_string(), "SET FOREIGN_KEY_CHECKS = 0".to_string()],
+        DatabaseType::PostgreSQL => vec![
+            "SET client_encoding = 'UTF8'".to_string(),
+            "SET standard_conforming_strings = on".to_string(),
+        ],
+        DatabaseType::SQLite => vec!["PRAGMA foreign_keys = OFF".to_string()],
+        DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB => Vec::new(),
+    }
+}
+
+pub fn dump_footer(db_type: DatabaseType) -> Vec<String> {
+    match db_type {
+        DatabaseType::MySQL => vec!["SET FOREIGN_KEY_CHECKS = 1".to_string()],
+        DatabaseType::SQLite => vec!["PRAGMA foreign_keys = ON".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+pub fn drop_table_sql(db_type: DatabaseType, table: &str) -> Result<String, String> {
+    let table = quote_identifier(db_type, table)?;
+    Ok(match db_type {
+        DatabaseType::PostgreSQL => format!("DROP TABLE IF EXISTS {} CASCADE", table),
+        _ => format!("DROP TABLE IF EXISTS {}", table),
+    })
+}
+
+// 读取表数据的查询，每列由数据库转换为SQL字面量文本（NULL 输出为 NULL）
+pub fn literal_select_sql(db_type: DatabaseType, table: &str, columns: &[DumpColumn]) -> Result<String, String> {
+    let mut expressions = Vec::with_capacity(columns.len());
+    for column in columns {
+        let name = quote_identifier(db_type, &column.name)?;
+        expressions.push(match db_type {
+            DatabaseType::SQLite => format!("quote({})", name),
+            DatabaseType::PostgreSQL => format!("quote_nullable({})", name),
+            DatabaseType::MySQL if column.binary => format!("IF({0} IS NULL, 'NULL', CONCAT('0x', HEX({0})))", name),
+            DatabaseType::MySQL => format!("QUOTE({})", name),
+            DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::DuckDB => return Err(format!("{:?}不支持备份", db_type)),
+        });
+    }
+    Ok(format!("SELECT {} FROM {}", expressions.join(", "), quote_identifier(db_type, table)?))
+}
+
+// 多行 INSERT 语句，values 为已转换好的SQL字面量
+pub fn insert_sql(db_type: DatabaseType, table: &str, columns: &[DumpColumn], rows: &[Vec<String>]) -> Result<String, String> {
+    let column_list = columns.iter()
+        .map(|c| quote_identifier(db_type, &c.name))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", ");
+    let values = rows.iter()
+        .map(|row| format!("({})", row.join(", ")))
+        .collect::<Vec<_>>()
+        .join(",\n  ");
+    Ok(format!("INSERT INTO {} ({}) VALUES\n  {}", quote_identifier(db_type, table)?, column_list, values))
+}
+
+// PostgreSQL 列定义：(列名, 类型, 非空, 默认值)
+type PgColumn = (String, String, bool, Option<String>);
+
+// 由系统目录信息生成 PostgreSQL 建表语句，默认值引用的序列先行创建
+fn postgres_table_dump(table: &str, columns: &[PgColumn], constraints: &[(String, String, String)], indexes: &[String]) -> Result<TableDump, String> {
+    lazy_static::lazy_static! {
+        static ref NEXTVAL_RE: Regex = Regex::new(r"nextval\('([^']+)'(?:::regclass)?\)").unwrap();
+    }
+    let db_type = DatabaseType::PostgreSQL;
+    let quoted_table = quote_identifier(db_type, table)?;
+    let mut dump = TableDump { table: table.to_string(), ..Default::default() };
+    let mut definitions = Vec::new();
+
+    for (name, data_type, not_null, default) in columns {
+        let quoted_name = quote_identifier(db_type, name)?;
+        let mut definition = format!("{} {}", quoted_name, data_type);
+        if let Some(default) = default {
+            if let Some(captures) = NEXTVAL_RE.captures(default) {
+                let sequence = &captures[1];
+                dump.schema.push(format!("CREATE SEQUENCE IF NOT EXISTS {}", sequence));
+                dump.sequence_updates.push(format!(
+                    "SELECT setval({}, COALESCE((SELECT MAX({}) FROM {}), 0) + 1, false)",
+                    quote_literal(db_type, sequence), quoted_name, quoted_table
+                ));
+            }
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if *not_null {
+            definition.push_str(" NOT NULL");
+        }
+        definitions.push(definition);
+        dump.columns.push(DumpColumn { name: name.clone(), binary: false });
+    }
+
+    // 主键、唯一、检查约束放在建表语句中，外键在数据导入后添加
+    for (name, kind, definition) in constraints {
+        let constraint = format!("CONSTRAINT {} {}", quote_identifier(db_type, name)?, definition);
+        if kind == "f" {
+            dump.post_schema.push(format!("ALTER TABLE {} ADD {}", quoted_table, constraint));
+        } else {
+            definitions.push(constraint);
+        }
+    }
+    dump.schema.push(format!("CREATE TABLE {} (\n  {}\n)", quoted_table, definitions.join(",\n  ")));
+    dump.post_schema.splice(0..0, indexes.iter().cloned());
+    Ok(dump)
+}
+
+fn is_mysql_binary_type(data_type: &str) -> bool {
+    matches!(
+        data_type.to_lowercase().as_str(),
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit"
+            | "geometry" | "point" | "linestring" | "polygon" | "multipoint" | "multilinestring"
+            | "multipolygon" | "geometrycollection"
+    )
+}
+
+impl DatabaseManager {
+    // 读取表的备份定义（建表语句、索引、触发器、外键和可导出的列）
+    pub async fn get_table_dump(&self, table_name: &str) -> Result<TableDump, DatabaseError> {
+        match &self.pool {
+            DatabasePool::SQLite(pool) => {
+                let objects = sqlx::query_as::<_, (String, String)>(
+                    "SELECT type, sql FROM sqlite_master
+                     WHERE tbl_name = ? AND sql IS NOT NULL
+                     ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 ELSE 2 END"
+                )
+                .bind(table_name)
+                .fetch_all(pool)
+                .await?;
+                // pragma_table_info 不包含生成列
+                let columns = sqlx::query_scalar::<_, String>("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+                    .bind(table_name)
+                    .fetch_all(pool)
+                    .await?;
+
+                let mut dump = TableDump { table: table_name.to_string(), ..Default::default() };
+                for (kind, sql) in objects {
+                    if kind == "table" {
+                        dump.schema.push(sql);
+                    } else {
+                        dump.post_schema.push(sql);
+                    }
+                }
+                dump.columns = columns.into_iter().map(|name| DumpColumn { name, binary: false }).collect();
+                Ok(dump)
+            }
+            DatabasePool::MySQL(pool) => {
+                let quoted = quote_identifier(DatabaseType::MySQL, table_name).map_err(DatabaseError::InvalidDefinition)?;
+                let (_, create_sql) = sqlx::query_as::<_, (String, String)>(&format!("SHOW CREATE TABLE {}", quoted))
+                    .fetch_one(pool)
+                    .await?;
+                // 生成列由数据库计算，不导出
+                let columns = sqlx::query_as::<_, (String, String)>(
+                    "SELECT COLUMN_NAME, DATA_TYPE FROM INFORMATION_SCHEMA.COLUMNS
+                     WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ? AND EXTRA NOT LIKE '%GENERATED%'
+                     ORDER BY ORDINAL_POSITION"
+                )
+                .bind(table_name)
+                .fetch_all(pool)
+                .await?;
+
+                Ok(TableDump {
+                    table: table_name.to_string(),
+                    columns: columns.into_iter()
+                        .map(|(name, data_type)| DumpColumn { binary: is_mysql_binary_type(&data_type), name })
+                        .collect(),
+                    schema: vec![create_sql],
+                    ..Default::default()
+                })
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                let columns = sqlx::query_as::<_, PgColumn>(
+                    r#"SELECT a.attname::text, pg_catalog.format_type(a.atttypid, a.atttypmod), a.attnotnull,
+                              pg_catalog.pg_get_expr(d.adbin, d.adrelid)
+                     FROM pg_attribute a
+                     JOIN pg_class c ON c.oid = a.attrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+                     WHERE c.relname = $1 AND n.nspname = ANY(current_schemas(false))
+                       AND a.attnum > 0 AND NOT a.attisdropped
+                     ORDER BY a.attnum"#
+                )
+                .bind(table_name)
+                .fetch_all(pool)
+                .await?;
+                let constraints = sqlx::query_as::<_, (String, String, String)>(
+                    r#"SELECT con.conname::text, con.contype::text, pg_catalog.pg_get_constraintdef(con.oid)
+                     FROM pg_constraint con
+                     JOIN pg_class c ON c.oid = con.conrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE c.relname = $1 AND n.nspname = ANY(current_schemas(false))
+                       AND con.contype IN ('p', 'u', 'c', 'f')
+                     ORDER BY con.contype, con.conname"#
+                )
+                .bind(table_name)
+                .fetch_all(pool)
+                .await?;
+                // 约束自带的索引已包含在约束中
+                let indexes = sqlx::query_scalar::<_, String>(
+                    r#"SELECT i.indexdef FROM pg_indexes i
+                     WHERE i.tablename = $1 AND i.schemaname = ANY(current_schemas(false))
+                       AND NOT EXISTS (SELECT 1 FROM pg_constraint con WHERE con.conname = i.indexname)
+                     ORDER BY i.indexname"#
+                )
+                .bind(table_name)
+                .fetch_all(pool)
+                .await?;
+
+                if columns.is_empty() {
+                    return Err(DatabaseError::InvalidDefinition(format!("表 {} 不存在", table_name)));
+                }
+                postgres_table_dump(table_name, &columns, &constraints, &indexes).map_err(DatabaseError::InvalidDefinition)
+            }
+            DatabasePool::MongoDB(_, _) => Err(DatabaseError::UnsupportedDatabaseType("mongodb".to_string())),
+            DatabasePool::Redis(_) => Err(DatabaseError::UnsupportedDatabaseType("redis".to_string())),
+            DatabasePool::DuckDB(_) => Err(DatabaseError::UnsupportedDatabaseType("duckdb".to_string())),
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[(&str, bool)]) -> Vec<DumpColumn> {
+        names.iter().map(|(name, binary)| DumpColumn { name: name.to_string(), binary: *binary }).collect()
+    }
+
+    #[test]
+    fn test_literal_select_sql() {
+        let cols = columns(&[("id", false), ("avatar", true)]);
+        assert_eq!(
+            literal_select_sql(DatabaseType::SQLite, "users", &cols).unwrap(),
+            r#"SELECT quote("id"), quote("avatar") FROM "users""#
+        );
+        assert_eq!(
+            literal_select_sql(DatabaseType::MySQL, "users", &cols).unwrap(),
+            "SELECT QUOTE(`id`), IF(`avatar` IS NULL, 'NULL', CONCAT('0x', HEX(`avatar`))) FROM `users`"
+        );
+        assert!(literal_select_sql(DatabaseType::PostgreSQL, "users", &columns(&[("a\"b", false)])).is_err());
+    }
+
+    #[test]
+    fn test_insert_sql() {
+        let cols = columns(&[("id", false), ("name", false)]);
+        let rows = vec![
+            vec!["1".to_string(), "'Alice'".to_string()],
+            vec!["2".to_string(), "NULL".to_string()],
+        ];
+        assert_eq!(
+            insert_sql(DatabaseType::PostgreSQL, "users", &cols, &rows).unwrap(),
+            "INSERT INTO \"users\" (\"id\", \"name\") VALUES\n  (1, 'Alice'),\n  (2, NULL)"
+        );
+    }
+
+    #[test]
+    fn test_ddl_statements() {
+        let dump = TableDump {
+            table: "users".to_string(),
+            schema: vec!["CREATE TABLE users (id INTEGER)".to_string()],
+            post_schema: vec!["CREATE INDEX idx_users_id ON users (id);".to_string()],
+            ..Default::default()
+        };
+        let statements = dump.ddl_statements();
+        assert_eq!(statements.len(), 2);
+        assert_eq!(
+            format_ddl(&statements),
+            "CREATE TABLE users (id INTEGER);\n\nCREATE INDEX idx_users_id ON users (id);"
+        );
+    }
+
+    #[test]
+    fn test_postgres_table_dump() {
+        let columns = vec![
+            ("id".to_string(), "integer".to_string(), true, Some("nextval('users_id_seq'::regclass)".to_string())),
+            ("email".to_string(), "character varying(100)".to_string(), false, None),
+            ("team_id".to_string(), "integer".to_string(), false, None),
+        ];
+        let constraints = vec![
+            ("users_pkey".to_string(), "p".to_string(), "PRIMARY KEY (id)".to_string()),
+            ("users_team_fk".to_string(), "f".to_string(), "FOREIGN KEY (team_id) REFERENCES teams(id)".to_string()),
+        ];
+        let indexes = vec!["CREATE INDEX idx_email ON public.users USING btree (email)".to_string()];
+        let dump = postgres_table_dump("users", &columns, &constraints, &indexes).unwrap();
+
+        assert_eq!(dump.schema[0], "CREATE SEQUENCE IF NOT EXISTS users_id_seq");
+        assert_eq!(
+            dump.schema[1],
+            "CREATE TABLE \"users\" (\n  \"id\" integer DEFAULT nextval('users_id_seq'::regclass) NOT NULL,\n  \"email\" character varying(100),\n  \"team_id\" integer,\n  CONSTRAINT \"users_pkey\" PRIMARY KEY (id)\n)"
+        );
+        assert_eq!(dump.post_schema, vec![
+            "CREATE INDEX idx_email ON public.users USING btree (email)".to_string(),
+            "ALTER TABLE \"users\" ADD CONSTRAINT \"users_team_fk\" FOREIGN KEY (team_id) REFERENCES teams(id)".to_string(),
+        ]);
+        assert_eq!(dump.sequence_updates, vec![
+            "SELECT setval('users_id_seq', COALESCE((SELECT MAX(\"id\") FROM \"users\"), 0) + 1, false)".to_string(),
+        ]);
+        assert_eq!(dump.columns.len(), 3);
+    }
+}
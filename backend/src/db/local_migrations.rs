@@ -0,0 +1,151 @@
+use sqlx::{Pool, Row, Sqlite};
+
+use super::local_storage::LocalStorageManager;
+
+// 版本化迁移之前创建的数据库中，迁移可能已经执行过，按以下条件判断
+#[derive(Debug, Clone, Copy)]
+pub enum AppliedCheck {
+    Always,                                 // 脚本可重复执行（CREATE ... IF NOT EXISTS）
+    Column(&'static str, &'static str),     // 表中已存在该列
+    Table(&'static str),                    // 已存在该表
+}
+
+// 本地存储迁移脚本
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+    pub applied_check: AppliedCheck,
+}
+
+// 按版本号顺序执行，新增表或列时在末尾追加
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "init_local_storage", sql: include_str!("../../migrations/001_init_local_storage.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 2, name: "add_environment_tag", sql: include_str!("../../migrations/002_add_environment_tag.sql"), applied_check: AppliedCheck::Column("connections", "environment") },
+    Migration { version: 3, name: "chat_conversations", sql: include_str!("../../migrations/003_chat_conversations.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 4, name: "add_connection_ssl", sql: include_str!("../../migrations/004_add_connection_ssl.sql"), applied_check: AppliedCheck::Column("connections", "ssl_mode") },
+    Migration { version: 5, name: "favorite_parameters", sql: include_str!("../../migrations/005_favorite_parameters.sql"), applied_check: AppliedCheck::Column("sql_favorites", "parameters") },
+    Migration { version: 6, name: "query_history_fts", sql: include_str!("../../migrations/006_query_history_fts.sql"), applied_check: AppliedCheck::Table("query_history_fts") },
+    Migration { version: 7, name: "slow_queries", sql: include_str!("../../migrations/007_slow_queries.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 8, name: "schema_embeddings", sql: include_str!("../../migrations/008_schema_embeddings.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 9, name: "ai_usage", sql: include_str!("../../migrations/009_ai_usage.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 10, name: "connection_groups", sql: include_str!("../../migrations/010_connection_groups.sql"), applied_check: AppliedCheck::Column("connections", "group_id") },
+    Migration { version: 11, name: "connection_health", sql: include_str!("../../migrations/011_connection_health.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 12, name: "connection_pool_options", sql: include_str!("../../migrations/012_connection_pool_options.sql"), applied_check: AppliedCheck::Column("connections", "pool_max_connections") },
+    Migration { version: 13, name: "connection_default_schema", sql: include_str!("../../migrations/013_connection_default_schema.sql"), applied_check: AppliedCheck::Column("connections", "default_schema") },
+    Migration { version: 14, name: "editor_sessions", sql: include_str!("../../migrations/014_editor_sessions.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 15, name: "object_comments", sql: include_str!("../../migrations/015_object_comments.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 16, name: "data_dictionary", sql: include_str!("../../migrations/016_data_dictionary.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 17, name: "connection_template_bindings", sql: include_str!("../../migrations/017_connection_template_bindings.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 18, name: "audit_log", sql: include_str!("../../migrations/018_audit_log.sql"), applied_check: AppliedCheck::Always },
+    Migration { version: 19, name: "connection_options", sql: include_str!("../../migrations/019_connection_options.sql"), applied_check: AppliedCheck::Column("connections", "options") },
+];
+
+const CREATE_SCHEMA_VERSION: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at INTEGER NOT NULL
+)
+"#;
+
+async fn table_exists(pool: &Pool<Sqlite>, table: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+async fn column_exists(pool: &Pool<Sqlite>, table: &str, column: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+// 迁移是否已在版本化之前执行过
+async fn already_applied(pool: &Pool<Sqlite>, check: AppliedCheck) -> Result<bool, sqlx::Error> {
+    match check {
+        AppliedCheck::Always => Ok(false),
+        AppliedCheck::Column(table, column) => column_exists(pool, table, column).await,
+        AppliedCheck::Table(table) => table_exists(pool, table).await,
+    }
+}
+
+// 当前数据库已执行的迁移版本
+pub async fn applied_versions(pool: &Pool<Sqlite>) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query(CREATE_SCHEMA_VERSION).execute(pool).await?;
+    let rows = sqlx::query("SELECT version FROM schema_version ORDER BY version")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(|row| row.get::<i64, _>(0)).collect())
+}
+
+// 执行未记录的迁移，每个迁移在单独的事务中执行并记录版本，返回本次执行的版本
+// 版本化之前创建的数据库首次升级时，已存在的表和列只记录版本不重复执行
+pub async fn run_migrations(pool: &Pool<Sqlite>) -> Result<Vec<i64>, sqlx::Error> {
+    let applied = applied_versions(pool).await?;
+    let mut executed = Vec::new();
+
+    for migration in MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)) {
+        let skip = already_applied(pool, migration.applied_check).await?;
+        let mut tx = pool.begin().await?;
+        if !skip {
+            sqlx::query(migration.sql).execute(&mut *tx).await?;
+            executed.push(migration.version);
+        }
+        sqlx::query("INSERT INTO schema_version (version, name, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(LocalStorageManager::current_timestamp())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        log::info!("[Migration] 本地存储迁移 {:03}_{} {}", migration.version, migration.name, if skip { "已存在，记录版本" } else { "执行完成" });
+    }
+    Ok(executed)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> Pool<Sqlite> {
+        SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap()
+    }
+
+    #[test]
+    fn test_versions_are_ordered() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations_once() {
+        let pool = memory_pool().await;
+        let executed = run_migrations(&pool).await.unwrap();
+        assert_eq!(executed.len(), MIGRATIONS.len());
+        assert!(column_exists(&pool, "connections", "default_schema").await.unwrap());
+
+        assert!(run_migrations(&pool).await.unwrap().is_empty());
+        assert_eq!(applied_versions(&pool).await.unwrap().len(), MIGRATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_database_is_baselined() {
+        // 版本化之前的数据库：已执行过初始化和环境标签迁移，但没有版本记录
+        let pool = memory_pool().await;
+        sqlx::query(MIGRATIONS[0].sql).execute(&pool).await.unwrap();
+        sqlx::query(MIGRATIONS[1].sql).execute(&pool).await.unwrap();
+
+        let executed = run_migrations(&pool).await.unwrap();
+        assert!(!executed.contains(&2));
+        assert!(executed.contains(&4));
+        assert_eq!(applied_versions(&pool).await.unwrap().len(), MIGRATIONS.len());
+    }
+}
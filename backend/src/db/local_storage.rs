@@ -1,7 +1,7 @@
-use sqlx::{Pool, Sqlite, SqlitePool, Row};
+use sqlx::{Pool, QueryBuilder, Sqlite, SqlitePool, Row};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::collections::HashMap;
-use crate::models::{DatabaseConnection, ConnectionRequest, QueryHistory, SqlFavorite};
+use crate::models::{DatabaseConnection, ConnectionRequest, ConnectionGroup, ConnectionHealthRecord, QueryHistory, QueryHistoryFilter, AuditLogEntry, AuditLogFilter, SlowQueryRecord, SlowQueryRanking, AiUsageRecord, SchemaEmbedding, ObjectComment, DictionaryEntry, DictionaryEntryRequest, ConnectionTemplateBinding, SqlFavorite, FavoriteParameter, ChatConversation, ChatMessageRecord, EditorSession, EditorTab};
 
 /// 本地SQLite存储管理器
 /// 用于存储连接配置、查询历史、SQL收藏等本地数据
@@ -16,29 +16,8 @@ impl LocalStorageManager {
         // 创建连接池
         let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", db_path)).await?;
         
-        // 执行初始化SQL
-        sqlx::query(include_str!("../../migrations/001_init_local_storage.sql"))
-            .execute(&pool)
-            .await?;
-        
-        // 检查是否已经存在environment列，如果不存在则添加
-        let environment_column_exists = sqlx::query(
-            "SELECT COUNT(*) as count FROM pragma_table_info('connections') WHERE name = 'environment'"
-        )
-        .fetch_one(&pool)
-        .await
-        .map(|row| {
-            let count: i64 = row.get(0);
-            count > 0
-        })
-        .unwrap_or(false);
-        
-        // 只有当environment列不存在时才执行环境标签迁移
-        if !environment_column_exists {
-            sqlx::query(include_str!("../../migrations/002_add_environment_tag.sql"))
-                .execute(&pool)
-                .await?;
-        }
+        // 按版本执行未记录的迁移（schema_version 表记录已执行的版本）
+        super::local_migrations::run_migrations(&pool).await?;
         
         Ok(Self { pool })
     }
@@ -51,17 +30,26 @@ impl LocalStorageManager {
             .as_secs() as i64
     }
     
+    /// 检测本地存储是否可用
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+    
     // ========== 连接配置管理 ==========
     
     /// 创建新连接配置
     pub async fn create_connection(&self, req: ConnectionRequest) -> Result<DatabaseConnection, sqlx::Error> {
         let now = Self::current_timestamp();
+        // 新连接排在所属分组最后
+        let sort_order = self.next_connection_sort_order(req.group_id).await?;
         
         let result = sqlx::query(
             r#"
             INSERT INTO connections 
-            (name, db_type, host, port, database_name, username, password, file_path, connection_string, environment, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            (name, db_type, host, port, database_name, username, password, file_path, connection_string, environment, ssl_mode, ssl_ca_path, group_id, sort_order,
+             pool_max_connections, pool_min_connections, pool_idle_timeout_secs, pool_max_lifetime_secs, default_schema, options, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&req.name)
@@ -74,6 +62,16 @@ impl LocalStorageManager {
         .bind(&req.file_path)
         .bind(&req.connection_string)
         .bind(req.environment.unwrap_or_else(|| "development".to_string()))
+        .bind(&req.ssl_mode)
+        .bind(&req.ssl_ca_path)
+        .bind(req.group_id)
+        .bind(sort_order)
+        .bind(req.pool_options.pool_max_connections)
+        .bind(req.pool_options.pool_min_connections)
+        .bind(req.pool_options.pool_idle_timeout_secs)
+        .bind(req.pool_options.pool_max_lifetime_secs)
+        .bind(&req.default_schema)
+        .bind(sqlx::types::Json(&req.options))
         .bind(now)
         .bind(now)
         .execute(&self.pool)
@@ -96,7 +94,7 @@ impl LocalStorageManager {
     /// 获取所有连接配置
     pub async fn list_connections(&self) -> Result<Vec<DatabaseConnection>, sqlx::Error> {
         sqlx::query_as::<_, DatabaseConnection>(
-            "SELECT * FROM connections ORDER BY last_connected_at DESC NULLS LAST, created_at DESC"
+            "SELECT * FROM connections ORDER BY sort_order, last_connected_at DESC NULLS LAST, created_at DESC"
         )
         .fetch_all(&self.pool)
         .await
@@ -110,7 +108,9 @@ impl LocalStorageManager {
             r#"
             UPDATE connections 
             SET name = ?, db_type = ?, host = ?, port = ?, database_name = ?, 
-                username = ?, password = ?, file_path = ?, connection_string = ?, environment = ?, updated_at = ?
+                username = ?, password = ?, file_path = ?, connection_string = ?, environment = ?,
+                ssl_mode = ?, ssl_ca_path = ?, pool_max_connections = ?, pool_min_connections = ?,
+                pool_idle_timeout_secs = ?, pool_max_lifetime_secs = ?, default_schema = ?, options = ?, updated_at = ?
             WHERE id = ?
             "#
         )
@@ -124,6 +124,14 @@ impl LocalStorageManager {
         .bind(&req.file_path)
         .bind(&req.connection_string)
         .bind(req.environment.unwrap_or_else(|| "development".to_string()))
+        .bind(&req.ssl_mode)
+        .bind(&req.ssl_ca_path)
+        .bind(req.pool_options.pool_max_connections)
+        .bind(req.pool_options.pool_min_connections)
+        .bind(req.pool_options.pool_idle_timeout_secs)
+        .bind(req.pool_options.pool_max_lifetime_secs)
+        .bind(&req.default_schema)
+        .bind(sqlx::types::Json(&req.options))
         .bind(now)
         .bind(id)
         .execute(&self.pool)
@@ -172,6 +180,136 @@ impl LocalStorageManager {
         .await
     }
     
+    // ========== 连接分组管理 ==========
+    
+    /// 获取所有连接分组
+    pub async fn list_connection_groups(&self) -> Result<Vec<ConnectionGroup>, sqlx::Error> {
+        sqlx::query_as::<_, ConnectionGroup>(
+            "SELECT * FROM connection_groups ORDER BY sort_order, name"
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// 获取单个连接分组
+    pub async fn get_connection_group(&self, id: i64) -> Result<ConnectionGroup, sqlx::Error> {
+        sqlx::query_as::<_, ConnectionGroup>(
+            "SELECT * FROM connection_groups WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 创建连接分组（未指定顺序时排在最后）
+    pub async fn create_connection_group(&self, name: &str, sort_order: Option<i64>) -> Result<ConnectionGroup, sqlx::Error> {
+        let now = Self::current_timestamp();
+        let sort_order = match sort_order {
+            Some(order) => order,
+            None => sqlx::query_scalar::<_, i64>("SELECT COALESCE(MAX(sort_order) + 1, 0) FROM connection_groups")
+                .fetch_one(&self.pool)
+                .await?,
+        };
+        let result = sqlx::query(
+            "INSERT INTO connection_groups (name, sort_order, created_at, updated_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(name)
+        .bind(sort_order)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        self.get_connection_group(result.last_insert_rowid()).await
+    }
+    
+    /// 重命名连接分组（sort_order 为空时保持不变）
+    pub async fn update_connection_group(&self, id: i64, name: &str, sort_order: Option<i64>) -> Result<ConnectionGroup, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE connection_groups SET name = ?, sort_order = COALESCE(?, sort_order), updated_at = ? WHERE id = ?"
+        )
+        .bind(name)
+        .bind(sort_order)
+        .bind(Self::current_timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        
+        self.get_connection_group(id).await
+    }
+    
+    /// 删除连接分组，分组内的连接移到未分组
+    pub async fn delete_connection_group(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE connections SET group_id = NULL WHERE group_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM connection_groups WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+    
+    /// 按给定顺序重新编号分组
+    pub async fn reorder_connection_groups(&self, ids: &[i64]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (index, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE connection_groups SET sort_order = ? WHERE id = ?")
+                .bind(index as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+    
+    /// 分组内下一个连接的顺序号
+    async fn next_connection_sort_order(&self, group_id: Option<i64>) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>("SELECT COALESCE(MAX(sort_order) + 1, 0) FROM connections WHERE group_id IS ?")
+            .bind(group_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+    
+    /// 移动连接到分组（group_id 为空表示未分组，sort_order 为空时排在最后）
+    pub async fn move_connection(&self, id: i64, group_id: Option<i64>, sort_order: Option<i64>) -> Result<DatabaseConnection, sqlx::Error> {
+        let sort_order = match sort_order {
+            Some(order) => order,
+            None => self.next_connection_sort_order(group_id).await?,
+        };
+        let result = sqlx::query("UPDATE connections SET group_id = ?, sort_order = ?, updated_at = ? WHERE id = ?")
+            .bind(group_id)
+            .bind(sort_order)
+            .bind(Self::current_timestamp())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        
+        self.get_connection(id).await
+    }
+    
+    /// 按给定顺序重新编号连接（用于分组内拖拽排序）
+    pub async fn reorder_connections(&self, ids: &[i64]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (index, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE connections SET sort_order = ? WHERE id = ?")
+                .bind(index as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+    
     // ========== 查询历史管理 ==========
     
     /// 添加查询历史记录
@@ -248,6 +386,311 @@ impl LocalStorageManager {
         }
     }
     
+    /// 搜索查询历史（全文检索 + 状态、时间范围、耗时过滤）
+    pub async fn search_query_history(&self, filter: &QueryHistoryFilter) -> Result<Vec<QueryHistory>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM query_history WHERE 1 = 1");
+        
+        if let Some(q) = filter.q.as_deref() {
+            // trigram分词要求每个词至少3个字符，较短的词使用LIKE匹配
+            let (fts_terms, like_terms): (Vec<&str>, Vec<&str>) = q.split_whitespace()
+                .partition(|term| term.chars().count() >= 3);
+            if !fts_terms.is_empty() {
+                let match_query = fts_terms.iter()
+                    .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                builder.push(" AND id IN (SELECT rowid FROM query_history_fts WHERE query_history_fts MATCH ")
+                    .push_bind(match_query)
+                    .push(")");
+            }
+            for term in like_terms {
+                let pattern = format!("%{}%", term);
+                builder.push(" AND (sql_text LIKE ")
+                    .push_bind(pattern.clone())
+                    .push(" OR error_message LIKE ")
+                    .push_bind(pattern)
+                    .push(")");
+            }
+        }
+        if let Some(connection_id) = filter.connection_id {
+            builder.push(" AND connection_id = ").push_bind(connection_id);
+        }
+        if let Some(success) = filter.success {
+            builder.push(" AND is_success = ").push_bind(success);
+        }
+        if let Some(start) = filter.start_time {
+            builder.push(" AND executed_at >= ").push_bind(start);
+        }
+        if let Some(end) = filter.end_time {
+            builder.push(" AND executed_at <= ").push_bind(end);
+        }
+        if let Some(min_duration) = filter.min_duration_ms {
+            builder.push(" AND execution_time_ms >= ").push_bind(min_duration);
+        }
+        
+        builder.push(" ORDER BY executed_at DESC LIMIT ")
+            .push_bind(filter.limit.unwrap_or(100))
+            .push(" OFFSET ")
+            .push_bind(filter.offset.unwrap_or(0));
+        
+        builder.build_query_as::<QueryHistory>()
+            .fetch_all(&self.pool)
+            .await
+    }
+    
+    /// 写入审计日志（executed_at 为 0 时使用当前时间）
+    pub async fn add_audit_log(&self, entry: &AuditLogEntry) -> Result<i64, sqlx::Error> {
+        let executed_at = if entry.executed_at > 0 { entry.executed_at } else { Self::current_timestamp() };
+        let result = sqlx::query(
+            "INSERT INTO audit_log (connection_id, connection_name, environment, statement_kind, operation, sql_text, source,
+                ai_generated, affected_rows, is_success, error_message, executed_by, executed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(entry.connection_id)
+        .bind(&entry.connection_name)
+        .bind(&entry.environment)
+        .bind(&entry.statement_kind)
+        .bind(&entry.operation)
+        .bind(&entry.sql_text)
+        .bind(&entry.source)
+        .bind(entry.ai_generated)
+        .bind(entry.affected_rows)
+        .bind(entry.is_success)
+        .bind(&entry.error_message)
+        .bind(&entry.executed_by)
+        .bind(executed_at)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid())
+    }
+    
+    /// 检索审计日志（按执行时间倒序）
+    pub async fn search_audit_log(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM audit_log WHERE 1 = 1");
+        
+        if let Some(connection_id) = filter.connection_id {
+            builder.push(" AND connection_id = ").push_bind(connection_id);
+        }
+        if let Some(environment) = filter.environment.as_deref() {
+            builder.push(" AND LOWER(environment) = LOWER(").push_bind(environment.to_string()).push(")");
+        }
+        if let Some(kind) = filter.statement_kind.as_deref() {
+            builder.push(" AND statement_kind = ").push_bind(kind.to_lowercase());
+        }
+        if let Some(operation) = filter.operation.as_deref() {
+            builder.push(" AND operation = ").push_bind(operation.to_uppercase());
+        }
+        if let Some(source) = filter.source.as_deref() {
+            builder.push(" AND source = ").push_bind(source.to_string());
+        }
+        if let Some(ai_generated) = filter.ai_generated {
+            builder.push(" AND ai_generated = ").push_bind(ai_generated);
+        }
+        if let Some(success) = filter.success {
+            builder.push(" AND is_success = ").push_bind(success);
+        }
+        if let Some(q) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            builder.push(" AND sql_text LIKE ").push_bind(format!("%{}%", q));
+        }
+        if let Some(start) = filter.start_time {
+            builder.push(" AND executed_at >= ").push_bind(start);
+        }
+        if let Some(end) = filter.end_time {
+            builder.push(" AND executed_at <= ").push_bind(end);
+        }
+        
+        builder.push(" ORDER BY executed_at DESC, id DESC LIMIT ")
+            .push_bind(filter.limit.unwrap_or(100))
+            .push(" OFFSET ")
+            .push_bind(filter.offset.unwrap_or(0));
+        
+        builder.build_query_as::<AuditLogEntry>()
+            .fetch_all(&self.pool)
+            .await
+    }
+    
+    /// 记录慢查询
+    pub async fn add_slow_query(
+        &self,
+        connection_id: Option<i64>,
+        fingerprint: &str,
+        sql_text: &str,
+        execution_time_ms: i64,
+        row_count: Option<i64>,
+        explain_plan: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        let now = Self::current_timestamp();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO slow_queries (connection_id, fingerprint, sql_text, execution_time_ms, row_count, explain_plan, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(connection_id)
+        .bind(fingerprint)
+        .bind(sql_text)
+        .bind(execution_time_ms)
+        .bind(row_count)
+        .bind(explain_plan)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid())
+    }
+    
+    /// 慢查询排行（按SQL指纹聚合），order_by: total_time / count / avg_time / max_time
+    pub async fn rank_slow_queries(
+        &self,
+        connection_id: Option<i64>,
+        order_by: &str,
+        limit: i64,
+    ) -> Result<Vec<SlowQueryRanking>, sqlx::Error> {
+        // 排序字段只允许白名单中的值
+        let order_column = match order_by {
+            "count" => "execution_count",
+            "avg_time" => "avg_time_ms",
+            "max_time" => "max_time_ms",
+            _ => "total_time_ms",
+        };
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT g.*, s.sql_text AS sample_sql
+            FROM (
+                SELECT fingerprint, connection_id,
+                       MAX(id) AS latest_id,
+                       COUNT(*) AS execution_count,
+                       SUM(execution_time_ms) AS total_time_ms,
+                       AVG(execution_time_ms) AS avg_time_ms,
+                       MAX(execution_time_ms) AS max_time_ms,
+                       MAX(recorded_at) AS last_recorded_at
+                FROM slow_queries
+            "#
+        );
+        if let Some(connection_id) = connection_id {
+            builder.push(" WHERE connection_id = ").push_bind(connection_id);
+        }
+        builder.push(format!(
+            " GROUP BY fingerprint, connection_id) g JOIN slow_queries s ON s.id = g.latest_id ORDER BY g.{} DESC LIMIT ",
+            order_column
+        ))
+        .push_bind(limit);
+        
+        builder.build_query_as::<SlowQueryRanking>()
+            .fetch_all(&self.pool)
+            .await
+    }
+    
+    /// 获取慢查询明细
+    pub async fn list_slow_queries(
+        &self,
+        fingerprint: Option<&str>,
+        connection_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<SlowQueryRecord>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM slow_queries WHERE 1 = 1");
+        if let Some(fingerprint) = fingerprint {
+            builder.push(" AND fingerprint = ").push_bind(fingerprint);
+        }
+        if let Some(connection_id) = connection_id {
+            builder.push(" AND connection_id = ").push_bind(connection_id);
+        }
+        builder.push(" ORDER BY recorded_at DESC, id DESC LIMIT ").push_bind(limit);
+        
+        builder.build_query_as::<SlowQueryRecord>()
+            .fetch_all(&self.pool)
+            .await
+    }
+    
+    /// 根据ID获取慢查询记录
+    pub async fn get_slow_query(&self, id: i64) -> Result<Option<SlowQueryRecord>, sqlx::Error> {
+        sqlx::query_as::<_, SlowQueryRecord>("SELECT * FROM slow_queries WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+    
+    /// 记录一次AI调用用量
+    pub async fn add_ai_usage(&self, usage: &AiUsageRecord) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO ai_usage (feature, provider, model, prompt_tokens, completion_tokens, estimated_cost, streamed, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&usage.feature)
+        .bind(&usage.provider)
+        .bind(&usage.model)
+        .bind(usage.prompt_tokens)
+        .bind(usage.completion_tokens)
+        .bind(usage.estimated_cost)
+        .bind(usage.streamed)
+        .bind(usage.created_at)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid())
+    }
+    
+    /// 获取指定时间（Unix时间戳，秒）之后的AI调用用量记录
+    pub async fn list_ai_usage(&self, since: i64, limit: i64) -> Result<Vec<AiUsageRecord>, sqlx::Error> {
+        sqlx::query_as::<_, AiUsageRecord>(
+            "SELECT * FROM ai_usage WHERE created_at >= ? ORDER BY created_at DESC, id DESC LIMIT ?"
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    // ========== 连接健康检查 ==========
+    
+    /// 添加一条连接健康检查记录
+    pub async fn add_connection_health(&self, record: &ConnectionHealthRecord) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO connection_health (connection_id, checked_at, available, latency_ms, error) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(record.connection_id)
+        .bind(record.checked_at)
+        .bind(record.available)
+        .bind(record.latency_ms)
+        .bind(&record.error)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid())
+    }
+    
+    /// 获取连接最近的健康检查记录（按时间倒序）
+    pub async fn list_connection_health(&self, connection_id: i64, limit: i64) -> Result<Vec<ConnectionHealthRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ConnectionHealthRecord>(
+            "SELECT * FROM connection_health WHERE connection_id = ? ORDER BY checked_at DESC, id DESC LIMIT ?"
+        )
+        .bind(connection_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// 删除指定时间（Unix时间戳，秒）之前的健康检查记录
+    pub async fn prune_connection_health(&self, before: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM connection_health WHERE checked_at < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+    
+    /// 清空慢查询日志（可只清空指定连接）
+    pub async fn clear_slow_queries(&self, connection_id: Option<i64>) -> Result<u64, sqlx::Error> {
+        let result = match connection_id {
+            Some(id) => sqlx::query("DELETE FROM slow_queries WHERE connection_id = ?").bind(id).execute(&self.pool).await?,
+            None => sqlx::query("DELETE FROM slow_queries").execute(&self.pool).await?,
+        };
+        Ok(result.rows_affected())
+    }
+    
     /// 获取收藏查询列表
     #[allow(dead_code)]
     pub async fn list_favorite_queries(&self) -> Result<Vec<QueryHistory>, sqlx::Error> {
@@ -284,6 +727,293 @@ impl LocalStorageManager {
         Ok(result.rows_affected())
     }
     
+    /// 删除指定时间（Unix时间戳，秒）之前的历史记录（保留收藏）
+    pub async fn prune_query_history(&self, before: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM query_history WHERE executed_at < ? AND is_favorite = 0")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+    
+    // ========== 表结构语义检索向量 ==========
+    
+    /// 写入或更新表结构向量（按 连接, 表, 列 唯一）
+    pub async fn upsert_schema_embeddings(&self, connection_id: i64, items: &[SchemaEmbedding]) -> Result<(), sqlx::Error> {
+        let now = Self::current_timestamp();
+        let mut tx = self.pool.begin().await?;
+        for item in items {
+            sqlx::query(
+                r#"
+                INSERT INTO schema_embeddings (connection_id, table_name, column_name, content, content_hash, model, vector, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(connection_id, table_name, column_name) DO UPDATE SET
+                    content = excluded.content,
+                    content_hash = excluded.content_hash,
+                    model = excluded.model,
+                    vector = excluded.vector,
+                    updated_at = excluded.updated_at
+                "#
+            )
+            .bind(connection_id)
+            .bind(&item.table_name)
+            .bind(item.column_name.as_deref().unwrap_or(""))
+            .bind(&item.content)
+            .bind(&item.content_hash)
+            .bind(&item.model)
+            .bind(vector_to_bytes(&item.vector))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+    
+    /// 获取连接的全部表结构向量
+    pub async fn list_schema_embeddings(&self, connection_id: i64) -> Result<Vec<SchemaEmbedding>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT table_name, column_name, content, content_hash, model, vector FROM schema_embeddings WHERE connection_id = ? ORDER BY id"
+        )
+        .bind(connection_id)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter()
+            .map(|row| {
+                let column_name: String = row.get("column_name");
+                SchemaEmbedding {
+                    table_name: row.get("table_name"),
+                    column_name: Some(column_name).filter(|c| !c.is_empty()),
+                    content: row.get("content"),
+                    content_hash: row.get("content_hash"),
+                    model: row.get("model"),
+                    vector: bytes_to_vector(&row.get::<Vec<u8>, _>("vector")),
+                }
+            })
+            .collect())
+    }
+    
+    /// 删除指定的表结构向量（表或列已不存在时）
+    pub async fn delete_schema_embeddings(&self, connection_id: i64, keys: &[(String, Option<String>)]) -> Result<u64, sqlx::Error> {
+        let mut deleted = 0;
+        let mut tx = self.pool.begin().await?;
+        for (table_name, column_name) in keys {
+            deleted += sqlx::query("DELETE FROM schema_embeddings WHERE connection_id = ? AND table_name = ? AND column_name = ?")
+                .bind(connection_id)
+                .bind(table_name)
+                .bind(column_name.as_deref().unwrap_or(""))
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+    
+    /// 清空连接的表结构向量
+    pub async fn clear_schema_embeddings(&self, connection_id: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM schema_embeddings WHERE connection_id = ?")
+            .bind(connection_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+    
+    // ========== 表/列注释（SQLite 连接） ==========
+    
+    /// 设置表或列的注释（column_name 为空字符串时为表注释），comment 为 None 时删除
+    pub async fn set_object_comment(
+        &self,
+        connection_id: i64,
+        table_name: &str,
+        column_name: &str,
+        comment: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        match comment {
+            Some(comment) => {
+                sqlx::query(
+                    "INSERT INTO object_comments (connection_id, table_name, column_name, comment, updated_at) VALUES (?, ?, ?, ?, ?)
+                     ON CONFLICT(connection_id, table_name, column_name) DO UPDATE SET comment = excluded.comment, updated_at = excluded.updated_at"
+                )
+                .bind(connection_id)
+                .bind(table_name)
+                .bind(column_name)
+                .bind(comment)
+                .bind(Self::current_timestamp())
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM object_comments WHERE connection_id = ? AND table_name = ? AND column_name = ?")
+                    .bind(connection_id)
+                    .bind(table_name)
+                    .bind(column_name)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// 获取表及其列的注释
+    pub async fn list_object_comments(&self, connection_id: i64, table_name: &str) -> Result<Vec<ObjectComment>, sqlx::Error> {
+        sqlx::query_as::<_, ObjectComment>(
+            "SELECT * FROM object_comments WHERE connection_id = ? AND table_name = ? ORDER BY column_name"
+        )
+        .bind(connection_id)
+        .bind(table_name)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    // ========== 数据字典 ==========
+    
+    /// 写入数据字典条目（同一连接的同一表/列已存在时覆盖）
+    pub async fn upsert_dictionary_entry(&self, req: &DictionaryEntryRequest) -> Result<DictionaryEntry, sqlx::Error> {
+        let now = Self::current_timestamp();
+        let column_name = req.column_name.as_deref().unwrap_or("");
+        
+        sqlx::query(
+            "INSERT INTO data_dictionary (connection_id, table_name, column_name, description, synonyms, enum_values, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(connection_id, table_name, column_name) DO UPDATE SET
+                 description = excluded.description,
+                 synonyms = excluded.synonyms,
+                 enum_values = excluded.enum_values,
+                 updated_at = excluded.updated_at"
+        )
+        .bind(req.connection_id)
+        .bind(&req.table_name)
+        .bind(column_name)
+        .bind(&req.description)
+        .bind(sqlx::types::Json(&req.synonyms))
+        .bind(sqlx::types::Json(&req.enum_values))
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query_as::<_, DictionaryEntry>(
+            "SELECT * FROM data_dictionary WHERE connection_id = ? AND table_name = ? AND column_name = ?"
+        )
+        .bind(req.connection_id)
+        .bind(&req.table_name)
+        .bind(column_name)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 获取数据字典条目
+    pub async fn get_dictionary_entry(&self, id: i64) -> Result<DictionaryEntry, sqlx::Error> {
+        sqlx::query_as::<_, DictionaryEntry>("SELECT * FROM data_dictionary WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+    }
+    
+    /// 获取连接的数据字典（可按表过滤），表级条目排在列条目之前
+    pub async fn list_dictionary_entries(&self, connection_id: i64, table_name: Option<&str>) -> Result<Vec<DictionaryEntry>, sqlx::Error> {
+        sqlx::query_as::<_, DictionaryEntry>(
+            "SELECT * FROM data_dictionary WHERE connection_id = ? AND (? IS NULL OR table_name = ?)
+             ORDER BY table_name, column_name"
+        )
+        .bind(connection_id)
+        .bind(table_name)
+        .bind(table_name)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// 更新数据字典条目（可修改所属的表和列）
+    pub async fn update_dictionary_entry(&self, id: i64, req: &DictionaryEntryRequest) -> Result<DictionaryEntry, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE data_dictionary SET connection_id = ?, table_name = ?, column_name = ?, description = ?, synonyms = ?, enum_values = ?, updated_at = ?
+             WHERE id = ?"
+        )
+        .bind(req.connection_id)
+        .bind(&req.table_name)
+        .bind(req.column_name.as_deref().unwrap_or(""))
+        .bind(&req.description)
+        .bind(sqlx::types::Json(&req.synonyms))
+        .bind(sqlx::types::Json(&req.enum_values))
+        .bind(Self::current_timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        self.get_dictionary_entry(id).await
+    }
+    
+    /// 删除数据字典条目，返回是否存在
+    pub async fn delete_dictionary_entry(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM data_dictionary WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+    
+    // ========== 连接模板绑定 ==========
+    
+    /// 绑定连接某种类型的提示词模板，已有绑定时覆盖
+    pub async fn set_template_binding(&self, connection_id: i64, template_type: &str, template_id: &str) -> Result<ConnectionTemplateBinding, sqlx::Error> {
+        let now = Self::current_timestamp();
+        sqlx::query(
+            "INSERT INTO connection_template_bindings (connection_id, template_type, template_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(connection_id, template_type) DO UPDATE SET template_id = excluded.template_id, updated_at = excluded.updated_at"
+        )
+        .bind(connection_id)
+        .bind(template_type)
+        .bind(template_id)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query_as::<_, ConnectionTemplateBinding>(
+            "SELECT connection_id, template_type, template_id, created_at, updated_at FROM connection_template_bindings
+             WHERE connection_id = ? AND template_type = ?"
+        )
+        .bind(connection_id)
+        .bind(template_type)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 获取连接某种类型绑定的模板ID
+    pub async fn get_template_binding(&self, connection_id: i64, template_type: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT template_id FROM connection_template_bindings WHERE connection_id = ? AND template_type = ?"
+        )
+        .bind(connection_id)
+        .bind(template_type)
+        .fetch_optional(&self.pool)
+        .await
+    }
+    
+    /// 获取连接的所有模板绑定
+    pub async fn list_template_bindings(&self, connection_id: i64) -> Result<Vec<ConnectionTemplateBinding>, sqlx::Error> {
+        sqlx::query_as::<_, ConnectionTemplateBinding>(
+            "SELECT connection_id, template_type, template_id, created_at, updated_at FROM connection_template_bindings
+             WHERE connection_id = ? ORDER BY template_type"
+        )
+        .bind(connection_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// 解除连接某种类型的模板绑定，返回是否存在
+    pub async fn delete_template_binding(&self, connection_id: i64, template_type: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM connection_template_bindings WHERE connection_id = ? AND template_type = ?")
+            .bind(connection_id)
+            .bind(template_type)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+    
     // ========== SQL收藏夹管理 ==========
     
     /// 创建SQL收藏
@@ -295,14 +1025,15 @@ impl LocalStorageManager {
         description: Option<&str>,
         category: Option<&str>,
         connection_id: Option<i64>,
+        parameters: Option<&[FavoriteParameter]>,
     ) -> Result<SqlFavorite, sqlx::Error> {
         let now = Self::current_timestamp();
         
         let result = sqlx::query(
             r#"
             INSERT INTO sql_favorites 
-            (name, sql_text, description, category, connection_id, created_at, updated_at, usage_count)
-            VALUES (?, ?, ?, ?, ?, ?, ?, 0)
+            (name, sql_text, description, category, connection_id, parameters, created_at, updated_at, usage_count)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, 0)
             "#
         )
         .bind(name)
@@ -310,6 +1041,7 @@ impl LocalStorageManager {
         .bind(description)
         .bind(category)
         .bind(connection_id)
+        .bind(parameters.map(sqlx::types::Json))
         .bind(now)
         .bind(now)
         .execute(&self.pool)
@@ -373,16 +1105,18 @@ impl LocalStorageManager {
         sql_text: &Option<String>,
         description: &Option<String>,
         category: &Option<String>,
+        parameters: &Option<Vec<FavoriteParameter>>,
     ) -> Result<(), sqlx::Error> {
         let now = Self::current_timestamp();
         
         sqlx::query(
-            "UPDATE sql_favorites SET name = COALESCE(?, name), sql_text = COALESCE(?, sql_text), description = COALESCE(?, description), category = COALESCE(?, category), updated_at = ? WHERE id = ?"
+            "UPDATE sql_favorites SET name = COALESCE(?, name), sql_text = COALESCE(?, sql_text), description = COALESCE(?, description), category = COALESCE(?, category), parameters = COALESCE(?, parameters), updated_at = ? WHERE id = ?"
         )
         .bind(name.as_ref().map(|s| s.as_str()))
         .bind(sql_text.as_ref().map(|s| s.as_str()))
         .bind(description.as_ref().map(|s| s.as_str()))
         .bind(category.as_ref().map(|s| s.as_str()))
+        .bind(parameters.as_ref().map(sqlx::types::Json))
         .bind(now)
         .bind(id)
         .execute(&self.pool)
@@ -447,6 +1181,15 @@ impl LocalStorageManager {
         Ok(())
     }
     
+    /// 删除应用配置
+    pub async fn delete_app_setting(&self, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM app_settings WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
     /// 获取所有应用配置
     #[allow(dead_code)]
     pub async fn get_all_app_settings(&self) -> Result<HashMap<String, String>, sqlx::Error> {
@@ -465,6 +1208,182 @@ impl LocalStorageManager {
         
         Ok(settings)
     }
+    
+    // ========== AI对话会话管理 ==========
+    
+    /// 创建对话会话
+    pub async fn create_chat_conversation(
+        &self,
+        connection_id: Option<i64>,
+        title: &str,
+    ) -> Result<ChatConversation, sqlx::Error> {
+        let now = Self::current_timestamp();
+        
+        let result = sqlx::query(
+            "INSERT INTO chat_conversations (connection_id, title, created_at, updated_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(connection_id)
+        .bind(title)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        self.get_chat_conversation(result.last_insert_rowid()).await
+    }
+    
+    /// 获取对话会话
+    pub async fn get_chat_conversation(&self, id: i64) -> Result<ChatConversation, sqlx::Error> {
+        sqlx::query_as::<_, ChatConversation>(
+            "SELECT * FROM chat_conversations WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 获取对话会话列表（按最后活动时间倒序）
+    pub async fn list_chat_conversations(&self, connection_id: Option<i64>) -> Result<Vec<ChatConversation>, sqlx::Error> {
+        match connection_id {
+            Some(conn_id) => {
+                sqlx::query_as::<_, ChatConversation>(
+                    "SELECT * FROM chat_conversations WHERE connection_id = ? ORDER BY updated_at DESC, id DESC"
+                )
+                .bind(conn_id)
+                .fetch_all(&self.pool)
+                .await
+            }
+            None => {
+                sqlx::query_as::<_, ChatConversation>(
+                    "SELECT * FROM chat_conversations ORDER BY updated_at DESC, id DESC"
+                )
+                .fetch_all(&self.pool)
+                .await
+            }
+        }
+    }
+    
+    /// 删除对话会话及其消息
+    pub async fn delete_chat_conversation(&self, id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM chat_messages WHERE conversation_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM chat_conversations WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    
+    /// 追加对话消息并更新会话活动时间
+    pub async fn add_chat_message(
+        &self,
+        conversation_id: i64,
+        role: &str,
+        content: &str,
+    ) -> Result<ChatMessageRecord, sqlx::Error> {
+        let now = Self::current_timestamp();
+        
+        let result = sqlx::query(
+            "INSERT INTO chat_messages (conversation_id, role, content, created_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(conversation_id)
+        .bind(role)
+        .bind(content)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        sqlx::query("UPDATE chat_conversations SET updated_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(conversation_id)
+            .execute(&self.pool)
+            .await?;
+        
+        sqlx::query_as::<_, ChatMessageRecord>(
+            "SELECT * FROM chat_messages WHERE id = ?"
+        )
+        .bind(result.last_insert_rowid())
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 获取会话的全部消息（按时间顺序）
+    pub async fn list_chat_messages(&self, conversation_id: i64) -> Result<Vec<ChatMessageRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ChatMessageRecord>(
+            "SELECT * FROM chat_messages WHERE conversation_id = ? ORDER BY id ASC"
+        )
+        .bind(conversation_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    // ========== 编辑器会话管理 ==========
+    
+    /// 保存编辑器会话（同名会话整体覆盖标签页）
+    pub async fn save_editor_session(
+        &self,
+        name: &str,
+        tabs: &[EditorTab],
+        active_tab: Option<&str>,
+    ) -> Result<EditorSession, sqlx::Error> {
+        let now = Self::current_timestamp();
+        
+        sqlx::query(
+            "INSERT INTO editor_sessions (name, tabs, active_tab, created_at, updated_at) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(name) DO UPDATE SET tabs = excluded.tabs, active_tab = excluded.active_tab, updated_at = excluded.updated_at"
+        )
+        .bind(name)
+        .bind(sqlx::types::Json(tabs))
+        .bind(active_tab)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        self.get_editor_session(name).await
+    }
+    
+    /// 获取编辑器会话
+    pub async fn get_editor_session(&self, name: &str) -> Result<EditorSession, sqlx::Error> {
+        sqlx::query_as::<_, EditorSession>(
+            "SELECT * FROM editor_sessions WHERE name = ?"
+        )
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+    }
+    
+    /// 获取编辑器会话列表（按最后保存时间倒序）
+    pub async fn list_editor_sessions(&self) -> Result<Vec<EditorSession>, sqlx::Error> {
+        sqlx::query_as::<_, EditorSession>(
+            "SELECT * FROM editor_sessions ORDER BY updated_at DESC, id DESC"
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    /// 删除编辑器会话，返回是否存在
+    pub async fn delete_editor_session(&self, name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM editor_sessions WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 向量按小端序f32存储
+fn vector_to_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn bytes_to_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
 }
 
 #[cfg(test)]
@@ -490,6 +1409,12 @@ mod tests {
             file_path: Some(":memory:".to_string()),
             connection_string: None,
             environment: Some("development".to_string()),
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
         };
         
         let conn = storage.create_connection(req).await.unwrap();
@@ -499,6 +1424,50 @@ mod tests {
         assert_eq!(list.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_connection_groups() {
+        let storage = setup_test_storage().await;
+        let request = |name: &str, group_id: Option<i64>| ConnectionRequest {
+            name: name.to_string(),
+            db_type: "sqlite".to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: Some(":memory:".to_string()),
+            connection_string: None,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+        };
+
+        let prod = storage.create_connection_group("生产", None).await.unwrap();
+        let test = storage.create_connection_group("测试", None).await.unwrap();
+        assert_eq!((prod.sort_order, test.sort_order), (0, 1));
+
+        let a = storage.create_connection(request("a", Some(prod.id))).await.unwrap();
+        let b = storage.create_connection(request("b", Some(prod.id))).await.unwrap();
+        assert_eq!((a.group_id, a.sort_order, b.sort_order), (Some(prod.id), 0, 1));
+
+        // 移动到另一个分组时排在最后
+        let moved = storage.move_connection(a.id.unwrap(), Some(test.id), None).await.unwrap();
+        assert_eq!((moved.group_id, moved.sort_order), (Some(test.id), 0));
+
+        storage.reorder_connection_groups(&[test.id, prod.id]).await.unwrap();
+        let renamed = storage.update_connection_group(prod.id, "正式环境", None).await.unwrap();
+        assert_eq!((renamed.name.as_str(), renamed.sort_order), ("正式环境", 1));
+
+        // 删除分组后连接移到未分组
+        assert!(storage.delete_connection_group(prod.id).await.unwrap());
+        assert_eq!(storage.get_connection(b.id.unwrap()).await.unwrap().group_id, None);
+        assert_eq!(storage.list_connection_groups().await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_set_active_connection() {
         let storage = setup_test_storage().await;
@@ -514,6 +1483,12 @@ mod tests {
             file_path: Some(":memory:".to_string()),
             connection_string: None,
             environment: Some("development".to_string()),
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
         };
         
         let conn = storage.create_connection(req).await.unwrap();
@@ -542,6 +1517,100 @@ mod tests {
         assert_eq!(history[0].sql_text, "SELECT * FROM users");
     }
 
+    #[tokio::test]
+    async fn test_search_query_history() {
+        let storage = setup_test_storage().await;
+        
+        storage.add_query_history(None, "SELECT * FROM orders o JOIN refunds r ON r.order_id = o.id", Some(2500), Some(10), true, None).await.unwrap();
+        storage.add_query_history(None, "SELECT * FROM users", Some(5), Some(3), true, None).await.unwrap();
+        storage.add_query_history(None, "SELECT * FROM refundz", Some(1), None, false, Some("表 refundz 不存在")).await.unwrap();
+        
+        let search = |q: &str| QueryHistoryFilter { q: Some(q.to_string()), ..Default::default() };
+        
+        let found = storage.search_query_history(&search("orders refunds")).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].sql_text.contains("JOIN refunds"));
+        
+        // 错误信息也参与检索
+        let found = storage.search_query_history(&search("不存在")).await.unwrap();
+        assert_eq!(found.len(), 1);
+        
+        let failed = storage.search_query_history(&QueryHistoryFilter { success: Some(false), ..Default::default() }).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        
+        let slow = storage.search_query_history(&QueryHistoryFilter { min_duration_ms: Some(1000), ..Default::default() }).await.unwrap();
+        assert_eq!(slow.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_ranking() {
+        let storage = setup_test_storage().await;
+        
+        storage.add_slow_query(None, "select * from orders where id = ?", "SELECT * FROM orders WHERE id = 1", 1500, Some(1), None).await.unwrap();
+        storage.add_slow_query(None, "select * from orders where id = ?", "SELECT * FROM orders WHERE id = 2", 1200, Some(1), None).await.unwrap();
+        storage.add_slow_query(None, "select count(*) from logs", "SELECT COUNT(*) FROM logs", 5000, Some(1), Some("SCAN logs")).await.unwrap();
+        
+        let by_total = storage.rank_slow_queries(None, "total_time", 10).await.unwrap();
+        assert_eq!(by_total[0].fingerprint, "select count(*) from logs");
+        
+        let by_count = storage.rank_slow_queries(None, "count", 10).await.unwrap();
+        assert_eq!(by_count[0].execution_count, 2);
+        assert_eq!(by_count[0].total_time_ms, 2700);
+        assert_eq!(by_count[0].sample_sql, "SELECT * FROM orders WHERE id = 2");
+        
+        assert_eq!(storage.clear_slow_queries(None).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_schema_embeddings() {
+        let storage = setup_test_storage().await;
+        let conn = storage.create_connection(ConnectionRequest {
+            name: "Embedding DB".to_string(),
+            db_type: "sqlite".to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: Some(":memory:".to_string()),
+            connection_string: None,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+        }).await.unwrap();
+        let conn_id = conn.id.unwrap();
+        
+        let table = SchemaEmbedding {
+            table_name: "orders".to_string(),
+            column_name: None,
+            content: "表 orders".to_string(),
+            content_hash: "h1".to_string(),
+            model: "m".to_string(),
+            vector: vec![0.5, -1.25, 3.0],
+        };
+        let column = SchemaEmbedding {
+            column_name: Some("total".to_string()),
+            content: "orders.total".to_string(),
+            ..table.clone()
+        };
+        storage.upsert_schema_embeddings(conn_id, &[table.clone(), column.clone()]).await.unwrap();
+        
+        // 相同键再次写入时更新
+        let updated = SchemaEmbedding { content_hash: "h2".to_string(), vector: vec![1.0], ..table.clone() };
+        storage.upsert_schema_embeddings(conn_id, std::slice::from_ref(&updated)).await.unwrap();
+        
+        let stored = storage.list_schema_embeddings(conn_id).await.unwrap();
+        assert_eq!(stored, vec![updated, column]);
+        
+        let deleted = storage.delete_schema_embeddings(conn_id, &[("orders".to_string(), Some("total".to_string()))]).await.unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(storage.clear_schema_embeddings(conn_id).await.unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_sql_favorites() {
         let storage = setup_test_storage().await;
@@ -552,10 +1621,283 @@ mod tests {
             Some("查询活跃用户"),
             Some("用户管理"),
             None,
+            None,
         ).await.unwrap();
         
         let favorites = storage.list_sql_favorites(None).await.unwrap();
         assert_eq!(favorites.len(), 1);
         assert_eq!(favorites[0].name, "常用查询");
     }
+
+    #[tokio::test]
+    async fn test_sql_favorite_parameters() {
+        let storage = setup_test_storage().await;
+        
+        let parameters = vec![FavoriteParameter {
+            name: "min_age".to_string(),
+            param_type: "integer".to_string(),
+            default: Some(serde_json::json!(18)),
+            description: Some("最小年龄".to_string()),
+        }];
+        let favorite = storage.create_sql_favorite(
+            "按年龄查询",
+            "SELECT * FROM users WHERE age >= :min_age",
+            None,
+            None,
+            None,
+            Some(&parameters),
+        ).await.unwrap();
+        
+        let declared = favorite.parameters.unwrap().0;
+        assert_eq!(declared.len(), 1);
+        assert_eq!(declared[0].name, "min_age");
+        assert_eq!(declared[0].default, Some(serde_json::json!(18)));
+    }
+
+    #[tokio::test]
+    async fn test_chat_conversations() {
+        let storage = setup_test_storage().await;
+        
+        let conversation = storage.create_chat_conversation(None, "用户表分析").await.unwrap();
+        let id = conversation.id.unwrap();
+        storage.add_chat_message(id, "user", "用户表有多少行？").await.unwrap();
+        storage.add_chat_message(id, "assistant", "SELECT COUNT(*) FROM users").await.unwrap();
+        
+        let messages = storage.list_chat_messages(id).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, "user");
+        
+        storage.delete_chat_conversation(id).await.unwrap();
+        assert!(storage.list_chat_conversations(None).await.unwrap().is_empty());
+        assert!(storage.list_chat_messages(id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_editor_sessions() {
+        let storage = setup_test_storage().await;
+        
+        let tab = |id: &str, sql: &str| EditorTab {
+            id: id.to_string(),
+            title: id.to_string(),
+            sql: sql.to_string(),
+            connection_id: None,
+            database: None,
+            cursor_line: 1,
+            cursor_column: sql.len() as u32 + 1,
+        };
+        storage.save_editor_session("default", &[tab("t1", "SELECT 1")], Some("t1")).await.unwrap();
+        let session = storage.save_editor_session("default", &[tab("t1", "SELECT 1"), tab("t2", "SELECT 2")], Some("t2")).await.unwrap();
+        assert_eq!(session.tabs.0.len(), 2);
+        assert_eq!(session.tabs.0[1], tab("t2", "SELECT 2"));
+        assert_eq!(session.active_tab.as_deref(), Some("t2"));
+        assert_eq!(storage.list_editor_sessions().await.unwrap().len(), 1);
+        
+        assert!(storage.delete_editor_session("default").await.unwrap());
+        assert!(!storage.delete_editor_session("default").await.unwrap());
+        assert!(storage.get_editor_session("default").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ai_usage() {
+        let storage = setup_test_storage().await;
+        
+        let usage = AiUsageRecord {
+            id: None,
+            feature: "sql_generation".to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            prompt_tokens: 120,
+            completion_tokens: 30,
+            estimated_cost: Some(0.000036),
+            streamed: false,
+            created_at: 1_700_000_000,
+        };
+        storage.add_ai_usage(&usage).await.unwrap();
+        storage.add_ai_usage(&AiUsageRecord { created_at: 1_600_000_000, ..usage.clone() }).await.unwrap();
+        
+        let records = storage.list_ai_usage(1_650_000_000, 100).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].model, "gpt-4o-mini");
+        assert_eq!(records[0].prompt_tokens, 120);
+    }
+
+    #[tokio::test]
+    async fn test_object_comments() {
+        let storage = setup_test_storage().await;
+        let conn = storage.create_connection(ConnectionRequest {
+            name: "Comment DB".to_string(),
+            db_type: "sqlite".to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: Some(":memory:".to_string()),
+            connection_string: None,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+        }).await.unwrap();
+        let id = conn.id.unwrap();
+        
+        storage.set_object_comment(id, "orders", "", Some("订单表")).await.unwrap();
+        storage.set_object_comment(id, "orders", "c_stat_cd", Some("状态")).await.unwrap();
+        storage.set_object_comment(id, "orders", "c_stat_cd", Some("订单状态")).await.unwrap();
+        let comments = storage.list_object_comments(id, "orders").await.unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!((comments[0].column_name.as_str(), comments[0].comment.as_str()), ("", "订单表"));
+        assert_eq!(comments[1].comment, "订单状态");
+        
+        storage.set_object_comment(id, "orders", "", None).await.unwrap();
+        assert_eq!(storage.list_object_comments(id, "orders").await.unwrap().len(), 1);
+        assert!(storage.list_object_comments(id, "users").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_data_dictionary() {
+        let storage = setup_test_storage().await;
+        let conn = storage.create_connection(ConnectionRequest {
+            name: "Dictionary DB".to_string(),
+            db_type: "sqlite".to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: Some(":memory:".to_string()),
+            connection_string: None,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+        }).await.unwrap();
+        let id = conn.id.unwrap();
+        
+        let mut req = DictionaryEntryRequest {
+            connection_id: id,
+            table_name: "t_ord".to_string(),
+            column_name: Some("c_stat_cd".to_string()),
+            description: "订单状态".to_string(),
+            synonyms: vec!["状态".to_string()],
+            enum_values: vec![crate::models::EnumValueMeaning { value: "1".to_string(), meaning: "待支付".to_string() }],
+        };
+        let entry = storage.upsert_dictionary_entry(&req).await.unwrap();
+        assert_eq!(entry.synonyms.0, vec!["状态"]);
+        assert_eq!(entry.enum_values.0[0].meaning, "待支付");
+        
+        // 同一列再次写入时覆盖
+        req.description = "订单状态代码".to_string();
+        let updated = storage.upsert_dictionary_entry(&req).await.unwrap();
+        assert_eq!(updated.id, entry.id);
+        assert_eq!(updated.description, "订单状态代码");
+        
+        req.column_name = None;
+        req.description = "订单表".to_string();
+        storage.upsert_dictionary_entry(&req).await.unwrap();
+        let entries = storage.list_dictionary_entries(id, Some("t_ord")).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].column_name, "");
+        assert_eq!(storage.list_dictionary_entries(id, None).await.unwrap().len(), 2);
+        assert!(storage.list_dictionary_entries(id, Some("t_user")).await.unwrap().is_empty());
+        
+        // 修改为已存在的表/列时违反唯一约束
+        let entry_id = entry.id.unwrap();
+        assert!(storage.update_dictionary_entry(entry_id, &req).await.is_err());
+        assert!(matches!(storage.update_dictionary_entry(-1, &req).await, Err(sqlx::Error::RowNotFound)));
+        
+        assert!(storage.delete_dictionary_entry(entry_id).await.unwrap());
+        assert!(!storage.delete_dictionary_entry(entry_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_template_bindings() {
+        let storage = setup_test_storage().await;
+        let conn = storage.create_connection(ConnectionRequest {
+            name: "OceanBase".to_string(),
+            db_type: "sqlite".to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: Some(":memory:".to_string()),
+            connection_string: None,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+        }).await.unwrap();
+        let id = conn.id.unwrap();
+        
+        assert_eq!(storage.get_template_binding(id, "sql_generation").await.unwrap(), None);
+        storage.set_template_binding(id, "sql_generation", "sql_generation_a").await.unwrap();
+        let binding = storage.set_template_binding(id, "sql_generation", "sql_generation_b").await.unwrap();
+        assert_eq!(binding.template_id, "sql_generation_b");
+        storage.set_template_binding(id, "sql_explain", "sql_explain_default").await.unwrap();
+        
+        assert_eq!(storage.get_template_binding(id, "sql_generation").await.unwrap().as_deref(), Some("sql_generation_b"));
+        let bindings = storage.list_template_bindings(id).await.unwrap();
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[0].template_type, "sql_explain");
+        
+        assert!(storage.delete_template_binding(id, "sql_generation").await.unwrap());
+        assert!(!storage.delete_template_binding(id, "sql_generation").await.unwrap());
+        assert_eq!(storage.get_template_binding(id, "sql_generation").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let storage = setup_test_storage().await;
+        let entry = |operation: &str, kind: &str, sql: &str, environment: Option<&str>| AuditLogEntry {
+            id: None,
+            connection_id: Some(1),
+            connection_name: "staging".to_string(),
+            environment: environment.map(str::to_string),
+            statement_kind: kind.to_string(),
+            operation: operation.to_string(),
+            sql_text: sql.to_string(),
+            source: "query".to_string(),
+            ai_generated: false,
+            affected_rows: None,
+            is_success: true,
+            error_message: None,
+            executed_by: Some("alice".to_string()),
+            executed_at: 0,
+        };
+        storage.add_audit_log(&entry("TRUNCATE", "destructive", "TRUNCATE TABLE orders_staging", Some("Staging"))).await.unwrap();
+        storage.add_audit_log(&AuditLogEntry {
+            ai_generated: true,
+            affected_rows: Some(3),
+            ..entry("DELETE", "dml", "DELETE FROM users WHERE id < 4", None)
+        }).await.unwrap();
+        
+        let all = storage.search_audit_log(&AuditLogFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].operation, "DELETE");
+        assert!(all[0].executed_at > 0);
+        
+        let truncated = storage.search_audit_log(&AuditLogFilter {
+            operation: Some("truncate".to_string()),
+            q: Some("orders_staging".to_string()),
+            environment: Some("staging".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(truncated.len(), 1);
+        assert_eq!(truncated[0].executed_by.as_deref(), Some("alice"));
+        
+        let ai = storage.search_audit_log(&AuditLogFilter { ai_generated: Some(true), ..Default::default() }).await.unwrap();
+        assert_eq!(ai.len(), 1);
+        assert_eq!(ai[0].affected_rows, Some(3));
+        assert!(storage.search_audit_log(&AuditLogFilter { statement_kind: Some("ddl".to_string()), ..Default::default() }).await.unwrap().is_empty());
+    }
 }
@@ -3,10 +3,72 @@ use std::env;
 use thiserror::Error;
 use mongodb::{Client, Database};
 use futures_util::TryStreamExt;
+use std::time::Duration;
+
+use crate::models::{ConnectionOptions, ConnectionPoolOptions};
 
 pub mod local_storage;
+pub mod local_migrations;
+pub mod ddl;
+pub mod comments;
+pub mod dump;
+pub mod duckdb_engine;
+pub mod mongo_schema;
+pub mod monitor;
+pub mod objects;
+pub mod pool_cache;
+pub mod privileges;
+pub mod processes;
+pub mod query_sessions;
+pub mod redis_client;
+pub mod row_values;
+pub mod ssl;
 
 pub use local_storage::LocalStorageManager;
+pub use row_values::RowValues;
+
+// 将JSON参数按类型绑定到查询（对象/数组按JSON文本绑定）
+macro_rules! bind_json_values {
+    ($query:expr, $values:expr) => {{
+        let mut query = $query;
+        for value in $values {
+            query = match value {
+                serde_json::Value::Null => query.bind(None::<String>),
+                serde_json::Value::Bool(b) => query.bind(*b),
+                serde_json::Value::Number(n) => match n.as_i64() {
+                    Some(i) => query.bind(i),
+                    None => query.bind(n.as_f64()),
+                },
+                serde_json::Value::String(s) => query.bind(s.clone()),
+                other => query.bind(other.to_string()),
+            };
+        }
+        query
+    }};
+}
+
+pub(crate) use bind_json_values;
+
+// 执行查询并返回 (结果行, 影响行数)：fetch_all 只返回结果行，UPDATE/DELETE/INSERT 的影响行数需要从执行结果中读取
+macro_rules! fetch_rows_affected {
+    ($query:expr, $executor:expr) => {
+        async {
+            use futures_util::TryStreamExt;
+            let mut stream = sqlx::Executor::fetch_many($executor, $query);
+            let mut rows = Vec::new();
+            let mut rows_affected: u64 = 0;
+            while let Some(item) = stream.try_next().await? {
+                match item {
+                    sqlx::Either::Left(done) => rows_affected += done.rows_affected(),
+                    sqlx::Either::Right(row) => rows.push(row),
+                }
+            }
+            Ok::<_, sqlx::Error>((rows, rows_affected))
+        }
+    };
+}
+
+pub(crate) use fetch_rows_affected;
 
 // 数据库错误定义
 #[derive(Error, Debug)]
@@ -17,21 +79,59 @@ pub enum DatabaseError {
     #[error("MongoDB连接失败: {0}")]
     MongoConnectionFailed(#[from] mongodb::error::Error),
     
+    #[error("Redis操作失败: {0}")]
+    RedisFailed(#[from] redis::RedisError),
+    
+    #[error("DuckDB操作失败: {0}")]
+    DuckDbFailed(String),
+    
     #[error("未找到数据库URL配置")]
     #[allow(dead_code)]
     MissingDatabaseUrl,
     
     #[error("不支持的数据库类型: {0}")]
     UnsupportedDatabaseType(String),
+    
+    #[error("无效的定义: {0}")]
+    InvalidDefinition(String),
 }
 
 // 数据库类型枚举
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DatabaseType {
     PostgreSQL,
     MySQL,
     SQLite,
     MongoDB,
+    Redis,
+    DuckDB,
+}
+
+impl DatabaseType {
+    // 由连接配置中的 db_type 识别数据库类型
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "postgresql" | "postgres" => Some(DatabaseType::PostgreSQL),
+            "mysql" | "mariadb" => Some(DatabaseType::MySQL),
+            "sqlite" => Some(DatabaseType::SQLite),
+            "mongodb" => Some(DatabaseType::MongoDB),
+            "redis" => Some(DatabaseType::Redis),
+            "duckdb" => Some(DatabaseType::DuckDB),
+            _ => None,
+        }
+    }
+
+    // 与连接配置中 db_type 一致的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseType::PostgreSQL => "postgresql",
+            DatabaseType::MySQL => "mysql",
+            DatabaseType::SQLite => "sqlite",
+            DatabaseType::MongoDB => "mongodb",
+            DatabaseType::Redis => "redis",
+            DatabaseType::DuckDB => "duckdb",
+        }
+    }
 }
 
 // 数据库连接池的枚举类型
@@ -41,6 +141,186 @@ pub enum DatabasePool {
     MySQL(sqlx::MySqlPool),
     SQLite(sqlx::SqlitePool),
     MongoDB(Client, String), // MongoDB客户端和数据库名称
+    Redis(redis::aio::ConnectionManager),
+    DuckDB(duckdb_engine::DuckDbPool),  // 嵌入式DuckDB（数据库文件或CSV/Parquet文件）
+}
+
+// SQLite统计行数的上限，超过时返回上限并标记为估算值
+pub const SQLITE_COUNT_CAP: u64 = 1_000_000;
+
+// 表的行数和空间统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableStats {
+    pub row_count: Option<u64>,
+    pub size_bytes: Option<u64>,
+    pub row_count_estimated: bool,
+}
+
+// 数据库级概览信息（不适用或无权限读取的项为None）
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct DatabaseOverview {
+    pub database_name: Option<String>,
+    pub server_version: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
+    pub active_connections: Option<u64>,
+    pub max_connections: Option<u64>,
+    pub uptime_seconds: Option<u64>,
+}
+
+// 将BSON数值转换为非负整数
+fn bson_as_u64(value: Option<&mongodb::bson::Bson>) -> Option<u64> {
+    match value {
+        Some(mongodb::bson::Bson::Int32(v)) => u64::try_from(*v).ok(),
+        Some(mongodb::bson::Bson::Int64(v)) => u64::try_from(*v).ok(),
+        Some(mongodb::bson::Bson::Double(v)) if *v >= 0.0 => Some(*v as u64),
+        _ => None,
+    }
+}
+
+// 连接池默认最大连接数
+pub const DEFAULT_POOL_MAX_CONNECTIONS: u32 = 10;
+// 连接池最大连接数上限
+pub const MAX_POOL_SIZE: i64 = 100;
+
+// 校验连接池配置
+pub fn validate_pool_options(options: &ConnectionPoolOptions) -> Result<(), String> {
+    if let Some(max) = options.pool_max_connections {
+        if !(1..=MAX_POOL_SIZE).contains(&max) {
+            return Err(format!("最大连接数必须在 1 到 {} 之间", MAX_POOL_SIZE));
+        }
+    }
+    if let Some(min) = options.pool_min_connections {
+        let max = options.pool_max_connections.unwrap_or(DEFAULT_POOL_MAX_CONNECTIONS as i64);
+        if min < 0 || min > max {
+            return Err(format!("最小连接数必须在 0 到 {} 之间", max));
+        }
+    }
+    for (name, secs) in [("空闲超时", options.pool_idle_timeout_secs), ("最长存活时间", options.pool_max_lifetime_secs)] {
+        if matches!(secs, Some(secs) if secs <= 0) {
+            return Err(format!("{}必须大于0秒", name));
+        }
+    }
+    Ok(())
+}
+
+// 会话初始化语句的数量上限
+pub const MAX_INIT_SQL_STATEMENTS: usize = 10;
+// 连接默认查询超时的上限（秒）
+pub const MAX_CONNECTION_TIMEOUT_SECS: u64 = 24 * 3600;
+
+// 校验连接级查询默认值，会话初始化语句只支持 MySQL / PostgreSQL / SQLite
+pub fn validate_connection_options(options: &ConnectionOptions, db_type: &str) -> Result<(), String> {
+    if options.max_rows == Some(0) {
+        return Err("默认最大行数必须大于0".to_string());
+    }
+    if let Some(secs) = options.timeout_secs {
+        if !(1..=MAX_CONNECTION_TIMEOUT_SECS).contains(&secs) {
+            return Err(format!("默认查询超时必须在 1 到 {} 秒之间", MAX_CONNECTION_TIMEOUT_SECS));
+        }
+    }
+    if options.init_sql.is_empty() {
+        return Ok(());
+    }
+    if !matches!(DatabaseType::from_name(db_type), Some(DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite)) {
+        return Err(format!("{} 不支持会话初始化语句", db_type));
+    }
+    if options.init_sql.len() > MAX_INIT_SQL_STATEMENTS {
+        return Err(format!("会话初始化语句最多 {} 条", MAX_INIT_SQL_STATEMENTS));
+    }
+    if options.init_sql.iter().any(|sql| sql.trim().is_empty()) {
+        return Err("会话初始化语句不能为空".to_string());
+    }
+    Ok(())
+}
+
+// 校验默认schema名称，空字符串视为未配置
+pub fn normalize_schema_name(schema: Option<&str>) -> Result<Option<String>, String> {
+    let Some(schema) = schema.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    if schema.len() > 63 {
+        return Err("schema名称不能超过63个字节".to_string());
+    }
+    if schema.chars().any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\\' | '\'' | ',')) {
+        return Err(format!("schema名称包含非法字符: {}", schema));
+    }
+    Ok(Some(schema.to_string()))
+}
+
+// 将默认schema追加到连接字符串：PostgreSQL 通过启动参数设置 search_path，其他数据库忽略
+pub fn apply_default_schema(url: &str, db_type: &str, schema: Option<&str>) -> Result<String, String> {
+    let Some(schema) = normalize_schema_name(schema)? else {
+        return Ok(url.to_string());
+    };
+    if db_type != "postgresql" {
+        return Ok(url.to_string());
+    }
+    // 加双引号保留大小写
+    let value = ssl::encode_param(&format!("\"{}\"", schema));
+    let separator = if url.contains('?') { '&' } else { '?' };
+    Ok(format!("{}{}options[search_path]={}", url, separator, value))
+}
+
+// 替换连接URL中的数据库名（scheme://authority/database?params），保留认证信息和参数
+pub fn replace_url_database(url: &str, database: &str) -> Option<String> {
+    let authority_start = url.find("://")? + 3;
+    let path_start = url[authority_start..].find(['/', '?', '#']).map(|p| authority_start + p).unwrap_or(url.len());
+    let path_end = url[path_start..].find(['?', '#']).map(|p| path_start + p).unwrap_or(url.len());
+    Some(format!("{}/{}{}", &url[..path_start], database, &url[path_end..]))
+}
+
+// 生成sqlx连接池配置：获取连接前先检测连接是否存活，失效的连接（如数据库重启、网络中断）会被丢弃并重新建立
+// 每个新建立的连接上依次执行会话初始化语句（init_sql）
+fn sqlx_pool_options<DB>(options: &ConnectionPoolOptions, init_sql: &[String]) -> sqlx::pool::PoolOptions<DB>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let mut pool_options = sqlx::pool::PoolOptions::<DB>::new()
+        .max_connections(options.pool_max_connections.map(|n| n as u32).unwrap_or(DEFAULT_POOL_MAX_CONNECTIONS))
+        .min_connections(options.pool_min_connections.map(|n| n as u32).unwrap_or(0))
+        .test_before_acquire(true);
+    if let Some(secs) = options.pool_idle_timeout_secs {
+        pool_options = pool_options.idle_timeout(Duration::from_secs(secs as u64));
+    }
+    if let Some(secs) = options.pool_max_lifetime_secs {
+        pool_options = pool_options.max_lifetime(Duration::from_secs(secs as u64));
+    }
+    if !init_sql.is_empty() {
+        let init_sql = std::sync::Arc::new(init_sql.to_vec());
+        pool_options = pool_options.after_connect(move |conn, _meta| {
+            let init_sql = init_sql.clone();
+            Box::pin(async move {
+                for sql in init_sql.iter() {
+                    (&mut *conn).execute(sql.as_str()).await?;
+                }
+                Ok(())
+            })
+        });
+    }
+    pool_options
+}
+
+// 会话模式的连接池：只保持一个连接且不因闲置或存活时间到期而关闭，会话状态在查询间保留
+fn session_pool_options<DB>(init_sql: &[String]) -> sqlx::pool::PoolOptions<DB>
+where
+    DB: sqlx::Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let options = ConnectionPoolOptions { pool_max_connections: Some(1), pool_min_connections: Some(1), ..Default::default() };
+    sqlx_pool_options::<DB>(&options, init_sql)
+        .idle_timeout(None)
+        .max_lifetime(None)
+}
+
+// 连接池使用情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolUsage {
+    pub size: u32,               // 当前连接数（含使用中和空闲）
+    pub idle: usize,
+    pub max_connections: u32,
 }
 
 // 数据库连接管理器
@@ -51,6 +331,28 @@ pub struct DatabaseManager {
 }
 
 impl DatabaseManager {
+    // 连接池使用情况（MongoDB、Redis、DuckDB 没有可统计的连接池）
+    pub fn pool_usage(&self) -> Option<PoolUsage> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => Some(PoolUsage {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max_connections: pool.options().get_max_connections(),
+            }),
+            DatabasePool::MySQL(pool) => Some(PoolUsage {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max_connections: pool.options().get_max_connections(),
+            }),
+            DatabasePool::SQLite(pool) => Some(PoolUsage {
+                size: pool.size(),
+                idle: pool.num_idle(),
+                max_connections: pool.options().get_max_connections(),
+            }),
+            DatabasePool::MongoDB(..) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => None,
+        }
+    }
+    
     // 创建新的数据库管理器（从环境变量）
     #[allow(dead_code)]
     pub async fn new() -> Result<Self, DatabaseError> {
@@ -60,8 +362,32 @@ impl DatabaseManager {
         Self::from_connection_string(&database_url).await
     }
     
-    // 从连接字符串创建数据库管理器
+    // 从连接字符串创建数据库管理器（使用默认连接池配置）
     pub async fn from_connection_string(database_url: &str) -> Result<Self, DatabaseError> {
+        Self::connect_with_options(database_url, &ConnectionPoolOptions::default()).await
+    }
+    
+    // 从连接字符串和连接池配置创建数据库管理器
+    pub async fn connect_with_options(database_url: &str, options: &ConnectionPoolOptions) -> Result<Self, DatabaseError> {
+        Self::connect_with_init(database_url, options, &[]).await
+    }
+    
+    // 创建只有一个专用连接的数据库管理器（会话模式，仅 MySQL / PostgreSQL / SQLite）
+    pub async fn connect_session(database_url: &str, init_sql: &[String]) -> Result<Self, DatabaseError> {
+        let (db_type, pool) = if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            (DatabaseType::PostgreSQL, DatabasePool::PostgreSQL(session_pool_options::<sqlx::Postgres>(init_sql).connect(database_url).await?))
+        } else if database_url.starts_with("mysql://") {
+            (DatabaseType::MySQL, DatabasePool::MySQL(session_pool_options::<sqlx::MySql>(init_sql).connect(database_url).await?))
+        } else if database_url.starts_with("sqlite:") {
+            (DatabaseType::SQLite, DatabasePool::SQLite(session_pool_options::<sqlx::Sqlite>(init_sql).connect(database_url).await?))
+        } else {
+            return Err(DatabaseError::UnsupportedDatabaseType(format!("会话模式仅支持 MySQL / PostgreSQL / SQLite: {}", database_url.split(':').next().unwrap_or_default())));
+        };
+        Ok(Self { pool, db_type })
+    }
+    
+    // 创建数据库管理器，新建立的连接上先执行会话初始化语句（仅 MySQL / PostgreSQL / SQLite）
+    pub async fn connect_with_init(database_url: &str, options: &ConnectionPoolOptions, init_sql: &[String]) -> Result<Self, DatabaseError> {
         // 检测数据库类型
         let db_type = if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
             DatabaseType::PostgreSQL
@@ -71,6 +397,10 @@ impl DatabaseManager {
             DatabaseType::SQLite
         } else if database_url.starts_with("mongodb://") || database_url.starts_with("mongodb+srv://") {
             DatabaseType::MongoDB
+        } else if database_url.starts_with("redis://") || database_url.starts_with("rediss://") {
+            DatabaseType::Redis
+        } else if database_url.starts_with(duckdb_engine::URL_PREFIX) {
+            DatabaseType::DuckDB
         } else {
             return Err(DatabaseError::UnsupportedDatabaseType(database_url.to_string()));
         };
@@ -78,20 +408,30 @@ impl DatabaseManager {
         // 根据类型创建对应的连接池
         let pool = match db_type {
             DatabaseType::PostgreSQL => {
-                let pg_pool = sqlx::PgPool::connect(database_url).await?;
+                let pg_pool = sqlx_pool_options::<sqlx::Postgres>(options, init_sql).connect(database_url).await?;
                 DatabasePool::PostgreSQL(pg_pool)
             }
             DatabaseType::MySQL => {
-                let mysql_pool = sqlx::MySqlPool::connect(database_url).await?;
+                let mysql_pool = sqlx_pool_options::<sqlx::MySql>(options, init_sql).connect(database_url).await?;
                 DatabasePool::MySQL(mysql_pool)
             }
             DatabaseType::SQLite => {
-                let sqlite_pool = sqlx::SqlitePool::connect(database_url).await?;
+                let sqlite_pool = sqlx_pool_options::<sqlx::Sqlite>(options, init_sql).connect(database_url).await?;
                 DatabasePool::SQLite(sqlite_pool)
             }
             DatabaseType::MongoDB => {
                 // 解析MongoDB连接字符串，提取数据库名称
-                let client = Client::with_uri_str(database_url).await?;
+                let mut client_options = mongodb::options::ClientOptions::parse(database_url).await?;
+                if let Some(max) = options.pool_max_connections {
+                    client_options.max_pool_size = Some(max as u32);
+                }
+                if let Some(min) = options.pool_min_connections {
+                    client_options.min_pool_size = Some(min as u32);
+                }
+                if let Some(secs) = options.pool_idle_timeout_secs {
+                    client_options.max_idle_time = Some(Duration::from_secs(secs as u64));
+                }
+                let client = Client::with_options(client_options)?;
                 
                 // 从连接字符串提取数据库名称
                 // MongoDB连接字符串格式: mongodb://[username:password@]host[:port][/database][?options]
@@ -119,6 +459,12 @@ impl DatabaseManager {
                 
                 DatabasePool::MongoDB(client, db_name)
             }
+            DatabaseType::Redis => {
+                DatabasePool::Redis(redis_client::connect(database_url).await?)
+            }
+            DatabaseType::DuckDB => {
+                DatabasePool::DuckDB(duckdb_engine::DuckDbPool::open(database_url).await?)
+            }
         };
         
         log::info!("数据库连接成功，类型: {:?}", db_type);
@@ -130,7 +476,6 @@ impl DatabaseManager {
     }
     
     // 测试数据库连接
-    #[allow(dead_code)]
     pub async fn test_connection(&self) -> Result<(), DatabaseError> {
         match &self.pool {
             DatabasePool::PostgreSQL(pool) => {
@@ -146,6 +491,12 @@ impl DatabaseManager {
                 let database = client.database(db_name);
                 database.run_command(mongodb::bson::doc! { "ping": 1 }, None).await?;
             }
+            DatabasePool::Redis(conn) => {
+                redis_client::ping(conn).await?;
+            }
+            DatabasePool::DuckDB(db) => {
+                db.query("SELECT 1").await?;
+            }
         }
         log::info!("数据库连接测试成功");
         Ok(())
@@ -153,16 +504,16 @@ impl DatabaseManager {
     
         // 获取数据库架构信息
     pub async fn get_schema(&self) -> Result<Vec<String>, DatabaseError> {
-        // 根据不同数据库类型执行不同的查询
+        // 根据不同数据库类型执行不同的查询（不含视图，视图由 get_views 列出）
         match &self.pool {
             DatabasePool::PostgreSQL(pool) => {
-                let tables = sqlx::query_scalar("SELECT table_name FROM information_schema.tables WHERE table_schema = 'public'")
+                let tables = sqlx::query_scalar("SELECT table_name FROM information_schema.tables WHERE table_schema = current_schema() AND table_type <> 'VIEW'")
                     .fetch_all(pool)
                     .await?;
                 Ok(tables)
             },
             DatabasePool::MySQL(pool) => {
-                let tables = sqlx::query_scalar("SHOW TABLES")
+                let tables = sqlx::query_scalar("SHOW FULL TABLES WHERE Table_type <> 'VIEW'")
                     .fetch_all(pool)
                     .await?;
                 Ok(tables)
@@ -178,6 +529,129 @@ impl DatabaseManager {
                 let collections = database.list_collection_names(None).await?;
                 Ok(collections)
             }
+            DatabasePool::Redis(conn) => {
+                // Redis没有表，按键前缀归纳出的模式作为"表"
+                Ok(redis_client::key_patterns(conn).await?)
+            }
+            DatabasePool::DuckDB(db) => db.list_tables().await,
+        }
+    }
+    
+    // 列出当前数据库 / schema 中的视图（MongoDB、Redis、DuckDB 返回空列表）
+    pub async fn get_views(&self) -> Result<Vec<String>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => Ok(sqlx::query_scalar(
+                "SELECT table_name::text FROM information_schema.views WHERE table_schema = current_schema() ORDER BY table_name"
+            )
+            .fetch_all(pool)
+            .await?),
+            DatabasePool::MySQL(pool) => Ok(sqlx::query_scalar(
+                "SELECT CAST(TABLE_NAME AS CHAR) FROM information_schema.VIEWS WHERE TABLE_SCHEMA = DATABASE() ORDER BY TABLE_NAME"
+            )
+            .fetch_all(pool)
+            .await?),
+            DatabasePool::SQLite(pool) => Ok(sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'view' ORDER BY name")
+                .fetch_all(pool)
+                .await?),
+            _ => Ok(Vec::new()),
+        }
+    }
+    
+    // 列出可用的schema（MySQL为数据库，SQLite为已附加的数据库，MongoDB为数据库）
+    pub async fn list_schemas(&self) -> Result<Vec<String>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => {
+                let schemas = sqlx::query_scalar(
+                    "SELECT nspname::text FROM pg_catalog.pg_namespace
+                     WHERE nspname NOT LIKE 'pg\\_%' AND nspname <> 'information_schema'
+                     ORDER BY nspname"
+                )
+                .fetch_all(pool)
+                .await?;
+                Ok(schemas)
+            }
+            DatabasePool::MySQL(pool) => {
+                let schemas = sqlx::query_scalar(
+                    "SELECT SCHEMA_NAME FROM information_schema.SCHEMATA
+                     WHERE SCHEMA_NAME NOT IN ('information_schema', 'mysql', 'performance_schema', 'sys')
+                     ORDER BY SCHEMA_NAME"
+                )
+                .fetch_all(pool)
+                .await?;
+                Ok(schemas)
+            }
+            DatabasePool::SQLite(pool) => {
+                let schemas = sqlx::query_scalar("SELECT name FROM pragma_database_list ORDER BY seq")
+                    .fetch_all(pool)
+                    .await?;
+                Ok(schemas)
+            }
+            DatabasePool::MongoDB(client, _) => Ok(client.list_database_names(None, None).await?),
+            DatabasePool::Redis(_) => Err(DatabaseError::UnsupportedDatabaseType("Redis不支持schema".to_string())),
+            DatabasePool::DuckDB(db) => {
+                let result = db.query("SELECT DISTINCT schema_name FROM information_schema.schemata ORDER BY schema_name").await?;
+                Ok(result.rows.into_iter().filter_map(|row| row.into_iter().next()?.as_str().map(str::to_string)).collect())
+            }
+        }
+    }
+    
+    // 列出服务器上的数据库
+    pub async fn list_databases(&self) -> Result<Vec<String>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => {
+                let databases = sqlx::query_scalar(
+                    "SELECT datname::text FROM pg_catalog.pg_database
+                     WHERE NOT datistemplate AND datallowconn
+                     ORDER BY datname"
+                )
+                .fetch_all(pool)
+                .await?;
+                Ok(databases)
+            }
+            DatabasePool::MySQL(pool) => {
+                let databases = sqlx::query_scalar("SHOW DATABASES")
+                    .fetch_all(pool)
+                    .await?;
+                Ok(databases)
+            }
+            DatabasePool::SQLite(pool) => {
+                let databases = sqlx::query_scalar("SELECT name FROM pragma_database_list ORDER BY seq")
+                    .fetch_all(pool)
+                    .await?;
+                Ok(databases)
+            }
+            DatabasePool::MongoDB(client, _) => Ok(client.list_database_names(None, None).await?),
+            DatabasePool::Redis(_) => Err(DatabaseError::UnsupportedDatabaseType("Redis不支持列出数据库".to_string())),
+            DatabasePool::DuckDB(db) => {
+                let result = db.query("SELECT database_name FROM duckdb_databases() WHERE NOT internal ORDER BY database_name").await?;
+                Ok(result.rows.into_iter().filter_map(|row| row.into_iter().next()?.as_str().map(str::to_string)).collect())
+            }
+        }
+    }
+    
+    // 获取当前连接的数据库
+    pub async fn current_database(&self) -> Result<Option<String>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => Ok(sqlx::query_scalar("SELECT current_database()::text").fetch_one(pool).await?),
+            DatabasePool::MySQL(pool) => Ok(sqlx::query_scalar("SELECT DATABASE()").fetch_one(pool).await?),
+            DatabasePool::SQLite(_) => Ok(Some("main".to_string())),
+            DatabasePool::MongoDB(_, db_name) => Ok(Some(db_name.clone())),
+            DatabasePool::Redis(_) => Ok(None),
+            DatabasePool::DuckDB(db) => Ok(db.query("SELECT current_database()").await?
+                .rows.first().and_then(|row| row.first()).and_then(|v| v.as_str()).map(str::to_string)),
+        }
+    }
+    
+    // 获取当前schema（未限定schema的表名所在的schema）
+    pub async fn current_schema(&self) -> Result<Option<String>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => Ok(sqlx::query_scalar("SELECT current_schema()::text").fetch_one(pool).await?),
+            DatabasePool::MySQL(pool) => Ok(sqlx::query_scalar("SELECT DATABASE()").fetch_one(pool).await?),
+            DatabasePool::SQLite(_) => Ok(Some("main".to_string())),
+            DatabasePool::MongoDB(_, db_name) => Ok(Some(db_name.clone())),
+            DatabasePool::Redis(_) => Ok(None),
+            DatabasePool::DuckDB(db) => Ok(db.query("SELECT current_schema()").await?
+                .rows.first().and_then(|row| row.first()).and_then(|v| v.as_str()).map(str::to_string)),
         }
     }
     
@@ -323,6 +797,299 @@ impl DatabaseManager {
                 
                 Ok(index_list)
             }
+            // 挂载的数据文件为视图，没有索引
+            DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => Ok(Vec::new()),
+        }
+    }
+    
+    // 获取表的行数和占用空间（字节）
+    // MySQL/PostgreSQL的行数为统计信息中的估算值；SQLite默认最多统计 SQLITE_COUNT_CAP 行，exact_count 为true时精确统计
+    pub async fn get_table_stats(&self, table_name: &str, exact_count: bool) -> Result<TableStats, DatabaseError> {
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let row = sqlx::query_as::<_, (Option<u64>, Option<u64>)>(
+                    "SELECT TABLE_ROWS, CAST(DATA_LENGTH + INDEX_LENGTH AS UNSIGNED)
+                     FROM INFORMATION_SCHEMA.TABLES
+                     WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?"
+                )
+                .bind(table_name)
+                .fetch_optional(pool)
+                .await?;
+                
+                let (row_count, size_bytes) = row.unwrap_or((None, None));
+                Ok(TableStats { row_count, size_bytes, row_count_estimated: true })
+            },
+            DatabasePool::PostgreSQL(pool) => {
+                let row = sqlx::query_as::<_, (i64, i64)>(
+                    r#"SELECT c.reltuples::bigint, pg_total_relation_size(c.oid)
+                     FROM pg_class c
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE c.relname = $1 AND n.nspname = ANY(current_schemas(false))
+                     LIMIT 1"#
+                )
+                .bind(table_name)
+                .fetch_optional(pool)
+                .await?;
+                
+                // 从未 ANALYZE 过的表 reltuples 为 -1
+                Ok(match row {
+                    Some((rows, size)) => TableStats {
+                        row_count: u64::try_from(rows).ok(),
+                        size_bytes: u64::try_from(size).ok(),
+                        row_count_estimated: true,
+                    },
+                    None => TableStats::default(),
+                })
+            },
+            DatabasePool::SQLite(pool) => {
+                let quoted = format!("\"{}\"", table_name.replace('"', "\"\""));
+                let count_sql = if exact_count {
+                    format!("SELECT COUNT(*) FROM {}", quoted)
+                } else {
+                    format!("SELECT COUNT(*) FROM (SELECT 1 FROM {} LIMIT {})", quoted, SQLITE_COUNT_CAP + 1)
+                };
+                let count: i64 = sqlx::query_scalar(&count_sql).fetch_one(pool).await?;
+                let count = count as u64;
+                
+                // dbstat 虚拟表不可用时不返回大小
+                let size_bytes = sqlx::query_scalar::<_, Option<i64>>("SELECT SUM(pgsize) FROM dbstat WHERE name = ?")
+                    .bind(table_name)
+                    .fetch_one(pool)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|size| size as u64);
+                
+                Ok(TableStats {
+                    row_count: Some(count.min(SQLITE_COUNT_CAP)),
+                    size_bytes,
+                    row_count_estimated: count > SQLITE_COUNT_CAP,
+                })
+            },
+            DatabasePool::MongoDB(client, db_name) => {
+                let stats = client.database(db_name)
+                    .run_command(mongodb::bson::doc! { "collStats": table_name }, None)
+                    .await?;
+                let as_u64 = |key: &str| bson_as_u64(stats.get(key));
+                // totalSize（4.4+）包含数据和索引，旧版本使用 storageSize + totalIndexSize
+                let size_bytes = as_u64("totalSize").or_else(|| {
+                    Some(as_u64("storageSize")? + as_u64("totalIndexSize").unwrap_or(0))
+                });
+                Ok(TableStats { row_count: as_u64("count"), size_bytes, row_count_estimated: false })
+            },
+            DatabasePool::Redis(_) => Ok(TableStats::default()),
+            DatabasePool::DuckDB(db) => {
+                // 数据文件没有统计信息，直接精确统计
+                let quoted = format!("\"{}\"", table_name.replace('"', "\"\""));
+                let result = db.query(&format!("SELECT COUNT(*) FROM {}", quoted)).await?;
+                let row_count = result.rows.first().and_then(|row| row.first()).and_then(|v| v.as_u64());
+                Ok(TableStats { row_count, size_bytes: None, row_count_estimated: false })
+            },
+        }
+    }
+    
+    // 获取数据库级概览：版本、大小、默认字符集/排序规则、连接数和运行时长
+    pub async fn get_overview(&self) -> Result<DatabaseOverview, DatabaseError> {
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let (database_name, server_version, charset, collation, max_connections) =
+                    sqlx::query_as::<_, (Option<String>, String, Option<String>, Option<String>, i64)>(
+                        "SELECT DATABASE(), VERSION(),
+                                CAST(@@character_set_database AS CHAR), CAST(@@collation_database AS CHAR),
+                                CAST(@@max_connections AS SIGNED)"
+                    )
+                    .fetch_one(pool)
+                    .await?;
+                
+                let size_bytes: u64 = sqlx::query_scalar(
+                    "SELECT CAST(COALESCE(SUM(DATA_LENGTH + INDEX_LENGTH), 0) AS UNSIGNED)
+                     FROM INFORMATION_SCHEMA.TABLES
+                     WHERE TABLE_SCHEMA = DATABASE()"
+                )
+                .fetch_one(pool)
+                .await?;
+                
+                let status = sqlx::query_as::<_, (String, String)>(
+                    "SHOW GLOBAL STATUS WHERE Variable_name IN ('Threads_connected', 'Uptime')"
+                )
+                .fetch_all(pool)
+                .await?;
+                let status_value = |name: &str| status.iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .and_then(|(_, value)| value.parse::<u64>().ok());
+                
+                Ok(DatabaseOverview {
+                    database_name,
+                    server_version: Some(server_version),
+                    size_bytes: Some(size_bytes),
+                    charset,
+                    collation,
+                    active_connections: status_value("Threads_connected"),
+                    max_connections: u64::try_from(max_connections).ok(),
+                    uptime_seconds: status_value("Uptime"),
+                })
+            },
+            DatabasePool::PostgreSQL(pool) => {
+                let (database_name, server_version, size_bytes, charset, collation, active_connections, max_connections, uptime_seconds) =
+                    sqlx::query_as::<_, (String, String, i64, String, String, i64, i64, i64)>(
+                        r#"SELECT
+                            d.datname::text,
+                            version(),
+                            pg_database_size(d.datname),
+                            pg_encoding_to_char(d.encoding)::text,
+                            d.datcollate::text,
+                            (SELECT count(*) FROM pg_stat_activity),
+                            current_setting('max_connections')::bigint,
+                            EXTRACT(EPOCH FROM now() - pg_postmaster_start_time())::bigint
+                         FROM pg_database d
+                         WHERE d.datname = current_database()"#
+                    )
+                    .fetch_one(pool)
+                    .await?;
+                
+                Ok(DatabaseOverview {
+                    database_name: Some(database_name),
+                    server_version: Some(server_version),
+                    size_bytes: u64::try_from(size_bytes).ok(),
+                    charset: Some(charset),
+                    collation: Some(collation),
+                    active_connections: u64::try_from(active_connections).ok(),
+                    max_connections: u64::try_from(max_connections).ok(),
+                    uptime_seconds: u64::try_from(uptime_seconds).ok(),
+                })
+            },
+            DatabasePool::SQLite(pool) => {
+                let server_version: String = sqlx::query_scalar("SELECT sqlite_version()").fetch_one(pool).await?;
+                let page_count: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(pool).await?;
+                let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(pool).await?;
+                let encoding: String = sqlx::query_scalar("PRAGMA encoding").fetch_one(pool).await?;
+                
+                // SQLite为嵌入式数据库，没有服务端连接数和运行时长
+                Ok(DatabaseOverview {
+                    database_name: Some("main".to_string()),
+                    server_version: Some(format!("SQLite {}", server_version)),
+                    size_bytes: u64::try_from(page_count * page_size).ok(),
+                    charset: Some(encoding),
+                    collation: Some("BINARY".to_string()),
+                    ..Default::default()
+                })
+            },
+            DatabasePool::MongoDB(client, db_name) => {
+                let database = client.database(db_name);
+                let build_info = database.run_command(mongodb::bson::doc! { "buildInfo": 1 }, None).await?;
+                let db_stats = database.run_command(mongodb::bson::doc! { "dbStats": 1 }, None).await?;
+                
+                // serverStatus 需要 clusterMonitor 权限，读取失败时只缺少连接数和运行时长
+                let server_status = match database.run_command(mongodb::bson::doc! { "serverStatus": 1 }, None).await {
+                    Ok(status) => Some(status),
+                    Err(e) => {
+                        log::warn!("[DB] 读取MongoDB serverStatus失败: {}", e);
+                        None
+                    }
+                };
+                let connections = server_status.as_ref().and_then(|s| s.get_document("connections").ok());
+                let active_connections = connections.and_then(|c| bson_as_u64(c.get("current")));
+                let available_connections = connections.and_then(|c| bson_as_u64(c.get("available")));
+                
+                let size_bytes = bson_as_u64(db_stats.get("totalSize")).or_else(|| {
+                    Some(bson_as_u64(db_stats.get("storageSize"))? + bson_as_u64(db_stats.get("indexSize")).unwrap_or(0))
+                });
+                
+                Ok(DatabaseOverview {
+                    database_name: Some(db_name.clone()),
+                    server_version: build_info.get_str("version").ok().map(|v| format!("MongoDB {}", v)),
+                    size_bytes,
+                    charset: Some("UTF-8".to_string()),
+                    collation: None,
+                    active_connections,
+                    max_connections: active_connections.zip(available_connections).map(|(current, available)| current + available),
+                    uptime_seconds: server_status.as_ref().and_then(|s| bson_as_u64(s.get("uptime"))),
+                })
+            },
+            DatabasePool::Redis(conn) => {
+                let info = redis_client::server_info(conn).await?;
+                let info_u64 = |key: &str| info.get(key).and_then(|v| v.trim().parse::<u64>().ok());
+                
+                Ok(DatabaseOverview {
+                    database_name: None,
+                    server_version: info.get("redis_version").map(|v| format!("Redis {}", v.trim())),
+                    size_bytes: info_u64("used_memory"),
+                    charset: None,
+                    collation: None,
+                    active_connections: info_u64("connected_clients"),
+                    max_connections: info_u64("maxclients"),
+                    uptime_seconds: info_u64("uptime_in_seconds"),
+                })
+            },
+            DatabasePool::DuckDB(db) => {
+                let result = db.query("SELECT version(), current_database()").await?;
+                let text = |i: usize| result.rows.first().and_then(|row| row.get(i)).and_then(|v| v.as_str()).map(str::to_string);
+                
+                // DuckDB为嵌入式数据库，没有服务端连接数和运行时长
+                Ok(DatabaseOverview {
+                    database_name: text(1),
+                    server_version: text(0).map(|v| format!("DuckDB {}", v)),
+                    charset: Some("UTF-8".to_string()),
+                    ..Default::default()
+                })
+            },
+        }
+    }
+    
+    // 获取当前库所有表的列目录：(表名, 列名, 列注释)，MongoDB/Redis返回空列表
+    pub async fn get_column_catalog(&self) -> Result<Vec<(String, String, Option<String>)>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let rows = sqlx::query_as::<_, (String, String, Option<String>)>(
+                    "SELECT TABLE_NAME, COLUMN_NAME, COLUMN_COMMENT
+                     FROM INFORMATION_SCHEMA.COLUMNS
+                     WHERE TABLE_SCHEMA = DATABASE()
+                     ORDER BY TABLE_NAME, ORDINAL_POSITION"
+                )
+                .fetch_all(pool)
+                .await?;
+                Ok(rows)
+            },
+            DatabasePool::PostgreSQL(pool) => {
+                let rows = sqlx::query_as::<_, (String, String, Option<String>)>(
+                    r#"SELECT c.relname::text, a.attname::text, col_description(c.oid, a.attnum)
+                     FROM pg_attribute a
+                     JOIN pg_class c ON c.oid = a.attrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE n.nspname = ANY(current_schemas(false))
+                       AND c.relkind IN ('r', 'v', 'm', 'p')
+                       AND a.attnum > 0 AND NOT a.attisdropped
+                     ORDER BY c.relname, a.attnum"#
+                )
+                .fetch_all(pool)
+                .await?;
+                Ok(rows)
+            },
+            DatabasePool::SQLite(pool) => {
+                let rows = sqlx::query_as::<_, (String, String)>(
+                    "SELECT m.name, p.name
+                     FROM sqlite_master m
+                     JOIN pragma_table_info(m.name) p
+                     WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%'
+                     ORDER BY m.name, p.cid"
+                )
+                .fetch_all(pool)
+                .await?;
+                // SQLite不支持列注释
+                Ok(rows.into_iter().map(|(table, column)| (table, column, None)).collect())
+            },
+            DatabasePool::DuckDB(db) => {
+                let result = db.query(
+                    "SELECT table_name, column_name, comment FROM duckdb_columns()
+                     WHERE schema_name = current_schema() AND NOT internal
+                     ORDER BY table_name, column_index"
+                ).await?;
+                let text = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).map(str::to_string);
+                Ok(result.rows.iter()
+                    .filter_map(|row| Some((text(row.first())?, text(row.get(1))?, text(row.get(2)))))
+                    .collect())
+            },
+            DatabasePool::MongoDB(..) | DatabasePool::Redis(_) => Ok(Vec::new()),
         }
     }
     
@@ -331,25 +1098,27 @@ impl DatabaseManager {
         // 根据不同数据库类型执行不同的查询
         match &self.pool {
             DatabasePool::PostgreSQL(pool) => {
-                // 查询PostgreSQL外键信息
+                // 查询PostgreSQL外键信息（pg_constraint，按列序号展开复合外键）
                 let fks = sqlx::query_as::<_, (String, String, String, String)>(
                     r#"
                     SELECT 
-                        tc.constraint_name,
-                        kcu.column_name,
-                        ccu.table_name AS referenced_table,
-                        ccu.column_name AS referenced_column
+                        con.conname::text AS constraint_name,
+                        att.attname::text AS column_name,
+                        ref_cls.relname::text AS referenced_table,
+                        ref_att.attname::text AS referenced_column
                     FROM 
-                        information_schema.table_constraints AS tc
-                    JOIN 
-                        information_schema.key_column_usage AS kcu
-                        ON tc.constraint_name = kcu.constraint_name
-                    JOIN 
-                        information_schema.constraint_column_usage AS ccu
-                        ON ccu.constraint_name = tc.constraint_name
+                        pg_constraint con
+                    JOIN pg_class cls ON cls.oid = con.conrelid
+                    JOIN pg_namespace nsp ON nsp.oid = cls.relnamespace
+                    JOIN pg_class ref_cls ON ref_cls.oid = con.confrelid
+                    CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, ref_attnum, ord)
+                    JOIN pg_attribute att ON att.attrelid = con.conrelid AND att.attnum = k.attnum
+                    JOIN pg_attribute ref_att ON ref_att.attrelid = con.confrelid AND ref_att.attnum = k.ref_attnum
                     WHERE 
-                        tc.constraint_type = 'FOREIGN KEY'
-                        AND tc.table_name = $1
+                        con.contype = 'f'
+                        AND cls.relname = $1
+                        AND nsp.nspname = ANY(current_schemas(false))
+                    ORDER BY con.conname, k.ord
                     "#
                 )
                 .bind(table_name)
@@ -415,35 +1184,158 @@ impl DatabaseManager {
                     seq: i32,
                     table: String,
                     from: String,
-                    to: String,
+                    // REFERENCES t 省略列名时为NULL，表示引用主键
+                    to: Option<String>,
                 }
                 
-                let fk_query = format!("PRAGMA foreign_key_list('{}')", table_name);
-                let sqlite_fks = sqlx::query_as::<_, SqliteForeignKey>(&fk_query)
+                let sqlite_fks = sqlx::query_as::<_, SqliteForeignKey>("SELECT * FROM pragma_foreign_key_list(?)")
+                    .bind(table_name)
                     .fetch_all(pool)
                     .await?;
                 
-                let result: Vec<crate::models::ForeignKeyInfo> = sqlite_fks
-                    .into_iter()
-                    .map(|fk| {
-                        crate::models::ForeignKeyInfo {
-                            constraint_name: format!("fk_{}_{}_{}", table_name, fk.from, fk.table),
-                            column_name: fk.from,
-                            referenced_table: fk.table,
-                            referenced_column: fk.to,
+                let mut result = Vec::with_capacity(sqlite_fks.len());
+                for fk in sqlite_fks {
+                    let referenced_column = match fk.to {
+                        Some(column) => column,
+                        None => {
+                            sqlx::query_scalar::<_, String>(
+                                "SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk LIMIT 1 OFFSET ?"
+                            )
+                            .bind(&fk.table)
+                            .bind(fk.seq)
+                            .fetch_optional(pool)
+                            .await?
+                            .unwrap_or_default()
                         }
-                    })
-                    .collect();
+                    };
+                    result.push(crate::models::ForeignKeyInfo {
+                        constraint_name: format!("fk_{}_{}_{}", table_name, fk.from, fk.table),
+                        column_name: fk.from,
+                        referenced_table: fk.table,
+                        referenced_column,
+                    });
+                }
                 
                 Ok(result)
             },
-            DatabasePool::MongoDB(_, _) => {
-                // MongoDB不支持外键约束
+            DatabasePool::MongoDB(_, _) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => {
+                // MongoDB/Redis不支持外键约束，DuckDB的数据文件视图没有外键
                 Ok(Vec::new())
             }
         }
     }
     
+    // 依次执行多条语句（DDL等），PostgreSQL/SQLite在同一事务中执行，MySQL的DDL会隐式提交
+    pub async fn execute_statements(&self, statements: &[String]) -> Result<(), DatabaseError> {
+        match &self.pool {
+            DatabasePool::PostgreSQL(pool) => {
+                let mut tx = pool.begin().await?;
+                for sql in statements {
+                    sqlx::query(sql).execute(&mut *tx).await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                // 使用文本协议执行：CREATE TRIGGER 等语句不支持预处理协议
+                for sql in statements {
+                    pool.execute(sql.as_str()).await?;
+                }
+            }
+            DatabasePool::SQLite(pool) => {
+                let mut tx = pool.begin().await?;
+                for sql in statements {
+                    sqlx::query(sql).execute(&mut *tx).await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::MongoDB(_, _) => {
+                return Err(DatabaseError::UnsupportedDatabaseType("mongodb".to_string()));
+            }
+            DatabasePool::Redis(_) => {
+                return Err(DatabaseError::UnsupportedDatabaseType("redis".to_string()));
+            }
+            DatabasePool::DuckDB(db) => {
+                for sql in statements {
+                    db.execute_batch(sql).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    // 在一个事务中执行多条DML语句（批量插入等），任一语句失败时整体回滚
+    pub async fn execute_in_transaction(&self, statements: &[String]) -> Result<(), DatabaseError> {
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let mut tx = pool.begin().await?;
+                for sql in statements {
+                    sqlx::query(sql).execute(&mut *tx).await?;
+                }
+                tx.commit().await?;
+            }
+            DatabasePool::PostgreSQL(_) | DatabasePool::SQLite(_) => {
+                self.execute_statements(statements).await?;
+            }
+            DatabasePool::MongoDB(_, _) => {
+                return Err(DatabaseError::UnsupportedDatabaseType("mongodb".to_string()));
+            }
+            DatabasePool::Redis(_) => {
+                return Err(DatabaseError::UnsupportedDatabaseType("redis".to_string()));
+            }
+            DatabasePool::DuckDB(_) => {
+                return Err(DatabaseError::UnsupportedDatabaseType("duckdb".to_string()));
+            }
+        }
+        Ok(())
+    }
+    
+    // 执行查询并以JSON值返回所有行（用于读取少量辅助数据）
+    pub async fn query_json_rows(&self, sql: &str) -> Result<Vec<Vec<serde_json::Value>>, DatabaseError> {
+        let rows: Vec<Vec<serde_json::Value>> = match &self.pool {
+            DatabasePool::MySQL(pool) => sqlx::query(sql).fetch_all(pool).await?.iter().map(|r| r.json_values()).collect(),
+            DatabasePool::PostgreSQL(pool) => sqlx::query(sql).fetch_all(pool).await?.iter().map(|r| r.json_values()).collect(),
+            DatabasePool::SQLite(pool) => sqlx::query(sql).fetch_all(pool).await?.iter().map(|r| r.json_values()).collect(),
+            DatabasePool::DuckDB(db) => db.query(sql).await?.rows,
+            DatabasePool::MongoDB(_, _) => return Err(DatabaseError::UnsupportedDatabaseType("mongodb".to_string())),
+            DatabasePool::Redis(_) => return Err(DatabaseError::UnsupportedDatabaseType("redis".to_string())),
+        };
+        Ok(rows)
+    }
+    
+    // 创建索引，返回执行的SQL
+    pub async fn create_index(&self, table_name: &str, index: &ddl::IndexDefinition) -> Result<String, DatabaseError> {
+        let sql = ddl::create_index_sql(self.db_type, table_name, index)
+            .map_err(DatabaseError::InvalidDefinition)?;
+        self.execute_statements(std::slice::from_ref(&sql)).await?;
+        log::info!("创建索引成功: {}", sql);
+        Ok(sql)
+    }
+    
+    // 删除索引，返回执行的SQL
+    pub async fn drop_index(&self, table_name: &str, index_name: &str) -> Result<String, DatabaseError> {
+        let sql = ddl::drop_index_sql(self.db_type, table_name, index_name)
+            .map_err(DatabaseError::InvalidDefinition)?;
+        self.execute_statements(std::slice::from_ref(&sql)).await?;
+        log::info!("删除索引成功: {}", sql);
+        Ok(sql)
+    }
+    
+    // 随机采样MongoDB集合文档并推断字段结构
+    pub async fn infer_mongo_fields(&self, collection_name: &str, sample_size: i64) -> Result<Vec<mongo_schema::InferredField>, DatabaseError> {
+        match &self.pool {
+            DatabasePool::MongoDB(client, db_name) => {
+                let collection = client.database(db_name).collection::<mongodb::bson::Document>(collection_name);
+                let pipeline = vec![mongodb::bson::doc! { "$sample": { "size": sample_size } }];
+                let docs: Vec<mongodb::bson::Document> = collection.aggregate(pipeline, None).await?
+                    .try_collect()
+                    .await?;
+                log::debug!("[MongoDB] 集合 {} 采样文档数: {}", collection_name, docs.len());
+                Ok(mongo_schema::infer_fields(&docs))
+            },
+            _ => Err(DatabaseError::UnsupportedDatabaseType("仅MongoDB支持文档采样推断结构".to_string())),
+        }
+    }
+    
     // 获取MongoDB数据库
     #[allow(dead_code)]
     pub fn get_mongo_database(&self) -> Option<Database> {
@@ -455,3 +1347,72 @@ impl DatabaseManager {
         }
     }
 }
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_pool_options() {
+        assert!(validate_pool_options(&ConnectionPoolOptions::default()).is_ok());
+        assert!(validate_pool_options(&ConnectionPoolOptions { pool_max_connections: Some(0), ..Default::default() }).is_err());
+        assert!(validate_pool_options(&ConnectionPoolOptions {
+            pool_max_connections: Some(5),
+            pool_min_connections: Some(6),
+            ..Default::default()
+        }).is_err());
+        assert!(validate_pool_options(&ConnectionPoolOptions { pool_idle_timeout_secs: Some(0), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_validate_connection_options() {
+        assert!(validate_connection_options(&ConnectionOptions::default(), "mongodb").is_ok());
+        let options = ConnectionOptions {
+            max_rows: Some(500),
+            timeout_secs: Some(120),
+            read_only: true,
+            init_sql: vec!["SET time_zone = '+08:00'".to_string()],
+        };
+        assert!(validate_connection_options(&options, "mysql").is_ok());
+        assert!(validate_connection_options(&options, "redis").is_err());
+        assert!(validate_connection_options(&ConnectionOptions { max_rows: Some(0), ..Default::default() }, "mysql").is_err());
+        assert!(validate_connection_options(&ConnectionOptions { timeout_secs: Some(0), ..Default::default() }, "mysql").is_err());
+        assert!(validate_connection_options(&ConnectionOptions { init_sql: vec![" ".to_string()], ..Default::default() }, "sqlite").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_init_sql_runs_on_new_connections() {
+        let options = ConnectionPoolOptions { pool_max_connections: Some(1), ..Default::default() };
+        let init_sql = vec!["PRAGMA user_version = 7".to_string()];
+        let manager = DatabaseManager::connect_with_init("sqlite::memory:", &options, &init_sql).await.unwrap();
+        let DatabasePool::SQLite(pool) = &manager.pool else {
+            panic!("expected sqlite pool");
+        };
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version").fetch_one(pool).await.unwrap();
+        assert_eq!(version, 7);
+    }
+
+    #[test]
+    fn test_apply_default_schema() {
+        let url = apply_default_schema("postgresql://u:p@db:5432/app?sslmode=require", "postgresql", Some(" Sales ")).unwrap();
+        assert_eq!(url, "postgresql://u:p@db:5432/app?sslmode=require&options[search_path]=%22Sales%22");
+        assert_eq!(apply_default_schema("mysql://db/app", "mysql", Some("sales")).unwrap(), "mysql://db/app");
+        assert_eq!(apply_default_schema("postgresql://db/app", "postgresql", Some("")).unwrap(), "postgresql://db/app");
+        assert!(normalize_schema_name(Some("a\"; DROP")).is_err());
+    }
+
+    #[test]
+    fn test_replace_url_database() {
+        assert_eq!(
+            replace_url_database("postgresql://u:p@db:5432/app?sslmode=require", "reports").as_deref(),
+            Some("postgresql://u:p@db:5432/reports?sslmode=require")
+        );
+        assert_eq!(replace_url_database("mysql://root@localhost", "shop").as_deref(), Some("mysql://root@localhost/shop"));
+        assert_eq!(
+            replace_url_database("mongodb://u:p@h:27017/?authSource=admin", "logs").as_deref(),
+            Some("mongodb://u:p@h:27017/logs?authSource=admin")
+        );
+        assert!(replace_url_database("sqlite:data.db", "x").is_none());
+    }
+}
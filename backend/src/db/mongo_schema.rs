@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, BTreeSet};
+use mongodb::bson::{Bson, Document};
+
+// 嵌套文档展开的最大深度
+const MAX_DEPTH: usize = 3;
+
+// 通过采样推断出的字段信息
+#[derive(Debug, Clone)]
+pub struct InferredField {
+    pub path: String,             // 字段路径，嵌套字段以 . 连接
+    pub bson_types: Vec<String>,  // 出现过的BSON类型
+    pub occurrences: usize,       // 出现该字段的文档数
+    pub sample_size: usize,       // 采样文档总数
+}
+
+impl InferredField {
+    // 字段出现频率（0.0 ~ 1.0）
+    pub fn frequency(&self) -> f64 {
+        if self.sample_size == 0 {
+            0.0
+        } else {
+            self.occurrences as f64 / self.sample_size as f64
+        }
+    }
+}
+
+// BSON值的类型名称
+pub fn bson_type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Double(_) => "Double",
+        Bson::String(_) => "String",
+        Bson::Array(_) => "Array",
+        Bson::Document(_) => "Document",
+        Bson::Boolean(_) => "Boolean",
+        Bson::Null => "Null",
+        Bson::Int32(_) => "Int32",
+        Bson::Int64(_) => "Int64",
+        Bson::ObjectId(_) => "ObjectId",
+        Bson::DateTime(_) => "DateTime",
+        Bson::Timestamp(_) => "Timestamp",
+        Bson::Decimal128(_) => "Decimal128",
+        Bson::Binary(_) => "Binary",
+        Bson::RegularExpression(_) => "Regex",
+        _ => "Other",
+    }
+}
+
+fn collect_fields(
+    doc: &Document,
+    prefix: &str,
+    depth: usize,
+    seen: &mut BTreeMap<String, BTreeSet<&'static str>>,
+) {
+    for (key, value) in doc {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        seen.entry(path.clone()).or_default().insert(bson_type_name(value));
+        if let Bson::Document(nested) = value {
+            if depth + 1 < MAX_DEPTH {
+                collect_fields(nested, &path, depth + 1, seen);
+            }
+        }
+    }
+}
+
+// 根据采样文档推断字段列表（_id 在前，其余按路径排序）
+pub fn infer_fields(docs: &[Document]) -> Vec<InferredField> {
+    let mut types: BTreeMap<String, BTreeSet<&'static str>> = BTreeMap::new();
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+
+    for doc in docs {
+        let mut seen = BTreeMap::new();
+        collect_fields(doc, "", 0, &mut seen);
+        for (path, doc_types) in seen {
+            *occurrences.entry(path.clone()).or_default() += 1;
+            types.entry(path).or_default().extend(doc_types);
+        }
+    }
+
+    let mut fields: Vec<InferredField> = types.into_iter()
+        .map(|(path, bson_types)| InferredField {
+            occurrences: occurrences.get(&path).copied().unwrap_or(0),
+            bson_types: bson_types.into_iter().map(String::from).collect(),
+            sample_size: docs.len(),
+            path,
+        })
+        .collect();
+    fields.sort_by_key(|f| f.path != "_id");
+    fields
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn test_infer_fields() {
+        let docs = vec![
+            doc! { "_id": 1, "name": "a", "address": { "city": "北京" } },
+            doc! { "_id": 2, "name": null, "age": 30_i64 },
+        ];
+        let fields = infer_fields(&docs);
+
+        assert_eq!(fields[0].path, "_id");
+        let name = fields.iter().find(|f| f.path == "name").unwrap();
+        assert_eq!(name.bson_types, vec!["Null", "String"]);
+        assert_eq!(name.frequency(), 1.0);
+        let city = fields.iter().find(|f| f.path == "address.city").unwrap();
+        assert_eq!(city.occurrences, 1);
+        assert_eq!(city.frequency(), 0.5);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{bson_as_u64, DatabaseError, DatabaseManager, DatabasePool};
+use crate::db::LocalStorageManager;
+
+// MySQL 采集的 SHOW GLOBAL STATUS 累计计数器（键为指标名）
+const MYSQL_COUNTERS: &[(&str, &str)] = &[
+    ("Questions", "questions"),
+    ("Com_select", "com_select"),
+    ("Com_insert", "com_insert"),
+    ("Com_update", "com_update"),
+    ("Com_delete", "com_delete"),
+    ("Slow_queries", "slow_queries"),
+    ("Bytes_received", "bytes_received"),
+    ("Bytes_sent", "bytes_sent"),
+    ("Aborted_connects", "aborted_connects"),
+    ("Innodb_row_lock_waits", "innodb_row_lock_waits"),
+    ("Innodb_buffer_pool_read_requests", "innodb_buffer_pool_read_requests"),
+    ("Innodb_buffer_pool_reads", "innodb_buffer_pool_reads"),
+];
+
+// MySQL 采集的瞬时值
+const MYSQL_GAUGES: &[(&str, &str)] = &[
+    ("Threads_connected", "threads_connected"),
+    ("Threads_running", "threads_running"),
+    ("Innodb_buffer_pool_pages_total", "innodb_buffer_pool_pages_total"),
+    ("Innodb_buffer_pool_pages_free", "innodb_buffer_pool_pages_free"),
+    ("Innodb_buffer_pool_pages_dirty", "innodb_buffer_pool_pages_dirty"),
+    ("Uptime", "uptime_seconds"),
+];
+
+// 一次监控采样
+// gauges 为瞬时值（连接数、命中率等），counters 为服务启动以来的累计值，rates 为累计值的每秒速率
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MonitorSnapshot {
+    pub collected_at: i64,
+    pub gauges: BTreeMap<String, f64>,
+    pub counters: BTreeMap<String, f64>,
+    pub rates: BTreeMap<String, f64>,
+}
+
+impl MonitorSnapshot {
+    fn gauge(&mut self, name: &str, value: Option<f64>) {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            self.gauges.insert(name.to_string(), value);
+        }
+    }
+
+    fn counter(&mut self, name: &str, value: Option<f64>) {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            self.counters.insert(name.to_string(), value);
+        }
+    }
+}
+
+// 比率，分母为 0 时为空
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+    (denominator > 0.0).then(|| numerator / denominator)
+}
+
+impl DatabaseManager {
+    // 采集数据库引擎的关键指标（不计算速率，速率由监控历史根据相邻两次采样计算）
+    // MySQL: SHOW GLOBAL STATUS；PostgreSQL: pg_stat_database / pg_stat_user_tables；MongoDB: serverStatus
+    pub async fn collect_metrics(&self) -> Result<MonitorSnapshot, DatabaseError> {
+        let mut snapshot = MonitorSnapshot {
+            collected_at: LocalStorageManager::current_timestamp(),
+            ..Default::default()
+        };
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let status = sqlx::query_as::<_, (String, String)>("SHOW GLOBAL STATUS").fetch_all(pool).await?;
+                let value = |name: &str| status.iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .and_then(|(_, value)| value.trim().parse::<f64>().ok());
+
+                for (variable, name) in MYSQL_COUNTERS {
+                    snapshot.counter(name, value(variable));
+                }
+                for (variable, name) in MYSQL_GAUGES {
+                    snapshot.gauge(name, value(variable));
+                }
+                // 缓冲池命中率：1 - 物理读次数 / 逻辑读请求数
+                if let (Some(requests), Some(reads)) = (value("Innodb_buffer_pool_read_requests"), value("Innodb_buffer_pool_reads")) {
+                    snapshot.gauge("innodb_buffer_pool_hit_ratio", ratio(requests - reads, requests));
+                }
+                if let (Some(total), Some(free)) = (value("Innodb_buffer_pool_pages_total"), value("Innodb_buffer_pool_pages_free")) {
+                    snapshot.gauge("innodb_buffer_pool_usage_ratio", ratio(total - free, total));
+                }
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                let (numbackends, xact_commit, xact_rollback, blks_read, blks_hit, tup_returned, tup_fetched, tup_inserted, tup_updated, tup_deleted, deadlocks, temp_bytes) =
+                    sqlx::query_as::<_, (i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64)>(
+                        r#"SELECT numbackends::bigint, xact_commit, xact_rollback, blks_read, blks_hit,
+                                  tup_returned, tup_fetched, tup_inserted, tup_updated, tup_deleted, deadlocks, temp_bytes
+                           FROM pg_stat_database
+                           WHERE datname = current_database()"#
+                    )
+                    .fetch_one(pool)
+                    .await?;
+                let (active, uptime_seconds) = sqlx::query_as::<_, (i64, i64)>(
+                    r#"SELECT (SELECT count(*) FROM pg_stat_activity WHERE state = 'active' AND datname = current_database()),
+                              EXTRACT(EPOCH FROM now() - pg_postmaster_start_time())::bigint"#
+                )
+                .fetch_one(pool)
+                .await?;
+                // 膨胀估算：用户表中死元组占比（VACUUM 后回落）
+                let (live_tuples, dead_tuples) = sqlx::query_as::<_, (i64, i64)>(
+                    "SELECT COALESCE(SUM(n_live_tup), 0)::bigint, COALESCE(SUM(n_dead_tup), 0)::bigint FROM pg_stat_user_tables"
+                )
+                .fetch_one(pool)
+                .await?;
+
+                let counters = [
+                    ("xact_commit", xact_commit),
+                    ("xact_rollback", xact_rollback),
+                    ("blks_read", blks_read),
+                    ("blks_hit", blks_hit),
+                    ("tup_returned", tup_returned),
+                    ("tup_fetched", tup_fetched),
+                    ("tup_inserted", tup_inserted),
+                    ("tup_updated", tup_updated),
+                    ("tup_deleted", tup_deleted),
+                    ("deadlocks", deadlocks),
+                    ("temp_bytes", temp_bytes),
+                ];
+                for (name, value) in counters {
+                    snapshot.counter(name, Some(value as f64));
+                }
+                snapshot.gauge("connections", Some(numbackends as f64));
+                snapshot.gauge("active_connections", Some(active as f64));
+                snapshot.gauge("uptime_seconds", Some(uptime_seconds as f64));
+                snapshot.gauge("cache_hit_ratio", ratio(blks_hit as f64, (blks_hit + blks_read) as f64));
+                snapshot.gauge("rollback_ratio", ratio(xact_rollback as f64, (xact_commit + xact_rollback) as f64));
+                snapshot.gauge("live_tuples", Some(live_tuples as f64));
+                snapshot.gauge("dead_tuples", Some(dead_tuples as f64));
+                snapshot.gauge("dead_tuple_ratio", ratio(dead_tuples as f64, (live_tuples + dead_tuples) as f64));
+            }
+            DatabasePool::MongoDB(client, db_name) => {
+                // serverStatus 需要 clusterMonitor 权限
+                let status = client.database(db_name).run_command(mongodb::bson::doc! { "serverStatus": 1 }, None).await?;
+                let number = |value: Option<&mongodb::bson::Bson>| bson_as_u64(value).map(|v| v as f64);
+
+                if let Ok(opcounters) = status.get_document("opcounters") {
+                    for name in ["insert", "query", "update", "delete", "getmore", "command"] {
+                        snapshot.counter(&format!("opcounters_{}", name), number(opcounters.get(name)));
+                    }
+                }
+                if let Ok(network) = status.get_document("network") {
+                    snapshot.counter("bytes_in", number(network.get("bytesIn")));
+                    snapshot.counter("bytes_out", number(network.get("bytesOut")));
+                }
+                if let Ok(connections) = status.get_document("connections") {
+                    snapshot.gauge("connections_current", number(connections.get("current")));
+                    snapshot.gauge("connections_available", number(connections.get("available")));
+                }
+                if let Ok(mem) = status.get_document("mem") {
+                    // resident 单位为 MB
+                    snapshot.gauge("mem_resident_mb", number(mem.get("resident")));
+                }
+                if let Ok(cache) = status.get_document("wiredTiger").and_then(|wt| wt.get_document("cache")) {
+                    let used = number(cache.get("bytes currently in the cache"));
+                    let max = number(cache.get("maximum bytes configured"));
+                    snapshot.gauge("wiredtiger_cache_bytes", used);
+                    if let (Some(used), Some(max)) = (used, max) {
+                        snapshot.gauge("wiredtiger_cache_usage_ratio", ratio(used, max));
+                    }
+                }
+                snapshot.gauge("uptime_seconds", number(status.get("uptime")));
+            }
+            _ => return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持监控指标", self.db_type))),
+        }
+        Ok(snapshot)
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionPoolOptions;
+
+    #[test]
+    fn test_snapshot_skips_missing_values() {
+        let mut snapshot = MonitorSnapshot::default();
+        snapshot.gauge("hit_ratio", ratio(0.0, 0.0));
+        snapshot.gauge("usage", ratio(3.0, 4.0));
+        snapshot.counter("questions", None);
+        snapshot.counter("bytes", Some(f64::NAN));
+        assert_eq!(snapshot.gauges.get("usage"), Some(&0.75));
+        assert!(!snapshot.gauges.contains_key("hit_ratio"));
+        assert!(snapshot.counters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_metrics_unsupported() {
+        let manager = DatabaseManager::connect_with_options("sqlite::memory:", &ConnectionPoolOptions::default()).await.unwrap();
+        assert!(matches!(manager.collect_metrics().await, Err(DatabaseError::UnsupportedDatabaseType(_))));
+    }
+}
@@ -0,0 +1,476 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{DatabaseError, DatabaseManager, DatabasePool};
+
+// 数据库对象类型（表以外）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Procedure,
+    Function,
+    Trigger,
+    View,
+}
+
+// 存储过程、函数、触发器、视图
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseObject {
+    pub kind: ObjectKind,
+    pub schema: Option<String>,
+    pub name: String,
+    pub table_name: Option<String>,    // 触发器所在的表
+    pub arguments: Option<String>,     // 参数签名，如 IN id INT, OUT total DECIMAL(10,2)
+    pub return_type: Option<String>,   // 函数返回类型
+    pub timing: Option<String>,        // 触发时机：BEFORE / AFTER / INSTEAD OF
+    pub event: Option<String>,         // 触发事件：INSERT / UPDATE / DELETE
+    pub definition: Option<String>,    // 源码 / 定义
+}
+
+impl DatabaseObject {
+    fn new(kind: ObjectKind, schema: Option<String>, name: String) -> Self {
+        Self {
+            kind,
+            schema,
+            name,
+            table_name: None,
+            arguments: None,
+            return_type: None,
+            timing: None,
+            event: None,
+            definition: None,
+        }
+    }
+}
+
+// MySQL 存储过程 / 函数的参数签名（参数按顺序排列，函数参数没有 IN/OUT）
+pub fn mysql_signature(params: &[(Option<String>, Option<String>, String)]) -> String {
+    params.iter()
+        .map(|(mode, name, data_type)| {
+            [mode.as_deref(), name.as_deref(), Some(data_type.as_str())]
+                .into_iter()
+                .flatten()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// 从 SQLite 触发器的建表语句中解析触发时机和事件
+pub fn sqlite_trigger_timing(sql: &str) -> (Option<String>, Option<String>) {
+    lazy_static::lazy_static! {
+        static ref TIMING_RE: Regex = Regex::new(r"(?i)\b(BEFORE|AFTER|INSTEAD\s+OF)?\s*\b(INSERT|UPDATE|DELETE)\b(?:\s+OF\b[^\n]*?)?\s+ON\b").unwrap();
+    }
+    let Some(caps) = TIMING_RE.captures(sql) else {
+        return (None, None);
+    };
+    // SQLite 未指定时机时默认为 BEFORE
+    let timing = caps.get(1)
+        .map(|m| m.as_str().split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase())
+        .unwrap_or_else(|| "BEFORE".to_string());
+    (Some(timing), caps.get(2).map(|m| m.as_str().to_uppercase()))
+}
+
+// 视图定义
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewSource {
+    pub view_name: String,
+    pub query: String,   // 视图的 SELECT 语句
+    pub ddl: String,     // 完整的建视图语句
+}
+
+// 从 SQLite 的建视图语句中取出 SELECT 部分
+pub fn sqlite_view_query(sql: &str) -> Option<String> {
+    lazy_static::lazy_static! {
+        static ref VIEW_RE: Regex = Regex::new(
+            r#"(?is)^\s*CREATE\s+(?:TEMP(?:ORARY)?\s+)?VIEW\s+(?:IF\s+NOT\s+EXISTS\s+)?(?:"[^"]*"|`[^`]*`|\[[^\]]*\]|[^\s(]+)(?:\s*\([^)]*\))?\s+AS\s+(.+)$"#
+        ).unwrap();
+    }
+    VIEW_RE.captures(sql)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim().trim_end_matches(';').trim_end().to_string())
+}
+
+impl DatabaseManager {
+    // 读取视图定义，视图不存在时返回 None（仅 MySQL / PostgreSQL / SQLite）
+    pub async fn get_view_source(&self, view: &str) -> Result<Option<ViewSource>, DatabaseError> {
+        let quoted = super::ddl::quote_identifier(self.db_type, view).map_err(DatabaseError::InvalidDefinition)?;
+        let source = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let query = sqlx::query_scalar::<_, Option<String>>(
+                    "SELECT CAST(VIEW_DEFINITION AS CHAR) FROM information_schema.VIEWS WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?"
+                )
+                .bind(view)
+                .fetch_optional(pool)
+                .await?;
+                let Some(query) = query else {
+                    return Ok(None);
+                };
+                let row = sqlx::query(&format!("SHOW CREATE VIEW {}", quoted)).fetch_one(pool).await?;
+                let ddl: String = sqlx::Row::try_get(&row, 1)?;
+                (query.unwrap_or_default(), ddl)
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                let query = sqlx::query_scalar::<_, Option<String>>(
+                    r#"SELECT pg_get_viewdef(c.oid, true)
+                     FROM pg_class c
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE c.relname = $1 AND c.relkind = 'v' AND n.nspname = ANY(current_schemas(false))
+                     LIMIT 1"#
+                )
+                .bind(view)
+                .fetch_optional(pool)
+                .await?;
+                let Some(query) = query.flatten() else {
+                    return Ok(None);
+                };
+                let query = query.trim().trim_end_matches(';').to_string();
+                let ddl = format!("CREATE OR REPLACE VIEW {} AS\n{}", quoted, query);
+                (query, ddl)
+            }
+            DatabasePool::SQLite(pool) => {
+                let ddl = sqlx::query_scalar::<_, Option<String>>("SELECT sql FROM sqlite_master WHERE type = 'view' AND name = ?")
+                    .bind(view)
+                    .fetch_optional(pool)
+                    .await?;
+                let Some(ddl) = ddl.flatten() else {
+                    return Ok(None);
+                };
+                (sqlite_view_query(&ddl).unwrap_or_default(), ddl)
+            }
+            _ => return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持视图", self.db_type))),
+        };
+        let (query, ddl) = source;
+        Ok(Some(ViewSource { view_name: view.to_string(), query, ddl }))
+    }
+
+    // 读取触发器的完整定义语句，触发器不存在时返回 None
+    // PostgreSQL 同时返回触发器函数的定义
+    pub async fn get_trigger_ddl(&self, trigger: &str) -> Result<Option<String>, DatabaseError> {
+        let ddl = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let exists = sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM information_schema.TRIGGERS WHERE TRIGGER_SCHEMA = DATABASE() AND TRIGGER_NAME = ?"
+                )
+                .bind(trigger)
+                .fetch_one(pool)
+                .await?;
+                if exists == 0 {
+                    return Ok(None);
+                }
+                let quoted = super::ddl::quote_identifier(self.db_type, trigger).map_err(DatabaseError::InvalidDefinition)?;
+                let row = sqlx::query(&format!("SHOW CREATE TRIGGER {}", quoted)).fetch_one(pool).await?;
+                // 第3列为 SQL Original Statement
+                Some(sqlx::Row::try_get::<String, _>(&row, 2)?)
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as::<_, (String, Option<String>)>(
+                    r#"SELECT pg_get_triggerdef(t.oid, true), pg_get_functiondef(t.tgfoid)
+                     FROM pg_trigger t
+                     JOIN pg_class c ON c.oid = t.tgrelid
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE t.tgname = $1 AND NOT t.tgisinternal AND n.nspname = ANY(current_schemas(false))
+                     LIMIT 1"#
+                )
+                .bind(trigger)
+                .fetch_optional(pool)
+                .await?
+                .map(|(trigger_def, function_def)| match function_def {
+                    Some(function_def) => format!("{};\n\n{}", function_def.trim_end().trim_end_matches(';'), trigger_def),
+                    None => trigger_def,
+                })
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_scalar::<_, Option<String>>("SELECT sql FROM sqlite_master WHERE type = 'trigger' AND name = ?")
+                    .bind(trigger)
+                    .fetch_optional(pool)
+                    .await?
+                    .flatten()
+            }
+            _ => return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持触发器", self.db_type))),
+        };
+        Ok(ddl)
+    }
+
+    // 列出存储过程、函数、触发器和视图（kind 为空时列出全部）
+    // schema 为空时使用当前数据库（MySQL）/ 搜索路径中的schema（PostgreSQL）
+    pub async fn list_objects(&self, kind: Option<ObjectKind>, schema: Option<&str>) -> Result<Vec<DatabaseObject>, DatabaseError> {
+        let wants = |k: ObjectKind| kind.is_none() || kind == Some(k);
+        let mut objects = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let mut objects = Vec::new();
+                if wants(ObjectKind::Procedure) || wants(ObjectKind::Function) {
+                    let routines = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>)>(
+                        r#"SELECT CAST(ROUTINE_SCHEMA AS CHAR), CAST(ROUTINE_NAME AS CHAR), CAST(ROUTINE_TYPE AS CHAR),
+                                  CAST(DTD_IDENTIFIER AS CHAR), CAST(ROUTINE_DEFINITION AS CHAR)
+                           FROM information_schema.ROUTINES
+                           WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE())
+                           ORDER BY ROUTINE_NAME"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    let params = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String)>(
+                        r#"SELECT CAST(SPECIFIC_NAME AS CHAR), CAST(ROUTINE_TYPE AS CHAR), CAST(PARAMETER_MODE AS CHAR),
+                                  CAST(PARAMETER_NAME AS CHAR), CAST(DTD_IDENTIFIER AS CHAR)
+                           FROM information_schema.PARAMETERS
+                           WHERE SPECIFIC_SCHEMA = COALESCE(?, DATABASE()) AND ORDINAL_POSITION > 0
+                           ORDER BY SPECIFIC_NAME, ORDINAL_POSITION"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+
+                    for (routine_schema, name, routine_type, return_type, definition) in routines {
+                        let kind = if routine_type.eq_ignore_ascii_case("FUNCTION") { ObjectKind::Function } else { ObjectKind::Procedure };
+                        if !wants(kind) {
+                            continue;
+                        }
+                        let signature: Vec<_> = params.iter()
+                            .filter(|(specific, specific_type, ..)| *specific == name && specific_type.eq_ignore_ascii_case(&routine_type))
+                            .map(|(_, _, mode, param, data_type)| (mode.clone(), param.clone(), data_type.clone()))
+                            .collect();
+                        let mut object = DatabaseObject::new(kind, Some(routine_schema), name);
+                        object.arguments = Some(mysql_signature(&signature));
+                        object.return_type = return_type.filter(|_| kind == ObjectKind::Function);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                if wants(ObjectKind::Trigger) {
+                    let triggers = sqlx::query_as::<_, (String, String, String, String, String, Option<String>)>(
+                        r#"SELECT CAST(TRIGGER_SCHEMA AS CHAR), CAST(TRIGGER_NAME AS CHAR), CAST(EVENT_OBJECT_TABLE AS CHAR),
+                                  CAST(ACTION_TIMING AS CHAR), CAST(EVENT_MANIPULATION AS CHAR), CAST(ACTION_STATEMENT AS CHAR)
+                           FROM information_schema.TRIGGERS
+                           WHERE TRIGGER_SCHEMA = COALESCE(?, DATABASE())
+                           ORDER BY TRIGGER_NAME"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    for (trigger_schema, name, table, timing, event, definition) in triggers {
+                        let mut object = DatabaseObject::new(ObjectKind::Trigger, Some(trigger_schema), name);
+                        object.table_name = Some(table);
+                        object.timing = Some(timing);
+                        object.event = Some(event);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                if wants(ObjectKind::View) {
+                    let views = sqlx::query_as::<_, (String, String, Option<String>)>(
+                        r#"SELECT CAST(TABLE_SCHEMA AS CHAR), CAST(TABLE_NAME AS CHAR), CAST(VIEW_DEFINITION AS CHAR)
+                           FROM information_schema.VIEWS
+                           WHERE TABLE_SCHEMA = COALESCE(?, DATABASE())
+                           ORDER BY TABLE_NAME"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    for (view_schema, name, definition) in views {
+                        let mut object = DatabaseObject::new(ObjectKind::View, Some(view_schema), name);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                objects
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                let mut objects = Vec::new();
+                if wants(ObjectKind::Procedure) || wants(ObjectKind::Function) {
+                    // 不含聚合函数、窗口函数和扩展安装的函数
+                    let routines = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, Option<String>)>(
+                        r#"SELECT n.nspname::text, p.proname::text, p.prokind::text,
+                                  pg_get_function_arguments(p.oid), pg_get_function_result(p.oid), pg_get_functiondef(p.oid)
+                           FROM pg_proc p
+                           JOIN pg_namespace n ON n.oid = p.pronamespace
+                           WHERE p.prokind IN ('f', 'p')
+                             AND (n.nspname = $1 OR ($1 IS NULL AND n.nspname = ANY(current_schemas(false))))
+                             AND NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e')
+                           ORDER BY n.nspname, p.proname"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    for (routine_schema, name, prokind, arguments, return_type, definition) in routines {
+                        let kind = if prokind == "p" { ObjectKind::Procedure } else { ObjectKind::Function };
+                        if !wants(kind) {
+                            continue;
+                        }
+                        let mut object = DatabaseObject::new(kind, Some(routine_schema), name);
+                        object.arguments = arguments;
+                        object.return_type = return_type.filter(|_| kind == ObjectKind::Function);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                if wants(ObjectKind::Trigger) {
+                    // tgtype 位标志：2 BEFORE，64 INSTEAD OF，4 INSERT，8 DELETE，16 UPDATE，32 TRUNCATE
+                    let triggers = sqlx::query_as::<_, (String, String, String, String, String, Option<String>)>(
+                        r#"SELECT n.nspname::text, t.tgname::text, c.relname::text,
+                                  CASE WHEN t.tgtype::int & 2 <> 0 THEN 'BEFORE' WHEN t.tgtype::int & 64 <> 0 THEN 'INSTEAD OF' ELSE 'AFTER' END,
+                                  concat_ws(' OR ',
+                                      CASE WHEN t.tgtype::int & 4 <> 0 THEN 'INSERT' END,
+                                      CASE WHEN t.tgtype::int & 16 <> 0 THEN 'UPDATE' END,
+                                      CASE WHEN t.tgtype::int & 8 <> 0 THEN 'DELETE' END,
+                                      CASE WHEN t.tgtype::int & 32 <> 0 THEN 'TRUNCATE' END),
+                                  pg_get_triggerdef(t.oid)
+                           FROM pg_trigger t
+                           JOIN pg_class c ON c.oid = t.tgrelid
+                           JOIN pg_namespace n ON n.oid = c.relnamespace
+                           WHERE NOT t.tgisinternal
+                             AND (n.nspname = $1 OR ($1 IS NULL AND n.nspname = ANY(current_schemas(false))))
+                           ORDER BY n.nspname, t.tgname"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    for (trigger_schema, name, table, timing, event, definition) in triggers {
+                        let mut object = DatabaseObject::new(ObjectKind::Trigger, Some(trigger_schema), name);
+                        object.table_name = Some(table);
+                        object.timing = Some(timing);
+                        object.event = Some(event);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                if wants(ObjectKind::View) {
+                    let views = sqlx::query_as::<_, (String, String, Option<String>)>(
+                        r#"SELECT schemaname::text, viewname::text, definition
+                           FROM pg_views
+                           WHERE schemaname = $1 OR ($1 IS NULL AND schemaname = ANY(current_schemas(false)))
+                           ORDER BY schemaname, viewname"#
+                    )
+                    .bind(schema)
+                    .fetch_all(pool)
+                    .await?;
+                    for (view_schema, name, definition) in views {
+                        let mut object = DatabaseObject::new(ObjectKind::View, Some(view_schema), name);
+                        object.definition = definition;
+                        objects.push(object);
+                    }
+                }
+                objects
+            }
+            DatabasePool::SQLite(pool) => {
+                // SQLite 没有存储过程和函数
+                let schema = schema.unwrap_or("main");
+                let quoted = super::ddl::quote_identifier(self.db_type, schema).map_err(DatabaseError::InvalidDefinition)?;
+                let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(&format!(
+                    "SELECT type, name, tbl_name, sql FROM {}.sqlite_master
+                     WHERE type IN ('view', 'trigger') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\'
+                     ORDER BY name",
+                    quoted
+                ))
+                .fetch_all(pool)
+                .await?;
+                rows.into_iter()
+                    .filter_map(|(object_type, name, table, sql)| {
+                        let kind = if object_type == "view" { ObjectKind::View } else { ObjectKind::Trigger };
+                        if !wants(kind) {
+                            return None;
+                        }
+                        let mut object = DatabaseObject::new(kind, Some(schema.to_string()), name);
+                        if kind == ObjectKind::Trigger {
+                            let (timing, event) = sql.as_deref().map(sqlite_trigger_timing).unwrap_or_default();
+                            object.table_name = Some(table);
+                            object.timing = timing;
+                            object.event = event;
+                        }
+                        object.definition = sql;
+                        Some(object)
+                    })
+                    .collect()
+            }
+            _ => return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持存储过程、触发器和视图", self.db_type))),
+        };
+        objects.sort_by(|a, b| a.schema.cmp(&b.schema).then_with(|| a.name.cmp(&b.name)));
+        Ok(objects)
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionPoolOptions;
+
+    #[test]
+    fn test_mysql_signature() {
+        let params = vec![
+            (Some("IN".to_string()), Some("id".to_string()), "int".to_string()),
+            (Some("OUT".to_string()), Some("total".to_string()), "decimal(10,2)".to_string()),
+        ];
+        assert_eq!(mysql_signature(&params), "IN id int, OUT total decimal(10,2)");
+        assert_eq!(mysql_signature(&[(None, Some("x".to_string()), "varchar(20)".to_string())]), "x varchar(20)");
+        assert_eq!(mysql_signature(&[]), "");
+    }
+
+    #[test]
+    fn test_sqlite_trigger_timing() {
+        assert_eq!(
+            sqlite_trigger_timing("CREATE TRIGGER t AFTER UPDATE OF name ON users BEGIN SELECT 1; END"),
+            (Some("AFTER".to_string()), Some("UPDATE".to_string()))
+        );
+        assert_eq!(
+            sqlite_trigger_timing("CREATE TRIGGER t instead  of insert ON v BEGIN SELECT 1; END"),
+            (Some("INSTEAD OF".to_string()), Some("INSERT".to_string()))
+        );
+        assert_eq!(
+            sqlite_trigger_timing("CREATE TRIGGER t DELETE ON users BEGIN SELECT 1; END"),
+            (Some("BEFORE".to_string()), Some("DELETE".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sqlite_view_query() {
+        assert_eq!(
+            sqlite_view_query("CREATE VIEW \"v\" (a, b) AS\nSELECT 1, 2;").as_deref(),
+            Some("SELECT 1, 2")
+        );
+        assert_eq!(
+            sqlite_view_query("create temp view if not exists v as select * from t").as_deref(),
+            Some("select * from t")
+        );
+        assert!(sqlite_view_query("CREATE TABLE t (a)").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_sqlite_objects() {
+        let options = ConnectionPoolOptions { pool_max_connections: Some(1), ..Default::default() };
+        let manager = DatabaseManager::connect_with_options("sqlite::memory:", &options).await.unwrap();
+        let DatabasePool::SQLite(pool) = &manager.pool else {
+            panic!("expected sqlite pool");
+        };
+        for sql in [
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE TABLE audit (user_id INTEGER)",
+            "CREATE VIEW active_users AS SELECT * FROM users",
+            "CREATE TRIGGER users_audit AFTER INSERT ON users BEGIN INSERT INTO audit VALUES (new.id); END",
+        ] {
+            sqlx::query(sql).execute(pool).await.unwrap();
+        }
+
+        let objects = manager.list_objects(None, None).await.unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].name, "active_users");
+        assert_eq!(objects[0].kind, ObjectKind::View);
+        assert_eq!(objects[1].kind, ObjectKind::Trigger);
+        assert_eq!(objects[1].table_name.as_deref(), Some("users"));
+        assert_eq!(objects[1].event.as_deref(), Some("INSERT"));
+
+        let views = manager.list_objects(Some(ObjectKind::View), None).await.unwrap();
+        assert_eq!(views.len(), 1);
+        assert_eq!(manager.get_views().await.unwrap(), vec!["active_users".to_string()]);
+        assert_eq!(manager.get_schema().await.unwrap().len(), 2);
+        let source = manager.get_view_source("active_users").await.unwrap().unwrap();
+        assert_eq!(source.query, "SELECT * FROM users");
+        assert!(manager.get_view_source("missing").await.unwrap().is_none());
+        let trigger_ddl = manager.get_trigger_ddl("users_audit").await.unwrap().unwrap();
+        assert!(trigger_ddl.starts_with("CREATE TRIGGER users_audit AFTER INSERT ON users"));
+        assert!(manager.get_trigger_ddl("missing").await.unwrap().is_none());
+        assert!(manager.list_objects(Some(ObjectKind::Procedure), None).await.unwrap().is_empty());
+    }
+}
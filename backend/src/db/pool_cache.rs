@@ -0,0 +1,268 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::{DatabaseError, DatabaseManager, DatabaseType, PoolUsage};
+use crate::db::LocalStorageManager;
+use crate::models::ConnectionPoolOptions;
+
+// 缓存的连接池闲置超过该时长后被释放
+pub const POOL_CACHE_IDLE_TTL: Duration = Duration::from_secs(30 * 60);
+
+// 缓存的连接池（fingerprint 为连接字符串和连接池配置的哈希，配置变化后重新建立）
+// 同一连接切换到其他数据库时单独缓存，键为 (连接ID, 数据库名)
+struct CachedPool {
+    fingerprint: u64,
+    manager: DatabaseManager,
+    last_used: Instant,
+}
+
+type PoolKey = (i64, Option<String>);
+
+static POOL_CACHE: OnceLock<Mutex<HashMap<PoolKey, CachedPool>>> = OnceLock::new();
+
+fn pool_cache() -> &'static Mutex<HashMap<PoolKey, CachedPool>> {
+    POOL_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 连接池的累计统计（连接池释放后保留，连接被修改或删除时清除）
+#[derive(Debug, Clone, Default)]
+struct PoolCounters {
+    checkouts: u64,                 // 获取连接池的次数
+    waits: u64,                     // 获取时连接已全部占用、需要排队等待的次数
+    connect_errors: u64,            // 建立连接池失败的次数
+    last_error: Option<String>,
+    last_error_at: Option<i64>,
+}
+
+static POOL_COUNTERS: OnceLock<Mutex<HashMap<PoolKey, PoolCounters>>> = OnceLock::new();
+
+fn pool_counters() -> &'static Mutex<HashMap<PoolKey, PoolCounters>> {
+    POOL_COUNTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn record_checkout(key: &PoolKey, manager: &DatabaseManager) {
+    let saturated = manager.pool_usage()
+        .is_some_and(|usage| usage.idle == 0 && usage.size >= usage.max_connections);
+    let mut counters = pool_counters().lock().unwrap();
+    let counters = counters.entry(key.clone()).or_default();
+    counters.checkouts += 1;
+    if saturated {
+        counters.waits += 1;
+    }
+}
+
+fn record_connect_error(key: &PoolKey, error: &DatabaseError) {
+    let mut counters = pool_counters().lock().unwrap();
+    let counters = counters.entry(key.clone()).or_default();
+    counters.connect_errors += 1;
+    counters.last_error = Some(error.to_string());
+    counters.last_error_at = Some(LocalStorageManager::current_timestamp());
+}
+
+fn fingerprint(database_url: &str, options: &ConnectionPoolOptions, init_sql: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    database_url.hash(&mut hasher);
+    options.hash(&mut hasher);
+    init_sql.hash(&mut hasher);
+    hasher.finish()
+}
+
+// 按连接ID获取缓存的连接池，不存在、配置已变化或闲置过久时重新建立
+// 连接池在取出连接前会检测连接是否存活，数据库重启或网络中断后自动重连
+pub async fn get_or_connect(
+    connection_id: Option<i64>,
+    database_url: &str,
+    options: &ConnectionPoolOptions,
+    init_sql: &[String],
+) -> Result<DatabaseManager, DatabaseError> {
+    get_or_connect_database(connection_id, None, database_url, options, init_sql).await
+}
+
+// 获取连接切换到指定数据库后的连接池（database 为None时为连接配置的数据库）
+pub async fn get_or_connect_database(
+    connection_id: Option<i64>,
+    database: Option<&str>,
+    database_url: &str,
+    options: &ConnectionPoolOptions,
+    init_sql: &[String],
+) -> Result<DatabaseManager, DatabaseError> {
+    let Some(connection_id) = connection_id else {
+        return DatabaseManager::connect_with_init(database_url, options, init_sql).await;
+    };
+    let key = (connection_id, database.map(str::to_string));
+    let fingerprint = fingerprint(database_url, options, init_sql);
+    {
+        let mut cache = pool_cache().lock().unwrap();
+        cache.retain(|_, cached| cached.last_used.elapsed() < POOL_CACHE_IDLE_TTL);
+        if let Some(cached) = cache.get_mut(&key) {
+            if cached.fingerprint == fingerprint {
+                cached.last_used = Instant::now();
+                let manager = cached.manager.clone();
+                drop(cache);
+                record_checkout(&key, &manager);
+                return Ok(manager);
+            }
+            cache.remove(&key);
+        }
+    }
+
+    // 在锁外建立连接，避免阻塞其他连接
+    let manager = DatabaseManager::connect_with_init(database_url, options, init_sql).await
+        .inspect_err(|e| record_connect_error(&key, e))?;
+    record_checkout(&key, &manager);
+    pool_cache().lock().unwrap().insert(key, CachedPool {
+        fingerprint,
+        manager: manager.clone(),
+        last_used: Instant::now(),
+    });
+    log::info!("[PoolCache] 已建立连接池: connection_id={}, database={:?}", connection_id, database);
+    Ok(manager)
+}
+
+// 移除连接的所有缓存连接池并关闭连接的会话（连接被修改或删除时调用）
+pub fn invalidate(connection_id: i64) {
+    super::query_sessions::close_connection(connection_id);
+    let mut cache = pool_cache().lock().unwrap();
+    let before = cache.len();
+    cache.retain(|(id, _), _| *id != connection_id);
+    if cache.len() < before {
+        log::info!("[PoolCache] 已释放连接池: connection_id={}, 数量={}", connection_id, before - cache.len());
+    }
+    pool_counters().lock().unwrap().retain(|(id, _), _| *id != connection_id);
+}
+
+// 连接池统计（连接池大小、空闲连接、等待次数、建立连接失败次数）
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    pub database: Option<String>,        // 切换到的数据库，为空时为连接配置的数据库
+    pub cached: bool,                    // 连接池是否仍在缓存中
+    pub db_type: Option<String>,
+    pub size: Option<u32>,               // 当前连接数（MongoDB、Redis、DuckDB 为空）
+    pub idle: Option<usize>,
+    pub active: Option<u32>,
+    pub max_connections: Option<u32>,
+    pub idle_secs: Option<u64>,          // 距上次使用的秒数
+    pub checkouts: u64,
+    pub waits: u64,
+    pub connect_errors: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+}
+
+// 连接的所有连接池统计（包括缓存中的和建立失败的），按数据库名排序
+pub fn pool_stats(connection_id: i64) -> Vec<PoolStats> {
+    let cache = pool_cache().lock().unwrap();
+    let counters = pool_counters().lock().unwrap();
+    let mut keys: Vec<&PoolKey> = cache.keys()
+        .chain(counters.keys())
+        .filter(|(id, _)| *id == connection_id)
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .map(|key| {
+            let cached = cache.get(key).filter(|cached| cached.last_used.elapsed() < POOL_CACHE_IDLE_TTL);
+            let usage = cached.and_then(|cached| cached.manager.pool_usage());
+            let counter = counters.get(key).cloned().unwrap_or_default();
+            PoolStats {
+                database: key.1.clone(),
+                cached: cached.is_some(),
+                db_type: cached.map(|cached| cached.manager.db_type.as_str().to_string()),
+                size: usage.map(|u| u.size),
+                idle: usage.map(|u| u.idle),
+                active: usage.map(|u| u.size.saturating_sub(u.idle as u32)),
+                max_connections: usage.map(|u| u.max_connections),
+                idle_secs: cached.map(|cached| cached.last_used.elapsed().as_secs()),
+                checkouts: counter.checkouts,
+                waits: counter.waits,
+                connect_errors: counter.connect_errors,
+                last_error: counter.last_error,
+                last_error_at: counter.last_error_at,
+            }
+        })
+        .collect()
+}
+
+// 缓存的连接池及其使用情况
+#[derive(Debug, Clone)]
+pub struct CachedPoolUsage {
+    pub connection_id: i64,
+    pub database: Option<String>,
+    pub db_type: DatabaseType,
+    pub usage: PoolUsage,
+}
+
+// 所有缓存连接池的使用情况（不含没有连接池的数据库类型）
+pub fn pool_usages() -> Vec<CachedPoolUsage> {
+    pool_cache().lock().unwrap()
+        .iter()
+        .filter(|(_, cached)| cached.last_used.elapsed() < POOL_CACHE_IDLE_TTL)
+        .filter_map(|((connection_id, database), cached)| Some(CachedPoolUsage {
+            connection_id: *connection_id,
+            database: database.clone(),
+            db_type: cached.manager.db_type,
+            usage: cached.manager.pool_usage()?,
+        }))
+        .collect()
+}
+
+// 当前缓存的连接池数量（不含已闲置过期的）
+pub fn cached_pool_count() -> usize {
+    pool_cache().lock().unwrap()
+        .values()
+        .filter(|cached| cached.last_used.elapsed() < POOL_CACHE_IDLE_TTL)
+        .count()
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pool_is_reused_until_options_change() {
+        let options = ConnectionPoolOptions { pool_max_connections: Some(2), ..Default::default() };
+        let key = (-70, None);
+        get_or_connect(Some(-70), "sqlite::memory:", &options, &[]).await.unwrap();
+        assert!(cached_pool_count() >= 1);
+        let first = pool_cache().lock().unwrap().get(&key).unwrap().fingerprint;
+        get_or_connect(Some(-70), "sqlite::memory:", &options, &[]).await.unwrap();
+        assert_eq!(pool_cache().lock().unwrap().get(&key).unwrap().fingerprint, first);
+
+        let changed = ConnectionPoolOptions { pool_max_connections: Some(4), ..Default::default() };
+        get_or_connect(Some(-70), "sqlite::memory:", &changed, &[]).await.unwrap();
+        assert_ne!(pool_cache().lock().unwrap().get(&key).unwrap().fingerprint, first);
+
+        // 切换数据库的连接池与默认连接池分开缓存，释放连接时一并释放
+        get_or_connect_database(Some(-70), Some("other"), "sqlite::memory:", &changed, &[]).await.unwrap();
+        assert!(pool_cache().lock().unwrap().contains_key(&key));
+        let stats = pool_stats(-70);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].database, None);
+        assert!(stats[0].cached && stats[0].checkouts >= 3);
+        assert_eq!(stats[0].max_connections, Some(4));
+        assert_eq!(stats[1].database.as_deref(), Some("other"));
+        invalidate(-70);
+        assert!(pool_stats(-70).is_empty());
+        let cache = pool_cache().lock().unwrap();
+        assert!(!cache.keys().any(|(id, _)| *id == -70));
+    }
+
+    #[tokio::test]
+    async fn test_pool_stats_record_connect_errors() {
+        let options = ConnectionPoolOptions::default();
+        assert!(get_or_connect(Some(-71), "unknown://localhost/db", &options, &[]).await.is_err());
+        let stats = pool_stats(-71);
+        assert_eq!(stats.len(), 1);
+        assert!(!stats[0].cached);
+        assert_eq!(stats[0].connect_errors, 1);
+        assert!(stats[0].last_error.is_some());
+        invalidate(-71);
+    }
+}
@@ -0,0 +1,235 @@
+use serde::Serialize;
+
+use super::{DatabaseError, DatabaseManager, DatabasePool};
+
+// 数据库用户 / 角色
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseUser {
+    pub name: String,
+    pub host: Option<String>,              // MySQL 账号的主机部分
+    pub superuser: Option<bool>,
+    pub can_login: Option<bool>,           // PostgreSQL：角色是否可以登录（用户 / 组角色）
+    pub member_of: Vec<String>,            // PostgreSQL：所属的角色
+    pub grant_statements: Vec<String>,     // MySQL：SHOW GRANTS 中与当前数据库相关的授权语句
+}
+
+// 权限授予记录
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivilegeGrant {
+    pub grantee: String,
+    pub object_type: String,               // GLOBAL / DATABASE / TABLE
+    pub schema: Option<String>,
+    pub object_name: Option<String>,       // 表名（库级、全局权限为空）
+    pub privilege_type: String,            // SELECT / INSERT / CONNECT 等
+    pub is_grantable: bool,
+}
+
+// 用户和权限概览（只读）
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivilegeOverview {
+    pub database: Option<String>,
+    pub users: Vec<DatabaseUser>,
+    pub grants: Vec<PrivilegeGrant>,
+}
+
+// 拆分 MySQL 账号 user@host（CURRENT_USER() 的返回值）
+fn split_mysql_account(account: &str) -> (String, Option<String>) {
+    match account.rsplit_once('@') {
+        Some((user, host)) => (user.to_string(), Some(host.to_string())),
+        None => (account.to_string(), None),
+    }
+}
+
+// 拆分 information_schema 中的 GRANTEE（'user'@'host'），返回 user@host 形式
+fn mysql_grantee(grantee: &str) -> String {
+    grantee.replace('\'', "")
+}
+
+// MySQL 授权语句是否与当前数据库相关：全局权限、该库（或该库中表）的权限，以及角色授予
+fn mysql_grant_applies(statement: &str, database: Option<&str>) -> bool {
+    let Some(on) = statement.find(" ON ") else {
+        return true;
+    };
+    let target = statement[on + 4..].trim_start();
+    if target.starts_with("*.*") {
+        return true;
+    }
+    let Some(database) = database else {
+        return false;
+    };
+    // 目标可能带有 PROCEDURE / FUNCTION 前缀
+    let target = target.split_once(' ')
+        .filter(|(prefix, _)| prefix.eq_ignore_ascii_case("PROCEDURE") || prefix.eq_ignore_ascii_case("FUNCTION"))
+        .map(|(_, rest)| rest.trim_start())
+        .unwrap_or(target);
+    let quoted = format!("`{}`.", database.replace('`', "``"));
+    target.starts_with(&quoted) || target.starts_with(&format!("{}.", database))
+}
+
+impl DatabaseManager {
+    // 列出用户 / 角色及其在当前数据库上的权限
+    // 无权读取 mysql.user 时只返回当前账号
+    pub async fn list_privileges(&self) -> Result<PrivilegeOverview, DatabaseError> {
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let database: Option<String> = sqlx::query_scalar("SELECT CAST(DATABASE() AS CHAR)")
+                    .fetch_one(pool)
+                    .await?;
+
+                let accounts = sqlx::query_as::<_, (String, String, String)>(
+                    "SELECT CAST(User AS CHAR), CAST(Host AS CHAR), CAST(Super_priv AS CHAR) FROM mysql.user ORDER BY User, Host"
+                )
+                .fetch_all(pool)
+                .await;
+                let accounts: Vec<(String, Option<String>, Option<bool>)> = match accounts {
+                    Ok(accounts) => accounts.into_iter()
+                        .map(|(user, host, super_priv)| (user, Some(host), Some(super_priv.eq_ignore_ascii_case("Y"))))
+                        .collect(),
+                    Err(e) => {
+                        log::warn!("[Privileges] 无法读取 mysql.user，只返回当前账号: {}", e);
+                        let current: String = sqlx::query_scalar("SELECT CAST(CURRENT_USER() AS CHAR)").fetch_one(pool).await?;
+                        let (user, host) = split_mysql_account(&current);
+                        vec![(user, host, None)]
+                    }
+                };
+
+                let mut users = Vec::new();
+                for (name, host, superuser) in accounts {
+                    let sql = match &host {
+                        Some(host) => format!("SHOW GRANTS FOR '{}'@'{}'", name.replace('\'', "''"), host.replace('\'', "''")),
+                        None => "SHOW GRANTS".to_string(),
+                    };
+                    let grant_statements = match sqlx::query_scalar::<_, String>(&sql).fetch_all(pool).await {
+                        Ok(statements) => statements.into_iter()
+                            .filter(|statement| mysql_grant_applies(statement, database.as_deref()))
+                            .collect(),
+                        Err(e) => {
+                            log::warn!("[Privileges] 读取 {} 的授权失败: {}", name, e);
+                            Vec::new()
+                        }
+                    };
+                    users.push(DatabaseUser {
+                        name,
+                        host,
+                        superuser,
+                        can_login: None,
+                        member_of: Vec::new(),
+                        grant_statements,
+                    });
+                }
+
+                let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, String)>(
+                    r#"SELECT CAST(GRANTEE AS CHAR), 'GLOBAL', NULL, NULL, CAST(PRIVILEGE_TYPE AS CHAR), CAST(IS_GRANTABLE AS CHAR)
+                       FROM information_schema.USER_PRIVILEGES
+                       UNION ALL
+                       SELECT CAST(GRANTEE AS CHAR), 'DATABASE', CAST(TABLE_SCHEMA AS CHAR), NULL, CAST(PRIVILEGE_TYPE AS CHAR), CAST(IS_GRANTABLE AS CHAR)
+                       FROM information_schema.SCHEMA_PRIVILEGES WHERE TABLE_SCHEMA = DATABASE()
+                       UNION ALL
+                       SELECT CAST(GRANTEE AS CHAR), 'TABLE', CAST(TABLE_SCHEMA AS CHAR), CAST(TABLE_NAME AS CHAR), CAST(PRIVILEGE_TYPE AS CHAR), CAST(IS_GRANTABLE AS CHAR)
+                       FROM information_schema.TABLE_PRIVILEGES WHERE TABLE_SCHEMA = DATABASE()"#
+                )
+                .fetch_all(pool)
+                .await?;
+                let grants = rows.into_iter()
+                    .map(|(grantee, object_type, schema, object_name, privilege_type, is_grantable)| PrivilegeGrant {
+                        grantee: mysql_grantee(&grantee),
+                        object_type,
+                        schema,
+                        object_name,
+                        privilege_type,
+                        is_grantable: is_grantable.eq_ignore_ascii_case("YES"),
+                    })
+                    .collect();
+
+                Ok(PrivilegeOverview { database, users, grants })
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                let database: String = sqlx::query_scalar("SELECT current_database()::text").fetch_one(pool).await?;
+
+                // 不含 pg_ 开头的内置角色
+                let roles = sqlx::query_as::<_, (String, bool, bool, Vec<String>)>(
+                    r#"SELECT r.rolname::text, r.rolsuper, r.rolcanlogin,
+                              ARRAY(SELECT g.rolname::text FROM pg_auth_members m JOIN pg_roles g ON g.oid = m.roleid
+                                    WHERE m.member = r.oid ORDER BY g.rolname)
+                       FROM pg_roles r
+                       WHERE r.rolname !~ '^pg_'
+                       ORDER BY r.rolname"#
+                )
+                .fetch_all(pool)
+                .await?;
+                let users = roles.into_iter()
+                    .map(|(name, superuser, can_login, member_of)| DatabaseUser {
+                        name,
+                        host: None,
+                        superuser: Some(superuser),
+                        can_login: Some(can_login),
+                        member_of,
+                        grant_statements: Vec::new(),
+                    })
+                    .collect();
+
+                // 库级权限（CONNECT / CREATE / TEMPORARY，grantee 为 0 表示 PUBLIC）及表级权限
+                let rows = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, String, bool)>(
+                    r#"SELECT CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END,
+                              'DATABASE', NULL::text, NULL::text, a.privilege_type::text, a.is_grantable
+                       FROM pg_database d, aclexplode(d.datacl) a
+                       WHERE d.datname = current_database()
+                       UNION ALL
+                       SELECT grantee::text, 'TABLE', table_schema::text, table_name::text, privilege_type::text, is_grantable = 'YES'
+                       FROM information_schema.role_table_grants
+                       WHERE table_catalog = current_database()
+                         AND table_schema NOT IN ('pg_catalog', 'information_schema')
+                       ORDER BY 1, 2, 3, 4, 5"#
+                )
+                .fetch_all(pool)
+                .await?;
+                let grants = rows.into_iter()
+                    .map(|(grantee, object_type, schema, object_name, privilege_type, is_grantable)| PrivilegeGrant {
+                        grantee,
+                        object_type,
+                        schema,
+                        object_name,
+                        privilege_type,
+                        is_grantable,
+                    })
+                    .collect();
+
+                Ok(PrivilegeOverview { database: Some(database), users, grants })
+            }
+            _ => Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持查看用户权限", self.db_type))),
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionPoolOptions;
+
+    #[test]
+    fn test_mysql_grant_applies() {
+        let db = Some("shop");
+        assert!(mysql_grant_applies("GRANT USAGE ON *.* TO `app`@`%`", db));
+        assert!(mysql_grant_applies("GRANT SELECT, INSERT ON `shop`.* TO `app`@`%`", db));
+        assert!(mysql_grant_applies("GRANT SELECT ON `shop`.`orders` TO `app`@`%`", db));
+        assert!(mysql_grant_applies("GRANT EXECUTE ON PROCEDURE `shop`.`refund` TO `app`@`%`", db));
+        assert!(mysql_grant_applies("GRANT `reader`@`%` TO `app`@`%`", db));
+        assert!(!mysql_grant_applies("GRANT ALL PRIVILEGES ON `crm`.* TO `app`@`%`", db));
+        assert!(!mysql_grant_applies("GRANT SELECT ON `shop_archive`.* TO `app`@`%`", db));
+        assert!(!mysql_grant_applies("GRANT SELECT ON `shop`.* TO `app`@`%`", None));
+    }
+
+    #[test]
+    fn test_mysql_account_names() {
+        assert_eq!(split_mysql_account("app@localhost"), ("app".to_string(), Some("localhost".to_string())));
+        assert_eq!(split_mysql_account("root"), ("root".to_string(), None));
+        assert_eq!(mysql_grantee("'app'@'%'"), "app@%");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_privileges_unsupported() {
+        let manager = DatabaseManager::connect_with_options("sqlite::memory:", &ConnectionPoolOptions::default()).await.unwrap();
+        assert!(matches!(manager.list_privileges().await, Err(DatabaseError::UnsupportedDatabaseType(_))));
+    }
+}
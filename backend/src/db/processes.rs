@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DatabaseError, DatabaseManager, DatabasePool, DatabaseType};
+
+// 服务端会话（MySQL 线程 / PostgreSQL 后端进程）
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerProcess {
+    pub id: i64,                        // MySQL 线程ID / PostgreSQL pid
+    pub user: Option<String>,
+    pub host: Option<String>,           // 客户端地址
+    pub database: Option<String>,
+    pub command: Option<String>,        // MySQL：Query / Sleep 等；PostgreSQL：backend_type
+    pub state: Option<String>,          // MySQL：线程状态；PostgreSQL：active / idle / idle in transaction 等
+    pub time_secs: Option<i64>,         // 当前语句（或状态）已持续的秒数
+    pub query: Option<String>,
+    pub current: bool,                  // 是否为执行本次列表查询的连接
+}
+
+// 终止方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillMode {
+    // 只取消正在执行的语句，保留会话（KILL QUERY / pg_cancel_backend）
+    #[default]
+    Query,
+    // 断开会话（KILL / pg_terminate_backend）
+    Connection,
+}
+
+// 终止会话使用的语句（用于日志和审计）
+pub fn kill_statement(db_type: DatabaseType, id: i64, mode: KillMode) -> Option<String> {
+    match (db_type, mode) {
+        (DatabaseType::MySQL, KillMode::Query) => Some(format!("KILL QUERY {}", id)),
+        (DatabaseType::MySQL, KillMode::Connection) => Some(format!("KILL {}", id)),
+        (DatabaseType::PostgreSQL, KillMode::Query) => Some(format!("SELECT pg_cancel_backend({})", id)),
+        (DatabaseType::PostgreSQL, KillMode::Connection) => Some(format!("SELECT pg_terminate_backend({})", id)),
+        _ => None,
+    }
+}
+
+impl DatabaseManager {
+    // 列出服务端的客户端会话（MySQL: information_schema.PROCESSLIST，PostgreSQL: pg_stat_activity）
+    // 按持续时间倒序，长时间运行的语句排在前面
+    pub async fn list_processes(&self) -> Result<Vec<ServerProcess>, DatabaseError> {
+        let mut processes = match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                let rows = sqlx::query_as::<_, (i64, Option<String>, Option<String>, Option<String>, Option<String>, Option<i64>, Option<String>, Option<String>, i64)>(
+                    r#"SELECT CAST(ID AS SIGNED), CAST(USER AS CHAR), CAST(HOST AS CHAR), CAST(DB AS CHAR), CAST(COMMAND AS CHAR),
+                              CAST(TIME AS SIGNED), CAST(STATE AS CHAR), CAST(INFO AS CHAR), CAST(ID = CONNECTION_ID() AS SIGNED)
+                       FROM information_schema.PROCESSLIST"#
+                )
+                .fetch_all(pool)
+                .await?;
+                rows.into_iter()
+                    .map(|(id, user, host, database, command, time_secs, state, query, current)| ServerProcess {
+                        id,
+                        user,
+                        host,
+                        database,
+                        command,
+                        state: state.filter(|s| !s.is_empty()),
+                        time_secs,
+                        query,
+                        current: current != 0,
+                    })
+                    .collect::<Vec<_>>()
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                // 只列出客户端连接（不含 autovacuum、WAL 等后台进程）
+                let rows = sqlx::query_as::<_, (i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<i64>, Option<String>, bool)>(
+                    r#"SELECT pid, usename::text, client_addr::text, datname::text, backend_type::text, state::text,
+                              EXTRACT(EPOCH FROM now() - COALESCE(CASE WHEN state = 'active' THEN query_start END, state_change))::bigint,
+                              query, pid = pg_backend_pid()
+                       FROM pg_stat_activity
+                       WHERE backend_type = 'client backend'"#
+                )
+                .fetch_all(pool)
+                .await?;
+                rows.into_iter()
+                    .map(|(id, user, host, database, command, state, time_secs, query, current)| ServerProcess {
+                        id: id as i64,
+                        user,
+                        host,
+                        database,
+                        command,
+                        state,
+                        time_secs,
+                        query: query.filter(|q| !q.is_empty()),
+                        current,
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持查看服务端会话", self.db_type))),
+        };
+        processes.sort_by(|a, b| b.time_secs.cmp(&a.time_secs).then_with(|| a.id.cmp(&b.id)));
+        Ok(processes)
+    }
+
+    // 终止服务端会话或其正在执行的语句，会话不存在时返回 false
+    pub async fn kill_process(&self, id: i64, mode: KillMode) -> Result<bool, DatabaseError> {
+        let Some(sql) = kill_statement(self.db_type, id, mode) else {
+            return Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持终止服务端会话", self.db_type)));
+        };
+        match &self.pool {
+            DatabasePool::MySQL(pool) => {
+                // KILL 不存在的线程会报错，先确认线程存在
+                let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM information_schema.PROCESSLIST WHERE ID = ?")
+                    .bind(id)
+                    .fetch_one(pool)
+                    .await?;
+                if exists == 0 {
+                    return Ok(false);
+                }
+                sqlx::query(&sql).execute(pool).await?;
+                Ok(true)
+            }
+            DatabasePool::PostgreSQL(pool) => {
+                // pid 为 int4，超出范围时 pg_cancel_backend 会报类型错误
+                i32::try_from(id).map_err(|_| DatabaseError::InvalidDefinition(format!("无效的进程ID: {}", id)))?;
+                let signaled = sqlx::query_scalar::<_, bool>(&sql).fetch_one(pool).await?;
+                Ok(signaled)
+            }
+            _ => Err(DatabaseError::UnsupportedDatabaseType(format!("{:?}不支持终止服务端会话", self.db_type))),
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionPoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_processes_unsupported() {
+        let manager = DatabaseManager::connect_with_options("sqlite::memory:", &ConnectionPoolOptions::default()).await.unwrap();
+        assert!(kill_statement(DatabaseType::SQLite, 1, KillMode::Query).is_none());
+        assert!(matches!(manager.list_processes().await, Err(DatabaseError::UnsupportedDatabaseType(_))));
+        assert!(matches!(manager.kill_process(1, KillMode::Connection).await, Err(DatabaseError::UnsupportedDatabaseType(_))));
+    }
+
+    #[test]
+    fn test_kill_mode_defaults_to_query() {
+        assert_eq!(kill_statement(DatabaseType::MySQL, 7, KillMode::default()).as_deref(), Some("KILL QUERY 7"));
+        assert_eq!(kill_statement(DatabaseType::PostgreSQL, 7, KillMode::Connection).as_deref(), Some("SELECT pg_terminate_backend(7)"));
+        assert_eq!(KillMode::default(), KillMode::Query);
+        let mode: KillMode = serde_json::from_str("\"connection\"").unwrap();
+        assert_eq!(mode, KillMode::Connection);
+    }
+}
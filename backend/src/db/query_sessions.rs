@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{DatabaseError, DatabaseManager, DatabaseType};
+use crate::db::LocalStorageManager;
+
+// 会话闲置超过该时长后关闭（释放专用连接）
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+// 同时打开的会话数量上限（每个会话占用一个数据库连接）
+pub const MAX_SESSIONS: usize = 50;
+
+// 查询会话：工作区标签页持有的专用连接，会话变量、临时表、USE 切换的数据库在查询间保留
+struct QuerySession {
+    connection_id: i64,
+    database: Option<String>,
+    manager: DatabaseManager,
+    created_at: i64,
+    last_used: Instant,
+}
+
+static SESSIONS: OnceLock<Mutex<HashMap<String, QuerySession>>> = OnceLock::new();
+
+fn sessions() -> &'static Mutex<HashMap<String, QuerySession>> {
+    SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 会话信息
+#[derive(Debug, Clone, Serialize)]
+pub struct QuerySessionInfo {
+    pub id: String,
+    pub connection_id: i64,
+    pub database: Option<String>,   // 打开会话时切换到的数据库，为空时为连接配置的数据库
+    pub db_type: String,
+    pub created_at: i64,
+    pub idle_secs: u64,              // 距上次使用的秒数
+}
+
+impl QuerySessionInfo {
+    fn new(id: &str, session: &QuerySession) -> Self {
+        Self {
+            id: id.to_string(),
+            connection_id: session.connection_id,
+            database: session.database.clone(),
+            db_type: session.manager.db_type.as_str().to_string(),
+            created_at: session.created_at,
+            idle_secs: session.last_used.elapsed().as_secs(),
+        }
+    }
+}
+
+// 取出的会话连接
+#[derive(Clone)]
+pub struct SessionConnection {
+    pub connection_id: i64,
+    pub database: Option<String>,
+    pub manager: DatabaseManager,
+}
+
+// 打开会话失败的原因
+#[derive(Debug)]
+pub enum SessionError {
+    TooManySessions,
+    Connect(DatabaseError),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::TooManySessions => write!(f, "已打开 {} 个会话，请先关闭不再使用的会话", MAX_SESSIONS),
+            SessionError::Connect(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// 为连接打开会话，建立专用连接
+pub async fn open(
+    connection_id: i64,
+    database: Option<&str>,
+    database_url: &str,
+    init_sql: &[String],
+) -> Result<QuerySessionInfo, SessionError> {
+    reap_idle();
+    if sessions().lock().unwrap().len() >= MAX_SESSIONS {
+        return Err(SessionError::TooManySessions);
+    }
+
+    // 在锁外建立连接，避免阻塞其他会话
+    let manager = DatabaseManager::connect_session(database_url, init_sql).await
+        .map_err(SessionError::Connect)?;
+    let id = Uuid::new_v4().to_string();
+    let session = QuerySession {
+        connection_id,
+        database: database.map(str::to_string),
+        manager,
+        created_at: LocalStorageManager::current_timestamp(),
+        last_used: Instant::now(),
+    };
+    let info = QuerySessionInfo::new(&id, &session);
+    sessions().lock().unwrap().insert(id.clone(), session);
+    log::info!("[Session] 已打开会话: id={}, connection_id={}, database={:?}", id, connection_id, database);
+    Ok(info)
+}
+
+// 按ID取出会话连接并刷新最后使用时间，不存在或已闲置过期时返回None
+pub fn get(id: &str) -> Option<SessionConnection> {
+    reap_idle();
+    let mut sessions = sessions().lock().unwrap();
+    let session = sessions.get_mut(id)?;
+    session.last_used = Instant::now();
+    Some(SessionConnection {
+        connection_id: session.connection_id,
+        database: session.database.clone(),
+        manager: session.manager.clone(),
+    })
+}
+
+// 会话列表（可按连接过滤），按创建时间排序
+pub fn list(connection_id: Option<i64>) -> Vec<QuerySessionInfo> {
+    reap_idle();
+    let sessions = sessions().lock().unwrap();
+    let mut list: Vec<QuerySessionInfo> = sessions.iter()
+        .filter(|(_, session)| connection_id.is_none() || connection_id == Some(session.connection_id))
+        .map(|(id, session)| QuerySessionInfo::new(id, session))
+        .collect();
+    list.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    list
+}
+
+// 关闭会话，返回会话是否存在
+pub fn close(id: &str) -> bool {
+    let removed = sessions().lock().unwrap().remove(id);
+    match removed {
+        Some(session) => {
+            close_manager(session.manager);
+            log::info!("[Session] 已关闭会话: id={}", id);
+            true
+        }
+        None => false,
+    }
+}
+
+// 关闭连接的所有会话（连接被修改或删除时调用）
+pub fn close_connection(connection_id: i64) {
+    let closed = remove_where(|session| session.connection_id == connection_id);
+    if closed > 0 {
+        log::info!("[Session] 已关闭连接的会话: connection_id={}, 数量={}", connection_id, closed);
+    }
+}
+
+// 关闭闲置超时的会话，返回关闭的数量
+pub fn reap_idle() -> usize {
+    let closed = remove_where(|session| session.last_used.elapsed() >= SESSION_IDLE_TIMEOUT);
+    if closed > 0 {
+        log::info!("[Session] 已关闭闲置超时的会话: 数量={}", closed);
+    }
+    closed
+}
+
+fn remove_where(predicate: impl Fn(&QuerySession) -> bool) -> usize {
+    let removed: Vec<QuerySession> = {
+        let mut sessions = sessions().lock().unwrap();
+        let ids: Vec<String> = sessions.iter()
+            .filter(|(_, session)| predicate(session))
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.iter().filter_map(|id| sessions.remove(id)).collect()
+    };
+    let count = removed.len();
+    for session in removed {
+        close_manager(session.manager);
+    }
+    count
+}
+
+// 关闭专用连接（在后台等待进行中的查询结束）
+fn close_manager(manager: DatabaseManager) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    runtime.spawn(async move {
+        match &manager.pool {
+            super::DatabasePool::PostgreSQL(pool) => pool.close().await,
+            super::DatabasePool::MySQL(pool) => pool.close().await,
+            super::DatabasePool::SQLite(pool) => pool.close().await,
+            _ => {}
+        }
+    });
+}
+
+// 会话模式是否支持该数据库类型
+pub fn is_supported(db_type: Option<DatabaseType>) -> bool {
+    matches!(db_type, Some(DatabaseType::MySQL | DatabaseType::PostgreSQL | DatabaseType::SQLite))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::DatabasePool;
+
+    #[tokio::test]
+    async fn test_session_keeps_state_across_queries() {
+        let info = open(-80, None, "sqlite::memory:", &[]).await.unwrap();
+        assert_eq!(info.db_type, "sqlite");
+
+        // 内存数据库的每个连接相互独立，临时表在后续查询中仍可见说明使用的是同一连接
+        let session = get(&info.id).unwrap();
+        let DatabasePool::SQLite(pool) = &session.manager.pool else {
+            panic!("expected sqlite pool");
+        };
+        sqlx::query("CREATE TEMP TABLE t (x INTEGER)").execute(pool).await.unwrap();
+        let session = get(&info.id).unwrap();
+        let DatabasePool::SQLite(pool) = &session.manager.pool else {
+            panic!("expected sqlite pool");
+        };
+        sqlx::query("INSERT INTO t VALUES (1)").execute(pool).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t").fetch_one(pool).await.unwrap();
+        assert_eq!(count, 1);
+
+        assert_eq!(list(Some(-80)).len(), 1);
+        assert!(close(&info.id));
+        assert!(get(&info.id).is_none());
+        assert!(!close(&info.id));
+    }
+
+    #[tokio::test]
+    async fn test_close_connection_sessions() {
+        let first = open(-81, None, "sqlite::memory:", &[]).await.unwrap();
+        let second = open(-81, Some("other"), "sqlite::memory:", &[]).await.unwrap();
+        assert_eq!(list(Some(-81)).len(), 2);
+        close_connection(-81);
+        assert!(list(Some(-81)).is_empty());
+        assert!(get(&first.id).is_none() && get(&second.id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_rejects_unsupported_database() {
+        assert!(matches!(open(-82, None, "redis://localhost", &[]).await, Err(SessionError::Connect(_))));
+        assert!(!is_supported(Some(DatabaseType::MongoDB)));
+        assert!(is_supported(Some(DatabaseType::PostgreSQL)));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeSet, HashMap};
+use redis::aio::ConnectionManager;
+use redis::{RedisError, Value};
+use serde_json::Value as JsonValue;
+
+// 浏览键空间时最多扫描的键数量
+const MAX_SCAN_KEYS: usize = 5000;
+
+// 允许通过查询接口执行的命令
+const ALLOWED_COMMANDS: &[&str] = &[
+    "GET", "SET", "MGET", "HGET", "HGETALL", "HKEYS", "LRANGE", "LLEN", "SMEMBERS", "SCARD",
+    "ZRANGE", "ZCARD", "SCAN", "TTL", "PTTL", "TYPE", "EXISTS", "STRLEN", "DBSIZE", "PING",
+];
+
+// 创建Redis连接（ConnectionManager 断线后自动重连）
+pub async fn connect(url: &str) -> Result<ConnectionManager, RedisError> {
+    let client = redis::Client::open(url)?;
+    ConnectionManager::new(client).await
+}
+
+// 测试连接
+pub async fn ping(conn: &ConnectionManager) -> Result<(), RedisError> {
+    let mut conn = conn.clone();
+    redis::cmd("PING").query_async::<_, String>(&mut conn).await?;
+    Ok(())
+}
+
+// 获取服务器版本（INFO server 中的 redis_version）
+pub async fn server_version(conn: &ConnectionManager) -> Result<Option<String>, RedisError> {
+    let mut conn = conn.clone();
+    let info: String = redis::cmd("INFO").arg("server").query_async(&mut conn).await?;
+    Ok(info.lines()
+        .find_map(|line| line.strip_prefix("redis_version:"))
+        .map(|v| v.trim().to_string()))
+}
+
+// 解析 INFO 命令输出为 键 -> 值（忽略 # 开头的分节标题）
+pub fn parse_info(info: &str) -> HashMap<String, String> {
+    info.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// 获取服务器全部 INFO 信息
+pub async fn server_info(conn: &ConnectionManager) -> Result<HashMap<String, String>, RedisError> {
+    let mut conn = conn.clone();
+    let info: String = redis::cmd("INFO").query_async(&mut conn).await?;
+    Ok(parse_info(&info))
+}
+
+// 将键归纳为模式：取最后一个分隔符之前的前缀，如 user:1001:profile -> user:1001:*
+pub fn key_pattern(key: &str) -> String {
+    match key.rfind(':') {
+        Some(idx) => format!("{}*", &key[..=idx]),
+        None => key.to_string(),
+    }
+}
+
+// 扫描键空间并归纳为模式列表（作为"表"展示）
+pub async fn key_patterns(conn: &ConnectionManager) -> Result<Vec<String>, RedisError> {
+    let mut conn = conn.clone();
+    let mut patterns = BTreeSet::new();
+    let mut cursor: u64 = 0;
+    let mut scanned = 0;
+
+    loop {
+        let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(500)
+            .query_async(&mut conn)
+            .await?;
+        scanned += keys.len();
+        patterns.extend(keys.iter().map(|k| key_pattern(k)));
+        cursor = next;
+        if cursor == 0 || scanned >= MAX_SCAN_KEYS {
+            break;
+        }
+    }
+
+    Ok(patterns.into_iter().collect())
+}
+
+// 将命令文本拆分为参数，支持单/双引号包裹含空格的参数
+pub fn parse_command(input: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+
+    let mut chars = input.trim().trim_end_matches(';').chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("命令中的引号未闭合".to_string());
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    let command = args.first().ok_or("命令不能为空")?.to_uppercase();
+    if !ALLOWED_COMMANDS.contains(&command.as_str()) {
+        return Err(format!("不支持的Redis命令: {}，仅支持 {}", command, ALLOWED_COMMANDS.join("/")));
+    }
+    args[0] = command;
+    Ok(args)
+}
+
+fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Nil => JsonValue::Null,
+        Value::Int(i) => JsonValue::from(*i),
+        Value::Data(bytes) => JsonValue::String(String::from_utf8_lossy(bytes).into_owned()),
+        Value::Status(s) => JsonValue::String(s.clone()),
+        Value::Okay => JsonValue::String("OK".to_string()),
+        Value::Bulk(items) => JsonValue::Array(items.iter().map(value_to_json).collect()),
+    }
+}
+
+// 将命令结果转换为表格（列名, 行）
+pub fn to_table(command: &str, value: &Value) -> (Vec<String>, Vec<Vec<JsonValue>>) {
+    match (command, value) {
+        // 哈希：字段/值成对返回
+        ("HGETALL", Value::Bulk(items)) => (
+            vec!["field".to_string(), "value".to_string()],
+            items.chunks(2)
+                .map(|pair| pair.iter().map(value_to_json).collect())
+                .collect(),
+        ),
+        // SCAN：[下一游标, [键...]]
+        ("SCAN", Value::Bulk(items)) if items.len() == 2 => {
+            let cursor = value_to_json(&items[0]);
+            let keys = match &items[1] {
+                Value::Bulk(keys) => keys.iter().map(|k| vec![value_to_json(k), cursor.clone()]).collect(),
+                _ => Vec::new(),
+            };
+            (vec!["key".to_string(), "next_cursor".to_string()], keys)
+        }
+        (_, Value::Bulk(items)) => (
+            vec!["index".to_string(), "value".to_string()],
+            items.iter().enumerate()
+                .map(|(i, item)| vec![JsonValue::from(i), value_to_json(item)])
+                .collect(),
+        ),
+        (_, other) => (vec!["result".to_string()], vec![vec![value_to_json(other)]]),
+    }
+}
+
+// 执行命令并返回表格结果
+pub async fn execute(conn: &ConnectionManager, args: &[String]) -> Result<(Vec<String>, Vec<Vec<JsonValue>>), RedisError> {
+    let mut conn = conn.clone();
+    let mut cmd = redis::cmd(&args[0]);
+    for arg in &args[1..] {
+        cmd.arg(arg);
+    }
+    let value: Value = cmd.query_async(&mut conn).await?;
+    Ok(to_table(&args[0], &value))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_pattern() {
+        assert_eq!(key_pattern("user:1001:profile"), "user:1001:*");
+        assert_eq!(key_pattern("counter"), "counter");
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let info = parse_info("# Server\r\nredis_version:7.2.4\r\nuptime_in_seconds:3600\r\n\r\n# Clients\r\nconnected_clients:3\r\n");
+        assert_eq!(info.get("redis_version").map(String::as_str), Some("7.2.4"));
+        assert_eq!(info.get("uptime_in_seconds").map(String::as_str), Some("3600"));
+        assert_eq!(info.get("connected_clients").map(String::as_str), Some("3"));
+        assert_eq!(info.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("set greeting 'hello world'").unwrap(), vec!["SET", "greeting", "hello world"]);
+        assert!(parse_command("FLUSHALL").is_err());
+        assert!(parse_command("GET \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_hgetall_table() {
+        let value = Value::Bulk(vec![
+            Value::Data(b"name".to_vec()),
+            Value::Data(b"tom".to_vec()),
+        ]);
+        let (columns, rows) = to_table("HGETALL", &value);
+        assert_eq!(columns, vec!["field", "value"]);
+        assert_eq!(rows, vec![vec![JsonValue::from("name"), JsonValue::from("tom")]]);
+    }
+}
@@ -0,0 +1,178 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use sqlx::mysql::MySqlRow;
+use sqlx::postgres::PgRow;
+use sqlx::sqlite::SqliteRow;
+use sqlx::types::{Decimal, Uuid};
+use sqlx::{Column, Decode, Row, Type, TypeInfo};
+
+// 二进制列最多按base64返回的字节数，超出时只返回长度说明
+pub const MAX_BINARY_DISPLAY_BYTES: usize = 64 * 1024;
+
+// 查询结果行转换为JSON值：按列类型解码，日期时间为ISO-8601，DECIMAL为字符串（保留精度），二进制为base64
+pub trait RowValues {
+    fn json_values(&self) -> Vec<JsonValue>;
+}
+
+// 按指定类型解码，类型不匹配时返回None以便回退
+fn decode<'r, R, T>(row: &'r R, index: usize) -> Option<Option<T>>
+where
+    R: Row,
+    usize: sqlx::ColumnIndex<R>,
+    T: Decode<'r, R::Database> + Type<R::Database>,
+{
+    row.try_get::<Option<T>, _>(index).ok()
+}
+
+fn to_json<T: Serialize>(value: Option<T>) -> JsonValue {
+    value.map(|v| serde_json::to_value(v).unwrap_or(JsonValue::Null)).unwrap_or(JsonValue::Null)
+}
+
+fn map_json<T>(value: Option<T>, f: impl FnOnce(T) -> String) -> JsonValue {
+    value.map(|v| JsonValue::String(f(v))).unwrap_or(JsonValue::Null)
+}
+
+pub fn format_datetime(value: NaiveDateTime) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+}
+
+pub fn format_timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+// 二进制数据转为base64，超过显示上限时返回长度说明
+pub fn binary_to_json(bytes: &[u8]) -> JsonValue {
+    if bytes.len() > MAX_BINARY_DISPLAY_BYTES {
+        JsonValue::String(format!("<binary {} bytes>", bytes.len()))
+    } else {
+        JsonValue::String(STANDARD.encode(bytes))
+    }
+}
+
+// 未识别的类型依次尝试文本和二进制
+fn fallback<R>(row: &R, index: usize) -> JsonValue
+where
+    R: Row,
+    usize: sqlx::ColumnIndex<R>,
+    for<'r> String: Decode<'r, R::Database> + Type<R::Database>,
+    for<'r> Vec<u8>: Decode<'r, R::Database> + Type<R::Database>,
+{
+    if let Some(v) = decode::<_, String>(row, index) {
+        return to_json(v);
+    }
+    match decode::<_, Vec<u8>>(row, index) {
+        Some(Some(bytes)) => binary_to_json(&bytes),
+        _ => JsonValue::Null,
+    }
+}
+
+fn type_name<R: Row>(row: &R, index: usize) -> String {
+    row.columns()[index].type_info().name().to_uppercase()
+}
+
+fn mysql_value(row: &MySqlRow, index: usize) -> Option<JsonValue> {
+    let name = type_name(row, index);
+    Some(match name.as_str() {
+        "BOOLEAN" => to_json(decode::<_, bool>(row, index)?),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => to_json(decode::<_, i64>(row, index)?),
+        n if n.ends_with("UNSIGNED") => to_json(decode::<_, u64>(row, index)?),
+        "FLOAT" => to_json(decode::<_, f32>(row, index)?),
+        "DOUBLE" => to_json(decode::<_, f64>(row, index)?),
+        "DECIMAL" => map_json(decode::<_, Decimal>(row, index)?, |v| v.to_string()),
+        "DATE" => map_json(decode::<_, NaiveDate>(row, index)?, |v| v.to_string()),
+        "TIME" => map_json(decode::<_, NaiveTime>(row, index)?, |v| v.to_string()),
+        "DATETIME" => map_json(decode::<_, NaiveDateTime>(row, index)?, format_datetime),
+        "TIMESTAMP" => map_json(decode::<_, DateTime<Utc>>(row, index)?, format_timestamp),
+        "JSON" => to_json(decode::<_, JsonValue>(row, index)?),
+        _ => return None,
+    })
+}
+
+fn postgres_value(row: &PgRow, index: usize) -> Option<JsonValue> {
+    let name = type_name(row, index);
+    Some(match name.as_str() {
+        "BOOL" => to_json(decode::<_, bool>(row, index)?),
+        "INT2" => to_json(decode::<_, i16>(row, index)?),
+        "INT4" => to_json(decode::<_, i32>(row, index)?),
+        "INT8" => to_json(decode::<_, i64>(row, index)?),
+        "OID" => to_json(decode::<_, sqlx::postgres::types::Oid>(row, index)?.map(|oid| oid.0)),
+        "FLOAT4" => to_json(decode::<_, f32>(row, index)?),
+        "FLOAT8" => to_json(decode::<_, f64>(row, index)?),
+        "NUMERIC" => map_json(decode::<_, Decimal>(row, index)?, |v| v.to_string()),
+        "DATE" => map_json(decode::<_, NaiveDate>(row, index)?, |v| v.to_string()),
+        "TIME" => map_json(decode::<_, NaiveTime>(row, index)?, |v| v.to_string()),
+        "TIMESTAMP" => map_json(decode::<_, NaiveDateTime>(row, index)?, format_datetime),
+        "TIMESTAMPTZ" => map_json(decode::<_, DateTime<Utc>>(row, index)?, format_timestamp),
+        "UUID" => map_json(decode::<_, Uuid>(row, index)?, |v| v.to_string()),
+        "JSON" | "JSONB" => to_json(decode::<_, JsonValue>(row, index)?),
+        "BYTEA" => decode::<_, Vec<u8>>(row, index)?.map(|b| binary_to_json(&b)).unwrap_or(JsonValue::Null),
+        "INT2[]" => to_json(decode::<_, Vec<i16>>(row, index)?),
+        "INT4[]" => to_json(decode::<_, Vec<i32>>(row, index)?),
+        "INT8[]" => to_json(decode::<_, Vec<i64>>(row, index)?),
+        "TEXT[]" | "VARCHAR[]" => to_json(decode::<_, Vec<String>>(row, index)?),
+        _ => return None,
+    })
+}
+
+fn sqlite_value(row: &SqliteRow, index: usize) -> Option<JsonValue> {
+    // SQLite按值的实际存储类型解码，只有声明为BOOLEAN的列需要特殊处理
+    if type_name(row, index) == "BOOLEAN" {
+        if let Some(v) = decode::<_, bool>(row, index) {
+            return Some(to_json(v));
+        }
+    }
+    if let Some(v) = decode::<_, i64>(row, index) {
+        return Some(to_json(v));
+    }
+    decode::<_, f64>(row, index).map(to_json)
+}
+
+impl RowValues for MySqlRow {
+    fn json_values(&self) -> Vec<JsonValue> {
+        (0..self.len()).map(|i| mysql_value(self, i).unwrap_or_else(|| fallback(self, i))).collect()
+    }
+}
+
+impl RowValues for PgRow {
+    fn json_values(&self) -> Vec<JsonValue> {
+        (0..self.len()).map(|i| postgres_value(self, i).unwrap_or_else(|| fallback(self, i))).collect()
+    }
+}
+
+impl RowValues for SqliteRow {
+    fn json_values(&self) -> Vec<JsonValue> {
+        (0..self.len()).map(|i| sqlite_value(self, i).unwrap_or_else(|| fallback(self, i))).collect()
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_helpers() {
+        let datetime = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap().and_hms_milli_opt(8, 30, 0, 250).unwrap();
+        assert_eq!(format_datetime(datetime), "2024-03-01T08:30:00.250");
+        assert_eq!(format_timestamp(Utc.from_utc_datetime(&datetime)), "2024-03-01T08:30:00.250Z");
+        assert_eq!(binary_to_json(&[0xde, 0xad, 0xbe, 0xef]), json!("3q2+7w=="));
+        assert_eq!(binary_to_json(&vec![0u8; MAX_BINARY_DISPLAY_BYTES + 1]), json!(format!("<binary {} bytes>", MAX_BINARY_DISPLAY_BYTES + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_row_values() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE t (id INTEGER, flag BOOLEAN, price REAL, name TEXT, data BLOB, created_at DATETIME)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO t VALUES (1, 1, 9.5, 'a', x'0102', '2024-03-01 08:30:00'), (2, NULL, NULL, NULL, NULL, NULL)")
+            .execute(&pool).await.unwrap();
+
+        let rows = sqlx::query("SELECT * FROM t ORDER BY id").fetch_all(&pool).await.unwrap();
+        assert_eq!(rows[0].json_values(), vec![json!(1), json!(true), json!(9.5), json!("a"), json!("AQI="), json!("2024-03-01 08:30:00")]);
+        assert_eq!(rows[1].json_values(), vec![json!(2), JsonValue::Null, JsonValue::Null, JsonValue::Null, JsonValue::Null, JsonValue::Null]);
+    }
+}
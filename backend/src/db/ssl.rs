@@ -0,0 +1,101 @@
+// 连接级别的 SSL/TLS 配置，转换为 sqlx 连接字符串参数
+
+// 支持的SSL模式（与PostgreSQL sslmode命名一致）
+pub const SSL_MODES: &[&str] = &["disable", "prefer", "require", "verify-ca", "verify-full"];
+
+// 校验SSL模式，空字符串视为未配置
+pub fn normalize_ssl_mode(ssl_mode: Option<&str>) -> Result<Option<String>, String> {
+    match ssl_mode.map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty()) {
+        Some(mode) if SSL_MODES.contains(&mode.as_str()) => Ok(Some(mode)),
+        Some(mode) => Err(format!("不支持的SSL模式: {}，可选值: {}", mode, SSL_MODES.join("/"))),
+        None => Ok(None),
+    }
+}
+
+// 查询参数值的百分号编码（证书路径可能包含空格、&、# 等字符）
+pub(crate) fn encode_param(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' | b'\\' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// 生成SSL相关的连接字符串参数，仅支持 mysql / postgresql
+pub fn ssl_url_params(db_type: &str, ssl_mode: Option<&str>, ssl_ca_path: Option<&str>) -> Result<Vec<String>, String> {
+    let mode = normalize_ssl_mode(ssl_mode)?;
+    let ca_path = ssl_ca_path.map(str::trim).filter(|p| !p.is_empty());
+
+    // 提供了CA证书但未指定模式时按 verify-ca 处理
+    let mode = match (mode, ca_path) {
+        (None, Some(_)) => Some("verify-ca".to_string()),
+        (mode, _) => mode,
+    };
+
+    let mut params = Vec::new();
+    match db_type {
+        "mysql" => {
+            if let Some(mode) = mode {
+                let mysql_mode = match mode.as_str() {
+                    "disable" => "DISABLED",
+                    "prefer" => "PREFERRED",
+                    "require" => "REQUIRED",
+                    "verify-ca" => "VERIFY_CA",
+                    _ => "VERIFY_IDENTITY",
+                };
+                params.push(format!("ssl-mode={}", mysql_mode));
+            }
+            if let Some(path) = ca_path {
+                params.push(format!("ssl-ca={}", encode_param(path)));
+            }
+        }
+        "postgresql" => {
+            if let Some(mode) = mode {
+                params.push(format!("sslmode={}", mode));
+            }
+            if let Some(path) = ca_path {
+                params.push(format!("sslrootcert={}", encode_param(path)));
+            }
+        }
+        _ => {}
+    }
+    Ok(params)
+}
+
+// 将SSL参数追加到连接字符串
+pub fn apply_ssl_params(url: &str, db_type: &str, ssl_mode: Option<&str>, ssl_ca_path: Option<&str>) -> Result<String, String> {
+    let params = ssl_url_params(db_type, ssl_mode, ssl_ca_path)?;
+    if params.is_empty() {
+        return Ok(url.to_string());
+    }
+    let separator = if url.contains('?') { '&' } else { '?' };
+    Ok(format!("{}{}{}", url, separator, params.join("&")))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mysql_params() {
+        let url = apply_ssl_params("mysql://root:@db:3306/app", "mysql", Some("verify-full"), Some("/certs/ca cert.pem")).unwrap();
+        assert_eq!(url, "mysql://root:@db:3306/app?ssl-mode=VERIFY_IDENTITY&ssl-ca=/certs/ca%20cert.pem");
+    }
+
+    #[test]
+    fn test_postgres_params() {
+        let url = apply_ssl_params("postgresql://u:p@db:5432/app", "postgresql", Some("Require"), None).unwrap();
+        assert_eq!(url, "postgresql://u:p@db:5432/app?sslmode=require");
+        let url = apply_ssl_params("postgresql://u:p@db:5432/app", "postgresql", None, Some("/ca.pem")).unwrap();
+        assert_eq!(url, "postgresql://u:p@db:5432/app?sslmode=verify-ca&sslrootcert=/ca.pem");
+    }
+
+    #[test]
+    fn test_unconfigured_and_invalid() {
+        assert_eq!(apply_ssl_params("mysql://db/app", "mysql", Some(" "), None).unwrap(), "mysql://db/app");
+        assert_eq!(apply_ssl_params("sqlite://a.db", "sqlite", Some("require"), None).unwrap(), "sqlite://a.db");
+        assert!(normalize_ssl_mode(Some("always")).is_err());
+    }
+}
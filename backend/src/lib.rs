@@ -3,5 +3,6 @@
 pub mod api;
 pub mod db;
 pub mod models;
+pub mod server;
 pub mod services;
 pub mod utils;
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use sqlx::types::JsonValue;
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 // 数据库表信息模型
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,7 +33,7 @@ pub struct TableSchema {
 }
 
 // 外键信息
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 #[allow(dead_code)]
 pub struct ForeignKeyInfo {
     pub constraint_name: String,
@@ -41,33 +42,64 @@ pub struct ForeignKeyInfo {
     pub referenced_column: String,
 }
 
-// SQL查询请求模型
-#[derive(Debug, Serialize, Deserialize)]
+// SQL查询请求模型（Default 供服务端内部构造请求时使用，只需填写与默认值不同的字段）
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
 pub struct SqlQueryRequest {
     pub sql: String,
     pub connection_id: Option<i64>,  // 指定要查询的连接ID
+    #[schema(value_type = Option<Vec<Object>>)]
     pub parameters: Option<Vec<JsonValue>>,
-    #[serde(default = "default_timeout")]
-    pub timeout_secs: u64,
+    // 查询超时（秒），为空时使用连接设置的默认超时，连接也未设置时为 DEFAULT_QUERY_TIMEOUT_SECS
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
     // 分页参数
     #[serde(default)]
     pub page: Option<u64>,           // 页码（从1开始）
-    #[serde(default = "default_page_size")]
-    pub page_size: u64,              // 每页大小
-}
-
-fn default_timeout() -> u64 {
-    30 // 默认30秒超时
+    #[serde(default)]
+    pub page_size: Option<u64>,      // 每页大小（为空时使用应用设置的默认值）
+    #[serde(default)]
+    pub count_total: bool,           // 分页时是否统计总行数
+    // 查询ID（可选，由客户端指定；未指定时由服务端生成），用于取消正在执行的查询
+    #[serde(default)]
+    pub query_id: Option<String>,
+    // 生产环境连接上执行写操作时需显式确认
+    #[serde(default)]
+    pub confirm_production: bool,
+    // 在同一服务器的其他数据库上执行（不修改连接配置），为空时使用连接配置的数据库
+    #[serde(default)]
+    pub database: Option<String>,
+    // 不添加/截断LIMIT（用于有意的全量导出）
+    #[serde(default)]
+    pub ignore_limit: bool,
+    // SQL由AI生成（记录到审计日志）
+    #[serde(default)]
+    pub ai_generated: bool,
+    // 游标分页（keyset）：按这些列排序并从 cursor 之后继续，代替 OFFSET（需同时指定 page）
+    #[serde(default)]
+    pub keyset_columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub keyset_descending: bool,
+    #[serde(default)]
+    pub cursor: Option<String>,      // 上一页返回的 next_cursor，第一页为空
+    // 不读取查询结果缓存（仍会用本次结果刷新缓存）
+    #[serde(default)]
+    pub bypass_cache: bool,
+    // 统计扫描行数（MySQL读取会话Handler计数，PostgreSQL额外执行EXPLAIN估算），每次查询会多几次数据库往返
+    #[serde(default)]
+    pub collect_stats: bool,
+    // 会话ID（/api/database/sessions 打开），在会话的专用连接上执行，会话状态在查询间保留
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
-fn default_page_size() -> u64 {
-    100 // 默认每页100行
-}
+// 请求和连接都未指定超时时的默认值（秒）
+pub const DEFAULT_QUERY_TIMEOUT_SECS: u64 = 30;
 
 // SQL查询结果模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SqlQueryResult {
     pub columns: Vec<String>,
+    #[schema(value_type = Vec<Vec<Object>>)]
     pub rows: Vec<Vec<JsonValue>>,
     pub row_count: usize,
     pub execution_time_ms: u128,
@@ -79,10 +111,42 @@ pub struct SqlQueryResult {
     // 性能监控信息
     #[serde(skip_serializing_if = "Option::is_none")]
     pub performance: Option<QueryPerformance>,
+    // 本次执行的查询ID，可用于 /api/database/query/:query_id/cancel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_id: Option<String>,
+    // 游标分页时下一页的游标（没有更多数据时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    // 结果来自查询结果缓存
+    #[serde(default)]
+    pub cached: bool,
+    // 数据库报告的影响行数（UPDATE/DELETE/INSERT），无法获取时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows_affected: Option<u64>,
+}
+
+// 多结果集中的单个结果集（不返回行的语句只有影响行数）
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SqlResultSet {
+    pub columns: Vec<String>,
+    #[schema(value_type = Vec<Vec<Object>>)]
+    pub rows: Vec<Vec<JsonValue>>,
+    pub row_count: usize,
+    pub rows_affected: u64,
+    pub truncated: bool,             // 超出单个结果集的行数上限，只返回了前面的行
+}
+
+// 多结果集查询结果（存储过程、SHOW 语句、多条语句）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SqlMultiResult {
+    pub result_sets: Vec<SqlResultSet>,
+    pub execution_time_ms: u128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_id: Option<String>,
 }
 
 // 查询性能监控信息
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct QueryPerformance {
     pub query_time_ms: u128,         // 查询执行时间
     pub fetch_time_ms: u128,         // 数据获取时间
@@ -90,20 +154,25 @@ pub struct QueryPerformance {
     pub rows_read: usize,            // 读取行数
     pub rows_returned: usize,        // 返回行数
     pub memory_used_kb: Option<f64>, // 内存使用（KB）
-    pub is_slow_query: bool,         // 是否慢查询（>1s）
+    pub is_slow_query: bool,         // 是否慢查询（超过应用设置的慢查询阈值）
     pub warnings: Vec<String>,       // 性能警告
 }
 
 impl QueryPerformance {
-    #[allow(dead_code)]
-    pub fn new(query_time_ms: u128, fetch_time_ms: u128, rows_read: usize, rows_returned: usize) -> Self {
+    // slow_threshold_ms 为应用设置中的慢查询阈值（slow_query_threshold_ms）
+    pub fn new(query_time_ms: u128, fetch_time_ms: u128, rows_read: usize, rows_returned: usize, slow_threshold_ms: u64) -> Self {
         let total_time_ms = query_time_ms + fetch_time_ms;
-        let is_slow_query = total_time_ms > 1000;
+        let is_slow_query = total_time_ms > slow_threshold_ms as u128;
         let mut warnings = Vec::new();
         
         // 生成性能警告
         if is_slow_query {
-            warnings.push("查询执行时间超过1秒，建议优化SQL或添加索引".to_string());
+            let threshold = if slow_threshold_ms.is_multiple_of(1000) {
+                format!("{}秒", slow_threshold_ms / 1000)
+            } else {
+                format!("{}ms", slow_threshold_ms)
+            };
+            warnings.push(format!("查询执行时间超过{}，建议优化SQL或添加索引", threshold));
         }
         if rows_read > 10000 {
             warnings.push(format!("扫描了{}行数据，可能需要优化查询条件", rows_read));
@@ -126,11 +195,11 @@ impl QueryPerformance {
 }
 
 // 数据库连接配置模型（扩展版）
-#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow, ToSchema)]
 pub struct DatabaseConnection {
     pub id: Option<i64>,
     pub name: String,
-    pub db_type: String,              // sqlite, mysql, postgresql, mongodb
+    pub db_type: String,              // sqlite, mysql, postgresql, mongodb, redis, duckdb
     pub host: Option<String>,
     pub port: Option<i32>,
     pub database_name: Option<String>,
@@ -142,13 +211,56 @@ pub struct DatabaseConnection {
     #[serde(default)]
     pub is_active: bool,
     pub environment: Option<String>,  // 环境标签: development, testing, staging, production
+    pub ssl_mode: Option<String>,     // SSL模式: disable, prefer, require, verify-ca, verify-full
+    pub ssl_ca_path: Option<String>,  // CA证书路径
+    pub group_id: Option<i64>,        // 所属分组，为空表示未分组
+    #[serde(default)]
+    pub sort_order: i64,              // 分组内显示顺序
+    #[sqlx(flatten)]
+    #[serde(default)]
+    pub pool_options: ConnectionPoolOptions,
+    pub default_schema: Option<String>,  // 默认schema（PostgreSQL search_path）
+    #[sqlx(json)]
+    #[serde(default)]
+    pub options: ConnectionOptions,      // 连接级查询默认值
     pub last_connected_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+// 单元测试使用的连接配置，只设置名称和数据库类型
+#[cfg(test)]
+impl DatabaseConnection {
+    pub(crate) fn for_test(name: &str, db_type: &str) -> Self {
+        DatabaseConnection {
+            id: Some(1),
+            name: name.to_string(),
+            db_type: db_type.to_string(),
+            host: None,
+            port: None,
+            database_name: None,
+            username: None,
+            password: None,
+            file_path: None,
+            connection_string: None,
+            is_active: true,
+            environment: None,
+            ssl_mode: None,
+            ssl_ca_path: None,
+            group_id: None,
+            sort_order: 0,
+            pool_options: Default::default(),
+            default_schema: None,
+            options: Default::default(),
+            last_connected_at: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+}
+
 // 连接配置创建/更新请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ConnectionRequest {
     pub name: String,
     pub db_type: String,
@@ -160,10 +272,69 @@ pub struct ConnectionRequest {
     pub file_path: Option<String>,
     pub connection_string: Option<String>,
     pub environment: Option<String>,  // 环境标签
+    pub ssl_mode: Option<String>,     // SSL模式
+    pub ssl_ca_path: Option<String>,  // CA证书路径
+    #[serde(default)]
+    pub group_id: Option<i64>,        // 创建时所属分组（更新连接时不修改分组，使用移动接口）
+    #[serde(default)]
+    pub pool_options: ConnectionPoolOptions,  // 高级选项：连接池配置
+    #[serde(default)]
+    pub default_schema: Option<String>,       // 默认schema，为空时使用数据库默认值
+    #[serde(default)]
+    pub options: ConnectionOptions,           // 高级选项：查询默认值和会话初始化语句
+}
+
+// 连接池配置（为空时使用默认值）
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash, sqlx::FromRow, ToSchema)]
+pub struct ConnectionPoolOptions {
+    pub pool_max_connections: Option<i64>,    // 最大连接数（默认10）
+    pub pool_min_connections: Option<i64>,    // 保持的最小空闲连接数（默认0）
+    pub pool_idle_timeout_secs: Option<i64>,  // 空闲连接超时关闭（秒）
+    pub pool_max_lifetime_secs: Option<i64>,  // 连接最长存活时间（秒），到期后重建
+}
+
+// 连接级查询默认值（为空的项使用应用设置），以JSON保存在 connections.options 列
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash, ToSchema)]
+#[serde(default)]
+pub struct ConnectionOptions {
+    pub max_rows: Option<u64>,        // 查询默认返回的最大行数（同时作为LIMIT上限）
+    pub timeout_secs: Option<u64>,    // 未指定超时的查询使用的超时时间（秒）
+    pub read_only: bool,              // 只读连接：拒绝所有写操作
+    pub init_sql: Vec<String>,        // 每个新建立的连接上执行的会话初始化语句（如 SET time_zone = '+08:00'）
+}
+
+// 连接分组（文件夹）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow, ToSchema)]
+pub struct ConnectionGroup {
+    pub id: i64,
+    pub name: String,
+    pub sort_order: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 连接分组创建/重命名请求
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConnectionGroupRequest {
+    pub name: String,
+    pub sort_order: Option<i64>,  // 为空时：创建时排在最后，更新时保持不变
+}
+
+// 移动连接到分组请求
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MoveConnectionRequest {
+    pub group_id: Option<i64>,    // 为空表示移到未分组
+    pub sort_order: Option<i64>,  // 为空时排在目标分组最后
+}
+
+// 调整顺序请求（按给定ID顺序重新编号）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReorderRequest {
+    pub ids: Vec<i64>,
 }
 
 // 连接测试请求
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ConnectionTestRequest {
     pub db_type: String,
     pub host: Option<String>,
@@ -174,10 +345,12 @@ pub struct ConnectionTestRequest {
     pub file_path: Option<String>,
     pub connection_string: Option<String>,
     pub environment: Option<String>,  // 环境标签
+    pub ssl_mode: Option<String>,     // SSL模式
+    pub ssl_ca_path: Option<String>,  // CA证书路径
 }
 
 // 连接测试响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ConnectionTestResponse {
     pub success: bool,
     pub message: String,
@@ -186,7 +359,7 @@ pub struct ConnectionTestResponse {
 }
 
 // 查询历史记录模型
-#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow, ToSchema)]
 pub struct QueryHistory {
     pub id: Option<i64>,
     pub connection_id: Option<i64>,
@@ -200,6 +373,175 @@ pub struct QueryHistory {
     pub is_favorite: bool,
 }
 
+// 查询历史检索条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryHistoryFilter {
+    pub q: Option<String>,               // 全文检索关键字（匹配SQL和错误信息）
+    pub connection_id: Option<i64>,
+    pub success: Option<bool>,           // 按执行成功/失败过滤
+    pub start_time: Option<i64>,         // 执行时间范围（Unix时间戳，秒）
+    pub end_time: Option<i64>,
+    pub min_duration_ms: Option<i64>,    // 最小执行耗时
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// 写操作审计日志记录
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    pub id: Option<i64>,
+    pub connection_id: Option<i64>,
+    pub connection_name: String,
+    pub environment: Option<String>,
+    pub statement_kind: String,          // dml / ddl / destructive / admin
+    pub operation: String,               // 首个关键字，如 TRUNCATE、DELETE
+    pub sql_text: String,
+    pub source: String,                  // 执行入口，如 query、script、table_data
+    pub ai_generated: bool,
+    pub affected_rows: Option<i64>,
+    pub is_success: bool,
+    pub error_message: Option<String>,
+    pub executed_by: Option<String>,     // 后端进程的系统用户
+    pub executed_at: i64,
+}
+
+// 审计日志检索条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    pub connection_id: Option<i64>,
+    pub environment: Option<String>,
+    pub statement_kind: Option<String>,
+    pub operation: Option<String>,
+    pub source: Option<String>,
+    pub ai_generated: Option<bool>,
+    pub success: Option<bool>,
+    pub q: Option<String>,               // 匹配SQL文本（如表名）
+    pub start_time: Option<i64>,         // 执行时间范围（Unix时间戳，秒）
+    pub end_time: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// 慢查询记录
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct SlowQueryRecord {
+    pub id: Option<i64>,
+    pub connection_id: Option<i64>,
+    pub fingerprint: String,
+    pub sql_text: String,
+    pub execution_time_ms: i64,
+    pub row_count: Option<i64>,
+    pub explain_plan: Option<String>,
+    pub recorded_at: i64,
+}
+
+// 慢查询排行（按SQL指纹聚合）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct SlowQueryRanking {
+    pub fingerprint: String,
+    pub connection_id: Option<i64>,
+    pub latest_id: i64,               // 最近一次记录的ID（可用于查看执行计划、AI优化）
+    pub sample_sql: String,
+    pub execution_count: i64,
+    pub total_time_ms: i64,
+    pub avg_time_ms: f64,
+    pub max_time_ms: i64,
+    pub last_recorded_at: i64,
+}
+
+// AI调用用量记录
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::FromRow)]
+pub struct AiUsageRecord {
+    pub id: Option<i64>,
+    pub feature: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub estimated_cost: Option<f64>,  // 估算费用（美元）
+    pub streamed: bool,
+    pub created_at: i64,
+}
+
+// 连接健康检查记录
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::FromRow)]
+pub struct ConnectionHealthRecord {
+    pub id: Option<i64>,
+    pub connection_id: i64,
+    pub checked_at: i64,
+    pub available: bool,
+    pub latency_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
+// 表结构语义检索向量（column_name 为None时为表级文档）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaEmbedding {
+    pub table_name: String,
+    pub column_name: Option<String>,
+    pub content: String,
+    pub content_hash: String,
+    pub model: String,
+    pub vector: Vec<f32>,
+}
+
+// 本地保存的表/列注释（column_name 为空字符串时为表注释）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::FromRow)]
+pub struct ObjectComment {
+    pub id: Option<i64>,
+    pub connection_id: i64,
+    pub table_name: String,
+    pub column_name: String,
+    pub comment: String,
+    pub updated_at: i64,
+}
+
+// 数据字典条目（column_name 为空字符串时为表级条目）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct DictionaryEntry {
+    pub id: Option<i64>,
+    pub connection_id: i64,
+    pub table_name: String,
+    pub column_name: String,
+    pub description: String,
+    pub synonyms: sqlx::types::Json<Vec<String>>,
+    pub enum_values: sqlx::types::Json<Vec<EnumValueMeaning>>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 枚举值及其业务含义（如 1 -> 待支付）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EnumValueMeaning {
+    pub value: String,
+    pub meaning: String,
+}
+
+// 创建/更新数据字典条目请求（不指定 column_name 时为表级条目）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DictionaryEntryRequest {
+    pub connection_id: i64,
+    pub table_name: String,
+    #[serde(default)]
+    pub column_name: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+    #[serde(default)]
+    pub enum_values: Vec<EnumValueMeaning>,
+}
+
+// 连接级提示词模板绑定（template_type 为 sql_generation / sql_explain / sql_optimize）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct ConnectionTemplateBinding {
+    pub connection_id: i64,
+    pub template_type: String,
+    pub template_id: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
 // SQL收藏记录模型
 #[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
 #[allow(dead_code)]
@@ -214,6 +556,76 @@ pub struct SqlFavorite {
     pub updated_at: i64,
     pub usage_count: i64,
     pub last_used_at: Option<i64>,
+    pub parameters: Option<sqlx::types::Json<Vec<FavoriteParameter>>>,  // 命名参数声明（JSON）
+}
+
+// 收藏SQL的命名参数声明，SQL中以 :name 引用
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FavoriteParameter {
+    pub name: String,
+    #[serde(rename = "type", default = "default_parameter_type")]
+    pub param_type: String,  // string, integer, number, boolean, date
+    #[serde(default)]
+    pub default: Option<JsonValue>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_parameter_type() -> String {
+    "string".to_string()
+}
+
+// AI对话会话模型
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct ChatConversation {
+    pub id: Option<i64>,
+    pub connection_id: Option<i64>,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// AI对话消息记录模型
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct ChatMessageRecord {
+    pub id: Option<i64>,
+    pub conversation_id: i64,
+    pub role: String,
+    pub content: String,
+    pub created_at: i64,
+}
+
+// 编辑器会话模型（打开的标签页及激活的标签页）
+#[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
+pub struct EditorSession {
+    pub id: Option<i64>,
+    pub name: String,
+    pub tabs: sqlx::types::Json<Vec<EditorTab>>,
+    pub active_tab: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 编辑器标签页（光标位置行号、列号从1开始）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EditorTab {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub sql: String,
+    #[serde(default)]
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default = "default_cursor")]
+    pub cursor_line: u32,
+    #[serde(default = "default_cursor")]
+    pub cursor_column: u32,
+}
+
+fn default_cursor() -> u32 {
+    1
 }
 
 // 数据库连接配置模型（遗留，保持向后兼容）
@@ -228,29 +640,46 @@ pub struct DatabaseConnectionConfig {
 }
 
 // SQL生成请求模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlGenerateRequest {
     pub natural_language: String,
     pub database_schema: Option<String>,
     pub database_type: Option<String>,
+    // 校验生成的SQL时在连接上执行 EXPLAIN（不会执行语句本身）
+    #[serde(default)]
+    pub explain: bool,
+    // 在表结构中附带低基数列的示例值（如状态枚举），会读取少量数据发送给AI，需显式开启
+    #[serde(default)]
+    pub include_sample_values: bool,
+    // 指定本次使用的提示词模板，未指定时使用连接绑定的模板或全局默认模板
+    #[serde(default)]
+    pub template_id: Option<String>,
 }
 
 // SQL生成响应模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlGenerateResponse {
     pub sql: String,
     pub explanation: Option<String>,
+    // 语法、表和列的校验结果（MongoDB / Redis 不校验）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<crate::services::sql_validation::SqlValidation>,
 }
 
 // SQL优化请求模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlOptimizeRequest {
     pub sql: String,
     pub database_type: Option<String>,
+    // 用于查找连接绑定的模板
+    #[serde(default)]
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub template_id: Option<String>,
 }
 
 // SQL优化响应模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlOptimizeResponse {
     pub optimized_sql: String,
     pub optimization_tips: String,
@@ -258,22 +687,28 @@ pub struct SqlOptimizeResponse {
 }
 
 // SQL转自然语言请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlToNaturalLanguageRequest {
     pub sql: String,
     pub database_type: Option<String>,
+    // 指定时将描述保存为该SQL收藏的说明
+    #[serde(default)]
+    pub favorite_id: Option<i64>,
 }
 
 // SQL转自然语言响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlToNaturalLanguageResponse {
     pub success: bool,
     pub natural_language: Option<String>,
     pub error: Option<String>,
+    // 描述已保存到的收藏ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_favorite_id: Option<i64>,
 }
 
 // SQL智能补全请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlCompletionRequest {
     pub partial_sql: String,
     pub database_schema: Option<String>,
@@ -281,52 +716,104 @@ pub struct SqlCompletionRequest {
 }
 
 // SQL智能补全响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlCompletionResponse {
     pub success: bool,
     pub suggestions: Option<Vec<String>>,
     pub error: Option<String>,
 }
 
+// 基于Schema的SQL自动补全请求
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SqlCompleteRequest {
+    pub sql: String,
+    pub cursor_position: Option<usize>,  // 光标位置（字符偏移），默认在末尾
+    pub connection_id: Option<i64>,
+    #[serde(default = "default_use_ai")]
+    pub use_ai: bool,                    // 是否附加AI语句补全
+    #[serde(default = "default_max_suggestions")]
+    pub max_suggestions: usize,
+}
+
+fn default_use_ai() -> bool {
+    true
+}
+
+fn default_max_suggestions() -> usize {
+    20
+}
+
+// 补全建议项
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CompletionSuggestion {
+    pub label: String,
+    pub kind: String,                    // table / column / keyword / statement
+    pub detail: Option<String>,          // 列类型、所属表等
+    pub insert_text: String,
+    pub score: f64,
+    pub source: String,                  // local / ai
+}
+
+// 基于Schema的SQL自动补全响应
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SqlCompleteResponse {
+    pub success: bool,
+    pub suggestions: Vec<CompletionSuggestion>,
+    pub error: Option<String>,
+}
+
 // 对话式AI分析请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChatAnalysisRequest {
     pub query: String,
     pub conversation_history: Option<Vec<ChatMessage>>,
     pub database_schema: Option<String>,
     pub database_type: Option<String>,
+    // 会话ID（为空时创建新会话，历史消息从本地存储加载）
+    #[serde(default)]
+    pub conversation_id: Option<i64>,
+    // 连接ID（未提供database_schema时用于读取表结构）
+    #[serde(default)]
+    pub connection_id: Option<i64>,
 }
 
 // 对话消息
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ChatMessage {
     pub role: String,  // "user" | "assistant" | "system"
     pub content: String,
 }
 
 // 对话式AI分析响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChatAnalysisResponse {
     pub success: bool,
     pub response: Option<String>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub conversation_id: Option<i64>,
 }
 
 // SQL解释请求模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlExplainRequest {
     pub sql: String,
+    // 用于查找连接绑定的模板
+    #[serde(default)]
+    pub connection_id: Option<i64>,
+    #[serde(default)]
+    pub template_id: Option<String>,
 }
 
 // SQL解释响应模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SqlExplainResponse {
     pub explanation: String,
     pub execution_plan: Option<String>,
 }
 
 // 错误响应模型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
@@ -334,9 +821,10 @@ pub struct ErrorResponse {
 }
 
 // 模板类型枚举
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
 pub enum TemplateType {
     #[serde(rename = "sql_generation")]
+    #[default]
     Generation,
     #[serde(rename = "sql_explain")]
     Explain,
@@ -354,6 +842,13 @@ impl TemplateType {
     }
 }
 
+// 模板列表查询参数
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateListQuery {
+    #[serde(default)]
+    pub template_type: Option<TemplateType>,
+}
+
 // 提示词模板请求
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TemplateRequest {
@@ -391,6 +886,8 @@ pub struct UpdateTemplateRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub content: Option<String>,
+    #[serde(default)]
+    pub template_type: Option<TemplateType>,
     pub variables: Option<Vec<String>>,
     pub default_variables: Option<HashMap<String, String>>,
 }
@@ -401,14 +898,32 @@ pub struct SetDefaultTemplateRequest {
     pub template_id: String,
 }
 
-// 批量SQL执行请求
+// 模板预览请求
 #[derive(Debug, Serialize, Deserialize)]
+pub struct TemplatePreviewRequest {
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+// 模板预览响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplatePreviewResponse {
+    pub template_id: String,
+    pub prompt: String,
+    pub variables: Vec<String>,           // 模板中引用的变量
+    pub missing_variables: Vec<String>,
+    pub unused_variables: Vec<String>,
+    pub valid: bool,                      // 没有缺少的变量，可以正常用于AI调用
+}
+
+// 批量SQL执行请求
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchSqlRequest {
     pub statements: Vec<String>,
 }
 
 // 单条SQL执行结果
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StatementResult {
     pub sql: String,
     pub result: Option<SqlQueryResult>,
@@ -418,7 +933,7 @@ pub struct StatementResult {
 }
 
 // 批量SQL执行结果
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchSqlResult {
     pub statements: Vec<StatementResult>,
     pub total_execution_time_ms: u128,
@@ -427,14 +942,17 @@ pub struct BatchSqlResult {
 }
 
 // 执行计划请求
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExecutionPlanRequest {
     pub sql: String,
     pub connection_id: Option<i64>,  // 指定要查询的连接ID
+    // 实际执行查询获取真实行数和耗时（EXPLAIN ANALYZE，仅允许SELECT）
+    #[serde(default)]
+    pub analyze: bool,
 }
 
 // 执行计划节点
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExecutionPlanNode {
     pub id: i32,
     pub parent: Option<i32>,
@@ -447,10 +965,21 @@ pub struct ExecutionPlanNode {
     pub width: Option<i32>,
     pub filter: Option<String>,
     pub join_type: Option<String>,
+    // EXPLAIN ANALYZE 的实际执行统计
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<PlanNodeActual>,
+}
+
+// 执行计划节点的实际执行统计
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PlanNodeActual {
+    pub rows: Option<f64>,      // 每次循环的平均返回行数
+    pub time_ms: Option<f64>,   // 每次循环的总耗时
+    pub loops: Option<i64>,
 }
 
 // 执行计划响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExecutionPlanResponse {
     pub plan: Vec<ExecutionPlanNode>,
     pub query_plan: Option<String>,
@@ -469,7 +998,7 @@ pub struct ActivateConnectionResponse {
 }
 
 // 表列信息（前端使用）
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TableColumn {
     pub name: String,
     #[serde(rename = "dataType")]
@@ -490,7 +1019,7 @@ pub struct TableColumn {
 }
 
 // 表索引信息（前端使用）
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TableIndex {
     pub name: String,
     #[serde(rename = "type")]
@@ -503,7 +1032,7 @@ pub struct TableIndex {
 }
 
 // 数据库信息
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DatabaseInfo {
     pub database_type: String,
     pub server_version: Option<String>,
@@ -0,0 +1,260 @@
+// 服务启动入口：独立运行（main.rs）和嵌入桌面应用进程内运行共用
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use axum::{routing::get, Extension, Router};
+use log::*;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tower_http::cors::{Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+
+use crate::api;
+use crate::db::LocalStorageManager;
+use crate::services::ai::AiService;
+use crate::services::{self, app_settings};
+use crate::services::templates::TemplateManager;
+use crate::utils;
+
+// 优雅停止时等待进行中请求（如WebSocket长连接）的最长时间
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type ServerError = Box<dyn std::error::Error + Send + Sync>;
+
+// 服务配置
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub host: IpAddr,
+    pub port: u16,                      // 0 表示由系统分配空闲端口
+    pub local_storage_path: PathBuf,    // 本地存储（连接配置、查询历史等）的SQLite文件
+    pub auth_token: Option<String>,     // 访问令牌，为空时不启用认证
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 8080,
+            local_storage_path: PathBuf::from("./data/smart_sql.db"),
+            auth_token: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    // 从环境变量读取配置（HOST、PORT、LOCAL_STORAGE_PATH、SMART_SQL_AUTH_TOKEN）
+    pub fn from_env() -> Result<Self, ServerError> {
+        let mut config = Self::default();
+        if let Ok(host) = std::env::var("HOST") {
+            config.host = host.parse()?;
+        }
+        if let Ok(port) = std::env::var("PORT") {
+            config.port = port.parse()?;
+        }
+        if let Ok(path) = std::env::var("LOCAL_STORAGE_PATH") {
+            config.local_storage_path = PathBuf::from(path);
+        }
+        config.auth_token = std::env::var(api::auth::AUTH_TOKEN_ENV).ok();
+        Ok(config)
+    }
+}
+
+// 运行中的服务句柄
+pub struct ServerHandle {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<std::io::Result<()>>,
+    background_tasks: Vec<JoinHandle<()>>,   // 健康检测、闲置会话清理等后台任务
+}
+
+impl ServerHandle {
+    // 实际监听的地址（端口为 0 时可由此获取分配的端口）
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    // 停止接受新连接，等待进行中的请求结束（最多 SHUTDOWN_TIMEOUT，超时后强制停止）
+    pub async fn shutdown(mut self) -> Result<(), ServerError> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let result = tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut self.task).await;
+        self.stop_background_tasks();
+        match result {
+            Ok(result) => result??,
+            Err(_) => {
+                warn!("等待请求结束超时，强制停止服务: http://{}", self.addr);
+                self.task.abort();
+            }
+        }
+        info!("服务已停止: http://{}", self.addr);
+        Ok(())
+    }
+
+    // 等待服务结束
+    pub async fn wait(mut self) -> Result<(), ServerError> {
+        let result = (&mut self.task).await;
+        self.stop_background_tasks();
+        result??;
+        info!("服务已停止: http://{}", self.addr);
+        Ok(())
+    }
+
+    fn stop_background_tasks(&mut self) {
+        for task in self.background_tasks.drain(..) {
+            task.abort();
+        }
+    }
+}
+
+// 初始化本地存储和各项服务并开始监听，绑定端口后立即返回
+pub async fn run_server(config: ServerConfig) -> Result<ServerHandle, ServerError> {
+    // 记录启动时间（详细健康检查中的运行时长）
+    api::routes::server_started_at();
+    // 安装指标记录器（/metrics）
+    services::metrics::install();
+
+    // 确保数据目录存在
+    if let Some(parent) = config.local_storage_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let local_storage_path = config.local_storage_path.to_string_lossy();
+    let local_storage = LocalStorageManager::new(&local_storage_path).await?;
+    info!("本地存储初始化成功: {}", local_storage_path);
+
+    // 应用设置生效（日志级别、清理过期查询历史）
+    let settings = app_settings::load_settings(&local_storage).await;
+    app_settings::apply(&local_storage, &settings).await;
+
+    // 注意：DatabaseManager 将在用户选择连接时动态创建，不在启动时初始化
+
+    // 后台定期检测激活连接的可用性和延迟
+    let mut background_tasks: Vec<JoinHandle<()>> = api::connection_health::spawn_health_monitor(local_storage.clone())
+        .into_iter()
+        .collect();
+    // 后台定期关闭闲置超时的查询会话
+    background_tasks.push(api::query_sessions::spawn_session_reaper());
+
+    // 初始化AI服务（即使API密钥未配置也初始化，允许用户后续配置）
+    let ai_service = match AiService::new(&local_storage).await {
+        Ok(service) => {
+            info!("AI服务初始化成功");
+            Some(service)
+        },
+        Err(e) => {
+            warn!("AI服务初始化失败: {}", e);
+            info!("AI服务将在用户配置API密钥后可用");
+            // 即使初始化失败，也创建一个服务实例，让它在调用时返回错误
+            // 这样用户可以先配置API密钥，然后再使用AI功能
+            Some(AiService::new_without_validation(&local_storage))
+        }
+    };
+
+    // 初始化模板管理器
+    let template_manager = TemplateManager::new();
+    info!("模板管理器初始化成功");
+
+    // 访问令牌认证（设置访问令牌时启用）
+    let auth_config = api::auth::AuthConfig::new(config.auth_token.clone());
+    if auth_config.enabled() {
+        info!("API访问令牌认证已启用");
+    } else {
+        warn!("未设置 {}，API未启用认证", api::auth::AUTH_TOKEN_ENV);
+    }
+
+    let app = create_app(auth_config, local_storage, ai_service, template_manager);
+
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((config.host, config.port))).await?;
+    let addr = listener.local_addr()?;
+    info!("服务器启动在 http://{}", addr);
+
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service())
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await
+    });
+
+    Ok(ServerHandle {
+        addr,
+        shutdown: Some(shutdown),
+        task,
+        background_tasks,
+    })
+}
+
+// 组装路由和中间件
+fn create_app(
+    auth_config: api::auth::AuthConfig,
+    local_storage: LocalStorageManager,
+    ai_service: Option<AiService>,
+    template_manager: TemplateManager,
+) -> Router {
+    // CORS 配置
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers(Any);
+
+    Router::new()
+        // 按路由模板统计请求数和耗时
+        .nest("/api", api::routes::create_routes().route_layer(axum::middleware::from_fn(api::metrics::track_requests)))
+        // Prometheus 指标
+        .route("/metrics", get(api::metrics::metrics_handler))
+        // OpenAPI文档（/api/openapi.json）和 Swagger UI（/api/docs）
+        .merge(api::openapi::swagger_routes())
+        // 认证在CORS之内，401响应同样带有CORS头
+        .layer(axum::middleware::from_fn_with_state(auth_config, api::auth::require_token))
+        .layer(Extension(local_storage))
+        .layer(Extension(ai_service))
+        .layer(Extension(template_manager))
+        // 请求日志span（带请求ID），请求ID由外层生成并回写到响应头
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(utils::logging::make_request_span)
+                .on_response(DefaultOnResponse::new().level(tracing::Level::INFO))
+        )
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .layer(cors)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_run_server_and_shutdown() {
+        let dir = std::env::temp_dir().join(format!("smart-sql-server-{}", uuid::Uuid::new_v4()));
+        let config = ServerConfig {
+            port: 0,
+            local_storage_path: dir.join("smart_sql.db"),
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let handle = run_server(config).await.unwrap();
+        assert_ne!(handle.port(), 0);
+
+        let mut stream = tokio::net::TcpStream::connect(handle.local_addr()).await.unwrap();
+        stream.write_all(b"GET /api/health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+
+        handle.shutdown().await.unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
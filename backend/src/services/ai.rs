@@ -4,53 +4,66 @@ use reqwest::{Client, Error as ReqwestError};
 
 // 引入提示词模板系统
 use crate::services::templates::{TemplateManager, PromptTemplate};
+use crate::services::llm::{ChatRequest, LlmConfig, ProviderKind, TokenUsage};
+use crate::services::ai_usage;
+use crate::services::metrics;
 use crate::db::LocalStorageManager;
 
-// OpenAI API 请求结构
-#[derive(Debug, Serialize)]
-struct OpenAiChatRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-    temperature: f32,
-    max_tokens: u32,
-}
+// 流式解析结果沿用此前的导出路径
+pub use crate::services::llm::{parse_openai_stream_line as parse_stream_line, StreamChunk};
 
-// 聊天消息结构
-#[derive(Debug, Serialize, Deserialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
-}
+// 测试AI配置时的请求超时
+const CONFIG_TEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
 
-// OpenAI API 响应结构
-#[derive(Debug, Deserialize)]
-struct OpenAiChatResponse {
-    #[allow(dead_code)]
-    id: String,
-    #[allow(dead_code)]
-    object: String,
-    #[allow(dead_code)]
-    created: u64,
-    #[allow(dead_code)]
-    model: String,
-    choices: Vec<Choice>,
-    usage: Usage,
-}
+// 流式输出时接收增量文本的通道
+pub type StreamSender = tokio::sync::mpsc::UnboundedSender<String>;
 
-#[derive(Debug, Deserialize)]
-struct Choice {
-    #[allow(dead_code)]
-    index: u32,
-    message: ChatMessage,
-    #[allow(dead_code)]
-    finish_reason: String,
+// AI功能类型（与提示词模板类型一致），可为每种功能单独配置模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiFeature {
+    SqlGeneration,
+    SqlExplain,
+    SqlOptimize,
+    SqlCompletion,
+    SqlToNaturalLanguage,
+    Chat,
 }
 
-#[derive(Debug, Deserialize)]
-struct Usage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
+impl AiFeature {
+    pub const ALL: [AiFeature; 6] = [
+        AiFeature::SqlGeneration,
+        AiFeature::SqlExplain,
+        AiFeature::SqlOptimize,
+        AiFeature::SqlCompletion,
+        AiFeature::SqlToNaturalLanguage,
+        AiFeature::Chat,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AiFeature::SqlGeneration => "sql_generation",
+            AiFeature::SqlExplain => "sql_explain",
+            AiFeature::SqlOptimize => "sql_optimize",
+            AiFeature::SqlCompletion => "sql_completion",
+            AiFeature::SqlToNaturalLanguage => "sql_to_natural_language",
+            AiFeature::Chat => "chat",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == value.trim())
+    }
+
+    // 功能专用模型的设置键，如 ai_model_sql_generation
+    pub fn model_setting_key(&self) -> String {
+        format!("ai_model_{}", self.as_str())
+    }
+    
+    // 用量统计中的功能名称，未指定功能的直接调用记为 general
+    pub fn usage_label(feature: Option<AiFeature>) -> &'static str {
+        feature.map(|f| f.as_str()).unwrap_or("general")
+    }
 }
 
 // AI服务错误类型
@@ -79,8 +92,8 @@ pub struct AiService {
 impl AiService {
     // 创建新的AI服务实例
     pub async fn new(local_storage: &LocalStorageManager) -> Result<Self, AiServiceError> {
-        // 只需要验证API密钥是否存在，不需要保存具体值
-        Self::get_setting(local_storage, "ai_api_key").await?;
+        // 只需要验证API密钥是否存在（本地Ollama无需密钥），不需要保存具体值
+        Self::load_config(local_storage, None).await?;
         
         Ok(Self::new_without_validation(local_storage))
     }
@@ -95,6 +108,11 @@ impl AiService {
         }
     }
     
+    // AI是否已配置（已配置API密钥，或使用无需密钥的本地服务商）
+    pub async fn is_configured(local_storage: &LocalStorageManager) -> bool {
+        Self::load_config(local_storage, None).await.is_ok()
+    }
+    
     // 从本地存储获取设置
     async fn get_setting(local_storage: &LocalStorageManager, key: &str) -> Result<String, AiServiceError> {
         match local_storage.get_app_setting(key).await {
@@ -104,23 +122,84 @@ impl AiService {
         }
     }
     
-    // 获取最新的AI配置
-    async fn get_latest_config(&self) -> Result<(String, String, String), AiServiceError> {
-        let api_key = Self::get_setting(&self.local_storage, "ai_api_key").await?;
+    // 获取可选设置，未配置或为空时返回None
+    async fn get_optional_setting(local_storage: &LocalStorageManager, key: &str) -> Option<String> {
+        local_storage.get_app_setting(key).await
+            .ok()
+            .flatten()
+            .filter(|value| !value.trim().is_empty())
+    }
+    
+    // HTTP客户端（供嵌入向量等其他AI接口复用）
+    pub(crate) fn http_client(&self) -> &Client {
+        &self.client
+    }
+    
+    async fn load_config(local_storage: &LocalStorageManager, feature: Option<AiFeature>) -> Result<LlmConfig, AiServiceError> {
+        let provider = Self::get_optional_setting(local_storage, "ai_provider").await
+            .and_then(|p| ProviderKind::parse(&p))
+            .unwrap_or(ProviderKind::OpenAi);
+        
+        let api_key = if provider.requires_api_key() {
+            Self::get_setting(local_storage, "ai_api_key").await?
+        } else {
+            Self::get_optional_setting(local_storage, "ai_api_key").await.unwrap_or_default()
+        };
+        
+        let base_url = Self::get_optional_setting(local_storage, "ai_api_base_url").await
+            .unwrap_or_else(|| provider.default_base_url().to_string());
         
-        let api_base_url = match self.local_storage.get_app_setting("ai_api_base_url").await {
-            Ok(Some(url)) => url,
-            Ok(None) => "https://api.openai.com/v1".to_string(),
-            Err(_) => "https://api.openai.com/v1".to_string(),
+        // 优先使用功能专用模型，未配置时回退到全局模型
+        let feature_model = match feature {
+            Some(feature) => Self::get_optional_setting(local_storage, &feature.model_setting_key()).await,
+            None => None,
         };
+        let model = match feature_model {
+            Some(model) => model,
+            None => Self::get_optional_setting(local_storage, "ai_model").await
+                .unwrap_or_else(|| provider.default_model().to_string()),
+        };
+        
+        let api_version = Self::get_optional_setting(local_storage, "ai_api_version").await;
         
-        let model = match self.local_storage.get_app_setting("ai_model").await {
-            Ok(Some(m)) => m,
-            Ok(None) => "gpt-4o-mini".to_string(),
-            Err(_) => "gpt-4o-mini".to_string(),
+        Ok(LlmConfig { provider, api_key, base_url, model, api_version })
+    }
+    
+    // 获取最新的AI配置（服务商、API密钥、API地址、模型），指定功能时使用该功能的模型
+    pub(crate) async fn get_latest_config(&self, feature: Option<AiFeature>) -> Result<LlmConfig, AiServiceError> {
+        Self::load_config(&self.local_storage, feature).await
+    }
+    
+    // 使用指定配置发送一次最小的补全请求，用于保存前验证配置，返回模型回复
+    pub async fn test_config(config: &LlmConfig) -> Result<String, AiServiceError> {
+        let client = Client::builder()
+            .timeout(CONFIG_TEST_TIMEOUT)
+            .build()?;
+        let messages = vec![("user".to_string(), "Reply with OK.".to_string())];
+        let request = ChatRequest {
+            messages: &messages,
+            temperature: 0.0,
+            max_tokens: 16,
+            stream: false,
         };
+        let provider = config.provider.provider();
         
-        Ok((api_key, api_base_url, model))
+        let response = provider.build_chat_request(&client, config, &request)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(AiServiceError::ApiError(format!("HTTP {}: {}", status, config.redact(&body))));
+        }
+        Ok(provider.parse_chat_response(&body)?.content.trim().to_string())
+    }
+    
+    // 使用指定的提示词模板替代其类型的默认模板，返回新的服务实例
+    pub fn with_prompt_template(&self, template: PromptTemplate) -> Self {
+        let mut service = self.clone();
+        service.template_manager.use_template(template);
+        service
     }
     
     // 添加自定义模板
@@ -136,38 +215,54 @@ impl AiService {
     }
 
     
-    // 发送聊天请求到OpenAI API
+    // 发送聊天请求到配置的AI服务商
     pub async fn chat_completion(
         &self,
         messages: Vec<(String, String)>, // (role, content) 对
         temperature: Option<f32>,
         max_tokens: Option<u32>,
+    ) -> Result<String, AiServiceError> {
+        self.send_chat(None, messages, temperature, max_tokens).await
+    }
+    
+    // 发送请求并记录调用耗时指标
+    async fn send_chat(
+        &self,
+        feature: Option<AiFeature>,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+    ) -> Result<String, AiServiceError> {
+        let start_time = std::time::Instant::now();
+        let result = self.send_chat_once(feature, messages, temperature, max_tokens).await;
+        metrics::record_ai_call(AiFeature::usage_label(feature), result.is_ok(), start_time.elapsed());
+        result
+    }
+    
+    async fn send_chat_once(
+        &self,
+        feature: Option<AiFeature>,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
     ) -> Result<String, AiServiceError> {
         // 获取最新的AI配置
-        let (api_key, api_base_url, model) = self.get_latest_config().await?;
-        
-        // 构建消息列表
-        let chat_messages: Vec<ChatMessage> = messages.iter()
-            .map(|(role, content)| ChatMessage {
-                role: role.clone(),
-                content: content.clone(),
-            })
-            .collect();
-        
-        // 构建请求体
-        let request = OpenAiChatRequest {
-            model: model.clone(),
-            messages: chat_messages,
+        let config = self.get_latest_config(feature).await?;
+        let provider = config.provider.provider();
+        
+        let request = ChatRequest {
+            messages: &messages,
             temperature: temperature.unwrap_or(0.7),
             max_tokens: max_tokens.unwrap_or(1000),
+            stream: false,
         };
         
         // 记录请求信息
-        log::info!("[AI-Request] 调用OpenAI API - URL: {}/chat/completions", api_base_url);
+        log::info!("[AI-Request] 调用AI服务 - Provider: {}, URL: {}", config.provider.as_str(), config.base_url);
         log::info!("[AI-Request] 请求参数 - Model: {}, Temperature: {}, MaxTokens: {}", 
-            model, 
-            temperature.unwrap_or(0.7), 
-            max_tokens.unwrap_or(1000)
+            config.model, 
+            request.temperature, 
+            request.max_tokens
         );
         log::debug!("[AI-Request] 请求消息数量: {}", messages.len());
         for (i, (role, content)) in messages.iter().enumerate() {
@@ -184,20 +279,10 @@ impl AiService {
             log::debug!("[AI-Request] 消息[{}] 角色: {} | 内容预览: {}", i, role, preview);
         }
         
-        // 记录完整请求体（JSON格式）
-        if let Ok(request_json) = serde_json::to_string_pretty(&request) {
-            log::trace!("[AI-Request] 完整请求体JSON:\n{}", request_json);
-        }
-        
         // 发送请求
-        let url = format!("{}/chat/completions", api_base_url);
         let start_time = std::time::Instant::now();
         
-        let response = self.client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
+        let response = provider.build_chat_request(&self.client, &config, &request)
             .send()
             .await?;
         
@@ -209,7 +294,7 @@ impl AiService {
         log::info!("[AI-Response] HTTP状态码: {}", status);
         
         if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "未知错误".to_string());
+            let error_text = config.redact(&response.text().await.unwrap_or_else(|_| "未知错误".to_string()));
             log::error!("[AI-Response] API返回错误 - 状态码: {}", status);
             log::error!("[AI-Response] 错误详情: {}", error_text);
             return Err(AiServiceError::ApiError(error_text));
@@ -218,43 +303,146 @@ impl AiService {
         // 解析响应
         let response_text = response.text().await?;
         log::debug!("[AI-Response] 原始响应长度: {} 字节", response_text.len());
-        log::trace!("[AI-Response] 完整响应体: {}", 
-            if response_text.len() > 1000 {
-                format!("{}... (总长度: {})", &response_text[..1000], response_text.len())
-            } else {
-                response_text.clone()
-            }
-        );
         
-        let response_data: OpenAiChatResponse = serde_json::from_str(&response_text)
+        let completion = provider.parse_chat_response(&response_text)
             .map_err(|e| {
-                log::error!("[AI-Response] JSON解析失败: {}", e);
+                log::error!("[AI-Response] 响应解析失败: {}", e);
                 log::error!("[AI-Response] 原始响应: {}", response_text);
-                AiServiceError::ParseError(format!("JSON解析失败: {}", e))
+                e
             })?;
         
-        log::info!("[AI-Response] Token使用统计 - prompt: {}, completion: {}, total: {}", 
-            response_data.usage.prompt_tokens,
-            response_data.usage.completion_tokens,
-            response_data.usage.total_tokens
-        );
+        if let Some(usage) = completion.usage {
+            log::info!("[AI-Response] Token使用统计 - prompt: {}, completion: {}, total: {}", 
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.prompt_tokens + usage.completion_tokens
+            );
+            self.record_usage(feature, &config, usage, false);
+        }
+        
+        let content = completion.content;
+        log::info!("[AI-Response] 生成内容长度: {} 字符", content.len());
+        log::trace!("[AI-Response] 完整生成内容: {}", content);
+        Ok(content)
+    }
+    
+    // 流式发送聊天请求，增量文本逐段发送到 sender，返回完整回复
+    // 接收端关闭（客户端断开）时停止读取
+    pub async fn chat_completion_stream(
+        &self,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        sender: StreamSender,
+    ) -> Result<String, AiServiceError> {
+        self.send_chat_stream(None, messages, temperature, max_tokens, sender).await
+    }
+    
+    // 发送流式请求并记录调用耗时指标（耗时为完整接收回复的时间）
+    async fn send_chat_stream(
+        &self,
+        feature: Option<AiFeature>,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        sender: StreamSender,
+    ) -> Result<String, AiServiceError> {
+        let start_time = std::time::Instant::now();
+        let result = self.send_chat_stream_once(feature, messages, temperature, max_tokens, sender).await;
+        metrics::record_ai_call(AiFeature::usage_label(feature), result.is_ok(), start_time.elapsed());
+        result
+    }
+    
+    async fn send_chat_stream_once(
+        &self,
+        feature: Option<AiFeature>,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        sender: StreamSender,
+    ) -> Result<String, AiServiceError> {
+        let config = self.get_latest_config(feature).await?;
+        let provider = config.provider.provider();
+        
+        let request = ChatRequest {
+            messages: &messages,
+            temperature: temperature.unwrap_or(0.7),
+            max_tokens: max_tokens.unwrap_or(1000),
+            stream: true,
+        };
+        
+        log::info!("[AI-Request] 调用AI流式接口 - Provider: {}, URL: {}, Model: {}", config.provider.as_str(), config.base_url, config.model);
+        let start_time = std::time::Instant::now();
+        
+        let mut response = provider.build_chat_request(&self.client, &config, &request)
+            .send()
+            .await?;
         
-        // 提取回复内容
-        if let Some(choice) = response_data.choices.first() {
-            let content = &choice.message.content;
-            log::info!("[AI-Response] 生成内容长度: {} 字符", content.len());
-            log::debug!("[AI-Response] 生成内容预览: {}", 
-                if content.len() > 200 {
-                    format!("{}... (总长度: {})", &content[..200], content.len())
-                } else {
-                    content.clone()
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = config.redact(&response.text().await.unwrap_or_else(|_| "未知错误".to_string()));
+            log::error!("[AI-Response] 流式API返回错误 - 状态码: {}, 详情: {}", status, error_text);
+            return Err(AiServiceError::ApiError(error_text));
+        }
+        
+        // 按行切分（行可能跨越多个数据块，按字节缓冲避免截断UTF-8字符）
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut content = String::new();
+        let mut usage: Option<TokenUsage> = None;
+        'read: while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                match provider.parse_stream_line(&String::from_utf8_lossy(&line)) {
+                    StreamChunk::Delta(text) => {
+                        content.push_str(&text);
+                        if sender.send(text).is_err() {
+                            log::info!("[AI-Response] 流式接收端已关闭，停止读取");
+                            break 'read;
+                        }
+                    }
+                    StreamChunk::Done => break 'read,
+                    StreamChunk::Error(message) => return Err(AiServiceError::ApiError(message)),
+                    StreamChunk::Usage(chunk_usage) => {
+                        usage = Some(usage.map_or(chunk_usage, |u| u.merge(chunk_usage)));
+                    }
+                    StreamChunk::Skip => {}
                 }
-            );
-            log::trace!("[AI-Response] 完整生成内容: {}", content);
-            Ok(content.clone())
-        } else {
-            log::error!("[AI-Response] API未返回任何回复选项");
-            Err(AiServiceError::ParseError("未返回任何回复".to_string()))
+            }
+        }
+        
+        log::info!("[AI-Response] 流式响应完成 - 耗时: {}ms, 内容长度: {} 字符", start_time.elapsed().as_millis(), content.len());
+        if let Some(usage) = usage {
+            self.record_usage(feature, &config, usage, true);
+        }
+        Ok(content)
+    }
+    
+    // 记录Token用量（后台写入本地存储）
+    fn record_usage(&self, feature: Option<AiFeature>, config: &LlmConfig, usage: TokenUsage, streamed: bool) {
+        metrics::record_ai_tokens(config.provider.as_str(), AiFeature::usage_label(feature), usage);
+        ai_usage::record_in_background(
+            self.local_storage.clone(),
+            AiFeature::usage_label(feature),
+            config.provider,
+            &config.model,
+            usage,
+            streamed,
+        );
+    }
+    
+    // 按功能选择模型，并按是否提供 sender 选择普通或流式请求
+    async fn complete(
+        &self,
+        feature: AiFeature,
+        messages: Vec<(String, String)>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        sender: Option<StreamSender>,
+    ) -> Result<String, AiServiceError> {
+        match sender {
+            Some(sender) => self.send_chat_stream(Some(feature), messages, temperature, max_tokens, sender).await,
+            None => self.send_chat(Some(feature), messages, temperature, max_tokens).await,
         }
     }
     
@@ -264,6 +452,27 @@ impl AiService {
         natural_language: &str,
         database_schema: Option<&str>,
         database_type: Option<&str>,
+    ) -> Result<String, AiServiceError> {
+        self.generate_sql_with(natural_language, database_schema, database_type, None).await
+    }
+    
+    // 流式生成SQL，返回清理后的SQL
+    pub async fn generate_sql_stream(
+        &self,
+        natural_language: &str,
+        database_schema: Option<&str>,
+        database_type: Option<&str>,
+        sender: StreamSender,
+    ) -> Result<String, AiServiceError> {
+        self.generate_sql_with(natural_language, database_schema, database_type, Some(sender)).await
+    }
+    
+    async fn generate_sql_with(
+        &self,
+        natural_language: &str,
+        database_schema: Option<&str>,
+        database_type: Option<&str>,
+        sender: Option<StreamSender>,
     ) -> Result<String, AiServiceError> {
         log::info!("[AI-Service] 开始生成SQL - 自然语言长度: {}, 数据库类型: {:?}", 
             natural_language.len(), database_type);
@@ -287,7 +496,7 @@ impl AiService {
         messages.push(("user".to_string(), natural_language.to_string()));
         
         // 调用聊天完成API
-        let result = self.chat_completion(messages, Some(0.3), Some(1500)).await?;
+        let result = self.complete(AiFeature::SqlGeneration, messages, Some(0.3), Some(1500), sender).await?;
         
         // 清理结果（去除可能的Markdown格式和XML标签）
         let clean_sql = result
@@ -317,6 +526,25 @@ impl AiService {
         &self,
         sql: &str,
         database_type: Option<&str>,
+    ) -> Result<(String, String), AiServiceError> {
+        self.optimize_sql_with(sql, database_type, None).await
+    }
+    
+    // 流式优化SQL，返回优化后的SQL和优化建议
+    pub async fn optimize_sql_stream(
+        &self,
+        sql: &str,
+        database_type: Option<&str>,
+        sender: StreamSender,
+    ) -> Result<(String, String), AiServiceError> {
+        self.optimize_sql_with(sql, database_type, Some(sender)).await
+    }
+    
+    async fn optimize_sql_with(
+        &self,
+        sql: &str,
+        database_type: Option<&str>,
+        sender: Option<StreamSender>,
     ) -> Result<(String, String), AiServiceError> {
         log::info!("[AI-Service] 开始优化SQL - SQL长度: {}, 数据库类型: {:?}", sql.len(), database_type);
         log::debug!("[AI-Service] 原始SQL: {}", sql);
@@ -336,7 +564,7 @@ impl AiService {
         messages.push(("user".to_string(), format!("请优化以下SQL查询：\n{}", sql)));
         
         // 调用聊天完成API，使用较低温度以确保一致性，增加max_tokens以获取详细优化信息
-        let result = self.chat_completion(messages, Some(0.1), Some(2500)).await?;
+        let result = self.complete(AiFeature::SqlOptimize, messages, Some(0.1), Some(2500), sender).await?;
         
         // 解析返回的结果，提取优化后的SQL和优化建议
         let optimized_sql = Self::extract_content_between(&result, "<optimized_sql>", "</optimized_sql>");
@@ -383,6 +611,25 @@ impl AiService {
         &self,
         sql: &str,
         database_type: Option<&str>,
+    ) -> Result<String, AiServiceError> {
+        self.explain_sql_with(sql, database_type, None).await
+    }
+    
+    // 流式解释SQL查询
+    pub async fn explain_sql_stream(
+        &self,
+        sql: &str,
+        database_type: Option<&str>,
+        sender: StreamSender,
+    ) -> Result<String, AiServiceError> {
+        self.explain_sql_with(sql, database_type, Some(sender)).await
+    }
+    
+    async fn explain_sql_with(
+        &self,
+        sql: &str,
+        database_type: Option<&str>,
+        sender: Option<StreamSender>,
     ) -> Result<String, AiServiceError> {
         log::info!("[AI-Service] 开始解释SQL - SQL长度: {}, 数据库类型: {:?}", sql.len(), database_type);
         log::debug!("[AI-Service] 待解释SQL: {}", sql);
@@ -402,7 +649,7 @@ impl AiService {
         messages.push(("user".to_string(), format!("请详细解释以下SQL查询语句：\n{}", sql)));
         
         // 调用聊天完成API，使用较低温度以确保一致性，增加max_tokens以获取详细解释
-        let result = self.chat_completion(messages, Some(0.2), Some(3000)).await?;
+        let result = self.complete(AiFeature::SqlExplain, messages, Some(0.2), Some(3000), sender).await?;
         log::info!("[AI-Service] SQL解释完成 - 解释长度: {}", result.len());
         log::debug!("[AI-Service] 解释内容: {}", result);
         Ok(result)
@@ -441,7 +688,7 @@ impl AiService {
         messages.push(("user".to_string(), format!("请将以下SQL查询转换为自然语言描述：\n{}", sql)));
         
         // 调用聊天完成API
-        let result = self.chat_completion(messages, Some(0.3), Some(2000)).await?;
+        let result = self.complete(AiFeature::SqlToNaturalLanguage, messages, Some(0.3), Some(2000), None).await?;
         log::info!("[AI-Service] SQL转自然语言完成 - 描述长度: {}", result.len());
         log::debug!("[AI-Service] 自然语言描述: {}", result);
         Ok(result)
@@ -494,7 +741,7 @@ impl AiService {
         messages.push(("user".to_string(), format!("请为以下部分SQL提供补全建议：\n{}", partial_sql)));
         
         // 调用聊天完成API
-        let result = self.chat_completion(messages, Some(0.5), Some(1500)).await?;
+        let result = self.complete(AiFeature::SqlCompletion, messages, Some(0.5), Some(1500), None).await?;
         
         // 解析JSON数组
         let suggestions: Vec<String> = match serde_json::from_str(&result) {
@@ -575,9 +822,277 @@ impl AiService {
         messages.push(("user".to_string(), current_query.to_string()));
         
         // 调用聊天完成API
-        let result = self.chat_completion(messages, Some(0.7), Some(3000)).await?;
+        let result = self.complete(AiFeature::Chat, messages, Some(0.7), Some(3000), None).await?;
         log::info!("[AI-Service] 对话式AI分析完成 - 回复长度: {}", result.len());
         log::debug!("[AI-Service] AI回复: {}", result);
         Ok(result)
     }
+    
+    // 总结查询结果：返回简短的分析结论和可继续追问的问题
+    pub async fn summarize_result(
+        &self,
+        question: &str,
+        sql: &str,
+        result_preview: &str,
+        database_type: Option<&str>,
+    ) -> Result<ResultSummary, AiServiceError> {
+        log::info!("[AI-Service] 开始总结查询结果 - 问题长度: {}, 结果预览长度: {}", question.len(), result_preview.len());
+        
+        let system_prompt = format!(
+            "你是一个数据分析师，根据用户的问题、执行的SQL和查询结果给出简短的分析结论。\n\
+            数据库类型: {}\n\n\
+            要求：\n\
+            1. 结论不超过200字，直接回答用户的问题，引用结果中的关键数字\n\
+            2. 结果为空或被截断时如实说明，不要编造结果中没有的数据\n\
+            3. 给出2-3个可以继续追问的问题，每行一个\n\
+            4. 使用中文回答\n\n\
+            返回格式：\n\
+            <summary>分析结论</summary>\n\
+            <follow_up>\n追问问题1\n追问问题2\n</follow_up>",
+            database_type.unwrap_or("通用SQL")
+        );
+        let messages = vec![
+            ("system".to_string(), system_prompt),
+            ("user".to_string(), format!("问题：{}\n\nSQL：\n{}\n\n查询结果：\n{}", question, sql, result_preview)),
+        ];
+        
+        let result = self.complete(AiFeature::Chat, messages, Some(0.3), Some(1000), None).await?;
+        let summary = parse_result_summary(&result);
+        log::info!("[AI-Service] 查询结果总结完成 - 结论长度: {}, 追问数: {}", summary.summary.len(), summary.follow_up_questions.len());
+        Ok(summary)
+    }
+    
+    // 诊断执行失败的SQL：根据数据库报错和相关表结构解释原因并给出修正后的SQL
+    pub async fn diagnose_error(
+        &self,
+        sql: &str,
+        error_message: &str,
+        database_schema: &str,
+        database_type: Option<&str>,
+    ) -> Result<ErrorDiagnosis, AiServiceError> {
+        log::info!("[AI-Service] 开始诊断SQL错误 - SQL长度: {}, 错误信息长度: {}", sql.len(), error_message.len());
+        
+        let system_prompt = format!(
+            "你是一个SQL专家，根据执行失败的SQL、数据库返回的错误信息和相关表结构诊断错误原因。\n\
+            数据库类型: {}\n\n\
+            要求：\n\
+            1. 用简短的中文说明错误原因，指出SQL中出错的具体位置（列名、表名、语法等）\n\
+            2. 列名或表名拼写错误时，从表结构中找出最可能的正确名称\n\
+            3. 给出修正后的完整SQL，保持原SQL的意图，不要添加原SQL中没有的写操作\n\
+            4. 无法确定如何修正时（如缺少权限、连接问题）不返回 fixed_sql，只说明原因和处理建议\n\n\
+            返回格式：\n\
+            <explanation>错误原因和处理建议</explanation>\n\
+            <fixed_sql>修正后的SQL</fixed_sql>",
+            database_type.unwrap_or("通用SQL")
+        );
+        let messages = vec![
+            ("system".to_string(), system_prompt),
+            ("user".to_string(), format!("SQL：\n{}\n\n错误信息：\n{}\n\n相关表结构：\n{}", sql, error_message, database_schema)),
+        ];
+        
+        let result = self.complete(AiFeature::SqlExplain, messages, Some(0.1), Some(2000), None).await?;
+        let diagnosis = parse_error_diagnosis(&result);
+        log::info!("[AI-Service] SQL错误诊断完成 - 说明长度: {}, 是否给出修正SQL: {}", diagnosis.explanation.len(), diagnosis.fixed_sql.is_some());
+        Ok(diagnosis)
+    }
+    
+    // 根据自然语言描述生成建表DDL（含索引和注释）
+    // repair 为上一次生成的DDL及其解析错误，用于让AI修正无法通过校验的DDL
+    pub async fn generate_table_ddl(
+        &self,
+        description: &str,
+        database_type: &str,
+        repair: Option<(&str, &str)>,
+    ) -> Result<String, AiServiceError> {
+        log::info!("[AI-Service] 开始生成建表DDL - 描述长度: {}, 数据库类型: {}, 修正: {}", description.len(), database_type, repair.is_some());
+        
+        let comment_rule = match database_type {
+            "mysql" => "列和表的说明使用 COMMENT 子句（列定义后 COMMENT '说明'，表选项 COMMENT='说明'）",
+            "postgresql" => "列和表的说明使用单独的 COMMENT ON TABLE / COMMENT ON COLUMN 语句",
+            _ => "SQLite 不支持注释语句，不要输出 COMMENT",
+        };
+        let system_prompt = format!(
+            "你是一个数据库设计专家，根据用户对业务实体的描述设计数据表。\n\
+            数据库类型: {}\n\n\
+            要求：\n\
+            1. 只输出 CREATE TABLE、CREATE INDEX 和 COMMENT 语句，每条语句以分号结尾，不要输出其他文字说明\n\
+            2. 表名和列名使用小写下划线命名，包含主键，按描述选择合适的数据类型、NOT NULL 和默认值\n\
+            3. 为常用的查询条件和唯一约束创建索引，索引名使用 idx_表名_列名\n\
+            4. {}\n\
+            5. 只使用该数据库支持的语法",
+            database_type, comment_rule
+        );
+        let mut messages = vec![
+            ("system".to_string(), system_prompt),
+            ("user".to_string(), format!("请为以下描述设计数据表：\n{}", description)),
+        ];
+        if let Some((previous_ddl, error)) = repair {
+            messages.push(("assistant".to_string(), previous_ddl.to_string()));
+            messages.push(("user".to_string(), format!("上面的DDL无法通过语法校验：{}\n请修正后重新输出完整的DDL。", error)));
+        }
+        
+        let result = self.complete(AiFeature::SqlGeneration, messages, Some(0.2), Some(3000), None).await?;
+        let ddl = result
+            .trim()
+            .trim_start_matches("```sql")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+        log::info!("[AI-Service] 建表DDL生成完成 - DDL长度: {}", ddl.len());
+        log::debug!("[AI-Service] 生成的DDL: {}", ddl);
+        Ok(ddl.to_string())
+    }
+    
+    // 根据查询结果推荐图表配置，返回None表示回复无法解析
+    pub async fn suggest_chart(
+        &self,
+        question: Option<&str>,
+        column_profiles: &str,
+        result_preview: &str,
+    ) -> Result<Option<crate::services::chart_suggest::ChartSpec>, AiServiceError> {
+        log::info!("[AI-Service] 开始推荐图表 - 结果预览长度: {}", result_preview.len());
+        
+        let system_prompt = "你是一个数据可视化专家，根据查询结果的列信息和数据预览推荐最合适的图表。\n\
+            要求：\n\
+            1. chart_type 只能是 line、bar、pie、scatter、table 之一\n\
+            2. x 为横轴（饼图为分类）列名，y 为数值列名数组，列名必须来自列信息\n\
+            3. 不适合图表展示时返回 table\n\
+            4. 只返回JSON对象，不要其他文字说明\n\n\
+            返回格式示例：\n\
+            {\"chart_type\": \"bar\", \"x\": \"status\", \"y\": [\"total\"], \"title\": \"各状态订单金额\", \"reason\": \"按分类比较数值\"}";
+        let mut user_prompt = String::new();
+        if let Some(question) = question {
+            user_prompt.push_str(&format!("问题：{}\n\n", question));
+        }
+        user_prompt.push_str(&format!("列信息：\n{}\n数据预览：\n{}", column_profiles, result_preview));
+        let messages = vec![
+            ("system".to_string(), system_prompt.to_string()),
+            ("user".to_string(), user_prompt),
+        ];
+        
+        let result = self.complete(AiFeature::Chat, messages, Some(0.2), Some(500), None).await?;
+        let spec = crate::services::chart_suggest::parse_chart_spec(&result);
+        if spec.is_none() {
+            log::warn!("[AI-Service] 图表推荐回复解析失败: {}", result);
+        }
+        Ok(spec)
+    }
+
+    // 根据列名、类型和注释建议测试数据的取值模式，回复无法解析的列不返回
+    pub async fn suggest_value_patterns(
+        &self,
+        table_name: &str,
+        column_descriptions: &str,
+    ) -> Result<std::collections::HashMap<String, crate::services::data_generator::ValuePattern>, AiServiceError> {
+        log::info!("[AI-Service] 开始建议测试数据模式 - 表: {}", table_name);
+
+        let system_prompt = "你是一个测试数据设计专家，根据表的列信息为每一列选择生成测试数据的取值模式。\n\
+            要求：\n\
+            1. kind 只能是 full_name、first_name、last_name、username、email、phone、url、address、city、country、company、\
+            title、text、word、uuid、boolean、integer、decimal、date、date_time、time、choice、json 之一\n\
+            2. integer 需要 min、max；decimal 需要 min、max、scale；date / date_time 可指定 days_back；choice 需要 values 数组\n\
+            3. 状态、类型等枚举列使用 choice，并根据列注释给出合理的候选值\n\
+            4. 只返回JSON对象，键为列名，不要其他文字说明\n\n\
+            返回格式示例：\n\
+            {\"status\": {\"kind\": \"choice\", \"values\": [\"paid\", \"shipped\"]}, \"amount\": {\"kind\": \"decimal\", \"min\": 1, \"max\": 500, \"scale\": 2}}";
+        let user_prompt = format!("表名：{}\n\n列信息：\n{}", table_name, column_descriptions);
+        let messages = vec![
+            ("system".to_string(), system_prompt.to_string()),
+            ("user".to_string(), user_prompt),
+        ];
+
+        let result = self.complete(AiFeature::Chat, messages, Some(0.2), Some(1000), None).await?;
+        let patterns = crate::services::data_generator::parse_value_patterns(&result);
+        if patterns.is_empty() {
+            log::warn!("[AI-Service] 测试数据模式回复解析失败: {}", result);
+        }
+        Ok(patterns)
+    }
+}
+
+// 查询结果总结
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultSummary {
+    pub summary: String,
+    pub follow_up_questions: Vec<String>,
+}
+
+// 解析结果总结回复（<summary> / <follow_up> 标签），缺少标签时整段回复作为结论
+pub fn parse_result_summary(text: &str) -> ResultSummary {
+    let summary = AiService::extract_content_between(text, "<summary>", "</summary>")
+        .unwrap_or_else(|| text.split("<follow_up>").next().unwrap_or(text));
+    let follow_up_questions = AiService::extract_content_between(text, "<follow_up>", "</follow_up>")
+        .map(|block| {
+            block.lines()
+                .map(|line| line.trim().trim_start_matches(['-', '*', '•']).trim())
+                .map(|line| {
+                    // 去掉 "1." "2、" 等编号
+                    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+                    match rest.strip_prefix(['.', '、', ')']) {
+                        Some(rest) if rest.len() < line.len() => rest.trim(),
+                        _ => line,
+                    }
+                })
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    ResultSummary { summary: summary.trim().to_string(), follow_up_questions }
+}
+
+// SQL错误诊断结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDiagnosis {
+    pub explanation: String,
+    pub fixed_sql: Option<String>,
+}
+
+// 解析错误诊断回复（<explanation> / <fixed_sql> 标签），缺少标签时整段回复作为说明
+pub fn parse_error_diagnosis(text: &str) -> ErrorDiagnosis {
+    let explanation = AiService::extract_content_between(text, "<explanation>", "</explanation>")
+        .unwrap_or_else(|| text.split("<fixed_sql>").next().unwrap_or(text));
+    let fixed_sql = AiService::extract_content_between(text, "<fixed_sql>", "</fixed_sql>")
+        .map(|sql| {
+            sql.trim()
+                .trim_start_matches("```sql")
+                .trim_start_matches("```")
+                .trim_end_matches("```")
+                .trim()
+                .to_string()
+        })
+        .filter(|sql| !sql.is_empty());
+    ErrorDiagnosis { explanation: explanation.trim().to_string(), fixed_sql }
+}
+
+// 查询结果预览（制表符分隔，最多 max_rows 行，单元格超长时截断），用于发送给AI
+pub fn format_result_preview(columns: &[String], rows: &[Vec<serde_json::Value>], max_rows: usize) -> String {
+    const MAX_CELL_CHARS: usize = 100;
+    
+    let cell = |value: &serde_json::Value| {
+        let text = match value {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Null => "NULL".to_string(),
+            other => other.to_string(),
+        };
+        let text = text.replace(['\t', '\n', '\r'], " ");
+        if text.chars().count() > MAX_CELL_CHARS {
+            format!("{}...", text.chars().take(MAX_CELL_CHARS).collect::<String>())
+        } else {
+            text
+        }
+    };
+    
+    let mut preview = columns.join("\t");
+    preview.push('\n');
+    for row in rows.iter().take(max_rows) {
+        preview.push_str(&row.iter().map(cell).collect::<Vec<_>>().join("\t"));
+        preview.push('\n');
+    }
+    if rows.len() > max_rows {
+        preview.push_str(&format!("...（共 {} 行，仅显示前 {} 行）\n", rows.len(), max_rows));
+    } else if rows.is_empty() {
+        preview.push_str("（无结果）\n");
+    }
+    preview
 }
\ No newline at end of file
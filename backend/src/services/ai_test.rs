@@ -1,4 +1,4 @@
-use super::ai::AiService;
+use super::ai::{format_result_preview, parse_error_diagnosis, parse_result_summary, parse_stream_line, AiFeature, AiService, StreamChunk};
 use crate::db::LocalStorageManager;
 
 #[tokio::test]
@@ -122,4 +122,71 @@ async fn test_ai_service_without_api_key() {
     assert!(result.is_err(), "没有API密钥应该返回错误");
     
     println!("✅ 正确处理了缺少API密钥的情况");
-}
\ No newline at end of file
+}
+#[test]
+fn test_parse_stream_line() {
+    // 测试流式响应行解析
+    assert_eq!(
+        parse_stream_line(r#"data: {"choices":[{"index":0,"delta":{"content":"SELECT"}}]}"#),
+        StreamChunk::Delta("SELECT".to_string())
+    );
+    assert_eq!(parse_stream_line(r#"data: {"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#), StreamChunk::Skip);
+    assert_eq!(parse_stream_line("data: [DONE]\n"), StreamChunk::Done);
+    assert_eq!(parse_stream_line(": keep-alive"), StreamChunk::Skip);
+    assert_eq!(parse_stream_line(""), StreamChunk::Skip);
+    assert_eq!(
+        parse_stream_line(r#"data: {"error":{"message":"rate limited"}}"#),
+        StreamChunk::Error("rate limited".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_feature_model_fallback() {
+    // 测试功能专用模型及回退到全局模型
+    let local_storage = LocalStorageManager::new(":memory:").await.unwrap();
+    local_storage.set_app_setting("ai_api_key", "test-api-key").await.unwrap();
+    local_storage.set_app_setting("ai_model", "gpt-4o").await.unwrap();
+    local_storage.set_app_setting(&AiFeature::SqlCompletion.model_setting_key(), "gpt-4o-mini").await.unwrap();
+    
+    let service = AiService::new(&local_storage).await.unwrap();
+    assert_eq!(service.get_latest_config(Some(AiFeature::SqlCompletion)).await.unwrap().model, "gpt-4o-mini");
+    assert_eq!(service.get_latest_config(Some(AiFeature::SqlOptimize)).await.unwrap().model, "gpt-4o");
+    assert_eq!(service.get_latest_config(None).await.unwrap().model, "gpt-4o");
+    assert_eq!(AiFeature::parse("sql_generation"), Some(AiFeature::SqlGeneration));
+}
+
+#[test]
+fn test_parse_result_summary() {
+    // 测试查询结果总结解析
+    let summary = parse_result_summary("<summary>\n上月共有 42 笔已发货订单。\n</summary>\n<follow_up>\n1. 按地区分布如何？\n- 2024年同期有多少？\n</follow_up>");
+    assert_eq!(summary.summary, "上月共有 42 笔已发货订单。");
+    assert_eq!(summary.follow_up_questions, vec!["按地区分布如何？", "2024年同期有多少？"]);
+    
+    let summary = parse_result_summary("没有找到符合条件的订单。");
+    assert_eq!(summary.summary, "没有找到符合条件的订单。");
+    assert!(summary.follow_up_questions.is_empty());
+}
+
+#[test]
+fn test_format_result_preview() {
+    // 测试查询结果预览截断
+    let columns = vec!["status".to_string(), "total".to_string()];
+    let rows = vec![
+        vec![serde_json::json!("shipped"), serde_json::json!(42)],
+        vec![serde_json::Value::Null, serde_json::json!("a\tb")],
+    ];
+    assert_eq!(format_result_preview(&columns, &rows, 5), "status\ttotal\nshipped\t42\nNULL\ta b\n");
+    assert!(format_result_preview(&columns, &rows, 1).ends_with("...（共 2 行，仅显示前 1 行）\n"));
+}
+
+#[test]
+fn test_parse_error_diagnosis() {
+    // 测试错误诊断解析
+    let diagnosis = parse_error_diagnosis("<explanation>列 usr_name 不存在，应为 user_name。</explanation>\n<fixed_sql>\n```sql\nSELECT user_name FROM users\n```\n</fixed_sql>");
+    assert_eq!(diagnosis.explanation, "列 usr_name 不存在，应为 user_name。");
+    assert_eq!(diagnosis.fixed_sql.as_deref(), Some("SELECT user_name FROM users"));
+    
+    let diagnosis = parse_error_diagnosis("当前账号没有 orders 表的 SELECT 权限，请联系管理员授权。");
+    assert_eq!(diagnosis.explanation, "当前账号没有 orders 表的 SELECT 权限，请联系管理员授权。");
+    assert!(diagnosis.fixed_sql.is_none());
+}
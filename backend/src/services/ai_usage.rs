@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashMap};
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::db::LocalStorageManager;
+use crate::models::AiUsageRecord;
+use crate::services::llm::{ProviderKind, TokenUsage};
+
+// 应用设置中保存自定义模型定价的键（JSON：{"模型名": {"input": 0.15, "output": 0.6}}）
+pub const PRICING_SETTING_KEY: &str = "ai_model_pricing";
+// 统计时最多读取的用量记录数
+pub const MAX_USAGE_RECORDS: i64 = 100_000;
+// 默认统计最近天数
+pub const DEFAULT_USAGE_DAYS: i64 = 30;
+
+// 模型定价（美元 / 百万Token）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+// 内置定价，按模型名前缀匹配（较长的前缀优先）
+const BUILTIN_PRICING: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.6),
+    ("gpt-4o", 2.5, 10.0),
+    ("gpt-4.1-nano", 0.1, 0.4),
+    ("gpt-4.1-mini", 0.4, 1.6),
+    ("gpt-4.1", 2.0, 8.0),
+    ("gpt-3.5-turbo", 0.5, 1.5),
+    ("deepseek-chat", 0.27, 1.1),
+    ("deepseek-reasoner", 0.55, 2.19),
+    ("claude-3-5-haiku", 0.8, 4.0),
+    ("claude-3-haiku", 0.25, 1.25),
+    ("claude-3-5-sonnet", 3.0, 15.0),
+    ("claude-3-7-sonnet", 3.0, 15.0),
+    ("claude-sonnet-4", 3.0, 15.0),
+    ("claude-opus-4", 15.0, 75.0),
+];
+
+// 读取自定义模型定价，未配置或解析失败时返回空
+pub async fn load_pricing(storage: &LocalStorageManager) -> HashMap<String, ModelPrice> {
+    match storage.get_app_setting(PRICING_SETTING_KEY).await.ok().flatten() {
+        Some(value) => serde_json::from_str(&value)
+            .map_err(|e| warn!("[AI-Usage] 模型定价配置解析失败: {}", e))
+            .unwrap_or_default(),
+        None => HashMap::new(),
+    }
+}
+
+// 查找模型定价：自定义定价优先，其次按前缀匹配内置定价；本地Ollama视为免费
+pub fn model_price(provider: ProviderKind, model: &str, custom: &HashMap<String, ModelPrice>) -> Option<ModelPrice> {
+    if let Some(price) = custom.get(model) {
+        return Some(*price);
+    }
+    if provider == ProviderKind::Ollama {
+        return Some(ModelPrice { input: 0.0, output: 0.0 });
+    }
+    let model = model.to_lowercase();
+    BUILTIN_PRICING.iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|(_, input, output)| ModelPrice { input: *input, output: *output })
+}
+
+// 估算一次调用的费用（美元）
+pub fn estimate_cost(price: ModelPrice, usage: TokenUsage) -> f64 {
+    (usage.prompt_tokens as f64 * price.input + usage.completion_tokens as f64 * price.output) / 1_000_000.0
+}
+
+// 在后台记录一次AI调用用量，不阻塞AI请求
+pub fn record_in_background(
+    storage: LocalStorageManager,
+    feature: &str,
+    provider: ProviderKind,
+    model: &str,
+    usage: TokenUsage,
+    streamed: bool,
+) {
+    let feature = feature.to_string();
+    let model = model.to_string();
+    tokio::spawn(async move {
+        let pricing = load_pricing(&storage).await;
+        let record = AiUsageRecord {
+            id: None,
+            feature,
+            provider: provider.as_str().to_string(),
+            estimated_cost: model_price(provider, &model, &pricing).map(|price| estimate_cost(price, usage)),
+            model,
+            prompt_tokens: usage.prompt_tokens as i64,
+            completion_tokens: usage.completion_tokens as i64,
+            streamed,
+            created_at: LocalStorageManager::current_timestamp(),
+        };
+        if let Err(e) = storage.add_ai_usage(&record).await {
+            warn!("[AI-Usage] 记录AI用量失败: {}", e);
+        }
+    });
+}
+
+// 每日用量（按功能和模型分组）
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct DailyUsage {
+    pub date: String,  // YYYY-MM-DD（本地时区）
+    pub feature: String,
+    pub model: String,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub estimated_cost: f64,
+}
+
+// 用量汇总
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct UsageTotals {
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub estimated_cost: f64,
+    pub unpriced_requests: i64,  // 没有定价信息、未计入费用的请求数
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub totals: UsageTotals,
+    pub by_feature: BTreeMap<String, UsageTotals>,
+    pub by_model: BTreeMap<String, UsageTotals>,
+    pub daily: Vec<DailyUsage>,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &AiUsageRecord) {
+        self.requests += 1;
+        self.prompt_tokens += record.prompt_tokens;
+        self.completion_tokens += record.completion_tokens;
+        self.total_tokens += record.prompt_tokens + record.completion_tokens;
+        match record.estimated_cost {
+            Some(cost) => self.estimated_cost += cost,
+            None => self.unpriced_requests += 1,
+        }
+    }
+}
+
+// 时间戳转换为本地日期
+fn local_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+// 汇总AI用量，daily 按日期升序排列
+pub fn compute_report(records: &[AiUsageRecord]) -> UsageReport {
+    let mut totals = UsageTotals::default();
+    let mut by_feature: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    let mut by_model: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    let mut daily: BTreeMap<(String, String, String), DailyUsage> = BTreeMap::new();
+
+    for record in records {
+        totals.add(record);
+        by_feature.entry(record.feature.clone()).or_default().add(record);
+        by_model.entry(record.model.clone()).or_default().add(record);
+
+        let date = local_date(record.created_at);
+        let day = daily.entry((date.clone(), record.feature.clone(), record.model.clone()))
+            .or_insert_with(|| DailyUsage {
+                date,
+                feature: record.feature.clone(),
+                model: record.model.clone(),
+                ..Default::default()
+            });
+        day.requests += 1;
+        day.prompt_tokens += record.prompt_tokens;
+        day.completion_tokens += record.completion_tokens;
+        day.total_tokens += record.prompt_tokens + record.completion_tokens;
+        day.estimated_cost += record.estimated_cost.unwrap_or(0.0);
+    }
+
+    UsageReport {
+        totals,
+        by_feature,
+        by_model,
+        daily: daily.into_values().collect(),
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(feature: &str, model: &str, prompt: i64, completion: i64, cost: Option<f64>, created_at: i64) -> AiUsageRecord {
+        AiUsageRecord {
+            id: None,
+            feature: feature.to_string(),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            estimated_cost: cost,
+            streamed: false,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_model_price() {
+        let custom = HashMap::new();
+        assert_eq!(model_price(ProviderKind::OpenAi, "gpt-4o-mini-2024-07-18", &custom), Some(ModelPrice { input: 0.15, output: 0.6 }));
+        assert_eq!(model_price(ProviderKind::OpenAi, "gpt-4o", &custom), Some(ModelPrice { input: 2.5, output: 10.0 }));
+        assert_eq!(model_price(ProviderKind::Ollama, "llama3.1", &custom), Some(ModelPrice { input: 0.0, output: 0.0 }));
+        assert_eq!(model_price(ProviderKind::OpenAi, "my-finetune", &custom), None);
+
+        let custom = HashMap::from([("my-finetune".to_string(), ModelPrice { input: 1.0, output: 2.0 })]);
+        let price = model_price(ProviderKind::OpenAi, "my-finetune", &custom).unwrap();
+        let cost = estimate_cost(price, TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 500_000 });
+        assert!((cost - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_report() {
+        let day = 1_700_000_000;
+        let records = vec![
+            record("sql_generation", "gpt-4o-mini", 100, 20, Some(0.01), day),
+            record("sql_generation", "gpt-4o-mini", 50, 10, Some(0.02), day + 60),
+            record("chat", "custom", 10, 5, None, day + 120),
+            record("chat", "custom", 10, 5, None, day + 86_400 * 2),
+        ];
+        let report = compute_report(&records);
+
+        assert_eq!(report.totals.requests, 4);
+        assert_eq!(report.totals.total_tokens, 210);
+        assert_eq!(report.totals.unpriced_requests, 2);
+        assert!((report.totals.estimated_cost - 0.03).abs() < 1e-9);
+        assert_eq!(report.by_feature["sql_generation"].requests, 2);
+        assert_eq!(report.by_model["custom"].prompt_tokens, 20);
+
+        assert_eq!(report.daily.len(), 3);
+        let generation = report.daily.iter().find(|d| d.feature == "sql_generation").unwrap();
+        assert_eq!(generation.requests, 2);
+        assert_eq!(generation.prompt_tokens, 150);
+        assert!(report.daily.first().unwrap().date <= report.daily.last().unwrap().date);
+    }
+}
@@ -0,0 +1,182 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use crate::db::LocalStorageManager;
+use crate::services::result_cache::{DEFAULT_RESULT_CACHE_MAX_ENTRIES, MAX_RESULT_CACHE_ENTRIES, MAX_RESULT_CACHE_TTL_SECS};
+use crate::services::slow_queries::{self, SLOW_QUERY_SETTING_KEY};
+
+// 应用设置中保存通用配置的键
+pub const APP_SETTINGS_KEY: &str = "general";
+
+// 未指定 LIMIT 的查询默认返回的行数
+pub const DEFAULT_QUERY_LIMIT: u64 = 200;
+// 查询结果行数上限（LIMIT 超出时截断为该值）
+pub const DEFAULT_MAX_QUERY_LIMIT: u64 = 1500;
+// 允许配置的行数上限
+pub const MAX_QUERY_LIMIT_CAP: u64 = 100_000;
+// 分页查询默认每页行数
+pub const DEFAULT_PAGE_SIZE: u64 = 100;
+
+// 应用通用配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppSettings {
+    #[serde(default = "default_query_limit")]
+    pub default_query_limit: u64,
+    #[serde(default = "default_max_query_limit")]
+    pub max_query_limit: u64,
+    #[serde(default = "default_page_size")]
+    pub default_page_size: u64,
+    // 查询历史保留天数（0 表示永久保留，收藏的历史不会被清理）
+    #[serde(default)]
+    pub history_retention_days: u32,
+    // 慢查询记录阈值（与 /api/performance/slow-queries/settings 共用）
+    #[serde(default = "default_slow_query_threshold")]
+    pub slow_query_threshold_ms: u64,
+    // 日志级别（如 "debug" 或 "info,sqlx=warn"），为空时使用 RUST_LOG
+    #[serde(default)]
+    pub log_level: Option<String>,
+    // 查询结果缓存有效期（秒，0 表示不缓存）
+    #[serde(default)]
+    pub result_cache_ttl_secs: u64,
+    // 查询结果缓存条目数上限
+    #[serde(default = "default_result_cache_max_entries")]
+    pub result_cache_max_entries: usize,
+}
+
+fn default_query_limit() -> u64 {
+    DEFAULT_QUERY_LIMIT
+}
+
+fn default_max_query_limit() -> u64 {
+    DEFAULT_MAX_QUERY_LIMIT
+}
+
+fn default_page_size() -> u64 {
+    DEFAULT_PAGE_SIZE
+}
+
+fn default_slow_query_threshold() -> u64 {
+    slow_queries::DEFAULT_THRESHOLD_MS
+}
+
+fn default_result_cache_max_entries() -> usize {
+    DEFAULT_RESULT_CACHE_MAX_ENTRIES
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            default_query_limit: DEFAULT_QUERY_LIMIT,
+            max_query_limit: DEFAULT_MAX_QUERY_LIMIT,
+            default_page_size: DEFAULT_PAGE_SIZE,
+            history_retention_days: 0,
+            slow_query_threshold_ms: slow_queries::DEFAULT_THRESHOLD_MS,
+            log_level: None,
+            result_cache_ttl_secs: 0,
+            result_cache_max_entries: DEFAULT_RESULT_CACHE_MAX_ENTRIES,
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_query_limit == 0 || self.max_query_limit > MAX_QUERY_LIMIT_CAP {
+            return Err(format!("查询行数上限必须在 1 到 {} 之间", MAX_QUERY_LIMIT_CAP));
+        }
+        if self.default_query_limit == 0 || self.default_query_limit > self.max_query_limit {
+            return Err("默认查询行数必须大于0且不超过查询行数上限".to_string());
+        }
+        if self.default_page_size == 0 || self.default_page_size > self.max_query_limit {
+            return Err("默认每页行数必须大于0且不超过查询行数上限".to_string());
+        }
+        if self.slow_query_threshold_ms < slow_queries::MIN_THRESHOLD_MS {
+            return Err(format!("慢查询阈值不能小于 {}ms", slow_queries::MIN_THRESHOLD_MS));
+        }
+        if self.result_cache_ttl_secs > MAX_RESULT_CACHE_TTL_SECS {
+            return Err(format!("查询结果缓存有效期不能超过 {} 秒", MAX_RESULT_CACHE_TTL_SECS));
+        }
+        if self.result_cache_max_entries == 0 || self.result_cache_max_entries > MAX_RESULT_CACHE_ENTRIES {
+            return Err(format!("查询结果缓存条目数必须在 1 到 {} 之间", MAX_RESULT_CACHE_ENTRIES));
+        }
+        if let Some(level) = self.log_level.as_deref().filter(|l| !l.trim().is_empty()) {
+            crate::utils::logging::parse_level(level)?;
+        }
+        Ok(())
+    }
+}
+
+// 读取应用通用配置，未配置时使用默认值
+pub async fn load_settings(storage: &LocalStorageManager) -> AppSettings {
+    let mut settings = match storage.get_app_setting(APP_SETTINGS_KEY).await.ok().flatten() {
+        Some(value) => serde_json::from_str(&value)
+            .map_err(|e| warn!("[Settings] 应用配置解析失败: {}", e))
+            .unwrap_or_default(),
+        None => AppSettings::default(),
+    };
+    settings.slow_query_threshold_ms = slow_queries::load_settings(storage).await.threshold_ms;
+    settings
+}
+
+// 保存应用通用配置，慢查询阈值同步写入慢查询日志配置
+pub async fn save_settings(storage: &LocalStorageManager, settings: &AppSettings) -> Result<(), String> {
+    let value = serde_json::to_string(settings).map_err(|e| format!("序列化应用配置失败: {}", e))?;
+    storage.set_app_setting(APP_SETTINGS_KEY, &value).await
+        .map_err(|e| format!("保存应用配置失败: {}", e))?;
+
+    let mut slow_query = slow_queries::load_settings(storage).await;
+    if slow_query.threshold_ms != settings.slow_query_threshold_ms {
+        slow_query.threshold_ms = settings.slow_query_threshold_ms;
+        let value = serde_json::to_string(&slow_query).map_err(|e| format!("序列化慢查询配置失败: {}", e))?;
+        storage.set_app_setting(SLOW_QUERY_SETTING_KEY, &value).await
+            .map_err(|e| format!("保存慢查询配置失败: {}", e))?;
+    }
+    Ok(())
+}
+
+// 应用配置生效：调整日志级别、清理过期的查询历史（启动时和保存配置后调用）
+pub async fn apply(storage: &LocalStorageManager, settings: &AppSettings) {
+    if let Some(level) = settings.log_level.as_deref().filter(|l| !l.trim().is_empty()) {
+        match crate::utils::logging::set_level(level) {
+            Ok(()) => info!("[Settings] 日志级别已调整为 {}", level),
+            Err(e) => warn!("[Settings] {}", e),
+        }
+    }
+    if settings.history_retention_days > 0 {
+        let before = LocalStorageManager::current_timestamp() - settings.history_retention_days as i64 * 24 * 3600;
+        match storage.prune_query_history(before).await {
+            Ok(deleted) if deleted > 0 => info!("[Settings] 已清理 {} 条过期查询历史", deleted),
+            Ok(_) => {}
+            Err(e) => warn!("[Settings] 清理过期查询历史失败: {}", e),
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_defaults_and_validation() {
+        let settings: AppSettings = serde_json::from_str(r#"{"max_query_limit": 5000}"#).unwrap();
+        assert_eq!(settings.default_query_limit, DEFAULT_QUERY_LIMIT);
+        assert_eq!(settings.default_page_size, DEFAULT_PAGE_SIZE);
+        assert_eq!(settings.history_retention_days, 0);
+        assert_eq!(settings.result_cache_ttl_secs, 0);
+        assert_eq!(settings.result_cache_max_entries, DEFAULT_RESULT_CACHE_MAX_ENTRIES);
+        assert!(settings.validate().is_ok());
+
+        let invalid = |f: fn(&mut AppSettings)| {
+            let mut settings = AppSettings::default();
+            f(&mut settings);
+            settings.validate().is_err()
+        };
+        assert!(invalid(|s| s.default_query_limit = s.max_query_limit + 1));
+        assert!(invalid(|s| s.max_query_limit = MAX_QUERY_LIMIT_CAP + 1));
+        assert!(invalid(|s| s.default_page_size = 0));
+        assert!(invalid(|s| s.slow_query_threshold_ms = 10));
+        assert!(invalid(|s| s.log_level = Some("sqlx=loud".to_string())));
+        assert!(invalid(|s| s.result_cache_ttl_secs = MAX_RESULT_CACHE_TTL_SECS + 1));
+        assert!(invalid(|s| s.result_cache_max_entries = 0));
+    }
+}
@@ -0,0 +1,196 @@
+use log::*;
+
+use crate::db::{DatabaseType, LocalStorageManager};
+use crate::models::{AuditLogEntry, DatabaseConnection};
+use crate::utils::security::{classify_statement_as, leading_keyword, StatementKind};
+
+// 审计日志中SQL文本的最大字符数
+const MAX_SQL_CHARS: usize = 10_000;
+
+// 执行入口（审计日志的 source 字段）
+pub const SOURCE_QUERY: &str = "query";
+pub const SOURCE_MULTI_QUERY: &str = "multi_query";
+pub const SOURCE_SCRIPT: &str = "script";
+pub const SOURCE_TABLE_DATA: &str = "table_data";
+pub const SOURCE_DDL: &str = "ddl";
+pub const SOURCE_GENERATE_DATA: &str = "generate_data";
+pub const SOURCE_PROCESSES: &str = "processes";
+
+// 管理操作（终止会话等）的语句类型
+const KIND_ADMIN: &str = "admin";
+
+// 一次执行的审计信息
+pub struct AuditRecord<'a> {
+    pub connection: &'a DatabaseConnection,
+    pub source: &'static str,
+    pub ai_generated: bool,
+    pub affected_rows: Option<i64>,
+    pub error: Option<String>,
+}
+
+fn kind_name(kind: StatementKind) -> Option<&'static str> {
+    match kind {
+        StatementKind::Dml => Some("dml"),
+        StatementKind::Ddl => Some("ddl"),
+        StatementKind::Destructive => Some("destructive"),
+        StatementKind::Query | StatementKind::Other => None,
+    }
+}
+
+// 执行后端服务的系统用户
+fn current_user() -> Option<String> {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok().filter(|u| !u.is_empty())
+}
+
+fn entry(record: &AuditRecord, sql: &str, kind: &str, affected_rows: Option<i64>, executed_by: Option<String>) -> AuditLogEntry {
+    AuditLogEntry {
+        id: None,
+        connection_id: record.connection.id,
+        connection_name: record.connection.name.clone(),
+        environment: record.connection.environment.clone().filter(|e| !e.trim().is_empty()),
+        statement_kind: kind.to_string(),
+        operation: leading_keyword(sql),
+        sql_text: sql.trim().chars().take(MAX_SQL_CHARS).collect(),
+        source: record.source.to_string(),
+        ai_generated: record.ai_generated,
+        affected_rows,
+        is_success: record.error.is_none(),
+        error_message: record.error.clone(),
+        executed_by,
+        executed_at: 0,
+    }
+}
+
+// 为写操作语句生成审计记录，只读语句不记录；
+// 影响行数只在单条语句时记录（多条语句时无法区分各自的行数）
+pub fn audit_entries(record: &AuditRecord, statements: &[String]) -> Vec<AuditLogEntry> {
+    let dialect = DatabaseType::from_name(&record.connection.db_type);
+    let writes: Vec<(&String, &'static str)> = statements.iter()
+        .filter_map(|sql| kind_name(classify_statement_as(dialect, sql)).map(|kind| (sql, kind)))
+        .collect();
+    let affected_rows = if statements.len() == 1 { record.affected_rows } else { None };
+    let executed_by = current_user();
+
+    writes.into_iter()
+        .map(|(sql, kind)| entry(record, sql, kind, affected_rows, executed_by.clone()))
+        .collect()
+}
+
+// 为管理操作生成审计记录（KILL、pg_cancel_backend 等不按语句类型过滤）
+pub fn admin_entry(record: &AuditRecord, statement: &str) -> AuditLogEntry {
+    entry(record, statement, KIND_ADMIN, record.affected_rows, current_user())
+}
+
+fn write_in_background(storage: &LocalStorageManager, entries: Vec<AuditLogEntry>) {
+    if entries.is_empty() {
+        return;
+    }
+    let storage = storage.clone();
+    tokio::spawn(async move {
+        for entry in entries {
+            if let Err(e) = storage.add_audit_log(&entry).await {
+                warn!("[Audit] 写入审计日志失败: 连接={}, 操作={}, 错误: {}", entry.connection_name, entry.operation, e);
+            }
+        }
+    });
+}
+
+// 后台写入审计日志，失败时只记录警告，不影响执行结果
+pub fn record_in_background(storage: &LocalStorageManager, record: AuditRecord, statements: &[String]) {
+    write_in_background(storage, audit_entries(&record, statements));
+}
+
+// 后台写入管理操作的审计日志
+pub fn record_admin_in_background(storage: &LocalStorageManager, record: AuditRecord, statement: &str) {
+    write_in_background(storage, vec![admin_entry(&record, statement)]);
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection() -> DatabaseConnection {
+        DatabaseConnection {
+            id: Some(7),
+            environment: Some("staging".to_string()),
+            ..DatabaseConnection::for_test("staging-db", "mysql")
+        }
+    }
+
+    #[test]
+    fn test_audit_entries() {
+        let connection = connection();
+        let record = AuditRecord {
+            connection: &connection,
+            source: SOURCE_QUERY,
+            ai_generated: true,
+            affected_rows: Some(5),
+            error: None,
+        };
+
+        let statements = vec![
+            "SELECT * FROM orders".to_string(),
+            "-- 清理\nTRUNCATE TABLE orders_staging".to_string(),
+            "UPDATE orders SET status = 1".to_string(),
+        ];
+        let entries = audit_entries(&record, &statements);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].operation, "TRUNCATE");
+        assert_eq!(entries[0].statement_kind, "destructive");
+        assert_eq!(entries[0].environment.as_deref(), Some("staging"));
+        assert_eq!(entries[1].statement_kind, "dml");
+        assert!(entries.iter().all(|e| e.ai_generated && e.is_success && e.affected_rows.is_none()));
+
+        let single = audit_entries(&record, &statements[2..]);
+        assert_eq!(single[0].affected_rows, Some(5));
+        assert_eq!(single[0].connection_id, Some(7));
+
+        assert!(audit_entries(&record, &statements[..1]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_audit_update_affected_rows() {
+        use crate::db::{bind_json_values, fetch_rows_affected};
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE orders (id INTEGER PRIMARY KEY, status INTEGER)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO orders (status) VALUES (0), (0), (1)").execute(&pool).await.unwrap();
+
+        let sql = "UPDATE orders SET status = ? WHERE status = 0";
+        let params = vec![serde_json::json!(2)];
+        let (rows, rows_affected) = fetch_rows_affected!(bind_json_values!(sqlx::query(sql), &params), &pool).await.unwrap();
+        assert!(rows.is_empty());
+        assert_eq!(rows_affected, 2);
+
+        let connection = connection();
+        let record = AuditRecord {
+            connection: &connection,
+            source: SOURCE_QUERY,
+            ai_generated: false,
+            affected_rows: Some(rows_affected as i64),
+            error: None,
+        };
+        let entries = audit_entries(&record, &[sql.to_string()]);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].affected_rows, Some(2));
+    }
+
+    #[test]
+    fn test_admin_entry() {
+        let connection = connection();
+        let record = AuditRecord {
+            connection: &connection,
+            source: SOURCE_PROCESSES,
+            ai_generated: false,
+            affected_rows: None,
+            error: Some("会话 42 不存在或已结束".to_string()),
+        };
+
+        let entry = admin_entry(&record, "SELECT pg_cancel_backend(42)");
+        assert_eq!(entry.statement_kind, "admin");
+        assert_eq!(entry.operation, "SELECT");
+        assert_eq!(entry.source, "processes");
+        assert!(!entry.is_success);
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+// 分类列适合饼图的最大不同值数量
+const MAX_PIE_CATEGORIES: usize = 8;
+// 分类列适合柱状图的最大不同值数量
+const MAX_BAR_CATEGORIES: usize = 50;
+// 单个图表最多映射的数值列
+const MAX_Y_COLUMNS: usize = 4;
+
+// 列在图表中的角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnRole {
+    Numeric,
+    Temporal,
+    Categorical,
+    Text,  // 不同值过多的文本列，不适合作为坐标轴
+}
+
+// 列的类型和基数统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    pub role: ColumnRole,
+    pub distinct_count: usize,
+    pub null_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartType {
+    Line,
+    Bar,
+    Pie,
+    Scatter,
+    Table,
+}
+
+// 图表配置：x 为横轴（饼图为分类），y 为数值列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSpec {
+    pub chart_type: ChartType,
+    pub x: Option<String>,
+    #[serde(default)]
+    pub y: Vec<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+fn is_temporal(value: &str) -> bool {
+    lazy_static::lazy_static! {
+        static ref DATE_RE: Regex = Regex::new(r"^\d{4}-\d{2}(-\d{2})?([ T]\d{2}:\d{2}(:\d{2})?.*)?$").unwrap();
+    }
+    DATE_RE.is_match(value)
+}
+
+// 按列的取值推断角色（DECIMAL 等以字符串返回的数字视为数值）
+pub fn profile_columns(columns: &[String], rows: &[Vec<JsonValue>]) -> Vec<ColumnProfile> {
+    columns.iter().enumerate().map(|(index, name)| {
+        let mut distinct = HashSet::new();
+        let (mut null_count, mut numeric, mut temporal, mut total) = (0, 0, 0, 0);
+        for value in rows.iter().filter_map(|row| row.get(index)) {
+            match value {
+                JsonValue::Null => {
+                    null_count += 1;
+                    continue;
+                }
+                JsonValue::Number(_) => numeric += 1,
+                JsonValue::String(s) if s.trim().parse::<f64>().is_ok() => numeric += 1,
+                JsonValue::String(s) if is_temporal(s) => temporal += 1,
+                _ => {}
+            }
+            total += 1;
+            distinct.insert(value.to_string());
+        }
+        let role = if total > 0 && numeric == total {
+            ColumnRole::Numeric
+        } else if total > 0 && temporal == total {
+            ColumnRole::Temporal
+        } else if distinct.len() <= MAX_BAR_CATEGORIES {
+            ColumnRole::Categorical
+        } else {
+            ColumnRole::Text
+        };
+        ColumnProfile { name: name.clone(), role, distinct_count: distinct.len(), null_count }
+    }).collect()
+}
+
+fn spec(chart_type: ChartType, x: Option<&ColumnProfile>, y: &[&ColumnProfile], reason: &str) -> ChartSpec {
+    ChartSpec {
+        chart_type,
+        x: x.map(|c| c.name.clone()),
+        y: y.iter().map(|c| c.name.clone()).collect(),
+        title: None,
+        reason: reason.to_string(),
+    }
+}
+
+// 按列角色推荐图表，按适合程度排序；没有合适的图表时只返回表格
+pub fn suggest_charts(profiles: &[ColumnProfile]) -> Vec<ChartSpec> {
+    let numeric: Vec<&ColumnProfile> = profiles.iter().filter(|c| c.role == ColumnRole::Numeric).take(MAX_Y_COLUMNS).collect();
+    let temporal = profiles.iter().find(|c| c.role == ColumnRole::Temporal);
+    let category = profiles.iter()
+        .filter(|c| c.role == ColumnRole::Categorical && c.distinct_count > 1)
+        .min_by_key(|c| c.distinct_count);
+
+    let mut suggestions = Vec::new();
+    if !numeric.is_empty() {
+        if let Some(time) = temporal {
+            suggestions.push(spec(ChartType::Line, Some(time), &numeric, "时间列作为横轴，展示数值随时间的变化"));
+            suggestions.push(spec(ChartType::Bar, Some(time), &numeric, "按时间分组比较数值"));
+        }
+        if let Some(category) = category {
+            suggestions.push(spec(ChartType::Bar, Some(category), &numeric, "按分类比较数值"));
+            if category.distinct_count <= MAX_PIE_CATEGORIES {
+                suggestions.push(spec(ChartType::Pie, Some(category), &numeric[..1], "分类较少，展示各分类的占比"));
+            }
+        }
+        if temporal.is_none() && category.is_none() && numeric.len() >= 2 {
+            suggestions.push(spec(ChartType::Scatter, Some(numeric[0]), &numeric[1..2], "两个数值列之间的相关性"));
+        }
+    }
+    suggestions.push(spec(ChartType::Table, None, &[], "以表格展示"));
+    suggestions
+}
+
+// 检查AI返回的图表配置引用的列是否都在结果中
+pub fn validate_spec(spec: &ChartSpec, columns: &[String]) -> Result<(), String> {
+    let missing: Vec<&String> = spec.x.iter().chain(spec.y.iter())
+        .filter(|c| !columns.contains(c))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("图表配置引用了结果中不存在的列: {:?}", missing));
+    }
+    if spec.chart_type != ChartType::Table && (spec.x.is_none() || spec.y.is_empty()) {
+        return Err("图表配置缺少横轴或数值列".to_string());
+    }
+    Ok(())
+}
+
+// 从AI回复中解析图表配置（允许JSON外包裹代码块或说明文字）
+pub fn parse_chart_spec(text: &str) -> Option<ChartSpec> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&text[start..=end]).ok()
+}
+
+// 列统计信息在提示词中的描述
+pub fn format_profiles(profiles: &[ColumnProfile]) -> String {
+    profiles.iter()
+        .map(|p| format!("- {}: {:?}, 不同值 {} 个, 空值 {} 个\n", p.name, p.role, p.distinct_count, p.null_count))
+        .collect()
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_profile_columns() {
+        let rows = vec![
+            vec![json!("2024-01-01"), json!("shipped"), json!("12.50"), JsonValue::Null],
+            vec![json!("2024-01-02"), json!("pending"), json!(7), json!("x")],
+        ];
+        let profiles = profile_columns(&names(&["day", "status", "amount", "note"]), &rows);
+        let roles: Vec<ColumnRole> = profiles.iter().map(|p| p.role).collect();
+        assert_eq!(roles, vec![ColumnRole::Temporal, ColumnRole::Categorical, ColumnRole::Numeric, ColumnRole::Categorical]);
+        assert_eq!(profiles[3].null_count, 1);
+    }
+
+    #[test]
+    fn test_suggest_charts() {
+        let rows = vec![
+            vec![json!("2024-01-01"), json!("shipped"), json!(10)],
+            vec![json!("2024-01-02"), json!("pending"), json!(7)],
+        ];
+        let charts = suggest_charts(&profile_columns(&names(&["day", "status", "total"]), &rows));
+        assert_eq!(charts[0].chart_type, ChartType::Line);
+        assert_eq!((charts[0].x.as_deref(), charts[0].y.clone()), (Some("day"), names(&["total"])));
+        assert!(charts.iter().any(|c| c.chart_type == ChartType::Pie && c.x.as_deref() == Some("status")));
+
+        let rows = vec![vec![json!(1.5), json!(3)], vec![json!(2.5), json!(4)]];
+        let charts = suggest_charts(&profile_columns(&names(&["price", "qty"]), &rows));
+        assert_eq!(charts[0].chart_type, ChartType::Scatter);
+
+        let charts = suggest_charts(&profile_columns(&names(&["name"]), &[vec![json!("a")]]));
+        assert_eq!(charts.len(), 1);
+        assert_eq!(charts[0].chart_type, ChartType::Table);
+    }
+
+    #[test]
+    fn test_validate_spec() {
+        let columns = names(&["status", "total"]);
+        let mut chart = spec(ChartType::Bar, None, &[], "");
+        chart.x = Some("status".to_string());
+        chart.y = names(&["total"]);
+        assert!(validate_spec(&chart, &columns).is_ok());
+        chart.y = names(&["revenue"]);
+        assert!(validate_spec(&chart, &columns).is_err());
+    }
+
+    #[test]
+    fn test_parse_chart_spec() {
+        let text = "```json\n{\"chart_type\": \"pie\", \"x\": \"status\", \"y\": [\"total\"], \"title\": \"订单状态\"}\n```";
+        let chart = parse_chart_spec(text).unwrap();
+        assert_eq!(chart.chart_type, ChartType::Pie);
+        assert_eq!(chart.title.as_deref(), Some("订单状态"));
+        assert_eq!(chart.reason, "");
+        assert!(parse_chart_spec("无法生成图表").is_none());
+        assert!(parse_chart_spec("{\"chart_type\": \"radar\"}").is_none());
+    }
+}
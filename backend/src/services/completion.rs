@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use regex::Regex;
+
+use crate::models::CompletionSuggestion;
+
+// 补全使用的表结构信息
+#[derive(Debug, Clone)]
+pub struct SchemaTable {
+    pub name: String,
+    pub columns: Vec<(String, Option<String>)>,  // (列名, 类型)
+}
+
+// 光标处的补全上下文
+#[derive(Debug, PartialEq)]
+enum CompletionContext {
+    Table,   // FROM / JOIN / INTO / UPDATE 之后
+    Column,  // SELECT / WHERE / ON / SET 等之后
+    Any,
+}
+
+const SQL_KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "JOIN", "LEFT JOIN", "RIGHT JOIN", "INNER JOIN", "ON",
+    "GROUP BY", "ORDER BY", "HAVING", "LIMIT", "OFFSET", "INSERT INTO", "VALUES",
+    "UPDATE", "SET", "DELETE FROM", "AND", "OR", "NOT", "NULL", "IS NULL", "IS NOT NULL",
+    "IN", "LIKE", "BETWEEN", "AS", "DISTINCT", "COUNT", "SUM", "AVG", "MIN", "MAX",
+    "ASC", "DESC", "UNION", "UNION ALL", "CASE", "WHEN", "THEN", "ELSE", "END",
+];
+
+// 将字符偏移转换为字节偏移（超出长度时取末尾）
+fn char_to_byte_offset(sql: &str, cursor: usize) -> usize {
+    sql.char_indices().nth(cursor).map(|(i, _)| i).unwrap_or(sql.len())
+}
+
+// 提取光标前正在输入的单词，返回 (限定名, 前缀)，如 "u.na" -> (Some("u"), "na")
+fn current_word(before: &str) -> (Option<String>, String) {
+    let word: String = before.chars().rev()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+
+    match word.rsplit_once('.') {
+        Some((qualifier, prefix)) => (Some(qualifier.to_string()), prefix.to_string()),
+        None => (None, word),
+    }
+}
+
+// 根据光标前最近的关键字判断上下文
+fn detect_context(before_word: &str) -> CompletionContext {
+    lazy_static::lazy_static! {
+        static ref KEYWORD_RE: Regex = Regex::new(
+            r"(?i)\b(FROM|JOIN|INTO|UPDATE|TABLE|SELECT|WHERE|AND|OR|ON|BY|SET|HAVING)\b"
+        ).unwrap();
+    }
+
+    match KEYWORD_RE.find_iter(before_word).last() {
+        Some(m) => match m.as_str().to_uppercase().as_str() {
+            "FROM" | "JOIN" | "INTO" | "UPDATE" | "TABLE" => {
+                // "FROM a, " 仍是表上下文；"FROM a WHERE" 已被后面的关键字覆盖
+                CompletionContext::Table
+            }
+            _ => CompletionContext::Column,
+        },
+        None => CompletionContext::Any,
+    }
+}
+
+// 解析SQL中的表别名，返回 别名/表名(小写) -> 表名
+fn table_aliases(sql: &str) -> HashMap<String, String> {
+    lazy_static::lazy_static! {
+        static ref ALIAS_RE: Regex = Regex::new(
+            r#"(?i)\b(?:FROM|JOIN|UPDATE|INTO)\s+[`"]?([\w.]+?)[`"]?(?:\s+(?:AS\s+)?(\w+))?(?:\s|,|$)"#
+        ).unwrap();
+        static ref RESERVED: Vec<&'static str> = vec![
+            "where", "join", "left", "right", "inner", "outer", "on", "group", "order", "limit", "set", "values",
+        ];
+    }
+
+    let mut aliases = HashMap::new();
+    for caps in ALIAS_RE.captures_iter(sql) {
+        let table = caps[1].rsplit('.').next().unwrap_or(&caps[1]).to_string();
+        aliases.insert(table.to_lowercase(), table.clone());
+        if let Some(alias) = caps.get(2) {
+            let alias = alias.as_str().to_lowercase();
+            if !RESERVED.contains(&alias.as_str()) {
+                aliases.insert(alias, table.clone());
+            }
+        }
+    }
+    aliases
+}
+
+// 候选项与前缀的匹配得分，不匹配时返回None
+fn match_score(candidate: &str, prefix: &str) -> Option<f64> {
+    if prefix.is_empty() {
+        return Some(10.0);
+    }
+    let candidate_lower = candidate.to_lowercase();
+    let prefix_lower = prefix.to_lowercase();
+    if candidate_lower == prefix_lower {
+        Some(100.0)
+    } else if candidate_lower.starts_with(&prefix_lower) {
+        // 越短的候选越接近输入
+        Some(90.0 - (candidate.len() - prefix.len()).min(30) as f64)
+    } else if candidate_lower.contains(&prefix_lower) {
+        Some(40.0)
+    } else {
+        None
+    }
+}
+
+fn suggestion(label: &str, kind: &str, detail: Option<String>, score: f64) -> CompletionSuggestion {
+    CompletionSuggestion {
+        label: label.to_string(),
+        kind: kind.to_string(),
+        detail,
+        insert_text: label.to_string(),
+        score,
+        source: "local".to_string(),
+    }
+}
+
+// 基于表结构的本地补全（表名、列名、关键字），按得分降序返回
+pub fn local_completions(sql: &str, cursor: usize, tables: &[SchemaTable], limit: usize) -> Vec<CompletionSuggestion> {
+    let before = &sql[..char_to_byte_offset(sql, cursor)];
+    let (qualifier, prefix) = current_word(before);
+    let before_word = &before[..before.len() - prefix.len() - qualifier.as_ref().map(|q| q.len() + 1).unwrap_or(0)];
+
+    let mut suggestions = Vec::new();
+
+    // 限定名：补全指定表（或别名）的列
+    if let Some(qualifier) = qualifier {
+        let aliases = table_aliases(sql);
+        let table_name = aliases.get(&qualifier.to_lowercase()).cloned().unwrap_or(qualifier);
+        if let Some(table) = tables.iter().find(|t| t.name.eq_ignore_ascii_case(&table_name)) {
+            for (column, data_type) in &table.columns {
+                if let Some(score) = match_score(column, &prefix) {
+                    suggestions.push(suggestion(column, "column", data_type.clone(), score + 20.0));
+                }
+            }
+        }
+    } else {
+        let context = detect_context(before_word);
+        let referenced: Vec<String> = table_aliases(sql).into_values().collect();
+
+        for table in tables {
+            if let Some(score) = match_score(&table.name, &prefix) {
+                let boost = if context == CompletionContext::Table { 20.0 } else { 0.0 };
+                suggestions.push(suggestion(&table.name, "table", Some(format!("{} 列", table.columns.len())), score + boost));
+            }
+
+            if context == CompletionContext::Table {
+                continue;
+            }
+            // 列上下文中优先推荐语句中已引用表的列
+            let in_query = referenced.iter().any(|r| r.eq_ignore_ascii_case(&table.name));
+            for (column, data_type) in &table.columns {
+                if let Some(score) = match_score(column, &prefix) {
+                    let boost = match (&context, in_query) {
+                        (CompletionContext::Column, true) => 25.0,
+                        (_, true) => 10.0,
+                        _ => -10.0,
+                    };
+                    let detail = Some(match data_type {
+                        Some(t) => format!("{}.{} ({})", table.name, column, t),
+                        None => format!("{}.{}", table.name, column),
+                    });
+                    suggestions.push(suggestion(column, "column", detail, score + boost));
+                }
+            }
+        }
+
+        if !prefix.is_empty() {
+            for keyword in SQL_KEYWORDS {
+                if let Some(score) = match_score(keyword, &prefix) {
+                    suggestions.push(suggestion(keyword, "keyword", None, score - 5.0));
+                }
+            }
+        }
+    }
+
+    suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    // 同名列可能来自多个表，保留得分最高的一项
+    let mut seen = std::collections::HashSet::new();
+    suggestions.retain(|s| seen.insert((s.kind.clone(), s.label.to_lowercase())));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+// 将表结构格式化为AI提示词中的Schema描述
+pub fn schema_prompt(tables: &[SchemaTable]) -> String {
+    tables.iter()
+        .map(|t| {
+            let columns: Vec<String> = t.columns.iter()
+                .map(|(name, data_type)| match data_type {
+                    Some(dt) => format!("{} {}", name, dt),
+                    None => name.clone(),
+                })
+                .collect();
+            format!("{}({})", t.name, columns.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<SchemaTable> {
+        vec![
+            SchemaTable {
+                name: "users".to_string(),
+                columns: vec![("id".to_string(), Some("int".to_string())), ("name".to_string(), Some("varchar".to_string()))],
+            },
+            SchemaTable {
+                name: "orders".to_string(),
+                columns: vec![("id".to_string(), None), ("user_id".to_string(), None)],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_table_context() {
+        let sql = "SELECT * FROM us";
+        let items = local_completions(sql, sql.len(), &schema(), 10);
+        assert_eq!(items[0].label, "users");
+        assert_eq!(items[0].kind, "table");
+    }
+
+    #[test]
+    fn test_alias_columns() {
+        let sql = "SELECT u.na FROM users u";
+        let items = local_completions(sql, "SELECT u.na".len(), &schema(), 10);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "name");
+    }
+}
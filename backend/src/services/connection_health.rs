@@ -0,0 +1,115 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::models::ConnectionHealthRecord;
+
+// 默认检测间隔（秒），可通过 CONNECTION_HEALTH_INTERVAL_SECS 调整，设为0时关闭后台检测
+pub const DEFAULT_HEALTH_INTERVAL_SECS: u64 = 60;
+// 单次检测超时
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+// 健康检查记录保留天数
+pub const HEALTH_RETENTION_DAYS: i64 = 7;
+// 接口默认返回的历史记录数
+pub const DEFAULT_HEALTH_HISTORY: i64 = 60;
+pub const MAX_HEALTH_HISTORY: i64 = 1000;
+
+// 读取后台检测间隔，为None时表示关闭
+pub fn health_interval() -> Option<Duration> {
+    let secs = std::env::var("CONNECTION_HEALTH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_HEALTH_INTERVAL_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+// 连接状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Up,
+    Down,
+    Unknown,  // 还没有检测记录
+}
+
+// 连接健康概况（history 按时间倒序，用于绘制延迟曲线）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionHealthSummary {
+    pub connection_id: i64,
+    pub status: HealthStatus,
+    pub last_checked_at: Option<i64>,
+    pub last_latency_ms: Option<i64>,
+    pub last_error: Option<String>,
+    pub availability: Option<f64>,     // 历史记录中可用的比例（0-100）
+    pub avg_latency_ms: Option<f64>,   // 可用时的平均延迟
+    pub history: Vec<ConnectionHealthRecord>,
+}
+
+// 汇总检测记录（records 按时间倒序）
+pub fn summarize(connection_id: i64, records: Vec<ConnectionHealthRecord>) -> ConnectionHealthSummary {
+    let latest = records.first();
+    let status = match latest {
+        Some(record) if record.available => HealthStatus::Up,
+        Some(_) => HealthStatus::Down,
+        None => HealthStatus::Unknown,
+    };
+    let availability = (!records.is_empty()).then(|| {
+        records.iter().filter(|r| r.available).count() as f64 * 100.0 / records.len() as f64
+    });
+    let latencies: Vec<i64> = records.iter()
+        .filter(|r| r.available)
+        .filter_map(|r| r.latency_ms)
+        .collect();
+    let avg_latency_ms = (!latencies.is_empty()).then(|| latencies.iter().sum::<i64>() as f64 / latencies.len() as f64);
+
+    ConnectionHealthSummary {
+        connection_id,
+        status,
+        last_checked_at: latest.map(|r| r.checked_at),
+        last_latency_ms: latest.and_then(|r| r.latency_ms),
+        last_error: latest.and_then(|r| r.error.clone()),
+        availability,
+        avg_latency_ms,
+        history: records,
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(checked_at: i64, available: bool, latency_ms: Option<i64>) -> ConnectionHealthRecord {
+        ConnectionHealthRecord {
+            id: None,
+            connection_id: 1,
+            checked_at,
+            available,
+            latency_ms,
+            error: (!available).then(|| "connection refused".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let summary = summarize(1, vec![
+            record(300, false, Some(10_000)),
+            record(200, true, Some(20)),
+            record(100, true, Some(40)),
+            record(0, true, Some(30)),
+        ]);
+        assert_eq!(summary.status, HealthStatus::Down);
+        assert_eq!(summary.last_checked_at, Some(300));
+        assert_eq!(summary.last_error.as_deref(), Some("connection refused"));
+        assert_eq!(summary.availability, Some(75.0));
+        // 不可用时的耗时不计入平均延迟
+        assert_eq!(summary.avg_latency_ms, Some(30.0));
+    }
+
+    #[test]
+    fn test_summarize_without_records() {
+        let summary = summarize(2, Vec::new());
+        assert_eq!(summary.status, HealthStatus::Unknown);
+        assert!(summary.availability.is_none());
+        assert!(summary.avg_latency_ms.is_none());
+    }
+}
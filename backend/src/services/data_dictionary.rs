@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use crate::models::{DictionaryEntry, DictionaryEntryRequest, EnumValueMeaning};
+
+// 业务说明的最大字符数
+const MAX_DESCRIPTION_CHARS: usize = 2000;
+// 单个条目最多的同义词数量
+const MAX_SYNONYMS: usize = 50;
+// 单个条目最多的枚举值数量
+const MAX_ENUM_VALUES: usize = 200;
+
+// 清理并校验数据字典条目：去掉首尾空白、空同义词和重复同义词（不区分大小写）
+pub fn normalize_request(mut req: DictionaryEntryRequest) -> Result<DictionaryEntryRequest, String> {
+    req.table_name = req.table_name.trim().to_string();
+    if req.table_name.is_empty() {
+        return Err("表名不能为空".to_string());
+    }
+    req.column_name = req.column_name.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    req.description = req.description.trim().to_string();
+    if req.description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!("业务说明不能超过 {} 个字符", MAX_DESCRIPTION_CHARS));
+    }
+
+    let mut seen = HashSet::new();
+    req.synonyms = req.synonyms.iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty() && seen.insert(s.to_lowercase()))
+        .collect();
+    if req.synonyms.len() > MAX_SYNONYMS {
+        return Err(format!("同义词不能超过 {} 个", MAX_SYNONYMS));
+    }
+
+    let mut values = HashSet::new();
+    let mut enum_values = Vec::with_capacity(req.enum_values.len());
+    for item in &req.enum_values {
+        let value = item.value.trim().to_string();
+        if value.is_empty() {
+            return Err("枚举值不能为空".to_string());
+        }
+        if !values.insert(value.clone()) {
+            return Err(format!("枚举值重复: {}", value));
+        }
+        enum_values.push(EnumValueMeaning { value, meaning: item.meaning.trim().to_string() });
+    }
+    if enum_values.len() > MAX_ENUM_VALUES {
+        return Err(format!("枚举值不能超过 {} 个", MAX_ENUM_VALUES));
+    }
+    req.enum_values = enum_values;
+
+    if req.description.is_empty() && req.synonyms.is_empty() && req.enum_values.is_empty() {
+        return Err("业务说明、同义词和枚举值至少需要填写一项".to_string());
+    }
+    Ok(req)
+}
+
+// 问题中提到了同义词或业务说明的表（按在 tables 中的顺序，只返回存在的表）
+pub fn matched_tables(question: &str, entries: &[DictionaryEntry], tables: &[String]) -> Vec<String> {
+    let question = question.to_lowercase();
+    let matched: HashSet<&str> = entries.iter()
+        .filter(|entry| {
+            entry.synonyms.0.iter().any(|s| question.contains(&s.to_lowercase()))
+                // 表级说明通常是业务名称（如“订单表”），较短时整体匹配
+                || (entry.column_name.is_empty() && !entry.description.is_empty()
+                    && entry.description.chars().count() <= 20 && question.contains(&entry.description.to_lowercase()))
+        })
+        .map(|entry| entry.table_name.as_str())
+        .collect();
+    tables.iter().filter(|t| matched.contains(t.as_str())).cloned().collect()
+}
+
+fn entry_line(entry: &DictionaryEntry) -> String {
+    let mut line = entry.description.clone();
+    if !entry.synonyms.0.is_empty() {
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&format!("（同义词: {}）", entry.synonyms.0.join("、")));
+    }
+    if !entry.enum_values.0.is_empty() {
+        let values: Vec<String> = entry.enum_values.0.iter()
+            .map(|v| if v.meaning.is_empty() { v.value.clone() } else { format!("{}={}", v.value, v.meaning) })
+            .collect();
+        if !line.is_empty() {
+            line.push('；');
+        }
+        line.push_str(&format!("取值: {}", values.join(", ")));
+    }
+    line
+}
+
+// 表的数据字典在提示词中的描述，没有条目时返回空字符串
+pub fn format_table_dictionary(table: &str, entries: &[DictionaryEntry]) -> String {
+    let entries: Vec<&DictionaryEntry> = entries.iter().filter(|e| e.table_name == table).collect();
+    if entries.is_empty() {
+        return String::new();
+    }
+    let mut text = String::new();
+    if let Some(entry) = entries.iter().find(|e| e.column_name.is_empty()) {
+        text.push_str(&format!("   业务说明: {}\n", entry_line(entry)));
+    }
+    let columns: Vec<&&DictionaryEntry> = entries.iter().filter(|e| !e.column_name.is_empty()).collect();
+    if !columns.is_empty() {
+        text.push_str("   字段业务含义:\n");
+        for entry in columns {
+            text.push_str(&format!("     - {}: {}\n", entry.column_name, entry_line(entry)));
+        }
+    }
+    text
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::types::Json;
+
+    fn entry(table: &str, column: &str, description: &str, synonyms: &[&str], enum_values: &[(&str, &str)]) -> DictionaryEntry {
+        DictionaryEntry {
+            id: None,
+            connection_id: 1,
+            table_name: table.to_string(),
+            column_name: column.to_string(),
+            description: description.to_string(),
+            synonyms: Json(synonyms.iter().map(|s| s.to_string()).collect()),
+            enum_values: Json(enum_values.iter()
+                .map(|(value, meaning)| EnumValueMeaning { value: value.to_string(), meaning: meaning.to_string() })
+                .collect()),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn request(synonyms: &[&str], enum_values: &[(&str, &str)]) -> DictionaryEntryRequest {
+        DictionaryEntryRequest {
+            connection_id: 1,
+            table_name: " t_ord ".to_string(),
+            column_name: Some(" ".to_string()),
+            description: String::new(),
+            synonyms: synonyms.iter().map(|s| s.to_string()).collect(),
+            enum_values: enum_values.iter()
+                .map(|(value, meaning)| EnumValueMeaning { value: value.to_string(), meaning: meaning.to_string() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_request() {
+        let req = normalize_request(request(&["Order", " order ", "", "订单"], &[(" 1 ", " 待支付 ")])).unwrap();
+        assert_eq!(req.table_name, "t_ord");
+        assert_eq!(req.column_name, None);
+        assert_eq!(req.synonyms, vec!["Order", "订单"]);
+        assert_eq!(req.enum_values, vec![EnumValueMeaning { value: "1".to_string(), meaning: "待支付".to_string() }]);
+
+        assert!(normalize_request(request(&[], &[])).is_err());
+        assert!(normalize_request(request(&[], &[("1", "a"), ("1", "b")])).is_err());
+        assert!(normalize_request(request(&[], &[(" ", "a")])).is_err());
+    }
+
+    #[test]
+    fn test_matched_tables() {
+        let entries = vec![
+            entry("t_ord", "", "订单表", &[], &[]),
+            entry("t_cust", "c_nm", "客户名称", &["Customer"], &[]),
+            entry("t_gone", "", "", &["订单"], &[]),
+        ];
+        let tables = vec!["t_cust".to_string(), "t_ord".to_string()];
+        assert_eq!(matched_tables("上个月订单表里每个customer的数量", &entries, &tables), vec!["t_cust", "t_ord"]);
+        assert!(matched_tables("库存", &entries, &tables).is_empty());
+    }
+
+    #[test]
+    fn test_format_table_dictionary() {
+        let entries = vec![
+            entry("t_ord", "", "订单表", &["订单"], &[]),
+            entry("t_ord", "c_stat_cd", "订单状态", &[], &[("1", "待支付"), ("2", "")]),
+            entry("t_cust", "c_nm", "客户名称", &[], &[]),
+        ];
+        assert_eq!(
+            format_table_dictionary("t_ord", &entries),
+            "   业务说明: 订单表 （同义词: 订单）\n   字段业务含义:\n     - c_stat_cd: 订单状态；取值: 1=待支付, 2\n"
+        );
+        assert_eq!(format_table_dictionary("t_user", &entries), "");
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use serde::{Deserialize, Serialize};
+
+// 参与对比的一侧数据（值统一为文本，NULL 为 None）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+// 有差异的行，值按 compared_columns 的顺序排列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowDiff {
+    pub key: Vec<Option<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_columns: Vec<String>,
+    pub source: Option<Vec<Option<String>>>,
+    pub target: Option<Vec<Option<String>>>,
+}
+
+// 数据对比结果（以源为基准：inserted 为仅目标存在的行，deleted 为仅源存在的行）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataDiffResult {
+    pub key_columns: Vec<String>,
+    pub compared_columns: Vec<String>,
+    pub source_only_columns: Vec<String>,
+    pub target_only_columns: Vec<String>,
+    pub source_rows: usize,
+    pub target_rows: usize,
+    pub truncated: bool,  // 任一侧超过行数上限，只对比了前面的行
+    pub inserted: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub inserted_rows: Vec<RowDiff>,
+    pub updated_rows: Vec<RowDiff>,
+    pub deleted_rows: Vec<RowDiff>,
+    pub execution_time_ms: u128,
+}
+
+// 按列名查找列（不区分大小写，兼容不同数据库返回的列名大小写）
+fn column_index(columns: &[String], name: &str) -> Option<usize> {
+    columns.iter().position(|c| c == name)
+        .or_else(|| columns.iter().position(|c| c.eq_ignore_ascii_case(name)))
+}
+
+fn row_checksum(row: &[Option<String>], indexes: &[usize]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for &i in indexes {
+        row[i].hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn pick(row: &[Option<String>], indexes: &[usize]) -> Vec<Option<String>> {
+    indexes.iter().map(|&i| row[i].clone()).collect()
+}
+
+// 按主键匹配两侧的行，用校验和判断行是否变化，每类差异最多返回 max_reported 行明细
+pub fn diff_tables(source: &DiffTable, target: &DiffTable, key_columns: &[String], max_reported: usize) -> Result<DataDiffResult, String> {
+    if key_columns.is_empty() {
+        return Err("没有可用于匹配行的主键列".to_string());
+    }
+    // 查询结果为空时拿不到列名，使用另一侧的列
+    let (empty_source, empty_target);
+    let source = if source.columns.is_empty() {
+        empty_source = DiffTable { columns: target.columns.clone(), rows: Vec::new() };
+        &empty_source
+    } else {
+        source
+    };
+    let target = if target.columns.is_empty() {
+        empty_target = DiffTable { columns: source.columns.clone(), rows: Vec::new() };
+        &empty_target
+    } else {
+        target
+    };
+    if source.columns.is_empty() {
+        return Ok(DataDiffResult { key_columns: key_columns.to_vec(), ..Default::default() });
+    }
+
+    let mut source_keys = Vec::with_capacity(key_columns.len());
+    let mut target_keys = Vec::with_capacity(key_columns.len());
+    for key in key_columns {
+        source_keys.push(column_index(&source.columns, key).ok_or_else(|| format!("源数据中不存在主键列 {}", key))?);
+        target_keys.push(column_index(&target.columns, key).ok_or_else(|| format!("目标数据中不存在主键列 {}", key))?);
+    }
+
+    // 两侧都存在的非主键列参与对比
+    let mut result = DataDiffResult { key_columns: key_columns.to_vec(), ..Default::default() };
+    let mut source_values = Vec::new();
+    let mut target_values = Vec::new();
+    for (i, column) in source.columns.iter().enumerate() {
+        if source_keys.contains(&i) {
+            continue;
+        }
+        match column_index(&target.columns, column) {
+            Some(j) => {
+                result.compared_columns.push(column.clone());
+                source_values.push(i);
+                target_values.push(j);
+            }
+            None => result.source_only_columns.push(column.clone()),
+        }
+    }
+    result.target_only_columns = target.columns.iter().enumerate()
+        .filter(|(j, _)| !target_keys.contains(j) && !target_values.contains(j))
+        .map(|(_, c)| c.clone())
+        .collect();
+    result.source_rows = source.rows.len();
+    result.target_rows = target.rows.len();
+
+    let mut target_index: HashMap<Vec<Option<String>>, (usize, u64)> = HashMap::with_capacity(target.rows.len());
+    for (i, row) in target.rows.iter().enumerate() {
+        let key = pick(row, &target_keys);
+        if target_index.insert(key.clone(), (i, row_checksum(row, &target_values))).is_some() {
+            return Err(format!("目标数据中主键重复: {:?}", key));
+        }
+    }
+
+    let mut matched = HashSet::with_capacity(source.rows.len());
+    for row in &source.rows {
+        let key = pick(row, &source_keys);
+        if !matched.insert(key.clone()) {
+            return Err(format!("源数据中主键重复: {:?}", key));
+        }
+        let Some(&(j, checksum)) = target_index.get(&key) else {
+            result.deleted += 1;
+            if result.deleted_rows.len() < max_reported {
+                result.deleted_rows.push(RowDiff { key, changed_columns: Vec::new(), source: Some(pick(row, &source_values)), target: None });
+            }
+            continue;
+        };
+        if checksum == row_checksum(row, &source_values) {
+            result.unchanged += 1;
+            continue;
+        }
+        result.updated += 1;
+        if result.updated_rows.len() < max_reported {
+            let target_row = &target.rows[j];
+            let changed_columns = source_values.iter().zip(&target_values).enumerate()
+                .filter(|(_, (&s, &t))| row[s] != target_row[t])
+                .map(|(k, _)| result.compared_columns[k].clone())
+                .collect();
+            result.updated_rows.push(RowDiff {
+                key,
+                changed_columns,
+                source: Some(pick(row, &source_values)),
+                target: Some(pick(target_row, &target_values)),
+            });
+        }
+    }
+
+    for row in &target.rows {
+        let key = pick(row, &target_keys);
+        if matched.contains(&key) {
+            continue;
+        }
+        result.inserted += 1;
+        if result.inserted_rows.len() < max_reported {
+            result.inserted_rows.push(RowDiff { key, changed_columns: Vec::new(), source: None, target: Some(pick(row, &target_values)) });
+        }
+    }
+    Ok(result)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(columns: &[&str], rows: &[&[Option<&str>]]) -> DiffTable {
+        DiffTable {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: rows.iter().map(|r| r.iter().map(|v| v.map(|s| s.to_string())).collect()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let source = table(&["id", "name", "price"], &[
+            &[Some("1"), Some("apple"), Some("3.5")],
+            &[Some("2"), Some("pear"), Some("2.0")],
+            &[Some("3"), Some("plum"), None],
+        ]);
+        // 目标列名大小写不同，且多出一列
+        let target = table(&["ID", "name", "price", "note"], &[
+            &[Some("1"), Some("apple"), Some("3.5"), None],
+            &[Some("3"), Some("plum"), Some("1.0"), None],
+            &[Some("4"), Some("kiwi"), Some("4.2"), Some("new")],
+        ]);
+
+        let result = diff_tables(&source, &target, &["id".to_string()], 10).unwrap();
+        assert_eq!(result.compared_columns, vec!["name", "price"]);
+        assert_eq!(result.target_only_columns, vec!["note"]);
+        assert_eq!((result.inserted, result.updated, result.deleted, result.unchanged), (1, 1, 1, 1));
+        assert_eq!(result.updated_rows[0].key, vec![Some("3".to_string())]);
+        assert_eq!(result.updated_rows[0].changed_columns, vec!["price"]);
+        assert_eq!(result.deleted_rows[0].key, vec![Some("2".to_string())]);
+        assert_eq!(result.inserted_rows[0].target, Some(vec![Some("kiwi".to_string()), Some("4.2".to_string())]));
+    }
+
+    #[test]
+    fn test_diff_tables_reports_limit_and_errors() {
+        let source = table(&["id"], &[&[Some("1")], &[Some("2")], &[Some("3")]]);
+        let target = DiffTable::default();
+        let result = diff_tables(&source, &target, &["id".to_string()], 2).unwrap();
+        assert_eq!(result.deleted, 3);
+        assert_eq!(result.deleted_rows.len(), 2);
+
+        assert!(diff_tables(&source, &target, &["missing".to_string()], 2).is_err());
+        let duplicated = table(&["id"], &[&[Some("1")], &[Some("1")]]);
+        assert!(diff_tables(&source, &duplicated, &["id".to_string()], 2).is_err());
+    }
+}
@@ -0,0 +1,647 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Local, NaiveDate};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::db::ddl::quote_identifier;
+use crate::db::DatabaseType;
+
+// 单次最多生成的行数
+pub const MAX_ROWS: usize = 100_000;
+// 默认每批插入的行数（每批在一个事务中执行）
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+// 每批最多插入的行数
+pub const MAX_BATCH_SIZE: usize = 5_000;
+// 外键列从引用表读取的候选值数量
+pub const MAX_REFERENCE_VALUES: usize = 1_000;
+// 响应中返回的预览行数
+pub const PREVIEW_ROWS: usize = 10;
+
+const FIRST_NAMES: &[&str] = &[
+    "James", "Mary", "John", "Linda", "Robert", "Emma", "Michael", "Olivia", "David", "Sophia",
+    "William", "Ava", "Daniel", "Mia", "Lucas", "Chloe", "Henry", "Grace", "Leo", "Zoe",
+];
+const LAST_NAMES: &[&str] = &[
+    "Smith", "Johnson", "Brown", "Taylor", "Miller", "Wilson", "Moore", "Clark", "Lewis", "Walker",
+    "Hall", "Young", "King", "Wright", "Green", "Baker", "Adams", "Nelson", "Carter", "Turner",
+];
+const CITIES: &[&str] = &[
+    "Beijing", "Shanghai", "Shenzhen", "Hangzhou", "Chengdu", "London", "Paris", "Berlin", "Tokyo", "Singapore",
+    "New York", "San Francisco", "Sydney", "Toronto", "Seoul",
+];
+const COUNTRIES: &[&str] = &[
+    "China", "United States", "United Kingdom", "France", "Germany", "Japan", "Singapore", "Australia", "Canada", "South Korea",
+];
+const STREET_SUFFIXES: &[&str] = &["Street", "Road", "Avenue", "Lane", "Boulevard"];
+const COMPANY_SUFFIXES: &[&str] = &["Inc.", "Ltd.", "Group", "Technologies", "Holdings", "Labs"];
+// 保留给示例使用的域名，生成的邮箱和URL不会指向真实站点
+const DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+const WORDS: &[&str] = &[
+    "alpha", "bright", "cloud", "delta", "eagle", "forest", "garden", "harbor", "island", "jade",
+    "kernel", "lotus", "maple", "nova", "ocean", "pixel", "quartz", "river", "summit", "tiger",
+    "unity", "vector", "willow", "yellow", "zenith", "amber", "breeze", "coral", "dune", "ember",
+];
+
+// 列的取值模式（kind 字段区分），可由规则推断、AI建议或请求中指定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValuePattern {
+    FullName,
+    FirstName,
+    LastName,
+    Username,
+    Email,
+    Phone,
+    Url,
+    Address,
+    City,
+    Country,
+    Company,
+    Title,  // 短语（标题、商品名等）
+    Text,   // 句子（描述、备注等）
+    Word,
+    Uuid,
+    Boolean,
+    Integer { min: i64, max: i64 },
+    Decimal {
+        min: f64,
+        max: f64,
+        #[serde(default = "default_scale")]
+        scale: u32,
+    },
+    Date {
+        #[serde(default = "default_days_back")]
+        days_back: i64,
+    },
+    DateTime {
+        #[serde(default = "default_days_back")]
+        days_back: i64,
+    },
+    Time,
+    Choice { values: Vec<JsonValue> },
+    Sequence {
+        #[serde(default = "default_sequence_start")]
+        start: i64,
+    },
+    Json,
+    Null,
+    // 从引用表已有的值中随机选择，values 在生成前从数据库读取
+    ForeignKey {
+        table: String,
+        column: String,
+        #[serde(skip)]
+        values: Vec<JsonValue>,
+    },
+}
+
+fn default_scale() -> u32 {
+    2
+}
+
+fn default_days_back() -> i64 {
+    365 * 3
+}
+
+fn default_sequence_start() -> i64 {
+    1
+}
+
+impl ValuePattern {
+    // 校验请求或AI给出的模式参数
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ValuePattern::Integer { min, max } if min > max => Err(format!("整数范围无效: {} > {}", min, max)),
+            ValuePattern::Decimal { min, max, scale } => {
+                if !min.is_finite() || !max.is_finite() || min > max {
+                    Err(format!("小数范围无效: {} ~ {}", min, max))
+                } else if *scale > 10 {
+                    Err(format!("小数位数不能超过10: {}", scale))
+                } else {
+                    Ok(())
+                }
+            }
+            ValuePattern::Date { days_back } | ValuePattern::DateTime { days_back } if !(0..=36_500).contains(days_back) => {
+                Err(format!("日期范围天数必须在 0 到 36500 之间: {}", days_back))
+            }
+            ValuePattern::Choice { values } if values.is_empty() => Err("choice 的候选值不能为空".to_string()),
+            ValuePattern::ForeignKey { table, column, .. } if table.trim().is_empty() || column.trim().is_empty() => {
+                Err("foreign_key 需要指定 table 和 column".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // 生成字符串的模式（唯一列通过追加后缀去重）
+    fn is_textual(&self) -> bool {
+        matches!(self,
+            ValuePattern::FullName | ValuePattern::FirstName | ValuePattern::LastName | ValuePattern::Username
+            | ValuePattern::Email | ValuePattern::Phone | ValuePattern::Url | ValuePattern::Address
+            | ValuePattern::City | ValuePattern::Country | ValuePattern::Company | ValuePattern::Title
+            | ValuePattern::Text | ValuePattern::Word)
+    }
+}
+
+// 列模式的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternSource {
+    Rule,
+    Ai,
+    Override,
+    ForeignKey,
+}
+
+// 单列的生成计划
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnPlan {
+    pub name: String,
+    pub pattern: ValuePattern,
+    pub unique: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    pub source: PatternSource,
+}
+
+// 将列名拆分为小写单词（下划线、连字符和驼峰）
+fn name_tokens(name: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+// 整数类型（含 PostgreSQL 的 serial）
+pub fn is_integer_type(data_type: &str) -> bool {
+    let data_type = data_type.to_lowercase();
+    (data_type.contains("int") && !data_type.contains("interval") && !data_type.contains("point"))
+        || data_type.contains("serial")
+}
+
+// 按列名和类型推断取值模式
+pub fn infer_pattern(name: &str, data_type: &str) -> ValuePattern {
+    let data_type = data_type.to_lowercase();
+    let lower = name.to_lowercase();
+    let tokens = name_tokens(name);
+    let has_token = |keys: &[&str]| tokens.iter().any(|t| keys.contains(&t.as_str()));
+    let contains = |keys: &[&str]| keys.iter().any(|k| lower.contains(k));
+
+    if data_type.contains("bool") || data_type == "bit" || data_type == "bit(1)" || data_type == "tinyint(1)"
+        || (data_type.starts_with("tinyint") && has_token(&["is", "has", "enabled", "active", "deleted"])) {
+        return ValuePattern::Boolean;
+    }
+    if data_type.contains("uuid") || data_type.contains("uniqueidentifier") || has_token(&["uuid", "guid"]) {
+        return ValuePattern::Uuid;
+    }
+    if data_type.contains("timestamp") || data_type.contains("datetime") {
+        return ValuePattern::DateTime { days_back: default_days_back() };
+    }
+    if data_type.contains("date") {
+        return ValuePattern::Date { days_back: default_days_back() };
+    }
+    if data_type.starts_with("time") {
+        return ValuePattern::Time;
+    }
+    if data_type == "year" {
+        return ValuePattern::Integer { min: 1990, max: 2030 };
+    }
+    if data_type.contains("json") {
+        return ValuePattern::Json;
+    }
+    if is_integer_type(&data_type) {
+        let (min, max) = if has_token(&["age"]) {
+            (18, 80)
+        } else if has_token(&["year"]) {
+            (1990, 2030)
+        } else if has_token(&["month"]) {
+            (1, 12)
+        } else if has_token(&["day"]) {
+            (1, 28)
+        } else if has_token(&["score", "rating", "percent"]) {
+            (0, 100)
+        } else if has_token(&["status", "type", "level", "state", "flag", "priority"]) {
+            (0, 5)
+        } else if has_token(&["quantity", "qty", "count", "num", "stock", "views"]) {
+            (0, 1000)
+        } else if has_token(&["price", "amount", "cost", "total", "salary"]) {
+            (1, 10_000)
+        } else {
+            (1, 100_000)
+        };
+        // 按整数类型的取值范围截断
+        let type_max = if data_type.starts_with("tinyint") {
+            127
+        } else if data_type.starts_with("smallint") || data_type.starts_with("int2") || data_type == "smallserial" {
+            32_767
+        } else {
+            i64::MAX
+        };
+        return ValuePattern::Integer { min: min.min(type_max), max: max.min(type_max) };
+    }
+    let is_decimal = ["decimal", "numeric", "float", "double", "real", "money", "number"].iter().any(|t| data_type.contains(t));
+    if is_decimal {
+        let (min, max, scale) = if has_token(&["lat", "latitude"]) {
+            (-90.0, 90.0, 6)
+        } else if has_token(&["lng", "lon", "longitude"]) {
+            (-180.0, 180.0, 6)
+        } else if has_token(&["rate", "ratio", "percent", "discount"]) {
+            (0.0, 1.0, 4)
+        } else if has_token(&["price", "amount", "cost", "total", "fee", "salary", "balance"]) {
+            (1.0, 10_000.0, 2)
+        } else {
+            (0.0, 1000.0, 2)
+        };
+        return ValuePattern::Decimal { min, max, scale };
+    }
+
+    // 字符串类型按列名推断
+    if contains(&["email", "mail"]) {
+        ValuePattern::Email
+    } else if contains(&["phone", "mobile"]) || has_token(&["tel", "fax"]) {
+        ValuePattern::Phone
+    } else if contains(&["url", "website", "homepage", "avatar", "image", "photo"]) || has_token(&["link", "img"]) {
+        ValuePattern::Url
+    } else if contains(&["username", "nickname", "login"]) || has_token(&["account"]) || (has_token(&["user"]) && has_token(&["name"])) {
+        ValuePattern::Username
+    } else if contains(&["firstname", "first_name", "given_name"]) || (has_token(&["first"]) && has_token(&["name"])) {
+        ValuePattern::FirstName
+    } else if contains(&["lastname", "last_name", "surname", "family_name"]) || (has_token(&["last"]) && has_token(&["name"])) {
+        ValuePattern::LastName
+    } else if contains(&["company", "organization", "employer", "vendor", "supplier"]) || has_token(&["org"]) {
+        ValuePattern::Company
+    } else if has_token(&["city"]) {
+        ValuePattern::City
+    } else if has_token(&["country", "nation"]) {
+        ValuePattern::Country
+    } else if contains(&["address", "street"]) || has_token(&["addr"]) {
+        ValuePattern::Address
+    } else if has_token(&["status"]) {
+        ValuePattern::Choice { values: vec![json!("active"), json!("inactive"), json!("pending")] }
+    } else if has_token(&["gender", "sex"]) {
+        ValuePattern::Choice { values: vec![json!("male"), json!("female")] }
+    } else if contains(&["desc", "remark", "comment", "content", "note", "summary", "detail"]) || has_token(&["body", "bio"]) {
+        ValuePattern::Text
+    } else if has_token(&["title", "subject", "headline"]) {
+        ValuePattern::Title
+    } else if has_token(&["name"]) {
+        // name / full_name / customer_name 等为人名，其余 *_name（商品名等）使用短语
+        if tokens.len() == 1 || has_token(&["full", "real", "contact", "customer", "author", "person", "display"]) {
+            ValuePattern::FullName
+        } else {
+            ValuePattern::Title
+        }
+    } else if lower.ends_with("_at") || has_token(&["time"]) {
+        ValuePattern::DateTime { days_back: default_days_back() }
+    } else if has_token(&["date", "birthday"]) {
+        ValuePattern::Date { days_back: default_days_back() }
+    } else {
+        ValuePattern::Word
+    }
+}
+
+// 从类型中解析字符串的最大长度，如 varchar(50)、character varying(50)
+pub fn parse_max_length(data_type: &str) -> Option<usize> {
+    lazy_static::lazy_static! {
+        static ref LENGTH_RE: Regex = Regex::new(r"(?i)char(?:acter)?(?:\s+varying)?\s*\(\s*(\d+)\s*\)").unwrap();
+    }
+    LENGTH_RE.captures(data_type).and_then(|c| c[1].parse().ok()).filter(|&n| n > 0)
+}
+
+// 按字符截断
+fn truncate_chars(value: &str, max_length: usize) -> String {
+    value.chars().take(max_length).collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// 随机数据生成器：指定 seed 时结果可重复
+pub struct DataGenerator {
+    rng: StdRng,
+    run_tag: String,  // 本次生成的随机标记，追加到唯一列的值中，降低与表中已有数据冲突的概率
+    today: NaiveDate,
+}
+
+impl DataGenerator {
+    pub fn new(seed: Option<u64>) -> Self {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let run_tag = (0..4).map(|_| (b'a' + rng.gen_range(0..26)) as char).collect();
+        Self { rng, run_tag, today: Local::now().date_naive() }
+    }
+
+    // 生成从第 start 行开始的 count 行（行号用于序列和唯一值）
+    pub fn generate_rows(&mut self, plans: &[ColumnPlan], start: usize, count: usize) -> Vec<Vec<JsonValue>> {
+        (start..start + count)
+            .map(|index| plans.iter().map(|plan| self.column_value(plan, index)).collect())
+            .collect()
+    }
+
+    fn column_value(&mut self, plan: &ColumnPlan, index: usize) -> JsonValue {
+        if plan.unique {
+            match &plan.pattern {
+                // 一对一外键：按顺序使用引用值，用完后为NULL
+                ValuePattern::ForeignKey { values, .. } => return values.get(index).cloned().unwrap_or(JsonValue::Null),
+                ValuePattern::Integer { min, .. } => return json!(min.saturating_add(index as i64)),
+                pattern if pattern.is_textual() => {
+                    let base = self.pattern_value(pattern, index);
+                    return json!(self.unique_text(base.as_str().unwrap_or_default(), pattern, index, plan.max_length));
+                }
+                _ => {}
+            }
+        }
+        match self.pattern_value(&plan.pattern, index) {
+            JsonValue::String(s) => match plan.max_length {
+                Some(max_length) => json!(truncate_chars(&s, max_length)),
+                None => JsonValue::String(s),
+            },
+            value => value,
+        }
+    }
+
+    // 唯一列追加 "_<标记><行号>" 后缀（邮箱加在 @ 之前），超长时截断前缀保留后缀
+    fn unique_text(&self, base: &str, pattern: &ValuePattern, index: usize, max_length: Option<usize>) -> String {
+        let suffix = format!("_{}{}", self.run_tag, index);
+        let (local, domain) = match (pattern, base.split_once('@')) {
+            (ValuePattern::Email, Some((local, domain))) => (local, format!("@{}", domain)),
+            _ => (base, String::new()),
+        };
+        let local = match max_length {
+            Some(max_length) => {
+                let available = max_length.saturating_sub(suffix.chars().count() + domain.chars().count());
+                truncate_chars(local, available)
+            }
+            None => local.to_string(),
+        };
+        let value = format!("{}{}{}", local, suffix, domain);
+        match max_length {
+            Some(max_length) => truncate_chars(&value, max_length),
+            None => value,
+        }
+    }
+
+    fn pick(&mut self, values: &[&'static str]) -> &'static str {
+        values.choose(&mut self.rng).copied().unwrap_or_default()
+    }
+
+    fn words(&mut self, min: usize, max: usize) -> Vec<&'static str> {
+        let count = self.rng.gen_range(min..=max);
+        (0..count).map(|_| self.pick(WORDS)).collect()
+    }
+
+    fn pattern_value(&mut self, pattern: &ValuePattern, index: usize) -> JsonValue {
+        match pattern {
+            ValuePattern::FullName => json!(format!("{} {}", self.pick(FIRST_NAMES), self.pick(LAST_NAMES))),
+            ValuePattern::FirstName => json!(self.pick(FIRST_NAMES)),
+            ValuePattern::LastName => json!(self.pick(LAST_NAMES)),
+            ValuePattern::Username => {
+                let name = self.pick(FIRST_NAMES).to_lowercase();
+                json!(format!("{}{}", name, self.rng.gen_range(1..10_000)))
+            }
+            ValuePattern::Email => {
+                let first = self.pick(FIRST_NAMES).to_lowercase();
+                let last = self.pick(LAST_NAMES).to_lowercase();
+                let number = self.rng.gen_range(1..100);
+                json!(format!("{}.{}{}@{}", first, last, number, self.pick(DOMAINS)))
+            }
+            ValuePattern::Phone => json!(format!("1{}{:09}", self.rng.gen_range(3..=9), self.rng.gen_range(0..1_000_000_000u32))),
+            ValuePattern::Url => {
+                let domain = self.pick(DOMAINS);
+                json!(format!("https://www.{}/{}", domain, self.pick(WORDS)))
+            }
+            ValuePattern::Address => {
+                let number = self.rng.gen_range(1..2000);
+                let street = capitalize(self.pick(WORDS));
+                json!(format!("{} {} {}", number, street, self.pick(STREET_SUFFIXES)))
+            }
+            ValuePattern::City => json!(self.pick(CITIES)),
+            ValuePattern::Country => json!(self.pick(COUNTRIES)),
+            ValuePattern::Company => {
+                let name = capitalize(self.pick(WORDS));
+                json!(format!("{} {}", name, self.pick(COMPANY_SUFFIXES)))
+            }
+            ValuePattern::Title => {
+                let words: Vec<String> = self.words(2, 4).into_iter().map(capitalize).collect();
+                json!(words.join(" "))
+            }
+            ValuePattern::Text => json!(format!("{}.", capitalize(&self.words(8, 20).join(" ")))),
+            ValuePattern::Word => json!(self.pick(WORDS)),
+            ValuePattern::Uuid => json!(uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid().to_string()),
+            ValuePattern::Boolean => json!(self.rng.gen_bool(0.5)),
+            ValuePattern::Integer { min, max } => json!(self.rng.gen_range(*min..=*max)),
+            ValuePattern::Decimal { min, max, scale } => {
+                let factor = 10f64.powi(*scale as i32);
+                let value = (self.rng.gen_range(*min..=*max) * factor).round() / factor;
+                serde_json::Number::from_f64(value).map(JsonValue::Number).unwrap_or(JsonValue::Null)
+            }
+            ValuePattern::Date { days_back } => {
+                let date = self.today - Duration::days(self.rng.gen_range(0..=*days_back));
+                json!(date.format("%Y-%m-%d").to_string())
+            }
+            ValuePattern::DateTime { days_back } => {
+                let date = self.today - Duration::days(self.rng.gen_range(0..=*days_back));
+                let seconds = self.rng.gen_range(0..86_400);
+                json!(format!("{} {:02}:{:02}:{:02}", date.format("%Y-%m-%d"), seconds / 3600, seconds / 60 % 60, seconds % 60))
+            }
+            ValuePattern::Time => {
+                let seconds = self.rng.gen_range(0..86_400);
+                json!(format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60))
+            }
+            ValuePattern::Choice { values } => values.choose(&mut self.rng).cloned().unwrap_or(JsonValue::Null),
+            ValuePattern::Sequence { start } => json!(start.saturating_add(index as i64)),
+            ValuePattern::Json => {
+                let key = self.pick(WORDS);
+                json!({ "key": key, "value": self.rng.gen_range(0..1000) })
+            }
+            ValuePattern::Null => JsonValue::Null,
+            ValuePattern::ForeignKey { values, .. } => values.choose(&mut self.rng).cloned().unwrap_or(JsonValue::Null),
+        }
+    }
+}
+
+// 读取外键引用表中已有值的SQL
+pub fn reference_values_sql(db_type: DatabaseType, table: &str, column: &str) -> Result<String, String> {
+    let column = quote_identifier(db_type, column)?;
+    Ok(format!(
+        "SELECT DISTINCT {0} FROM {1} WHERE {0} IS NOT NULL LIMIT {2}",
+        column, quote_identifier(db_type, table)?, MAX_REFERENCE_VALUES
+    ))
+}
+
+// 读取唯一整数列当前最大值的SQL，生成的序列从最大值之后开始
+pub fn max_value_sql(db_type: DatabaseType, table: &str, column: &str) -> Result<String, String> {
+    Ok(format!("SELECT MAX({}) FROM {}", quote_identifier(db_type, column)?, quote_identifier(db_type, table)?))
+}
+
+// 解析AI回复中的列模式（JSON对象：列名 -> 模式），无法识别或参数无效的列被忽略
+pub fn parse_value_patterns(text: &str) -> HashMap<String, ValuePattern> {
+    let (Some(start), Some(end)) = (text.find('{'), text.rfind('}')) else {
+        return HashMap::new();
+    };
+    if end < start {
+        return HashMap::new();
+    }
+    let Ok(object) = serde_json::from_str::<HashMap<String, JsonValue>>(&text[start..=end]) else {
+        return HashMap::new();
+    };
+    object.into_iter()
+        .filter_map(|(column, value)| {
+            let pattern: ValuePattern = serde_json::from_value(value).ok()?;
+            // AI不能指定外键（外键由表结构决定）
+            if matches!(pattern, ValuePattern::ForeignKey { .. }) || pattern.validate().is_err() {
+                return None;
+            }
+            Some((column, pattern))
+        })
+        .collect()
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(name: &str, pattern: ValuePattern, unique: bool, max_length: Option<usize>) -> ColumnPlan {
+        ColumnPlan { name: name.to_string(), pattern, unique, max_length, source: PatternSource::Rule }
+    }
+
+    #[test]
+    fn test_name_tokens() {
+        assert_eq!(name_tokens("userName"), vec!["user", "name"]);
+        assert_eq!(name_tokens("created_at"), vec!["created", "at"]);
+        assert_eq!(name_tokens("HTTPStatus"), vec!["httpstatus"]);
+    }
+
+    #[test]
+    fn test_infer_pattern() {
+        assert_eq!(infer_pattern("email", "varchar(255)"), ValuePattern::Email);
+        assert_eq!(infer_pattern("contact_phone", "varchar"), ValuePattern::Phone);
+        assert_eq!(infer_pattern("userName", "text"), ValuePattern::Username);
+        assert_eq!(infer_pattern("name", "varchar"), ValuePattern::FullName);
+        assert_eq!(infer_pattern("product_name", "varchar"), ValuePattern::Title);
+        assert_eq!(infer_pattern("description", "text"), ValuePattern::Text);
+        assert_eq!(infer_pattern("is_active", "tinyint"), ValuePattern::Boolean);
+        assert_eq!(infer_pattern("enabled", "boolean"), ValuePattern::Boolean);
+        assert_eq!(infer_pattern("age", "int"), ValuePattern::Integer { min: 18, max: 80 });
+        assert_eq!(infer_pattern("page", "int"), ValuePattern::Integer { min: 1, max: 100_000 });
+        assert_eq!(infer_pattern("level", "tinyint"), ValuePattern::Integer { min: 0, max: 5 });
+        assert_eq!(infer_pattern("views", "smallint"), ValuePattern::Integer { min: 0, max: 1000 });
+        assert_eq!(infer_pattern("price", "decimal"), ValuePattern::Decimal { min: 1.0, max: 10_000.0, scale: 2 });
+        assert_eq!(infer_pattern("created_at", "timestamp without time zone"), ValuePattern::DateTime { days_back: 365 * 3 });
+        assert_eq!(infer_pattern("created_at", "TEXT"), ValuePattern::DateTime { days_back: 365 * 3 });
+        assert_eq!(infer_pattern("birthday", "date"), ValuePattern::Date { days_back: 365 * 3 });
+        assert_eq!(infer_pattern("id", "uuid"), ValuePattern::Uuid);
+        assert_eq!(infer_pattern("settings", "jsonb"), ValuePattern::Json);
+        assert_eq!(infer_pattern("code", "varchar"), ValuePattern::Word);
+    }
+
+    #[test]
+    fn test_parse_max_length() {
+        assert_eq!(parse_max_length("varchar(20)"), Some(20));
+        assert_eq!(parse_max_length("CHARACTER VARYING(8)"), Some(8));
+        assert_eq!(parse_max_length("decimal(10,2)"), None);
+        assert_eq!(parse_max_length("text"), None);
+    }
+
+    #[test]
+    fn test_generate_rows_is_reproducible() {
+        let plans = vec![
+            plan("id", ValuePattern::Sequence { start: 100 }, false, None),
+            plan("name", ValuePattern::FullName, false, None),
+            plan("price", ValuePattern::Decimal { min: 1.0, max: 2.0, scale: 2 }, false, None),
+            plan("created_at", ValuePattern::DateTime { days_back: 30 }, false, None),
+        ];
+        let rows = DataGenerator::new(Some(7)).generate_rows(&plans, 0, 5);
+        assert_eq!(rows, DataGenerator::new(Some(7)).generate_rows(&plans, 0, 5));
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0][0], json!(100));
+        assert_eq!(rows[4][0], json!(104));
+        for row in &rows {
+            let price = row[2].as_f64().unwrap();
+            assert!((1.0..=2.0).contains(&price));
+            assert_eq!(row[3].as_str().unwrap().len(), "2024-01-01 00:00:00".len());
+        }
+    }
+
+    #[test]
+    fn test_unique_values() {
+        let plans = vec![
+            plan("email", ValuePattern::Email, true, Some(30)),
+            plan("code", ValuePattern::Choice { values: vec![json!("a")] }, false, None),
+            plan("sku", ValuePattern::Word, true, Some(8)),
+            plan("rank", ValuePattern::Integer { min: 10, max: 20 }, true, None),
+        ];
+        let rows = DataGenerator::new(Some(1)).generate_rows(&plans, 0, 200);
+        for column in [0, 2, 3] {
+            let distinct: std::collections::HashSet<String> = rows.iter().map(|r| r[column].to_string()).collect();
+            assert_eq!(distinct.len(), rows.len());
+        }
+        for row in &rows {
+            let email = row[0].as_str().unwrap();
+            assert!(email.contains('@') && email.chars().count() <= 30);
+            assert!(row[2].as_str().unwrap().chars().count() <= 8);
+        }
+        assert_eq!(rows[199][3], json!(209));
+    }
+
+    #[test]
+    fn test_foreign_key_values() {
+        let values = vec![json!(1), json!(2)];
+        let fk = ValuePattern::ForeignKey { table: "users".to_string(), column: "id".to_string(), values };
+        let rows = DataGenerator::new(Some(3)).generate_rows(&[plan("user_id", fk.clone(), false, None)], 0, 20);
+        assert!(rows.iter().all(|r| r[0] == json!(1) || r[0] == json!(2)));
+        // 唯一外键按顺序使用引用值
+        let rows = DataGenerator::new(Some(3)).generate_rows(&[plan("user_id", fk, true, None)], 0, 3);
+        assert_eq!(rows, vec![vec![json!(1)], vec![json!(2)], vec![JsonValue::Null]]);
+    }
+
+    #[test]
+    fn test_parse_value_patterns() {
+        let text = "建议如下：{\"status\": {\"kind\": \"choice\", \"values\": [\"paid\", \"refunded\"]}, \
+            \"age\": {\"kind\": \"integer\", \"min\": 60, \"max\": 10}, \
+            \"owner_id\": {\"kind\": \"foreign_key\", \"table\": \"users\", \"column\": \"id\"}, \
+            \"score\": {\"kind\": \"decimal\", \"min\": 0, \"max\": 5}, \"note\": {\"kind\": \"unknown\"}}";
+        let patterns = parse_value_patterns(text);
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns["status"], ValuePattern::Choice { values: vec![json!("paid"), json!("refunded")] });
+        assert_eq!(patterns["score"], ValuePattern::Decimal { min: 0.0, max: 5.0, scale: 2 });
+        assert!(parse_value_patterns("无法给出建议").is_empty());
+    }
+
+    #[test]
+    fn test_sql_helpers() {
+        assert_eq!(
+            reference_values_sql(DatabaseType::MySQL, "users", "id").unwrap(),
+            "SELECT DISTINCT `id` FROM `users` WHERE `id` IS NOT NULL LIMIT 1000"
+        );
+        assert_eq!(max_value_sql(DatabaseType::PostgreSQL, "orders", "no").unwrap(), "SELECT MAX(\"no\") FROM \"orders\"");
+    }
+}
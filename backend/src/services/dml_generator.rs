@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::db::ddl::{quote_identifier, quote_literal};
+use crate::db::DatabaseType;
+
+// 生成的语句类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DmlKind {
+    #[default]
+    Insert,
+    Update,  // 按主键更新其余列
+    Delete,  // 按主键删除
+}
+
+// 将结果中的JSON值转换为对应方言的SQL字面量
+pub fn sql_literal(db_type: DatabaseType, value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "NULL".to_string(),
+        JsonValue::Bool(b) => match db_type {
+            DatabaseType::SQLite => if *b { "1" } else { "0" }.to_string(),
+            _ => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        },
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => quote_literal(db_type, s),
+        other => quote_literal(db_type, &other.to_string()),
+    }
+}
+
+// 由结果行生成 INSERT / UPDATE / DELETE 语句（每行一条，不含结尾分号）
+pub fn generate_dml(
+    db_type: DatabaseType,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<JsonValue>],
+    kind: DmlKind,
+    key_columns: &[String],
+) -> Result<Vec<String>, String> {
+    if columns.is_empty() {
+        return Err("列不能为空".to_string());
+    }
+    let quoted_table = quote_identifier(db_type, table)?;
+    let quoted_columns = columns.iter()
+        .map(|c| quote_identifier(db_type, c))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // UPDATE / DELETE 需要主键列出现在结果中
+    let key_indexes = if kind == DmlKind::Insert {
+        Vec::new()
+    } else {
+        if key_columns.is_empty() {
+            return Err("生成 UPDATE / DELETE 语句需要主键列".to_string());
+        }
+        key_columns.iter()
+            .map(|key| columns.iter().position(|c| c == key).ok_or_else(|| format!("结果中缺少主键列 {}", key)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    if kind == DmlKind::Update && key_indexes.len() == columns.len() {
+        return Err("结果中除主键外没有可更新的列".to_string());
+    }
+
+    let mut statements = Vec::with_capacity(rows.len());
+    for (row_number, row) in rows.iter().enumerate() {
+        if row.len() != columns.len() {
+            return Err(format!("第 {} 行有 {} 个值，与列数 {} 不一致", row_number + 1, row.len(), columns.len()));
+        }
+        let mut where_clauses = Vec::with_capacity(key_indexes.len());
+        for &i in &key_indexes {
+            if row[i].is_null() {
+                return Err(format!("第 {} 行的主键列 {} 为空", row_number + 1, columns[i]));
+            }
+            where_clauses.push(format!("{} = {}", quoted_columns[i], sql_literal(db_type, &row[i])));
+        }
+        statements.push(match kind {
+            DmlKind::Insert => format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                quoted_table,
+                quoted_columns.join(", "),
+                row.iter().map(|v| sql_literal(db_type, v)).collect::<Vec<_>>().join(", ")
+            ),
+            DmlKind::Update => format!(
+                "UPDATE {} SET {} WHERE {}",
+                quoted_table,
+                (0..columns.len())
+                    .filter(|i| !key_indexes.contains(i))
+                    .map(|i| format!("{} = {}", quoted_columns[i], sql_literal(db_type, &row[i])))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                where_clauses.join(" AND ")
+            ),
+            DmlKind::Delete => format!("DELETE FROM {} WHERE {}", quoted_table, where_clauses.join(" AND ")),
+        });
+    }
+    Ok(statements)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn columns() -> Vec<String> {
+        vec!["id".to_string(), "name".to_string(), "active".to_string()]
+    }
+
+    #[test]
+    fn test_sql_literal() {
+        assert_eq!(sql_literal(DatabaseType::PostgreSQL, &json!("O'Brien")), "'O''Brien'");
+        assert_eq!(sql_literal(DatabaseType::MySQL, &json!("C:\\temp")), "'C:\\\\temp'");
+        assert_eq!(sql_literal(DatabaseType::SQLite, &json!(true)), "1");
+        assert_eq!(sql_literal(DatabaseType::MySQL, &json!({"a": 1})), "'{\"a\":1}'");
+        assert_eq!(sql_literal(DatabaseType::PostgreSQL, &JsonValue::Null), "NULL");
+    }
+
+    #[test]
+    fn test_generate_dml() {
+        let rows = vec![vec![json!(1), json!("alice"), json!(true)], vec![json!(2), JsonValue::Null, json!(false)]];
+        let keys = vec!["id".to_string()];
+
+        let inserts = generate_dml(DatabaseType::MySQL, "users", &columns(), &rows, DmlKind::Insert, &[]).unwrap();
+        assert_eq!(inserts[0], "INSERT INTO `users` (`id`, `name`, `active`) VALUES (1, 'alice', TRUE)");
+        assert_eq!(inserts[1], "INSERT INTO `users` (`id`, `name`, `active`) VALUES (2, NULL, FALSE)");
+
+        let updates = generate_dml(DatabaseType::PostgreSQL, "users", &columns(), &rows, DmlKind::Update, &keys).unwrap();
+        assert_eq!(updates[0], "UPDATE \"users\" SET \"name\" = 'alice', \"active\" = TRUE WHERE \"id\" = 1");
+
+        let deletes = generate_dml(DatabaseType::SQLite, "users", &columns(), &rows, DmlKind::Delete, &keys).unwrap();
+        assert_eq!(deletes[1], "DELETE FROM \"users\" WHERE \"id\" = 2");
+    }
+
+    #[test]
+    fn test_generate_dml_errors() {
+        let rows = vec![vec![json!(1), json!("alice")]];
+        assert!(generate_dml(DatabaseType::MySQL, "users", &columns(), &rows, DmlKind::Insert, &[]).is_err());
+        let rows = vec![vec![json!(1), json!("alice"), json!(true)]];
+        assert!(generate_dml(DatabaseType::MySQL, "users", &columns(), &rows, DmlKind::Delete, &[]).is_err());
+        assert!(generate_dml(DatabaseType::MySQL, "users", &columns(), &rows, DmlKind::Update, &["email".to_string()]).is_err());
+    }
+}
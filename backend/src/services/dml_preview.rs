@@ -0,0 +1,118 @@
+use serde::Serialize;
+use sqlparser::ast::{Expr, FromTable, OrderByExpr, Statement, TableFactor, TableWithJoins};
+
+use crate::db::DatabaseType;
+use crate::utils::security::parse_statements_as;
+
+// UPDATE / DELETE 改写后的预览查询
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DmlPreview {
+    pub operation: String,      // UPDATE 或 DELETE
+    pub table: String,          // 被修改的表
+    pub select_sql: String,     // 查询将受影响行的SELECT
+}
+
+fn join_tables(tables: &[TableWithJoins]) -> String {
+    tables.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// 表在查询中的引用名（有别名时使用别名）和表名
+fn table_reference(table: &TableWithJoins) -> Result<(String, String), String> {
+    match &table.relation {
+        TableFactor::Table { name, alias, .. } => Ok((
+            alias.as_ref().map(|a| a.name.to_string()).unwrap_or_else(|| name.to_string()),
+            name.to_string(),
+        )),
+        _ => Err("只支持修改普通表的语句".to_string()),
+    }
+}
+
+// 拼接 SELECT 语句：单表时返回所有列，多表时只返回被修改表的列
+fn build_select(
+    target: &str,
+    from: &[TableWithJoins],
+    selection: Option<&Expr>,
+    order_by: &[OrderByExpr],
+    limit: Option<&Expr>,
+) -> String {
+    let single_table = from.len() == 1 && from[0].joins.is_empty();
+    let projection = if single_table { "*".to_string() } else { format!("{}.*", target) };
+    let mut sql = format!("SELECT {} FROM {}", projection, join_tables(from));
+    if let Some(selection) = selection {
+        sql.push_str(&format!(" WHERE {}", selection));
+    }
+    if !order_by.is_empty() {
+        sql.push_str(&format!(" ORDER BY {}", order_by.iter().map(|o| o.to_string()).collect::<Vec<_>>().join(", ")));
+    }
+    if let Some(limit) = limit {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+    sql
+}
+
+// 将 UPDATE / DELETE 改写为使用相同表和 WHERE 条件的 SELECT，用于执行前预览受影响的行
+pub fn preview_query(db_type: Option<DatabaseType>, sql: &str) -> Result<DmlPreview, String> {
+    let mut statements = parse_statements_as(db_type, sql)?;
+    if statements.len() != 1 {
+        return Err("预览只支持单条 UPDATE / DELETE 语句".to_string());
+    }
+
+    match statements.remove(0) {
+        Statement::Update { table, from, selection, .. } => {
+            let (target, table_name) = table_reference(&table)?;
+            let mut tables = vec![table];
+            tables.extend(from);
+            Ok(DmlPreview {
+                operation: "UPDATE".to_string(),
+                table: table_name,
+                select_sql: build_select(&target, &tables, selection.as_ref(), &[], None),
+            })
+        }
+        Statement::Delete { tables: targets, from, using, selection, order_by, limit, .. } => {
+            let (FromTable::WithFromKeyword(mut tables) | FromTable::WithoutKeyword(mut tables)) = from;
+            if targets.len() > 1 {
+                return Err("预览只支持删除单个表的语句".to_string());
+            }
+            let first = tables.first().ok_or_else(|| "DELETE 语句缺少表".to_string())?;
+            let (target, table_name) = match targets.first() {
+                // MySQL 多表语法：DELETE t1 FROM t1 JOIN t2 ...，目标可能是别名
+                Some(target) => (target.to_string(), target.to_string()),
+                None => table_reference(first)?,
+            };
+            tables.extend(using.unwrap_or_default());
+            Ok(DmlPreview {
+                operation: "DELETE".to_string(),
+                table: table_name,
+                select_sql: build_select(&target, &tables, selection.as_ref(), &order_by, limit.as_ref()),
+            })
+        }
+        _ => Err("预览只支持 UPDATE / DELETE 语句".to_string()),
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_query() {
+        let preview = preview_query(Some(DatabaseType::MySQL), "UPDATE orders SET status = 2 WHERE created_at < '2024-01-01' AND status = 1").unwrap();
+        assert_eq!(preview.operation, "UPDATE");
+        assert_eq!(preview.table, "orders");
+        assert_eq!(preview.select_sql, "SELECT * FROM orders WHERE created_at < '2024-01-01' AND status = 1");
+
+        let preview = preview_query(Some(DatabaseType::MySQL), "DELETE FROM logs WHERE level = 'debug' ORDER BY id LIMIT 100").unwrap();
+        assert_eq!(preview.select_sql, "SELECT * FROM logs WHERE level = 'debug' ORDER BY id LIMIT 100");
+
+        let preview = preview_query(Some(DatabaseType::PostgreSQL), "UPDATE orders o SET total = s.total FROM summary s WHERE o.id = s.order_id").unwrap();
+        assert_eq!(preview.table, "orders");
+        assert_eq!(preview.select_sql, "SELECT o.* FROM orders AS o, summary AS s WHERE o.id = s.order_id");
+
+        let preview = preview_query(Some(DatabaseType::SQLite), "DELETE FROM sessions").unwrap();
+        assert_eq!(preview.select_sql, "SELECT * FROM sessions");
+
+        assert!(preview_query(Some(DatabaseType::MySQL), "SELECT * FROM orders").is_err());
+        assert!(preview_query(Some(DatabaseType::MySQL), "DELETE FROM a; DELETE FROM b").is_err());
+    }
+}
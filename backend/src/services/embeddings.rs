@@ -0,0 +1,318 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::db::LocalStorageManager;
+use crate::models::SchemaEmbedding;
+use crate::services::ai::{AiService, AiServiceError};
+use crate::services::table_relevance;
+
+// 应用设置中嵌入模型的键
+pub const EMBEDDING_MODEL_SETTING_KEY: &str = "ai_embedding_model";
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+// 单次嵌入请求的最大文本数
+const EMBEDDING_BATCH_SIZE: usize = 256;
+
+// 默认返回的检索结果数
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+// OpenAI 兼容的嵌入响应结构（请求由各服务商构建）
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+// 嵌入向量服务错误
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingError {
+    #[error("AI服务错误: {0}")]
+    Ai(#[from] AiServiceError),
+    #[error("本地存储错误: {0}")]
+    Storage(#[from] sqlx::Error),
+}
+
+// 参与向量化的表结构文档（column_name 为None时为表级文档）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaDocument {
+    pub table_name: String,
+    pub column_name: Option<String>,
+    pub content: String,
+}
+
+// 向量同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncSummary {
+    pub documents: usize,
+    pub embedded: usize,
+    pub removed: u64,
+}
+
+// 语义检索命中的表或列
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaSearchHit {
+    pub table_name: String,
+    pub column_name: Option<String>,
+    pub content: String,
+    pub score: f32,
+}
+
+// 读取嵌入模型配置，未配置时使用默认模型
+pub async fn embedding_model(storage: &LocalStorageManager) -> String {
+    storage.get_app_setting(EMBEDDING_MODEL_SETTING_KEY).await.ok().flatten()
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string())
+}
+
+// 调用AI服务商的嵌入接口，返回与输入顺序一致的向量
+pub async fn embed_texts(ai_service: &AiService, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, AiServiceError> {
+    let config = ai_service.get_latest_config(None).await?;
+    let provider = config.provider.provider();
+
+    let mut vectors = Vec::with_capacity(inputs.len());
+    for batch in inputs.chunks(EMBEDDING_BATCH_SIZE) {
+        log::info!("[AI-Request] 调用嵌入接口 - Provider: {}, Model: {}, 文本数: {}", config.provider.as_str(), model, batch.len());
+        let request = provider.build_embeddings_request(ai_service.http_client(), &config, model, batch)
+            .ok_or_else(|| AiServiceError::ApiError(format!("AI服务商 {} 不支持嵌入接口", config.provider.as_str())))?;
+        let response = request.send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = config.redact(&response.text().await.unwrap_or_else(|_| "未知错误".to_string()));
+            log::error!("[AI-Response] 嵌入接口返回错误 - 状态码: {}, 详情: {}", status, error_text);
+            return Err(AiServiceError::ApiError(error_text));
+        }
+
+        let mut data = response.json::<EmbeddingResponse>().await
+            .map_err(|e| AiServiceError::ParseError(format!("嵌入响应解析失败: {}", e)))?
+            .data;
+        if data.len() != batch.len() {
+            return Err(AiServiceError::ParseError(format!("嵌入接口返回 {} 个向量，期望 {} 个", data.len(), batch.len())));
+        }
+        data.sort_by_key(|d| d.index);
+        vectors.extend(data.into_iter().map(|d| d.embedding));
+    }
+    Ok(vectors)
+}
+
+// 由表列表和列目录（表名, 列名, 注释）生成表级和列级文档
+pub fn schema_documents(table_names: &[String], catalog: Vec<(String, String, Option<String>)>) -> Vec<SchemaDocument> {
+    let mut documents = Vec::new();
+    for profile in table_relevance::build_profiles(table_names, catalog) {
+        let columns = profile.columns.iter()
+            .map(|(name, comment)| match comment.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(comment) => format!("{}({})", name, comment),
+                None => name.clone(),
+            })
+            .collect::<Vec<_>>();
+        documents.push(SchemaDocument {
+            table_name: profile.name.clone(),
+            column_name: None,
+            content: format!("表 {}: {}", profile.name, columns.join(", ")),
+        });
+        for (column, comment) in profile.columns {
+            let content = match comment.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                Some(comment) => format!("{}.{} {}", profile.name, column, comment),
+                None => format!("{}.{}", profile.name, column),
+            };
+            documents.push(SchemaDocument {
+                table_name: profile.name.clone(),
+                column_name: Some(column),
+                content,
+            });
+        }
+    }
+    documents
+}
+
+// 文本摘要（FNV-1a 64位），用于判断文档是否需要重新向量化
+pub fn content_hash(content: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+// 余弦相似度，维度不一致或零向量时为0
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+// 按与查询向量的相似度排序，取前 limit 个
+pub fn rank_by_similarity(query: &[f32], embeddings: &[SchemaEmbedding], limit: usize) -> Vec<SchemaSearchHit> {
+    let mut hits: Vec<SchemaSearchHit> = embeddings.iter()
+        .map(|e| SchemaSearchHit {
+            table_name: e.table_name.clone(),
+            column_name: e.column_name.clone(),
+            content: e.content.clone(),
+            score: cosine_similarity(query, &e.vector),
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    hits.truncate(limit);
+    hits
+}
+
+// 检索结果涉及的表（按首次出现顺序去重）
+pub fn hit_tables(hits: &[SchemaSearchHit]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    hits.iter()
+        .filter(|hit| seen.insert(hit.table_name.clone()))
+        .map(|hit| hit.table_name.clone())
+        .collect()
+}
+
+// 增量同步连接的表结构向量：只向量化新增或内容变化的文档，删除已不存在的表和列
+pub async fn sync_schema_embeddings(
+    ai_service: &AiService,
+    storage: &LocalStorageManager,
+    connection_id: i64,
+    documents: Vec<SchemaDocument>,
+) -> Result<SyncSummary, EmbeddingError> {
+    let model = embedding_model(storage).await;
+    let existing: HashMap<(String, Option<String>), SchemaEmbedding> = storage.list_schema_embeddings(connection_id).await?
+        .into_iter()
+        .map(|e| ((e.table_name.clone(), e.column_name.clone()), e))
+        .collect();
+
+    let current_keys: HashSet<(String, Option<String>)> = documents.iter()
+        .map(|d| (d.table_name.clone(), d.column_name.clone()))
+        .collect();
+    let stale: Vec<(String, Option<String>)> = existing.keys()
+        .filter(|key| !current_keys.contains(*key))
+        .cloned()
+        .collect();
+
+    let pending: Vec<(SchemaDocument, String)> = documents.iter()
+        .map(|d| (d.clone(), content_hash(&d.content)))
+        .filter(|(d, hash)| match existing.get(&(d.table_name.clone(), d.column_name.clone())) {
+            Some(e) => e.content_hash != *hash || e.model != model,
+            None => true,
+        })
+        .collect();
+
+    if !pending.is_empty() {
+        let inputs: Vec<String> = pending.iter().map(|(d, _)| d.content.clone()).collect();
+        let vectors = embed_texts(ai_service, &model, &inputs).await?;
+        let items: Vec<SchemaEmbedding> = pending.iter()
+            .zip(vectors)
+            .map(|((d, hash), vector)| SchemaEmbedding {
+                table_name: d.table_name.clone(),
+                column_name: d.column_name.clone(),
+                content: d.content.clone(),
+                content_hash: hash.clone(),
+                model: model.clone(),
+                vector,
+            })
+            .collect();
+        storage.upsert_schema_embeddings(connection_id, &items).await?;
+    }
+
+    let removed = if stale.is_empty() {
+        0
+    } else {
+        storage.delete_schema_embeddings(connection_id, &stale).await?
+    };
+
+    log::info!("[Embeddings] 连接 {} 向量同步完成: 文档 {} 个, 新向量化 {} 个, 删除 {} 个", connection_id, documents.len(), pending.len(), removed);
+    Ok(SyncSummary {
+        documents: documents.len(),
+        embedded: pending.len(),
+        removed,
+    })
+}
+
+// 用已存储的向量检索与问题语义相关的表和列（只比较当前嵌入模型生成的向量）
+pub async fn search_schema(
+    ai_service: &AiService,
+    storage: &LocalStorageManager,
+    connection_id: i64,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SchemaSearchHit>, EmbeddingError> {
+    let model = embedding_model(storage).await;
+    let embeddings: Vec<SchemaEmbedding> = storage.list_schema_embeddings(connection_id).await?
+        .into_iter()
+        .filter(|e| e.model == model)
+        .collect();
+    if embeddings.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query_vector = embed_texts(ai_service, &model, &[query.to_string()]).await?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    Ok(rank_by_similarity(&query_vector, &embeddings, limit))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding(table: &str, column: Option<&str>, vector: Vec<f32>) -> SchemaEmbedding {
+        SchemaEmbedding {
+            table_name: table.to_string(),
+            column_name: column.map(str::to_string),
+            content: table.to_string(),
+            content_hash: String::new(),
+            model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            vector,
+        }
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn test_rank_by_similarity() {
+        let embeddings = vec![
+            embedding("users", None, vec![0.0, 1.0]),
+            embedding("orders", None, vec![1.0, 0.1]),
+            embedding("orders", Some("total"), vec![0.9, 0.2]),
+        ];
+        let hits = rank_by_similarity(&[1.0, 0.0], &embeddings, 2);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].table_name, "orders");
+        assert_eq!(hit_tables(&hits), vec!["orders"]);
+    }
+
+    #[test]
+    fn test_schema_documents() {
+        let tables = vec!["orders".to_string()];
+        let catalog = vec![
+            ("orders".to_string(), "id".to_string(), None),
+            ("orders".to_string(), "total".to_string(), Some("订单金额".to_string())),
+        ];
+        let documents = schema_documents(&tables, catalog);
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[0].content, "表 orders: id, total(订单金额)");
+        assert_eq!(documents[2].column_name.as_deref(), Some("total"));
+        assert_eq!(documents[2].content, "orders.total 订单金额");
+        assert_eq!(content_hash("abc"), content_hash("abc"));
+        assert_ne!(content_hash("abc"), content_hash("abd"));
+    }
+}
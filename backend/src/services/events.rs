@@ -0,0 +1,214 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::services::jobs::{JobKind, JobStatus};
+
+// 事件通道容量，订阅方处理过慢时会丢弃最旧的事件
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+// 查询执行中推送进度事件的间隔
+pub const QUERY_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+// 事件中携带的SQL最大长度
+const SQL_PREVIEW_CHARS: usize = 200;
+
+// 服务端推送事件（通过 /api/ws 发送给前端，type 字段区分事件类型）
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    QueryStarted {
+        query_id: String,
+        connection_id: Option<i64>,
+        sql: String,
+        timestamp: i64,
+    },
+    QueryProgress {
+        query_id: String,
+        elapsed_ms: u128,
+        timestamp: i64,
+    },
+    QueryFinished {
+        query_id: String,
+        connection_id: Option<i64>,
+        success: bool,
+        row_count: usize,
+        execution_time_ms: u128,
+        error: Option<String>,
+        timestamp: i64,
+    },
+    SchemaRefreshed {
+        connection_id: Option<i64>,
+        cleared_tables: usize,
+        timestamp: i64,
+    },
+    // SQL脚本每执行完一条语句推送一次
+    ScriptProgress {
+        script_id: String,
+        index: usize,      // 语句序号（从0开始）
+        total: usize,
+        line: usize,
+        success: bool,
+        timestamp: i64,
+    },
+    ScriptFinished {
+        script_id: String,
+        succeeded: usize,
+        failed: usize,
+        skipped: usize,
+        execution_time_ms: u128,
+        timestamp: i64,
+    },
+    // 后台健康检查每检测一个连接推送一次
+    ConnectionHealth {
+        connection_id: i64,
+        available: bool,
+        latency_ms: Option<i64>,
+        error: Option<String>,
+        timestamp: i64,
+    },
+    // 后台任务结束（成功、失败或取消），结果通过 /api/jobs/:id/result 获取
+    JobFinished {
+        job_id: String,
+        kind: JobKind,
+        status: JobStatus,
+        error: Option<String>,
+        timestamp: i64,
+    },
+}
+
+impl ServerEvent {
+    // 事件类型（与序列化后的 type 字段一致，用于订阅过滤）
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            ServerEvent::QueryStarted { .. } => "query_started",
+            ServerEvent::QueryProgress { .. } => "query_progress",
+            ServerEvent::QueryFinished { .. } => "query_finished",
+            ServerEvent::SchemaRefreshed { .. } => "schema_refreshed",
+            ServerEvent::ScriptProgress { .. } => "script_progress",
+            ServerEvent::ScriptFinished { .. } => "script_finished",
+            ServerEvent::ConnectionHealth { .. } => "connection_health",
+            ServerEvent::JobFinished { .. } => "job_finished",
+        }
+    }
+
+    // 事件关联的查询ID（脚本事件为脚本ID，任务事件为任务ID）
+    pub fn query_id(&self) -> Option<&str> {
+        match self {
+            ServerEvent::QueryStarted { query_id, .. }
+            | ServerEvent::QueryProgress { query_id, .. }
+            | ServerEvent::QueryFinished { query_id, .. } => Some(query_id),
+            ServerEvent::ScriptProgress { script_id, .. }
+            | ServerEvent::ScriptFinished { script_id, .. } => Some(script_id),
+            ServerEvent::JobFinished { job_id, .. } => Some(job_id),
+            ServerEvent::SchemaRefreshed { .. } | ServerEvent::ConnectionHealth { .. } => None,
+        }
+    }
+}
+
+pub fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+pub fn sql_preview(sql: &str) -> String {
+    let sql = sql.trim();
+    match sql.char_indices().nth(SQL_PREVIEW_CHARS) {
+        Some((idx, _)) => format!("{}...", &sql[..idx]),
+        None => sql.to_string(),
+    }
+}
+
+static EVENT_SENDER: OnceLock<broadcast::Sender<ServerEvent>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<ServerEvent> {
+    EVENT_SENDER.get_or_init(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+}
+
+// 发布事件（没有订阅方时直接丢弃）
+pub fn publish(event: ServerEvent) {
+    let _ = sender().send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<ServerEvent> {
+    sender().subscribe()
+}
+
+// 查询执行期间定时推送进度事件，返回的句柄在查询结束后需要 abort
+pub fn spawn_query_progress(query_id: String) -> tokio::task::JoinHandle<()> {
+    let start = Instant::now();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(QUERY_PROGRESS_INTERVAL);
+        // 第一次tick立即返回，跳过
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            publish(ServerEvent::QueryProgress {
+                query_id: query_id.clone(),
+                elapsed_ms: start.elapsed().as_millis(),
+                timestamp: now(),
+            });
+        }
+    })
+}
+
+// WebSocket订阅过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub event_types: Vec<String>,  // 为空时接收全部类型
+    pub query_id: Option<String>,  // 只接收指定查询（或脚本）的事件
+}
+
+impl EventFilter {
+    pub fn accepts(&self, event: &ServerEvent) -> bool {
+        if !self.event_types.is_empty() && !self.event_types.iter().any(|t| t == event.event_type()) {
+            return false;
+        }
+        match &self.query_id {
+            Some(query_id) => event.query_id() == Some(query_id.as_str()),
+            None => true,
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = ServerEvent::QueryProgress { query_id: "q1".to_string(), elapsed_ms: 1500, timestamp: 1 };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "query_progress");
+        assert_eq!(value["type"], event.event_type());
+        assert_eq!(value["elapsed_ms"], 1500);
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let started = ServerEvent::QueryStarted { query_id: "q1".to_string(), connection_id: Some(1), sql: "SELECT 1".to_string(), timestamp: 1 };
+        let refreshed = ServerEvent::SchemaRefreshed { connection_id: Some(1), cleared_tables: 3, timestamp: 1 };
+
+        assert!(EventFilter::default().accepts(&started));
+        let by_type = EventFilter { event_types: vec!["schema_refreshed".to_string()], ..Default::default() };
+        assert!(!by_type.accepts(&started));
+        assert!(by_type.accepts(&refreshed));
+        let by_query = EventFilter { query_id: Some("q2".to_string()), ..Default::default() };
+        assert!(!by_query.accepts(&started));
+        assert!(!by_query.accepts(&refreshed));
+    }
+
+    #[test]
+    fn test_sql_preview() {
+        assert_eq!(sql_preview("  SELECT 1  "), "SELECT 1");
+        let long = "中".repeat(SQL_PREVIEW_CHARS + 5);
+        assert_eq!(sql_preview(&long).chars().count(), SQL_PREVIEW_CHARS + 3);
+    }
+
+    #[tokio::test]
+    async fn test_publish_subscribe() {
+        let mut receiver = subscribe();
+        publish(ServerEvent::SchemaRefreshed { connection_id: None, cleared_tables: 0, timestamp: 1 });
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.event_type(), "schema_refreshed");
+    }
+}
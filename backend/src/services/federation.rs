@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use crate::db::ddl::quote_identifier;
+use crate::db::DatabaseType;
+
+// 单次联邦查询最多的数据源数量
+pub const MAX_SOURCES: usize = 8;
+// 单个数据源最多载入的行数
+pub const MAX_SOURCE_ROWS: usize = 200_000;
+// 联邦查询结果最多返回的行数
+pub const MAX_RESULT_ROWS: usize = 10_000;
+
+// 数据源别名即内存表名，只允许普通标识符
+pub fn validate_alias(alias: &str) -> Result<(), String> {
+    lazy_static::lazy_static! {
+        static ref ALIAS_RE: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]{0,63}$").unwrap();
+    }
+    if !ALIAS_RE.is_match(alias) {
+        return Err(format!("数据源别名只能包含字母、数字和下划线，且不能以数字开头: {}", alias));
+    }
+    if alias.to_lowercase().starts_with("sqlite_") {
+        return Err(format!("数据源别名不能以 sqlite_ 开头: {}", alias));
+    }
+    Ok(())
+}
+
+// 内存表的列名：替换引号和控制字符，空列名使用 column_n，重名（不区分大小写）时追加序号
+pub fn table_columns(columns: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns.iter().enumerate()
+        .map(|(index, name)| {
+            let cleaned: String = name.trim().chars()
+                .map(|c| if c == '"' || c == '`' || c.is_control() { '_' } else { c })
+                .collect();
+            let base = if cleaned.is_empty() { format!("column_{}", index + 1) } else { cleaned };
+            let mut candidate = base.clone();
+            let mut suffix = 2;
+            while !seen.insert(candidate.to_lowercase()) {
+                candidate = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            candidate
+        })
+        .collect()
+}
+
+// 按列的取值推断SQLite列类型，使比较和排序按数值进行
+pub fn infer_column_type(rows: &[Vec<JsonValue>], index: usize) -> &'static str {
+    let mut column_type = "";
+    for value in rows.iter().filter_map(|row| row.get(index)) {
+        column_type = match (column_type, value) {
+            (_, JsonValue::Null) => continue,
+            ("" | "INTEGER", JsonValue::Bool(_)) => "INTEGER",
+            ("" | "INTEGER", JsonValue::Number(n)) if n.is_i64() || n.is_u64() => "INTEGER",
+            ("" | "INTEGER" | "REAL", JsonValue::Number(_)) => "REAL",
+            _ => return "TEXT",
+        };
+    }
+    if column_type.is_empty() { "TEXT" } else { column_type }
+}
+
+// 创建内存表的语句
+pub fn create_table_sql(alias: &str, columns: &[String], rows: &[Vec<JsonValue>]) -> Result<String, String> {
+    let definitions = columns.iter().enumerate()
+        .map(|(index, column)| Ok(format!("{} {}", quote_identifier(DatabaseType::SQLite, column)?, infer_column_type(rows, index))))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("CREATE TABLE {} ({})", quote_identifier(DatabaseType::SQLite, alias)?, definitions.join(", ")))
+}
+
+// 插入一行的参数化语句
+pub fn insert_sql(alias: &str, column_count: usize) -> Result<String, String> {
+    let placeholders = vec!["?"; column_count].join(", ");
+    Ok(format!("INSERT INTO {} VALUES ({})", quote_identifier(DatabaseType::SQLite, alias)?, placeholders))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_alias() {
+        assert!(validate_alias("orders").is_ok());
+        assert!(validate_alias("_tmp1").is_ok());
+        assert!(validate_alias("1orders").is_err());
+        assert!(validate_alias("orders; DROP").is_err());
+        assert!(validate_alias("sqlite_master").is_err());
+    }
+
+    #[test]
+    fn test_table_columns() {
+        let columns = vec!["id".to_string(), "ID".to_string(), "".to_string(), "a\"b".to_string(), "id".to_string()];
+        assert_eq!(table_columns(&columns), vec!["id", "ID_2", "column_3", "a_b", "id_3"]);
+    }
+
+    #[test]
+    fn test_create_table_sql() {
+        let columns = vec!["id".to_string(), "price".to_string(), "name".to_string(), "flag".to_string()];
+        let rows = vec![
+            vec![json!(1), json!(2), json!("a"), JsonValue::Null],
+            vec![json!(2), json!(2.5), json!(3), JsonValue::Null],
+        ];
+        assert_eq!(
+            create_table_sql("orders", &columns, &rows).unwrap(),
+            "CREATE TABLE \"orders\" (\"id\" INTEGER, \"price\" REAL, \"name\" TEXT, \"flag\" TEXT)"
+        );
+        assert_eq!(insert_sql("orders", 3).unwrap(), "INSERT INTO \"orders\" VALUES (?, ?, ?)");
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+
+use crate::models::QueryHistory;
+use crate::utils::security::referenced_tables;
+
+// 统计时最多读取的历史记录数
+pub const MAX_STATS_RECORDS: i64 = 50_000;
+// 默认返回的常用表 / 慢查询数量
+pub const DEFAULT_TOP_N: usize = 10;
+
+// 每日执行统计
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DailyStat {
+    pub date: String,  // YYYY-MM-DD（本地时区）
+    pub executions: i64,
+    pub failures: i64,
+    pub avg_duration_ms: Option<f64>,
+}
+
+// 表使用次数
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TableUsage {
+    pub table: String,
+    pub count: i64,
+}
+
+// 查询历史统计结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryStats {
+    pub total_executions: i64,
+    pub success_count: i64,
+    pub failure_count: i64,
+    pub failure_rate: f64,
+    pub avg_duration_ms: Option<f64>,
+    pub p50_duration_ms: Option<i64>,
+    pub p95_duration_ms: Option<i64>,
+    pub p99_duration_ms: Option<i64>,
+    pub daily: Vec<DailyStat>,
+    pub top_tables: Vec<TableUsage>,
+    pub slowest_queries: Vec<QueryHistory>,
+}
+
+// 最近秩法计算百分位（durations 已升序排列）
+fn percentile(durations: &[i64], p: f64) -> Option<i64> {
+    if durations.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * durations.len() as f64).ceil() as usize;
+    Some(durations[rank.clamp(1, durations.len()) - 1])
+}
+
+fn average(durations: &[i64]) -> Option<f64> {
+    if durations.is_empty() {
+        None
+    } else {
+        Some(durations.iter().sum::<i64>() as f64 / durations.len() as f64)
+    }
+}
+
+// 时间戳转换为本地日期
+fn local_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+// 汇总查询历史
+pub fn compute_stats(records: &[QueryHistory], top_n: usize) -> HistoryStats {
+    let total = records.len() as i64;
+    let failures = records.iter().filter(|r| !r.is_success).count() as i64;
+
+    let mut durations: Vec<i64> = records.iter().filter_map(|r| r.execution_time_ms).collect();
+    durations.sort_unstable();
+
+    // 按日期分组
+    let mut days: BTreeMap<String, (i64, i64, Vec<i64>)> = BTreeMap::new();
+    for record in records {
+        let day = days.entry(local_date(record.executed_at)).or_default();
+        day.0 += 1;
+        if !record.is_success {
+            day.1 += 1;
+        }
+        if let Some(ms) = record.execution_time_ms {
+            day.2.push(ms);
+        }
+    }
+    let daily = days.into_iter()
+        .map(|(date, (executions, failures, durations))| DailyStat {
+            date,
+            executions,
+            failures,
+            avg_duration_ms: average(&durations),
+        })
+        .collect();
+
+    // 解析SQL统计表的使用次数（同一条SQL中重复引用只计一次）
+    let mut table_counts: HashMap<String, i64> = HashMap::new();
+    for record in records {
+        for table in referenced_tables(&record.sql_text) {
+            *table_counts.entry(table.to_lowercase()).or_default() += 1;
+        }
+    }
+    let mut top_tables: Vec<TableUsage> = table_counts.into_iter()
+        .map(|(table, count)| TableUsage { table, count })
+        .collect();
+    top_tables.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.table.cmp(&b.table)));
+    top_tables.truncate(top_n);
+
+    let mut slowest_queries: Vec<QueryHistory> = records.iter()
+        .filter(|r| r.execution_time_ms.is_some())
+        .cloned()
+        .collect();
+    slowest_queries.sort_by_key(|r| std::cmp::Reverse(r.execution_time_ms));
+    slowest_queries.truncate(top_n);
+
+    HistoryStats {
+        total_executions: total,
+        success_count: total - failures,
+        failure_count: failures,
+        failure_rate: if total > 0 { failures as f64 / total as f64 } else { 0.0 },
+        avg_duration_ms: average(&durations),
+        p50_duration_ms: percentile(&durations, 50.0),
+        p95_duration_ms: percentile(&durations, 95.0),
+        p99_duration_ms: percentile(&durations, 99.0),
+        daily,
+        top_tables,
+        slowest_queries,
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(sql: &str, executed_at: i64, ms: Option<i64>, success: bool) -> QueryHistory {
+        QueryHistory {
+            id: None,
+            connection_id: None,
+            sql_text: sql.to_string(),
+            executed_at,
+            execution_time_ms: ms,
+            row_count: None,
+            is_success: success,
+            error_message: None,
+            is_favorite: false,
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let durations: Vec<i64> = (1..=100).collect();
+        assert_eq!(percentile(&durations, 50.0), Some(50));
+        assert_eq!(percentile(&durations, 95.0), Some(95));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let records = vec![
+            record("SELECT * FROM orders JOIN users ON users.id = orders.user_id", 1_700_000_000, Some(100), true),
+            record("SELECT * FROM Orders", 1_700_000_100, Some(300), true),
+            record("SELECT * FROM missing", 1_700_000_200, None, false),
+        ];
+        let stats = compute_stats(&records, 1);
+
+        assert_eq!(stats.total_executions, 3);
+        assert_eq!(stats.failure_count, 1);
+        assert!((stats.failure_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.avg_duration_ms, Some(200.0));
+        assert_eq!(stats.daily.iter().map(|d| d.executions).sum::<i64>(), 3);
+        assert_eq!(stats.top_tables, vec![TableUsage { table: "orders".to_string(), count: 2 }]);
+        assert_eq!(stats.slowest_queries[0].execution_time_ms, Some(300));
+    }
+}
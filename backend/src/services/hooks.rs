@@ -0,0 +1,198 @@
+use std::sync::OnceLock;
+use log::*;
+
+use crate::models::DatabaseConnection;
+use crate::services::events::{self, ServerEvent};
+
+// 查询执行上下文（传递给钩子的查询信息）
+pub struct QueryContext<'a> {
+    pub query_id: &'a str,
+    pub sql: &'a str,
+    pub connection: &'a DatabaseConnection,
+}
+
+// 查询执行结果元数据
+#[derive(Debug, Clone)]
+pub struct QueryOutcome {
+    pub success: bool,
+    pub row_count: usize,
+    pub execution_time_ms: u128,
+    pub error: Option<String>,
+}
+
+// 查询执行钩子
+// 通过实现此trait并在 registered_hooks() 中注册，即可在不修改执行流程的情况下
+// 扩展审计、策略检查等功能
+pub trait QueryHook: Send + Sync {
+    // 钩子名称（用于日志）
+    fn name(&self) -> &str;
+
+    // 查询执行前调用，返回Err将阻止查询执行
+    fn before_execute(&self, _ctx: &QueryContext) -> Result<(), String> {
+        Ok(())
+    }
+
+    // 查询执行后调用（成功、失败或取消均会调用）
+    fn after_execute(&self, _ctx: &QueryContext, _outcome: &QueryOutcome) {}
+}
+
+// 钩子拒绝执行错误
+#[derive(Debug)]
+pub struct HookRejection {
+    pub hook: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for HookRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.hook, self.reason)
+    }
+}
+
+impl std::error::Error for HookRejection {}
+
+// 钩子注册表
+pub struct HookRegistry {
+    hooks: Vec<Box<dyn QueryHook>>,
+}
+
+impl HookRegistry {
+    pub fn new(hooks: Vec<Box<dyn QueryHook>>) -> Self {
+        Self { hooks }
+    }
+
+    // 依次执行所有before钩子，任一钩子拒绝即停止
+    pub fn run_before(&self, ctx: &QueryContext) -> Result<(), HookRejection> {
+        for hook in &self.hooks {
+            if let Err(reason) = hook.before_execute(ctx) {
+                warn!("[Hooks] 钩子 {} 拒绝执行查询: query_id={}, 原因: {}", hook.name(), ctx.query_id, reason);
+                return Err(HookRejection {
+                    hook: hook.name().to_string(),
+                    reason,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // 依次执行所有after钩子
+    pub fn run_after(&self, ctx: &QueryContext, outcome: &QueryOutcome) {
+        for hook in &self.hooks {
+            hook.after_execute(ctx, outcome);
+        }
+    }
+
+    pub fn hook_names(&self) -> Vec<&str> {
+        self.hooks.iter().map(|h| h.name()).collect()
+    }
+}
+
+// 编译期注册的钩子列表，新增钩子时在此处添加
+fn registered_hooks() -> Vec<Box<dyn QueryHook>> {
+    vec![
+        Box::new(ExecutionLogHook),
+        Box::new(QueryEventHook),
+    ]
+}
+
+static HOOK_REGISTRY: OnceLock<HookRegistry> = OnceLock::new();
+
+// 获取全局钩子注册表
+pub fn registry() -> &'static HookRegistry {
+    HOOK_REGISTRY.get_or_init(|| {
+        let registry = HookRegistry::new(registered_hooks());
+        info!("[Hooks] 已注册查询钩子: {:?}", registry.hook_names());
+        registry
+    })
+}
+
+// 内置钩子：记录查询执行日志
+pub struct ExecutionLogHook;
+
+impl QueryHook for ExecutionLogHook {
+    fn name(&self) -> &str {
+        "execution_log"
+    }
+
+    fn after_execute(&self, ctx: &QueryContext, outcome: &QueryOutcome) {
+        if outcome.success {
+            info!("[Hooks] 查询完成: query_id={}, 连接={}, 行数={}, 耗时={}ms",
+                ctx.query_id, ctx.connection.name, outcome.row_count, outcome.execution_time_ms);
+        } else {
+            info!("[Hooks] 查询失败: query_id={}, 连接={}, 错误: {}",
+                ctx.query_id, ctx.connection.name, outcome.error.as_deref().unwrap_or(""));
+        }
+    }
+}
+
+// 内置钩子：推送查询开始/结束事件（/api/ws）
+pub struct QueryEventHook;
+
+impl QueryHook for QueryEventHook {
+    fn name(&self) -> &str {
+        "query_events"
+    }
+
+    fn before_execute(&self, ctx: &QueryContext) -> Result<(), String> {
+        events::publish(ServerEvent::QueryStarted {
+            query_id: ctx.query_id.to_string(),
+            connection_id: ctx.connection.id,
+            sql: events::sql_preview(ctx.sql),
+            timestamp: events::now(),
+        });
+        Ok(())
+    }
+
+    fn after_execute(&self, ctx: &QueryContext, outcome: &QueryOutcome) {
+        events::publish(ServerEvent::QueryFinished {
+            query_id: ctx.query_id.to_string(),
+            connection_id: ctx.connection.id,
+            success: outcome.success,
+            row_count: outcome.row_count,
+            execution_time_ms: outcome.execution_time_ms,
+            error: outcome.error.clone(),
+            timestamp: events::now(),
+        });
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DenyDropHook;
+
+    impl QueryHook for DenyDropHook {
+        fn name(&self) -> &str {
+            "deny_drop"
+        }
+
+        fn before_execute(&self, ctx: &QueryContext) -> Result<(), String> {
+            if ctx.sql.to_uppercase().contains("DROP") {
+                return Err("禁止执行DROP语句".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    fn test_connection() -> DatabaseConnection {
+        DatabaseConnection {
+            file_path: Some(":memory:".to_string()),
+            ..DatabaseConnection::for_test("test", "sqlite")
+        }
+    }
+
+    #[test]
+    fn test_before_hook_rejects_query() {
+        let registry = HookRegistry::new(vec![Box::new(DenyDropHook)]);
+        let connection = test_connection();
+
+        let ctx = QueryContext { query_id: "q1", sql: "DROP TABLE users", connection: &connection };
+        let rejection = registry.run_before(&ctx).unwrap_err();
+        assert_eq!(rejection.hook, "deny_drop");
+
+        let ctx = QueryContext { query_id: "q2", sql: "SELECT 1", connection: &connection };
+        assert!(registry.run_before(&ctx).is_ok());
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use sqlparser::ast::{
+    BinaryOperator, Expr, FromTable, GroupByExpr, JoinConstraint, JoinOperator, Query, SetExpr, Statement,
+    TableFactor, TableWithJoins,
+};
+
+use crate::db::DatabaseType;
+use crate::utils::security::parse_statements;
+
+// 单个建议索引最多包含的列数
+const MAX_INDEX_COLUMNS: usize = 4;
+
+// 列在查询中的用途（按组合索引中的列顺序排列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnUsage {
+    Equality,  // = / IN / IS NULL
+    Join,      // JOIN ON a.x = b.y
+    Range,     // > < BETWEEN LIKE 'x%'
+    OrderBy,   // ORDER BY / GROUP BY
+}
+
+// 单条SQL中某个表使用到的列
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableColumnUsage {
+    pub table: String,
+    pub columns: Vec<(String, ColumnUsage)>,
+}
+
+// 已存在的索引
+#[derive(Debug, Clone)]
+pub struct ExistingIndex {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+// 索引建议
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSuggestion {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub usages: Vec<ColumnUsage>,
+    pub create_sql: String,
+    pub occurrences: usize,          // 工作负载中受益的SQL数量
+    pub sample_sql: String,
+    pub partially_covered_by: Option<String>,  // 已有索引覆盖了部分前缀列
+}
+
+// 单条语句的列收集器：维护别名到表名的映射
+#[derive(Default)]
+struct UsageCollector {
+    aliases: HashMap<String, String>,
+    tables: Vec<String>,
+    usages: Vec<(String, String, ColumnUsage)>,
+}
+
+impl UsageCollector {
+    fn add_relation(&mut self, factor: &TableFactor) {
+        if let TableFactor::Table { name, alias, .. } = factor {
+            if let Some(table) = name.0.last().map(|i| i.value.clone()) {
+                self.aliases.insert(table.to_lowercase(), table.clone());
+                if let Some(alias) = alias {
+                    self.aliases.insert(alias.name.value.to_lowercase(), table.clone());
+                }
+                if !self.tables.contains(&table) {
+                    self.tables.push(table);
+                }
+            }
+        }
+    }
+
+    fn add_from(&mut self, from: &[TableWithJoins]) {
+        for table_with_joins in from {
+            self.add_relation(&table_with_joins.relation);
+            for join in &table_with_joins.joins {
+                self.add_relation(&join.relation);
+            }
+        }
+        // 先登记所有表和别名，再解析 JOIN 条件
+        for table_with_joins in from {
+            for join in &table_with_joins.joins {
+                let constraint = match &join.join_operator {
+                    JoinOperator::Inner(c)
+                    | JoinOperator::LeftOuter(c)
+                    | JoinOperator::RightOuter(c)
+                    | JoinOperator::FullOuter(c)
+                    | JoinOperator::LeftSemi(c)
+                    | JoinOperator::RightSemi(c)
+                    | JoinOperator::LeftAnti(c)
+                    | JoinOperator::RightAnti(c) => c,
+                    _ => continue,
+                };
+                match constraint {
+                    JoinConstraint::On(expr) => self.visit_condition(expr),
+                    JoinConstraint::Using(columns) => {
+                        for table in self.tables.clone() {
+                            for column in columns {
+                                self.usages.push((table.clone(), column.value.clone(), ColumnUsage::Join));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // 解析列引用所属的表；未限定的列只在单表查询中能确定
+    fn resolve(&self, expr: &Expr) -> Option<(String, String)> {
+        match expr {
+            Expr::Identifier(ident) if self.tables.len() == 1 => Some((self.tables[0].clone(), ident.value.clone())),
+            Expr::CompoundIdentifier(parts) if parts.len() >= 2 => {
+                let qualifier = parts[parts.len() - 2].value.to_lowercase();
+                let table = self.aliases.get(&qualifier)?;
+                Some((table.clone(), parts[parts.len() - 1].value.clone()))
+            }
+            Expr::Nested(inner) => self.resolve(inner),
+            _ => None,
+        }
+    }
+
+    fn record(&mut self, expr: &Expr, usage: ColumnUsage) {
+        if let Some((table, column)) = self.resolve(expr) {
+            self.usages.push((table, column, usage));
+        }
+    }
+
+    fn visit_condition(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BinaryOp { left, op, right } => match op {
+                BinaryOperator::And | BinaryOperator::Or => {
+                    self.visit_condition(left);
+                    self.visit_condition(right);
+                }
+                BinaryOperator::Eq => match (self.resolve(left), self.resolve(right)) {
+                    // 两侧都是列：连接条件
+                    (Some(_), Some(_)) => {
+                        self.record(left, ColumnUsage::Join);
+                        self.record(right, ColumnUsage::Join);
+                    }
+                    (Some(_), None) => self.record(left, ColumnUsage::Equality),
+                    (None, Some(_)) => self.record(right, ColumnUsage::Equality),
+                    (None, None) => {}
+                },
+                BinaryOperator::Gt | BinaryOperator::Lt | BinaryOperator::GtEq | BinaryOperator::LtEq => {
+                    self.record(left, ColumnUsage::Range);
+                    self.record(right, ColumnUsage::Range);
+                }
+                _ => {}
+            },
+            Expr::Nested(inner) => self.visit_condition(inner),
+            Expr::InList { expr, negated: false, .. } | Expr::InSubquery { expr, negated: false, .. } => {
+                self.record(expr, ColumnUsage::Equality)
+            }
+            Expr::IsNull(expr) => self.record(expr, ColumnUsage::Equality),
+            Expr::Between { expr, negated: false, .. } => self.record(expr, ColumnUsage::Range),
+            // 只有前缀匹配（不以通配符开头）才能使用索引
+            Expr::Like { expr, pattern, negated: false, .. } => {
+                if let Expr::Value(sqlparser::ast::Value::SingleQuotedString(p)) = pattern.as_ref() {
+                    if !p.starts_with('%') && !p.starts_with('_') {
+                        self.record(expr, ColumnUsage::Range);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        if let SetExpr::Select(select) = query.body.as_ref() {
+            self.add_from(&select.from);
+            if let Some(selection) = &select.selection {
+                self.visit_condition(selection);
+            }
+            if let GroupByExpr::Expressions(exprs) = &select.group_by {
+                for expr in exprs {
+                    self.record(expr, ColumnUsage::OrderBy);
+                }
+            }
+        }
+        for order in &query.order_by {
+            self.record(&order.expr, ColumnUsage::OrderBy);
+        }
+    }
+}
+
+// 分析SQL中每个表在过滤、连接、排序中使用的列
+pub fn analyze_sql(sql: &str) -> Vec<TableColumnUsage> {
+    let mut result: Vec<TableColumnUsage> = Vec::new();
+
+    for statement in parse_statements(sql).unwrap_or_default() {
+        let mut collector = UsageCollector::default();
+        match &statement {
+            Statement::Query(query) => collector.visit_query(query),
+            Statement::Update { table, selection, .. } => {
+                collector.add_from(std::slice::from_ref(table));
+                if let Some(selection) = selection {
+                    collector.visit_condition(selection);
+                }
+            }
+            Statement::Delete { from, selection, .. } => {
+                let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = from;
+                collector.add_from(from);
+                if let Some(selection) = selection {
+                    collector.visit_condition(selection);
+                }
+            }
+            _ => continue,
+        }
+
+        for (table, column, usage) in collector.usages {
+            let index = match result.iter().position(|t| t.table == table) {
+                Some(index) => index,
+                None => {
+                    result.push(TableColumnUsage { table: table.clone(), columns: Vec::new() });
+                    result.len() - 1
+                }
+            };
+            let entry = &mut result[index];
+            match entry.columns.iter_mut().find(|(c, _)| c.eq_ignore_ascii_case(&column)) {
+                // 同一列多种用途时保留在组合索引中更靠前的用途
+                Some(existing) => existing.1 = existing.1.min(usage),
+                None => entry.columns.push((column, usage)),
+            }
+        }
+    }
+    result
+}
+
+// 组合索引列顺序：等值 -> 连接 -> 第一个范围列（之后的列无法使用索引）或排序列
+pub fn candidate_columns(usage: &TableColumnUsage) -> Vec<(String, ColumnUsage)> {
+    let mut columns: Vec<(String, ColumnUsage)> = usage.columns.iter()
+        .filter(|(_, u)| matches!(u, ColumnUsage::Equality | ColumnUsage::Join))
+        .cloned()
+        .collect();
+    columns.sort_by_key(|(_, u)| *u);
+
+    let range = usage.columns.iter().find(|(_, u)| *u == ColumnUsage::Range);
+    match range {
+        Some(range) => columns.push(range.clone()),
+        None => columns.extend(usage.columns.iter().filter(|(_, u)| *u == ColumnUsage::OrderBy).cloned()),
+    }
+    columns.truncate(MAX_INDEX_COLUMNS);
+    columns
+}
+
+fn quote_identifier(db_type: &DatabaseType, name: &str) -> String {
+    match db_type {
+        DatabaseType::MySQL => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+// 生成 CREATE INDEX 语句（索引名限制在63个字符内）
+pub fn create_index_sql(db_type: &DatabaseType, table: &str, columns: &[String]) -> String {
+    let mut name = format!("idx_{}_{}", table, columns.join("_")).to_lowercase();
+    name.truncate(63);
+    format!(
+        "CREATE INDEX {} ON {} ({})",
+        quote_identifier(db_type, &name),
+        quote_identifier(db_type, table),
+        columns.iter().map(|c| quote_identifier(db_type, c)).collect::<Vec<_>>().join(", ")
+    )
+}
+
+fn starts_with_columns(index: &[String], columns: &[String]) -> bool {
+    index.len() >= columns.len() && index.iter().zip(columns).all(|(a, b)| a.eq_ignore_ascii_case(b))
+}
+
+// 根据工作负载和已有索引生成建议；existing 为表名 -> 已有索引
+pub fn suggest_indexes(
+    workload: &[String],
+    existing: &HashMap<String, Vec<ExistingIndex>>,
+    db_type: &DatabaseType,
+) -> Vec<IndexSuggestion> {
+    let mut suggestions: Vec<IndexSuggestion> = Vec::new();
+
+    for sql in workload {
+        for usage in analyze_sql(sql) {
+            let candidate = candidate_columns(&usage);
+            if candidate.is_empty() {
+                continue;
+            }
+            let columns: Vec<String> = candidate.iter().map(|(c, _)| c.clone()).collect();
+            let indexes = existing.get(&usage.table).map(Vec::as_slice).unwrap_or(&[]);
+
+            // 已有索引以候选列为前缀时无需新建
+            if indexes.iter().any(|index| starts_with_columns(&index.columns, &columns)) {
+                continue;
+            }
+
+            if let Some(suggestion) = suggestions.iter_mut()
+                .find(|s| s.table == usage.table && s.columns.len() == columns.len() && starts_with_columns(&s.columns, &columns))
+            {
+                suggestion.occurrences += 1;
+                continue;
+            }
+
+            // 已有索引只覆盖了首列
+            let partially_covered_by = indexes.iter()
+                .find(|index| starts_with_columns(&index.columns, &columns[..1]))
+                .map(|index| index.name.clone());
+
+            suggestions.push(IndexSuggestion {
+                create_sql: create_index_sql(db_type, &usage.table, &columns),
+                table: usage.table.clone(),
+                columns,
+                usages: candidate.iter().map(|(_, u)| *u).collect(),
+                occurrences: 1,
+                sample_sql: sql.clone(),
+                partially_covered_by,
+            });
+        }
+    }
+
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.occurrences));
+    suggestions
+}
+
+// 构建请求AI解释索引建议的提示词
+pub fn explanation_prompt(db_type: &DatabaseType, suggestions: &[IndexSuggestion]) -> String {
+    let mut prompt = format!(
+        "以下是根据 {:?} 数据库的查询工作负载生成的索引建议。请逐条简要说明每个索引对哪些查询有帮助、列顺序的原因，以及可能的写入开销，使用中文回答。\n\n",
+        db_type
+    );
+    for (i, suggestion) in suggestions.iter().enumerate() {
+        prompt.push_str(&format!(
+            "{}. {}\n   受益查询数: {}\n   示例SQL: {}\n",
+            i + 1, suggestion.create_sql, suggestion.occurrences, suggestion.sample_sql
+        ));
+    }
+    prompt
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_sql() {
+        let usage = analyze_sql(
+            "SELECT * FROM orders o JOIN users u ON u.id = o.user_id \
+             WHERE o.status = 'paid' AND o.created_at > '2024-01-01' ORDER BY o.created_at"
+        );
+        let orders = usage.iter().find(|t| t.table == "orders").unwrap();
+        assert_eq!(orders.columns, vec![
+            ("user_id".to_string(), ColumnUsage::Join),
+            ("status".to_string(), ColumnUsage::Equality),
+            ("created_at".to_string(), ColumnUsage::Range),
+        ]);
+        let users = usage.iter().find(|t| t.table == "users").unwrap();
+        assert_eq!(users.columns, vec![("id".to_string(), ColumnUsage::Join)]);
+    }
+
+    #[test]
+    fn test_candidate_columns_order() {
+        let usage = analyze_sql("SELECT * FROM logs WHERE level = 'error' AND ts >= 100 AND host IN ('a', 'b')");
+        let columns: Vec<String> = candidate_columns(&usage[0]).into_iter().map(|(c, _)| c).collect();
+        assert_eq!(columns, vec!["level", "host", "ts"]);
+
+        // 前导通配符的LIKE不能使用索引
+        assert!(analyze_sql("SELECT * FROM logs WHERE message LIKE '%timeout%'").is_empty());
+    }
+
+    #[test]
+    fn test_suggest_indexes() {
+        let workload = vec![
+            "SELECT * FROM users WHERE email = 'a@b.c'".to_string(),
+            "SELECT * FROM users WHERE email = 'x@y.z'".to_string(),
+            "SELECT * FROM users WHERE id = 1".to_string(),
+            "SELECT * FROM users WHERE status = 1 ORDER BY created_at".to_string(),
+        ];
+        let mut existing = HashMap::new();
+        existing.insert("users".to_string(), vec![
+            ExistingIndex { name: "PRIMARY".to_string(), columns: vec!["id".to_string()] },
+            ExistingIndex { name: "idx_status".to_string(), columns: vec!["status".to_string()] },
+        ]);
+
+        let suggestions = suggest_indexes(&workload, &existing, &DatabaseType::MySQL);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].columns, vec!["email"]);
+        assert_eq!(suggestions[0].occurrences, 2);
+        assert_eq!(suggestions[0].create_sql, "CREATE INDEX `idx_users_email` ON `users` (`email`)");
+        assert_eq!(suggestions[1].columns, vec!["status", "created_at"]);
+        assert_eq!(suggestions[1].partially_covered_by.as_deref(), Some("idx_status"));
+    }
+}
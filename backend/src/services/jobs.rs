@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::services::events::{self, ServerEvent};
+
+// 已结束任务的保留时间（毫秒），超过后连同结果一起清理
+pub const JOB_RETENTION_MS: i64 = 60 * 60 * 1000;
+// 最多保留的已结束任务数
+pub const MAX_FINISHED_JOBS: usize = 200;
+
+// 任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Query,
+    Script,
+    Backup,
+}
+
+// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+// 任务信息（列表和详情接口返回）
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub description: String,
+    pub connection_id: Option<i64>,
+    pub progress: Option<f64>,       // 进度百分比（0-100），无法估计时为空
+    pub error: Option<String>,
+    pub has_result: bool,
+    pub created_at: i64,             // 毫秒时间戳
+    pub finished_at: Option<i64>,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    result: Option<JsonValue>,
+    cancel: watch::Sender<bool>,
+}
+
+// 任务执行方持有的句柄，用于上报进度和响应取消
+pub struct JobHandle {
+    pub id: String,
+    cancel: watch::Receiver<bool>,
+}
+
+impl JobHandle {
+    // 等待取消请求（任务结束前一直挂起）
+    pub async fn cancelled(&mut self) {
+        let _ = self.cancel.wait_for(|cancelled| *cancelled).await;
+    }
+
+    pub fn set_progress(&self, percent: f64) {
+        registry().set_progress(&self.id, percent);
+    }
+}
+
+// 任务列表过滤条件
+#[derive(Debug, Default, Deserialize)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    pub kind: Option<JobKind>,
+    pub connection_id: Option<i64>,
+}
+
+// 取消 / 删除任务失败的原因
+#[derive(Debug, PartialEq, Eq)]
+pub enum JobError {
+    NotFound,
+    InvalidState(JobStatus),   // 取消时任务已结束，删除时任务仍在运行
+}
+
+// 后台任务登记表：记录运行中和已结束的任务，已结束的任务按保留时间和数量自动清理
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+}
+
+impl Default for JobRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 登记新任务，id 为空时自动生成
+    pub fn register(&self, id: Option<String>, kind: JobKind, connection_id: Option<i64>, description: impl Into<String>) -> Result<JobHandle, String> {
+        let id = id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).unwrap_or_else(|| Uuid::new_v4().to_string());
+        let (cancel, cancel_rx) = watch::channel(false);
+        let mut jobs = self.jobs.lock().unwrap();
+        prune(&mut jobs, events::now());
+        if jobs.contains_key(&id) {
+            return Err(format!("任务ID {} 已存在", id));
+        }
+        let info = JobInfo {
+            id: id.clone(),
+            kind,
+            status: JobStatus::Running,
+            description: description.into(),
+            connection_id,
+            progress: None,
+            error: None,
+            has_result: false,
+            created_at: events::now(),
+            finished_at: None,
+        };
+        jobs.insert(id.clone(), JobEntry { info, result: None, cancel });
+        Ok(JobHandle { id, cancel: cancel_rx })
+    }
+
+    pub fn set_progress(&self, id: &str, percent: f64) {
+        if let Some(entry) = self.jobs.lock().unwrap().get_mut(id) {
+            if entry.info.status == JobStatus::Running {
+                entry.info.progress = Some(percent.clamp(0.0, 100.0));
+            }
+        }
+    }
+
+    // 记录任务结果；已被取消的任务保持取消状态
+    pub fn finish(&self, id: &str, outcome: Result<Option<JsonValue>, String>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(entry) = jobs.get_mut(id) else {
+            return;
+        };
+        if entry.info.status == JobStatus::Running {
+            match outcome {
+                Ok(result) => {
+                    entry.info.status = JobStatus::Succeeded;
+                    entry.info.progress = Some(100.0);
+                    entry.info.has_result = result.is_some();
+                    entry.result = result;
+                }
+                Err(error) => {
+                    entry.info.status = JobStatus::Failed;
+                    entry.info.error = Some(error);
+                }
+            }
+            entry.info.finished_at = Some(events::now());
+        }
+        let info = entry.info.clone();
+        drop(jobs);
+        debug!("[Jobs] 任务结束: id={}, kind={:?}, status={:?}", info.id, info.kind, info.status);
+        events::publish(ServerEvent::JobFinished {
+            job_id: info.id,
+            kind: info.kind,
+            status: info.status,
+            error: info.error,
+            timestamp: events::now(),
+        });
+    }
+
+    // 请求取消运行中的任务，任务执行方收到取消请求后自行结束
+    pub fn cancel(&self, id: &str) -> Result<JobInfo, JobError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(id).ok_or(JobError::NotFound)?;
+        if entry.info.status != JobStatus::Running {
+            return Err(JobError::InvalidState(entry.info.status));
+        }
+        entry.info.status = JobStatus::Cancelled;
+        entry.info.finished_at = Some(events::now());
+        let _ = entry.cancel.send(true);
+        Ok(entry.info.clone())
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobInfo> {
+        self.jobs.lock().unwrap().get(id).map(|entry| entry.info.clone())
+    }
+
+    pub fn result(&self, id: &str) -> Option<(JobInfo, Option<JsonValue>)> {
+        self.jobs.lock().unwrap().get(id).map(|entry| (entry.info.clone(), entry.result.clone()))
+    }
+
+    // 按创建时间倒序列出任务
+    pub fn list(&self, filter: &JobFilter) -> Vec<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        prune(&mut jobs, events::now());
+        let mut list: Vec<JobInfo> = jobs.values()
+            .map(|entry| &entry.info)
+            .filter(|info| filter.status.is_none_or(|s| info.status == s))
+            .filter(|info| filter.kind.is_none_or(|k| info.kind == k))
+            .filter(|info| filter.connection_id.is_none_or(|c| info.connection_id == Some(c)))
+            .cloned()
+            .collect();
+        list.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+        list
+    }
+
+    // 删除已结束的任务及其结果
+    pub fn remove(&self, id: &str) -> Result<JobInfo, JobError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let status = jobs.get(id).map(|entry| entry.info.status).ok_or(JobError::NotFound)?;
+        if status == JobStatus::Running {
+            return Err(JobError::InvalidState(status));
+        }
+        jobs.remove(id).map(|entry| entry.info).ok_or(JobError::NotFound)
+    }
+}
+
+// 清理超过保留时间的已结束任务，并只保留最近的 MAX_FINISHED_JOBS 个
+fn prune(jobs: &mut HashMap<String, JobEntry>, now: i64) {
+    jobs.retain(|_, entry| entry.info.finished_at.is_none_or(|t| now - t < JOB_RETENTION_MS));
+    let mut finished: Vec<(i64, String)> = jobs.values()
+        .filter_map(|entry| entry.info.finished_at.map(|t| (t, entry.info.id.clone())))
+        .collect();
+    if finished.len() > MAX_FINISHED_JOBS {
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+            jobs.remove(id);
+        }
+    }
+}
+
+static JOB_REGISTRY: OnceLock<JobRegistry> = OnceLock::new();
+
+// 全局任务登记表
+pub fn registry() -> &'static JobRegistry {
+    JOB_REGISTRY.get_or_init(JobRegistry::new)
+}
+
+// 在后台执行任务，结束后记录结果
+pub fn spawn<F, Fut>(handle: JobHandle, run: F)
+where
+    F: FnOnce(JobHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Option<JsonValue>, String>> + Send + 'static,
+{
+    let id = handle.id.clone();
+    tokio::spawn(async move {
+        let outcome = run(handle).await;
+        registry().finish(&id, outcome);
+    });
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_lifecycle() {
+        let jobs = JobRegistry::new();
+        let first = jobs.register(Some("job-1".to_string()), JobKind::Query, Some(1), "SELECT 1").unwrap();
+        assert!(jobs.register(Some("job-1".to_string()), JobKind::Query, None, "dup").is_err());
+        let second = jobs.register(None, JobKind::Script, Some(2), "script").unwrap();
+
+        jobs.set_progress(&second.id, 150.0);
+        assert_eq!(jobs.get(&second.id).unwrap().progress, Some(100.0));
+
+        jobs.finish(&first.id, Ok(Some(serde_json::json!({"rows": 1}))));
+        let (info, result) = jobs.result("job-1").unwrap();
+        assert_eq!(info.status, JobStatus::Succeeded);
+        assert!(info.has_result && result.is_some());
+        assert_eq!(jobs.cancel("job-1").unwrap_err(), JobError::InvalidState(JobStatus::Succeeded));
+
+        assert_eq!(jobs.cancel(&second.id).unwrap().status, JobStatus::Cancelled);
+        assert!(*second.cancel.borrow());
+        // 取消后执行方上报的结果不覆盖取消状态
+        jobs.finish(&second.id, Err("查询已被取消".to_string()));
+        assert_eq!(jobs.get(&second.id).unwrap().status, JobStatus::Cancelled);
+
+        let filter = JobFilter { kind: Some(JobKind::Script), ..Default::default() };
+        assert_eq!(jobs.list(&filter).len(), 1);
+        assert_eq!(jobs.remove("missing").unwrap_err(), JobError::NotFound);
+        assert!(jobs.remove("job-1").is_ok());
+        assert_eq!(jobs.list(&JobFilter::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_prune_finished_jobs() {
+        let jobs = JobRegistry::new();
+        let running = jobs.register(None, JobKind::Backup, None, "backup").unwrap();
+        for i in 0..MAX_FINISHED_JOBS + 5 {
+            let handle = jobs.register(Some(format!("job-{}", i)), JobKind::Query, None, "q").unwrap();
+            jobs.finish(&handle.id, Ok(None));
+        }
+        let mut map = jobs.jobs.lock().unwrap();
+        prune(&mut map, events::now());
+        assert_eq!(map.len(), MAX_FINISHED_JOBS + 1);
+        prune(&mut map, events::now() + JOB_RETENTION_MS);
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(&running.id));
+    }
+}
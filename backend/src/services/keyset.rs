@@ -0,0 +1,143 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde_json::Value as JsonValue;
+
+use crate::db::ddl::quote_identifier;
+use crate::db::DatabaseType;
+
+// 游标分页最多支持的排序列数
+const MAX_KEYSET_COLUMNS: usize = 4;
+
+// 游标分页（keyset）参数：按排序列的值定位，不使用 OFFSET
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyset {
+    pub columns: Vec<String>,
+    pub descending: bool,
+    pub after: Option<Vec<JsonValue>>,    // 上一页最后一行的排序列值，第一页为空
+}
+
+impl Keyset {
+    // 校验排序列并解析游标
+    pub fn new(columns: &[String], descending: bool, cursor: Option<&str>) -> Result<Self, String> {
+        let columns: Vec<String> = columns.iter().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+        if columns.is_empty() {
+            return Err("游标分页需要至少一个排序列".to_string());
+        }
+        if columns.len() > MAX_KEYSET_COLUMNS {
+            return Err(format!("游标分页最多支持 {} 个排序列", MAX_KEYSET_COLUMNS));
+        }
+        let after = match cursor.map(str::trim).filter(|c| !c.is_empty()) {
+            Some(cursor) => Some(decode_cursor(cursor, columns.len())?),
+            None => None,
+        };
+        Ok(Self { columns, descending, after })
+    }
+
+    // 游标中的值，作为追加在查询参数之后的绑定参数
+    pub fn params(&self) -> &[JsonValue] {
+        self.after.as_deref().unwrap_or(&[])
+    }
+}
+
+// 游标为排序列值的JSON数组（URL安全的base64编码）
+pub fn encode_cursor(values: &[JsonValue]) -> String {
+    URL_SAFE_NO_PAD.encode(JsonValue::Array(values.to_vec()).to_string())
+}
+
+fn decode_cursor(cursor: &str, expected: usize) -> Result<Vec<JsonValue>, String> {
+    let invalid = || "无效的分页游标".to_string();
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let JsonValue::Array(values) = serde_json::from_slice(&bytes).map_err(|_| invalid())? else {
+        return Err(invalid());
+    };
+    if values.len() != expected {
+        return Err(format!("分页游标包含 {} 个值，与 {} 个排序列不一致", values.len(), expected));
+    }
+    if !values.iter().all(|v| v.is_string() || v.is_number() || v.is_boolean()) {
+        return Err(invalid());
+    }
+    Ok(values)
+}
+
+fn placeholder(db_type: DatabaseType, n: usize) -> String {
+    match db_type {
+        DatabaseType::PostgreSQL => format!("${}", n),
+        _ => "?".to_string(),
+    }
+}
+
+// 将查询包装为游标分页查询：按排序列排序，从游标之后开始，多取一行用于判断是否还有下一页
+// 游标值的占位符编号从 param_offset + 1 开始（排在查询自身的参数之后）
+pub fn build_keyset_sql(db_type: DatabaseType, sql: &str, keyset: &Keyset, param_offset: usize, limit: u64) -> Result<String, String> {
+    let columns = keyset.columns.iter()
+        .map(|c| quote_identifier(db_type, c))
+        .collect::<Result<Vec<_>, _>>()?;
+    let direction = if keyset.descending { "DESC" } else { "ASC" };
+
+    let mut paged = format!("SELECT * FROM ({}) AS _paged", sql.trim().trim_end_matches(';').trim_end());
+    if keyset.after.is_some() {
+        let placeholders: Vec<String> = (1..=columns.len()).map(|i| placeholder(db_type, param_offset + i)).collect();
+        let op = if keyset.descending { "<" } else { ">" };
+        // 多列时使用行值比较 (a, b) > (?, ?)
+        if columns.len() == 1 {
+            paged.push_str(&format!(" WHERE {} {} {}", columns[0], op, placeholders[0]));
+        } else {
+            paged.push_str(&format!(" WHERE ({}) {} ({})", columns.join(", "), op, placeholders.join(", ")));
+        }
+    }
+    let order: Vec<String> = columns.iter().map(|c| format!("{} {}", c, direction)).collect();
+    paged.push_str(&format!(" ORDER BY {} LIMIT {}", order.join(", "), limit));
+    Ok(paged)
+}
+
+// 根据本页最后一行生成下一页的游标
+pub fn next_cursor(keyset: &Keyset, columns: &[String], last_row: &[JsonValue]) -> Result<String, String> {
+    let values = keyset.columns.iter()
+        .map(|name| {
+            let index = columns.iter().position(|c| c.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("排序列 {} 不在查询结果中", name))?;
+            match last_row.get(index) {
+                Some(JsonValue::Null) | None => Err(format!("排序列 {} 存在 NULL 值，无法使用游标分页", name)),
+                Some(value) => Ok(value.clone()),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(encode_cursor(&values))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_keyset_sql_and_cursor() {
+        let columns = vec!["created_at".to_string(), "id".to_string()];
+        let first = Keyset::new(&columns, false, None).unwrap();
+        assert_eq!(
+            build_keyset_sql(DatabaseType::MySQL, "SELECT * FROM orders;", &first, 0, 51).unwrap(),
+            "SELECT * FROM (SELECT * FROM orders) AS _paged ORDER BY `created_at` ASC, `id` ASC LIMIT 51"
+        );
+
+        let result_columns = vec!["id".to_string(), "created_at".to_string(), "total".to_string()];
+        let cursor = next_cursor(&first, &result_columns, &[json!(42), json!("2024-05-01 10:00:00"), json!(9.5)]).unwrap();
+        let next = Keyset::new(&columns, true, Some(&cursor)).unwrap();
+        assert_eq!(next.params(), &[json!("2024-05-01 10:00:00"), json!(42)]);
+        assert_eq!(
+            build_keyset_sql(DatabaseType::PostgreSQL, "SELECT * FROM orders WHERE status = $1", &next, 1, 51).unwrap(),
+            "SELECT * FROM (SELECT * FROM orders WHERE status = $1) AS _paged WHERE (\"created_at\", \"id\") < ($2, $3) ORDER BY \"created_at\" DESC, \"id\" DESC LIMIT 51"
+        );
+
+        let single = Keyset::new(&["id".to_string()], false, Some(&encode_cursor(&[json!(7)]))).unwrap();
+        assert_eq!(
+            build_keyset_sql(DatabaseType::SQLite, "SELECT id FROM t", &single, 0, 11).unwrap(),
+            "SELECT * FROM (SELECT id FROM t) AS _paged WHERE \"id\" > ? ORDER BY \"id\" ASC LIMIT 11"
+        );
+
+        assert!(Keyset::new(&[], false, None).is_err());
+        assert!(Keyset::new(&columns, false, Some("not-a-cursor")).is_err());
+        assert!(Keyset::new(&columns, false, Some(&encode_cursor(&[json!(1)]))).is_err());
+        assert!(next_cursor(&first, &result_columns, &[json!(1), JsonValue::Null, json!(0)]).is_err());
+        assert!(next_cursor(&first, &["id".to_string()], &[json!(1)]).is_err());
+    }
+}
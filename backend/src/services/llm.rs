@@ -0,0 +1,643 @@
+use serde::{Deserialize, Serialize};
+use reqwest::{Client, RequestBuilder};
+
+use crate::services::ai::AiServiceError;
+use crate::utils::security::mask_secret;
+
+// Azure OpenAI 默认API版本
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-02-15-preview";
+// Anthropic Messages API 版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+// AI服务商类型（AI配置中的 provider 字段）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    OpenAi,     // OpenAI 及其他兼容 chat/completions 的服务
+    Azure,      // Azure OpenAI
+    Anthropic,
+    DeepSeek,
+    Ollama,     // 本地 Ollama
+}
+
+impl ProviderKind {
+    pub const ALL: [ProviderKind; 5] = [
+        ProviderKind::OpenAi,
+        ProviderKind::Azure,
+        ProviderKind::Anthropic,
+        ProviderKind::DeepSeek,
+        ProviderKind::Ollama,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Azure => "azure",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::DeepSeek => "deepseek",
+            ProviderKind::Ollama => "ollama",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+
+    // 未配置 base_url 时的默认地址（Azure 需要填写资源地址）
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "https://api.openai.com/v1",
+            ProviderKind::Azure => "",
+            ProviderKind::Anthropic => "https://api.anthropic.com/v1",
+            ProviderKind::DeepSeek => "https://api.deepseek.com/v1",
+            ProviderKind::Ollama => "http://localhost:11434",
+        }
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi | ProviderKind::Azure => "gpt-4o-mini",
+            ProviderKind::Anthropic => "claude-3-5-haiku-latest",
+            ProviderKind::DeepSeek => "deepseek-chat",
+            ProviderKind::Ollama => "llama3.1",
+        }
+    }
+
+    // 本地 Ollama 不需要API密钥
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self, ProviderKind::Ollama)
+    }
+
+    pub fn provider(&self) -> &'static dyn LlmProvider {
+        match self {
+            ProviderKind::OpenAi | ProviderKind::DeepSeek => &OpenAiCompatibleProvider,
+            ProviderKind::Azure => &AzureOpenAiProvider,
+            ProviderKind::Anthropic => &AnthropicProvider,
+            ProviderKind::Ollama => &OllamaProvider,
+        }
+    }
+}
+
+// 当前生效的AI配置
+#[derive(Debug, Clone)]
+pub struct LlmConfig {
+    pub provider: ProviderKind,
+    pub api_key: String,
+    pub base_url: String,
+    pub model: String,
+    pub api_version: Option<String>,  // 仅 Azure 使用
+}
+
+impl LlmConfig {
+    fn base_url(&self) -> &str {
+        self.base_url.trim_end_matches('/')
+    }
+
+    // 将文本（如服务商返回的错误信息）中出现的API密钥替换为掩码，避免写入日志或返回前端
+    pub fn redact(&self, text: &str) -> String {
+        if self.api_key.len() < 8 {
+            return text.to_string();
+        }
+        text.replace(&self.api_key, &mask_secret(&self.api_key))
+    }
+}
+
+// 一次聊天请求
+#[derive(Debug, Clone, Copy)]
+pub struct ChatRequest<'a> {
+    pub messages: &'a [(String, String)],  // (role, content)
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub stream: bool,
+}
+
+// Token使用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl TokenUsage {
+    // 合并流式响应中分段返回的用量（各字段为累计值，取最大值）
+    pub fn merge(self, other: TokenUsage) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.max(other.prompt_tokens),
+            completion_tokens: self.completion_tokens.max(other.completion_tokens),
+        }
+    }
+}
+
+fn usage_field(value: &serde_json::Value, pointer: &str) -> u32 {
+    value.pointer(pointer).and_then(|v| v.as_u64()).unwrap_or(0) as u32
+}
+
+// 非流式请求的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatCompletion {
+    pub content: String,
+    pub usage: Option<TokenUsage>,
+}
+
+// 流式响应中一行数据的解析结果
+#[derive(Debug, PartialEq)]
+pub enum StreamChunk {
+    Delta(String),   // 增量文本
+    Done,            // 流结束
+    Error(String),   // 服务端在流中返回的错误
+    Usage(TokenUsage), // 流中返回的Token用量
+    Skip,            // 空行、注释、无内容的增量
+}
+
+// AI服务商接口：负责构建请求（地址、认证头、请求体）和解析响应
+pub trait LlmProvider: Send + Sync {
+    fn build_chat_request(&self, client: &Client, config: &LlmConfig, request: &ChatRequest) -> RequestBuilder;
+
+    fn parse_chat_response(&self, body: &str) -> Result<ChatCompletion, AiServiceError>;
+
+    fn parse_stream_line(&self, line: &str) -> StreamChunk;
+
+    // OpenAI 格式的嵌入接口请求，服务商不支持时返回None
+    fn build_embeddings_request(&self, _client: &Client, _config: &LlmConfig, _model: &str, _inputs: &[String]) -> Option<RequestBuilder> {
+        None
+    }
+}
+
+fn parse_error(e: serde_json::Error) -> AiServiceError {
+    AiServiceError::ParseError(format!("JSON解析失败: {}", e))
+}
+
+// ---------- OpenAI 兼容（OpenAI / DeepSeek / Azure） ----------
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiChatRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    // 流式请求时要求在最后一个数据块返回用量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<serde_json::Value>,
+}
+
+impl<'a> OpenAiChatRequest<'a> {
+    fn new(model: Option<&'a str>, request: &ChatRequest) -> Self {
+        Self {
+            model,
+            messages: request.messages.iter()
+                .map(|(role, content)| ChatMessage { role: role.clone(), content: content.clone() })
+                .collect(),
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+            stream: request.stream,
+            stream_options: None,
+        }
+    }
+
+    fn with_stream_usage(mut self) -> Self {
+        if self.stream {
+            self.stream_options = Some(serde_json::json!({ "include_usage": true }));
+        }
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChatResponse {
+    choices: Vec<OpenAiChoice>,
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+fn parse_openai_response(body: &str) -> Result<ChatCompletion, AiServiceError> {
+    let response: OpenAiChatResponse = serde_json::from_str(body).map_err(parse_error)?;
+    let choice = response.choices.into_iter().next()
+        .ok_or_else(|| AiServiceError::ParseError("未返回任何回复".to_string()))?;
+    Ok(ChatCompletion {
+        content: choice.message.content,
+        usage: response.usage.map(|u| TokenUsage { prompt_tokens: u.prompt_tokens, completion_tokens: u.completion_tokens }),
+    })
+}
+
+// 解析OpenAI流式响应的一行（data: {...}）
+pub fn parse_openai_stream_line(line: &str) -> StreamChunk {
+    let data = match line.trim().strip_prefix("data:") {
+        Some(data) => data.trim(),
+        None => return StreamChunk::Skip,
+    };
+    if data == "[DONE]" {
+        return StreamChunk::Done;
+    }
+    let value: serde_json::Value = match serde_json::from_str(data) {
+        Ok(value) => value,
+        Err(_) => return StreamChunk::Skip,
+    };
+    if let Some(error) = value.get("error") {
+        let message = error.get("message").and_then(|m| m.as_str()).map(str::to_string).unwrap_or_else(|| error.to_string());
+        return StreamChunk::Error(message);
+    }
+    // 开启 include_usage 时最后一个数据块的 choices 为空，只包含用量
+    if value.get("usage").is_some_and(|u| u.is_object()) {
+        return StreamChunk::Usage(TokenUsage {
+            prompt_tokens: usage_field(&value, "/usage/prompt_tokens"),
+            completion_tokens: usage_field(&value, "/usage/completion_tokens"),
+        });
+    }
+    match value.pointer("/choices/0/delta/content").and_then(|c| c.as_str()) {
+        Some(content) if !content.is_empty() => StreamChunk::Delta(content.to_string()),
+        _ => StreamChunk::Skip,
+    }
+}
+
+pub struct OpenAiCompatibleProvider;
+
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn build_chat_request(&self, client: &Client, config: &LlmConfig, request: &ChatRequest) -> RequestBuilder {
+        client.post(format!("{}/chat/completions", config.base_url()))
+            .bearer_auth(&config.api_key)
+            .json(&OpenAiChatRequest::new(Some(config.model.as_str()), request).with_stream_usage())
+    }
+
+    fn parse_chat_response(&self, body: &str) -> Result<ChatCompletion, AiServiceError> {
+        parse_openai_response(body)
+    }
+
+    fn parse_stream_line(&self, line: &str) -> StreamChunk {
+        parse_openai_stream_line(line)
+    }
+
+    fn build_embeddings_request(&self, client: &Client, config: &LlmConfig, model: &str, inputs: &[String]) -> Option<RequestBuilder> {
+        Some(client.post(format!("{}/embeddings", config.base_url()))
+            .bearer_auth(&config.api_key)
+            .json(&EmbeddingRequest { model, input: inputs }))
+    }
+}
+
+// Azure OpenAI：模型名即部署名，使用 api-key 认证头
+pub struct AzureOpenAiProvider;
+
+impl AzureOpenAiProvider {
+    fn deployment_url(config: &LlmConfig, deployment: &str, operation: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{}?api-version={}",
+            config.base_url(),
+            deployment,
+            operation,
+            config.api_version.as_deref().unwrap_or(DEFAULT_AZURE_API_VERSION)
+        )
+    }
+}
+
+impl LlmProvider for AzureOpenAiProvider {
+    fn build_chat_request(&self, client: &Client, config: &LlmConfig, request: &ChatRequest) -> RequestBuilder {
+        client.post(Self::deployment_url(config, &config.model, "chat/completions"))
+            .header("api-key", &config.api_key)
+            .json(&OpenAiChatRequest::new(None, request))
+    }
+
+    fn parse_chat_response(&self, body: &str) -> Result<ChatCompletion, AiServiceError> {
+        parse_openai_response(body)
+    }
+
+    fn parse_stream_line(&self, line: &str) -> StreamChunk {
+        parse_openai_stream_line(line)
+    }
+
+    fn build_embeddings_request(&self, client: &Client, config: &LlmConfig, model: &str, inputs: &[String]) -> Option<RequestBuilder> {
+        Some(client.post(Self::deployment_url(config, model, "embeddings"))
+            .header("api-key", &config.api_key)
+            .json(&EmbeddingRequest { model, input: inputs }))
+    }
+}
+
+// ---------- Anthropic Messages API ----------
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest<'a> {
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<ChatMessage>,
+    max_tokens: u32,
+    temperature: f32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicUsage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+pub struct AnthropicProvider;
+
+impl LlmProvider for AnthropicProvider {
+    fn build_chat_request(&self, client: &Client, config: &LlmConfig, request: &ChatRequest) -> RequestBuilder {
+        // system 消息单独放在 system 字段，其余消息按原顺序发送
+        let system = request.messages.iter()
+            .filter(|(role, _)| role == "system")
+            .map(|(_, content)| content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let body = AnthropicRequest {
+            model: &config.model,
+            system: Some(system).filter(|s| !s.is_empty()),
+            messages: request.messages.iter()
+                .filter(|(role, _)| role != "system")
+                .map(|(role, content)| ChatMessage { role: role.clone(), content: content.clone() })
+                .collect(),
+            max_tokens: request.max_tokens,
+            temperature: request.temperature,
+            stream: request.stream,
+        };
+        client.post(format!("{}/messages", config.base_url()))
+            .header("x-api-key", &config.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+    }
+
+    fn parse_chat_response(&self, body: &str) -> Result<ChatCompletion, AiServiceError> {
+        let response: AnthropicResponse = serde_json::from_str(body).map_err(parse_error)?;
+        let content = response.content.into_iter()
+            .filter(|c| c.kind == "text")
+            .map(|c| c.text)
+            .collect::<String>();
+        Ok(ChatCompletion {
+            content,
+            usage: response.usage.map(|u| TokenUsage { prompt_tokens: u.input_tokens, completion_tokens: u.output_tokens }),
+        })
+    }
+
+    // 流式事件：content_block_delta 携带增量文本，message_stop 表示结束
+    fn parse_stream_line(&self, line: &str) -> StreamChunk {
+        let data = match line.trim().strip_prefix("data:") {
+            Some(data) => data.trim(),
+            None => return StreamChunk::Skip,
+        };
+        let value: serde_json::Value = match serde_json::from_str(data) {
+            Ok(value) => value,
+            Err(_) => return StreamChunk::Skip,
+        };
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("content_block_delta") => match value.pointer("/delta/text").and_then(|t| t.as_str()) {
+                Some(text) if !text.is_empty() => StreamChunk::Delta(text.to_string()),
+                _ => StreamChunk::Skip,
+            },
+            // 输入用量在 message_start 中返回，输出用量在 message_delta 中累计返回
+            Some("message_start") => StreamChunk::Usage(TokenUsage {
+                prompt_tokens: usage_field(&value, "/message/usage/input_tokens"),
+                completion_tokens: usage_field(&value, "/message/usage/output_tokens"),
+            }),
+            Some("message_delta") => StreamChunk::Usage(TokenUsage {
+                prompt_tokens: usage_field(&value, "/usage/input_tokens"),
+                completion_tokens: usage_field(&value, "/usage/output_tokens"),
+            }),
+            Some("message_stop") => StreamChunk::Done,
+            Some("error") => StreamChunk::Error(
+                value.pointer("/error/message").and_then(|m| m.as_str()).unwrap_or("未知错误").to_string()
+            ),
+            _ => StreamChunk::Skip,
+        }
+    }
+}
+
+// ---------- Ollama ----------
+
+#[derive(Debug, Serialize)]
+struct OllamaRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+    num_predict: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    message: ChatMessage,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+}
+
+pub struct OllamaProvider;
+
+impl LlmProvider for OllamaProvider {
+    fn build_chat_request(&self, client: &Client, config: &LlmConfig, request: &ChatRequest) -> RequestBuilder {
+        let body = OllamaRequest {
+            model: &config.model,
+            messages: request.messages.iter()
+                .map(|(role, content)| ChatMessage { role: role.clone(), content: content.clone() })
+                .collect(),
+            // Ollama 默认流式返回，非流式请求需显式关闭
+            stream: request.stream,
+            options: OllamaOptions { temperature: request.temperature, num_predict: request.max_tokens },
+        };
+        client.post(format!("{}/api/chat", config.base_url())).json(&body)
+    }
+
+    fn parse_chat_response(&self, body: &str) -> Result<ChatCompletion, AiServiceError> {
+        let response: OllamaResponse = serde_json::from_str(body).map_err(parse_error)?;
+        let usage = match (response.prompt_eval_count, response.eval_count) {
+            (Some(prompt_tokens), Some(completion_tokens)) => Some(TokenUsage { prompt_tokens, completion_tokens }),
+            _ => None,
+        };
+        Ok(ChatCompletion { content: response.message.content, usage })
+    }
+
+    // 流式响应为每行一个JSON对象，done 为true时结束
+    fn parse_stream_line(&self, line: &str) -> StreamChunk {
+        let value: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(value) => value,
+            Err(_) => return StreamChunk::Skip,
+        };
+        if let Some(error) = value.get("error").and_then(|e| e.as_str()) {
+            return StreamChunk::Error(error.to_string());
+        }
+        // 最后一行带有用量统计，返回用量后服务端会关闭连接
+        if value.get("done").and_then(|d| d.as_bool()).unwrap_or(false) {
+            if value.get("eval_count").is_some() {
+                return StreamChunk::Usage(TokenUsage {
+                    prompt_tokens: usage_field(&value, "/prompt_eval_count"),
+                    completion_tokens: usage_field(&value, "/eval_count"),
+                });
+            }
+            return StreamChunk::Done;
+        }
+        match value.pointer("/message/content").and_then(|c| c.as_str()) {
+            Some(content) if !content.is_empty() => StreamChunk::Delta(content.to_string()),
+            _ => StreamChunk::Skip,
+        }
+    }
+
+    // 使用 Ollama 的 OpenAI 兼容嵌入接口
+    fn build_embeddings_request(&self, client: &Client, config: &LlmConfig, model: &str, inputs: &[String]) -> Option<RequestBuilder> {
+        Some(client.post(format!("{}/v1/embeddings", config.base_url()))
+            .json(&EmbeddingRequest { model, input: inputs }))
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: ProviderKind) -> LlmConfig {
+        LlmConfig {
+            provider,
+            api_key: "secret".to_string(),
+            base_url: "https://example.com/v1/".to_string(),
+            model: "test-model".to_string(),
+            api_version: None,
+        }
+    }
+
+    fn messages() -> Vec<(String, String)> {
+        vec![
+            ("system".to_string(), "你是SQL专家".to_string()),
+            ("user".to_string(), "统计用户数".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_provider_kind() {
+        assert_eq!(ProviderKind::parse(" Anthropic "), Some(ProviderKind::Anthropic));
+        assert_eq!(ProviderKind::parse("unknown"), None);
+        assert!(!ProviderKind::Ollama.requires_api_key());
+        assert_eq!(serde_json::to_string(&ProviderKind::DeepSeek).unwrap(), "\"deepseek\"");
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut config = config(ProviderKind::OpenAi);
+        config.api_key = "sk-abcdefghijklmnop1234".to_string();
+        assert_eq!(config.redact("Incorrect API key provided: sk-abcdefghijklmnop1234"), "Incorrect API key provided: sk-...1234");
+    }
+
+    #[test]
+    fn test_anthropic_request() {
+        let client = Client::new();
+        let messages = messages();
+        let request = ChatRequest { messages: &messages, temperature: 0.2, max_tokens: 100, stream: false };
+        let built = AnthropicProvider.build_chat_request(&client, &config(ProviderKind::Anthropic), &request).build().unwrap();
+
+        assert_eq!(built.url().as_str(), "https://example.com/v1/messages");
+        assert_eq!(built.headers()["x-api-key"], "secret");
+        let body: serde_json::Value = serde_json::from_slice(built.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["system"], "你是SQL专家");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_azure_request() {
+        let client = Client::new();
+        let messages = messages();
+        let request = ChatRequest { messages: &messages, temperature: 0.2, max_tokens: 100, stream: true };
+        let built = AzureOpenAiProvider.build_chat_request(&client, &config(ProviderKind::Azure), &request).build().unwrap();
+
+        assert_eq!(
+            built.url().as_str(),
+            format!("https://example.com/v1/openai/deployments/test-model/chat/completions?api-version={}", DEFAULT_AZURE_API_VERSION)
+        );
+        assert_eq!(built.headers()["api-key"], "secret");
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let openai = r#"{"id":"x","choices":[{"index":0,"message":{"role":"assistant","content":"SELECT 1"},"finish_reason":"stop"}],"usage":{"prompt_tokens":5,"completion_tokens":2,"total_tokens":7}}"#;
+        let parsed = OpenAiCompatibleProvider.parse_chat_response(openai).unwrap();
+        assert_eq!(parsed.content, "SELECT 1");
+        assert_eq!(parsed.usage, Some(TokenUsage { prompt_tokens: 5, completion_tokens: 2 }));
+
+        let anthropic = r#"{"content":[{"type":"text","text":"SELECT "},{"type":"text","text":"1"}],"usage":{"input_tokens":3,"output_tokens":4}}"#;
+        assert_eq!(AnthropicProvider.parse_chat_response(anthropic).unwrap().content, "SELECT 1");
+
+        let ollama = r#"{"message":{"role":"assistant","content":"SELECT 1"},"done":true,"prompt_eval_count":8,"eval_count":3}"#;
+        assert_eq!(OllamaProvider.parse_chat_response(ollama).unwrap().usage, Some(TokenUsage { prompt_tokens: 8, completion_tokens: 3 }));
+    }
+
+    #[test]
+    fn test_parse_stream_lines() {
+        assert_eq!(
+            AnthropicProvider.parse_stream_line(r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"SEL"}}"#),
+            StreamChunk::Delta("SEL".to_string())
+        );
+        assert_eq!(AnthropicProvider.parse_stream_line("event: message_stop"), StreamChunk::Skip);
+        assert_eq!(AnthropicProvider.parse_stream_line(r#"data: {"type":"message_stop"}"#), StreamChunk::Done);
+
+        assert_eq!(
+            OllamaProvider.parse_stream_line(r#"{"message":{"role":"assistant","content":"SEL"},"done":false}"#),
+            StreamChunk::Delta("SEL".to_string())
+        );
+        assert_eq!(OllamaProvider.parse_stream_line(r#"{"message":{"role":"assistant","content":""},"done":true}"#), StreamChunk::Done);
+        assert_eq!(
+            OllamaProvider.parse_stream_line(r#"{"message":{"role":"assistant","content":""},"done":true,"prompt_eval_count":8,"eval_count":3}"#),
+            StreamChunk::Usage(TokenUsage { prompt_tokens: 8, completion_tokens: 3 })
+        );
+    }
+
+    #[test]
+    fn test_stream_usage() {
+        assert_eq!(
+            parse_openai_stream_line(r#"data: {"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":5,"total_tokens":17}}"#),
+            StreamChunk::Usage(TokenUsage { prompt_tokens: 12, completion_tokens: 5 })
+        );
+
+        let start = AnthropicProvider.parse_stream_line(r#"data: {"type":"message_start","message":{"usage":{"input_tokens":20,"output_tokens":1}}}"#);
+        let delta = AnthropicProvider.parse_stream_line(r#"data: {"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":15}}"#);
+        match (start, delta) {
+            (StreamChunk::Usage(a), StreamChunk::Usage(b)) => {
+                assert_eq!(a.merge(b), TokenUsage { prompt_tokens: 20, completion_tokens: 15 });
+            }
+            other => panic!("unexpected chunks: {:?}", other),
+        }
+    }
+}
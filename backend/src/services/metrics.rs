@@ -0,0 +1,129 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use ::metrics::{counter, gauge, histogram};
+use log::*;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use metrics_util::MetricKindMask;
+
+use crate::db::pool_cache;
+use crate::services::llm::TokenUsage;
+
+// 指标名前缀
+const PREFIX: &str = "smart_sql";
+// 耗时直方图的分桶（秒）
+const DURATION_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+// AI调用耗时的分桶（秒）
+const AI_DURATION_BUCKETS: &[f64] = &[0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+// 超过该时间未更新的连接池指标被移除（连接池已释放）
+const POOL_GAUGE_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+static HANDLE: OnceLock<Option<PrometheusHandle>> = OnceLock::new();
+
+fn name(metric: &str) -> String {
+    format!("{}_{}", PREFIX, metric)
+}
+
+// 安装全局指标记录器（首次调用时安装，之后复用）
+fn handle() -> Option<&'static PrometheusHandle> {
+    HANDLE.get_or_init(|| {
+        let installed = PrometheusBuilder::new()
+            .set_buckets_for_metric(Matcher::Suffix("ai_request_duration_seconds".to_string()), AI_DURATION_BUCKETS)
+            .and_then(|builder| builder.set_buckets(DURATION_BUCKETS))
+            .map(|builder| builder.idle_timeout(MetricKindMask::GAUGE, Some(POOL_GAUGE_IDLE_TIMEOUT)))
+            .and_then(|builder| builder.install_recorder());
+        match installed {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                warn!("[Metrics] 指标记录器安装失败: {}", e);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+// 服务启动时调用，之后记录的指标才会被收集
+pub fn install() {
+    handle();
+}
+
+// 以 Prometheus 文本格式输出所有指标（输出前刷新连接池使用情况）
+pub fn render() -> String {
+    let Some(handle) = handle() else {
+        return String::new();
+    };
+    update_pool_gauges();
+    handle.render()
+}
+
+fn outcome(success: bool) -> &'static str {
+    if success { "success" } else { "error" }
+}
+
+// 记录一次HTTP请求（route 为路由模板，如 /api/connections/:id）
+pub fn record_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    counter!(name("http_requests_total"), "method" => method.to_string(), "route" => route.to_string(), "status" => status.to_string())
+        .increment(1);
+    histogram!(name("http_request_duration_seconds"), "method" => method.to_string(), "route" => route.to_string())
+        .record(elapsed.as_secs_f64());
+}
+
+// 记录一次查询执行（backend 为数据库类型）
+pub fn record_query(backend: &str, success: bool, elapsed: Duration) {
+    counter!(name("queries_total"), "backend" => backend.to_string(), "outcome" => outcome(success)).increment(1);
+    histogram!(name("query_duration_seconds"), "backend" => backend.to_string()).record(elapsed.as_secs_f64());
+}
+
+// 记录一次AI调用的耗时（feature 为功能名，如 sql_generation）
+pub fn record_ai_call(feature: &str, success: bool, elapsed: Duration) {
+    counter!(name("ai_requests_total"), "feature" => feature.to_string(), "outcome" => outcome(success)).increment(1);
+    histogram!(name("ai_request_duration_seconds"), "feature" => feature.to_string()).record(elapsed.as_secs_f64());
+}
+
+// 记录AI调用的Token用量
+pub fn record_ai_tokens(provider: &str, feature: &str, usage: TokenUsage) {
+    for (kind, tokens) in [("prompt", usage.prompt_tokens), ("completion", usage.completion_tokens)] {
+        counter!(name("ai_tokens_total"), "provider" => provider.to_string(), "feature" => feature.to_string(), "type" => kind)
+            .increment(tokens as u64);
+    }
+}
+
+// 缓存连接池的连接数（使用中 / 空闲 / 上限）
+fn update_pool_gauges() {
+    let pools = pool_cache::pool_usages();
+    gauge!(name("db_pools_cached")).set(pools.len() as f64);
+    for pool in pools {
+        let idle = pool.usage.idle as f64;
+        let active = (pool.usage.size as f64 - idle).max(0.0);
+        let max = pool.usage.max_connections as f64;
+        for (state, value) in [("idle", idle), ("active", active), ("max", max)] {
+            gauge!(
+                name("db_pool_connections"),
+                "connection_id" => pool.connection_id.to_string(),
+                "database" => pool.database.clone().unwrap_or_default(),
+                "backend" => pool.db_type.as_str(),
+                "state" => state
+            ).set(value);
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_recorded_metrics() {
+        install();
+        record_query("sqlite", true, Duration::from_millis(12));
+        record_request("GET", "/api/health", 200, Duration::from_millis(1));
+        record_ai_tokens("openai", "chat", TokenUsage { prompt_tokens: 10, completion_tokens: 5 });
+
+        let output = render();
+        assert!(output.contains("smart_sql_queries_total{backend=\"sqlite\",outcome=\"success\"}"));
+        assert!(output.contains("smart_sql_query_duration_seconds_bucket{backend=\"sqlite\""));
+        assert!(output.contains("route=\"/api/health\""));
+        assert!(output.contains("smart_sql_ai_tokens_total"));
+    }
+}
@@ -1,5 +1,41 @@
 pub mod ai;
+pub mod llm;
 pub mod templates;
+pub mod hooks;
+pub mod completion;
+pub mod policy;
+pub mod notifications;
+pub mod history_stats;
+pub mod slow_queries;
+pub mod index_advisor;
+pub mod schema_cache;
+pub mod table_relevance;
+pub mod embeddings;
+pub mod ai_usage;
+pub mod events;
+pub mod script_runner;
+pub mod data_diff;
+pub mod connection_bundle;
+pub mod connection_health;
+pub mod result_sets;
+pub mod result_edit;
+pub mod dml_generator;
+pub mod sql_lint;
+pub mod app_settings;
+pub mod sql_validation;
+pub mod sample_values;
+pub mod chart_suggest;
+pub mod result_aggregate;
+pub mod federation;
+pub mod data_generator;
+pub mod data_dictionary;
+pub mod audit;
+pub mod dml_preview;
+pub mod keyset;
+pub mod result_cache;
+pub mod jobs;
+pub mod metrics;
+pub mod monitor;
 
 #[cfg(test)]
-mod ai_test;
\ No newline at end of file
+mod ai_test;
@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::db::monitor::MonitorSnapshot;
+
+// 每个连接保留的监控采样数（前端每 10 秒轮询时约 1 小时）
+pub const MAX_MONITOR_HISTORY: usize = 360;
+// 接口默认返回的历史采样数
+pub const DEFAULT_MONITOR_HISTORY: usize = 60;
+
+struct Sample {
+    taken_at: Instant,
+    snapshot: MonitorSnapshot,
+}
+
+static MONITOR_HISTORY: OnceLock<Mutex<HashMap<i64, VecDeque<Sample>>>> = OnceLock::new();
+
+fn monitor_history() -> &'static Mutex<HashMap<i64, VecDeque<Sample>>> {
+    MONITOR_HISTORY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// 根据上一次采样计算累计值的每秒速率（如 questions_per_sec 即 QPS）
+// 没有上一次采样时按服务运行时长计算启动以来的平均速率；计数器回退（服务重启）时不计算
+pub fn compute_rates(snapshot: &mut MonitorSnapshot, previous: Option<(&MonitorSnapshot, f64)>) {
+    let uptime = snapshot.gauges.get("uptime_seconds").copied().filter(|u| *u > 0.0);
+    let mut rates = BTreeMap::new();
+    for (name, value) in &snapshot.counters {
+        let rate = match previous {
+            Some((previous, elapsed)) if elapsed > 0.0 => previous.counters.get(name)
+                .map(|before| value - before)
+                .filter(|delta| *delta >= 0.0)
+                .map(|delta| delta / elapsed),
+            _ => uptime.map(|uptime| value / uptime),
+        };
+        if let Some(rate) = rate {
+            rates.insert(format!("{}_per_sec", name), rate);
+        }
+    }
+    snapshot.rates = rates;
+}
+
+// 记录连接的一次采样（计算速率后加入历史，超出上限时丢弃最早的采样），返回带速率的采样
+pub fn record(connection_id: i64, mut snapshot: MonitorSnapshot) -> MonitorSnapshot {
+    let now = Instant::now();
+    let mut history = monitor_history().lock().unwrap();
+    let samples = history.entry(connection_id).or_default();
+    let previous = samples.back().map(|sample| (&sample.snapshot, now.duration_since(sample.taken_at).as_secs_f64()));
+    compute_rates(&mut snapshot, previous);
+
+    samples.push_back(Sample { taken_at: now, snapshot: snapshot.clone() });
+    while samples.len() > MAX_MONITOR_HISTORY {
+        samples.pop_front();
+    }
+    snapshot
+}
+
+// 连接最近的采样（按时间正序，用于绘制曲线）
+pub fn history(connection_id: i64, limit: usize) -> Vec<MonitorSnapshot> {
+    let history = monitor_history().lock().unwrap();
+    let Some(samples) = history.get(&connection_id) else {
+        return Vec::new();
+    };
+    samples.iter()
+        .skip(samples.len().saturating_sub(limit))
+        .map(|sample| sample.snapshot.clone())
+        .collect()
+}
+
+// 清除连接的监控历史（连接被删除时调用）
+pub fn clear(connection_id: i64) {
+    monitor_history().lock().unwrap().remove(&connection_id);
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(questions: f64, uptime: f64) -> MonitorSnapshot {
+        let mut snapshot = MonitorSnapshot::default();
+        snapshot.counters.insert("questions".to_string(), questions);
+        snapshot.gauges.insert("uptime_seconds".to_string(), uptime);
+        snapshot
+    }
+
+    #[test]
+    fn test_compute_rates() {
+        // 首次采样按运行时长计算平均速率
+        let mut first = snapshot(1000.0, 100.0);
+        compute_rates(&mut first, None);
+        assert_eq!(first.rates.get("questions_per_sec"), Some(&10.0));
+
+        let mut second = snapshot(1500.0, 110.0);
+        compute_rates(&mut second, Some((&first, 10.0)));
+        assert_eq!(second.rates.get("questions_per_sec"), Some(&50.0));
+
+        // 服务重启后计数器回退，不计算速率
+        let mut restarted = snapshot(20.0, 5.0);
+        compute_rates(&mut restarted, Some((&second, 10.0)));
+        assert!(restarted.rates.is_empty());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        for i in 0..(MAX_MONITOR_HISTORY + 5) {
+            record(-90, snapshot(i as f64, 100.0));
+        }
+        let all = history(-90, usize::MAX);
+        assert_eq!(all.len(), MAX_MONITOR_HISTORY);
+        assert_eq!(all[0].counters.get("questions"), Some(&5.0));
+        assert_eq!(history(-90, 3).len(), 3);
+        clear(-90);
+        assert!(history(-90, 10).is_empty());
+    }
+}
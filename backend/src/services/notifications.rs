@@ -0,0 +1,228 @@
+use std::time::Duration;
+use log::*;
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::db::LocalStorageManager;
+
+// 应用设置中保存Webhook配置的键
+pub const WEBHOOK_SETTING_KEY: &str = "notification_webhook";
+
+// 允许配置的最小阈值，低于此耗时的查询不检查配置
+pub const MIN_SLOW_QUERY_THRESHOLD_MS: u64 = 100;
+
+// 通知事件
+pub const EVENT_SLOW_QUERY: &str = "slow_query";
+pub const EVENT_QUERY_FAILED: &str = "query_failed";
+pub const EVENT_TEST: &str = "test";
+
+// Webhook消息格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    #[default]
+    Generic,   // 原样POST通知JSON
+    Slack,
+    #[serde(rename = "dingtalk")]
+    DingTalk,  // 钉钉机器人
+    #[serde(rename = "wecom")]
+    WeCom,     // 企业微信机器人
+}
+
+// Webhook配置（全局配置保存在应用设置中，调用方也可传入单独的配置）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // 订阅的事件，为空时接收全部事件
+    #[serde(default)]
+    pub events: Vec<String>,
+    // 慢查询告警阈值（毫秒），为空时使用应用设置的慢查询阈值
+    #[serde(default)]
+    pub slow_query_threshold_ms: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WebhookConfig {
+    // 是否接收该事件（测试事件总是发送）
+    pub fn accepts(&self, event: &str) -> bool {
+        event == EVENT_TEST || (self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event)))
+    }
+
+    // 慢查询告警阈值，default_ms 为应用设置的慢查询阈值
+    pub fn slow_query_threshold(&self, default_ms: u64) -> u64 {
+        self.slow_query_threshold_ms.unwrap_or(default_ms)
+    }
+
+    // 校验URL格式和阈值
+    pub fn validate(&self) -> Result<(), String> {
+        let url = self.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err("Webhook URL 必须以 http:// 或 https:// 开头".to_string());
+        }
+        if self.slow_query_threshold_ms.is_some_and(|ms| ms < MIN_SLOW_QUERY_THRESHOLD_MS) {
+            return Err(format!("慢查询阈值不能小于 {}ms", MIN_SLOW_QUERY_THRESHOLD_MS));
+        }
+        Ok(())
+    }
+}
+
+// 通知内容
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: String,
+    pub title: String,
+    pub message: String,
+    pub details: Option<JsonValue>,
+    pub timestamp: i64,
+}
+
+impl Notification {
+    pub fn new(event: &str, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            event: event.to_string(),
+            title: title.into(),
+            message: message.into(),
+            details: None,
+            timestamp: LocalStorageManager::current_timestamp(),
+        }
+    }
+
+    pub fn with_details(mut self, details: JsonValue) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+// 按目标平台格式构建请求体
+pub fn build_payload(format: WebhookFormat, notification: &Notification) -> JsonValue {
+    match format {
+        WebhookFormat::Generic => serde_json::to_value(notification).unwrap_or(JsonValue::Null),
+        WebhookFormat::Slack => json!({
+            "text": format!("*{}*\n{}", notification.title, notification.message),
+        }),
+        WebhookFormat::DingTalk => json!({
+            "msgtype": "markdown",
+            "markdown": {
+                "title": notification.title,
+                "text": format!("### {}\n\n{}", notification.title, notification.message),
+            },
+        }),
+        WebhookFormat::WeCom => json!({
+            "msgtype": "markdown",
+            "markdown": {
+                "content": format!("**{}**\n{}", notification.title, notification.message),
+            },
+        }),
+    }
+}
+
+// 发送通知
+pub async fn send(config: &WebhookConfig, notification: &Notification) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+    let response = client.post(config.url.trim())
+        .json(&build_payload(config.format, notification))
+        .send()
+        .await
+        .map_err(|e| format!("发送Webhook失败: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook返回错误状态: {}", response.status()));
+    }
+    Ok(())
+}
+
+// 读取全局Webhook配置
+pub async fn load_config(storage: &LocalStorageManager) -> Option<WebhookConfig> {
+    let value = storage.get_app_setting(WEBHOOK_SETTING_KEY).await.ok().flatten()?;
+    serde_json::from_str(&value)
+        .map_err(|e| warn!("[Notify] Webhook配置解析失败: {}", e))
+        .ok()
+}
+
+// 后台发送通知（使用全局配置，未配置、未订阅该事件或 should_send 返回false时忽略）
+fn dispatch(
+    storage: LocalStorageManager,
+    notification: Notification,
+    should_send: impl FnOnce(&WebhookConfig) -> bool + Send + 'static,
+) {
+    tokio::spawn(async move {
+        let Some(config) = load_config(&storage).await else {
+            return;
+        };
+        if !config.accepts(&notification.event) || !should_send(&config) {
+            return;
+        }
+        match send(&config, &notification).await {
+            Ok(()) => info!("[Notify] 通知已发送: event={}, title={}", notification.event, notification.title),
+            Err(e) => warn!("[Notify] 通知发送失败: event={}, 错误: {}", notification.event, e),
+        }
+    });
+}
+
+// 后台发送通知
+pub fn notify_in_background(storage: LocalStorageManager, notification: Notification) {
+    dispatch(storage, notification, |_| true);
+}
+
+// 查询耗时超过配置的阈值时发送慢查询告警（Webhook未配置阈值时使用应用设置的慢查询阈值 threshold_ms）
+pub fn notify_slow_query(storage: LocalStorageManager, connection_name: &str, sql: &str, elapsed_ms: u128, threshold_ms: u64) {
+    if elapsed_ms < MIN_SLOW_QUERY_THRESHOLD_MS as u128 {
+        return;
+    }
+    let notification = Notification::new(
+        EVENT_SLOW_QUERY,
+        "慢查询告警",
+        format!("连接 {} 上的查询耗时 {}ms\n```\n{}\n```", connection_name, elapsed_ms, sql),
+    )
+    .with_details(json!({ "connection": connection_name, "sql": sql, "elapsed_ms": elapsed_ms as u64 }));
+    dispatch(storage, notification, move |config| elapsed_ms >= config.slow_query_threshold(threshold_ms) as u128);
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification() -> Notification {
+        Notification::new(EVENT_SLOW_QUERY, "慢查询告警", "耗时 5000ms")
+    }
+
+    #[test]
+    fn test_build_payload_formats() {
+        let slack = build_payload(WebhookFormat::Slack, &notification());
+        assert_eq!(slack["text"], "*慢查询告警*\n耗时 5000ms");
+
+        let dingtalk = build_payload(WebhookFormat::DingTalk, &notification());
+        assert_eq!(dingtalk["msgtype"], "markdown");
+        assert_eq!(dingtalk["markdown"]["title"], "慢查询告警");
+
+        let wecom = build_payload(WebhookFormat::WeCom, &notification());
+        assert_eq!(wecom["markdown"]["content"], "**慢查询告警**\n耗时 5000ms");
+
+        let generic = build_payload(WebhookFormat::Generic, &notification());
+        assert_eq!(generic["event"], EVENT_SLOW_QUERY);
+    }
+
+    #[test]
+    fn test_event_subscription() {
+        let config: WebhookConfig = serde_json::from_str(
+            r#"{"url": "https://example.com/hook", "format": "dingtalk", "events": ["query_failed"]}"#
+        ).unwrap();
+        assert_eq!(config.format, WebhookFormat::DingTalk);
+        assert!(config.accepts(EVENT_QUERY_FAILED));
+        assert!(!config.accepts(EVENT_SLOW_QUERY));
+        assert!(config.accepts(EVENT_TEST));
+        assert_eq!(config.slow_query_threshold(1000), 1000);
+    }
+}
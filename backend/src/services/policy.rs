@@ -0,0 +1,156 @@
+use serde::Serialize;
+
+use crate::db::DatabaseType;
+use crate::models::DatabaseConnection;
+use crate::utils::security::{classify_statement_as, parse_statements};
+
+pub use crate::utils::security::{classify_statement, StatementKind};
+
+// 需要执行保护的环境标签
+pub const PRODUCTION_ENVIRONMENT: &str = "production";
+
+// 生产环境执行警告（序列化后放入错误响应的 details，供前端弹出确认框）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductionWarning {
+    pub connection_id: Option<i64>,
+    pub connection_name: String,
+    pub environment: String,
+    pub statement_kinds: Vec<StatementKind>,
+    pub statements: Vec<String>,
+    pub blocked: bool,  // true: 策略禁止执行；false: 携带 confirm_production 后可执行
+    pub message: String,
+}
+
+// 生产环境执行策略
+#[derive(Debug, Clone, Default)]
+pub struct ProductionPolicy {
+    pub block_destructive: bool,  // 是否完全禁止 DROP / TRUNCATE
+}
+
+impl ProductionPolicy {
+    // 从环境变量读取策略：PRODUCTION_BLOCK_DESTRUCTIVE=true 时禁止破坏性语句
+    pub fn from_env() -> Self {
+        let block_destructive = std::env::var("PRODUCTION_BLOCK_DESTRUCTIVE")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        Self { block_destructive }
+    }
+
+    // 检查语句能否在该连接上执行（警告较大，装箱后返回）
+    pub fn check(
+        &self,
+        connection: &DatabaseConnection,
+        statements: &[String],
+        confirmed: bool,
+    ) -> Result<(), Box<ProductionWarning>> {
+        if !is_production(connection) {
+            return Ok(());
+        }
+
+        let dialect = DatabaseType::from_name(&connection.db_type);
+        let kinds: Vec<StatementKind> = statements.iter().map(|s| classify_statement_as(dialect, s)).collect();
+        if !kinds.iter().any(StatementKind::is_write) {
+            return Ok(());
+        }
+
+        let destructive = kinds.contains(&StatementKind::Destructive);
+        let (blocked, message) = if destructive && self.block_destructive {
+            (true, format!("连接 {} 为生产环境，已禁止执行 DROP/TRUNCATE 语句", connection.name))
+        } else if !confirmed {
+            (false, format!("连接 {} 为生产环境，执行写操作前需要确认", connection.name))
+        } else {
+            return Ok(());
+        };
+
+        Err(Box::new(ProductionWarning {
+            connection_id: connection.id,
+            connection_name: connection.name.clone(),
+            environment: PRODUCTION_ENVIRONMENT.to_string(),
+            statement_kinds: kinds,
+            statements: statements.to_vec(),
+            blocked,
+            message,
+        }))
+    }
+}
+
+// 连接是否标记为生产环境
+pub fn is_production(connection: &DatabaseConnection) -> bool {
+    connection.environment.as_deref()
+        .map(|e| e.trim().eq_ignore_ascii_case(PRODUCTION_ENVIRONMENT))
+        .unwrap_or(false)
+}
+
+// 只读连接拒绝写操作，返回拒绝原因
+pub fn check_read_only(connection: &DatabaseConnection, statements: &[String]) -> Result<(), String> {
+    if !connection.options.read_only {
+        return Ok(());
+    }
+    let dialect = DatabaseType::from_name(&connection.db_type);
+    match statements.iter().find(|s| classify_statement_as(dialect, s).is_write()) {
+        Some(statement) => Err(format!("连接 {} 为只读连接，不能执行写操作: {}", connection.name, statement)),
+        None => Ok(()),
+    }
+}
+
+// 将SQL拆分为单条语句，无法解析时整体作为一条
+pub fn split_statements(sql: &str) -> Vec<String> {
+    match parse_statements(sql) {
+        Ok(statements) if !statements.is_empty() => statements.iter().map(|s| s.to_string()).collect(),
+        _ => vec![sql.trim().to_string()],
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(environment: &str) -> DatabaseConnection {
+        DatabaseConnection {
+            environment: Some(environment.to_string()),
+            ..DatabaseConnection::for_test("prod-db", "mysql")
+        }
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        assert_eq!(classify_statement("-- 注释\nSELECT 1"), StatementKind::Query);
+        assert_eq!(classify_statement("/* x */ update t set a = 1"), StatementKind::Dml);
+        assert_eq!(classify_statement("ALTER TABLE t ADD c INT"), StatementKind::Ddl);
+        assert_eq!(classify_statement("truncate table t"), StatementKind::Destructive);
+    }
+
+    #[test]
+    fn test_production_requires_confirmation() {
+        let policy = ProductionPolicy::default();
+        let statements = split_statements("SELECT 1; DELETE FROM t WHERE id = 1");
+        assert_eq!(statements.len(), 2);
+
+        let warning = policy.check(&connection("production"), &statements, false).unwrap_err();
+        assert!(!warning.blocked);
+        assert_eq!(warning.statement_kinds, vec![StatementKind::Query, StatementKind::Dml]);
+
+        assert!(policy.check(&connection("production"), &statements, true).is_ok());
+        assert!(policy.check(&connection("development"), &statements, false).is_ok());
+        assert!(policy.check(&connection("production"), &["SELECT 1".to_string()], false).is_ok());
+    }
+
+    #[test]
+    fn test_production_blocks_destructive() {
+        let policy = ProductionPolicy { block_destructive: true };
+        let warning = policy.check(&connection("Production"), &["DROP TABLE t".to_string()], true).unwrap_err();
+        assert!(warning.blocked);
+    }
+
+    #[test]
+    fn test_read_only_rejects_writes() {
+        let mut conn = connection("development");
+        let statements = split_statements("SELECT 1; UPDATE t SET a = 1");
+        assert!(check_read_only(&conn, &statements).is_ok());
+
+        conn.options.read_only = true;
+        assert!(check_read_only(&conn, &statements).is_err());
+        assert!(check_read_only(&conn, &["SELECT 1".to_string()]).is_ok());
+    }
+}
@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+// 透视列最多展开的不同值数量
+pub const MAX_PIVOT_VALUES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    fn name(self) -> &'static str {
+        match self {
+            AggregateFunction::Count => "count",
+            AggregateFunction::CountDistinct => "count_distinct",
+            AggregateFunction::Sum => "sum",
+            AggregateFunction::Avg => "avg",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Max => "max",
+        }
+    }
+}
+
+// 单个聚合：column 为空时只能用于 count（统计行数）
+#[derive(Debug, Clone, Deserialize)]
+pub struct Aggregation {
+    pub function: AggregateFunction,
+    #[serde(default)]
+    pub column: Option<String>,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+impl Aggregation {
+    // 结果列名，未指定别名时为 "sum(amount)" 形式
+    pub fn output_name(&self) -> String {
+        match &self.alias {
+            Some(alias) if !alias.trim().is_empty() => alias.trim().to_string(),
+            _ => format!("{}({})", self.function.name(), self.column.as_deref().unwrap_or("*")),
+        }
+    }
+}
+
+// 聚合配置：按 group_by 分组；指定 pivot 时该列的每个不同值展开为一组聚合列
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AggregateSpec {
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    #[serde(default)]
+    pub aggregations: Vec<Aggregation>,
+    #[serde(default)]
+    pub pivot: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregateResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+    pub group_count: usize,
+    pub source_rows: usize,
+}
+
+fn as_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        // DECIMAL 等类型以字符串返回
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// 数值优先按大小比较，否则按文本比较
+fn compare_values(a: &JsonValue, b: &JsonValue) -> Ordering {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => match (a, b) {
+            (JsonValue::String(x), JsonValue::String(y)) => x.cmp(y),
+            _ => a.to_string().cmp(&b.to_string()),
+        },
+    }
+}
+
+// 单个聚合的累加状态
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    count: usize,
+    distinct: HashSet<String>,
+    sum: f64,
+    int_sum: Option<i64>,  // 全部为整数且未溢出时保留整数结果
+    numeric: usize,
+    extreme: Option<JsonValue>,
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        Self { int_sum: Some(0), ..Default::default() }
+    }
+
+    fn push(&mut self, function: AggregateFunction, value: Option<&JsonValue>) {
+        let value = match value {
+            // count(*) 统计所有行
+            None => {
+                self.count += 1;
+                return;
+            }
+            Some(JsonValue::Null) => return,
+            Some(value) => value,
+        };
+        match function {
+            AggregateFunction::Count => self.count += 1,
+            AggregateFunction::CountDistinct => {
+                self.distinct.insert(value.to_string());
+            }
+            AggregateFunction::Sum | AggregateFunction::Avg => {
+                if let Some(number) = as_number(value) {
+                    self.numeric += 1;
+                    self.sum += number;
+                    self.int_sum = match (self.int_sum, value.as_i64()) {
+                        (Some(total), Some(n)) => total.checked_add(n),
+                        _ => None,
+                    };
+                }
+            }
+            AggregateFunction::Min | AggregateFunction::Max => {
+                let replace = match &self.extreme {
+                    None => true,
+                    Some(current) => {
+                        let ordering = compare_values(value, current);
+                        if function == AggregateFunction::Min { ordering == Ordering::Less } else { ordering == Ordering::Greater }
+                    }
+                };
+                if replace {
+                    self.extreme = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    fn finish(self, function: AggregateFunction) -> JsonValue {
+        let float = |v: f64| serde_json::Number::from_f64(v).map(JsonValue::Number).unwrap_or(JsonValue::Null);
+        match function {
+            AggregateFunction::Count => JsonValue::from(self.count),
+            AggregateFunction::CountDistinct => JsonValue::from(self.distinct.len()),
+            AggregateFunction::Sum if self.numeric == 0 => JsonValue::Null,
+            AggregateFunction::Sum => self.int_sum.map(JsonValue::from).unwrap_or_else(|| float(self.sum)),
+            AggregateFunction::Avg if self.numeric == 0 => JsonValue::Null,
+            AggregateFunction::Avg => float(self.sum / self.numeric as f64),
+            AggregateFunction::Min | AggregateFunction::Max => self.extreme.unwrap_or(JsonValue::Null),
+        }
+    }
+}
+
+fn column_index(columns: &[String], name: &str) -> Result<usize, String> {
+    columns.iter().position(|c| c == name).ok_or_else(|| format!("结果中不存在列: {}", name))
+}
+
+// 对结果行分组聚合；分组按首次出现的顺序输出，透视列的值按升序展开
+pub fn aggregate(columns: &[String], rows: &[Vec<JsonValue>], spec: &AggregateSpec) -> Result<AggregateResult, String> {
+    if spec.aggregations.is_empty() {
+        return Err("至少需要一个聚合".to_string());
+    }
+    let group_indexes = spec.group_by.iter().map(|c| column_index(columns, c)).collect::<Result<Vec<_>, _>>()?;
+    let agg_indexes = spec.aggregations.iter()
+        .map(|agg| match (&agg.column, agg.function) {
+            (Some(column), _) => column_index(columns, column).map(Some),
+            (None, AggregateFunction::Count) => Ok(None),
+            (None, function) => Err(format!("{} 需要指定列", function.name())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let pivot_index = spec.pivot.as_deref().map(|c| column_index(columns, c)).transpose()?;
+
+    // 透视列的不同值
+    let mut pivot_values: Vec<JsonValue> = Vec::new();
+    if let Some(index) = pivot_index {
+        let mut seen = HashSet::new();
+        for value in rows.iter().map(|row| row.get(index).unwrap_or(&JsonValue::Null)) {
+            if seen.insert(value.to_string()) {
+                pivot_values.push(value.clone());
+                if pivot_values.len() > MAX_PIVOT_VALUES {
+                    return Err(format!("透视列的不同值超过 {} 个", MAX_PIVOT_VALUES));
+                }
+            }
+        }
+        pivot_values.sort_by(compare_values);
+    }
+    let pivot_slots: HashMap<String, usize> = pivot_values.iter().enumerate().map(|(i, v)| (v.to_string(), i)).collect();
+    let slot_count = pivot_values.len().max(1);
+
+    // 分组键 -> (分组列的值, 每个透视值下每个聚合的累加器)
+    let mut group_positions: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(Vec<JsonValue>, Vec<Vec<Accumulator>>)> = Vec::new();
+    for row in rows {
+        let key_values: Vec<JsonValue> = group_indexes.iter().map(|&i| row.get(i).cloned().unwrap_or(JsonValue::Null)).collect();
+        let key = serde_json::to_string(&key_values).unwrap_or_default();
+        let position = *group_positions.entry(key).or_insert_with(|| {
+            let accumulators = vec![vec![Accumulator::new(); spec.aggregations.len()]; slot_count];
+            groups.push((key_values, accumulators));
+            groups.len() - 1
+        });
+        let slot = pivot_index
+            .map(|i| pivot_slots[&row.get(i).unwrap_or(&JsonValue::Null).to_string()])
+            .unwrap_or(0);
+        for (accumulator, (agg, index)) in groups[position].1[slot].iter_mut().zip(spec.aggregations.iter().zip(&agg_indexes)) {
+            accumulator.push(agg.function, index.map(|i| row.get(i).unwrap_or(&JsonValue::Null)));
+        }
+    }
+
+    let mut output_columns = spec.group_by.clone();
+    if pivot_index.is_some() {
+        for value in &pivot_values {
+            let label = match value {
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            for agg in &spec.aggregations {
+                output_columns.push(if spec.aggregations.len() == 1 { label.clone() } else { format!("{}_{}", label, agg.output_name()) });
+            }
+        }
+    } else {
+        output_columns.extend(spec.aggregations.iter().map(Aggregation::output_name));
+    }
+
+    let group_count = groups.len();
+    let output_rows = groups.into_iter()
+        .map(|(mut values, slots)| {
+            for accumulators in slots {
+                for (accumulator, agg) in accumulators.into_iter().zip(&spec.aggregations) {
+                    values.push(accumulator.finish(agg.function));
+                }
+            }
+            values
+        })
+        .collect();
+    Ok(AggregateResult { columns: output_columns, rows: output_rows, group_count, source_rows: rows.len() })
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> (Vec<String>, Vec<Vec<JsonValue>>) {
+        let columns = ["region", "status", "amount"].iter().map(|c| c.to_string()).collect();
+        let rows = vec![
+            vec![json!("east"), json!("paid"), json!(10)],
+            vec![json!("west"), json!("paid"), json!("2.5")],
+            vec![json!("east"), json!("refund"), json!(4)],
+            vec![json!("east"), json!("paid"), JsonValue::Null],
+        ];
+        (columns, rows)
+    }
+
+    fn agg(function: AggregateFunction, column: Option<&str>) -> Aggregation {
+        Aggregation { function, column: column.map(str::to_string), alias: None }
+    }
+
+    #[test]
+    fn test_group_and_aggregate() {
+        let (columns, rows) = sample();
+        let spec = AggregateSpec {
+            group_by: vec!["region".to_string()],
+            aggregations: vec![
+                agg(AggregateFunction::Count, None),
+                agg(AggregateFunction::Sum, Some("amount")),
+                agg(AggregateFunction::Avg, Some("amount")),
+                agg(AggregateFunction::Max, Some("status")),
+                agg(AggregateFunction::CountDistinct, Some("status")),
+            ],
+            pivot: None,
+        };
+        let result = aggregate(&columns, &rows, &spec).unwrap();
+        assert_eq!(result.columns, vec!["region", "count(*)", "sum(amount)", "avg(amount)", "max(status)", "count_distinct(status)"]);
+        assert_eq!(result.rows[0], vec![json!("east"), json!(3), json!(14), json!(7.0), json!("refund"), json!(2)]);
+        assert_eq!(result.rows[1], vec![json!("west"), json!(1), json!(2.5), json!(2.5), json!("paid"), json!(1)]);
+        assert_eq!((result.group_count, result.source_rows), (2, 4));
+    }
+
+    #[test]
+    fn test_pivot() {
+        let (columns, rows) = sample();
+        let spec = AggregateSpec {
+            group_by: vec!["region".to_string()],
+            aggregations: vec![agg(AggregateFunction::Count, Some("amount"))],
+            pivot: Some("status".to_string()),
+        };
+        let result = aggregate(&columns, &rows, &spec).unwrap();
+        assert_eq!(result.columns, vec!["region", "paid", "refund"]);
+        assert_eq!(result.rows, vec![vec![json!("east"), json!(1), json!(1)], vec![json!("west"), json!(1), json!(0)]]);
+    }
+
+    #[test]
+    fn test_invalid_spec() {
+        let (columns, rows) = sample();
+        let mut spec = AggregateSpec { aggregations: vec![agg(AggregateFunction::Sum, None)], ..Default::default() };
+        assert!(aggregate(&columns, &rows, &spec).is_err());
+        spec.aggregations = vec![agg(AggregateFunction::Sum, Some("price"))];
+        assert!(aggregate(&columns, &rows, &spec).is_err());
+        spec.aggregations.clear();
+        assert!(aggregate(&columns, &rows, &spec).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value as JsonValue;
+
+use crate::db::DatabaseType;
+use crate::utils::security::ensure_select_only_as;
+
+// 默认缓存条目数上限
+pub const DEFAULT_RESULT_CACHE_MAX_ENTRIES: usize = 100;
+// 允许配置的最长有效期
+pub const MAX_RESULT_CACHE_TTL_SECS: u64 = 3600;
+// 允许配置的最大条目数
+pub const MAX_RESULT_CACHE_ENTRIES: usize = 10_000;
+
+// 缓存键：连接 + 数据库 + 规范化的SQL + 参数 + 影响结果的查询选项
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey {
+    pub connection_id: i64,
+    pub database: Option<String>,
+    pub sql: String,
+    pub params: String,
+    pub options: String,
+}
+
+impl ResultCacheKey {
+    pub fn new(connection_id: i64, database: Option<&str>, sql: &str, params: &[JsonValue], options: String) -> Self {
+        Self {
+            connection_id,
+            database: database.map(str::to_string),
+            sql: normalize_sql(sql),
+            params: JsonValue::Array(params.to_vec()).to_string(),
+            options,
+        }
+    }
+}
+
+// 合并空白、去掉末尾分号；不改变大小写（字符串常量区分大小写）
+pub fn normalize_sql(sql: &str) -> String {
+    sql.trim().trim_end_matches(';').split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 只缓存能够解析的单条只读SELECT（数据修改型CTE、SELECT ... INTO 和无法解析的语句不缓存）
+pub fn is_cacheable(db_type: Option<DatabaseType>, sql: &str) -> bool {
+    !matches!(db_type, Some(DatabaseType::MongoDB | DatabaseType::Redis))
+        && ensure_select_only_as(db_type, sql).is_ok()
+}
+
+struct CacheEntry<T> {
+    value: T,
+    stored_at: Instant,
+}
+
+// 查询结果缓存：按有效期过期，超出条目数上限时淘汰最早写入的条目
+// 连接上执行写操作或调用清除接口时显式失效
+pub struct ResultCache<T> {
+    entries: Mutex<HashMap<ResultCacheKey, CacheEntry<T>>>,
+}
+
+impl<T: Clone> Default for ResultCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> ResultCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 获取未过期的缓存结果，过期的条目顺便移除
+    pub fn get(&self, key: &ResultCacheKey, ttl: Duration) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.stored_at.elapsed() < ttl => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn put(&self, key: ResultCacheKey, value: T, ttl: Duration, max_entries: usize) {
+        if max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key, CacheEntry { value, stored_at: Instant::now() });
+        if entries.len() > max_entries {
+            entries.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+        }
+        while entries.len() > max_entries {
+            let oldest = entries.iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 清除连接的全部缓存结果，返回清除的条目数
+    pub fn invalidate(&self, connection_id: i64) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|key, _| key.connection_id != connection_id);
+        before - entries.len()
+    }
+
+    // 清除所有缓存结果
+    pub fn invalidate_all(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn key(connection_id: i64, sql: &str) -> ResultCacheKey {
+        ResultCacheKey::new(connection_id, None, sql, &[json!(1)], String::new())
+    }
+
+    #[test]
+    fn test_cache_hit_eviction_and_invalidate() {
+        let cache: ResultCache<String> = ResultCache::new();
+        cache.put(key(1, "SELECT *  FROM orders\n WHERE id = ?;"), "a".to_string(), TTL, 2);
+        assert_eq!(cache.get(&key(1, "SELECT * FROM orders WHERE id = ?"), TTL).as_deref(), Some("a"));
+        assert!(cache.get(&key(2, "SELECT * FROM orders WHERE id = ?"), TTL).is_none());
+        assert!(cache.get(&ResultCacheKey::new(1, None, "SELECT * FROM orders WHERE id = ?", &[json!(2)], String::new()), TTL).is_none());
+
+        // 写入时间相同时无法确定最早的条目
+        std::thread::sleep(Duration::from_millis(2));
+        cache.put(key(1, "SELECT 2"), "b".to_string(), TTL, 2);
+        std::thread::sleep(Duration::from_millis(2));
+        cache.put(key(2, "SELECT 3"), "c".to_string(), TTL, 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key(1, "SELECT * FROM orders WHERE id = ?"), TTL).is_none());
+
+        assert_eq!(cache.invalidate(1), 1);
+        assert_eq!(cache.get(&key(2, "SELECT 3"), TTL).as_deref(), Some("c"));
+        assert!(cache.get(&key(2, "SELECT 3"), Duration::ZERO).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(is_cacheable(Some(DatabaseType::MySQL), "SELECT * FROM orders"));
+        assert!(!is_cacheable(Some(DatabaseType::MySQL), "UPDATE orders SET status = 1"));
+        assert!(!is_cacheable(Some(DatabaseType::MongoDB), "db.orders.find({})"));
+        assert!(!is_cacheable(Some(DatabaseType::PostgreSQL), "WITH x AS (UPDATE orders SET status = 1 RETURNING id) SELECT * FROM x"));
+        assert!(!is_cacheable(Some(DatabaseType::MySQL), "SELECT * FROM orders WHERE ((("));
+        assert!(!is_cacheable(Some(DatabaseType::MySQL), "SELECT 1; SELECT 2"));
+    }
+}
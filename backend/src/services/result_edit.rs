@@ -0,0 +1,117 @@
+use sqlparser::ast::{Expr, GroupByExpr, SelectItem, SetExpr, Statement, TableFactor};
+
+use crate::utils::security::parse_statements;
+
+// 结果列对应的表列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditableColumn {
+    pub schema: Option<String>,  // 查询中限定的schema（app.users 中的 app），未限定时为空
+    pub table: String,
+    pub column: String,
+}
+
+// 列表达式为普通列（col 或 t.col）时返回列名
+fn plain_column(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Identifier(ident) => Some(&ident.value),
+        Expr::CompoundIdentifier(parts) => parts.last().map(|i| i.value.as_str()),
+        _ => None,
+    }
+}
+
+// 根据原始SELECT推断结果列对应的表和列
+// 只支持单表、无聚合、无CTE的查询，结果列必须直接来自表中的列（* 或 列名、列名 AS 别名）
+pub fn resolve_editable_column(sql: &str, result_column: &str, table_hint: Option<&str>) -> Result<EditableColumn, String> {
+    let mut statements = parse_statements(sql)?;
+    if statements.len() != 1 {
+        return Err("只支持单条SELECT语句的结果编辑".to_string());
+    }
+    let Statement::Query(query) = statements.remove(0) else {
+        return Err("只支持SELECT查询的结果编辑".to_string());
+    };
+    // FROM 中的名称可能是CTE而不是表
+    if query.with.is_some() {
+        return Err("包含 WITH 子句的查询结果不可编辑".to_string());
+    }
+    let SetExpr::Select(select) = *query.body else {
+        return Err("UNION 等组合查询的结果不可编辑".to_string());
+    };
+    if select.from.len() != 1 || !select.from[0].joins.is_empty() {
+        return Err("只支持单表查询的结果编辑".to_string());
+    }
+    let TableFactor::Table { name, .. } = &select.from[0].relation else {
+        return Err("子查询的结果不可编辑".to_string());
+    };
+    let (schema, table) = match name.0.as_slice() {
+        [table] => (None, table.value.clone()),
+        [schema, table] => (Some(schema.value.clone()), table.value.clone()),
+        _ => return Err(format!("不支持编辑 {} 的结果，请使用当前数据库中的表", name)),
+    };
+    if let Some(hint) = table_hint.map(str::trim).filter(|t| !t.is_empty()) {
+        if !hint.eq_ignore_ascii_case(&table) {
+            return Err(format!("查询的表 {} 与指定的表 {} 不一致", table, hint));
+        }
+    }
+    let grouped = !matches!(&select.group_by, GroupByExpr::Expressions(exprs) if exprs.is_empty());
+    if grouped || select.having.is_some() || select.distinct.is_some() {
+        return Err("聚合或去重查询的结果不可编辑".to_string());
+    }
+
+    let mut has_wildcard = false;
+    for item in &select.projection {
+        match item {
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => has_wildcard = true,
+            SelectItem::UnnamedExpr(expr) => {
+                if let Some(column) = plain_column(expr).filter(|c| c.eq_ignore_ascii_case(result_column)) {
+                    return Ok(EditableColumn { schema, table, column: column.to_string() });
+                }
+            }
+            SelectItem::ExprWithAlias { expr, alias } if alias.value.eq_ignore_ascii_case(result_column) => {
+                return match plain_column(expr) {
+                    Some(column) => Ok(EditableColumn { schema, table, column: column.to_string() }),
+                    None => Err(format!("列 {} 是计算列，不可编辑", result_column)),
+                };
+            }
+            SelectItem::ExprWithAlias { .. } => {}
+        }
+    }
+    if has_wildcard {
+        Ok(EditableColumn { schema, table, column: result_column.to_string() })
+    } else {
+        Err(format!("结果列 {} 不在查询中", result_column))
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(sql: &str, column: &str) -> Result<EditableColumn, String> {
+        resolve_editable_column(sql, column, None)
+    }
+
+    #[test]
+    fn test_resolve_columns() {
+        let editable = |schema: Option<&str>, table: &str, column: &str| Ok(EditableColumn {
+            schema: schema.map(str::to_string),
+            table: table.to_string(),
+            column: column.to_string(),
+        });
+        assert_eq!(resolve("SELECT * FROM users WHERE id > 10", "email"), editable(None, "users", "email"));
+        assert_eq!(resolve("SELECT u.id, u.name AS user_name FROM app.users u", "user_name"), editable(Some("app"), "users", "name"));
+        assert_eq!(resolve("SELECT id, Email FROM users", "email"), editable(None, "users", "Email"));
+        assert!(resolve_editable_column("SELECT * FROM users", "email", Some("orders")).is_err());
+    }
+
+    #[test]
+    fn test_reject_non_editable() {
+        assert!(resolve("SELECT id, UPPER(name) AS name FROM users", "name").is_err());
+        assert!(resolve("SELECT * FROM users u JOIN orders o ON o.user_id = u.id", "id").is_err());
+        assert!(resolve("SELECT status, COUNT(*) AS n FROM orders GROUP BY status", "status").is_err());
+        assert!(resolve("SELECT id FROM users", "email").is_err());
+        assert!(resolve("UPDATE users SET name = 'x'", "name").is_err());
+        assert!(resolve("WITH users AS (SELECT 1 AS id) SELECT * FROM users", "id").is_err());
+        assert!(resolve("SELECT * FROM db.app.users", "id").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use serde_json::Value as JsonValue;
+
+use crate::models::SqlResultSet;
+
+// 单个结果集默认最多返回的行数，超出部分丢弃并标记 truncated
+pub const DEFAULT_MAX_ROWS_PER_SET: usize = 1000;
+
+// 按数据库返回的顺序收集多个结果集：行属于当前结果集，收到语句完成信号时结束当前结果集
+pub struct ResultSetCollector {
+    max_rows: usize,
+    current: Option<SqlResultSet>,
+    sets: Vec<SqlResultSet>,
+}
+
+impl ResultSetCollector {
+    pub fn new(max_rows: usize) -> Self {
+        Self { max_rows, current: None, sets: Vec::new() }
+    }
+
+    // 添加一行；列名只在结果集的第一行读取
+    pub fn push_row(&mut self, columns: impl FnOnce() -> Vec<String>, values: impl FnOnce() -> Vec<JsonValue>) {
+        let max_rows = self.max_rows;
+        let set = self.current.get_or_insert_with(|| SqlResultSet {
+            columns: columns(),
+            rows: Vec::new(),
+            row_count: 0,
+            rows_affected: 0,
+            truncated: false,
+        });
+        set.row_count += 1;
+        if set.rows.len() < max_rows {
+            set.rows.push(values());
+        } else {
+            set.truncated = true;
+        }
+    }
+
+    // 当前语句执行完成
+    pub fn finish_set(&mut self, rows_affected: u64) {
+        let mut set = self.current.take().unwrap_or(SqlResultSet {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            row_count: 0,
+            rows_affected: 0,
+            truncated: false,
+        });
+        set.rows_affected = rows_affected;
+        self.sets.push(set);
+    }
+
+    pub fn finish(mut self) -> Vec<SqlResultSet> {
+        // 没有收到完成信号的行也作为一个结果集返回
+        if let Some(set) = self.current.take() {
+            self.sets.push(set);
+        }
+        self.sets
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collects_sets_in_order() {
+        let mut collector = ResultSetCollector::new(2);
+        for id in 1..=3 {
+            collector.push_row(|| vec!["id".to_string()], || vec![json!(id)]);
+        }
+        collector.finish_set(0);
+        // CALL 语句最后的状态结果没有行
+        collector.finish_set(1);
+        collector.push_row(|| vec!["Variable_name".to_string(), "Value".to_string()], || vec![json!("a"), json!("b")]);
+
+        let sets = collector.finish();
+        assert_eq!(sets.len(), 3);
+        assert_eq!(sets[0].rows, vec![vec![json!(1)], vec![json!(2)]]);
+        assert_eq!(sets[0].row_count, 3);
+        assert!(sets[0].truncated);
+        assert!(sets[1].columns.is_empty());
+        assert_eq!(sets[1].rows_affected, 1);
+        assert_eq!(sets[2].columns, vec!["Variable_name", "Value"]);
+    }
+}
@@ -0,0 +1,109 @@
+use serde_json::Value as JsonValue;
+
+use crate::db::ddl::quote_identifier;
+use crate::db::DatabaseType;
+
+// 每张表读取的采样行数
+pub const SAMPLE_ROWS: usize = 200;
+// 每张表最多采样的列数
+pub const MAX_SAMPLE_COLUMNS: usize = 8;
+// 低基数列最多列出的不同值数量
+pub const MAX_DISTINCT_VALUES: usize = 10;
+// 单个值超过该长度时视为自由文本，不写入提示词
+pub const MAX_VALUE_CHARS: usize = 40;
+// 提示词中示例值的总字符数上限
+pub const MAX_SAMPLE_CHARS: usize = 2000;
+
+// 可能是枚举/状态值的列类型（PostgreSQL 枚举的 data_type 为 USER-DEFINED）
+pub fn is_sampled_type(data_type: &str) -> bool {
+    let data_type = data_type.to_lowercase();
+    ["char", "text", "enum", "set", "user-defined", "string"].iter().any(|t| data_type.contains(t))
+        && !data_type.contains("[]")
+}
+
+// 读取采样行的SQL（各列转换为文本）
+pub fn sample_sql(db_type: DatabaseType, table: &str, columns: &[String]) -> Result<String, String> {
+    let expressions = columns.iter()
+        .map(|column| {
+            let quoted = quote_identifier(db_type, column)?;
+            Ok(match db_type {
+                DatabaseType::MySQL => format!("CAST({0} AS CHAR) AS {0}", quoted),
+                DatabaseType::PostgreSQL => format!("{0}::text AS {0}", quoted),
+                _ => format!("CAST({0} AS TEXT) AS {0}", quoted),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(format!("SELECT {} FROM {} LIMIT {}", expressions.join(", "), quote_identifier(db_type, table)?, SAMPLE_ROWS))
+}
+
+// 从采样行中找出低基数列的取值（按出现次数降序）
+// 只保留值有重复出现的列（不同值数量不超过非空行数的一半），避免把姓名、邮箱等个人数据发送给AI
+pub fn low_cardinality_values(columns: &[String], rows: &[Vec<JsonValue>]) -> Vec<(String, Vec<String>)> {
+    let mut result = Vec::new();
+    for (index, column) in columns.iter().enumerate() {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut non_null = 0;
+        let mut free_text = false;
+        for value in rows.iter().filter_map(|row| row.get(index)).filter_map(|v| v.as_str()) {
+            non_null += 1;
+            if value.chars().count() > MAX_VALUE_CHARS {
+                free_text = true;
+                break;
+            }
+            match counts.iter_mut().find(|(v, _)| v == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value.to_string(), 1)),
+            }
+            if counts.len() > MAX_DISTINCT_VALUES {
+                break;
+            }
+        }
+        if free_text || counts.is_empty() || counts.len() > MAX_DISTINCT_VALUES || counts.len() * 2 > non_null {
+            continue;
+        }
+        counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+        result.push((column.clone(), counts.into_iter().map(|(v, _)| v).collect()));
+    }
+    result
+}
+
+// 示例值在提示词中的一行
+pub fn format_sample_line(column: &str, values: &[String]) -> String {
+    let values = values.iter().map(|v| format!("'{}'", v.replace('\'', "''"))).collect::<Vec<_>>().join(", ");
+    format!("     - {}: {}\n", column, values)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_low_cardinality_values() {
+        let columns = vec!["status".to_string(), "email".to_string(), "note".to_string()];
+        let long = "x".repeat(MAX_VALUE_CHARS + 1);
+        let rows: Vec<Vec<JsonValue>> = (0..6)
+            .map(|i| vec![
+                json!(if i % 3 == 0 { "pending" } else { "shipped" }),
+                json!(format!("user{}@example.com", i)),
+                json!(long.clone()),
+            ])
+            .collect();
+        let values = low_cardinality_values(&columns, &rows);
+        assert_eq!(values, vec![("status".to_string(), vec!["shipped".to_string(), "pending".to_string()])]);
+        assert_eq!(format_sample_line("status", &values[0].1), "     - status: 'shipped', 'pending'\n");
+    }
+
+    #[test]
+    fn test_sampled_types_and_sql() {
+        assert!(is_sampled_type("varchar"));
+        assert!(is_sampled_type("USER-DEFINED"));
+        assert!(!is_sampled_type("integer"));
+        assert!(!is_sampled_type("text[]"));
+        assert_eq!(
+            sample_sql(DatabaseType::MySQL, "orders", &["status".to_string()]).unwrap(),
+            format!("SELECT CAST(`status` AS CHAR) AS `status` FROM `orders` LIMIT {}", SAMPLE_ROWS)
+        );
+    }
+}
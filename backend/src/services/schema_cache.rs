@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::services::policy::{classify_statement, StatementKind};
+
+// 表结构缓存默认有效期
+pub const DEFAULT_SCHEMA_CACHE_TTL: Duration = Duration::from_secs(300);
+
+struct CacheEntry<T> {
+    value: T,
+    loaded_at: Instant,
+}
+
+// 按连接ID缓存的表列表和表结构，供AI接口和表结构接口共用
+// 过期后按需重新读取；执行DDL或调用刷新接口时显式失效
+pub struct SchemaCache<T> {
+    ttl: Duration,
+    tables: Mutex<HashMap<i64, CacheEntry<Vec<String>>>>,
+    structures: Mutex<HashMap<(i64, String), CacheEntry<T>>>,
+}
+
+impl<T: Clone> SchemaCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            tables: Mutex::new(HashMap::new()),
+            structures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_fresh(&self, loaded_at: Instant) -> bool {
+        loaded_at.elapsed() < self.ttl
+    }
+
+    // 获取连接的表列表，未缓存或已过期时返回None
+    pub fn tables(&self, connection_id: i64) -> Option<Vec<String>> {
+        let tables = self.tables.lock().unwrap();
+        tables.get(&connection_id)
+            .filter(|entry| self.is_fresh(entry.loaded_at))
+            .map(|entry| entry.value.clone())
+    }
+
+    pub fn put_tables(&self, connection_id: i64, tables: Vec<String>) {
+        self.tables.lock().unwrap().insert(connection_id, CacheEntry { value: tables, loaded_at: Instant::now() });
+    }
+
+    // 获取表结构，未缓存或已过期时返回None
+    pub fn structure(&self, connection_id: i64, table: &str) -> Option<T> {
+        let structures = self.structures.lock().unwrap();
+        structures.get(&(connection_id, table.to_string()))
+            .filter(|entry| self.is_fresh(entry.loaded_at))
+            .map(|entry| entry.value.clone())
+    }
+
+    pub fn put_structure(&self, connection_id: i64, table: &str, structure: T) {
+        self.structures.lock().unwrap().insert(
+            (connection_id, table.to_string()),
+            CacheEntry { value: structure, loaded_at: Instant::now() },
+        );
+    }
+
+    // 清除连接的全部缓存，返回清除的表结构数量
+    pub fn invalidate(&self, connection_id: i64) -> usize {
+        self.tables.lock().unwrap().remove(&connection_id);
+        let mut structures = self.structures.lock().unwrap();
+        let before = structures.len();
+        structures.retain(|(id, _), _| *id != connection_id);
+        before - structures.len()
+    }
+
+    // 清除所有连接的缓存
+    pub fn invalidate_all(&self) -> usize {
+        self.tables.lock().unwrap().clear();
+        let mut structures = self.structures.lock().unwrap();
+        let count = structures.len();
+        structures.clear();
+        count
+    }
+}
+
+// 语句是否可能修改表结构（执行成功后需使缓存失效）
+pub fn changes_schema(sql: &str) -> bool {
+    matches!(classify_statement(sql), StatementKind::Ddl | StatementKind::Destructive)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_hit_and_invalidate() {
+        let cache: SchemaCache<String> = SchemaCache::new(DEFAULT_SCHEMA_CACHE_TTL);
+        assert!(cache.tables(1).is_none());
+
+        cache.put_tables(1, vec!["users".to_string()]);
+        cache.put_structure(1, "users", "users(id)".to_string());
+        cache.put_structure(2, "orders", "orders(id)".to_string());
+        assert_eq!(cache.tables(1), Some(vec!["users".to_string()]));
+        assert_eq!(cache.structure(1, "users").as_deref(), Some("users(id)"));
+        assert!(cache.structure(1, "orders").is_none());
+
+        assert_eq!(cache.invalidate(1), 1);
+        assert!(cache.tables(1).is_none());
+        assert!(cache.structure(1, "users").is_none());
+        assert_eq!(cache.structure(2, "orders").as_deref(), Some("orders(id)"));
+        assert_eq!(cache.invalidate_all(), 1);
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let cache: SchemaCache<String> = SchemaCache::new(Duration::ZERO);
+        cache.put_tables(1, vec!["users".to_string()]);
+        cache.put_structure(1, "users", "users(id)".to_string());
+        assert!(cache.tables(1).is_none());
+        assert!(cache.structure(1, "users").is_none());
+    }
+
+    #[test]
+    fn test_changes_schema() {
+        assert!(changes_schema("ALTER TABLE users ADD COLUMN age INT"));
+        assert!(changes_schema("DROP TABLE users"));
+        assert!(!changes_schema("SELECT * FROM users"));
+        assert!(!changes_schema("UPDATE users SET name = 'a'"));
+    }
+}
@@ -0,0 +1,217 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::services::events::{self, ServerEvent};
+use crate::utils::sql_script::ScriptStatement;
+
+// 单个脚本最多包含的语句数
+pub const MAX_SCRIPT_STATEMENTS: usize = 5000;
+
+// 语句执行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementStatus {
+    Success,
+    Failed,
+    Skipped,  // 前面的语句失败或脚本被取消，未执行
+}
+
+// 单条语句的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptStatementResult {
+    pub index: usize,
+    pub line: usize,
+    pub sql: String,  // 语句预览（过长时截断），完整语句按 line 在脚本中定位
+    pub status: StatementStatus,
+    pub rows_affected: Option<u64>,
+    pub execution_time_ms: u128,
+    pub error: Option<String>,
+}
+
+// 脚本执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptResult {
+    pub script_id: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub cancelled: bool,
+    pub rolled_back: bool,  // 事务模式下出错后已回滚
+    pub execution_time_ms: u128,
+    pub statements: Vec<ScriptStatementResult>,
+}
+
+// 脚本执行状态：按顺序取出待执行语句、记录结果并推送进度事件
+// 数据库相关的执行由调用方完成，以便在同一连接上执行所有语句
+pub struct ScriptRunner {
+    script_id: String,
+    statements: Vec<ScriptStatement>,
+    results: Vec<ScriptStatementResult>,
+    stop_on_error: bool,
+    cancelled: bool,
+    start: Instant,
+}
+
+impl ScriptRunner {
+    pub fn new(script_id: String, statements: Vec<ScriptStatement>, stop_on_error: bool) -> Self {
+        Self {
+            script_id,
+            results: Vec::with_capacity(statements.len()),
+            statements,
+            stop_on_error,
+            cancelled: false,
+            start: Instant::now(),
+        }
+    }
+
+    // 下一条要执行的语句，已取消或（stop_on_error 时）已有语句失败时返回None
+    pub fn next_statement(&self) -> Option<String> {
+        if self.cancelled || (self.stop_on_error && self.has_failed()) {
+            return None;
+        }
+        self.statements.get(self.results.len()).map(|s| s.sql.clone())
+    }
+
+    // 记录当前语句的执行结果（Ok为影响行数）
+    pub fn record(&mut self, result: Result<u64, String>, elapsed: Duration) {
+        let index = self.results.len();
+        let Some(statement) = self.statements.get(index) else {
+            return;
+        };
+        let (status, rows_affected, error) = match result {
+            Ok(rows) => (StatementStatus::Success, Some(rows), None),
+            Err(e) => (StatementStatus::Failed, None, Some(e)),
+        };
+        self.results.push(ScriptStatementResult {
+            index,
+            line: statement.line,
+            sql: events::sql_preview(&statement.sql),
+            status,
+            rows_affected,
+            execution_time_ms: elapsed.as_millis(),
+            error,
+        });
+        events::publish(ServerEvent::ScriptProgress {
+            script_id: self.script_id.clone(),
+            index,
+            total: self.statements.len(),
+            line: statement.line,
+            success: status == StatementStatus::Success,
+            timestamp: events::now(),
+        });
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.results.iter().any(|r| r.status == StatementStatus::Failed)
+    }
+
+    // 已成功执行的语句
+    pub fn executed_sql(&self) -> impl Iterator<Item = &str> {
+        self.results.iter()
+            .filter(|r| r.status == StatementStatus::Success)
+            .map(|r| self.statements[r.index].sql.as_str())
+    }
+
+    // 已执行（成功或失败）的完整语句及其结果
+    pub fn attempted(&self) -> impl Iterator<Item = (&str, &ScriptStatementResult)> {
+        self.results.iter()
+            .filter(|r| r.status != StatementStatus::Skipped)
+            .map(|r| (self.statements[r.index].sql.as_str(), r))
+    }
+
+    // 汇总结果，未执行的语句标记为跳过，并推送脚本完成事件
+    pub fn finish(mut self, rolled_back: bool) -> ScriptResult {
+        for (index, statement) in self.statements.iter().enumerate().skip(self.results.len()) {
+            self.results.push(ScriptStatementResult {
+                index,
+                line: statement.line,
+                sql: events::sql_preview(&statement.sql),
+                status: StatementStatus::Skipped,
+                rows_affected: None,
+                execution_time_ms: 0,
+                error: None,
+            });
+        }
+
+        let count = |status| self.results.iter().filter(|r| r.status == status).count();
+        let result = ScriptResult {
+            script_id: self.script_id,
+            total: self.statements.len(),
+            succeeded: count(StatementStatus::Success),
+            failed: count(StatementStatus::Failed),
+            skipped: count(StatementStatus::Skipped),
+            cancelled: self.cancelled,
+            rolled_back,
+            execution_time_ms: self.start.elapsed().as_millis(),
+            statements: self.results,
+        };
+        events::publish(ServerEvent::ScriptFinished {
+            script_id: result.script_id.clone(),
+            succeeded: result.succeeded,
+            failed: result.failed,
+            skipped: result.skipped,
+            execution_time_ms: result.execution_time_ms,
+            timestamp: events::now(),
+        });
+        result
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statements(count: usize) -> Vec<ScriptStatement> {
+        (0..count).map(|i| ScriptStatement { sql: format!("SELECT {}", i), line: i + 1 }).collect()
+    }
+
+    fn run(runner: &mut ScriptRunner, fail_at: Option<usize>) {
+        let mut index = 0;
+        while runner.next_statement().is_some() {
+            let result = if Some(index) == fail_at { Err("语法错误".to_string()) } else { Ok(1) };
+            runner.record(result, Duration::from_millis(1));
+            index += 1;
+        }
+    }
+
+    #[test]
+    fn test_stop_on_error() {
+        let mut runner = ScriptRunner::new("s1".to_string(), statements(4), true);
+        run(&mut runner, Some(1));
+        let result = runner.finish(false);
+        assert_eq!((result.succeeded, result.failed, result.skipped), (1, 1, 2));
+        assert_eq!(result.statements[1].error.as_deref(), Some("语法错误"));
+        assert_eq!(result.statements[3].status, StatementStatus::Skipped);
+        assert_eq!(result.statements[3].line, 4);
+    }
+
+    #[test]
+    fn test_continue_on_error() {
+        let mut runner = ScriptRunner::new("s2".to_string(), statements(4), false);
+        run(&mut runner, Some(1));
+        assert_eq!(runner.executed_sql().count(), 3);
+        let result = runner.finish(false);
+        assert_eq!((result.succeeded, result.failed, result.skipped), (3, 1, 0));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut runner = ScriptRunner::new("s3".to_string(), statements(3), true);
+        runner.record(Ok(0), Duration::ZERO);
+        runner.cancel();
+        assert!(runner.next_statement().is_none());
+        let result = runner.finish(false);
+        assert!(result.cancelled);
+        assert_eq!(result.skipped, 2);
+    }
+}
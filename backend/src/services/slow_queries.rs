@@ -0,0 +1,188 @@
+use log::*;
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use serde_json::Value as JsonValue;
+use sqlx::Row;
+
+use crate::db::{bind_json_values, DatabaseManager, DatabasePool, LocalStorageManager};
+use crate::services::policy::{classify_statement, StatementKind};
+
+// 应用设置中保存慢查询日志配置的键
+pub const SLOW_QUERY_SETTING_KEY: &str = "slow_query_log";
+
+// 默认慢查询记录阈值（毫秒）
+pub const DEFAULT_THRESHOLD_MS: u64 = 1000;
+// 允许配置的最小阈值，低于此耗时的查询不读取配置
+pub const MIN_THRESHOLD_MS: u64 = 100;
+
+// 慢查询日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQuerySettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_threshold")]
+    pub threshold_ms: u64,
+    // 是否同时记录执行计划（只对查询语句执行 EXPLAIN）
+    #[serde(default = "default_enabled")]
+    pub capture_explain: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_threshold() -> u64 {
+    DEFAULT_THRESHOLD_MS
+}
+
+impl Default for SlowQuerySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_ms: DEFAULT_THRESHOLD_MS,
+            capture_explain: true,
+        }
+    }
+}
+
+impl SlowQuerySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold_ms < MIN_THRESHOLD_MS {
+            return Err(format!("慢查询阈值不能小于 {}ms", MIN_THRESHOLD_MS));
+        }
+        Ok(())
+    }
+}
+
+// 读取慢查询日志配置，未配置时使用默认值
+pub async fn load_settings(storage: &LocalStorageManager) -> SlowQuerySettings {
+    match storage.get_app_setting(SLOW_QUERY_SETTING_KEY).await.ok().flatten() {
+        Some(value) => serde_json::from_str(&value)
+            .map_err(|e| warn!("[SlowQuery] 慢查询配置解析失败: {}", e))
+            .unwrap_or_default(),
+        None => SlowQuerySettings::default(),
+    }
+}
+
+// 计算SQL指纹：字面量替换为?，IN列表合并，空白归一，转小写
+pub fn fingerprint(sql: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref STRING_RE: Regex = Regex::new(r"'(?:[^']|'')*'").unwrap();
+        static ref NUMBER_RE: Regex = Regex::new(r"\b\d+(?:\.\d+)?\b").unwrap();
+        static ref IN_LIST_RE: Regex = Regex::new(r"(?i)\bin\s*\(\s*\?(?:\s*,\s*\?)*\s*\)").unwrap();
+        static ref SPACE_RE: Regex = Regex::new(r"\s+").unwrap();
+    }
+
+    let normalized = STRING_RE.replace_all(sql, "?");
+    let normalized = NUMBER_RE.replace_all(&normalized, "?");
+    let normalized = IN_LIST_RE.replace_all(&normalized, "in (?)");
+    let normalized = SPACE_RE.replace_all(&normalized, " ");
+    normalized.trim().trim_end_matches(';').trim_end().to_lowercase()
+}
+
+// 获取查询语句的执行计划文本，非查询语句或执行失败时返回None
+pub async fn explain_text(db_manager: &DatabaseManager, sql: &str, params: &[JsonValue]) -> Option<String> {
+    if classify_statement(sql) != StatementKind::Query {
+        return None;
+    }
+
+    let result = match &db_manager.pool {
+        DatabasePool::MySQL(pool) => {
+            let explain_sql = format!("EXPLAIN FORMAT=TREE {}", sql);
+            bind_json_values!(sqlx::query_scalar::<_, String>(&explain_sql), params)
+                .fetch_all(pool)
+                .await
+        }
+        DatabasePool::PostgreSQL(pool) => {
+            let explain_sql = format!("EXPLAIN {}", sql);
+            bind_json_values!(sqlx::query_scalar::<_, String>(&explain_sql), params)
+                .fetch_all(pool)
+                .await
+        }
+        DatabasePool::SQLite(pool) => {
+            let explain_sql = format!("EXPLAIN QUERY PLAN {}", sql);
+            bind_json_values!(sqlx::query(&explain_sql), params)
+                .fetch_all(pool)
+                .await
+                .map(|rows| rows.iter().filter_map(|row| row.try_get::<String, _>("detail").ok()).collect())
+        }
+        DatabasePool::MongoDB(..) | DatabasePool::Redis(_) | DatabasePool::DuckDB(_) => return None,
+    };
+
+    match result {
+        Ok(lines) => Some(lines.join("\n")),
+        Err(e) => {
+            debug!("[SlowQuery] 获取执行计划失败: {}", e);
+            None
+        }
+    }
+}
+
+// 查询耗时超过阈值时后台记录慢查询（附带执行计划）
+pub fn record_in_background(
+    storage: LocalStorageManager,
+    db_manager: DatabaseManager,
+    connection_id: Option<i64>,
+    sql: String,
+    params: Vec<JsonValue>,
+    elapsed_ms: u128,
+    row_count: usize,
+) {
+    if elapsed_ms < MIN_THRESHOLD_MS as u128 {
+        return;
+    }
+    tokio::spawn(async move {
+        let settings = load_settings(&storage).await;
+        if !settings.enabled || elapsed_ms < settings.threshold_ms as u128 {
+            return;
+        }
+
+        let plan = if settings.capture_explain {
+            explain_text(&db_manager, &sql, &params).await
+        } else {
+            None
+        };
+
+        if let Err(e) = storage.add_slow_query(
+            connection_id,
+            &fingerprint(&sql),
+            &sql,
+            elapsed_ms as i64,
+            Some(row_count as i64),
+            plan.as_deref(),
+        ).await {
+            warn!("[SlowQuery] 记录慢查询失败: {}", e);
+        } else {
+            info!("[SlowQuery] 已记录慢查询: connection_id={:?}, 耗时={}ms", connection_id, elapsed_ms);
+        }
+    });
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint("SELECT *  FROM orders\n WHERE id = 42 AND status = 'it''s' ;"),
+            "select * from orders where id = ? and status = ?"
+        );
+        assert_eq!(
+            fingerprint("select * from t where id in (1, 2, 3)"),
+            fingerprint("SELECT * FROM t WHERE id IN (4)")
+        );
+        // 标识符中的数字保持不变
+        assert_eq!(fingerprint("SELECT col1 FROM t2"), "select col1 from t2");
+    }
+
+    #[test]
+    fn test_settings_defaults() {
+        let settings: SlowQuerySettings = serde_json::from_str(r#"{"threshold_ms": 2000}"#).unwrap();
+        assert!(settings.enabled);
+        assert!(settings.capture_explain);
+        assert_eq!(settings.threshold_ms, 2000);
+        assert!(SlowQuerySettings { threshold_ms: 10, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use regex::Regex;
+use serde::Serialize;
+use sqlparser::ast::{Query, Select, SelectItem, SetExpr, Statement};
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Location, Token, Tokenizer};
+
+use crate::db::DatabaseType;
+use crate::utils::security::dialect_for;
+
+// 问题级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+// 单个检查问题（行号、列号从1开始，无法定位时为空）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    pub severity: LintSeverity,
+    pub rule: String,
+    pub message: String,
+    pub line: Option<u64>,
+    pub column: Option<u64>,
+    pub statement_index: Option<usize>,
+}
+
+impl LintIssue {
+    fn warning(rule: &str, message: &str, statement_index: usize, location: Option<Location>) -> Self {
+        Self {
+            severity: LintSeverity::Warning,
+            rule: rule.to_string(),
+            message: message.to_string(),
+            line: location.map(|l| l.line),
+            column: location.map(|l| l.column),
+            statement_index: Some(statement_index),
+        }
+    }
+}
+
+// 从解析错误中提取位置（sqlparser 错误信息以 "at Line: X, Column Y" 结尾）
+fn syntax_error(message: &str) -> LintIssue {
+    lazy_static::lazy_static! {
+        static ref LOCATION_RE: Regex = Regex::new(r"^(?:sql parser error: )?(.*?)(?: at Line: (\d+), Column:? (\d+))?$").unwrap();
+    }
+    let captures = LOCATION_RE.captures(message);
+    let group = |i: usize| captures.as_ref().and_then(|c| c.get(i)).map(|m| m.as_str());
+    LintIssue {
+        severity: LintSeverity::Error,
+        rule: "syntax_error".to_string(),
+        message: format!("SQL 语法错误: {}", group(1).unwrap_or(message)),
+        line: group(2).and_then(|v| v.parse().ok()),
+        column: group(3).and_then(|v| v.parse().ok()),
+        statement_index: None,
+    }
+}
+
+// 每条语句起始位置（跳过空白、注释和多余的分号）
+fn statement_locations(tokens: &[sqlparser::tokenizer::TokenWithLocation]) -> Vec<Location> {
+    let mut locations = Vec::new();
+    let mut at_start = true;
+    for token in tokens {
+        match &token.token {
+            Token::Whitespace(_) | Token::EOF => {}
+            Token::SemiColon => at_start = true,
+            _ if at_start => {
+                locations.push(token.location);
+                at_start = false;
+            }
+            _ => {}
+        }
+    }
+    locations
+}
+
+// 收集查询中的所有 SELECT（包括 CTE、UNION 各分支和括号子查询）
+fn collect_selects<'a>(query: &'a Query, selects: &mut Vec<&'a Select>) {
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            collect_selects(&cte.query, selects);
+        }
+    }
+    collect_set_expr(&query.body, selects);
+}
+
+fn collect_set_expr<'a>(body: &'a SetExpr, selects: &mut Vec<&'a Select>) {
+    match body {
+        SetExpr::Select(select) => selects.push(select),
+        SetExpr::Query(query) => collect_selects(query, selects),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr(left, selects);
+            collect_set_expr(right, selects);
+        }
+        _ => {}
+    }
+}
+
+fn lint_select(select: &Select, index: usize, location: Option<Location>, issues: &mut Vec<LintIssue>) {
+    if select.projection.iter().any(|item| matches!(item, SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..))) {
+        issues.push(LintIssue::warning("select_star", "使用了 SELECT *，建议列出需要的列", index, location));
+    }
+    if select.from.len() > 1 {
+        issues.push(LintIssue::warning(
+            "implicit_cross_join",
+            "FROM 中以逗号分隔多张表（隐式交叉连接），建议使用显式 JOIN ... ON",
+            index,
+            location,
+        ));
+    }
+}
+
+fn lint_statement(statement: &Statement, index: usize, location: Option<Location>, issues: &mut Vec<LintIssue>) {
+    let mut selects = Vec::new();
+    match statement {
+        Statement::Query(query) => collect_selects(query, &mut selects),
+        Statement::Insert { source: Some(query), .. } => collect_selects(query, &mut selects),
+        Statement::Update { selection: None, .. } => {
+            issues.push(LintIssue::warning("missing_where", "UPDATE 语句没有 WHERE 条件，将修改全表数据", index, location));
+        }
+        Statement::Delete { selection: None, .. } => {
+            issues.push(LintIssue::warning("missing_where", "DELETE 语句没有 WHERE 条件，将删除全表数据", index, location));
+        }
+        _ => {}
+    }
+    for select in selects {
+        lint_select(select, index, location, issues);
+    }
+}
+
+// 按数据库方言解析SQL，返回语法错误（带位置）和常见问题警告
+pub fn lint_sql(db_type: DatabaseType, sql: &str) -> Vec<LintIssue> {
+    let dialect = dialect_for(db_type);
+    let tokens = match Tokenizer::new(dialect.as_ref(), sql).tokenize_with_location() {
+        Ok(tokens) => tokens,
+        Err(e) => return vec![syntax_error(&e.to_string())],
+    };
+    let statements = match Parser::parse_sql(dialect.as_ref(), sql) {
+        Ok(statements) => statements,
+        Err(e) => return vec![syntax_error(&e.to_string())],
+    };
+
+    let locations = statement_locations(&tokens);
+    let mut issues = Vec::new();
+    for (index, statement) in statements.iter().enumerate() {
+        lint_statement(statement, index, locations.get(index).copied(), &mut issues);
+    }
+    issues
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(issues: &[LintIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_warnings() {
+        let sql = "SELECT * FROM users;\nUPDATE users SET name = 'x';\n  DELETE FROM orders WHERE id = 1;\nSELECT u.id FROM users u, orders o";
+        let issues = lint_sql(DatabaseType::PostgreSQL, sql);
+        assert_eq!(rules(&issues), vec!["select_star", "missing_where", "implicit_cross_join"]);
+        assert_eq!((issues[1].line, issues[1].column, issues[1].statement_index), (Some(2), Some(1), Some(1)));
+        assert_eq!(issues[2].statement_index, Some(3));
+
+        assert!(lint_sql(DatabaseType::MySQL, "SELECT `id` FROM `users` WHERE id = 1").is_empty());
+        assert!(lint_sql(DatabaseType::PostgreSQL, "SELECT id::text FROM users WHERE id = 1").is_empty());
+    }
+
+    #[test]
+    fn test_lint_syntax_error() {
+        let issues = lint_sql(DatabaseType::PostgreSQL, "SELECT id\nFROM WHERE id = 1");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, LintSeverity::Error);
+        assert_eq!(issues[0].line, Some(2));
+        assert!(!issues[0].message.contains("at Line"));
+
+        let issues = lint_sql(DatabaseType::MySQL, "SELECT 'unterminated");
+        assert_eq!(issues[0].rule, "syntax_error");
+        assert_eq!((issues[0].line, issues[0].column), (Some(1), Some(8)));
+    }
+}
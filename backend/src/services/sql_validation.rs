@@ -0,0 +1,489 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::{
+    Expr, FromTable, FunctionArg, FunctionArgExpr, GroupByExpr, JoinConstraint, JoinOperator, ObjectName, Query,
+    SelectItem, SetExpr, Statement, TableAlias, TableFactor, TableWithJoins,
+};
+
+use crate::db::DatabaseType;
+use crate::utils::security::parse_statements_as;
+
+// 行号、会话变量等不属于表结构的标识符
+const PSEUDO_COLUMNS: &[&str] = &[
+    "rowid", "oid", "_rowid_", "ctid", "default", "current_date", "current_time", "current_timestamp",
+    "localtime", "localtimestamp", "current_user", "session_user", "user", "sysdate",
+];
+
+// 校验问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationIssueKind {
+    SyntaxError,
+    UnknownTable,
+    UnknownColumn,
+    ExplainFailed,
+}
+
+// 单个校验问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ValidationIssue {
+    pub kind: ValidationIssueKind,
+    pub message: String,
+    pub table: Option<String>,
+    pub column: Option<String>,
+}
+
+// 生成SQL的校验结果（explained 表示已在连接上执行过 EXPLAIN）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SqlValidation {
+    pub valid: bool,
+    pub explained: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl SqlValidation {
+    pub fn from_issues(issues: Vec<ValidationIssue>) -> Self {
+        Self { valid: issues.is_empty(), explained: false, issues }
+    }
+
+    pub fn push(&mut self, issue: ValidationIssue) {
+        self.valid = false;
+        self.issues.push(issue);
+    }
+}
+
+fn issue(kind: ValidationIssueKind, message: String, table: Option<&str>, column: Option<&str>) -> ValidationIssue {
+    ValidationIssue {
+        kind,
+        message,
+        table: table.map(str::to_string),
+        column: column.map(str::to_string),
+    }
+}
+
+// SQL中引用的表和列
+// 不区分子查询作用域：整条语句中出现的表和别名都可用于解析列，宁可漏报也不误报
+#[derive(Debug, Default)]
+pub struct SqlReferences {
+    // 引用的表（不含CTE和带schema前缀的表）
+    pub tables: Vec<String>,
+    // 小写的表名或别名 -> 实际表名（子查询、CTE等列未知的来源为None）
+    relations: HashMap<String, Option<String>>,
+    ctes: HashSet<String>,
+    output_aliases: HashSet<String>,
+    // 存在列未知的来源时无法校验不带前缀的列
+    opaque: bool,
+    columns: Vec<(Option<String>, String)>,
+}
+
+impl SqlReferences {
+    fn add_table(&mut self, name: &ObjectName, alias: Option<&TableAlias>) {
+        let table = name.0.last().map(|i| i.value.clone()).unwrap_or_default();
+        let known = name.0.len() == 1 && !self.ctes.contains(&table.to_lowercase());
+        let target = known.then(|| table.clone());
+        if known {
+            if !self.tables.iter().any(|t| t.eq_ignore_ascii_case(&table)) {
+                self.tables.push(table.clone());
+            }
+        } else {
+            self.opaque = true;
+        }
+        self.relations.insert(table.to_lowercase(), target.clone());
+        if let Some(alias) = alias {
+            self.relations.insert(alias.name.value.to_lowercase(), target);
+        }
+    }
+
+    fn add_relation(&mut self, factor: &TableFactor) {
+        match factor {
+            TableFactor::Table { name, alias, .. } => self.add_table(name, alias.as_ref()),
+            TableFactor::Derived { subquery, alias, .. } => {
+                self.opaque = true;
+                if let Some(alias) = alias {
+                    self.relations.insert(alias.name.value.to_lowercase(), None);
+                }
+                self.visit_query(subquery);
+            }
+            TableFactor::NestedJoin { table_with_joins, alias } => {
+                if let Some(alias) = alias {
+                    self.relations.insert(alias.name.value.to_lowercase(), None);
+                }
+                self.add_from(std::slice::from_ref(table_with_joins.as_ref()));
+            }
+            other => {
+                self.opaque = true;
+                let alias = match other {
+                    TableFactor::TableFunction { alias, .. }
+                    | TableFactor::Function { alias, .. }
+                    | TableFactor::UNNEST { alias, .. } => alias.as_ref(),
+                    _ => None,
+                };
+                if let Some(alias) = alias {
+                    self.relations.insert(alias.name.value.to_lowercase(), None);
+                }
+            }
+        }
+    }
+
+    fn add_from(&mut self, from: &[TableWithJoins]) {
+        for table_with_joins in from {
+            self.add_relation(&table_with_joins.relation);
+            for join in &table_with_joins.joins {
+                self.add_relation(&join.relation);
+            }
+        }
+        for table_with_joins in from {
+            for join in &table_with_joins.joins {
+                let constraint = match &join.join_operator {
+                    JoinOperator::Inner(c)
+                    | JoinOperator::LeftOuter(c)
+                    | JoinOperator::RightOuter(c)
+                    | JoinOperator::FullOuter(c)
+                    | JoinOperator::LeftSemi(c)
+                    | JoinOperator::RightSemi(c)
+                    | JoinOperator::LeftAnti(c)
+                    | JoinOperator::RightAnti(c) => c,
+                    _ => continue,
+                };
+                if let JoinConstraint::On(expr) = constraint {
+                    self.visit_expr(expr);
+                }
+            }
+        }
+    }
+
+    fn add_column(&mut self, parts: &[sqlparser::ast::Ident]) {
+        match parts {
+            [column] => self.columns.push((None, column.value.clone())),
+            [qualifier, column] => self.columns.push((Some(qualifier.value.clone()), column.value.clone())),
+            _ => {}  // schema.table.column 等形式不校验
+        }
+    }
+
+    fn add_qualified_wildcard(&mut self, name: &ObjectName) {
+        if let [qualifier] = name.0.as_slice() {
+            self.columns.push((Some(qualifier.value.clone()), "*".to_string()));
+        }
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                self.ctes.insert(cte.alias.name.value.to_lowercase());
+                self.visit_query(&cte.query);
+            }
+        }
+        self.visit_set_expr(&query.body);
+        for order in &query.order_by {
+            self.visit_expr(&order.expr);
+        }
+    }
+
+    fn visit_set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => {
+                self.add_from(&select.from);
+                for item in &select.projection {
+                    match item {
+                        SelectItem::UnnamedExpr(expr) => self.visit_expr(expr),
+                        SelectItem::ExprWithAlias { expr, alias } => {
+                            self.output_aliases.insert(alias.value.to_lowercase());
+                            self.visit_expr(expr);
+                        }
+                        SelectItem::QualifiedWildcard(name, _) => self.add_qualified_wildcard(name),
+                        SelectItem::Wildcard(_) => {}
+                    }
+                }
+                if let Some(selection) = &select.selection {
+                    self.visit_expr(selection);
+                }
+                if let GroupByExpr::Expressions(exprs) = &select.group_by {
+                    self.visit_exprs(exprs);
+                }
+                if let Some(having) = &select.having {
+                    self.visit_expr(having);
+                }
+            }
+            SetExpr::Query(query) => self.visit_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.visit_set_expr(left);
+                self.visit_set_expr(right);
+            }
+            SetExpr::Values(values) => {
+                for row in &values.rows {
+                    self.visit_exprs(row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Identifier(ident) => self.add_column(std::slice::from_ref(ident)),
+            Expr::CompoundIdentifier(parts) => self.add_column(parts),
+            Expr::QualifiedWildcard(name) => self.add_qualified_wildcard(name),
+            Expr::BinaryOp { left, right, .. }
+            | Expr::AnyOp { left, right, .. }
+            | Expr::AllOp { left, right, .. }
+            | Expr::IsDistinctFrom(left, right)
+            | Expr::IsNotDistinctFrom(left, right) => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::Like { expr, pattern, .. }
+            | Expr::ILike { expr, pattern, .. }
+            | Expr::SimilarTo { expr, pattern, .. }
+            | Expr::RLike { expr, pattern, .. } => {
+                self.visit_expr(expr);
+                self.visit_expr(pattern);
+            }
+            Expr::UnaryOp { expr, .. }
+            | Expr::Nested(expr)
+            | Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::IsTrue(expr)
+            | Expr::IsNotTrue(expr)
+            | Expr::IsFalse(expr)
+            | Expr::IsNotFalse(expr)
+            | Expr::IsUnknown(expr)
+            | Expr::IsNotUnknown(expr)
+            | Expr::Cast { expr, .. }
+            | Expr::TryCast { expr, .. }
+            | Expr::SafeCast { expr, .. }
+            | Expr::Convert { expr, .. }
+            | Expr::Extract { expr, .. }
+            | Expr::Ceil { expr, .. }
+            | Expr::Floor { expr, .. }
+            | Expr::Collate { expr, .. }
+            | Expr::AtTimeZone { timestamp: expr, .. } => self.visit_expr(expr),
+            Expr::Between { expr, low, high, .. } => {
+                self.visit_expr(expr);
+                self.visit_expr(low);
+                self.visit_expr(high);
+            }
+            Expr::InList { expr, list, .. } => {
+                self.visit_expr(expr);
+                self.visit_exprs(list);
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.visit_expr(expr);
+                self.visit_query(subquery);
+            }
+            Expr::Exists { subquery, .. } | Expr::Subquery(subquery) => self.visit_query(subquery),
+            Expr::Case { operand, conditions, results, else_result } => {
+                if let Some(operand) = operand {
+                    self.visit_expr(operand);
+                }
+                self.visit_exprs(conditions);
+                self.visit_exprs(results);
+                if let Some(else_result) = else_result {
+                    self.visit_expr(else_result);
+                }
+            }
+            Expr::Function(function) => {
+                for arg in &function.args {
+                    let (FunctionArg::Named { arg, .. } | FunctionArg::Unnamed(arg)) = arg;
+                    if let FunctionArgExpr::Expr(expr) = arg {
+                        self.visit_expr(expr);
+                    }
+                }
+                if let Some(filter) = &function.filter {
+                    self.visit_expr(filter);
+                }
+                for order in &function.order_by {
+                    self.visit_expr(&order.expr);
+                }
+            }
+            Expr::Tuple(exprs) => self.visit_exprs(exprs),
+            Expr::Substring { expr, substring_from, substring_for, .. } => {
+                self.visit_expr(expr);
+                for e in [substring_from, substring_for].into_iter().flatten() {
+                    self.visit_expr(e);
+                }
+            }
+            Expr::Position { expr, r#in } => {
+                self.visit_expr(expr);
+                self.visit_expr(r#in);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Query(query) => self.visit_query(query),
+            Statement::Insert { table_name, columns, source, .. } => {
+                self.add_table(table_name, None);
+                if let Some(table) = table_name.0.last() {
+                    for column in columns {
+                        self.add_column(&[table.clone(), column.clone()]);
+                    }
+                }
+                if let Some(source) = source {
+                    self.visit_query(source);
+                }
+            }
+            Statement::Update { table, assignments, from, selection, .. } => {
+                self.add_from(std::slice::from_ref(table));
+                if let Some(from) = from {
+                    self.add_from(std::slice::from_ref(from));
+                }
+                for assignment in assignments {
+                    self.add_column(&assignment.id);
+                    self.visit_expr(&assignment.value);
+                }
+                if let Some(selection) = selection {
+                    self.visit_expr(selection);
+                }
+            }
+            Statement::Delete { from, using, selection, .. } => {
+                let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = from;
+                self.add_from(from);
+                if let Some(using) = using {
+                    self.add_from(using);
+                }
+                if let Some(selection) = selection {
+                    self.visit_expr(selection);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 对照表列表和表结构（键为小写表名）检查引用；表结构缺失的表不检查其列
+    pub fn check(&self, known_tables: &[String], table_columns: &HashMap<String, Vec<String>>) -> Vec<ValidationIssue> {
+        let mut issues: Vec<ValidationIssue> = Vec::new();
+        let mut missing = HashSet::new();
+        for table in &self.tables {
+            if !known_tables.iter().any(|t| t.eq_ignore_ascii_case(table)) {
+                missing.insert(table.to_lowercase());
+                issues.push(issue(ValidationIssueKind::UnknownTable, format!("表 {} 不存在", table), Some(table), None));
+            }
+        }
+
+        let has_column = |table: &str, column: &str| {
+            table_columns.get(&table.to_lowercase()).map(|columns| columns.iter().any(|c| c.eq_ignore_ascii_case(column)))
+        };
+        // 所有表的结构都已知时才能判断不带前缀的列
+        let all_known = !self.opaque && self.tables.iter().all(|t| table_columns.contains_key(&t.to_lowercase()));
+
+        for (qualifier, column) in &self.columns {
+            if column.starts_with('@') || PSEUDO_COLUMNS.contains(&column.to_lowercase().as_str()) {
+                continue;
+            }
+            let found = match qualifier {
+                Some(qualifier) => match self.relations.get(&qualifier.to_lowercase()) {
+                    None => {
+                        issues.push(issue(
+                            ValidationIssueKind::UnknownColumn,
+                            format!("列 {}.{} 引用了未知的表或别名 {}", qualifier, column, qualifier),
+                            None,
+                            Some(column),
+                        ));
+                        continue;
+                    }
+                    Some(None) => continue,
+                    Some(Some(table)) if missing.contains(&table.to_lowercase()) || column == "*" => continue,
+                    Some(Some(table)) => match has_column(table, column) {
+                        Some(false) => {
+                            issues.push(issue(
+                                ValidationIssueKind::UnknownColumn,
+                                format!("表 {} 中不存在列 {}", table, column),
+                                Some(table),
+                                Some(column),
+                            ));
+                            continue;
+                        }
+                        _ => true,
+                    },
+                },
+                None => {
+                    self.output_aliases.contains(&column.to_lowercase())
+                        || !all_known
+                        || self.tables.iter().any(|t| has_column(t, column) == Some(true))
+                }
+            };
+            if !found {
+                issues.push(issue(
+                    ValidationIssueKind::UnknownColumn,
+                    format!("查询的表中不存在列 {}", column),
+                    None,
+                    Some(column),
+                ));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        issues.retain(|i| seen.insert(i.message.clone()));
+        issues
+    }
+}
+
+// 按数据库方言解析SQL并收集引用的表和列
+pub fn collect_references(db_type: DatabaseType, sql: &str) -> Result<SqlReferences, String> {
+    let statements = parse_statements_as(Some(db_type), sql)?;
+    let mut references = SqlReferences::default();
+    for statement in &statements {
+        references.visit_statement(statement);
+    }
+    Ok(references)
+}
+
+// 语法错误转换为校验问题
+pub fn syntax_issue(message: String) -> ValidationIssue {
+    issue(ValidationIssueKind::SyntaxError, message, None, None)
+}
+
+// EXPLAIN 失败转换为校验问题
+pub fn explain_issue(message: String) -> ValidationIssue {
+    issue(ValidationIssueKind::ExplainFailed, message, None, None)
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> (Vec<String>, HashMap<String, Vec<String>>) {
+        let columns = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut table_columns = HashMap::new();
+        table_columns.insert("users".to_string(), columns(&["id", "name", "email"]));
+        table_columns.insert("orders".to_string(), columns(&["id", "user_id", "amount"]));
+        (vec!["users".to_string(), "Orders".to_string()], table_columns)
+    }
+
+    fn check(db_type: DatabaseType, sql: &str) -> Vec<ValidationIssue> {
+        let (tables, columns) = schema();
+        collect_references(db_type, sql).unwrap().check(&tables, &columns)
+    }
+
+    #[test]
+    fn test_valid_references() {
+        assert!(check(DatabaseType::MySQL, "SELECT u.name, SUM(o.amount) AS total FROM users u JOIN orders o ON o.user_id = u.id GROUP BY u.name ORDER BY total DESC").is_empty());
+        assert!(check(DatabaseType::PostgreSQL, "WITH t AS (SELECT user_id, COUNT(*) AS n FROM orders GROUP BY user_id) SELECT t.n, x FROM t").is_empty());
+        assert!(check(DatabaseType::PostgreSQL, "SELECT id FROM users WHERE id IN (SELECT user_id FROM orders WHERE amount > 10)").is_empty());
+        assert!(check(DatabaseType::SQLite, "UPDATE users SET name = 'x' WHERE rowid = 1").is_empty());
+    }
+
+    #[test]
+    fn test_hallucinated_references() {
+        let issues = check(DatabaseType::MySQL, "SELECT u.nickname, p.title FROM users u JOIN posts p ON p.user_id = u.id");
+        let kinds: Vec<_> = issues.iter().map(|i| (i.kind, i.column.as_deref())).collect();
+        assert!(kinds.contains(&(ValidationIssueKind::UnknownTable, None)));
+        assert!(kinds.contains(&(ValidationIssueKind::UnknownColumn, Some("nickname"))));
+        assert_eq!(issues.len(), 2);
+
+        let issues = check(DatabaseType::PostgreSQL, "SELECT id, phone FROM users WHERE x.id = 1");
+        assert_eq!(issues.len(), 2);
+        let issues = check(DatabaseType::MySQL, "INSERT INTO orders (user_id, total) VALUES (1, 10)");
+        assert_eq!(issues[0].table.as_deref(), Some("orders"));
+    }
+}
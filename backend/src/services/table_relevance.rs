@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+
+// 提示词中默认包含的相关表数量
+pub const DEFAULT_TOP_K: usize = 8;
+
+// 表名完全匹配、表名片段匹配、列名片段匹配、注释匹配的得分
+const TABLE_NAME_SCORE: f64 = 10.0;
+const TABLE_PART_SCORE: f64 = 5.0;
+const COLUMN_PART_SCORE: f64 = 2.0;
+const COMMENT_SCORE: f64 = 2.0;
+// 单张表的列匹配得分上限，避免宽表仅凭列数取胜
+const MAX_COLUMN_SCORE: f64 = 8.0;
+
+// 问题中不参与匹配的常见英文词
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "of", "and", "or", "all", "any", "show", "get", "list", "find", "give", "me",
+    "with", "by", "for", "in", "on", "from", "to", "select", "each", "per", "what", "how", "many",
+    "which", "who", "top", "is", "are", "was", "were", "that", "this", "have", "has", "their", "than", "id",
+];
+
+// 参与相关性评分的表（列为 列名, 注释）
+#[derive(Debug, Clone, Default)]
+pub struct TableProfile {
+    pub name: String,
+    pub columns: Vec<(String, Option<String>)>,
+}
+
+// 按列目录（表名, 列名, 注释）构建表画像，保持 table_names 的顺序
+pub fn build_profiles(table_names: &[String], catalog: Vec<(String, String, Option<String>)>) -> Vec<TableProfile> {
+    let mut columns: HashMap<String, Vec<(String, Option<String>)>> = HashMap::new();
+    for (table, column, comment) in catalog {
+        columns.entry(table).or_default().push((column, comment));
+    }
+    table_names.iter()
+        .map(|name| TableProfile {
+            name: name.clone(),
+            columns: columns.remove(name).unwrap_or_default(),
+        })
+        .collect()
+}
+
+// 简单的英文单数化：categories -> category, orders -> order
+fn singular(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies") {
+        if stem.len() >= 2 {
+            return format!("{}y", stem);
+        }
+    }
+    if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+// 将标识符拆分为小写单数片段：orderItems / order_items -> [order, item]
+fn identifier_parts(identifier: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in identifier.chars() {
+        if !c.is_ascii_alphanumeric() {
+            prev_lower = false;
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts.into_iter().map(|p| singular(&p)).collect()
+}
+
+// 提取问题中的英文关键词（小写、单数化、去停用词）
+pub fn question_terms(question: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    question.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|w| w.len() >= 2)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .map(|w| if w.contains('_') { w } else { singular(&w) })
+        .filter(|w| seen.insert(w.clone()))
+        .collect()
+}
+
+// 提取问题中的中文片段的二元组，用于匹配列注释
+fn cjk_bigrams(question: &str) -> HashSet<String> {
+    let mut bigrams = HashSet::new();
+    let mut run: Vec<char> = Vec::new();
+    for c in question.chars().chain(std::iter::once(' ')) {
+        if ('\u{4e00}'..='\u{9fff}').contains(&c) {
+            run.push(c);
+            continue;
+        }
+        for pair in run.windows(2) {
+            bigrams.insert(pair.iter().collect());
+        }
+        run.clear();
+    }
+    bigrams
+}
+
+// 计算单张表与问题的相关性得分
+pub fn score_table(terms: &[String], bigrams: &HashSet<String>, table: &TableProfile) -> f64 {
+    let table_lower = table.name.to_lowercase();
+    let table_parts = identifier_parts(&table.name);
+    let mut score = 0.0;
+
+    for term in terms {
+        if *term == table_lower || *term == singular(&table_lower) {
+            score += TABLE_NAME_SCORE;
+        } else {
+            let term_parts = identifier_parts(term);
+            if !term_parts.is_empty() && term_parts.iter().all(|p| table_parts.contains(p)) {
+                score += TABLE_PART_SCORE;
+            }
+        }
+    }
+
+    let mut column_score = 0.0;
+    for (column, comment) in &table.columns {
+        let column_parts = identifier_parts(column);
+        if terms.iter().any(|term| column.eq_ignore_ascii_case(term) || column_parts.contains(term)) {
+            column_score += COLUMN_PART_SCORE;
+        }
+        if let Some(comment) = comment.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            if bigrams.iter().any(|bigram| comment.contains(bigram.as_str())) {
+                column_score += COMMENT_SCORE;
+            }
+        }
+    }
+    score + column_score.min(MAX_COLUMN_SCORE)
+}
+
+// 按相关性选出前 top_k 张表，得分相同时保持原顺序；没有任何表匹配时返回空列表
+pub fn select_relevant_tables(question: &str, tables: &[TableProfile], top_k: usize) -> Vec<String> {
+    let terms = question_terms(question);
+    let bigrams = cjk_bigrams(question);
+
+    let mut scored: Vec<(usize, f64)> = tables.iter()
+        .enumerate()
+        .map(|(idx, table)| (idx, score_table(&terms, &bigrams, table)))
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+
+    scored.into_iter()
+        .take(top_k)
+        .map(|(idx, _)| tables[idx].name.clone())
+        .collect()
+}
+
+// 交替合并两个排序结果（如关键词匹配与语义检索），去重后取前 top_k 个
+pub fn merge_ranked(first: &[String], second: &[String], top_k: usize) -> Vec<String> {
+    let mut merged: Vec<String> = Vec::new();
+    for idx in 0..first.len().max(second.len()) {
+        for name in [first.get(idx), second.get(idx)].into_iter().flatten() {
+            if merged.len() < top_k && !merged.contains(name) {
+                merged.push(name.clone());
+            }
+        }
+    }
+    merged
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, columns: &[(&str, Option<&str>)]) -> TableProfile {
+        TableProfile {
+            name: name.to_string(),
+            columns: columns.iter().map(|(c, comment)| (c.to_string(), comment.map(str::to_string))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_identifier_parts() {
+        assert_eq!(identifier_parts("order_items"), vec!["order", "item"]);
+        assert_eq!(identifier_parts("orderItems"), vec!["order", "item"]);
+        assert_eq!(identifier_parts("categories"), vec!["category"]);
+        assert_eq!(question_terms("Show all orders by the customers"), vec!["order", "customer"]);
+    }
+
+    #[test]
+    fn test_select_relevant_tables() {
+        let tables = vec![
+            profile("audit_log", &[("id", None), ("action", None)]),
+            profile("customers", &[("id", None), ("email", Some("邮箱"))]),
+            profile("orders", &[("id", None), ("customer_id", None), ("total_amount", Some("订单金额"))]),
+            profile("order_items", &[("order_id", None), ("product_id", None)]),
+            profile("products", &[("id", None), ("price", None)]),
+        ];
+
+        let selected = select_relevant_tables("total amount of orders per customer", &tables, 3);
+        assert_eq!(selected[0], "orders");
+        assert!(selected.contains(&"customers".to_string()));
+        assert!(!selected.contains(&"audit_log".to_string()));
+
+        // 中文问题通过列注释匹配
+        assert_eq!(select_relevant_tables("统计每个用户的订单金额", &tables, 3), vec!["orders"]);
+
+        // 没有匹配时返回空列表
+        assert!(select_relevant_tables("hello world", &tables, 3).is_empty());
+    }
+
+    #[test]
+    fn test_merge_ranked() {
+        let keyword = vec!["orders".to_string(), "customers".to_string()];
+        let semantic = vec!["orders".to_string(), "payments".to_string(), "refunds".to_string()];
+        assert_eq!(merge_ranked(&keyword, &semantic, 3), vec!["orders", "customers", "payments"]);
+        assert_eq!(merge_ranked(&[], &semantic, 8), semantic);
+    }
+
+    #[test]
+    fn test_build_profiles() {
+        let names = vec!["a".to_string(), "b".to_string()];
+        let catalog = vec![
+            ("b".to_string(), "x".to_string(), None),
+            ("a".to_string(), "y".to_string(), Some("注释".to_string())),
+        ];
+        let profiles = build_profiles(&names, catalog);
+        assert_eq!(profiles[0].name, "a");
+        assert_eq!(profiles[0].columns, vec![("y".to_string(), Some("注释".to_string()))]);
+        assert_eq!(profiles[1].columns.len(), 1);
+    }
+}
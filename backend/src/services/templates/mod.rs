@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use regex::Regex;
 use serde::{Serialize, Deserialize};
 
+use crate::models::TemplateType;
+
 // 模板错误类型
 #[derive(Debug)]
 pub enum TemplateError {
@@ -19,7 +22,28 @@ impl std::fmt::Display for TemplateError {
 
 impl std::error::Error for TemplateError {}
 
+lazy_static::lazy_static! {
+    // 模板变量占位符 {{name}}
+    static ref PLACEHOLDER_RE: Regex = Regex::new(r"\{\{([A-Za-z0-9_]+)\}\}").unwrap();
+}
 
+// 模板内容中的变量名（按首次出现顺序去重）
+pub fn placeholders(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    PLACEHOLDER_RE.captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+// 模板预览结果：缺少的变量保留原占位符
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplatePreview {
+    pub rendered: String,
+    pub placeholders: Vec<String>,
+    pub missing_variables: Vec<String>,   // 既未提供也没有默认值的变量
+    pub unused_variables: Vec<String>,    // 提供了但模板中没有引用的变量
+}
 
 // 提示词模板结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +52,8 @@ pub struct PromptTemplate {
     pub name: String,
     pub description: String,
     pub content: String,
+    #[serde(default)]
+    pub template_type: TemplateType,
     pub variables: Vec<String>,
     pub default_variables: HashMap<String, String>,
 }
@@ -63,6 +89,7 @@ impl TemplateManager {
             name: "默认SQL生成模板".to_string(),
             description: "用于从自然语言生成SQL查询的标准模板".to_string(),
             content: include_str!("sql_generation_default.txt").to_string(),
+            template_type: TemplateType::Generation,
             variables: vec!["database_type".to_string(), "database_schema".to_string()],
             default_variables: HashMap::from([
                 ("database_type".to_string(), "通用SQL".to_string()),
@@ -75,6 +102,7 @@ impl TemplateManager {
             name: "默认SQL解释模板".to_string(),
             description: "用于解释SQL查询含义的标准模板".to_string(),
             content: include_str!("sql_explain_default.txt").to_string(),
+            template_type: TemplateType::Explain,
             variables: vec!["database_type".to_string()],
             default_variables: HashMap::from([
                 ("database_type".to_string(), "通用SQL".to_string()),
@@ -87,6 +115,7 @@ impl TemplateManager {
             name: "默认SQL优化模板".to_string(),
             description: "用于优化SQL查询的标准模板".to_string(),
             content: include_str!("sql_optimize_default.txt").to_string(),
+            template_type: TemplateType::Optimize,
             variables: vec!["database_type".to_string()],
             default_variables: HashMap::from([
                 ("database_type".to_string(), "通用SQL".to_string()),
@@ -94,9 +123,9 @@ impl TemplateManager {
         });
         
         // 设置默认模板映射
-        self.default_templates.insert("sql_generation".to_string(), "sql_generation_default".to_string());
-        self.default_templates.insert("sql_explain".to_string(), "sql_explain_default".to_string());
-        self.default_templates.insert("sql_optimize".to_string(), "sql_optimize_default".to_string());
+        self.set_default_template(&TemplateType::Generation, "sql_generation_default");
+        self.set_default_template(&TemplateType::Explain, "sql_explain_default");
+        self.set_default_template(&TemplateType::Optimize, "sql_optimize_default");
     }
     
     // 添加模板
@@ -150,8 +179,19 @@ impl TemplateManager {
     }
     
     // 设置默认模板
-    pub fn set_default_template(&mut self, template_type: &str, template_id: &str) {
-        self.default_templates.insert(template_type.to_string(), template_id.to_string());
+    pub fn set_default_template(&mut self, template_type: &TemplateType, template_id: &str) {
+        self.default_templates.insert(template_type.as_str().to_string(), template_id.to_string());
+    }
+    
+    // 使用指定模板作为其类型的默认模板（用于按请求或连接覆盖模板，不检查重名）
+    pub fn use_template(&mut self, template: PromptTemplate) {
+        self.set_default_template(&template.template_type, &template.template_id);
+        self.templates.insert(template.template_id.clone(), template);
+    }
+    
+    // 模板是否为其类型的默认模板
+    pub fn is_default(&self, template: &PromptTemplate) -> bool {
+        self.default_templates.get(template.template_type.as_str()) == Some(&template.template_id)
     }
     
     // 获取模板
@@ -164,6 +204,11 @@ impl TemplateManager {
         self.templates.values().collect()
     }
     
+    // 获取指定类型的模板
+    pub fn get_templates_by_type(&self, template_type: &TemplateType) -> Vec<&PromptTemplate> {
+        self.templates.values().filter(|t| &t.template_type == template_type).collect()
+    }
+    
     // 获取默认模板
     #[allow(dead_code)]
     pub fn get_default_template(&self, template_type: &str) -> Option<&PromptTemplate> {
@@ -187,6 +232,19 @@ impl TemplateManager {
         self.render(&template.content, variables, &template.default_variables)
     }
     
+    // 预览模板：返回渲染结果以及缺少/未使用的变量，不因缺少变量而失败
+    pub fn preview_template(&self, template_id: &str, variables: &HashMap<String, String>) -> Result<TemplatePreview, TemplateError> {
+        let template = self.get_template(template_id).ok_or(TemplateError::NotFound)?;
+        let (rendered, missing_variables) = Self::substitute(&template.content, variables, &template.default_variables);
+        let placeholders = placeholders(&template.content);
+        let mut unused_variables: Vec<String> = variables.keys()
+            .filter(|name| !placeholders.contains(name))
+            .cloned()
+            .collect();
+        unused_variables.sort();
+        Ok(TemplatePreview { rendered, placeholders, missing_variables, unused_variables })
+    }
+    
     // 渲染默认模板
     pub fn render_default_template(&self, template_type: &str, variables: &HashMap<String, String>) -> Result<String, String> {
         // 获取默认模板ID
@@ -205,26 +263,31 @@ impl TemplateManager {
         self.render(&template.content, variables, &template.default_variables)
     }
     
+    // 替换内容中的变量（优先使用提供的值，其次默认值），返回结果和缺少的变量
+    fn substitute(content: &str, variables: &HashMap<String, String>, default_variables: &HashMap<String, String>) -> (String, Vec<String>) {
+        let mut missing = Vec::new();
+        let result = PLACEHOLDER_RE.replace_all(content, |caps: &regex::Captures| {
+            let name = &caps[1];
+            match variables.get(name).or_else(|| default_variables.get(name)) {
+                Some(value) => value.clone(),
+                None => {
+                    if !missing.iter().any(|m| m == name) {
+                        missing.push(name.to_string());
+                    }
+                    caps[0].to_string()
+                }
+            }
+        });
+        (result.into_owned(), missing)
+    }
+    
     // 渲染内容中的变量
     fn render(&self, content: &str, variables: &HashMap<String, String>, default_variables: &HashMap<String, String>) -> Result<String, String> {
-        let mut result = content.to_string();
-        
-        // 处理所有模板变量
-        for (var_name, default_value) in default_variables {
-            let placeholder = format!("{{{{{}}}}}", var_name);
-            
-            // 优先使用提供的变量值，如果没有则使用默认值
-            let value = variables.get(var_name).unwrap_or(default_value);
-            
-            result = result.replace(&placeholder, value);
-        }
+        let (result, missing) = Self::substitute(content, variables, default_variables);
         
         // 检查是否还有未替换的变量
-        if let Some(unreplaced) = result.find("{{") {
-            if let Some(end) = result[unreplaced..].find("}}") {
-                let var_name = &result[unreplaced + 2..unreplaced + end];
-                return Err(format!("缺少必要变量: {}", var_name));
-            }
+        if let Some(var_name) = missing.first() {
+            return Err(format!("缺少必要变量: {}", var_name));
         }
         
         Ok(result)
@@ -259,4 +322,70 @@ mod tests {
         assert!(rendered.is_ok());
         assert!(rendered.unwrap().contains("PostgreSQL"));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_preview_template() {
+        let mut manager = TemplateManager::new();
+        manager.add_template(PromptTemplate {
+            template_id: "preview_test".to_string(),
+            name: "预览测试".to_string(),
+            description: String::new(),
+            content: "{{database_type}} {{database_schema}} {{database_type}} {{question}}".to_string(),
+            template_type: TemplateType::Generation,
+            variables: vec!["database_type".to_string(), "database_schema".to_string(), "question".to_string()],
+            default_variables: HashMap::from([("database_type".to_string(), "MySQL".to_string())]),
+        }).unwrap();
+        
+        let variables = HashMap::from([
+            ("database_schema".to_string(), "users(id)".to_string()),
+            ("extra".to_string(), "x".to_string()),
+        ]);
+        let preview = manager.preview_template("preview_test", &variables).unwrap();
+        assert_eq!(preview.rendered, "MySQL users(id) MySQL {{question}}");
+        assert_eq!(preview.placeholders, vec!["database_type", "database_schema", "question"]);
+        assert_eq!(preview.missing_variables, vec!["question"]);
+        assert_eq!(preview.unused_variables, vec!["extra"]);
+        
+        // 渲染时提供的变量即使没有默认值也会被替换，缺少变量时报错
+        assert_eq!(
+            manager.render_template("preview_test", &variables).unwrap_err(),
+            "缺少必要变量: question"
+        );
+        assert!(matches!(manager.preview_template("missing", &variables), Err(TemplateError::NotFound)));
+    }
+    
+    #[test]
+    fn test_template_type() {
+        let mut manager = TemplateManager::new();
+        // 导入的模板ID不含类型前缀，类型以字段为准
+        manager.add_template(PromptTemplate {
+            template_id: "imported-oceanbase".to_string(),
+            name: "OceanBase优化".to_string(),
+            description: String::new(),
+            content: "{{database_type}}".to_string(),
+            template_type: TemplateType::Optimize,
+            variables: vec!["database_type".to_string()],
+            default_variables: HashMap::new(),
+        }).unwrap();
+        
+        let optimize: Vec<&str> = manager.get_templates_by_type(&TemplateType::Optimize).iter()
+            .map(|t| t.template_id.as_str())
+            .collect();
+        assert_eq!(optimize.len(), 2);
+        assert!(optimize.contains(&"imported-oceanbase"));
+        assert_eq!(manager.get_templates_by_type(&TemplateType::Explain).len(), 1);
+        
+        let template = manager.get_template("imported-oceanbase").unwrap().clone();
+        assert!(!manager.is_default(&template));
+        manager.set_default_template(&template.template_type, &template.template_id);
+        assert!(manager.is_default(&template));
+        assert_eq!(manager.get_default_template("sql_optimize").unwrap().template_id, "imported-oceanbase");
+        
+        // 覆盖模板与已有模板重名时也可使用
+        let mut overridden = template.clone();
+        overridden.template_type = TemplateType::Explain;
+        overridden.name = "默认SQL解释模板".to_string();
+        manager.use_template(overridden);
+        assert_eq!(manager.get_default_template("sql_explain").unwrap().template_id, "imported-oceanbase");
+    }
+}
@@ -0,0 +1,583 @@
+// MongoDB Shell 语法解析器
+// 将 db.collection.find({...}).sort({...}).limit(n) 等语句解析为结构化查询，
+// 参数支持Shell字面量（未加引号的键、单引号字符串、ObjectId()、ISODate()等）
+
+use mongodb::bson::{oid::ObjectId, Bson, DateTime, Document};
+
+// find 未指定limit时的默认返回条数
+pub const DEFAULT_LIMIT: i64 = 200;
+// 单次查询最多返回的文档数
+pub const MAX_LIMIT: i64 = 1500;
+
+// 禁止使用的操作符（可执行服务端JavaScript或写入其他集合）
+const DANGEROUS_OPERATORS: &[&str] = &["$where", "$function", "$accumulator", "$out", "$merge"];
+
+// 解析后的MongoDB查询
+#[derive(Debug, Clone, PartialEq)]
+pub struct MongoQuery {
+    pub collection: String,
+    pub operation: MongoOperation,
+}
+
+// 查询操作类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum MongoOperation {
+    Find {
+        filter: Document,
+        projection: Option<Document>,
+        sort: Option<Document>,
+        skip: Option<u64>,
+        limit: i64,
+    },
+    Count {
+        filter: Document,
+    },
+    Distinct {
+        field: String,
+        filter: Document,
+    },
+    Aggregate {
+        pipeline: Vec<Document>,
+    },
+}
+
+// ========== Shell字面量解析 ==========
+
+struct LiteralParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> LiteralParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(format!("位置 {} 处应为 '{}'，实际为 '{}'", self.pos, expected, c)),
+            None => Err(format!("语句意外结束，缺少 '{}'", expected)),
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '$' {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        self.input[start..self.pos].to_string()
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let quote = self.peek().ok_or("语句意外结束")?;
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let (_, escaped) = chars.next().ok_or("字符串转义不完整")?;
+                    value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        other => other,
+                    });
+                }
+                c if c == quote => {
+                    self.pos += i + 1;
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err("字符串缺少结束引号".to_string())
+    }
+
+    fn number(&mut self) -> Result<Bson, String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text = &self.input[start..self.pos];
+        if let Ok(i) = text.parse::<i64>() {
+            Ok(match i32::try_from(i) {
+                Ok(small) => Bson::Int32(small),
+                Err(_) => Bson::Int64(i),
+            })
+        } else {
+            text.parse::<f64>()
+                .map(Bson::Double)
+                .map_err(|_| format!("无效的数字: {}", text))
+        }
+    }
+
+    fn value(&mut self) -> Result<Bson, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.document().map(extended_json),
+            Some('[') => self.array().map(Bson::Array),
+            Some('"') | Some('\'') => self.string().map(Bson::String),
+            Some(c) if c.is_ascii_digit() || c == '-' || c == '.' => self.number(),
+            Some(_) => self.keyword_value(),
+            None => Err("语句意外结束，缺少值".to_string()),
+        }
+    }
+
+    // true/false/null 以及 ObjectId("...")、ISODate("...") 等构造函数
+    fn keyword_value(&mut self) -> Result<Bson, String> {
+        let mut name = self.identifier();
+        if name == "new" {
+            self.skip_whitespace();
+            name = self.identifier();
+        }
+        match name.as_str() {
+            "true" => return Ok(Bson::Boolean(true)),
+            "false" => return Ok(Bson::Boolean(false)),
+            "null" | "undefined" => return Ok(Bson::Null),
+            "" => return Err(format!("位置 {} 处存在无法识别的字符", self.pos)),
+            _ => {}
+        }
+
+        self.expect('(')?;
+        let arg = if self.eat(')') { None } else {
+            let value = self.value()?;
+            self.expect(')')?;
+            Some(value)
+        };
+
+        match (name.as_str(), arg) {
+            ("ObjectId", Some(Bson::String(hex))) => ObjectId::parse_str(&hex)
+                .map(Bson::ObjectId)
+                .map_err(|e| format!("无效的ObjectId '{}': {}", hex, e)),
+            ("ObjectId", None) => Ok(Bson::ObjectId(ObjectId::new())),
+            ("ISODate", Some(Bson::String(s))) | ("Date", Some(Bson::String(s))) => {
+                DateTime::parse_rfc3339_str(&s)
+                    .map(Bson::DateTime)
+                    .map_err(|e| format!("无效的日期 '{}': {}", s, e))
+            }
+            ("ISODate", None) | ("Date", None) => Ok(Bson::DateTime(DateTime::now())),
+            ("NumberLong", Some(v)) => as_i64(&v).map(Bson::Int64),
+            ("NumberInt", Some(v)) => as_i64(&v)
+                .and_then(|i| i32::try_from(i).map_err(|_| format!("NumberInt超出范围: {}", i)))
+                .map(Bson::Int32),
+            (other, _) => Err(format!("不支持的构造函数: {}()", other)),
+        }
+    }
+
+    fn document(&mut self) -> Result<Document, String> {
+        self.expect('{')?;
+        let mut doc = Document::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(doc);
+            }
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.string()?,
+                _ => {
+                    let start = self.pos;
+                    while let Some(c) = self.peek() {
+                        if c.is_alphanumeric() || matches!(c, '_' | '$' | '.') {
+                            self.pos += c.len_utf8();
+                        } else {
+                            break;
+                        }
+                    }
+                    if start == self.pos {
+                        return Err(format!("位置 {} 处应为字段名", self.pos));
+                    }
+                    self.input[start..self.pos].to_string()
+                }
+            };
+            self.expect(':')?;
+            let value = self.value()?;
+            doc.insert(key, value);
+            if !self.eat(',') {
+                self.expect('}')?;
+                return Ok(doc);
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Vec<Bson>, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            if self.eat(']') {
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(items);
+            }
+        }
+    }
+
+    // 解析调用参数列表，直到匹配的右括号（左括号已被消费）
+    fn arguments(&mut self) -> Result<Vec<Bson>, String> {
+        let mut args = Vec::new();
+        loop {
+            if self.eat(')') {
+                return Ok(args);
+            }
+            args.push(self.value()?);
+            if !self.eat(',') {
+                self.expect(')')?;
+                return Ok(args);
+            }
+        }
+    }
+}
+
+fn as_i64(value: &Bson) -> Result<i64, String> {
+    match value {
+        Bson::Int32(i) => Ok(*i as i64),
+        Bson::Int64(i) => Ok(*i),
+        Bson::Double(f) if f.fract() == 0.0 => Ok(*f as i64),
+        Bson::String(s) => s.parse::<i64>().map_err(|_| format!("无效的整数: {}", s)),
+        other => Err(format!("应为整数，实际为: {}", other)),
+    }
+}
+
+// 将扩展JSON形式的 {"$oid": "..."} / {"$date": "..."} 转换为对应的BSON类型
+fn extended_json(doc: Document) -> Bson {
+    if doc.len() == 1 {
+        if let Ok(hex) = doc.get_str("$oid") {
+            if let Ok(oid) = ObjectId::parse_str(hex) {
+                return Bson::ObjectId(oid);
+            }
+        }
+        if let Ok(date) = doc.get_str("$date") {
+            if let Ok(dt) = DateTime::parse_rfc3339_str(date) {
+                return Bson::DateTime(dt);
+            }
+        }
+    }
+    Bson::Document(doc)
+}
+
+// 解析单个Shell字面量文档，如 { name: 'a', _id: ObjectId("...") }
+pub fn parse_document(input: &str) -> Result<Document, String> {
+    let mut parser = LiteralParser::new(input);
+    let doc = parser.document()?;
+    parser.skip_whitespace();
+    if !parser.rest().is_empty() {
+        return Err(format!("文档后存在多余内容: {}", parser.rest()));
+    }
+    Ok(doc)
+}
+
+// ========== 安全检查 ==========
+
+fn check_bson(value: &Bson) -> Result<(), String> {
+    match value {
+        Bson::Document(doc) => check_dangerous_operators(doc),
+        Bson::Array(items) => items.iter().try_for_each(check_bson),
+        _ => Ok(()),
+    }
+}
+
+// 递归检查文档中是否包含危险操作符
+pub fn check_dangerous_operators(doc: &Document) -> Result<(), String> {
+    for (key, value) in doc {
+        if DANGEROUS_OPERATORS.contains(&key.as_str()) {
+            return Err(format!("出于安全考虑，不允许使用 {} 操作符", key));
+        }
+        check_bson(value)?;
+    }
+    Ok(())
+}
+
+// 将limit限制在 [1, MAX_LIMIT] 范围内，未指定时使用默认值
+pub fn clamp_limit(limit: Option<i64>) -> i64 {
+    match limit {
+        Some(0) | None => DEFAULT_LIMIT,
+        // 负数limit在Shell中表示单批返回，按绝对值处理
+        Some(n) => n.saturating_abs().min(MAX_LIMIT),
+    }
+}
+
+// 限制聚合管道的返回条数：已有的 $limit 阶段被限制在 MAX_LIMIT 以内，没有时追加默认limit
+fn clamp_pipeline(pipeline: &mut Vec<Document>) {
+    let mut has_limit = false;
+    for stage in pipeline.iter_mut() {
+        if let Some(limit) = stage.get("$limit").and_then(|v| as_i64(v).ok()) {
+            stage.insert("$limit", clamp_limit(Some(limit)));
+            has_limit = true;
+        }
+    }
+    if !has_limit {
+        let mut stage = Document::new();
+        stage.insert("$limit", DEFAULT_LIMIT);
+        pipeline.push(stage);
+    }
+}
+
+// ========== 语句解析 ==========
+
+fn doc_arg(args: &[Bson], index: usize, method: &str) -> Result<Option<Document>, String> {
+    match args.get(index) {
+        None | Some(Bson::Null) => Ok(None),
+        Some(Bson::Document(doc)) => Ok(Some(doc.clone())),
+        Some(other) => Err(format!("{}() 的第 {} 个参数应为对象，实际为: {}", method, index + 1, other)),
+    }
+}
+
+fn int_arg(args: &[Bson], method: &str) -> Result<i64, String> {
+    args.first()
+        .ok_or_else(|| format!("{}() 缺少参数", method))
+        .and_then(as_i64)
+}
+
+// 解析集合名：db.name 或 db.getCollection("name")
+fn parse_collection(parser: &mut LiteralParser) -> Result<String, String> {
+    let name = parser.identifier();
+    if name == "getCollection" {
+        parser.expect('(')?;
+        parser.skip_whitespace();
+        let collection = parser.string()?;
+        parser.expect(')')?;
+        return Ok(collection);
+    }
+    if name.is_empty() {
+        return Err("缺少集合名".to_string());
+    }
+    Ok(name)
+}
+
+// 解析MongoDB Shell查询语句
+// 支持：find/findOne/aggregate/count/countDocuments/distinct 以及链式的 sort/skip/limit/projection/count
+pub fn parse_shell_query(input: &str) -> Result<MongoQuery, String> {
+    let statement = input.trim().trim_end_matches(';').trim();
+    if statement.is_empty() {
+        return Err("查询语句为空".to_string());
+    }
+
+    // 仅包含集合名时视为查询全部文档
+    if !statement.starts_with("db.") {
+        if statement.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            return Ok(MongoQuery {
+                collection: statement.to_string(),
+                operation: MongoOperation::Find {
+                    filter: Document::new(),
+                    projection: None,
+                    sort: None,
+                    skip: None,
+                    limit: DEFAULT_LIMIT,
+                },
+            });
+        }
+        return Err("MongoDB语句应以 db. 开头，如 db.users.find({})".to_string());
+    }
+
+    let mut parser = LiteralParser::new(statement);
+    parser.pos = 3;
+    let collection = parse_collection(&mut parser)?;
+
+    let mut operation: Option<MongoOperation> = None;
+    let mut limit: Option<i64> = None;
+
+    while parser.eat('.') {
+        let method = parser.identifier();
+        parser.expect('(')?;
+        let args = parser.arguments()?;
+
+        operation = Some(match (method.as_str(), operation) {
+            ("find", None) | ("findOne", None) => MongoOperation::Find {
+                filter: doc_arg(&args, 0, &method)?.unwrap_or_default(),
+                projection: doc_arg(&args, 1, &method)?,
+                sort: None,
+                skip: None,
+                limit: if method == "findOne" { 1 } else { DEFAULT_LIMIT },
+            },
+            ("aggregate", None) => {
+                let pipeline = match args.first() {
+                    Some(Bson::Array(stages)) => stages.iter()
+                        .map(|stage| match stage {
+                            Bson::Document(doc) => Ok(doc.clone()),
+                            other => Err(format!("聚合管道阶段应为对象，实际为: {}", other)),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => Vec::new(),
+                    Some(other) => return Err(format!("aggregate() 参数应为数组，实际为: {}", other)),
+                };
+                MongoOperation::Aggregate { pipeline }
+            }
+            ("count", None) | ("countDocuments", None) => MongoOperation::Count {
+                filter: doc_arg(&args, 0, &method)?.unwrap_or_default(),
+            },
+            ("distinct", None) => {
+                let field = match args.first() {
+                    Some(Bson::String(field)) => field.clone(),
+                    _ => return Err("distinct() 的第一个参数应为字段名字符串".to_string()),
+                };
+                MongoOperation::Distinct {
+                    field,
+                    filter: doc_arg(&args, 1, &method)?.unwrap_or_default(),
+                }
+            }
+            // find 之后的链式调用
+            ("sort", Some(MongoOperation::Find { filter, projection, skip, limit: l, .. })) => MongoOperation::Find {
+                sort: doc_arg(&args, 0, &method)?,
+                filter, projection, skip, limit: l,
+            },
+            ("skip", Some(MongoOperation::Find { filter, projection, sort, limit: l, .. })) => {
+                let skip = int_arg(&args, &method)?;
+                if skip < 0 {
+                    return Err("skip() 不能为负数".to_string());
+                }
+                MongoOperation::Find { skip: Some(skip as u64), filter, projection, sort, limit: l }
+            }
+            ("limit", Some(op @ MongoOperation::Find { .. })) => {
+                limit = Some(int_arg(&args, &method)?);
+                op
+            }
+            ("projection", Some(MongoOperation::Find { filter, sort, skip, limit: l, .. }))
+            | ("project", Some(MongoOperation::Find { filter, sort, skip, limit: l, .. })) => MongoOperation::Find {
+                projection: doc_arg(&args, 0, &method)?,
+                filter, sort, skip, limit: l,
+            },
+            ("count", Some(MongoOperation::Find { filter, .. })) => MongoOperation::Count { filter },
+            // 不影响结果的Shell辅助方法
+            ("pretty", Some(op)) | ("toArray", Some(op)) => op,
+            (method, None) => return Err(format!("不支持的查询方法: {}()", method)),
+            (method, Some(_)) => return Err(format!("不支持的链式调用: .{}()", method)),
+        });
+    }
+
+    parser.skip_whitespace();
+    if !parser.rest().is_empty() {
+        return Err(format!("无法解析的内容: {}", parser.rest()));
+    }
+
+    let mut operation = operation.ok_or("缺少查询方法，如 .find({})")?;
+
+    // 安全检查与limit限制
+    match &mut operation {
+        MongoOperation::Find { filter, projection, sort, limit: l, .. } => {
+            check_dangerous_operators(filter)?;
+            if let Some(projection) = projection {
+                check_dangerous_operators(projection)?;
+            }
+            if let Some(sort) = sort {
+                check_dangerous_operators(sort)?;
+            }
+            if let Some(limit) = limit {
+                *l = clamp_limit(Some(limit));
+            }
+        }
+        MongoOperation::Count { filter } | MongoOperation::Distinct { filter, .. } => {
+            check_dangerous_operators(filter)?;
+        }
+        MongoOperation::Aggregate { pipeline } => {
+            pipeline.iter().try_for_each(check_dangerous_operators)?;
+            clamp_pipeline(pipeline);
+        }
+    }
+
+    Ok(MongoQuery { collection, operation })
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn test_parse_find_chain() {
+        let query = parse_shell_query(
+            "db.users.find({ age: { $gt: 18 }, name: 'tom' }, { name: 1 }).sort({ age: -1 }).skip(10).limit(5000);"
+        ).unwrap();
+        assert_eq!(query.collection, "users");
+        assert_eq!(query.operation, MongoOperation::Find {
+            filter: doc! { "age": { "$gt": 18 }, "name": "tom" },
+            projection: Some(doc! { "name": 1 }),
+            sort: Some(doc! { "age": -1 }),
+            skip: Some(10),
+            limit: MAX_LIMIT,
+        });
+    }
+
+    #[test]
+    fn test_parse_get_collection_and_count() {
+        let query = parse_shell_query(r#"db.getCollection("order-items").find({status: "paid"}).count()"#).unwrap();
+        assert_eq!(query.collection, "order-items");
+        assert_eq!(query.operation, MongoOperation::Count { filter: doc! { "status": "paid" } });
+    }
+
+    #[test]
+    fn test_parse_aggregate_appends_limit() {
+        let query = parse_shell_query("db.orders.aggregate([{ $group: { _id: '$status', n: { $sum: 1 } } }])").unwrap();
+        match query.operation {
+            MongoOperation::Aggregate { pipeline } => {
+                assert_eq!(pipeline.len(), 2);
+                assert_eq!(pipeline[1], doc! { "$limit": DEFAULT_LIMIT });
+            }
+            other => panic!("unexpected operation: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_object_id_and_distinct() {
+        let query = parse_shell_query(
+            r#"db.users.distinct("city", { _id: ObjectId("507f1f77bcf86cd799439011") })"#
+        ).unwrap();
+        match query.operation {
+            MongoOperation::Distinct { field, filter } => {
+                assert_eq!(field, "city");
+                assert!(matches!(filter.get("_id"), Some(Bson::ObjectId(_))));
+            }
+            other => panic!("unexpected operation: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rejects_dangerous_operators() {
+        assert!(parse_shell_query("db.users.find({ $where: 'this.a > 1' })").is_err());
+        assert!(parse_shell_query("db.users.aggregate([{ $match: {} }, { $out: 'copy' }])").is_err());
+    }
+}
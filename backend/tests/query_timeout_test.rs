@@ -13,11 +13,11 @@ async fn test_query_timeout_model() {
     let request: SqlQueryRequest = serde_json::from_value(json_without_timeout)
         .expect("应该能够反序列化不包含timeout_secs字段的请求");
     
-    assert_eq!(request.timeout_secs, 30, "默认超时时间应该是30秒");
+    assert_eq!(request.timeout_secs, None, "未指定超时时使用连接设置的超时（默认30秒）");
     assert_eq!(request.sql, "SELECT * FROM test");
     assert!(request.parameters.is_none());
     
-    println!("✓ 默认超时时间验证通过：30秒");
+    println!("✓ 默认超时验证通过：未指定");
 }
 
 #[tokio::test]
@@ -31,7 +31,7 @@ async fn test_query_timeout_custom_value() {
     let request: SqlQueryRequest = serde_json::from_value(json_with_timeout)
         .expect("应该能够反序列化包含timeout_secs字段的请求");
     
-    assert_eq!(request.timeout_secs, 60, "自定义超时时间应该是60秒");
+    assert_eq!(request.timeout_secs, Some(60), "自定义超时时间应该是60秒");
     assert_eq!(request.sql, "SELECT * FROM large_table");
     
     println!("✓ 自定义超时时间验证通过：60秒");
@@ -48,7 +48,7 @@ async fn test_query_timeout_zero_value() {
     let request: SqlQueryRequest = serde_json::from_value(json_zero_timeout)
         .expect("应该能够反序列化timeout_secs为0的请求");
     
-    assert_eq!(request.timeout_secs, 0, "超时时间可以设置为0");
+    assert_eq!(request.timeout_secs, Some(0), "超时时间可以设置为0");
     
     println!("✓ 超时时间0值验证通过（注意：实际使用中0秒超时会立即失败）");
 }
@@ -64,7 +64,7 @@ async fn test_query_timeout_large_value() {
     let request: SqlQueryRequest = serde_json::from_value(json_large_timeout)
         .expect("应该能够反序列化大超时值的请求");
     
-    assert_eq!(request.timeout_secs, 3600, "超时时间应该支持3600秒（1小时）");
+    assert_eq!(request.timeout_secs, Some(3600), "超时时间应该支持3600秒（1小时）");
     
     println!("✓ 大超时值验证通过：3600秒（1小时）");
 }
@@ -81,7 +81,7 @@ async fn test_query_with_parameters_and_timeout() {
     let request: SqlQueryRequest = serde_json::from_value(json_full_request)
         .expect("应该能够反序列化完整的请求");
     
-    assert_eq!(request.timeout_secs, 45);
+    assert_eq!(request.timeout_secs, Some(45));
     assert_eq!(request.sql, "SELECT * FROM users WHERE id = ?");
     assert!(request.parameters.is_some());
     assert_eq!(request.parameters.as_ref().unwrap().len(), 2);
@@ -96,9 +96,9 @@ fn test_timeout_serialization() {
         sql: "SELECT * FROM test".to_string(),
         connection_id: None,
         parameters: None,
-        timeout_secs: 30,
-        page: None,
-        page_size: 100,
+        timeout_secs: Some(30),
+        page_size: Some(100),
+        ..Default::default()
     };
     
     let json = serde_json::to_value(&request).expect("应该能够序列化请求");
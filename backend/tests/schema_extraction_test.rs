@@ -235,3 +235,33 @@ async fn test_get_foreign_keys_multiple_foreign_keys() {
     assert!(fk_columns.contains(&"category_id"), "应该包含category_id外键");
 }
 
+
+#[tokio::test]
+async fn test_get_foreign_keys_implicit_primary_key() {
+    // 测试 REFERENCES 省略列名（引用主键）的外键
+    let pool = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+    
+    sqlx::query("CREATE TABLE authors (author_id INTEGER PRIMARY KEY, name TEXT)")
+        .execute(&pool)
+        .await
+        .expect("创建authors表失败");
+    
+    sqlx::query("CREATE TABLE books (id INTEGER PRIMARY KEY, author INTEGER REFERENCES authors)")
+        .execute(&pool)
+        .await
+        .expect("创建books表失败");
+    
+    let db_manager = DatabaseManager {
+        pool: DatabasePool::SQLite(pool),
+        db_type: DatabaseType::SQLite,
+    };
+    
+    let foreign_keys = db_manager.get_foreign_keys("books").await
+        .expect("获取外键失败");
+    
+    assert_eq!(foreign_keys.len(), 1, "books表应该有1个外键");
+    assert_eq!(foreign_keys[0].referenced_table, "authors");
+    assert_eq!(foreign_keys[0].referenced_column, "author_id", "省略列名时应解析为主键列");
+}
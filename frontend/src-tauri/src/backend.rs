@@ -0,0 +1,242 @@
+// 后端进程管理：分配端口、启动后端、等待健康检查通过、崩溃后重启、应用退出时终止
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 后端访问令牌环境变量（与后端 api::auth::AUTH_TOKEN_ENV 一致）
+const AUTH_TOKEN_ENV: &str = "SMART_SQL_AUTH_TOKEN";
+// 指定后端可执行文件路径的环境变量（未设置时按默认位置查找）
+const BACKEND_PATH_ENV: &str = "SMART_SQL_BACKEND_PATH";
+// 后端可执行文件名（不含扩展名）
+const BACKEND_BIN: &str = "smart-sql-backend";
+// 存活检查路径（后端不要求令牌）
+const HEALTH_PATH: &str = "/api/health";
+// 等待后端就绪的最长时间
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+// 检查后端进程状态的间隔
+const MONITOR_INTERVAL: Duration = Duration::from_millis(500);
+// 连续重启失败的次数上限，超过后不再重启
+const MAX_RESTARTS: u32 = 5;
+// 后端持续运行超过该时间后重置重启计数
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+// 后端进程监管器
+pub struct BackendSupervisor {
+    port: u16,
+    token: String,
+    executable: PathBuf,
+    child: Mutex<Option<Child>>,
+    shutting_down: AtomicBool,
+}
+
+impl BackendSupervisor {
+    // 分配空闲端口并启动后端，健康检查通过后开始监控进程
+    pub fn start(token: String) -> Result<Arc<Self>, String> {
+        let executable = find_backend_executable()?;
+        let port = free_port()?;
+        let supervisor = Arc::new(Self {
+            port,
+            token,
+            executable,
+            child: Mutex::new(None),
+            shutting_down: AtomicBool::new(false),
+        });
+        supervisor.spawn_and_wait()?;
+
+        let monitor = Arc::clone(&supervisor);
+        thread::spawn(move || monitor.monitor());
+        Ok(supervisor)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    // 终止后端进程（应用退出时调用），之后不再重启
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            println!("Stopping backend service (pid {})", child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    // 启动后端进程并等待健康检查通过
+    fn spawn_and_wait(&self) -> Result<(), String> {
+        let mut child = Command::new(&self.executable)
+            .env("HOST", Ipv4Addr::LOCALHOST.to_string())
+            .env("PORT", self.port.to_string())
+            .env(AUTH_TOKEN_ENV, &self.token)
+            .spawn()
+            .map_err(|e| format!("Failed to start backend {}: {}", self.executable.display(), e))?;
+        println!("Backend service started (pid {}) on port {}", child.id(), self.port);
+
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("Backend service exited during startup: {}", status));
+            }
+            if health_check(self.port) {
+                break;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Backend service did not become healthy within {}s", STARTUP_TIMEOUT.as_secs()));
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+        println!("Backend service is healthy");
+
+        if self.shutting_down.load(Ordering::SeqCst) {
+            let _ = child.kill();
+            let _ = child.wait();
+        } else {
+            *self.child.lock().unwrap() = Some(child);
+        }
+        Ok(())
+    }
+
+    // 监控后端进程，意外退出时按退避间隔在同一端口重启
+    fn monitor(&self) {
+        let mut failures = 0u32;
+        let mut started_at = Instant::now();
+        loop {
+            thread::sleep(MONITOR_INTERVAL);
+            if self.shutting_down.load(Ordering::SeqCst) {
+                return;
+            }
+            let exited = match self.child.lock().unwrap().as_mut() {
+                Some(child) => child.try_wait().ok().flatten().map(|status| status.to_string()),
+                None => Some("not running".to_string()),
+            };
+            let Some(status) = exited else {
+                continue;
+            };
+            if self.shutting_down.load(Ordering::SeqCst) {
+                return;
+            }
+            self.child.lock().unwrap().take();
+            println!("Backend service exited unexpectedly: {}", status);
+
+            if started_at.elapsed() >= STABLE_AFTER {
+                failures = 0;
+            }
+            loop {
+                if failures >= MAX_RESTARTS {
+                    println!("Backend service failed {} times in a row, giving up", failures);
+                    return;
+                }
+                // 退避：1s、2s、4s...
+                thread::sleep(Duration::from_secs(1 << failures));
+                failures += 1;
+                if self.shutting_down.load(Ordering::SeqCst) {
+                    return;
+                }
+                match self.spawn_and_wait() {
+                    Ok(()) => {
+                        started_at = Instant::now();
+                        break;
+                    }
+                    Err(e) => println!("Failed to restart backend service: {}", e),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for BackendSupervisor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// 由系统分配一个空闲端口
+fn free_port() -> Result<u16, String> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to allocate a free port: {}", e))
+}
+
+// 请求后端存活检查，返回 200 时视为就绪
+fn health_check(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(500)) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    let request = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n", HEALTH_PATH, port);
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut status_line = [0u8; 12];
+    stream.read_exact(&mut status_line).is_ok() && status_line.ends_with(b" 200")
+}
+
+// 查找后端可执行文件：环境变量 > 与应用同目录 > 开发目录下的构建产物
+fn find_backend_executable() -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var(BACKEND_PATH_ENV) {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(format!("{} points to a missing file: {}", BACKEND_PATH_ENV, path.display()))
+        };
+    }
+
+    let file_name = format!("{}{}", BACKEND_BIN, std::env::consts::EXE_SUFFIX);
+    let mut candidates = Vec::new();
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+        candidates.push(dir.join(&file_name));
+    }
+    let backend_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../backend");
+    for profile in ["release", "debug"] {
+        candidates.push(backend_dir.join("target").join(profile).join(&file_name));
+    }
+    if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+        return Ok(path.clone());
+    }
+
+    // 开发环境下找不到时尝试构建后端
+    if cfg!(debug_assertions) && build_backend(&backend_dir) {
+        let built = backend_dir.join("target/release").join(&file_name);
+        if built.is_file() {
+            return Ok(built);
+        }
+    }
+    Err(format!("Backend executable {} not found, set {} to its path", file_name, BACKEND_PATH_ENV))
+}
+
+// 构建后端服务
+fn build_backend(backend_dir: &Path) -> bool {
+    println!("Building backend service...");
+    match Command::new("cargo")
+        .current_dir(backend_dir)
+        .args(["build", "--release"])
+        .status() {
+        Ok(status) if status.success() => {
+            println!("Backend service built successfully");
+            true
+        },
+        Ok(status) => {
+            println!("Failed to build backend service, exit code: {}", status);
+            false
+        },
+        Err(e) => {
+            println!("Failed to execute cargo build: {}", e);
+            false
+        }
+    }
+}
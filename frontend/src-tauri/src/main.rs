@@ -1,61 +1,93 @@
 // Tauri应用主入口
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::Command;
-use std::thread;
-use std::time::Duration;
-
-// 启动后端服务
-fn start_backend() {
-    // 构建后端服务路径
-    let backend_path = "../../backend/target/release/smart-sql-backend.exe";
-    
-    // 尝试启动后端服务
-    match Command::new(backend_path)
-        .spawn() {
-        Ok(_child) => {
-            println!("Backend service started successfully");
-        },
-        Err(e) => {
-            println!("Failed to start backend service: {}", e);
-            // 如果无法启动后端服务，尝试构建后端服务
-            build_backend();
-        }
-    };
+#[cfg(not(feature = "embedded-backend"))]
+mod backend;
+
+use tauri::{Manager, RunEvent};
+
+// 本次运行的后端端口和访问令牌
+struct BackendInfo {
+    port: u16,
+    token: String,
+}
+
+// 前端获取访问令牌，请求时放在 Authorization: Bearer 头中
+#[tauri::command]
+fn get_api_token(backend: tauri::State<'_, BackendInfo>) -> String {
+    backend.token.clone()
+}
+
+// 前端获取后端端口（每次启动随机分配）
+#[tauri::command]
+fn get_api_port(backend: tauri::State<'_, BackendInfo>) -> u16 {
+    backend.port
 }
 
-// 构建后端服务
-fn build_backend() {
-    println!("Building backend service...");
+// 作为子进程启动后端，健康检查通过后再打开窗口，退出时终止子进程
+#[cfg(not(feature = "embedded-backend"))]
+fn main() {
+    use std::sync::Arc;
+    use backend::BackendSupervisor;
+
+    // 每次启动生成新的访问令牌，只有本应用的前端能访问后端
+    let token = uuid::Uuid::new_v4().simple().to_string();
     
-    // 切换到backend目录并构建后端服务
-    match Command::new("cargo")
-        .current_dir("../../backend")
-        .args(["build", "--release"])
-        .status() {
-        Ok(status) if status.success() => {
-            println!("Backend service built successfully");
-            // 构建成功后，再次尝试启动后端服务
-            start_backend();
-        },
-        Ok(status) => {
-            println!("Failed to build backend service, exit code: {}", status);
-        },
+    // 启动后端服务并等待健康检查通过
+    let backend = match BackendSupervisor::start(token) {
+        Ok(backend) => backend,
         Err(e) => {
-            println!("Failed to execute cargo build: {}", e);
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
     };
+    
+    // 启动Tauri应用，退出时终止后端进程
+    tauri::Builder::default()
+        .manage(BackendInfo { port: backend.port(), token: backend.token().to_string() })
+        .manage(backend)
+        .invoke_handler(tauri::generate_handler![get_api_token, get_api_port])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                app.state::<Arc<BackendSupervisor>>().shutdown();
+            }
+        });
 }
 
+// 在应用进程内运行后端，本地存储放在应用数据目录下，退出时停止服务
+#[cfg(feature = "embedded-backend")]
 fn main() {
-    // 启动后端服务
-    start_backend();
-    
-    // 等待后端服务启动
-    thread::sleep(Duration::from_secs(2));
-    
-    // 启动Tauri应用
+    use std::sync::Mutex;
+    use smart_sql_backend::server::{run_server, ServerConfig, ServerHandle};
+
     tauri::Builder::default()
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
\ No newline at end of file
+        .setup(|app| {
+            let config = ServerConfig {
+                port: 0,
+                local_storage_path: app.path().app_data_dir()?.join("smart_sql.db"),
+                auth_token: Some(uuid::Uuid::new_v4().simple().to_string()),
+                ..Default::default()
+            };
+            let token = config.auth_token.clone().unwrap_or_default();
+            let server = tauri::async_runtime::block_on(run_server(config))
+                .map_err(|e| e.to_string())?;
+            app.manage(BackendInfo { port: server.port(), token });
+            app.manage(Mutex::new(Some(server)));
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![get_api_token, get_api_port])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                let server = app.state::<Mutex<Option<ServerHandle>>>().lock().unwrap().take();
+                if let Some(server) = server {
+                    if let Err(e) = tauri::async_runtime::block_on(server.shutdown()) {
+                        eprintln!("Failed to stop embedded backend: {}", e);
+                    }
+                }
+            }
+        });
+}
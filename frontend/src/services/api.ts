@@ -1,9 +1,20 @@
 import type {
   HealthResponse,
+  DetailedHealthResponse,
   DatabaseInfoResponse,
   ErrorResponse,
   SqlQueryRequest,
   SqlQueryResult,
+  QuerySession,
+  DatabaseObject,
+  DatabaseObjectKind,
+  ViewSource,
+  DatabaseUser,
+  PrivilegeGrant,
+  ServerProcess,
+  MonitorSnapshot,
+  DdlResponse,
+  TriggerDefinition,
   SqlGenerationRequest,
   SqlGenerationResult,
   DatabaseConnection,
@@ -24,12 +35,21 @@ import type {
 // API基础URL
 let API_BASE_URL = "/api";
 
+// 后端访问令牌（桌面应用启动后端时生成，浏览器开发模式下为空）
+let apiToken: string | null = null;
+
+// 后端返回 401 时派发的事件，界面据此提示用户重新启动应用
+export const UNAUTHORIZED_EVENT = 'api-unauthorized';
+
 // 尝试导入Tauri API，检测是否在Tauri环境中
 async function detectTauriEnvironment() {
   try {
-    if (typeof window !== "undefined" && (window as any).__TAURI__) {
-      // 在Tauri环境中，使用完整的后端URL
-      API_BASE_URL = "http://127.0.0.1:8080/api";
+    if (typeof window !== "undefined" && ((window as any).__TAURI__ || (window as any).__TAURI_INTERNALS__)) {
+      // 在Tauri环境中，使用完整的后端URL（端口由桌面应用启动后端时分配）
+      const { invoke } = await import('@tauri-apps/api/core');
+      const port = await invoke<number>('get_api_port');
+      API_BASE_URL = `http://127.0.0.1:${port}/api`;
+      apiToken = await invoke<string>('get_api_token');
     }
   } catch (error) {
     console.log("Not running in Tauri environment:", error);
@@ -37,13 +57,20 @@ async function detectTauriEnvironment() {
 }
 
 // 初始化环境检测
-detectTauriEnvironment();
+const environmentReady = detectTauriEnvironment();
+
+// 访问后端所需的认证请求头（供直接使用 fetch / WebSocket 的模块复用）
+export async function getAuthHeaders(): Promise<Record<string, string>> {
+  await environmentReady;
+  return apiToken ? { Authorization: `Bearer ${apiToken}` } : {};
+}
 
 // 通用fetch函数封装
 async function fetchApi<T>(
   endpoint: string,
   options: RequestInit = {}
 ): Promise<T> {
+  const authHeaders = await getAuthHeaders();
   const url = endpoint.startsWith('http') ? endpoint : `${API_BASE_URL}${endpoint}`;
   
   try {
@@ -51,6 +78,7 @@ async function fetchApi<T>(
       ...options,
       headers: {
         'Content-Type': 'application/json',
+        ...authHeaders,
         ...options.headers,
       },
     });
@@ -62,6 +90,10 @@ async function fetchApi<T>(
 
     const data = await response.json();
 
+    if (response.status === 401) {
+      window.dispatchEvent(new CustomEvent(UNAUTHORIZED_EVENT, { detail: data }));
+    }
+
     if (!response.ok) {
       throw new Error((data as ErrorResponse).message || 'API请求失败');
     }
@@ -82,6 +114,11 @@ export async function healthCheck(): Promise<HealthResponse> {
   return fetchApi<HealthResponse>('/health');
 }
 
+// 详细健康检查（版本、本地存储、连接池、AI配置、运行时长）
+export async function detailedHealthCheck(): Promise<DetailedHealthResponse> {
+  return fetchApi<DetailedHealthResponse>('/health/detailed');
+}
+
 // 获取数据库信息
 export async function getDatabaseInfo(connectionId?: number): Promise<DatabaseInfoResponse> {
   const url = connectionId ? `/database/info?connection_id=${connectionId}` : '/database/info';
@@ -144,6 +181,184 @@ export async function cancelQuery(
   });
 }
 
+// 获取存储过程、函数、触发器和视图列表
+export async function listDatabaseObjects(
+  connectionId?: number,
+  options: { kind?: DatabaseObjectKind; schema?: string; database?: string } = {}
+): Promise<{ connection_id?: number; objects: DatabaseObject[]; count: number }> {
+  const params = new URLSearchParams();
+  if (connectionId !== undefined) params.set('connection_id', String(connectionId));
+  if (options.kind) params.set('kind', options.kind);
+  if (options.schema) params.set('schema', options.schema);
+  if (options.database) params.set('database', options.database);
+  const query = params.toString();
+  return fetchApi<{ connection_id?: number; objects: DatabaseObject[]; count: number }>(`/database/objects${query ? `?${query}` : ''}`);
+}
+
+// 获取用户 / 角色及其在当前数据库上的权限（只读）
+export async function listPrivileges(
+  connectionId?: number,
+  database?: string
+): Promise<{ connection_id?: number; database?: string; users: DatabaseUser[]; grants: PrivilegeGrant[] }> {
+  const params = new URLSearchParams();
+  if (connectionId !== undefined) params.set('connection_id', String(connectionId));
+  if (database) params.set('database', database);
+  const query = params.toString();
+  return fetchApi<{ connection_id?: number; database?: string; users: DatabaseUser[]; grants: PrivilegeGrant[] }>(
+    `/database/privileges${query ? `?${query}` : ''}`
+  );
+}
+
+// 获取服务端会话列表（SHOW PROCESSLIST / pg_stat_activity）
+export async function listServerProcesses(
+  connectionId?: number
+): Promise<{ connection_id?: number; processes: ServerProcess[]; count: number }> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<{ connection_id?: number; processes: ServerProcess[]; count: number }>(`/database/processes${query}`);
+}
+
+// 终止服务端会话（query：只取消正在执行的语句；connection：断开会话）
+export async function killServerProcess(
+  id: number,
+  options: { connectionId?: number; mode?: 'query' | 'connection'; confirmProduction?: boolean } = {}
+): Promise<{ success: boolean; statement: string; message: string }> {
+  const params = new URLSearchParams({ mode: options.mode ?? 'query' });
+  if (options.connectionId !== undefined) params.set('connection_id', String(options.connectionId));
+  if (options.confirmProduction) params.set('confirm_production', 'true');
+  return fetchApi<{ success: boolean; statement: string; message: string }>(`/database/processes/${id}?${params}`, {
+    method: 'DELETE',
+  });
+}
+
+// 采集数据库监控指标（history=true 时同时返回近期采样，用于绘制曲线）
+export async function getDatabaseMonitor(
+  connectionId?: number,
+  options: { history?: boolean; limit?: number } = {}
+): Promise<{ connection_id?: number; database_type: string; snapshot: MonitorSnapshot; history: MonitorSnapshot[] }> {
+  const params = new URLSearchParams();
+  if (connectionId !== undefined) params.set('connection_id', String(connectionId));
+  if (options.history) params.set('history', 'true');
+  if (options.limit !== undefined) params.set('limit', String(options.limit));
+  const query = params.toString();
+  return fetchApi<{ connection_id?: number; database_type: string; snapshot: MonitorSnapshot; history: MonitorSnapshot[] }>(
+    `/database/monitor${query ? `?${query}` : ''}`
+  );
+}
+
+// 获取视图定义
+export async function getViewDefinition(view: string, connectionId?: number): Promise<ViewSource> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<ViewSource>(`/database/view/${encodeURIComponent(view)}${query}`);
+}
+
+// 创建视图（SQL为单条 SELECT 语句）
+export async function createView(
+  request: { view_name: string; query: string; columns?: string[] },
+  connectionId?: number
+): Promise<DdlResponse> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<DdlResponse>(`/database/view${query}`, {
+    method: 'POST',
+    body: JSON.stringify(request),
+  });
+}
+
+// 修改视图
+export async function alterView(
+  view: string,
+  request: { query: string; columns?: string[] },
+  connectionId?: number
+): Promise<DdlResponse> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<DdlResponse>(`/database/view/${encodeURIComponent(view)}${query}`, {
+    method: 'PUT',
+    body: JSON.stringify(request),
+  });
+}
+
+// 删除视图
+export async function dropView(view: string, connectionId?: number, ifExists = false): Promise<DdlResponse> {
+  const params = new URLSearchParams({ if_exists: String(ifExists) });
+  if (connectionId !== undefined) params.set('connection_id', String(connectionId));
+  return fetchApi<DdlResponse>(`/database/view/${encodeURIComponent(view)}?${params}`, {
+    method: 'DELETE',
+  });
+}
+
+// 获取表的触发器列表
+export async function listTableTriggers(table: string, connectionId?: number): Promise<DatabaseObject[]> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<DatabaseObject[]>(`/database/table/${encodeURIComponent(table)}/triggers${query}`);
+}
+
+// 获取触发器定义
+export async function getTriggerDdl(
+  table: string,
+  trigger: string,
+  connectionId?: number
+): Promise<{ table: string; trigger: string; ddl: string }> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<{ table: string; trigger: string; ddl: string }>(
+    `/database/table/${encodeURIComponent(table)}/triggers/${encodeURIComponent(trigger)}${query}`
+  );
+}
+
+// 创建触发器
+export async function createTrigger(
+  table: string,
+  trigger: TriggerDefinition,
+  connectionId?: number
+): Promise<DdlResponse> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<DdlResponse>(`/database/table/${encodeURIComponent(table)}/triggers${query}`, {
+    method: 'POST',
+    body: JSON.stringify(trigger),
+  });
+}
+
+// 删除触发器
+export async function dropTrigger(
+  table: string,
+  trigger: string,
+  connectionId?: number,
+  ifExists = false
+): Promise<DdlResponse> {
+  const params = new URLSearchParams({ if_exists: String(ifExists) });
+  if (connectionId !== undefined) params.set('connection_id', String(connectionId));
+  return fetchApi<DdlResponse>(
+    `/database/table/${encodeURIComponent(table)}/triggers/${encodeURIComponent(trigger)}?${params}`,
+    { method: 'DELETE' }
+  );
+}
+
+// 打开查询会话（专用连接，闲置超时后自动关闭）
+export async function openQuerySession(
+  connectionId?: number,
+  database?: string
+): Promise<QuerySession> {
+  return fetchApi<QuerySession>('/database/sessions', {
+    method: 'POST',
+    body: JSON.stringify({ connection_id: connectionId, database }),
+  });
+}
+
+// 获取查询会话列表
+export async function listQuerySessions(
+  connectionId?: number
+): Promise<{ success: boolean; data: QuerySession[]; count: number }> {
+  const query = connectionId !== undefined ? `?connection_id=${connectionId}` : '';
+  return fetchApi<{ success: boolean; data: QuerySession[]; count: number }>(`/database/sessions${query}`);
+}
+
+// 关闭查询会话
+export async function closeQuerySession(
+  sessionId: string
+): Promise<{ success: boolean; message: string }> {
+  return fetchApi<{ success: boolean; message: string }>(`/database/sessions/${sessionId}`, {
+    method: 'DELETE',
+  });
+}
+
 // 生成SQL
 export async function generateSql(
   request: SqlGenerationRequest
@@ -176,6 +391,30 @@ export async function explainSql(
   });
 }
 
+// 诊断执行失败的SQL：返回错误原因和修正后的SQL
+export async function diagnoseSqlError(
+  sql: string,
+  error: string,
+  connectionId?: number,
+  database?: string
+): Promise<{ explanation: string; fixed_sql?: string; validation?: any; tables: string[] }> {
+  return fetchApi<{ explanation: string; fixed_sql?: string; validation?: any; tables: string[] }>("/ai/diagnose", {
+    method: "POST",
+    body: JSON.stringify({ sql, error, connection_id: connectionId, database }),
+  });
+}
+
+// 根据实体描述生成建表DDL（含索引和注释，经语法校验）
+export async function generateTableSchema(
+  description: string,
+  options: { dialect?: 'mysql' | 'postgresql' | 'sqlite'; connectionId?: number } = {}
+): Promise<{ dialect: string; ddl: string; statements: string[]; tables: string[]; attempts: number }> {
+  return fetchApi<{ dialect: string; ddl: string; statements: string[]; tables: string[]; attempts: number }>("/ai/schema/generate", {
+    method: "POST",
+    body: JSON.stringify({ description, dialect: options.dialect, connection_id: options.connectionId }),
+  });
+}
+
 // ==================== 连接管理 API ====================
 
 // 获取所有连接
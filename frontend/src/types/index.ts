@@ -72,10 +72,24 @@ export interface HealthResponse {
   message: string;
 }
 
+// 详细健康检查响应
+export interface DetailedHealthResponse {
+  status: 'ok' | 'degraded';
+  version: string;
+  uptime_secs: number;
+  local_storage: {
+    ok: boolean;
+    error?: string;
+  };
+  cached_pools: number;
+  ai_configured: boolean;
+}
+
 // 数据库信息响应
 export interface DatabaseInfoResponse {
   database_type: string;
   tables: string[];
+  views?: string[]; // 视图与表分开列出
 }
 
 // 错误响应
@@ -84,6 +98,14 @@ export interface ErrorResponse {
   message: string;
 }
 
+// 连接级查询默认值
+export interface ConnectionOptions {
+  max_rows?: number; // 查询默认最大行数
+  timeout_secs?: number; // 未指定超时的查询使用的超时时间（秒）
+  read_only?: boolean; // 只读连接，禁止写操作
+  init_sql?: string[]; // 新建会话时执行的语句，如 SET time_zone = '+08:00'
+}
+
 // 数据库连接配置
 export interface DatabaseConnection {
   id?: number;
@@ -98,6 +120,7 @@ export interface DatabaseConnection {
   connection_string?: string;
   is_active?: boolean;
   environment?: string; // 环境标签: development, testing, staging, production
+  options?: ConnectionOptions;
   created_at?: string;
   updated_at?: string;
 }
@@ -114,6 +137,7 @@ export interface ConnectionRequest {
   file_path?: string;
   connection_string?: string; // 手动输入的连接URL
   environment?: string; // 环境标签
+  options?: ConnectionOptions; // 连接级查询默认值
   // 高级配置选项（可选，用于扩展）
   timeout_seconds?: number; // 连接超时（秒）
   charset?: string; // 字符集
@@ -147,6 +171,106 @@ export interface SqlQueryRequest {
   connection_id?: number;
   database_id?: string;
   parameters?: unknown[];
+  page?: number;
+  page_size?: number;
+  count_total?: boolean;
+  // 游标分页：按排序列定位下一页（代替 OFFSET），cursor 取上一页的 next_cursor
+  keyset_columns?: string[];
+  keyset_descending?: boolean;
+  cursor?: string;
+  bypass_cache?: boolean;
+  collect_stats?: boolean; // 统计扫描行数（会增加数据库往返）
+  session_id?: string; // 在查询会话的专用连接上执行（会话变量、临时表在查询间保留）
+}
+
+// 数据库对象（存储过程、函数、触发器、视图）
+export type DatabaseObjectKind = "procedure" | "function" | "trigger" | "view";
+
+export interface DatabaseObject {
+  kind: DatabaseObjectKind;
+  schema?: string;
+  name: string;
+  table_name?: string; // 触发器所在的表
+  arguments?: string; // 参数签名
+  return_type?: string; // 函数返回类型
+  timing?: string; // 触发时机：BEFORE / AFTER / INSTEAD OF
+  event?: string; // 触发事件：INSERT / UPDATE / DELETE
+  definition?: string; // 源码 / 定义
+}
+
+// 数据库用户 / 角色
+export interface DatabaseUser {
+  name: string;
+  host?: string; // MySQL 账号的主机部分
+  superuser?: boolean;
+  can_login?: boolean; // PostgreSQL：角色是否可以登录
+  member_of: string[]; // PostgreSQL：所属的角色
+  grant_statements: string[]; // MySQL：与当前数据库相关的授权语句
+}
+
+// 权限授予记录
+export interface PrivilegeGrant {
+  grantee: string;
+  object_type: 'GLOBAL' | 'DATABASE' | 'TABLE';
+  schema?: string;
+  object_name?: string;
+  privilege_type: string;
+  is_grantable: boolean;
+}
+
+// 服务端会话（MySQL 线程 / PostgreSQL 后端进程）
+export interface ServerProcess {
+  id: number;
+  user?: string;
+  host?: string;
+  database?: string;
+  command?: string;
+  state?: string;
+  time_secs?: number; // 当前语句（或状态）已持续的秒数
+  query?: string;
+  current: boolean; // 是否为执行列表查询的连接
+}
+
+// 监控采样（gauges 瞬时值，counters 累计值，rates 为累计值的每秒速率，如 questions_per_sec）
+export interface MonitorSnapshot {
+  collected_at: number;
+  gauges: Record<string, number>;
+  counters: Record<string, number>;
+  rates: Record<string, number>;
+}
+
+// 视图定义
+export interface ViewSource {
+  view_name: string;
+  query: string; // 视图的 SELECT 语句
+  ddl: string; // 完整的建视图语句
+}
+
+// DDL执行结果
+export interface DdlResponse {
+  success: boolean;
+  statements: string[];
+  message: string;
+}
+
+// 触发器定义（创建触发器）
+export interface TriggerDefinition {
+  trigger_name: string;
+  timing: string; // BEFORE / AFTER / INSTEAD OF
+  events: string[]; // INSERT / UPDATE / DELETE（MySQL、SQLite 只能指定一个）
+  when?: string; // 触发条件（PostgreSQL / SQLite）
+  body?: string; // 触发时执行的语句；PostgreSQL 为 PL/pgSQL 函数体
+  function_name?: string; // PostgreSQL：使用已有的触发器函数
+}
+
+// 查询会话（工作区标签页持有的专用连接）
+export interface QuerySession {
+  id: string;
+  connection_id: number;
+  database?: string;
+  db_type: string;
+  created_at: number;
+  idle_secs: number;
 }
 
 // SQL查询结果
@@ -160,6 +284,8 @@ export interface SqlQueryResult {
   page?: number;
   page_size?: number;
   has_more?: boolean;
+  next_cursor?: string;
+  cached?: boolean;
   performance?: QueryPerformance;
 }
 